@@ -0,0 +1,54 @@
+/*
+ * C interface to the rust_forex engine (src/ffi.rs).
+ *
+ * Build the library with `cargo build --release`; link against
+ * target/release/librust_forex.so (or .a / .dylib / .dll).
+ *
+ * Amounts are decimal strings ("1234.56"). Functions returning int give 0
+ * on success or a negative FOREX_* code; those returning a pointer give NULL
+ * on failure. forex_last_error() describes the last failure on the calling
+ * thread. Strings returned by the library are released with
+ * forex_string_free().
+ */
+#ifndef RUST_FOREX_H
+#define RUST_FOREX_H
+
+#ifdef __cplusplus
+extern "C" {
+#endif
+
+/* An argument was NULL, not UTF-8, or not a valid amount. */
+#define FOREX_INVALID_ARGUMENT (-1)
+/* The bank refused the operation (unknown account, insufficient funds, ...). */
+#define FOREX_REFUSED (-2)
+/* Reading or writing a snapshot failed. */
+#define FOREX_IO (-3)
+
+typedef struct ForexBank ForexBank;
+
+/* Bank with the built-in configuration and no accounts. */
+ForexBank *forex_bank_new(void);
+/* Bank loaded from a snapshot file; NULL on failure. */
+ForexBank *forex_bank_load(const char *path);
+int forex_bank_save(ForexBank *bank, const char *path);
+void forex_bank_free(ForexBank *bank);
+
+/* pin may be NULL for an unprotected account. */
+int forex_account_open(ForexBank *bank, const char *name, const char *pin);
+/* Amounts are in the account's currency; pin may be NULL. */
+int forex_deposit(ForexBank *bank, const char *name, const char *amount, const char *pin);
+int forex_withdraw(ForexBank *bank, const char *name, const char *amount, const char *pin);
+
+/* "<amount> <code>", e.g. "1234.56 PHP"; free with forex_string_free. */
+char *forex_balance(ForexBank *bank, const char *name);
+char *forex_convert(ForexBank *bank, const char *amount, const char *from, const char *to);
+
+/* Last failure on this thread, or NULL. Owned by the library. */
+const char *forex_last_error(void);
+void forex_string_free(char *s);
+
+#ifdef __cplusplus
+}
+#endif
+
+#endif /* RUST_FOREX_H */
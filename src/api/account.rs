@@ -1,3 +1,37 @@
+use std::fmt;
+use std::io::{self, Write};
+use std::sync::{Mutex, PoisonError};
+
+use crate::api::alert::AlertSettings;
+use crate::api::budget::{BudgetError, Envelope, EnvelopeStatus};
+use crate::api::credential::Credential;
+use crate::api::date::{format_utc_time, Date, Month, TimeZone};
+use crate::api::decimal::Decimal;
+use crate::api::goal::{GoalError, GoalProgress, SavingsGoal};
+use crate::api::cheque::{Cheque, ChequeError};
+use crate::api::hold::{Hold, HoldError};
+use crate::api::import::{ImportError, ImportFailure, ImportReport, ImportRow};
+use crate::api::integrity::Violation;
+use crate::api::ledger::{TransactionLog, TransactionRef};
+use crate::api::loan::PaymentFrequency;
+use crate::api::money::{CurrencyMismatch, Money};
+use crate::api::position::CurrencyPosition;
+use crate::api::rates::{self, YearBasis};
+use crate::api::search::TransactionQuery;
+use crate::api::service_charge::ServiceCharges;
+use crate::api::format::Locale;
+use crate::api::forex::default_symbol;
+use crate::api::tag::{TagError, TagTotal};
+use crate::api::statement::{
+    csv_field, ofx_datetime, qif_date, xml_escape, CsvOptions, InterestSummary, Statement, StatementFormat, StatementLine, BANK_NAME,
+    CARRYING_CHARGE_MEMO, INTEREST_MEMO, OFX_BANK_ID, WITHHOLDING_TAX_MEMO,
+};
+use crate::api::tax::{TaxCertificate, TaxCertificateLine};
+
+/// Days per year in the daily interest formula by default (Actual/365
+/// Fixed: leap years also count as 365; see `YearBasis`).
+pub const DAY_COUNT_BASIS: u32 = 365;
+
 /// Transaction types supported by an Account.
 /// - Deposit adds a positive amount
 /// - Withdraw records a negative amount (see `create_transaction`)
@@ -7,89 +41,1636 @@ pub enum TransactionType {
     Withdraw,
 }
 
+/// Errors raised when a transaction is refused by an account.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum AccountError {
+    /// The PIN was missing or did not match.
+    InvalidPin,
+    /// The amount is not in the account's currency.
+    CurrencyMismatch(CurrencyMismatch),
+    /// The amount rounds to zero or below in the account's minor unit.
+    NonPositiveAmount,
+    /// The amount does not fit in the account's minor-unit storage.
+    AmountOutOfRange,
+    /// A withdrawal exceeds the available balance.
+    InsufficientFunds { balance: Money, requested: Money },
+    /// A savings goal was refused or not found.
+    Goal(GoalError),
+    /// A budget envelope was refused or not found.
+    Budget(BudgetError),
+    /// A promotion's bonus must be above zero.
+    NonPositiveBonus,
+    /// A promotion must last at least a day.
+    EmptyPromotion,
+    /// A tag was refused.
+    Tag(TagError),
+    /// A hold was refused or not found.
+    Hold(HoldError),
+    /// A cheque was refused, not found, or could not be returned.
+    Cheque(ChequeError),
+}
+
+impl fmt::Display for AccountError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            AccountError::InvalidPin => write!(f, "incorrect PIN"),
+            AccountError::CurrencyMismatch(e) => write!(f, "{}", e),
+            AccountError::NonPositiveAmount => write!(f, "amount must be greater than zero"),
+            AccountError::AmountOutOfRange => write!(f, "amount is out of range"),
+            AccountError::InsufficientFunds { balance, requested } => {
+                write!(f, "insufficient balance: {} available, {} requested", balance, requested)
+            }
+            AccountError::Goal(e) => write!(f, "{}", e),
+            AccountError::Budget(e) => write!(f, "{}", e),
+            AccountError::NonPositiveBonus => write!(f, "promotional bonus must be greater than zero"),
+            AccountError::EmptyPromotion => write!(f, "promotion must last at least one day"),
+            AccountError::Tag(e) => write!(f, "{}", e),
+            AccountError::Hold(e) => write!(f, "{}", e),
+            AccountError::Cheque(e) => write!(f, "{}", e),
+        }
+    }
+}
+
+impl std::error::Error for AccountError {}
+
+impl From<GoalError> for AccountError {
+    fn from(e: GoalError) -> Self {
+        AccountError::Goal(e)
+    }
+}
+
+impl From<BudgetError> for AccountError {
+    fn from(e: BudgetError) -> Self {
+        AccountError::Budget(e)
+    }
+}
+
+impl From<TagError> for AccountError {
+    fn from(e: TagError) -> Self {
+        AccountError::Tag(e)
+    }
+}
+
+impl From<HoldError> for AccountError {
+    fn from(e: HoldError) -> Self {
+        AccountError::Hold(e)
+    }
+}
+
+impl From<ChequeError> for AccountError {
+    fn from(e: ChequeError) -> Self {
+        AccountError::Cheque(e)
+    }
+}
+
+impl From<CurrencyMismatch> for AccountError {
+    fn from(e: CurrencyMismatch) -> Self {
+        AccountError::CurrencyMismatch(e)
+    }
+}
+
 /// Immutable transaction record containing the signed value applied
-/// to the account balance.
-#[derive(Debug, Clone, Copy)]
+/// to the account balance, when it was posted (Unix seconds, UTC), an
+/// optional free-form memo, the budget `category` it is filed under,
+/// if any, and its free-form `tags` (see `tag::normalize`). The value is stored exactly as an integer number of minor units
+/// (`units` × 10^-`dp`, e.g. centavos when `dp` = 2).
+/// `sequence` is the bank-wide posting number the `Bank` gives it, a total
+/// order over every account's transactions; 0 until it is numbered.
+#[derive(Debug, Clone)]
 pub struct Transaction {
-    pub value: f64,
+    pub units: i64,
+    pub dp: u32,
+    pub timestamp: i64,
+    pub sequence: u64,
+    pub memo: String,
+    pub category: Option<String>,
+    pub tags: Vec<String>,
+}
+
+impl Transaction {
+    /// Signed value applied to the balance.
+    pub fn value(&self) -> Decimal {
+        Decimal::new(self.units, self.dp)
+    }
+
+    /// Transaction type implied by the sign of the stored value.
+    pub fn tx_type(&self) -> TransactionType {
+        if self.units < 0 {
+            TransactionType::Withdraw
+        } else {
+            TransactionType::Deposit
+        }
+    }
+
+    /// Unsigned amount of the transaction.
+    pub fn amount(&self) -> Decimal {
+        self.value().abs()
+    }
+
+    /// Calendar date (UTC) the transaction was posted.
+    pub fn date(&self) -> Date {
+        Date::from_timestamp(self.timestamp)
+    }
 }
 
 /// Bank account model that keeps a running list of transactions and
 /// computes balances and interest forecasts. The annual interest is
-/// stored per-account so different accounts can have different rates.
+/// stored per-account so different accounts can have different rates:
+/// `annual_interest` is the rate the account opened with, and
+/// `rate_changes` every later change by effective date, and `promotion` a
+/// temporary bonus on top of them (see `rate_on`).
+/// `id` is assigned by the `Bank` when the account is opened (0 = unassigned).
+/// `credential` optionally protects the account with a hashed PIN/passphrase.
+/// `currency` is the code every transaction and balance is denominated in;
+/// `minor_unit_dp` is that currency's number of decimal places, which
+/// fixes the integer minor units transactions are stored in.
+/// `goals` are the holder's savings goals, in the order they were set, and
+/// `envelopes` their monthly budgets by category.
+/// `aliases` are alternate names the `Bank` also finds the account by,
+/// e.g. "Mom's savings" (see `Bank::add_alias`).
+/// `position` is what the balance cost in the bank's base currency, kept
+/// up to date by the `Bank` for accounts in another currency.
+/// `version` counts changes to the account, from 0 when it opens: every
+/// method here that changes it adds one, so a client can tell whether the
+/// account moved since it last looked (see `Bank::expect_version`). Code
+/// that changes fields directly must call `touch` afterwards.
+/// `archived` is the day the account was archived, if it is: it keeps its
+/// history but takes no more postings and is left out of listings and
+/// reports (see `Bank::archive_account`).
+/// `interest_payout` names the account posted interest is paid into
+/// instead of being added to this one, e.g. a time deposit's interest
+/// swept to savings (see `Bank::set_interest_payout`).
+/// `auto_convert` lists the currencies deposits in which are converted into
+/// the account's currency as they are posted (see
+/// `Bank::set_auto_conversion`).
+/// `alerts` are what the holder wants to be alerted about, and what has
+/// been reported already (see `Bank::set_alerts`).
+/// `holds` are its authorization holds (see `place_hold`) and `cheques`
+/// the cheques deposited to it (see `Bank::deposit_cheque`); active holds
+/// and cheques still clearing are kept back from `available_balance`.
+/// `service_charges` are the fees taken from it at each month's end (see
+/// `Bank::set_service_charges`).
+/// `account_type` is its product type, e.g. "savings", which picks the
+/// limit profile it falls under (see `Bank::set_account_type`).
+/// `year_basis` is how many days a year counts when its interest accrues
+/// (see `YearBasis`), `interest_method` when accrued interest starts
+/// earning too (see `InterestMethod`), and `interest_grace_days` how long a
+/// deposit waits after it is posted before it earns.
+/// The balance is cached as a running total of minor units, kept current
+/// by `push_transaction` and `pop_transaction`. Code that edits
+/// `transactions` directly must call `invalidate_balance` afterwards.
 #[derive(Debug, Clone)]
 pub struct Account {
+    pub id: usize,
     pub name: String,
-    pub transactions: Vec<Transaction>,
-    pub annual_interest: f64,
+    pub currency: String,
+    pub minor_unit_dp: u32,
+    pub transactions: TransactionLog,
+    pub annual_interest: Decimal,
+    pub rate_changes: Vec<RateChange>,
+    pub promotion: Option<Promotion>,
+    pub credential: Option<Credential>,
+    pub goals: Vec<SavingsGoal>,
+    pub envelopes: Vec<Envelope>,
+    pub aliases: Vec<String>,
+    pub position: CurrencyPosition,
+    pub version: u64,
+    pub archived: Option<Date>,
+    pub interest_payout: Option<String>,
+    pub auto_convert: Vec<String>,
+    pub alerts: AlertSettings,
+    pub service_charges: ServiceCharges,
+    pub account_type: Option<String>,
+    pub holds: Vec<Hold>,
+    pub cheques: Vec<Cheque>,
+    pub year_basis: YearBasis,
+    pub interest_method: InterestMethod,
+    pub interest_grace_days: u32,
+    /// `(transaction count, balance in minor units)` when last known; only
+    /// trusted while the count still matches.
+    cached_balance: Option<(usize, i64)>,
+    /// The longest forecast computed so far, reused by `forecast_with_rate`.
+    forecast_cache: ForecastMemo,
+}
+
+/// `forecast_with_rate`'s cache. A `Mutex` rather than a `RefCell` keeps
+/// accounts `Sync`, so bank-wide work can read them from several threads.
+#[derive(Debug, Default)]
+struct ForecastMemo(Mutex<Option<ForecastCache>>);
+
+impl Clone for ForecastMemo {
+    fn clone(&self) -> Self {
+        Self(Mutex::new(self.0.lock().unwrap_or_else(PoisonError::into_inner).clone()))
+    }
+}
+
+/// A forecast from `opening` on `start` at `annual_interest`, then at each
+/// of `changes` from its date, on `basis` by `method`, with `waiting` of
+/// the opening balance still in its grace period (see
+/// `Account::waiting_on`); `days[i]` is day `i + 1`, and `accrual` the
+/// interest as of the last of them.
+#[derive(Debug, Clone)]
+struct ForecastCache {
+    opening: Money,
+    start: Date,
+    basis: YearBasis,
+    method: InterestMethod,
+    waiting: Vec<(Date, Decimal)>,
+    annual_interest: Decimal,
+    changes: Vec<RateChange>,
+    days: Vec<InterestForecast>,
+    accrual: Accrual,
+}
+
+/// How an account's daily interest is figured and when it starts earning
+/// interest itself.
+/// - `DailyBalance`: each day earns on that day's closing balance with the
+///   interest accrued so far, compounding daily; the default.
+/// - `AverageDailyBalance`: each day earns on its closing balance without
+///   the month's interest, which is added at the month's end, so a month
+///   earns its average daily balance × rate × its days / the year's days,
+///   compounding monthly, as savings accounts commonly pay.
+/// - `MinimumMonthlyBalance`: as `AverageDailyBalance`, but every day of a
+///   month earns on the month's lowest closing balance, as Philippine
+///   savings accounts commonly pay; one withdrawal costs the whole month's
+///   interest on it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum InterestMethod {
+    #[default]
+    DailyBalance,
+    AverageDailyBalance,
+    MinimumMonthlyBalance,
+}
+
+impl InterestMethod {
+    /// Parse "daily", "average" (also "adb"), or "minimum" (also "mmb"),
+    /// any case.
+    pub fn parse(s: &str) -> Option<Self> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "daily" => Some(InterestMethod::DailyBalance),
+            "average" | "adb" => Some(InterestMethod::AverageDailyBalance),
+            "minimum" | "mmb" => Some(InterestMethod::MinimumMonthlyBalance),
+            _ => None,
+        }
+    }
+
+    /// Lowercase name, as accepted by `parse`.
+    pub fn name(&self) -> &'static str {
+        match self {
+            InterestMethod::DailyBalance => "daily",
+            InterestMethod::AverageDailyBalance => "average",
+            InterestMethod::MinimumMonthlyBalance => "minimum",
+        }
+    }
+
+    /// A short description, e.g. "Average daily balance, credited monthly".
+    pub fn label(&self) -> &'static str {
+        match self {
+            InterestMethod::DailyBalance => "Daily balance, compounded daily",
+            InterestMethod::AverageDailyBalance => "Average daily balance, credited monthly",
+            InterestMethod::MinimumMonthlyBalance => "Minimum monthly balance, credited monthly",
+        }
+    }
+}
+
+/// Interest accruing day by day under an `InterestMethod`: `credited` has
+/// been added to the balance and earns, `pending` is the month's interest
+/// under the monthly methods, not yet earning.
+#[derive(Debug, Clone, Copy, Default)]
+struct Accrual {
+    credited: Decimal,
+    pending: Decimal,
+}
+
+impl Accrual {
+    /// Accrue `date`'s interest at the daily rate `daily` on `principal`
+    /// (the balance without interest) and the interest credited so far,
+    /// crediting what is pending when `method` says so. Returns the day's
+    /// interest.
+    fn accrue(&mut self, method: InterestMethod, principal: Decimal, daily: Decimal, date: Date) -> Result<Decimal, AccountError> {
+        let interest = principal
+            .checked_add(self.credited)
+            .and_then(|balance| balance.checked_mul(daily))
+            .ok_or(AccountError::AmountOutOfRange)?;
+        self.pending = self.pending.checked_add(interest).ok_or(AccountError::AmountOutOfRange)?;
+        if method == InterestMethod::DailyBalance || date.add_days(1).month != date.month {
+            self.credited = self.credited.checked_add(self.pending).ok_or(AccountError::AmountOutOfRange)?;
+            self.pending = Decimal::ZERO;
+        }
+        Ok(interest)
+    }
+
+    /// All the interest accrued, credited or not.
+    fn total(&self) -> Decimal {
+        self.credited + self.pending
+    }
+}
+
+/// The part of `opening` that earns on `date` in a forecast from `start`,
+/// less the deposits in `waiting` (see `Account::waiting_on`) that have not
+/// yet started earning, but never below zero unless `opening` is. Under
+/// `MinimumMonthlyBalance` it is the month's lowest, which is on its first
+/// day in the forecast, as deposits only join it.
+fn earning_on(opening: Decimal, waiting: &[(Date, Decimal)], method: InterestMethod, start: Date, date: Date) -> Decimal {
+    let on = match method {
+        InterestMethod::MinimumMonthlyBalance => start.max(Month::of(date).first_day()),
+        _ => date,
+    };
+    let held: Decimal = waiting.iter().filter(|(from, _)| *from > on).map(|(_, amount)| *amount).sum();
+    (opening - held).max(opening.min(Decimal::ZERO))
+}
+
+/// An account's annual interest rate (a fraction) from `effective` on,
+/// until the next change.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RateChange {
+    pub effective: Date,
+    pub annual_interest: Decimal,
+}
+
+/// A temporary bonus on an account's annual rate: `bonus` (a fraction) is
+/// added to the rate in force on each day from `start` up to, but not
+/// including, `end`, when the account reverts to its own rate.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Promotion {
+    pub bonus: Decimal,
+    pub start: Date,
+    pub end: Date,
+}
+
+impl Promotion {
+    /// Whether the bonus applies on `date`.
+    pub fn is_active(&self, date: Date) -> bool {
+        self.start <= date && date < self.end
+    }
 }
 
 impl Account {
-    /// Create a new account with a default annual interest (5%).
+    /// Create a new PHP account with a default annual interest (5%).
     /// Simple constructor analogous to constructors in C/Java.
     pub fn new(name: &str) -> Self {
         Self {
+            id: 0,
             name: name.to_string(),
-            transactions: Vec::new(),
-            annual_interest: 0.05,
+            currency: String::from("PHP"),
+            minor_unit_dp: 2,
+            transactions: TransactionLog::default(),
+            annual_interest: Decimal::new(5, 2),
+            rate_changes: Vec::new(),
+            promotion: None,
+            credential: None,
+            goals: Vec::new(),
+            envelopes: Vec::new(),
+            aliases: Vec::new(),
+            position: CurrencyPosition::default(),
+            version: 0,
+            archived: None,
+            interest_payout: None,
+            auto_convert: Vec::new(),
+            alerts: AlertSettings::default(),
+            service_charges: ServiceCharges::default(),
+            account_type: None,
+            holds: Vec::new(),
+            cheques: Vec::new(),
+            year_basis: YearBasis::default(),
+            interest_method: InterestMethod::default(),
+            interest_grace_days: 0,
+            cached_balance: Some((0, 0)),
+            forecast_cache: ForecastMemo::default(),
         }
     }
 
     /// Builder method: set the annual interest rate for this account and
     /// return the updated account for chaining.
     /// Usage: `let acct = Account::new("Alice").with_interest(0.05);`
-    pub fn with_interest(mut self, annual_interest: f64) -> Self {
+    pub fn with_interest(mut self, annual_interest: Decimal) -> Self {
         self.annual_interest = annual_interest;
         self
     }
 
-    /// Append a transaction. The `amount` must be > 0.
+    /// Builder method: count `basis` days a year when interest accrues.
+    pub fn with_year_basis(mut self, basis: YearBasis) -> Self {
+        self.year_basis = basis;
+        self
+    }
+
+    /// Count `basis` days a year in forecasts and accruals from now on.
+    pub fn set_year_basis(&mut self, basis: YearBasis) {
+        self.year_basis = basis;
+        self.touch();
+    }
+
+    /// Builder method: accrue interest by `method`.
+    pub fn with_interest_method(mut self, method: InterestMethod) -> Self {
+        self.interest_method = method;
+        self
+    }
+
+    /// Accrue interest by `method` in forecasts and accruals from now on.
+    pub fn set_interest_method(&mut self, method: InterestMethod) {
+        self.interest_method = method;
+        self.touch();
+    }
+
+    /// Builder method: let deposits start earning `days` days after they
+    /// are posted.
+    pub fn with_interest_grace(mut self, days: u32) -> Self {
+        self.interest_grace_days = days;
+        self
+    }
+
+    /// Let deposits start earning `days` days after they are posted, in
+    /// forecasts and accruals from now on; zero earns from the day itself.
+    pub fn set_interest_grace(&mut self, days: u32) {
+        self.interest_grace_days = days;
+        self.touch();
+    }
+
+    /// The deposits that wait out `interest_grace_days` before they earn:
+    /// each one's posting date (UTC), the first day it earns, and its value
+    /// in minor units, oldest first. Interest credited to the account earns
+    /// from the day it is posted, so it is left out; without a grace period
+    /// there are none.
+    fn grace_deposits(&self) -> Vec<(Date, Date, i64)> {
+        if self.interest_grace_days == 0 {
+            return Vec::new();
+        }
+        let paid_in = format!("{} from ", INTEREST_MEMO);
+        let mut deposits: Vec<(Date, Date, i64)> = self
+            .transactions
+            .iter()
+            .filter(|tx| tx.units > 0 && tx.memo != INTEREST_MEMO && !tx.memo.starts_with(&paid_in))
+            .map(|tx| (tx.date(), tx.date().add_days(i64::from(self.interest_grace_days)), tx.units))
+            .collect();
+        deposits.sort_by_key(|(date, _, _)| *date);
+        deposits
+    }
+
+    /// The deposits in the balance still in their grace period on `start`:
+    /// the first day each earns, and its amount.
+    fn waiting_on(&self, start: Date) -> Vec<(Date, Decimal)> {
+        self.grace_deposits()
+            .into_iter()
+            .filter(|(_, from, _)| *from > start)
+            .map(|(_, from, units)| (from, Decimal::new(units, self.minor_unit_dp)))
+            .collect()
+    }
+
+    /// Earn `annual_interest` from `effective` on, replacing any change
+    /// already recorded for that date. Changes may be dated in the past or
+    /// the future; forecasts and accruals use each over the days it is in
+    /// force.
+    pub fn change_interest(&mut self, annual_interest: Decimal, effective: Date) {
+        let at = self.rate_changes.partition_point(|c| c.effective < effective);
+        match self.rate_changes.get_mut(at) {
+            Some(c) if c.effective == effective => c.annual_interest = annual_interest,
+            _ => self.rate_changes.insert(at, RateChange { effective, annual_interest }),
+        }
+        self.touch();
+    }
+
+    /// Add `bonus` to the account's rate for `days` days from `start`,
+    /// replacing any promotion already set. Rate changes during the window
+    /// keep the bonus on top. Fails if `bonus` is not above zero or `days`
+    /// is zero.
+    pub fn start_promotion(&mut self, bonus: Decimal, start: Date, days: usize) -> Result<Promotion, AccountError> {
+        if bonus <= Decimal::ZERO {
+            return Err(AccountError::NonPositiveBonus);
+        }
+        if days == 0 {
+            return Err(AccountError::EmptyPromotion);
+        }
+        let promotion = Promotion { bonus, start, end: start.add_days(days as i64) };
+        self.promotion = Some(promotion);
+        self.touch();
+        Ok(promotion)
+    }
+
+    /// Drop the promotion, if any, so the account earns its own rate from
+    /// now on. Returns the promotion removed.
+    pub fn end_promotion(&mut self) -> Option<Promotion> {
+        let promotion = self.promotion.take();
+        if promotion.is_some() {
+            self.touch();
+        }
+        promotion
+    }
+
+    /// The annual rate in force on `date`: the latest change effective on
+    /// or before it, else the rate the account opened with, plus the
+    /// promotion's bonus while it is active.
+    pub fn rate_on(&self, date: Date) -> Decimal {
+        let bonus = self.promotion.filter(|p| p.is_active(date)).map_or(Decimal::ZERO, |p| p.bonus);
+        self.standard_rate_on(date) + bonus
+    }
+
+    /// `rate_on` without the promotion.
+    pub fn standard_rate_on(&self, date: Date) -> Decimal {
+        let at = self.rate_changes.partition_point(|c| c.effective <= date);
+        at.checked_sub(1).map_or(self.annual_interest, |i| self.rate_changes[i].annual_interest)
+    }
+
+    /// Builder method: set the currency the account is denominated in.
+    pub fn with_currency(mut self, code: &str) -> Self {
+        self.currency = code.to_string();
+        self
+    }
+
+    /// Builder method: set the number of decimal places of the account's
+    /// currency (2 for PHP/USD, 0 for JPY).
+    pub fn with_minor_unit_dp(mut self, dp: u32) -> Self {
+        self.minor_unit_dp = dp;
+        self
+    }
+
+    /// Builder method: set the bank-assigned account ID.
+    pub fn with_id(mut self, id: usize) -> Self {
+        self.id = id;
+        self
+    }
+
+    /// Protect the account with a PIN/passphrase. Only a salted hash is kept.
+    pub fn set_pin(&mut self, secret: &str) {
+        self.credential = Some(Credential::new(secret));
+        self.touch();
+    }
+
+    /// Returns true once the account has been archived.
+    pub fn is_archived(&self) -> bool {
+        self.archived.is_some()
+    }
+
+    /// Returns true if the account requires a PIN for transactions.
+    pub fn is_protected(&self) -> bool {
+        self.credential.is_some()
+    }
+
+    /// Verify a PIN/passphrase. Unprotected accounts always verify; protected
+    /// accounts fail when `secret` is `None` or does not match.
+    pub fn verify_pin(&self, secret: Option<&str>) -> bool {
+        match (&self.credential, secret) {
+            (None, _) => true,
+            (Some(cred), Some(secret)) => cred.verify(secret),
+            (Some(_), None) => false,
+        }
+    }
+
+    /// Append a transaction posted at `timestamp` (Unix seconds, UTC). The
+    /// `amount` must be > 0 and in the account's currency; it is rounded
+    /// (half away from zero) to the currency's minor unit before being
+    /// stored.
     /// - Deposit: the stored value is `+amount`.
-    /// - Withdraw: the stored value is `-amount`.
-    pub fn create_transaction(&mut self, tx_type: TransactionType, amount: f64) {
-        assert!(amount > 0.0, "amount must be > 0");
-        assert!(
-            tx_type == TransactionType::Withdraw 
-            && self.get_balance() >= amount 
-            || tx_type == TransactionType::Deposit, 
-            "insufficient balance for withdrawal"
-        );
-        let value = match tx_type {
-            TransactionType::Deposit => amount,
-            TransactionType::Withdraw => -amount,
+    /// - Withdraw: the stored value is `-amount`; fails if it exceeds the
+    ///   available balance on the day of `timestamp` (see
+    ///   `available_balance`).
+    ///
+    /// Nothing is recorded when an error is returned.
+    pub fn create_transaction(&mut self, tx_type: TransactionType, amount: Money, timestamp: i64) -> Result<(), AccountError> {
+        self.create_transaction_with_memo(tx_type, amount, "", timestamp)
+    }
+
+    /// Same as `create_transaction`, attaching `memo` to the record.
+    pub fn create_transaction_with_memo(&mut self, tx_type: TransactionType, amount: Money, memo: &str, timestamp: i64) -> Result<(), AccountError> {
+        let limit = match tx_type {
+            TransactionType::Withdraw => self.balance_units() - self.held_units(Date::from_timestamp(timestamp)),
+            TransactionType::Deposit => self.balance_units(),
         };
-        self.transactions.push(Transaction { value });
+        let units = self.signed_units(tx_type, &amount, limit)?;
+        self.push_transaction(Transaction {
+            units,
+            dp: self.minor_unit_dp,
+            timestamp,
+            sequence: 0,
+            memo: memo.to_string(),
+            category: None,
+            tags: Vec::new(),
+        });
+        Ok(())
     }
 
-    /// Compute the current balance as the sum of all transaction values.
-    pub fn get_balance(&self) -> f64 {
-        self.transactions.iter().map(|t| t.value).sum()
+    /// The signed minor units `amount` would post as against a balance of
+    /// `balance_units`, after the checks described on `create_transaction`.
+    fn signed_units(&self, tx_type: TransactionType, amount: &Money, balance_units: i64) -> Result<i64, AccountError> {
+        let balance = || Money::new(Decimal::new(balance_units, self.minor_unit_dp), &self.currency);
+        balance().same_currency(amount)?;
+        let units = amount
+            .amount
+            .to_minor_units(self.minor_unit_dp)
+            .ok_or(AccountError::AmountOutOfRange)?;
+        if units <= 0 {
+            return Err(AccountError::NonPositiveAmount);
+        }
+        match tx_type {
+            TransactionType::Deposit => {
+                balance_units.checked_add(units).ok_or(AccountError::AmountOutOfRange)?;
+                Ok(units)
+            }
+            TransactionType::Withdraw if units > balance_units => {
+                Err(AccountError::InsufficientFunds { balance: balance(), requested: amount.clone() })
+            }
+            TransactionType::Withdraw => Ok(-units),
+        }
     }
 
-    /// Produce a day-by-day compound interest projection using
-    /// Daily Interest = Balance × (Annual Rate / 365).
-    /// The balance is incremented each day by that day's interest.
-    pub fn get_interest_forecast(&self, days: usize) -> Vec<InterestForecast> {
-        let daily_rate = self.annual_interest / 365.0;
-        let mut balance = self.get_balance();
-
-        (1..=days)
-            .map(|day| {
-                let interest = balance * daily_rate;
-                balance += interest;
-                InterestForecast {
-                    day,
-                    balance,
-                    interest,
+    /// Append many historical transactions in one pass, e.g. rows read by
+    /// `import::CsvRows`. Each row gets the checks of `create_transaction`
+    /// against the balance left by the rows before it, but keeps its own
+    /// timestamp, memo, and category. `Err` items are input that could not
+    /// be read and are reported as malformed.
+    ///
+    /// Rows are consumed as they come, so the input is never held in
+    /// memory. Rejected rows are skipped and listed in the report; the
+    /// others stay posted.
+    pub fn import_transactions(&mut self, rows: impl IntoIterator<Item = Result<ImportRow, String>>) -> ImportReport {
+        let mut report = ImportReport::default();
+        let mut balance_units = self.balance_units();
+        for (index, row) in rows.into_iter().enumerate() {
+            let posted = row.map_err(ImportFailure::Malformed).and_then(|row| {
+                let units = self.signed_units(row.tx_type, &row.amount, balance_units).map_err(ImportFailure::Rejected)?;
+                Ok(Transaction { units, dp: self.minor_unit_dp, timestamp: row.timestamp, sequence: 0, memo: row.memo, category: row.category, tags: Vec::new() })
+            });
+            match posted {
+                Ok(tx) => {
+                    balance_units += tx.units;
+                    self.transactions.push(tx);
+                    report.imported += 1;
+                }
+                Err(failure) => report.errors.push(ImportError { row: index + 1, failure }),
+            }
+        }
+        self.cached_balance = Some((self.transactions.len(), balance_units));
+        if report.imported > 0 {
+            self.touch();
+        }
+        report
+    }
+
+    /// Append `tx` as is, without the checks of `create_transaction`, and
+    /// add it to the cached balance. Used when restoring a snapshot.
+    pub fn push_transaction(&mut self, tx: Transaction) {
+        let units = self.balance_units() + tx.units;
+        self.transactions.push(tx);
+        self.cached_balance = Some((self.transactions.len(), units));
+        self.touch();
+    }
+
+    /// Remove the last transaction, e.g. to roll back half of a failed
+    /// transfer. The cached balance is dropped and rebuilt on the next
+    /// posting.
+    pub fn pop_transaction(&mut self) -> Option<Transaction> {
+        self.invalidate_balance();
+        self.touch();
+        self.transactions.pop()
+    }
+
+    /// Forget the cached balance after `transactions` was edited directly.
+    pub fn invalidate_balance(&mut self) {
+        self.cached_balance = None;
+    }
+
+    /// Count a change made to the account's fields directly, bumping
+    /// `version`.
+    pub fn touch(&mut self) {
+        self.version += 1;
+    }
+
+    /// The balance in minor units: the cached total while it still covers
+    /// every transaction, else the sum of all of them.
+    fn balance_units(&self) -> i64 {
+        match self.cached_balance {
+            Some((count, units)) if count == self.transactions.len() => units,
+            _ => self.transactions.sum_units(),
+        }
+    }
+
+    /// The current balance: the sum of all transaction values in minor
+    /// units, read from the cache when it is current.
+    pub fn get_balance(&self) -> Money {
+        Money::new(Decimal::new(self.balance_units(), self.minor_unit_dp), &self.currency)
+    }
+
+    /// The balance less the holds active on `on` and the cheques still
+    /// clearing then: what a withdrawal can take that day.
+    pub fn available_balance(&self, on: Date) -> Money {
+        Money::new(Decimal::new(self.balance_units() - self.held_units(on), self.minor_unit_dp), &self.currency)
+    }
+
+    /// The holds keeping money back on `on`, oldest first.
+    pub fn active_holds(&self, on: Date) -> impl Iterator<Item = &Hold> {
+        self.holds.iter().filter(move |h| h.is_active(on))
+    }
+
+    /// The cheques deposited but not yet cleared on `on`, oldest first.
+    pub fn clearing_cheques(&self, on: Date) -> impl Iterator<Item = &Cheque> {
+        self.cheques.iter().filter(move |c| c.is_clearing(on))
+    }
+
+    fn held_units(&self, on: Date) -> i64 {
+        let amounts = self.active_holds(on).map(|h| &h.amount).chain(self.clearing_cheques(on).map(|c| &c.amount));
+        amounts.filter_map(|m| m.amount.to_minor_units(self.minor_unit_dp)).sum()
+    }
+
+    /// Keep `amount` back from the available balance for `reason` through
+    /// `expiry` without posting anything, as a card authorization does, and
+    /// return the hold's ID. Holds that lapsed before `today` are dropped
+    /// first. Fails if `amount` is not in the account's currency, rounds
+    /// to zero, or exceeds the available balance, if `reason` is blank, or
+    /// if `expiry` is before `today`.
+    pub fn place_hold(&mut self, amount: Money, reason: &str, expiry: Date, today: Date) -> Result<usize, AccountError> {
+        let reason = reason.trim();
+        if reason.is_empty() {
+            return Err(HoldError::BlankReason.into());
+        }
+        if expiry < today {
+            return Err(HoldError::ExpiryPassed(expiry).into());
+        }
+        self.holds.retain(|h| h.is_active(today));
+        let units = self.signed_units(TransactionType::Withdraw, &amount, self.balance_units() - self.held_units(today))?;
+        let id = self.holds.iter().map(|h| h.id).max().unwrap_or(0) + 1;
+        let amount = Money::new(Decimal::new(-units, self.minor_unit_dp), &self.currency);
+        self.holds.push(Hold { id, amount, reason: reason.to_string(), placed: today, expiry });
+        self.touch();
+        Ok(id)
+    }
+
+    /// Remove and return hold `id`, making what it kept back available
+    /// again.
+    pub fn release_hold(&mut self, id: usize) -> Result<Hold, AccountError> {
+        let pos = self.holds.iter().position(|h| h.id == id).ok_or(HoldError::NotFound(id))?;
+        self.touch();
+        Ok(self.holds.remove(pos))
+    }
+
+    /// Return cheque `id` unpaid for `reason` on `today`, while it is still
+    /// clearing: reverse its credit with a withdrawal at `timestamp`, with
+    /// the memo "Returned cheque", its number, and the reason. What is
+    /// available does not change, as the cheque was never part of it.
+    pub fn return_cheque(&mut self, id: usize, reason: &str, today: Date, timestamp: i64) -> Result<Cheque, AccountError> {
+        let reason = reason.trim();
+        let pos = self.cheques.iter().position(|c| c.id == id).ok_or(ChequeError::NotFound(id))?;
+        let cheque = &self.cheques[pos];
+        if cheque.returned.is_some() {
+            return Err(ChequeError::Returned(id).into());
+        }
+        if !cheque.is_clearing(today) {
+            return Err(ChequeError::Cleared(id).into());
+        }
+        if reason.is_empty() {
+            return Err(ChequeError::BlankReason.into());
+        }
+        let memo = format!("Returned cheque {}: {}", cheque.number, reason);
+        let units = cheque.amount.amount.to_minor_units(self.minor_unit_dp).ok_or(AccountError::AmountOutOfRange)?;
+        if units > self.balance_units() {
+            return Err(AccountError::InsufficientFunds { balance: self.get_balance(), requested: cheque.amount.clone() });
+        }
+        self.push_transaction(Transaction { units: -units, dp: self.minor_unit_dp, timestamp, sequence: 0, memo, category: None, tags: Vec::new() });
+        self.cheques[pos].returned = Some((today, reason.to_string()));
+        Ok(self.cheques[pos].clone())
+    }
+
+    /// Check the ledger against itself: every transaction recorded in the
+    /// account's minor unit, the cached balance (while current) equal to
+    /// their sum, and the running balance never below zero.
+    pub fn verify(&self) -> Vec<Violation> {
+        let money = |units: i64| Money::new(Decimal::new(units, self.minor_unit_dp), &self.currency);
+        let mut violations = Vec::new();
+        let mut running = Some(0i64);
+        let mut overdrawn = false;
+        for (index, tx) in self.transactions.iter().enumerate() {
+            if tx.dp != self.minor_unit_dp {
+                violations.push(Violation::MinorUnitMismatch { account: self.name.clone(), index, dp: tx.dp });
+            }
+            running = running.and_then(|units| units.checked_add(tx.units));
+            if let Some(units) = running
+                && units < 0
+                && !overdrawn
+            {
+                overdrawn = true;
+                violations.push(Violation::BelowMinimum { account: self.name.clone(), index, balance: money(units) });
+            }
+        }
+        match (running, self.cached_balance) {
+            (None, _) => violations.push(Violation::SumOverflow(self.name.clone())),
+            (Some(summed), Some((count, units))) if count == self.transactions.len() && units != summed => {
+                violations.push(Violation::BalanceMismatch { account: self.name.clone(), recorded: money(units), summed: money(summed) });
+            }
+            _ => {}
+        }
+        violations
+    }
+
+    /// The balance at the end of `date` (UTC): every transaction posted on
+    /// or before it.
+    pub fn balance_on(&self, date: Date) -> Money {
+        let log = &self.transactions;
+        let units: i64 = log.timestamps().iter().zip(log.units()).filter(|(ts, _)| Date::from_timestamp(**ts) <= date).map(|(_, u)| u).sum();
+        Money::new(Decimal::new(units, self.minor_unit_dp), &self.currency)
+    }
+
+    /// The mean of the balances at the end of each day from `start` through
+    /// `end` (UTC), rounded to the minor unit. Only days from the account's
+    /// first posting on count, so an account opened within the period is
+    /// averaged over the days it was open; `None` if it had none by `end`.
+    pub fn average_daily_balance(&self, start: Date, end: Date) -> Option<Money> {
+        let log = &self.transactions;
+        let mut flows: Vec<(Date, i64)> = log.timestamps().iter().zip(log.units()).map(|(ts, u)| (Date::from_timestamp(*ts), *u)).collect();
+        flows.sort_by_key(|(date, _)| *date);
+        let from = flows.first().map(|(first, _)| start.max(*first)).filter(|from| *from <= end)?;
+        let mut flows = flows.into_iter().peekable();
+        let (mut held, mut total) = (0i64, Decimal::ZERO);
+        for day in 0..=end.days() - from.days() {
+            let date = from.add_days(day);
+            while let Some((_, units)) = flows.next_if(|(d, _)| *d <= date) {
+                held += units;
+            }
+            total += Decimal::new(held, self.minor_unit_dp);
+        }
+        let days = Decimal::from((end.days() - from.days() + 1) as usize);
+        Some(Money::new((total / days).round_dp(self.minor_unit_dp), &self.currency))
+    }
+
+    /// Transactions matching `query` in posting order, each paired with the
+    /// running balance right after it was posted. The running balance counts
+    /// every transaction, including those filtered out.
+    pub fn history(&self, query: &TransactionQuery) -> Vec<HistoryEntry<'_>> {
+        let mut units: i64 = 0;
+        self.transactions
+            .iter()
+            .filter_map(|t| {
+                units += t.units;
+                query.matches(&t).then(|| HistoryEntry {
+                    transaction: t,
+                    balance: Money::new(Decimal::new(units, self.minor_unit_dp), &self.currency),
+                })
+            })
+            .collect()
+    }
+
+    /// Write the transactions matching `options.query` as CSV, one row per
+    /// transaction: `date` (ISO `YYYY-MM-DD`, UTC), optionally `time`, then
+    /// `type`, `amount` (unsigned), `balance` (after the transaction),
+    /// `currency`, `memo`, and `sequence`, the bank-wide posting number.
+    /// Amounts are plain numbers with the currency's decimal places, so
+    /// spreadsheets read them as numbers.
+    pub fn export_csv(&self, writer: &mut impl Write, options: &CsvOptions) -> io::Result<()> {
+        let mut columns = vec!["date", "time", "type", "amount", "balance", "currency", "memo", "sequence"];
+        if !options.time {
+            columns.remove(1);
+        }
+        let sep = options.delimiter.to_string();
+        if options.header {
+            writeln!(writer, "{}", columns.join(&sep))?;
+        }
+        let dp = self.minor_unit_dp as usize;
+        for entry in self.history(&options.query) {
+            let tx = entry.transaction;
+            let mut cells = vec![tx.date().to_string()];
+            if options.time {
+                cells.push(format_utc_time(tx.timestamp));
+            }
+            cells.push(format!("{:?}", tx.tx_type()).to_lowercase());
+            cells.push(format!("{:.*}", dp, tx.amount()));
+            cells.push(format!("{:.*}", dp, entry.balance.amount));
+            cells.push(self.currency.clone());
+            cells.push(tx.memo.to_string());
+            cells.push(tx.sequence.to_string());
+            let line: Vec<String> = cells.iter().map(|c| csv_field(c, options.delimiter)).collect();
+            writeln!(writer, "{}", line.join(&sep))?;
+        }
+        Ok(())
+    }
+
+    /// Write the transactions matching `query` as an OFX 2.1 bank statement
+    /// for import into personal finance tools. Each transaction gets a
+    /// stable `FITID` (`{id}-{n}`, n counting from 1 over the whole history)
+    /// so re-importing does not duplicate it; the ledger balance is the
+    /// current balance, as of `now` (Unix seconds, UTC).
+    pub fn export_ofx(&self, writer: &mut impl Write, query: &TransactionQuery, now: i64) -> io::Result<()> {
+        let dp = self.minor_unit_dp as usize;
+        let now = ofx_datetime(now);
+        let matching: Vec<(usize, TransactionRef<'_>)> =
+            self.transactions.iter().enumerate().filter(|(_, t)| query.matches(t)).collect();
+        let start = matching.first().map_or_else(|| now.clone(), |(_, t)| ofx_datetime(t.timestamp));
+        let end = matching.last().map_or_else(|| now.clone(), |(_, t)| ofx_datetime(t.timestamp));
+
+        writeln!(writer, "<?xml version=\"1.0\" encoding=\"UTF-8\"?>")?;
+        writeln!(writer, "<?OFX OFXHEADER=\"200\" VERSION=\"211\" SECURITY=\"NONE\" OLDFILEUID=\"NONE\" NEWFILEUID=\"NONE\"?>")?;
+        writeln!(writer, "<OFX>")?;
+        writeln!(writer, "<SIGNONMSGSRSV1><SONRS>")?;
+        writeln!(writer, "<STATUS><CODE>0</CODE><SEVERITY>INFO</SEVERITY></STATUS>")?;
+        writeln!(writer, "<DTSERVER>{}</DTSERVER><LANGUAGE>ENG</LANGUAGE>", now)?;
+        writeln!(writer, "</SONRS></SIGNONMSGSRSV1>")?;
+        writeln!(writer, "<BANKMSGSRSV1><STMTTRNRS>")?;
+        writeln!(writer, "<TRNUID>0</TRNUID>")?;
+        writeln!(writer, "<STATUS><CODE>0</CODE><SEVERITY>INFO</SEVERITY></STATUS>")?;
+        writeln!(writer, "<STMTRS>")?;
+        writeln!(writer, "<CURDEF>{}</CURDEF>", xml_escape(&self.currency))?;
+        writeln!(writer, "<BANKACCTFROM><BANKID>{}</BANKID><ACCTID>{}</ACCTID><ACCTTYPE>SAVINGS</ACCTTYPE></BANKACCTFROM>", OFX_BANK_ID, self.id)?;
+        writeln!(writer, "<BANKTRANLIST>")?;
+        writeln!(writer, "<DTSTART>{}</DTSTART><DTEND>{}</DTEND>", start, end)?;
+        for (index, tx) in matching {
+            let (kind, label) = match tx.tx_type() {
+                TransactionType::Deposit => ("CREDIT", "Deposit"),
+                TransactionType::Withdraw => ("DEBIT", "Withdrawal"),
+            };
+            let name: String = if tx.memo.is_empty() { label.to_string() } else { tx.memo.chars().take(32).collect() };
+            writeln!(writer, "<STMTTRN>")?;
+            writeln!(writer, "<TRNTYPE>{}</TRNTYPE>", kind)?;
+            writeln!(writer, "<DTPOSTED>{}</DTPOSTED>", ofx_datetime(tx.timestamp))?;
+            writeln!(writer, "<TRNAMT>{:.*}</TRNAMT>", dp, tx.value())?;
+            writeln!(writer, "<FITID>{}-{}</FITID>", self.id, index + 1)?;
+            writeln!(writer, "<NAME>{}</NAME>", xml_escape(&name))?;
+            if !tx.memo.is_empty() {
+                writeln!(writer, "<MEMO>{}</MEMO>", xml_escape(tx.memo))?;
+            }
+            writeln!(writer, "</STMTTRN>")?;
+        }
+        writeln!(writer, "</BANKTRANLIST>")?;
+        writeln!(writer, "<LEDGERBAL><BALAMT>{:.*}</BALAMT><DTASOF>{}</DTASOF></LEDGERBAL>", dp, self.get_balance().amount, now)?;
+        writeln!(writer, "</STMTRS>")?;
+        writeln!(writer, "</STMTTRNRS></BANKMSGSRSV1>")?;
+        writeln!(writer, "</OFX>")
+    }
+
+    /// Write the transactions matching `query` as a QIF `!Type:Bank` list:
+    /// date (`MM/DD/YYYY`), signed amount, and memo (also used as the payee).
+    pub fn export_qif(&self, writer: &mut impl Write, query: &TransactionQuery) -> io::Result<()> {
+        let dp = self.minor_unit_dp as usize;
+        writeln!(writer, "!Type:Bank")?;
+        for tx in self.transactions.iter().filter(|t| query.matches(t)) {
+            writeln!(writer, "D{}", qif_date(tx.date()))?;
+            writeln!(writer, "T{:.*}", dp, tx.value())?;
+            if !tx.memo.is_empty() {
+                let memo = tx.memo.replace(['\n', '\r'], " ");
+                writeln!(writer, "P{}", memo)?;
+                writeln!(writer, "M{}", memo)?;
+            }
+            writeln!(writer, "^")?;
+        }
+        Ok(())
+    }
+
+    /// A printable statement of `start` through `end`, generated at
+    /// `generated` (Unix seconds, UTC), with the default locale, times in
+    /// UTC, the currency's usual symbol, and no holder (see `Bank::statement` for the
+    /// bank's own).
+    pub fn statement(&self, start: Date, end: Date, generated: i64) -> Statement {
+        let query = TransactionQuery { from: Some(start), to: Some(end), ..TransactionQuery::default() };
+        let lines: Vec<StatementLine> = self
+            .history(&query)
+            .into_iter()
+            .map(|e| StatementLine {
+                timestamp: e.transaction.timestamp,
+                tx_type: e.transaction.tx_type(),
+                memo: e.transaction.memo.to_string(),
+                amount: e.transaction.amount(),
+                balance: e.balance.amount,
+            })
+            .collect();
+        let sum = |tx_type: TransactionType, memo: &str| {
+            lines
+                .iter()
+                .filter(|l| l.tx_type == tx_type && l.memo == memo)
+                .fold(Decimal::ZERO, |total, l| total + l.amount)
+        };
+        let interest = InterestSummary {
+            opening_rate: self.rate_on(start),
+            closing_rate: self.rate_on(end),
+            credited: sum(TransactionType::Deposit, INTEREST_MEMO),
+            charged: sum(TransactionType::Withdraw, CARRYING_CHARGE_MEMO),
+        };
+        Statement {
+            bank: BANK_NAME.to_string(),
+            generated,
+            account_id: self.id,
+            account: self.name.clone(),
+            holder: None,
+            contact: None,
+            currency: self.currency.clone(),
+            symbol: default_symbol(&self.currency),
+            decimals: self.minor_unit_dp,
+            locale: Locale::default(),
+            time_zone: TimeZone::UTC,
+            start,
+            end,
+            opening: self.balance_on(start.add_days(-1)).amount,
+            closing: self.balance_on(end).amount,
+            lines,
+            interest,
+        }
+    }
+
+    /// The interest credited in `year` and the tax withheld from it, month
+    /// by month, generated at `generated` (Unix seconds, UTC), with the
+    /// same defaults as `statement` (see `Bank::tax_certificate` for the
+    /// bank's own).
+    pub fn tax_certificate(&self, year: i32, generated: i64) -> TaxCertificate {
+        let paid_in = format!("{} from ", INTEREST_MEMO);
+        let mut months: Vec<TaxCertificateLine> = (1..=12)
+            .filter_map(|month| Month::new(year, month))
+            .map(|month| TaxCertificateLine { month, gross: Decimal::ZERO, withheld: Decimal::ZERO })
+            .collect();
+        for tx in self.transactions.iter() {
+            let date = tx.date();
+            if date.year != year {
+                continue;
+            }
+            let line = &mut months[date.month as usize - 1];
+            match tx.tx_type() {
+                TransactionType::Deposit if tx.memo == INTEREST_MEMO || tx.memo.starts_with(&paid_in) => line.gross += tx.amount(),
+                TransactionType::Withdraw if tx.memo == WITHHOLDING_TAX_MEMO => line.withheld += tx.amount(),
+                _ => {}
+            }
+        }
+        TaxCertificate {
+            bank: BANK_NAME.to_string(),
+            generated,
+            year,
+            account_id: self.id,
+            account: self.name.clone(),
+            holder: None,
+            address: None,
+            identification: None,
+            currency: self.currency.clone(),
+            symbol: default_symbol(&self.currency),
+            decimals: self.minor_unit_dp,
+            locale: Locale::default(),
+            time_zone: TimeZone::UTC,
+            months,
+        }
+    }
+
+    /// What the transactions posted from `start` through `end` paid in and
+    /// took out under each tag, ordered by tag. A transaction with several
+    /// tags counts towards each of them, so the totals can add up to more
+    /// than the account's movements; untagged transactions are left out.
+    pub fn report_by_tag(&self, start: Date, end: Date) -> Vec<TagTotal> {
+        let mut totals: Vec<TagTotal> = Vec::new();
+        for tx in self.transactions.iter().filter(|t| (start..=end).contains(&t.date())) {
+            for name in tx.tags.iter() {
+                let at = match totals.binary_search_by(|t| t.tag.as_str().cmp(name)) {
+                    Ok(at) => at,
+                    Err(at) => {
+                        totals.insert(at, TagTotal::empty(name, &self.currency));
+                        at
+                    }
+                };
+                let total = &mut totals[at];
+                total.count += 1;
+                match tx.tx_type() {
+                    TransactionType::Deposit => total.inflow.amount += tx.amount(),
+                    TransactionType::Withdraw => total.outflow.amount += tx.amount(),
                 }
+            }
+        }
+        totals
+    }
+
+    /// The period a statement covers by default: from the first
+    /// transaction (or `today`, without any) through `today`, narrowed by
+    /// the query's `from` and `to`.
+    pub fn statement_period(&self, query: &TransactionQuery, today: Date) -> (Date, Date) {
+        let first = self.transactions.iter().next().map_or(today, |t| t.date());
+        (query.from.unwrap_or(first), query.to.unwrap_or(today))
+    }
+
+    /// Write the transactions matching `query` in `format` as of `now`
+    /// (Unix seconds, UTC); CSV uses the default `CsvOptions` otherwise.
+    /// HTML writes `statement` for the query's period (see
+    /// `statement_period`), ignoring its other filters.
+    pub fn export(&self, writer: &mut impl Write, format: StatementFormat, query: &TransactionQuery, now: i64) -> io::Result<()> {
+        match format {
+            StatementFormat::Csv => self.export_csv(writer, &CsvOptions { query: query.clone(), ..CsvOptions::default() }),
+            StatementFormat::Ofx => self.export_ofx(writer, query, now),
+            StatementFormat::Qif => self.export_qif(writer, query),
+            StatementFormat::Html => {
+                let (start, end) = self.statement_period(query, Date::from_timestamp(now));
+                self.statement(start, end, now).write_html(writer)
+            }
+        }
+    }
+
+    /// Add a savings goal. Fails if the target is not in the account's
+    /// currency or rounds to zero, the date is not after `today`, or the
+    /// account already has a goal with that name.
+    pub fn add_goal(&mut self, goal: SavingsGoal, today: Date) -> Result<(), AccountError> {
+        self.get_balance().same_currency(&goal.target)?;
+        if goal.target.amount.round_dp(self.minor_unit_dp) <= Decimal::ZERO {
+            return Err(GoalError::NonPositiveTarget.into());
+        }
+        if goal.target_date <= today {
+            return Err(GoalError::DateNotInFuture(goal.target_date).into());
+        }
+        if self.goals.iter().any(|g| g.name == goal.name) {
+            return Err(GoalError::Duplicate(goal.name).into());
+        }
+        self.goals.push(goal);
+        self.touch();
+        Ok(())
+    }
+
+    /// Remove and return the goal named `name`.
+    pub fn remove_goal(&mut self, name: &str) -> Result<SavingsGoal, AccountError> {
+        let pos = self
+            .goals
+            .iter()
+            .position(|g| g.name == name)
+            .ok_or_else(|| GoalError::NotFound(name.to_string()))?;
+        self.touch();
+        Ok(self.goals.remove(pos))
+    }
+
+    /// Progress on the goal named `name` as of `today`, with the account's
+    /// balance and the interest rate in force then, contributing at
+    /// `frequency` (see `SavingsGoal::progress`).
+    pub fn goal_progress(&self, name: &str, frequency: PaymentFrequency, today: Date) -> Result<GoalProgress, AccountError> {
+        let goal = self
+            .goals
+            .iter()
+            .find(|g| g.name == name)
+            .ok_or_else(|| GoalError::NotFound(name.to_string()))?;
+        goal.progress(&self.get_balance(), self.rate_on(today), frequency, today, self.minor_unit_dp)
+            .ok_or(AccountError::AmountOutOfRange)
+    }
+
+    /// Add a budget envelope. Fails if the limit is not in the account's
+    /// currency or rounds to zero, or the account already has an envelope
+    /// for that category.
+    pub fn add_envelope(&mut self, envelope: Envelope) -> Result<(), AccountError> {
+        self.get_balance().same_currency(&envelope.limit)?;
+        if envelope.limit.amount.round_dp(self.minor_unit_dp) <= Decimal::ZERO {
+            return Err(BudgetError::NonPositiveLimit.into());
+        }
+        if self.envelope(&envelope.category).is_some() {
+            return Err(BudgetError::Duplicate(envelope.category).into());
+        }
+        self.envelopes.push(envelope);
+        self.touch();
+        Ok(())
+    }
+
+    /// Remove and return the envelope for `category`. Withdrawals already
+    /// filed under it keep their category.
+    pub fn remove_envelope(&mut self, category: &str) -> Result<Envelope, AccountError> {
+        let pos = self
+            .envelopes
+            .iter()
+            .position(|e| e.category == category)
+            .ok_or_else(|| BudgetError::NotFound(category.to_string()))?;
+        self.touch();
+        Ok(self.envelopes.remove(pos))
+    }
+
+    /// The envelope for `category`, if the account has one.
+    pub fn envelope(&self, category: &str) -> Option<&Envelope> {
+        self.envelopes.iter().find(|e| e.category == category)
+    }
+
+    /// The envelope for `category` in the calendar month containing `on`:
+    /// its withdrawals that month, net of the reversals filed with them.
+    pub fn envelope_status(&self, category: &str, on: Date) -> Result<EnvelopeStatus, AccountError> {
+        let envelope = self
+            .envelope(category)
+            .ok_or_else(|| BudgetError::NotFound(category.to_string()))?;
+        let units: i64 = self
+            .transactions
+            .iter()
+            .filter(|t| t.category == Some(category))
+            .filter(|t| {
+                let date = Date::from_timestamp(t.timestamp);
+                (date.year, date.month) == (on.year, on.month)
             })
+            .map(|t| -t.units)
+            .sum();
+        Ok(EnvelopeStatus::new(envelope, Decimal::new(units, self.minor_unit_dp)))
+    }
+
+    /// `envelope_status` of every envelope, in the order they were set.
+    pub fn budget(&self, on: Date) -> Vec<EnvelopeStatus> {
+        self.envelopes
+            .iter()
+            .filter_map(|e| self.envelope_status(&e.category, on).ok())
             .collect()
     }
+
+    /// Total (unrounded) interest earned over `days` of daily compounding
+    /// from `start`; the sum of the forecast's interest column.
+    pub fn accrued_interest(&self, days: usize, start: Date) -> Result<Money, AccountError> {
+        let total = self
+            .get_interest_forecast(days, ForecastStep::Daily, start)?
+            .into_iter()
+            .try_fold(Decimal::ZERO, |total, f| total.checked_add(f.interest.amount))
+            .ok_or(AccountError::AmountOutOfRange)?;
+        Ok(Money::new(total, &self.currency))
+    }
+
+    /// Produce a day-by-day compound interest projection using
+    /// Daily Interest = Balance × (Annual Rate / days in the year), the
+    /// days counted on the account's `year_basis`.
+    /// The balance is incremented each day by that day's interest, or at
+    /// each month's end by the month's under `AverageDailyBalance` (see
+    /// `InterestMethod`); a row's balance includes what has accrued. A
+    /// deposit still in its grace period (see `interest_grace_days`) is in
+    /// the balance but earns only from its first earning day. Day 1
+    /// is `start` (normally today), and each day earns the rate in force on it (see
+    /// `rate_on`), so scheduled changes and promotions take effect on
+    /// their dates. With a `step` longer than a day, the days are
+    /// summarized into one row per week, month, or year (see
+    /// `summarize_forecast`). Fails with `AmountOutOfRange` if the balance
+    /// grows too large to represent.
+    pub fn get_interest_forecast(&self, days: usize, step: ForecastStep, start: Date) -> Result<Vec<InterestForecast>, AccountError> {
+        let (annual_interest, changes) = self.rate_schedule(start);
+        summarize_forecast(self.forecast(days, start, annual_interest, changes)?, start, step)
+    }
+
+    /// The totals of `get_interest_forecast(days, .., start)` without
+    /// keeping its rows: interest earned, the ending balance, the effective
+    /// annual yield of the rates in force, and the first day the balance
+    /// reaches each of `milestones` (amounts in the account's currency). A
+    /// milestone above the opening balance is reached when the balance
+    /// rises to it, one below when it falls to it (at a negative rate), and
+    /// one equal to it on day 0. Fails with `AmountOutOfRange` if the
+    /// balance grows too large to represent.
+    pub fn forecast_summary(&self, days: usize, milestones: &[Decimal], start: Date) -> Result<ForecastSummary, AccountError> {
+        let (opening_rate, changes) = self.rate_schedule(start);
+        let opening = self.get_balance().amount;
+        let waiting = self.waiting_on(start);
+        let mut reached: Vec<Option<usize>> = milestones.iter().map(|m| (*m == opening).then_some(0)).collect();
+        // `unit` follows one unit of currency, for the yield.
+        let (mut accrual, mut unit) = (Accrual::default(), Accrual::default());
+        let mut balance = opening;
+        for day in 1..=days {
+            let date = start.add_days(day as i64 - 1);
+            let at = changes.partition_point(|c| c.effective <= date);
+            let daily = rates::daily_on(at.checked_sub(1).map_or(opening_rate, |i| changes[i].annual_interest), self.year_basis, date);
+            accrual.accrue(self.interest_method, earning_on(opening, &waiting, self.interest_method, start, date), daily, date)?;
+            unit.accrue(self.interest_method, Decimal::ONE, daily, date)?;
+            balance = opening.checked_add(accrual.total()).ok_or(AccountError::AmountOutOfRange)?;
+            for (milestone, hit) in milestones.iter().zip(&mut reached) {
+                if hit.is_none() && ((*milestone > opening && balance >= *milestone) || (*milestone < opening && balance <= *milestone)) {
+                    *hit = Some(day);
+                }
+            }
+        }
+        let effective_annual_yield = match days {
+            0 => Decimal::ZERO,
+            _ => rates::annualize_years(Decimal::ONE + unit.total(), self.year_basis.years(start, days)).ok_or(AccountError::AmountOutOfRange)?.round_dp(YIELD_DP),
+        };
+        Ok(ForecastSummary {
+            days,
+            total_interest: Money::new(balance - opening, &self.currency),
+            ending_balance: Money::new(balance, &self.currency),
+            effective_annual_yield,
+            milestones: milestones
+                .iter()
+                .zip(reached)
+                .map(|(target, day)| Milestone { target: Money::new(*target, &self.currency), day })
+                .collect(),
+        })
+    }
+
+    /// The rate in force on `start`, and each later date the rate changes
+    /// (a scheduled change, or a promotion starting or ending) with the
+    /// rate from then on, in date order.
+    fn rate_schedule(&self, start: Date) -> (Decimal, Vec<RateChange>) {
+        let mut dates: Vec<Date> = self
+            .rate_changes
+            .iter()
+            .map(|c| c.effective)
+            .chain(self.promotion.iter().flat_map(|p| [p.start, p.end]))
+            .filter(|d| *d > start)
+            .collect();
+        dates.sort();
+        dates.dedup();
+        let changes = dates.into_iter().map(|effective| RateChange { effective, annual_interest: self.rate_on(effective) }).collect();
+        (self.rate_on(start), changes)
+    }
+
+    /// `get_interest_forecast` as if the account earned `annual_interest`
+    /// throughout instead of its own rates, for comparing what-if
+    /// scenarios. The account is not changed.
+    pub fn forecast_with_rate(&self, days: usize, annual_interest: Decimal, start: Date) -> Result<Vec<InterestForecast>, AccountError> {
+        self.forecast(days, start, annual_interest, Vec::new())
+    }
+
+    /// A value-dated forecast of `days` days from `since`: each day earns
+    /// interest on the balance at its end as the transactions left it,
+    /// each counted from the day it was posted, plus the interest accrued
+    /// since `since`, at the rate in force on it. Days before today follow
+    /// the deposits and withdrawals actually made; from today on no more
+    /// are posted, so the rest projects the current balance forward as
+    /// `get_interest_forecast(.., today)` would, plus the interest accrued
+    /// before today; started today, it is that forecast. A deposit earns
+    /// only once its grace period is over (see `interest_grace_days`).
+    /// Under `MinimumMonthlyBalance` each month earns on its lowest earning
+    /// balance within the forecast. Fails with `AmountOutOfRange` if the
+    /// balance grows too large to represent.
+    pub fn value_dated_forecast(&self, days: usize, since: Date) -> Result<Vec<InterestForecast>, AccountError> {
+        let (opening_rate, changes) = self.rate_schedule(since);
+        let log = &self.transactions;
+        let mut flows: Vec<(Date, i64)> = log.timestamps().iter().zip(log.units()).map(|(ts, u)| (Date::from_timestamp(*ts), *u)).collect();
+        flows.sort_by_key(|(date, _)| *date);
+        let mut flows = flows.into_iter().peekable();
+        // A deposit is `waiting` from the day it is posted until it starts
+        // earning; both come in the order of `grace_deposits`.
+        let grace = self.grace_deposits();
+        let (mut joined, mut matured) = (grace.iter().peekable(), grace.iter().peekable());
+        let (mut posted, mut waiting) = (0i64, 0i64);
+        let mut closing = Vec::with_capacity(days);
+        let mut earning = Vec::with_capacity(days);
+        for day in 1..=days {
+            let date = since.add_days(day as i64 - 1);
+            while let Some((_, units)) = flows.next_if(|(d, _)| *d <= date) {
+                posted = posted.checked_add(units).ok_or(AccountError::AmountOutOfRange)?;
+            }
+            while let Some((_, _, units)) = joined.next_if(|(d, _, _)| *d <= date) {
+                waiting += units;
+            }
+            while let Some((_, _, units)) = matured.next_if(|(_, from, _)| *from <= date) {
+                waiting -= units;
+            }
+            closing.push(posted);
+            earning.push(posted.saturating_sub(waiting).max(posted.min(0)));
+        }
+        // Under `MinimumMonthlyBalance` every day of a month earns on the
+        // lowest of its earning balances within the forecast.
+        if self.interest_method == InterestMethod::MinimumMonthlyBalance {
+            let mut first = 0;
+            while first < days {
+                let month = Month::of(since.add_days(first as i64));
+                let len = (first..days).take_while(|&i| Month::of(since.add_days(i as i64)) == month).count();
+                let lowest = earning[first..first + len].iter().copied().min().unwrap_or_default();
+                earning[first..first + len].fill(lowest);
+                first += len;
+            }
+        }
+        let mut accrual = Accrual::default();
+        let mut rows = Vec::with_capacity(days);
+        for day in 1..=days {
+            let date = since.add_days(day as i64 - 1);
+            let at = changes.partition_point(|c| c.effective <= date);
+            let rate = at.checked_sub(1).map_or(opening_rate, |i| changes[i].annual_interest);
+            let principal = Decimal::new(earning[day - 1], self.minor_unit_dp);
+            let interest = accrual.accrue(self.interest_method, principal, rates::daily_on(rate, self.year_basis, date), date)?;
+            let balance = Decimal::new(closing[day - 1], self.minor_unit_dp)
+                .checked_add(accrual.total())
+                .ok_or(AccountError::AmountOutOfRange)?;
+            rows.push(InterestForecast {
+                first_day: day,
+                day,
+                annual_interest: rate,
+                balance: Money::new(balance, &self.currency),
+                interest: Money::new(interest, &self.currency),
+                real_balance: None,
+                real_interest: None,
+                converted_balance: None,
+                converted_interest: None,
+            });
+        }
+        Ok(rows)
+    }
+
+    /// Total (unrounded) interest the balance earned from `since` through
+    /// the day before `until`, value-dated: the sum of
+    /// `value_dated_forecast`'s interest over those days. Zero unless
+    /// `since` is before `until`.
+    pub fn value_dated_interest(&self, since: Date, until: Date) -> Result<Money, AccountError> {
+        let days = usize::try_from(until.days() - since.days()).unwrap_or(0);
+        let total = self
+            .value_dated_forecast(days, since)?
+            .into_iter()
+            .try_fold(Decimal::ZERO, |total, f| total.checked_add(f.interest.amount))
+            .ok_or(AccountError::AmountOutOfRange)?;
+        Ok(Money::new(total, &self.currency))
+    }
+
+    /// A forecast from `start` at `annual_interest`, then at each of
+    /// `changes` (all after `start`, in date order) from its date.
+    ///
+    /// The projection is memoized: while the balance and rates match the
+    /// previous call, a shorter horizon is a prefix of the cached days and
+    /// a longer one only computes the days past them.
+    fn forecast(&self, days: usize, start: Date, annual_interest: Decimal, changes: Vec<RateChange>) -> Result<Vec<InterestForecast>, AccountError> {
+        let opening = self.get_balance();
+        let waiting = self.waiting_on(start);
+        let mut cache = self.forecast_cache.0.lock().unwrap_or_else(PoisonError::into_inner);
+        let cache = match &mut *cache {
+            Some(c) if c.opening == opening && c.start == start && (c.basis, c.method) == (self.year_basis, self.interest_method) && c.waiting == waiting && c.annual_interest == annual_interest && c.changes == changes => c,
+            stale => stale.insert(ForecastCache {
+                opening,
+                start,
+                basis: self.year_basis,
+                method: self.interest_method,
+                waiting,
+                annual_interest,
+                changes,
+                days: Vec::new(),
+                accrual: Accrual::default(),
+            }),
+        };
+
+        for day in cache.days.len() + 1..=days {
+            let date = start.add_days(day as i64 - 1);
+            let at = cache.changes.partition_point(|c| c.effective <= date);
+            let rate = at.checked_sub(1).map_or(cache.annual_interest, |i| cache.changes[i].annual_interest);
+            let principal = earning_on(cache.opening.amount, &cache.waiting, cache.method, start, date);
+            let interest = cache.accrual.accrue(cache.method, principal, rates::daily_on(rate, cache.basis, date), date)?;
+            let balance = cache.opening.amount.checked_add(cache.accrual.total()).ok_or(AccountError::AmountOutOfRange)?;
+            cache.days.push(InterestForecast {
+                first_day: day,
+                day,
+                annual_interest: rate,
+                balance: Money::new(balance, &self.currency),
+                interest: Money::new(interest, &self.currency),
+                real_balance: None,
+                real_interest: None,
+                converted_balance: None,
+                converted_interest: None,
+            });
+        }
+        Ok(cache.days[..days].to_vec())
+    }
 }
 
+/// One row of a forecast: days `first_day` through `day` (one day unless
+/// summarized by `summarize_forecast`), with the interest earned over them,
+/// the balance at the end of `day`, and the rate in force on it.
+/// `real_balance` and `real_interest` are the same amounts in today's
+/// money, filled in by `adjust_for_inflation`; `converted_balance` and
+/// `converted_interest` are them in another currency, filled in by
+/// `convert_forecast`.
 #[derive(Debug, Clone)]
 pub struct InterestForecast {
+    pub first_day: usize,
     pub day: usize,
-    pub balance: f64,
-    pub interest: f64,
+    pub annual_interest: Decimal,
+    pub balance: Money,
+    pub interest: Money,
+    pub real_balance: Option<Money>,
+    pub real_interest: Option<Money>,
+    pub converted_balance: Option<Money>,
+    pub converted_interest: Option<Money>,
+}
+
+/// Fractional digits of `ForecastSummary::effective_annual_yield`.
+pub const YIELD_DP: u32 = 6;
+
+/// The totals of a forecast over `days` (see `Account::forecast_summary`).
+/// `effective_annual_yield` is the growth of one unit over the horizon,
+/// annualized over the years it spans on the account's `year_basis`, as a
+/// fraction: 5% credited daily yields about 0.051267.
+#[derive(Debug, Clone)]
+pub struct ForecastSummary {
+    pub days: usize,
+    pub total_interest: Money,
+    pub ending_balance: Money,
+    pub effective_annual_yield: Decimal,
+    pub milestones: Vec<Milestone>,
+}
+
+/// A balance to watch for in a forecast, and the day it is first reached;
+/// `None` if not within the horizon.
+#[derive(Debug, Clone)]
+pub struct Milestone {
+    pub target: Money,
+    pub day: Option<usize>,
+}
+
+/// How much of a forecast each row covers: a day, a week (days 1-7, 8-14,
+/// ...), or a calendar month or year, counted from the forecast's start
+/// date, so the first and last rows may be partial periods.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ForecastStep {
+    #[default]
+    Daily,
+    Weekly,
+    Monthly,
+    Yearly,
+}
+
+impl ForecastStep {
+    /// Parse "daily", "weekly", "monthly", or "yearly" (any case).
+    pub fn parse(s: &str) -> Option<Self> {
+        match s.trim().to_lowercase().as_str() {
+            "daily" => Some(ForecastStep::Daily),
+            "weekly" => Some(ForecastStep::Weekly),
+            "monthly" => Some(ForecastStep::Monthly),
+            "yearly" => Some(ForecastStep::Yearly),
+            _ => None,
+        }
+    }
+
+    /// Lowercase name, as accepted by `parse`.
+    pub fn name(&self) -> &'static str {
+        match self {
+            ForecastStep::Daily => "daily",
+            ForecastStep::Weekly => "weekly",
+            ForecastStep::Monthly => "monthly",
+            ForecastStep::Yearly => "yearly",
+        }
+    }
+
+    /// The period holding `day` of a forecast starting on `start`; days
+    /// with the same key share a row.
+    fn period(&self, start: Date, day: usize) -> (i64, u32) {
+        let date = start.add_days(day as i64 - 1);
+        match self {
+            ForecastStep::Daily => (day as i64, 0),
+            ForecastStep::Weekly => (((day - 1) / 7) as i64, 0),
+            ForecastStep::Monthly => (date.year as i64, date.month),
+            ForecastStep::Yearly => (date.year as i64, 0),
+        }
+    }
+}
+
+/// Merge the daily rows of a forecast starting on `start` into one row per
+/// `step` period: the interest (and real and converted interest) summed,
+/// and the balance (and real and converted balance) and rate of the
+/// period's last day. Apply `adjust_for_inflation` and `convert_forecast`
+/// to the daily rows first. Fails with
+/// `AmountOutOfRange` if a sum overflows.
+pub fn summarize_forecast(forecast: Vec<InterestForecast>, start: Date, step: ForecastStep) -> Result<Vec<InterestForecast>, AccountError> {
+    if step == ForecastStep::Daily {
+        return Ok(forecast);
+    }
+    let add = |total: &Money, m: &Money| {
+        total
+            .amount
+            .checked_add(m.amount)
+            .map(|amount| Money::new(amount, &m.currency))
+            .ok_or(AccountError::AmountOutOfRange)
+    };
+    let mut rows: Vec<InterestForecast> = Vec::new();
+    for f in forecast {
+        match rows.last_mut() {
+            Some(row) if step.period(start, row.day) == step.period(start, f.day) => {
+                row.interest = add(&row.interest, &f.interest)?;
+                row.real_interest = match (&row.real_interest, &f.real_interest) {
+                    (Some(total), Some(m)) => Some(add(total, m)?),
+                    _ => None,
+                };
+                row.converted_interest = match (&row.converted_interest, &f.converted_interest) {
+                    (Some(total), Some(m)) => Some(add(total, m)?),
+                    _ => None,
+                };
+                row.day = f.day;
+                row.annual_interest = f.annual_interest;
+                row.balance = f.balance;
+                row.real_balance = f.real_balance;
+                row.converted_balance = f.converted_balance;
+            }
+            _ => rows.push(f),
+        }
+    }
+    Ok(rows)
+}
+
+/// Fill in the real amounts of `forecast`, a daily forecast from `start`,
+/// as if prices rose by `annual_inflation` (a fraction) a year, compounded
+/// daily on `basis`, the year basis its interest accrued on. Each day's
+/// amounts are divided by that day's price level, so a balance that only
+/// keeps pace with inflation stays flat in real terms.
+pub fn adjust_for_inflation(forecast: &mut [InterestForecast], annual_inflation: Decimal, basis: YearBasis, start: Date) -> Result<(), AccountError> {
+    let mut price_level = Decimal::ONE;
+    for f in forecast {
+        let date = start.add_days(f.day as i64 - 1);
+        price_level = price_level
+            .checked_mul(Decimal::ONE + rates::daily_on(annual_inflation, basis, date))
+            .ok_or(AccountError::AmountOutOfRange)?;
+        let real = |m: &Money| {
+            m.amount
+                .checked_div(price_level)
+                .map(|amount| Money::new(amount, &m.currency))
+                .ok_or(AccountError::AmountOutOfRange)
+        };
+        f.real_balance = Some(real(&f.balance)?);
+        f.real_interest = Some(real(&f.interest)?);
+    }
+    Ok(())
+}
+
+/// Fill in the converted amounts of `forecast`: each day's balance and
+/// interest in `currency` at `rate`, units of it per unit of the account's
+/// currency. One rate holds throughout, so the converted balance grows
+/// only with interest, not with the exchange rate.
+pub fn convert_forecast(forecast: &mut [InterestForecast], currency: &str, rate: Decimal) -> Result<(), AccountError> {
+    for f in forecast {
+        let convert = |m: &Money| {
+            m.amount
+                .checked_mul(rate)
+                .map(|amount| Money::new(amount, currency))
+                .ok_or(AccountError::AmountOutOfRange)
+        };
+        f.converted_balance = Some(convert(&f.balance)?);
+        f.converted_interest = Some(convert(&f.interest)?);
+    }
+    Ok(())
+}
+
+/// One row of an account's history: a transaction and the balance right
+/// after it was posted.
+#[derive(Debug, Clone)]
+pub struct HistoryEntry<'a> {
+    pub transaction: TransactionRef<'a>,
+    pub balance: Money,
 }
@@ -0,0 +1,153 @@
+use std::collections::BTreeSet;
+use std::fmt;
+
+use crate::api::account::TransactionType;
+use crate::api::bank::Bank;
+use crate::api::date::Date;
+use crate::api::decimal::Decimal;
+use crate::api::event::BankEvent;
+use crate::api::money::Money;
+
+/// Errors raised when an account's alerts are set.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum AlertError {
+    /// A balance or withdrawal threshold (given) is not above zero.
+    NonPositiveThreshold(Decimal),
+    /// The inactivity alert is set for zero days.
+    ZeroDays,
+}
+
+impl fmt::Display for AlertError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            AlertError::NonPositiveThreshold(threshold) => write!(f, "alert threshold {} must be greater than zero", threshold),
+            AlertError::ZeroDays => write!(f, "an inactivity alert needs at least one day"),
+        }
+    }
+}
+
+impl std::error::Error for AlertError {}
+
+/// What an account's holder wants to be alerted about, in the account's
+/// currency: the balance falling below `balance_below`, any one withdrawal
+/// (or transfer out) above `withdrawal_above`, and no posting for
+/// `inactive_days` days. `None` turns that alert off.
+///
+/// `below_raised` and `inactive_raised` remember what has already been
+/// reported, so a balance that stays low or an account that stays dormant
+/// is alerted about once: the low-balance alert fires again only after the
+/// balance has been back at or above the threshold, and the inactivity
+/// alert once per last posting date.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct AlertSettings {
+    pub balance_below: Option<Decimal>,
+    pub withdrawal_above: Option<Decimal>,
+    pub inactive_days: Option<u32>,
+    pub below_raised: bool,
+    pub inactive_raised: Option<Date>,
+}
+
+impl AlertSettings {
+    /// Alerts with nothing reported yet. Fails if a threshold is not above
+    /// zero or `inactive_days` is zero.
+    pub fn new(balance_below: Option<Decimal>, withdrawal_above: Option<Decimal>, inactive_days: Option<u32>) -> Result<Self, AlertError> {
+        if let Some(threshold) = balance_below.into_iter().chain(withdrawal_above).find(|t| *t <= Decimal::ZERO) {
+            return Err(AlertError::NonPositiveThreshold(threshold));
+        }
+        if inactive_days == Some(0) {
+            return Err(AlertError::ZeroDays);
+        }
+        Ok(Self { balance_below, withdrawal_above, inactive_days, ..Self::default() })
+    }
+
+    /// Returns true when every alert is off.
+    pub fn is_empty(&self) -> bool {
+        self.balance_below.is_none() && self.withdrawal_above.is_none() && self.inactive_days.is_none()
+    }
+}
+
+/// Something an account's `AlertSettings` asked to hear about.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Alert {
+    /// The balance fell below `threshold`.
+    LowBalance { balance: Money, threshold: Decimal },
+    /// One withdrawal or transfer out of `amount` went above `threshold`.
+    LargeWithdrawal { amount: Money, threshold: Decimal },
+    /// Nothing has been posted for `days` days, since `last`.
+    Inactive { days: u32, last: Date },
+}
+
+impl Alert {
+    /// Stable snake_case name, e.g. "low_balance".
+    pub fn kind(&self) -> &'static str {
+        match self {
+            Alert::LowBalance { .. } => "low_balance",
+            Alert::LargeWithdrawal { .. } => "large_withdrawal",
+            Alert::Inactive { .. } => "inactive",
+        }
+    }
+}
+
+impl fmt::Display for Alert {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Alert::LowBalance { balance, threshold } => write!(f, "balance {} is below {}", balance, threshold),
+            Alert::LargeWithdrawal { amount, threshold } => write!(f, "withdrawal of {} is above {}", amount, threshold),
+            Alert::Inactive { days, last } => write!(f, "no activity for {} days since {}", days, last),
+        }
+    }
+}
+
+/// Check `events`, just taken from `bank`, against each account's alerts
+/// and return an `AlertRaised` event for each alert due, for the event bus
+/// to publish after them. Withdrawals and transfers out are checked one by
+/// one; the balance of every account the events touched is checked as it
+/// now stands; and every account with an inactivity alert is checked
+/// against its last posting and the bank's date. What was reported is
+/// recorded on the account's settings, so nothing is alerted twice.
+/// Archived accounts raise no alerts.
+pub fn evaluate(bank: &mut Bank, events: &[BankEvent]) -> Vec<BankEvent> {
+    let mut raised = Vec::new();
+    let mut touched = BTreeSet::new();
+    for event in events {
+        let (account, withdrawn) = match event {
+            BankEvent::TransactionPosted { account, tx_type, amount, .. } => (account, (*tx_type == TransactionType::Withdraw).then_some(amount)),
+            BankEvent::TransferCompleted { from, to, debited, .. } => {
+                touched.insert(to.clone());
+                (from, Some(debited))
+            }
+            BankEvent::InterestPosted { account, .. } | BankEvent::TransactionsImported { account, .. } => (account, None),
+            _ => continue,
+        };
+        touched.insert(account.clone());
+        let threshold = bank.find_account(account).filter(|a| !a.is_archived()).and_then(|a| a.alerts.withdrawal_above);
+        if let (Some(threshold), Some(amount)) = (threshold, withdrawn)
+            && amount.amount > threshold
+        {
+            let alert = Alert::LargeWithdrawal { amount: amount.clone(), threshold };
+            raised.push(BankEvent::AlertRaised { account: account.clone(), alert });
+        }
+    }
+    let today = bank.today();
+    for acct in bank.accounts.iter_mut().filter(|a| !a.is_archived()) {
+        if let Some(threshold) = acct.alerts.balance_below
+            && touched.contains(&acct.name)
+        {
+            let balance = acct.get_balance();
+            let below = balance.amount < threshold;
+            if below && !acct.alerts.below_raised {
+                raised.push(BankEvent::AlertRaised { account: acct.name.clone(), alert: Alert::LowBalance { balance, threshold } });
+            }
+            acct.alerts.below_raised = below;
+        }
+        let last = acct.transactions.last().map(|t| t.date());
+        if let (Some(days), Some(last)) = (acct.alerts.inactive_days, last)
+            && today.days() - last.days() >= i64::from(days)
+            && acct.alerts.inactive_raised != Some(last)
+        {
+            acct.alerts.inactive_raised = Some(last);
+            raised.push(BankEvent::AlertRaised { account: acct.name.clone(), alert: Alert::Inactive { days, last } });
+        }
+    }
+    raised
+}
@@ -1,107 +1,4144 @@
-use crate::api::account::Account;
-use crate::api::forex::{Currency, Forex};
+use std::collections::{BTreeMap, HashSet, VecDeque};
+use std::fmt;
+use std::fs;
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+use crate::api::account::{Account, AccountError, ForecastStep, InterestMethod, Promotion, TransactionType};
+use crate::api::alert::{AlertError, AlertSettings};
+use crate::api::budget::{BudgetError, EnvelopeStatus};
+use crate::api::calendar::{BusinessCalendar, Holiday};
+use crate::api::cheque::{Cheque, ChequeError, DEFAULT_CLEARING_DAYS};
+use crate::api::comparison::{AccountComparison, ComparedAccount, ComparisonError, ComparisonPoint};
+use crate::api::compliance::{ComplianceSettings, ConversionLimit, FlaggedTransaction, LimitBreach, LimitPeriod};
+use crate::api::credential::Credential;
+use crate::api::conversion_log::{ConversionFilter, ConversionRecord};
+use crate::api::customer::{Customer, Identification, VerificationStatus};
+use crate::api::date::{days_in_month, format_timestamp, format_utc_time, Clock, Date, Month, SimulationClock, SystemClock, TimeZone, Weekday};
+use crate::api::decimal::{Decimal, RoundingStrategy};
+use crate::api::event::{BankEvent, EVENT_LIMIT};
+use crate::api::fee::{Conversion, ConversionLeg};
+use crate::api::forex::{Currency, Forex, ForexError, RateSource, RateType};
+use crate::api::format::{format_amount, Locale};
+use crate::api::error::Error;
+use crate::api::hold::{Hold, HoldError, DEFAULT_HOLD_DAYS};
+use crate::api::import::{AccountImportReport, AccountRow, AccountRows, ImportError, ImportFailure, ImportReport, ImportRow, LedgerImportReport, LedgerRows};
+use crate::api::integrity::{IntegrityReport, Violation};
+use crate::api::forward::{ForwardContract, ForwardError, ForwardSettlement, ForwardSide, ForwardValuation};
+use crate::api::ledger::TransactionRef;
+use crate::api::limit_order::{LimitOrder, LimitOrderError, LimitOrderFill};
+use crate::api::limit_profile::{self, LimitExceeded, LimitKind, LimitProfile, LimitTarget};
+use crate::api::loan::{AmortizationRow, Loan, LoanError, PaymentFrequency};
+use crate::api::market::{RateModel, Rng};
+use crate::api::money::Money;
+use crate::api::moving_average::MovingAverage;
+use crate::api::monte_carlo::{FxPath, MonteCarlo};
+use crate::api::parallel;
+use crate::api::pending::{HeldTransaction, PendingError, PendingTransaction, TransactionState};
+use crate::api::portfolio::{Asset, Holding, Portfolio};
+use crate::api::position::PositionReport;
+use crate::api::rates::YearBasis;
+use crate::api::receipt::ExchangeReceipt;
+use crate::api::rounding::RoundingPolicy;
+use crate::api::search::{NameMatch, TransactionQuery};
+use crate::api::service_charge::{MaintainingBalance, ServiceChargeError, ServiceChargeRun, ServiceCharges};
+use crate::api::simulation::{SimulationProfile, SimulationReport, Stopwatch};
+use crate::api::standing_order::{StandingOrder, StandingOrderError, StandingOrderRun};
+use crate::api::summary::{AccountSummary, MonthlySummary};
+use crate::api::sweep::{SweepError, SweepRule, SweepRun};
+use crate::api::tag;
+use crate::api::tax::TaxCertificate;
+use crate::api::statement::{csv_field, file_stem, Statement, StatementFormat, BANK_NAME, CARRYING_CHARGE_MEMO, INTEREST_MEMO, MAINTAINING_BALANCE_MEMO, SERVICE_CHARGE_MEMO, WITHHOLDING_TAX_MEMO};
+use crate::api::denomination::CashBreakdown;
+use crate::api::idempotency::{IdempotencyRecord, IdempotencyStore, IdempotentResult};
+use crate::api::till::{Till, TillError, TillReport};
+
+/// Decimal places of the rate recorded in `Bank::exchange` memos.
+pub const EXCHANGE_RATE_DP: u32 = 6;
+
+/// Errors raised by bank-level operations.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum BankError {
+    AccountNotFound(String),
+    /// An account, or an alias, already has this name.
+    AccountExists(String),
+    AliasNotFound(String),
+    CustomerNotFound(usize),
+    FlagNotFound(usize),
+    PendingNotFound(usize),
+    ReceiptNotFound(usize),
+    LoanNotFound(usize),
+    StandingOrderNotFound(usize),
+    SweepNotFound(usize),
+    LimitOrderNotFound(usize),
+    CheckpointNotFound(String),
+    /// The alias is already an account's name or another alias.
+    AliasTaken(String),
+    /// An alias made only of whitespace.
+    BlankAlias,
+    /// The account has no transaction at this (0-based) index.
+    TransactionNotFound(String, usize),
+    /// The account has no transaction with this bank-wide sequence number.
+    SequenceNotFound(String, u64),
+    /// The account is archived, so it takes no postings.
+    AccountArchived(String),
+    /// The account is not archived, so there is nothing to restore.
+    AccountNotArchived(String),
+    /// The account cannot be archived while it still holds money.
+    BalanceRemaining(String),
+    /// Transactions cannot be compacted up to a day after today (see
+    /// `compaction::compact`).
+    CutoffAfterToday(Date),
+    /// A transfer named the same account as source and destination.
+    SameAccount(String),
+    /// An exchange between two accounts held in the same currency.
+    SameCurrency(String),
+    /// An exchange between accounts of different customers.
+    DifferentOwners(String, String),
+    /// Interest can only be paid into an account in the same currency.
+    PayoutCurrency(String),
+    /// Deposits in the account's own currency need no conversion.
+    AutoConversionCurrency(String),
+    /// The currency cannot be retired while accounts are denominated in it.
+    CurrencyInUse(String, usize),
+    /// The account is held in the base currency, so it has no FX position.
+    BaseCurrencyAccount(String),
+    /// The account refused the transaction.
+    Account(AccountError),
+    /// An amount could not be converted between currencies.
+    Forex(ForexError),
+    /// The loan could not be set up or paid.
+    Loan(LoanError),
+    /// The standing order could not be set up.
+    StandingOrder(StandingOrderError),
+    /// The sweep rule could not be set up.
+    Sweep(SweepError),
+    /// The account's alerts could not be set.
+    Alert(AlertError),
+    /// The account's service charges could not be set.
+    ServiceCharge(ServiceChargeError),
+    /// The accounts could not be compared.
+    Comparison(ComparisonError),
+    /// The forward contract could not be booked.
+    Forward(ForwardError),
+    /// The limit order could not be placed.
+    LimitOrder(LimitOrderError),
+    /// Cash could not move through the till.
+    Till(TillError),
+    /// The held transaction could not be posted or rejected.
+    Pending(PendingError),
+    /// A conversion would exceed a currency's conversion limit (see
+    /// `ComplianceSettings::conversion_limits`).
+    ConversionLimit(Box<LimitBreach>),
+    /// The admin passphrase was rejected.
+    InvalidPassphrase,
+    /// The withdrawal or transfer is above the approval threshold, so it
+    /// was queued as this request instead of posted (see `Bank::approve`).
+    AwaitingApproval(usize),
+    /// The idempotency key was already used for a different request.
+    IdempotencyKeyReused(String),
+    /// The account changed since the client read it: its version is
+    /// `actual`, not the `expected` one (see `Bank::expect_version`).
+    VersionConflict { account: String, expected: u64, actual: u64 },
+    /// The customer cannot be verified, or rejected, without an identity
+    /// document on file.
+    MissingIdentification(usize),
+    /// The named account's holder has not passed KYC, and the amount would
+    /// exceed the lower limits for unverified accounts (see
+    /// `ComplianceSettings::unverified_limit`).
+    UnverifiedLimit(String, Box<LimitBreach>),
+    /// The amount is above a cap of a limit profile the account falls
+    /// under (see `ComplianceSettings::limit_profiles`).
+    LimitExceeded(Box<LimitExceeded>),
+    /// An account type name (given) is blank or holds a colon.
+    InvalidAccountType(String),
+    /// An annual interest rate below zero was refused because negative
+    /// rates are not enabled (see `ComplianceSettings::allow_negative_rates`).
+    NegativeRate(Decimal),
+    /// An annual interest rate of -100% or less, which would take more
+    /// than the whole balance within a year.
+    RateOutOfRange(Decimal),
+    /// A change was refused because the bank is read-only (see
+    /// `Bank::set_read_only`).
+    ReadOnly,
+}
+
+impl fmt::Display for BankError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            BankError::AccountNotFound(name) => write!(f, "account {} not found", name),
+            BankError::AccountExists(name) => write!(f, "an account named {} already exists", name),
+            BankError::AliasNotFound(alias) => write!(f, "no account has the alias {}", alias),
+            BankError::AliasTaken(alias) => write!(f, "{} is already an account name or alias", alias),
+            BankError::BlankAlias => write!(f, "an alias cannot be blank"),
+            BankError::CustomerNotFound(id) => write!(f, "customer {} not found", id),
+            BankError::FlagNotFound(id) => write!(f, "no flagged transaction with ID {}", id),
+            BankError::PendingNotFound(id) => write!(f, "no held transaction with ID {}", id),
+            BankError::ReceiptNotFound(number) => write!(f, "no exchange receipt numbered {}", number),
+            BankError::LoanNotFound(id) => write!(f, "loan {} not found", id),
+            BankError::StandingOrderNotFound(id) => write!(f, "standing order {} not found", id),
+            BankError::SweepNotFound(id) => write!(f, "sweep {} not found", id),
+            BankError::LimitOrderNotFound(id) => write!(f, "limit order {} not found", id),
+            BankError::CheckpointNotFound(label) => write!(f, "no checkpoint named {}", label),
+            BankError::TransactionNotFound(name, index) => write!(f, "account {} has no transaction {}", name, index + 1),
+            BankError::SequenceNotFound(name, sequence) => write!(f, "account {} has no transaction with sequence number {}", name, sequence),
+            BankError::AccountArchived(name) => write!(f, "account {} is archived", name),
+            BankError::AccountNotArchived(name) => write!(f, "account {} is not archived", name),
+            BankError::BalanceRemaining(name) => write!(f, "account {} still holds money; empty it before archiving", name),
+            BankError::CutoffAfterToday(date) => write!(f, "cannot compact transactions up to {}, which is after today", date),
+            BankError::SameAccount(name) => write!(f, "cannot transfer from {} to itself", name),
+            BankError::SameCurrency(code) => write!(f, "both accounts are held in {}; use a transfer", code),
+            BankError::DifferentOwners(from, to) => write!(f, "{} and {} belong to different customers", from, to),
+            BankError::AutoConversionCurrency(code) => write!(f, "deposits in {} need no conversion", code),
+            BankError::PayoutCurrency(name) => write!(f, "interest cannot be paid into {}, which is in another currency", name),
+            BankError::CurrencyInUse(code, n) => write!(f, "{} is still held by {} account(s)", code, n),
+            BankError::BaseCurrencyAccount(name) => write!(f, "account {} is held in the base currency and has no FX position", name),
+            BankError::Account(e) => write!(f, "{}", e),
+            BankError::Forex(e) => write!(f, "{}", e),
+            BankError::Loan(e) => write!(f, "{}", e),
+            BankError::StandingOrder(e) => write!(f, "{}", e),
+            BankError::Sweep(e) => write!(f, "{}", e),
+            BankError::Alert(e) => write!(f, "{}", e),
+            BankError::ServiceCharge(e) => write!(f, "{}", e),
+            BankError::Comparison(e) => write!(f, "{}", e),
+            BankError::Forward(e) => write!(f, "{}", e),
+            BankError::LimitOrder(e) => write!(f, "{}", e),
+            BankError::Till(e) => write!(f, "{}", e),
+            BankError::Pending(e) => write!(f, "{}", e),
+            BankError::ConversionLimit(breach) => write!(f, "converting {}", breach),
+            BankError::IdempotencyKeyReused(key) => write!(f, "idempotency key {} was already used for a different request", key),
+            BankError::VersionConflict { account, expected, actual } => {
+                write!(f, "account {} has changed: expected version {}, found {}", account, expected, actual)
+            }
+            BankError::InvalidPassphrase => write!(f, "incorrect admin passphrase"),
+            BankError::AwaitingApproval(id) => write!(f, "held for an admin's approval as request {}", id),
+            BankError::MissingIdentification(id) => write!(f, "customer {} has no identification on file", id),
+            BankError::UnverifiedLimit(name, breach) => write!(f, "account {} is not verified, and {}", name, breach),
+            BankError::LimitExceeded(breach) => write!(f, "{}", breach),
+            BankError::InvalidAccountType(name) => write!(f, "invalid account type {:?} (expected a name without colons)", name),
+            BankError::NegativeRate(rate) => {
+                write!(f, "annual rate {} is below zero, and negative rates are not enabled", rate)
+            }
+            BankError::RateOutOfRange(rate) => write!(f, "annual rate {} must be above -1 (-100%)", rate),
+            BankError::ReadOnly => write!(f, "the bank is open read-only"),
+        }
+    }
+}
+
+impl std::error::Error for BankError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            BankError::Account(e) => Some(e),
+            BankError::Forex(e) => Some(e),
+            BankError::Loan(e) => Some(e),
+            BankError::StandingOrder(e) => Some(e),
+            BankError::Sweep(e) => Some(e),
+            BankError::Alert(e) => Some(e),
+            BankError::ServiceCharge(e) => Some(e),
+            BankError::Comparison(e) => Some(e),
+            BankError::Forward(e) => Some(e),
+            BankError::LimitOrder(e) => Some(e),
+            BankError::Till(e) => Some(e),
+            BankError::Pending(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+impl From<AccountError> for BankError {
+    fn from(e: AccountError) -> Self {
+        BankError::Account(e)
+    }
+}
+
+impl From<ForexError> for BankError {
+    fn from(e: ForexError) -> Self {
+        BankError::Forex(e)
+    }
+}
+
+impl From<LoanError> for BankError {
+    fn from(e: LoanError) -> Self {
+        BankError::Loan(e)
+    }
+}
+
+impl From<StandingOrderError> for BankError {
+    fn from(e: StandingOrderError) -> Self {
+        BankError::StandingOrder(e)
+    }
+}
+
+impl From<SweepError> for BankError {
+    fn from(e: SweepError) -> Self {
+        BankError::Sweep(e)
+    }
+}
+
+impl From<AlertError> for BankError {
+    fn from(e: AlertError) -> Self {
+        BankError::Alert(e)
+    }
+}
+
+impl From<ServiceChargeError> for BankError {
+    fn from(e: ServiceChargeError) -> Self {
+        BankError::ServiceCharge(e)
+    }
+}
+
+impl From<ComparisonError> for BankError {
+    fn from(e: ComparisonError) -> Self {
+        BankError::Comparison(e)
+    }
+}
+
+impl From<ForwardError> for BankError {
+    fn from(e: ForwardError) -> Self {
+        BankError::Forward(e)
+    }
+}
+
+impl From<LimitOrderError> for BankError {
+    fn from(e: LimitOrderError) -> Self {
+        BankError::LimitOrder(e)
+    }
+}
+
+impl From<TillError> for BankError {
+    fn from(e: TillError) -> Self {
+        BankError::Till(e)
+    }
+}
+
+impl From<PendingError> for BankError {
+    fn from(e: PendingError) -> Self {
+        BankError::Pending(e)
+    }
+}
+
+/// Result of a successful `Bank::transfer`.
+/// - `debited`: amount taken from the source, in its currency.
+/// - `credited`: amount added to the destination, in its currency.
+/// - `rate`: units of the destination currency per unit of the source
+///   currency used for the conversion (1 when they match).
+/// - `fee`: the conversion fee kept from the destination leg, in its
+///   currency; zero when both accounts share a currency.
+/// - `legs`: how the debited amount was converted, one leg per currency
+///   it passed through (see `Forex::route`), exact and before the fee;
+///   empty when both accounts share a currency.
+/// - `receipt`: the number of the conversion's exchange receipt (see
+///   `exchange_receipt`); `None` when both accounts share a currency.
+#[derive(Debug, Clone)]
+pub struct TransferReceipt {
+    pub from: String,
+    pub to: String,
+    pub debited: Money,
+    pub credited: Money,
+    pub rate: Decimal,
+    pub fee: Money,
+    pub legs: Vec<ConversionLeg>,
+    pub receipt: Option<usize>,
+}
+
+/// What `Bank::end_of_day` did: on a day that is not a business day, the
+/// currencies whose last close was carried onto it, with the rate carried;
+/// forwards settled on their value date, then standing-order transfers,
+/// then sweeps, then, on the last day of a month, the service charges taken
+/// and the number of month-end statements queued for delivery (see
+/// `take_statements`).
+#[derive(Debug, Clone)]
+pub struct EndOfDay {
+    pub date: Date,
+    pub carried: Vec<(String, Decimal)>,
+    pub forwards: Vec<ForwardSettlement>,
+    pub standing_orders: Vec<StandingOrderRun>,
+    pub sweeps: Vec<SweepRun>,
+    pub service_charges: Vec<ServiceChargeRun>,
+    pub statements: usize,
+}
 
 /// Bank is the top-level orchestrator that holds:
 /// - a Forex calculator and registry
-/// - a global annual interest rate
+/// - a global annual interest rate, the tax withheld from interest, and
+///   the year basis and method new accounts accrue interest by, and the
+///   days their deposits wait before earning
 /// - a chosen base currency
-/// - a list of accounts
+/// - a list of accounts and the customers that own them
+/// - the loans drawn into, and repaid from, those accounts
+/// - standing orders that repeat transfers between them, and sweep rules
+///   that move what an account holds above a threshold to another
+/// - FX forward contracts booked for them, open and settled
+/// - the limit-order book, filled as rates are updated
+/// - the teller's cash drawer, which the cash operations move bills and
+///   coins through (see `cash_deposit`)
+/// - compliance settings, including per-currency conversion limits, the
+///   queue of flagged large transactions, and the pending withdrawals and
+///   transfers held for approval, with what became of them
+/// - an optional admin passphrase guarding the admin role
+/// - the rounding policy for posted interest and settled conversions, plus
+///   the per-currency residue left over by that rounding
+/// - the display locale used to format amounts (see `format_money`), and
+///   the time zone timestamps are shown in (see `format_timestamp`)
+/// - the business-day calendar scheduled dates are rolled by (see
+///   `BusinessCalendar`)
+/// - the idempotency keys of recent postings and what they returned (see
+///   `post_transaction_once`)
+/// - the bank-wide sequence number for the next posting (see
+///   `next_sequence`)
+/// - labelled in-memory checkpoints of the whole state (see `checkpoint`)
+/// - the clock every timestamp, accrual date, and schedule is read from
+///   (see `set_clock`), and the simulation clock when one is driving it
+/// - the teller at the counter and the customer whose session is open,
+///   which exchange receipts record (see `set_teller` and `log_in`)
+/// - the `BankEvent`s recorded since observers last drained them (see
+///   `take_events`), and the month-end statements not yet delivered (see
+///   `take_statements`)
 ///
-/// Builder pattern: methods like `set_forex`, `set_annual_interest`, and
-/// `set_base_currency` take and return `Self` so calls can be chained
-/// fluently (similar to Java builders). Example:
-/// `Bank::new().set_forex(...).set_annual_interest(0.05).build()`.
-#[derive(Debug)]
+/// Builder pattern: `Bank::builder()` returns a `BankBuilder` whose methods
+/// like `set_forex` and `set_annual_interest` take and return the builder so
+/// calls can be chained fluently (similar to Java builders). `build()` only
+/// compiles after `set_forex`. Example:
+/// `Bank::builder().set_forex(forex).set_annual_interest(Decimal::new(5, 2)).build()`.
+#[derive(Debug, Clone)]
 pub struct Bank {
     pub forex: Forex,
-    pub annual_interest: f64,
+    pub annual_interest: Decimal,
+    pub withholding_tax_rate: Decimal,
+    pub year_basis: YearBasis,
+    pub interest_method: InterestMethod,
+    pub interest_grace_days: u32,
+    pub cheque_clearing_days: u32,
     pub base_currency: Currency,
     pub accounts: Vec<Account>,
+    pub customers: Vec<Customer>,
+    pub loans: Vec<Loan>,
+    pub standing_orders: Vec<StandingOrder>,
+    pub sweeps: Vec<SweepRule>,
+    pub forwards: Vec<ForwardContract>,
+    pub limit_orders: Vec<LimitOrder>,
+    pub till: Till,
+    pub compliance: ComplianceSettings,
+    pub flagged: Vec<FlaggedTransaction>,
+    pub pending: Vec<PendingTransaction>,
+    pub admin_credential: Option<Credential>,
+    pub rounding: RoundingPolicy,
+    pub rounding_residue: BTreeMap<String, Decimal>,
+    pub locale: Locale,
+    pub time_zone: TimeZone,
+    pub calendar: BusinessCalendar,
+    pub idempotency: IdempotencyStore,
+    checkpoints: Vec<(String, Bank)>,
+    events: VecDeque<BankEvent>,
+    statements: Vec<Statement>,
+    read_only: bool,
+    limits_overridden: bool,
+    teller: Option<String>,
+    session: Option<String>,
+    next_sequence: u64,
+    clock: Arc<dyn Clock>,
+    simulation: Option<Arc<SimulationClock>>,
 }
 
-impl Bank {
-    /// Create a bank with default fields; builder methods configure details.
-    pub fn new() -> Self {
-        Self {
-            forex: Forex::new(),
-            annual_interest: 0.05,
-            base_currency: Currency {
-                code: String::from(""),
-                name: String::from(""),
-                rate: 0.0,
-            },
-            accounts: Vec::new(),
-        }
+/// Typestate marker: the `BankBuilder` has no `Forex` yet.
+#[derive(Debug, Clone, Copy)]
+pub struct NoForex;
+
+/// Builder for `Bank`. `build()` only exists once `set_forex` has supplied
+/// the currency catalog; the bank's base currency is the Forex base, which
+/// the `Forex` builder guarantees is registered.
+#[derive(Debug, Clone)]
+pub struct BankBuilder<F> {
+    forex: F,
+    annual_interest: Decimal,
+    withholding_tax_rate: Decimal,
+    year_basis: YearBasis,
+    interest_method: InterestMethod,
+    interest_grace_days: u32,
+    cheque_clearing_days: u32,
+    compliance: ComplianceSettings,
+    admin_credential: Option<Credential>,
+    rounding: RoundingPolicy,
+    locale: Locale,
+    time_zone: TimeZone,
+    calendar: Option<BusinessCalendar>,
+    clock: Arc<dyn Clock>,
+}
+
+impl<F> BankBuilder<F> {
+    /// Set the bank-wide annual interest rate as a fraction (e.g., `Decimal::new(5, 2)` = 5%).
+    pub fn set_annual_interest(mut self, rate: Decimal) -> Self {
+        self.annual_interest = rate;
+        self
     }
 
-    /// Set the Forex instance. Returns `Self` for chaining.
-    pub fn set_forex(mut self, forex: Forex) -> Self {
-        self.forex = forex;
+    /// Withhold `rate` of posted interest as tax (e.g. `Decimal::new(20, 2)`
+    /// = 20%), none by default (see `Bank::post_interest`).
+    pub fn set_withholding_tax_rate(mut self, rate: Decimal) -> Self {
+        self.withholding_tax_rate = rate;
         self
     }
 
-    /// Set the bank-wide annual interest rate as a fraction (e.g., 0.05 = 5%).
-    /// Returns `Self` for chaining.
-    pub fn set_annual_interest(mut self, rate: f64) -> Self {
-        self.annual_interest = rate;
+    /// Accrue new accounts' interest on `basis`, `YearBasis::Fixed365` by
+    /// default.
+    pub fn set_year_basis(mut self, basis: YearBasis) -> Self {
+        self.year_basis = basis;
         self
     }
 
-    /// Choose the base currency by code (e.g., "PHP"). If the code is not
-    /// already registered in Forex, a placeholder is created. Returns `Self`.
-    pub fn set_base_currency(mut self, code: &str) -> Self {
-        if let Some(cur) = self
-            .forex
-            .currencies_detailed()
-            .into_iter()
-            .find(|c| c.code == code)
-        {
-            self.base_currency = cur;
-        } else {
-            self.base_currency = Currency {
-                code: code.to_string(),
-                name: code.to_string(),
-                rate: 1.0,
-            };
-        }
+    /// Accrue new accounts' interest by `method`,
+    /// `InterestMethod::DailyBalance` by default.
+    pub fn set_interest_method(mut self, method: InterestMethod) -> Self {
+        self.interest_method = method;
         self
     }
 
-    /// Finalize the builder. If `base_currency` is still empty, attempt to use
-    /// the `Forex` base code; otherwise, keep as-is.
-    pub fn build(mut self) -> Self {
-        if self.base_currency.code.is_empty() {
-            let base_code = self.forex.get_base_rate().to_string();
-            if let Some(cur) = self
-                .forex
-                .currencies_detailed()
-                .into_iter()
-                .find(|c| c.code == base_code)
-            {
-                self.base_currency = cur;
-            } else if !base_code.is_empty() {
-                self.base_currency = Currency {
-                    code: base_code.clone(),
-                    name: base_code,
-                    rate: 1.0,
-                };
+    /// Let new accounts' deposits start earning `days` days after they are
+    /// posted; none by default.
+    pub fn set_interest_grace_days(mut self, days: u32) -> Self {
+        self.interest_grace_days = days;
+        self
+    }
+
+    /// Clear deposited cheques `days` business days after they are
+    /// deposited, `DEFAULT_CLEARING_DAYS` by default (see
+    /// `Bank::deposit_cheque`).
+    pub fn set_cheque_clearing_days(mut self, days: u32) -> Self {
+        self.cheque_clearing_days = days;
+        self
+    }
+
+    /// Flag deposits/withdrawals strictly above `amount` for compliance
+    /// review.
+    pub fn set_large_transaction_threshold(mut self, amount: Decimal) -> Self {
+        self.compliance.large_threshold = Some(amount);
+        self
+    }
+
+    /// Require front-ends to confirm large transactions before posting.
+    pub fn set_require_large_confirmation(mut self, required: bool) -> Self {
+        self.compliance.require_confirmation = required;
+        self
+    }
+
+    /// Require an explicit summary-and-confirm step for withdrawals and
+    /// transfers strictly above `amount` (in the base currency).
+    pub fn set_confirmation_threshold(mut self, amount: Decimal) -> Self {
+        self.compliance.confirm_threshold = Some(amount);
+        self
+    }
+
+    /// Hold withdrawals and transfers strictly above `amount` (in the base
+    /// currency) until an admin approves them.
+    pub fn set_approval_threshold(mut self, amount: Decimal) -> Self {
+        self.compliance.approval_threshold = Some(amount);
+        self
+    }
+
+    /// Require confirmation before a rate is overwritten by more than
+    /// `fraction` of its current value (e.g. `Decimal::new(10, 2)` = 10%).
+    pub fn set_rate_change_confirmation(mut self, fraction: Decimal) -> Self {
+        self.compliance.rate_change_limit = Some(fraction);
+        self
+    }
+
+    /// Accept annual interest rates below zero (down to, but not including,
+    /// -100%), charged to balances as a carrying cost.
+    pub fn set_allow_negative_rates(mut self, allowed: bool) -> Self {
+        self.compliance.allow_negative_rates = allowed;
+        self
+    }
+
+    /// Cap conversions from and into `code` (see `ConversionLimit`).
+    pub fn set_conversion_limit(mut self, code: &str, limit: ConversionLimit) -> Self {
+        self.compliance.conversion_limits.insert(code.to_string(), limit);
+        self
+    }
+
+    /// Put `profile`'s caps on the accounts `target` covers (see
+    /// `LimitProfile`).
+    pub fn set_limit_profile(mut self, target: LimitTarget, profile: LimitProfile) -> Self {
+        self.compliance.limit_profiles.insert(target, profile);
+        self
+    }
+
+    /// Cap what an account whose holder has not passed KYC may deposit,
+    /// withdraw, or transfer at once at `amount` (in the base currency).
+    pub fn set_unverified_limit(mut self, amount: Decimal) -> Self {
+        self.compliance.unverified_limit = Some(amount);
+        self
+    }
+
+    /// Cap what such an account may have posted in a day at `amount` (in
+    /// the base currency).
+    pub fn set_unverified_daily_limit(mut self, amount: Decimal) -> Self {
+        self.compliance.unverified_daily_limit = Some(amount);
+        self
+    }
+
+    /// Require `passphrase` to enter the admin role. Without it, admin mode is
+    /// unrestricted.
+    pub fn set_admin_passphrase(mut self, passphrase: &str) -> Self {
+        self.admin_credential = Some(Credential::new(passphrase));
+        self
+    }
+
+    /// Choose the rounding strategy (e.g. banker's rounding) applied to posted
+    /// interest and settled conversions.
+    pub fn set_rounding(mut self, strategy: RoundingStrategy) -> Self {
+        self.rounding.strategy = strategy;
+        self
+    }
+
+    /// Choose the locale used to format amounts for display (digit grouping,
+    /// decimal separator, symbol placement).
+    pub fn set_locale(mut self, locale: Locale) -> Self {
+        self.locale = locale;
+        self
+    }
+
+    /// Choose the time zone timestamps are displayed in (Asia/Manila by
+    /// default); they are still stored in UTC.
+    pub fn set_time_zone(mut self, time_zone: TimeZone) -> Self {
+        self.time_zone = time_zone;
+        self
+    }
+
+    /// Roll scheduled dates by `calendar` instead of the default calendar
+    /// for the locale (see `BusinessCalendar::for_locale`).
+    pub fn set_calendar(mut self, calendar: BusinessCalendar) -> Self {
+        self.calendar = Some(calendar);
+        self
+    }
+
+    /// Read the time from `clock` instead of the system clock (see
+    /// `Bank::set_clock`).
+    pub fn set_clock(mut self, clock: Arc<dyn Clock>) -> Self {
+        self.clock = clock;
+        self
+    }
+}
+
+impl BankBuilder<NoForex> {
+    /// Supply the Forex catalog; its base currency becomes the bank's.
+    pub fn set_forex(self, forex: Forex) -> BankBuilder<Forex> {
+        BankBuilder {
+            forex,
+            annual_interest: self.annual_interest,
+            withholding_tax_rate: self.withholding_tax_rate,
+            year_basis: self.year_basis,
+            interest_method: self.interest_method,
+            interest_grace_days: self.interest_grace_days,
+            cheque_clearing_days: self.cheque_clearing_days,
+            compliance: self.compliance,
+            admin_credential: self.admin_credential,
+            rounding: self.rounding,
+            locale: self.locale,
+            time_zone: self.time_zone,
+            calendar: self.calendar,
+            clock: self.clock,
+        }
+    }
+}
+
+impl BankBuilder<Forex> {
+    /// Finalize the builder into a `Bank` with no accounts or customers.
+    pub fn build(self) -> Bank {
+        let mut bank = Bank {
+            base_currency: self.forex.base_currency().clone(),
+            forex: self.forex,
+            annual_interest: self.annual_interest,
+            withholding_tax_rate: self.withholding_tax_rate,
+            year_basis: self.year_basis,
+            interest_method: self.interest_method,
+            interest_grace_days: self.interest_grace_days,
+            cheque_clearing_days: self.cheque_clearing_days,
+            accounts: Vec::new(),
+            customers: Vec::new(),
+            loans: Vec::new(),
+            standing_orders: Vec::new(),
+            sweeps: Vec::new(),
+            forwards: Vec::new(),
+            limit_orders: Vec::new(),
+            till: Till::new(),
+            compliance: self.compliance,
+            flagged: Vec::new(),
+            pending: Vec::new(),
+            admin_credential: self.admin_credential,
+            rounding: self.rounding,
+            rounding_residue: BTreeMap::new(),
+            calendar: self.calendar.unwrap_or_else(|| BusinessCalendar::for_locale(self.locale)),
+            locale: self.locale,
+            time_zone: self.time_zone,
+            idempotency: IdempotencyStore::default(),
+            checkpoints: Vec::new(),
+            events: VecDeque::new(),
+            statements: Vec::new(),
+            read_only: false,
+            limits_overridden: false,
+            teller: None,
+            session: None,
+            next_sequence: 1,
+            clock: Arc::new(SystemClock),
+            simulation: None,
+        };
+        bank.set_clock(self.clock);
+        bank
+    }
+}
+
+impl Bank {
+    /// Start building a bank with a 5% annual interest rate and default
+    /// compliance, rounding, locale, time zone, and calendar settings.
+    pub fn builder() -> BankBuilder<NoForex> {
+        BankBuilder {
+            forex: NoForex,
+            annual_interest: Decimal::new(5, 2),
+            withholding_tax_rate: Decimal::ZERO,
+            year_basis: YearBasis::default(),
+            interest_method: InterestMethod::default(),
+            interest_grace_days: 0,
+            cheque_clearing_days: DEFAULT_CLEARING_DAYS,
+            compliance: ComplianceSettings::default(),
+            admin_credential: None,
+            rounding: RoundingPolicy::default(),
+            locale: Locale::default(),
+            time_zone: TimeZone::default(),
+            calendar: None,
+            clock: Arc::new(SystemClock),
+        }
+    }
+
+    /// The current Unix timestamp (seconds, UTC) on the bank's clock.
+    pub fn now(&self) -> i64 {
+        self.clock.now()
+    }
+
+    /// Today's date (UTC) on the bank's clock.
+    pub fn today(&self) -> Date {
+        self.clock.today()
+    }
+
+    /// The clock the bank reads the time from.
+    pub fn clock(&self) -> Arc<dyn Clock> {
+        Arc::clone(&self.clock)
+    }
+
+    /// The simulation clock driving the bank, if one is (see
+    /// `set_simulation_clock`).
+    pub fn simulation_clock(&self) -> Option<Arc<SimulationClock>> {
+        self.simulation.clone()
+    }
+
+    /// Read the time from `clock` from now on: transaction and rate
+    /// timestamps, the dates interest accrues and schedules fall due on,
+    /// statements, and forecasts. The clock is not part of a snapshot or
+    /// checkpoint.
+    pub fn set_clock(&mut self, clock: Arc<dyn Clock>) {
+        self.forex.set_clock(Arc::clone(&clock));
+        self.clock = clock;
+        self.simulation = None;
+    }
+
+    /// Let `clock` drive the bank, so `advance_clock` and
+    /// `set_simulated_time` move it.
+    pub fn set_simulation_clock(&mut self, clock: Arc<SimulationClock>) {
+        self.set_clock(clock.clone());
+        self.simulation = Some(clock);
+    }
+
+    /// Move the simulation clock forward by `seconds` and return the new
+    /// time. Without one, a simulation clock is installed first, starting
+    /// from the current time, so simulations (e.g. `MarketSimulator`) can
+    /// step days at will; from then on time only moves when this or
+    /// `set_simulated_time` is called.
+    pub fn advance_clock(&mut self, seconds: i64) -> i64 {
+        self.simulation().advance(seconds)
+    }
+
+    /// Stand the simulation clock at `timestamp`, installing one if needed,
+    /// e.g. to replay a day of historical rates.
+    pub fn set_simulated_time(&mut self, timestamp: i64) {
+        self.simulation().set(timestamp);
+    }
+
+    /// The simulation clock, installed at the current time if there is none.
+    fn simulation(&mut self) -> Arc<SimulationClock> {
+        match &self.simulation {
+            Some(clock) => Arc::clone(clock),
+            None => {
+                let clock = Arc::new(SimulationClock::new(self.now()));
+                self.set_simulation_clock(Arc::clone(&clock));
+                clock
             }
         }
-        self
     }
 
-    /// Create and store a new account configured with the bank's
-    /// current annual interest rate. Returns a mutable reference so
-    /// callers can immediately add transactions.
-    pub fn create_account(&mut self, name: &str) -> &mut Account {
-        let acct = Account::new(name).with_interest(self.annual_interest);
+    /// Returns true if `passphrase` unlocks the admin role. A bank without
+    /// an admin credential unlocks it for nobody, so approvals and the other
+    /// admin-only operations fail closed rather than open to anyone.
+    pub fn verify_admin(&self, passphrase: &str) -> bool {
+        self.admin_credential
+            .as_ref()
+            .is_some_and(|cred| cred.verify(passphrase))
+    }
+
+    /// Check that `rate` may be used as an annual interest rate: above
+    /// -100%, and not below zero unless negative rates are enabled.
+    pub fn check_interest_rate(&self, rate: Decimal) -> Result<(), BankError> {
+        if rate <= -Decimal::ONE {
+            return Err(BankError::RateOutOfRange(rate));
+        }
+        if rate < Decimal::ZERO && !self.compliance.allow_negative_rates {
+            return Err(BankError::NegativeRate(rate));
+        }
+        Ok(())
+    }
+
+    /// Change the bank-wide annual interest rate after construction. The new
+    /// rate applies to newly opened accounts and is pushed to existing ones
+    /// as a change effective today, keeping their earlier rates on record.
+    /// Fails, changing nothing, if `check_interest_rate` refuses the rate.
+    pub fn change_annual_interest(&mut self, rate: Decimal) -> Result<(), BankError> {
+        self.ensure_writable()?;
+        self.check_interest_rate(rate)?;
+        self.annual_interest = rate;
+        let today = self.today();
+        for acct in self.accounts.iter_mut() {
+            acct.change_interest(rate, today);
+        }
+        Ok(())
+    }
+
+    /// Change the named account's annual interest rate from `effective` on
+    /// (see `Account::change_interest`). Fails if the account does not
+    /// exist or `check_interest_rate` refuses the rate.
+    pub fn change_account_interest(&mut self, name: &str, rate: Decimal, effective: Date) -> Result<&Account, BankError> {
+        self.ensure_writable()?;
+        self.check_interest_rate(rate)?;
+        let acct = self.find_account_mut(name)?;
+        acct.change_interest(rate, effective);
+        Ok(acct)
+    }
+
+    /// Accrue interest on `basis` from now on: new accounts open on it and
+    /// every existing account is moved to it. Interest already posted is
+    /// left alone.
+    pub fn change_year_basis(&mut self, basis: YearBasis) -> Result<(), BankError> {
+        self.ensure_writable()?;
+        self.year_basis = basis;
+        for acct in self.accounts.iter_mut().filter(|a| a.year_basis != basis) {
+            acct.set_year_basis(basis);
+        }
+        Ok(())
+    }
+
+    /// Accrue the named account's interest on `basis` from now on, apart
+    /// from the bank's. Fails if the account does not exist.
+    pub fn change_account_year_basis(&mut self, name: &str, basis: YearBasis) -> Result<&Account, BankError> {
+        self.ensure_writable()?;
+        let acct = self.find_account_mut(name)?;
+        acct.set_year_basis(basis);
+        Ok(acct)
+    }
+
+    /// Accrue interest by `method` from now on: new accounts open with it
+    /// and every existing account is moved to it. Interest already posted
+    /// is left alone.
+    pub fn change_interest_method(&mut self, method: InterestMethod) -> Result<(), BankError> {
+        self.ensure_writable()?;
+        self.interest_method = method;
+        for acct in self.accounts.iter_mut().filter(|a| a.interest_method != method) {
+            acct.set_interest_method(method);
+        }
+        Ok(())
+    }
+
+    /// Accrue the named account's interest by `method` from now on, apart
+    /// from the bank's. Fails if the account does not exist.
+    pub fn change_account_interest_method(&mut self, name: &str, method: InterestMethod) -> Result<&Account, BankError> {
+        self.ensure_writable()?;
+        let acct = self.find_account_mut(name)?;
+        acct.set_interest_method(method);
+        Ok(acct)
+    }
+
+    /// Let deposits start earning `days` days after they are posted, from
+    /// now on: new accounts open with the grace period and every existing
+    /// account is moved to it, deposits already made included. Interest
+    /// already posted is left alone.
+    pub fn change_interest_grace(&mut self, days: u32) -> Result<(), BankError> {
+        self.ensure_writable()?;
+        self.interest_grace_days = days;
+        for acct in self.accounts.iter_mut().filter(|a| a.interest_grace_days != days) {
+            acct.set_interest_grace(days);
+        }
+        Ok(())
+    }
+
+    /// Give the named account's deposits a grace period of `days` days
+    /// from now on, apart from the bank's. Fails if the account does not
+    /// exist.
+    pub fn change_account_interest_grace(&mut self, name: &str, days: u32) -> Result<&Account, BankError> {
+        self.ensure_writable()?;
+        let acct = self.find_account_mut(name)?;
+        acct.set_interest_grace(days);
+        Ok(acct)
+    }
+
+    /// Give the named account the type `kind`, or none, which picks the
+    /// limit profile it falls under. Type names are kept in lowercase.
+    /// Takes the admin passphrase, failing with `InvalidPassphrase` if it is
+    /// rejected. Fails if the account does not exist or `kind` is blank or
+    /// holds a colon.
+    pub fn set_account_type(&mut self, name: &str, kind: Option<&str>, passphrase: &str) -> Result<&Account, BankError> {
+        self.ensure_writable()?;
+        if !self.verify_admin(passphrase) {
+            return Err(BankError::InvalidPassphrase);
+        }
+        let kind = match kind {
+            Some(raw) => Some(limit_profile::account_type(raw).ok_or_else(|| BankError::InvalidAccountType(raw.to_string()))?),
+            None => None,
+        };
+        let acct = self.find_account_mut(name)?;
+        acct.account_type = kind;
+        acct.touch();
+        Ok(acct)
+    }
+
+    /// Add `bonus` to the named account's rate for `days` days from `start`
+    /// (see `Account::start_promotion`). Fails if the account does not
+    /// exist or the promotion is refused.
+    pub fn start_promotion(&mut self, name: &str, bonus: Decimal, start: Date, days: usize) -> Result<&Account, BankError> {
+        self.ensure_writable()?;
+        let acct = self.find_account_mut(name)?;
+        acct.start_promotion(bonus, start, days)?;
+        Ok(acct)
+    }
+
+    /// End the named account's promotion early, returning it, or `None` if
+    /// it had none. Fails if the account does not exist.
+    pub fn end_promotion(&mut self, name: &str) -> Result<Option<Promotion>, BankError> {
+        self.ensure_writable()?;
+        let acct = self.find_account_mut(name)?;
+        Ok(acct.end_promotion())
+    }
+
+    /// Create and store a new account in the base currency configured with
+    /// the bank's current annual interest rate. Returns a mutable reference
+    /// so callers can immediately add transactions. Fails with
+    /// `AccountExists` if `name` is already an account's name or alias.
+    pub fn create_account(&mut self, name: &str) -> Result<&mut Account, BankError> {
+        let code = self.base_currency.code.clone();
+        self.create_account_in(name, &code)
+    }
+
+    /// Like `create_account`, but the account holds `code`, which must be in
+    /// the catalog. Its FX profit and loss against the base currency is
+    /// tracked as money moves in and out (see `position_report`).
+    pub fn create_account_in(&mut self, name: &str, code: &str) -> Result<&mut Account, BankError> {
+        self.ensure_writable()?;
+        if self.account_index(name).is_some() {
+            return Err(BankError::AccountExists(name.to_string()));
+        }
+        let dp = self.forex.currency(code).ok_or_else(|| ForexError::UnknownCurrency(code.to_string()))?.decimals;
+        Ok(self.push_account(name, code, dp))
+    }
+
+    fn push_account(&mut self, name: &str, code: &str, dp: u32) -> &mut Account {
+        let id = self.accounts.len() + 1;
+        let acct = Account::new(name)
+            .with_id(id)
+            .with_currency(code)
+            .with_minor_unit_dp(dp)
+            .with_interest(self.annual_interest)
+            .with_year_basis(self.year_basis)
+            .with_interest_method(self.interest_method)
+            .with_interest_grace(self.interest_grace_days);
+        self.emit(BankEvent::AccountOpened { account: name.to_string(), currency: acct.currency.clone() });
         self.accounts.push(acct);
         let idx = self.accounts.len() - 1;
         &mut self.accounts[idx]
     }
 
-    /// Find an account by name (mutable). Returns `None` if not found.
-    pub fn find_account_mut(&mut self, name: &str) -> Option<&mut Account> {
-        self.accounts.iter_mut().find(|a| a.name == name)
+    /// Find an account by name or alias. An account's own name wins over
+    /// another account's alias. Returns `None` if not found.
+    pub fn find_account(&self, name: &str) -> Option<&Account> {
+        self.account_index(name).map(|i| &self.accounts[i])
+    }
+
+    /// Find an account by name or alias to change it, like `find_account`.
+    /// Fails with `ReadOnly` on a read-only bank, or if there is no such
+    /// account.
+    pub fn find_account_mut(&mut self, name: &str) -> Result<&mut Account, BankError> {
+        self.ensure_writable()?;
+        let i = self.account_index(name).ok_or_else(|| BankError::AccountNotFound(name.to_string()))?;
+        Ok(&mut self.accounts[i])
+    }
+
+    fn account_index(&self, name: &str) -> Option<usize> {
+        self.accounts
+            .iter()
+            .position(|a| a.name == name)
+            .or_else(|| self.accounts.iter().position(|a| a.aliases.iter().any(|alias| alias == name)))
+    }
+
+    /// Check that the account found by `name` is still at `expected`, the
+    /// `Account::version` a client last saw. Fails with `VersionConflict` if
+    /// it has changed since. Checking and then changing the account through
+    /// the same `&mut Bank` is a compare-and-set: nothing can change the
+    /// account in between.
+    pub fn expect_version(&self, name: &str, expected: u64) -> Result<(), BankError> {
+        let acct = self.find_account(name).ok_or_else(|| BankError::AccountNotFound(name.to_string()))?;
+        if acct.version != expected {
+            return Err(BankError::VersionConflict { account: acct.name.clone(), expected, actual: acct.version });
+        }
+        Ok(())
+    }
+
+    /// Archive the account found by `name`: it keeps its history for audit
+    /// but takes no more postings, and `active_accounts` (and so listings
+    /// and reports) leave it out. `restore_account` brings it back. Fails
+    /// with `AccountArchived` if it already is, or `BalanceRemaining` unless
+    /// its balance is zero.
+    pub fn archive_account(&mut self, name: &str) -> Result<&Account, BankError> {
+        self.ensure_writable()?;
+        let index = self.account_index(name).ok_or_else(|| BankError::AccountNotFound(name.to_string()))?;
+        self.ensure_open(index)?;
+        let today = self.today();
+        let acct = &mut self.accounts[index];
+        if !acct.get_balance().amount.is_zero() {
+            return Err(BankError::BalanceRemaining(acct.name.clone()));
+        }
+        acct.archived = Some(today);
+        acct.touch();
+        Ok(acct)
+    }
+
+    /// Pay the named account's posted interest into `payout` rather than
+    /// adding it to the account, or add it to the account again with
+    /// `None` (or the account itself). The payout account must be open and
+    /// in the same currency, else `PayoutCurrency`. Carrying charges at a
+    /// negative rate still come off the account itself, and interest is
+    /// added to the account after all if the payout account is archived
+    /// by the time it is posted.
+    pub fn set_interest_payout(&mut self, name: &str, payout: Option<&str>) -> Result<&Account, BankError> {
+        self.ensure_writable()?;
+        let index = self.account_index(name).ok_or_else(|| BankError::AccountNotFound(name.to_string()))?;
+        let payout = match payout {
+            Some(payout) => {
+                let target = self.account_index(payout).ok_or_else(|| BankError::AccountNotFound(payout.to_string()))?;
+                self.ensure_open(target)?;
+                if self.accounts[target].currency != self.accounts[index].currency {
+                    return Err(BankError::PayoutCurrency(self.accounts[target].name.clone()));
+                }
+                Some(self.accounts[target].name.clone()).filter(|_| target != index)
+            }
+            None => None,
+        };
+        let acct = &mut self.accounts[index];
+        acct.interest_payout = payout;
+        acct.touch();
+        Ok(acct)
+    }
+
+    /// Bring back an archived account, as it was. Fails with
+    /// `AccountNotArchived` if it is not archived.
+    pub fn restore_account(&mut self, name: &str) -> Result<&Account, BankError> {
+        self.ensure_writable()?;
+        let index = self.account_index(name).ok_or_else(|| BankError::AccountNotFound(name.to_string()))?;
+        let acct = &mut self.accounts[index];
+        if acct.archived.take().is_none() {
+            return Err(BankError::AccountNotArchived(acct.name.clone()));
+        }
+        acct.touch();
+        Ok(acct)
+    }
+
+    /// Accounts that are not archived, in opening order.
+    pub fn active_accounts(&self) -> impl Iterator<Item = &Account> {
+        self.accounts.iter().filter(|a| !a.is_archived())
+    }
+
+    /// Archived accounts, in opening order.
+    pub fn archived_accounts(&self) -> impl Iterator<Item = &Account> {
+        self.accounts.iter().filter(|a| a.is_archived())
+    }
+
+    /// Fails with `AccountArchived` if the account at `index` is archived.
+    fn ensure_open(&self, index: usize) -> Result<(), BankError> {
+        let acct = &self.accounts[index];
+        if acct.is_archived() {
+            return Err(BankError::AccountArchived(acct.name.clone()));
+        }
+        Ok(())
+    }
+
+    /// The real name of the account called `name` or aliased `name`.
+    pub fn resolve_account(&self, name: &str) -> Option<&str> {
+        self.find_account(name).map(|a| a.name.as_str())
+    }
+
+    /// Register `alias` as another name for the account found by `account`
+    /// (a name or an existing alias). The alias is trimmed; fails with
+    /// `BlankAlias` if nothing is left, or `AliasTaken` if it is already an
+    /// account's name or any account's alias.
+    pub fn add_alias(&mut self, account: &str, alias: &str) -> Result<&Account, BankError> {
+        self.ensure_writable()?;
+        let index = self.account_index(account).ok_or_else(|| BankError::AccountNotFound(account.to_string()))?;
+        let alias = alias.trim();
+        if alias.is_empty() {
+            return Err(BankError::BlankAlias);
+        }
+        if self.account_index(alias).is_some() {
+            return Err(BankError::AliasTaken(alias.to_string()));
+        }
+        let acct = &mut self.accounts[index];
+        acct.aliases.push(alias.to_string());
+        acct.touch();
+        Ok(acct)
+    }
+
+    /// Convert deposits to the named account in any of `codes` into its
+    /// currency as they are posted, replacing the currencies it converted
+    /// before; an empty list stops converting. Fails, changing nothing, if
+    /// a code is not in the catalog, or with `AutoConversionCurrency` if it
+    /// is the account's own currency.
+    pub fn set_auto_conversion(&mut self, name: &str, codes: &[String]) -> Result<&Account, BankError> {
+        self.ensure_writable()?;
+        let index = self.account_index(name).ok_or_else(|| BankError::AccountNotFound(name.to_string()))?;
+        for code in codes {
+            if self.forex.currency(code).is_none() {
+                return Err(ForexError::UnknownCurrency(code.clone()).into());
+            }
+            if self.accounts[index].currency == *code {
+                return Err(BankError::AutoConversionCurrency(code.clone()));
+            }
+        }
+        let acct = &mut self.accounts[index];
+        acct.auto_convert = codes.to_vec();
+        acct.auto_convert.sort();
+        acct.auto_convert.dedup();
+        acct.touch();
+        Ok(acct)
+    }
+
+    /// Set the named account's alerts, in its currency: its balance falling
+    /// below `balance_below`, a withdrawal or transfer out above
+    /// `withdrawal_above`, and `inactive_days` days without a posting,
+    /// each `None` to turn it off. The settings replace the old ones, so
+    /// anything already alerted about may be alerted about again. The
+    /// alerts are raised by the event bus (see `alert::evaluate`). Fails,
+    /// changing nothing, if a threshold is not above zero or the days are
+    /// zero.
+    pub fn set_alerts(&mut self, name: &str, balance_below: Option<Decimal>, withdrawal_above: Option<Decimal>, inactive_days: Option<u32>) -> Result<&Account, BankError> {
+        self.ensure_writable()?;
+        let settings = AlertSettings::new(balance_below, withdrawal_above, inactive_days)?;
+        let acct = self.find_account_mut(name)?;
+        acct.alerts = settings;
+        acct.touch();
+        Ok(acct)
+    }
+
+    /// Set the named account's service charges, in its currency: a flat
+    /// `monthly` fee and a `maintaining` balance with the charge for a
+    /// month below it, each `None` to turn it off. They are taken at each
+    /// month's end by `end_of_day`. Fails, changing nothing, if a charge or
+    /// the maintaining balance is not above zero.
+    pub fn set_service_charges(&mut self, name: &str, monthly: Option<Decimal>, maintaining: Option<MaintainingBalance>) -> Result<&Account, BankError> {
+        self.ensure_writable()?;
+        let charges = ServiceCharges::new(monthly, maintaining)?;
+        let acct = self.find_account_mut(name)?;
+        acct.service_charges = charges;
+        acct.touch();
+        Ok(acct)
+    }
+
+    /// Remove `alias` from whichever account has it, returning that
+    /// account's name. Fails with `AliasNotFound` if no account does.
+    pub fn remove_alias(&mut self, alias: &str) -> Result<String, BankError> {
+        self.ensure_writable()?;
+        let acct = self
+            .accounts
+            .iter_mut()
+            .find(|a| a.aliases.iter().any(|a| a == alias))
+            .ok_or_else(|| BankError::AliasNotFound(alias.to_string()))?;
+        acct.aliases.retain(|a| a != alias);
+        acct.touch();
+        Ok(acct.name.clone())
+    }
+
+    /// Register a new customer and return a mutable reference to it.
+    pub fn create_customer(&mut self, name: &str, contact: &str) -> &mut Customer {
+        let id = self.customers.len() + 1;
+        self.customers.push(Customer::new(id, name, contact));
+        let idx = self.customers.len() - 1;
+        &mut self.customers[idx]
+    }
+
+    /// Find a customer by ID. Returns `None` if not found.
+    pub fn find_customer(&self, customer_id: usize) -> Option<&Customer> {
+        self.customers.iter().find(|c| c.id == customer_id)
+    }
+
+    /// Put `identification` and `address` on file for the customer, for
+    /// KYC. New documents need checking again, so the customer becomes
+    /// `Pending` whatever their status was. Fails if the customer does not
+    /// exist or the ID number is blank.
+    pub fn record_identification(&mut self, customer_id: usize, identification: Identification, address: &str) -> Result<&Customer, BankError> {
+        self.ensure_writable()?;
+        if identification.id_number.trim().is_empty() {
+            return Err(BankError::MissingIdentification(customer_id));
+        }
+        let customer = self
+            .customers
+            .iter_mut()
+            .find(|c| c.id == customer_id)
+            .ok_or(BankError::CustomerNotFound(customer_id))?;
+        customer.identification = Some(identification);
+        customer.address = address.to_string();
+        customer.verification = VerificationStatus::Pending;
+        Ok(customer)
+    }
+
+    /// Record the outcome of the customer's KYC check. `Verified` and
+    /// `Rejected` need identification on file (`MissingIdentification`).
+    pub fn set_verification(&mut self, customer_id: usize, status: VerificationStatus) -> Result<(), BankError> {
+        self.ensure_writable()?;
+        let customer = self
+            .customers
+            .iter_mut()
+            .find(|c| c.id == customer_id)
+            .ok_or(BankError::CustomerNotFound(customer_id))?;
+        let reviewed = matches!(status, VerificationStatus::Verified | VerificationStatus::Rejected);
+        if reviewed && customer.identification.is_none() {
+            return Err(BankError::MissingIdentification(customer_id));
+        }
+        customer.verification = status;
+        Ok(())
+    }
+
+    /// Returns true if the account with `account_id` belongs to a verified
+    /// customer. Accounts without a holder are never verified.
+    pub fn is_account_verified(&self, account_id: usize) -> bool {
+        self.customers.iter().any(|c| c.owns(account_id) && c.is_verified())
+    }
+
+    /// True if any of the customer's accounts is PIN-protected, so that
+    /// `log_in` asks for a PIN.
+    pub fn is_customer_protected(&self, customer_id: usize) -> bool {
+        self.find_customer(customer_id)
+            .is_some_and(|c| self.accounts.iter().any(|a| c.owns(a.id) && a.is_protected()))
+    }
+
+    /// Start a session for `customer_id`, recording a `SessionStarted`
+    /// event so the operations that follow can be attributed to the
+    /// customer, and naming the customer on the exchange receipts issued
+    /// until `log_out`. A customer with a PIN-protected account must give
+    /// `pin`, the PIN of one of them. Fails if the customer does not exist,
+    /// or with `InvalidPin` if no such account takes `pin`.
+    pub fn log_in(&mut self, customer_id: usize, pin: Option<&str>) -> Result<(), BankError> {
+        let found = self.find_customer(customer_id).ok_or(BankError::CustomerNotFound(customer_id))?;
+        if self.is_customer_protected(customer_id) && !self.accounts.iter().any(|a| found.owns(a.id) && a.is_protected() && a.verify_pin(pin)) {
+            return Err(AccountError::InvalidPin.into());
+        }
+        let customer = found.name.clone();
+        self.session = Some(customer.clone());
+        self.emit(BankEvent::SessionStarted { customer_id, customer });
+        Ok(())
+    }
+
+    /// End the session `log_in` started for `customer_id`, recording a
+    /// `SessionEnded` event. Fails if the customer does not exist.
+    pub fn log_out(&mut self, customer_id: usize) -> Result<(), BankError> {
+        let customer = self.find_customer(customer_id).ok_or(BankError::CustomerNotFound(customer_id))?.name.clone();
+        self.session = None;
+        self.emit(BankEvent::SessionEnded { customer_id, customer });
+        Ok(())
+    }
+
+    /// Name `teller` (`None` for nobody) on the exchange receipts issued
+    /// from now on; a blank name counts as none. Like the customer session,
+    /// it belongs to whoever is at the counter and is not part of a
+    /// snapshot.
+    pub fn set_teller(&mut self, teller: Option<&str>) {
+        self.teller = teller.map(str::trim).filter(|t| !t.is_empty()).map(str::to_string);
+    }
+
+    pub fn teller(&self) -> Option<&str> {
+        self.teller.as_deref()
+    }
+
+    /// Open a new account named `account_name` owned by `customer_id`.
+    /// Fails (and opens nothing) if the customer does not exist or the
+    /// name is taken (see `create_account`).
+    pub fn open_account_for(&mut self, customer_id: usize, account_name: &str) -> Result<&mut Account, BankError> {
+        self.ensure_writable()?;
+        let customer = self.customers.iter().position(|c| c.id == customer_id).ok_or(BankError::CustomerNotFound(customer_id))?;
+        let id = self.create_account(account_name)?.id;
+        self.customers[customer].account_ids.push(id);
+        let index = self.accounts.len() - 1;
+        Ok(&mut self.accounts[index])
+    }
+
+    /// Active accounts owned by the given customer, in opening order.
+    pub fn customer_accounts(&self, customer_id: usize) -> Vec<&Account> {
+        match self.find_customer(customer_id) {
+            Some(customer) => self
+                .active_accounts()
+                .filter(|a| customer.owns(a.id))
+                .collect(),
+            None => Vec::new(),
+        }
+    }
+
+    /// Total relationship balance: the sum of all balances of the accounts
+    /// owned by the customer, expressed in the bank's base currency. Fails if
+    /// the customer does not exist or a balance cannot be converted.
+    pub fn customer_balance(&self, customer_id: usize) -> Result<Money, BankError> {
+        self.find_customer(customer_id)
+            .ok_or(BankError::CustomerNotFound(customer_id))?;
+        let base = &self.base_currency.code;
+        self.customer_accounts(customer_id)
+            .iter()
+            .try_fold(Money::zero(base), |total, a| {
+                let converted = self.forex.convert(&a.get_balance(), base)?;
+                Ok(Money::new(total.amount + converted.amount, base))
+            })
+    }
+
+    /// Returns true if `pin` unlocks the named account (always true for
+    /// accounts without a credential). Unknown accounts never verify.
+    pub fn verify_account(&self, name: &str, pin: Option<&str>) -> bool {
+        self.accounts
+            .iter()
+            .find(|a| a.name == name)
+            .is_some_and(|a| a.verify_pin(pin))
+    }
+
+    /// Post a deposit or withdrawal with an optional `memo` to the named
+    /// account, flagging it for review when it exceeds the compliance
+    /// threshold (compared in the base currency). Protected accounts
+    /// require a matching `pin`. A deposit in a currency the account
+    /// converts (see `set_auto_conversion`) is posted converted, by
+    /// `post_converted_deposit`. Returns the updated balance; fails if the
+    /// account does not exist, the PIN is rejected, `amount` is not in the
+    /// account's currency, or a withdrawal exceeds the balance. A
+    /// withdrawal above the approval threshold is not posted but queued,
+    /// failing with `AwaitingApproval`.
+    pub fn post_transaction(&mut self, name: &str, tx_type: TransactionType, amount: Money, memo: &str, pin: Option<&str>) -> Result<Money, BankError> {
+        self.post_or_hold(name, tx_type, amount, memo, None, pin)
+    }
+
+    /// `post_transaction`, keeping the budget `category` of a withdrawal
+    /// held for approval so it is filed there once approved.
+    fn post_or_hold(&mut self, name: &str, tx_type: TransactionType, amount: Money, memo: &str, category: Option<&str>, pin: Option<&str>) -> Result<Money, BankError> {
+        self.ensure_writable()?;
+        let acct = self
+            .accounts
+            .iter()
+            .find(|a| a.name == name)
+            .ok_or_else(|| BankError::AccountNotFound(name.to_string()))?;
+        if !acct.verify_pin(pin) {
+            return Err(AccountError::InvalidPin.into());
+        }
+        self.check_account_limits(acct, tx_type, &amount)?;
+        if tx_type == TransactionType::Deposit && acct.auto_convert.contains(&amount.currency) {
+            return self.post_converted_deposit(name, amount, memo);
+        }
+        if tx_type == TransactionType::Withdraw && self.needs_approval(&amount) {
+            let held = HeldTransaction::Withdrawal { account: name.to_string(), amount, memo: memo.to_string(), category: category.map(str::to_string) };
+            return Err(self.hold(held));
+        }
+        self.post_authorized(name, tx_type, amount, memo)
+    }
+
+    /// Deposit `amount`, in a foreign currency, to the named account as a
+    /// single deposit of what it converts to at transfer rates, less the
+    /// conversion fee, so the conversion cannot be booked without the
+    /// deposit or the deposit without it. The gross and fee are rounded
+    /// with the bank's rounding policy, and the conversion is checked
+    /// against the conversion limits and logged under the account. The
+    /// memo records the amount and rate, after `memo` if one is given.
+    fn post_converted_deposit(&mut self, name: &str, amount: Money, memo: &str) -> Result<Money, BankError> {
+        let code = self.find_account(name).map_or_else(String::new, |a| a.currency.clone());
+        let conversion = self.forex.exchange_at(&amount, &code, RateType::Transfer)?;
+        let dp = self.forex.decimals(&code);
+        let (gross, gross_residue) = self.rounding.apply(&conversion.gross, dp);
+        let (fee, fee_residue) = self.rounding.apply(&conversion.fee, dp);
+        let net = Money::new(gross.amount - fee.amount, &code);
+        self.check_conversion_limits(Some(name), [&amount, &net])?;
+        let detail = format!("{:.*} converted at {}", self.forex.decimals(&amount.currency) as usize, amount, conversion.rate.round_dp(EXCHANGE_RATE_DP));
+        let memo = if memo.is_empty() { detail } else { format!("{} ({})", memo, detail) };
+        let balance = self.post_authorized(name, TransactionType::Deposit, net.clone(), &memo)?;
+        self.add_residue(&code, gross_residue + fee_residue);
+        self.log_conversion(Some(name), amount, net, conversion.rate, fee);
+        Ok(balance)
+    }
+
+    /// Withdraw `amount` from the named account like `post_transaction` and
+    /// file it under the budget `category`, which must be one of the
+    /// account's envelopes. Returns the updated balance and the envelope's
+    /// status this month, which `is_overspent` once the withdrawal takes it
+    /// past its limit. The withdrawal is not refused for going over budget.
+    pub fn spend(&mut self, name: &str, amount: Money, category: &str, memo: &str, pin: Option<&str>) -> Result<(Money, EnvelopeStatus), BankError> {
+        self.ensure_writable()?;
+        let acct = self
+            .accounts
+            .iter()
+            .find(|a| a.name == name)
+            .ok_or_else(|| BankError::AccountNotFound(name.to_string()))?;
+        if acct.envelope(category).is_none() {
+            return Err(AccountError::from(BudgetError::NotFound(category.to_string())).into());
+        }
+        let balance = self.post_or_hold(name, TransactionType::Withdraw, amount, memo, Some(category), pin)?;
+        let today = self.today();
+        let acct = self.find_account_mut(name)?;
+        let last = acct.transactions.len().saturating_sub(1);
+        acct.transactions.set_category(last, Some(category));
+        let status = acct.envelope_status(category, today)?;
+        Ok((balance, status))
+    }
+
+    /// `post_transaction` once the account's PIN has been checked.
+    fn post_authorized(&mut self, name: &str, tx_type: TransactionType, amount: Money, memo: &str) -> Result<Money, BankError> {
+        let index = self
+            .accounts
+            .iter()
+            .position(|a| a.name == name)
+            .ok_or_else(|| BankError::AccountNotFound(name.to_string()))?;
+        self.post_at(index, tx_type, amount, memo)
+    }
+
+    /// `post_authorized` to the account at `index`.
+    fn post_at(&mut self, index: usize, tx_type: TransactionType, amount: Money, memo: &str) -> Result<Money, BankError> {
+        let base_amount = self
+            .forex
+            .convert(&amount, &self.base_currency.code)
+            .map_or(amount.amount, |m| m.amount);
+        let is_large = self.compliance.is_large(base_amount);
+        self.ensure_open(index)?;
+        let now = self.now();
+        // Rounded with the bank's policy rather than the account's half away
+        // from zero, so the fraction dropped can be tracked as residue.
+        let (rounded, residue) = self.rounding.apply(&amount, self.accounts[index].minor_unit_dp);
+        if tx_type == TransactionType::Withdraw {
+            self.ensure_available(index, rounded.amount)?;
+        }
+        let acct = &mut self.accounts[index];
+        let held = acct.get_balance().amount;
+        acct.create_transaction_with_memo(tx_type, rounded, memo, now)?;
+        let balance = acct.get_balance();
+        // The amount as recorded, in the account's minor unit.
+        let posted = Money::new(acct.transactions.last().map_or(amount.amount, |t| t.amount()), &amount.currency);
+        self.add_residue(&posted.currency, residue);
+        self.sequence_last(index);
+        self.track_position(index, tx_type, &posted, held);
+        let name = self.accounts[index].name.clone();
+        self.emit(BankEvent::TransactionPosted {
+            account: name.clone(),
+            tx_type,
+            amount: posted.clone(),
+            balance: balance.clone(),
+            memo: memo.to_string(),
+        });
+
+        if is_large {
+            self.flag(&name, tx_type, posted);
+        }
+        Ok(balance)
+    }
+
+    /// Load a transaction history into the named account with
+    /// `Account::import_transactions`, e.g. from `import::CsvRows`. Protected
+    /// accounts require a matching `pin`.
+    ///
+    /// Meant for large back-loads, so rows are not checked against the
+    /// compliance threshold and emit no `TransactionPosted` events; a single
+    /// `TransactionsImported` event summarizes the load instead. A foreign
+    /// account's position moves once, by the net amount imported, valued at
+    /// today's rate. Rejected rows are listed in the report, not returned
+    /// as an error.
+    pub fn bulk_load(&mut self, name: &str, rows: impl IntoIterator<Item = Result<ImportRow, String>>, pin: Option<&str>) -> Result<ImportReport, BankError> {
+        self.ensure_writable()?;
+        let index = self
+            .accounts
+            .iter()
+            .position(|a| a.name == name)
+            .ok_or_else(|| BankError::AccountNotFound(name.to_string()))?;
+        self.ensure_open(index)?;
+        let acct = &mut self.accounts[index];
+        if !acct.verify_pin(pin) {
+            return Err(AccountError::InvalidPin.into());
+        }
+        let held = acct.get_balance();
+        let start = acct.transactions.len();
+        let report = acct.import_transactions(rows);
+        let balance = acct.get_balance();
+        self.sequence(index, start);
+        let net = balance.amount - held.amount;
+        if net != Decimal::ZERO {
+            let tx_type = if net > Decimal::ZERO { TransactionType::Deposit } else { TransactionType::Withdraw };
+            self.track_position(index, tx_type, &Money::new(net.abs(), &balance.currency), held.amount);
+        }
+        self.emit(BankEvent::TransactionsImported {
+            account: name.to_string(),
+            imported: report.imported,
+            rejected: report.errors.len(),
+            balance,
+        });
+        Ok(report)
+    }
+
+    /// Open the accounts listed in the CSV file at `path` (see
+    /// `import::AccountRows`), e.g. the `accounts.csv` written by
+    /// `export_bundle`. Each row is checked in full before its account is
+    /// opened: the name must not be an account name or alias already, the
+    /// currency must be in the catalog, the rate must pass
+    /// `check_interest_rate`, and each alias must be free. Rows that fail
+    /// are skipped and listed in the report with the reason; the others
+    /// stay opened, with no transactions.
+    ///
+    /// Fails only if the bank is read-only or the file cannot be opened or
+    /// has no `name` column.
+    pub fn import_accounts_csv(&mut self, path: impl AsRef<Path>) -> Result<AccountImportReport, Error> {
+        self.ensure_writable()?;
+        let rows = AccountRows::new(io::BufReader::new(fs::File::open(path)?), ',')?;
+        let mut report = AccountImportReport::default();
+        for (index, row) in rows.enumerate() {
+            match row.map_err(ImportFailure::Malformed).and_then(|row| self.open_imported(row)) {
+                Ok(name) => report.opened.push(name),
+                Err(failure) => report.errors.push(ImportError { row: index + 1, failure }),
+            }
+        }
+        Ok(report)
+    }
+
+    /// Check and open one row of `import_accounts_csv`.
+    fn open_imported(&mut self, row: AccountRow) -> Result<String, ImportFailure> {
+        if self.account_index(&row.name).is_some() {
+            return Err(ImportFailure::Refused(BankError::AccountExists(row.name)));
+        }
+        let code = row.currency.unwrap_or_else(|| self.base_currency.code.clone());
+        let dp = match self.forex.currency(&code) {
+            Some(c) => c.decimals,
+            None => return Err(ImportFailure::Refused(ForexError::UnknownCurrency(code).into())),
+        };
+        if let Some(rate) = row.annual_interest {
+            self.check_interest_rate(rate).map_err(ImportFailure::Refused)?;
+        }
+        for (i, alias) in row.aliases.iter().enumerate() {
+            if *alias == row.name || row.aliases[..i].contains(alias) || self.account_index(alias).is_some() {
+                return Err(ImportFailure::Refused(BankError::AliasTaken(alias.clone())));
+            }
+        }
+        let acct = self.push_account(&row.name, &code, dp);
+        if let Some(rate) = row.annual_interest {
+            acct.annual_interest = rate;
+        }
+        acct.aliases = row.aliases;
+        Ok(row.name)
+    }
+
+    /// Post the transactions in the CSV file at `path` (see
+    /// `import::LedgerRows`) to the accounts they name, by name or alias,
+    /// e.g. the `transactions.csv` written by `export_bundle`. Rows are
+    /// taken in file order, each checked like in `bulk_load` against the
+    /// balance its account has after the rows before it, so no account is
+    /// ever overdrawn part way through its history: a withdrawal that
+    /// would overdraw is skipped and listed. Rows naming a missing or
+    /// archived account are skipped too. The report counts the rows each
+    /// account took and gives every skipped row's number, account, and
+    /// reason.
+    ///
+    /// Like `bulk_load`, PINs and the compliance threshold are not checked,
+    /// and each account that took rows sends one `TransactionsImported`
+    /// event. Fails only if the bank is read-only or the file cannot be
+    /// opened or lacks a required column.
+    pub fn import_transactions_csv(&mut self, path: impl AsRef<Path>) -> Result<LedgerImportReport, Error> {
+        self.ensure_writable()?;
+        let mut currencies = BTreeMap::new();
+        for acct in &self.accounts {
+            for name in std::iter::once(&acct.name).chain(&acct.aliases) {
+                currencies.entry(name.clone()).or_insert_with(|| acct.currency.clone());
+            }
+        }
+        let rows = LedgerRows::new(io::BufReader::new(fs::File::open(path)?), currencies, ',')?;
+        // (account index, balance before the import, rows taken, rejected)
+        let mut touched: Vec<(usize, Money, usize, usize)> = Vec::new();
+        let mut report = LedgerImportReport::default();
+        for (index, (account, row)) in rows.enumerate() {
+            let posted = row.map_err(ImportFailure::Malformed).and_then(|row| {
+                let at = self.account_index(&account).ok_or_else(|| ImportFailure::Refused(BankError::AccountNotFound(account.clone())))?;
+                self.ensure_open(at).map_err(ImportFailure::Refused)?;
+                let entry = match touched.iter().position(|(i, ..)| *i == at) {
+                    Some(entry) => entry,
+                    None => {
+                        touched.push((at, self.accounts[at].get_balance(), 0, 0));
+                        touched.len() - 1
+                    }
+                };
+                let start = self.accounts[at].transactions.len();
+                let result = self.accounts[at].import_transactions([Ok(row)]);
+                match result.errors.into_iter().next() {
+                    Some(e) => {
+                        touched[entry].3 += 1;
+                        Err(e.failure)
+                    }
+                    None => {
+                        self.sequence(at, start);
+                        touched[entry].2 += 1;
+                        Ok(())
+                    }
+                }
+            });
+            if let Err(failure) = posted {
+                report.errors.push((account, ImportError { row: index + 1, failure }));
+            }
+        }
+        for (at, held, imported, rejected) in touched {
+            let balance = self.accounts[at].get_balance();
+            let net = balance.amount - held.amount;
+            if net != Decimal::ZERO {
+                let tx_type = if net > Decimal::ZERO { TransactionType::Deposit } else { TransactionType::Withdraw };
+                self.track_position(at, tx_type, &Money::new(net.abs(), &balance.currency), held.amount);
+            }
+            let account = self.accounts[at].name.clone();
+            if imported > 0 {
+                report.imported.push((account.clone(), imported));
+            }
+            self.emit(BankEvent::TransactionsImported { account, imported, rejected, balance });
+        }
+        Ok(report)
+    }
+
+    /// Reverse transaction `index` (0-based, posting order) of the named
+    /// account by posting an offsetting entry for the same amount with the
+    /// memo "Reversal" (plus the original memo), filed under the original's
+    /// budget category and tags. The original stays in the history.
+    /// Returns the updated balance; fails if the account or transaction does
+    /// not exist, or reversing a deposit would overdraw the account.
+    pub fn reverse_transaction(&mut self, name: &str, index: usize) -> Result<Money, BankError> {
+        self.ensure_writable()?;
+        let at = self
+            .accounts
+            .iter()
+            .position(|a| a.name == name)
+            .ok_or_else(|| BankError::AccountNotFound(name.to_string()))?;
+        self.ensure_open(at)?;
+        let now = self.now();
+        let acct = &mut self.accounts[at];
+        let tx = acct
+            .transactions
+            .get(index)
+            .map(|t| t.to_transaction())
+            .ok_or_else(|| BankError::TransactionNotFound(name.to_string(), index))?;
+        let opposite = match tx.tx_type() {
+            TransactionType::Deposit => TransactionType::Withdraw,
+            TransactionType::Withdraw => TransactionType::Deposit,
+        };
+        let amount = Money::new(tx.amount(), &acct.currency);
+        let memo = if tx.memo.is_empty() { String::from("Reversal") } else { format!("Reversal: {}", tx.memo) };
+        let held = acct.get_balance().amount;
+        acct.create_transaction_with_memo(opposite, amount.clone(), &memo, now)?;
+        let last = acct.transactions.len() - 1;
+        acct.transactions.set_category(last, tx.category.as_deref());
+        acct.transactions.set_tags(last, &tx.tags);
+        let balance = acct.get_balance();
+        self.sequence_last(at);
+        self.track_position(at, opposite, &amount, held);
+        self.emit(BankEvent::TransactionPosted { account: name.to_string(), tx_type: opposite, amount, balance: balance.clone(), memo });
+        Ok(balance)
+    }
+
+    /// The index (0-based, posting order) of the named account's
+    /// transaction with the bank-wide `sequence` number.
+    pub fn transaction_index(&self, name: &str, sequence: u64) -> Result<usize, BankError> {
+        let acct = self.find_account(name).ok_or_else(|| BankError::AccountNotFound(name.to_string()))?;
+        acct.transactions
+            .sequences()
+            .iter()
+            .position(|&s| s == sequence)
+            .ok_or_else(|| BankError::SequenceNotFound(acct.name.clone(), sequence))
+    }
+
+    /// Replace the tags of transaction `index` (0-based, posting order) of
+    /// the named account with `tags`, normalized by `tag::normalize`; none
+    /// removes them. Returns the tags as stored. Fails if the account or
+    /// transaction does not exist, the account is archived, or a tag is
+    /// refused.
+    pub fn tag_transaction(&mut self, name: &str, index: usize, tags: &[&str]) -> Result<Vec<String>, BankError> {
+        self.ensure_writable()?;
+        let tags = tag::normalize(tags).map_err(AccountError::from)?;
+        let at = self
+            .accounts
+            .iter()
+            .position(|a| a.name == name)
+            .ok_or_else(|| BankError::AccountNotFound(name.to_string()))?;
+        self.ensure_open(at)?;
+        let acct = &mut self.accounts[at];
+        if !acct.transactions.set_tags(index, &tags) {
+            return Err(BankError::TransactionNotFound(name.to_string(), index));
+        }
+        acct.touch();
+        Ok(tags)
+    }
+
+    /// Move `amount` from account `from` to account `to`. `amount` may be in
+    /// any currency; it is converted into each account's currency and rounded
+    /// with the bank's rounding policy. Between accounts in different
+    /// currencies, the conversion fee for the amount's volume (see
+    /// `Forex::exchange`) is kept from the credit. The source's `pin` is
+    /// required if it is protected, and transfers above the compliance
+    /// threshold are flagged against the source. A conversion must stay
+    /// within both currencies' conversion limits (see
+    /// `override_conversion_limits`). Either both legs are posted or
+    /// neither is. A transfer above the approval threshold is queued
+    /// instead, failing with `AwaitingApproval`.
+    pub fn transfer(&mut self, from: &str, to: &str, amount: Money, pin: Option<&str>) -> Result<TransferReceipt, BankError> {
+        self.ensure_writable()?;
+        let (src, dst) = self.transfer_accounts(from, to)?;
+        if !self.accounts[src].verify_pin(pin) {
+            return Err(AccountError::InvalidPin.into());
+        }
+        if self.needs_approval(&amount) {
+            return Err(self.hold(HeldTransaction::Transfer { from: from.to_string(), to: to.to_string(), amount }));
+        }
+        self.post_transfer(src, dst, amount, |_, _| (format!("Transfer to {}", to), format!("Transfer from {}", from)))
+    }
+
+    /// `post_transaction`, at most once per idempotency `key`: a retry with
+    /// a key already used for the same request posts nothing and returns
+    /// the balance the first attempt returned. Fails with
+    /// `IdempotencyKeyReused` if the key was used for a different request.
+    /// A retry still needs the account's PIN. Only successful postings are
+    /// remembered, and only the most recent keys (see
+    /// `IDEMPOTENCY_KEYS_KEPT`).
+    pub fn post_transaction_once(
+        &mut self,
+        key: &str,
+        name: &str,
+        tx_type: TransactionType,
+        amount: Money,
+        memo: &str,
+        pin: Option<&str>,
+    ) -> Result<Money, BankError> {
+        self.ensure_writable()?;
+        let request = format!("{:?} {} {} {}", tx_type, name, amount, memo);
+        match self.replay(key, &request, name, pin)? {
+            Some(IdempotentResult::Posted(balance)) => return Ok(balance),
+            Some(_) => return Err(BankError::IdempotencyKeyReused(key.to_string())),
+            None => {}
+        }
+        let balance = self.post_transaction(name, tx_type, amount, memo, pin)?;
+        self.idempotency.insert(IdempotencyRecord { key: key.to_string(), request, result: IdempotentResult::Posted(balance.clone()) });
+        Ok(balance)
+    }
+
+    /// `transfer`, at most once per idempotency `key`, like
+    /// `post_transaction_once`: a retry returns the first receipt.
+    pub fn transfer_once(&mut self, key: &str, from: &str, to: &str, amount: Money, pin: Option<&str>) -> Result<TransferReceipt, BankError> {
+        self.ensure_writable()?;
+        let request = format!("Transfer {} {} {}", from, to, amount);
+        match self.replay(key, &request, from, pin)? {
+            Some(IdempotentResult::Transferred(receipt)) => return Ok(*receipt),
+            Some(_) => return Err(BankError::IdempotencyKeyReused(key.to_string())),
+            None => {}
+        }
+        let receipt = self.transfer(from, to, amount, pin)?;
+        self.idempotency.insert(IdempotencyRecord { key: key.to_string(), request, result: IdempotentResult::Transferred(Box::new(receipt.clone())) });
+        Ok(receipt)
+    }
+
+    /// The remembered result for `key`, if it was used for `request`, once
+    /// `pin` opens `account`; `None` if the key is new.
+    fn replay(&self, key: &str, request: &str, account: &str, pin: Option<&str>) -> Result<Option<IdempotentResult>, BankError> {
+        let Some(record) = self.idempotency.get(key) else {
+            return Ok(None);
+        };
+        if record.request != request {
+            return Err(BankError::IdempotencyKeyReused(key.to_string()));
+        }
+        if !self.verify_account(account, pin) {
+            return Err(AccountError::InvalidPin.into());
+        }
+        Ok(Some(record.result.clone()))
+    }
+
+    /// Exchange `amount` of account `from`'s currency into account `to`,
+    /// another of the holder's currency wallets: like `transfer`, but the
+    /// accounts must be in different currencies and, when either belongs to
+    /// a customer, both to the same one. The legs are linked by their memos,
+    /// which capture the rate and fee: "Exchange to Bob-USD at 0.017208"
+    /// on the debit and "Exchange from Bob at 0.017208, fee 0.02 USD" on
+    /// the credit. The source's `pin` is required if it is protected.
+    pub fn exchange(&mut self, from: &str, to: &str, amount: Decimal, pin: Option<&str>) -> Result<TransferReceipt, BankError> {
+        self.ensure_writable()?;
+        let (src, dst) = self.transfer_accounts(from, to)?;
+        let (source, target) = (&self.accounts[src], &self.accounts[dst]);
+        if source.currency == target.currency {
+            return Err(BankError::SameCurrency(source.currency.clone()));
+        }
+        let owner = |id: usize| self.customers.iter().find(|c| c.owns(id)).map(|c| c.id);
+        if owner(source.id) != owner(target.id) {
+            return Err(BankError::DifferentOwners(from.to_string(), to.to_string()));
+        }
+        if !source.verify_pin(pin) {
+            return Err(AccountError::InvalidPin.into());
+        }
+        let amount = Money::new(amount, &source.currency);
+        let dp = self.forex.decimals(&target.currency) as usize;
+        self.post_transfer(src, dst, amount, |rate, fee| {
+            let rate = rate.round_dp(EXCHANGE_RATE_DP);
+            let credit = if fee.amount.is_zero() {
+                format!("Exchange from {} at {}", from, rate)
+            } else {
+                format!("Exchange from {} at {}, fee {:.*}", from, rate, dp, fee)
+            };
+            (format!("Exchange to {} at {}", to, rate), credit)
+        })
+    }
+
+    /// Positions of the source and destination accounts of a transfer.
+    fn transfer_accounts(&self, from: &str, to: &str) -> Result<(usize, usize), BankError> {
+        if from == to {
+            return Err(BankError::SameAccount(from.to_string()));
+        }
+        let position = |name: &str| {
+            self.accounts
+                .iter()
+                .position(|a| a.name == name)
+                .ok_or_else(|| BankError::AccountNotFound(name.to_string()))
+        };
+        Ok((position(from)?, position(to)?))
+    }
+
+    /// Both legs of a transfer between the accounts at `src` and `dst`,
+    /// once the source has been authorized. `memos` gives the debit and
+    /// credit memos from the rate and the fee.
+    fn post_transfer(
+        &mut self,
+        src: usize,
+        dst: usize,
+        amount: Money,
+        memos: impl FnOnce(Decimal, &Money) -> (String, String),
+    ) -> Result<TransferReceipt, BankError> {
+        self.ensure_open(src)?;
+        self.ensure_open(dst)?;
+        let (from, to) = (self.accounts[src].name.clone(), self.accounts[dst].name.clone());
+        let leg = |bank: &Self, code: &str| -> Result<(Money, Decimal), BankError> {
+            let exact = bank.forex.convert(&amount, code)?;
+            Ok(bank.rounding.apply(&exact, bank.forex.decimals(code)))
+        };
+        let (debited, debit_residue) = leg(self, &self.accounts[src].currency)?;
+        let (gross, credit_residue) = leg(self, &self.accounts[dst].currency)?;
+        let rate = self
+            .forex
+            .convert(&Money::new(Decimal::ONE, &debited.currency), &gross.currency)?
+            .amount;
+        let base_amount = self
+            .forex
+            .convert(&amount, &self.base_currency.code)
+            .map_or(amount.amount, |m| m.amount);
+        let (fee_rate, _) = self.forex.fee_rate(&debited.currency, &gross.currency, base_amount);
+        let exact_fee = gross.amount.checked_mul(fee_rate).ok_or(ForexError::Overflow)?;
+        let (fee, fee_residue) = self.rounding.apply(&Money::new(exact_fee, &gross.currency), self.forex.decimals(&gross.currency));
+        let credited = Money::new(gross.amount - fee.amount, &gross.currency);
+        if debited.currency != credited.currency {
+            self.check_conversion_limits(Some(&from), [&debited, &credited])?;
+        }
+        self.check_account_limits(&self.accounts[src], TransactionType::Withdraw, &debited)?;
+        self.check_account_limits(&self.accounts[dst], TransactionType::Deposit, &credited)?;
+        let legs = self.forex.route(&debited, &gross.currency, RateType::Transfer)?;
+        let (debit_memo, credit_memo) = memos(rate, &fee);
+
+        self.ensure_available(src, debited.amount)?;
+        let (src_held, dst_held) = (self.accounts[src].get_balance().amount, self.accounts[dst].get_balance().amount);
+        let now = self.now();
+        self.accounts[src].create_transaction_with_memo(TransactionType::Withdraw, debited.clone(), &debit_memo, now)?;
+        if let Err(e) = self.accounts[dst].create_transaction_with_memo(TransactionType::Deposit, credited.clone(), &credit_memo, now) {
+            self.accounts[src].pop_transaction();
+            return Err(e.into());
+        }
+        self.sequence_last(src);
+        self.sequence_last(dst);
+        self.track_position(src, TransactionType::Withdraw, &debited, src_held);
+        self.track_position(dst, TransactionType::Deposit, &credited, dst_held);
+        self.add_residue(&debited.currency, debit_residue);
+        self.add_residue(&credited.currency, credit_residue);
+        self.add_residue(&credited.currency, fee_residue);
+
+        self.emit(BankEvent::TransferCompleted {
+            from: from.clone(),
+            to: to.clone(),
+            debited: debited.clone(),
+            credited: credited.clone(),
+        });
+        if self.compliance.is_large(base_amount) {
+            self.flag(&from, TransactionType::Withdraw, debited.clone());
+        }
+        let receipt = (debited.currency != credited.currency).then(|| self.log_conversion(Some(&from), debited.clone(), credited.clone(), rate, fee.clone()));
+        Ok(TransferReceipt { from, to, debited, credited, rate, fee, legs, receipt })
+    }
+
+    /// Set up a standing order moving `amount` from `from` to `to` every
+    /// `interval_days` days, the first transfer falling due on `first`.
+    /// The source's `pin` is required once, here, if it is protected; the
+    /// transfers themselves are made by `run_standing_orders`. IDs are one
+    /// more than the highest in use.
+    pub fn create_standing_order(
+        &mut self,
+        from: &str,
+        to: &str,
+        amount: Money,
+        interval_days: u32,
+        first: Date,
+        pin: Option<&str>,
+    ) -> Result<&StandingOrder, BankError> {
+        self.ensure_writable()?;
+        let (src, _) = self.transfer_accounts(from, to)?;
+        if !self.accounts[src].verify_pin(pin) {
+            return Err(AccountError::InvalidPin.into());
+        }
+        self.forex.convert(&amount, &self.accounts[src].currency)?;
+        let id = self.standing_orders.iter().map(|o| o.id).max().unwrap_or(0) + 1;
+        self.standing_orders.push(StandingOrder::new(id, from, to, amount, interval_days, first)?);
+        Ok(&self.standing_orders[self.standing_orders.len() - 1])
+    }
+
+    /// Find a standing order by ID. Returns `None` if not found.
+    pub fn find_standing_order(&self, id: usize) -> Option<&StandingOrder> {
+        self.standing_orders.iter().find(|o| o.id == id)
+    }
+
+    /// Skip the order's next transfer and return the business day of the
+    /// one after.
+    pub fn skip_standing_order(&mut self, id: usize) -> Result<Date, BankError> {
+        self.ensure_writable()?;
+        let order = self.standing_orders.iter_mut().find(|o| o.id == id).ok_or(BankError::StandingOrderNotFound(id))?;
+        order.advance();
+        Ok(order.due_date(&self.calendar))
+    }
+
+    /// Remove a standing order so it makes no further transfers.
+    pub fn cancel_standing_order(&mut self, id: usize) -> Result<StandingOrder, BankError> {
+        self.ensure_writable()?;
+        let index = self.standing_orders.iter().position(|o| o.id == id).ok_or(BankError::StandingOrderNotFound(id))?;
+        Ok(self.standing_orders.remove(index))
+    }
+
+    /// Make every standing-order transfer due on or before
+    /// `today`, oldest due date first (then lowest ID), and move each order
+    /// on to its next date. Scheduled dates that are not business days are
+    /// rolled by the bank's calendar (see `StandingOrder::due_date`). An order that fell behind catches up with one
+    /// transfer per missed date. A refused transfer (insufficient funds, an
+    /// unknown currency) is reported in its run and not retried; the order
+    /// still moves on.
+    pub fn run_standing_orders(&mut self, today: Date) -> Vec<StandingOrderRun> {
+        if self.read_only {
+            return Vec::new();
+        }
+        let mut runs = Vec::new();
+        while let Some(index) = (0..self.standing_orders.len())
+            .filter(|&i| self.standing_orders[i].is_due(today, &self.calendar))
+            .min_by_key(|&i| (self.standing_orders[i].due_date(&self.calendar), self.standing_orders[i].id))
+        {
+            let order = self.standing_orders[index].clone();
+            let date = order.due_date(&self.calendar);
+            let result = self.transfer_accounts(&order.from, &order.to).and_then(|(src, dst)| {
+                self.post_transfer(src, dst, order.amount.clone(), |_, _| {
+                    (format!("Standing order {} to {}", order.id, order.to), format!("Standing order {} from {}", order.id, order.from))
+                })
+            });
+            self.standing_orders[index].advance();
+            runs.push(StandingOrderRun { order: order.id, date, result });
+        }
+        runs
+    }
+
+    /// Set up a sweep moving whatever `from` holds above `threshold` (in its
+    /// currency) to `to` at each end of day. The source's `pin` is required
+    /// once, here, if it is protected; the sweeps themselves are made by
+    /// `run_sweeps`. IDs are one more than the highest in use.
+    pub fn create_sweep(&mut self, from: &str, to: &str, threshold: Decimal, pin: Option<&str>) -> Result<&SweepRule, BankError> {
+        self.ensure_writable()?;
+        let (src, _) = self.transfer_accounts(from, to)?;
+        if !self.accounts[src].verify_pin(pin) {
+            return Err(AccountError::InvalidPin.into());
+        }
+        let threshold = Money::new(threshold, &self.accounts[src].currency);
+        let id = self.sweeps.iter().map(|s| s.id).max().unwrap_or(0) + 1;
+        self.sweeps.push(SweepRule::new(id, from, to, threshold)?);
+        Ok(&self.sweeps[self.sweeps.len() - 1])
+    }
+
+    /// Find a sweep rule by ID. Returns `None` if not found.
+    pub fn find_sweep(&self, id: usize) -> Option<&SweepRule> {
+        self.sweeps.iter().find(|s| s.id == id)
+    }
+
+    /// Remove a sweep rule so it moves nothing further.
+    pub fn cancel_sweep(&mut self, id: usize) -> Result<SweepRule, BankError> {
+        self.ensure_writable()?;
+        let index = self.sweeps.iter().position(|s| s.id == id).ok_or(BankError::SweepNotFound(id))?;
+        Ok(self.sweeps.remove(index))
+    }
+
+    /// Move what each sweep's source holds above its threshold to its
+    /// destination, lowest ID first, so a sweep into an account that is
+    /// itself swept moves on in the same run if its ID is higher. The two
+    /// legs are memoed "Sweep N to NAME" and "Sweep N from NAME". Sources
+    /// at or below their threshold are left out; a refused transfer (an
+    /// unknown account, a conversion limit) is reported in its run.
+    pub fn run_sweeps(&mut self) -> Vec<SweepRun> {
+        if self.read_only {
+            return Vec::new();
+        }
+        let mut runs = Vec::new();
+        for rule in self.sweeps.clone() {
+            let result = self.transfer_accounts(&rule.from, &rule.to).and_then(|(src, dst)| {
+                let Some(excess) = rule.excess(&self.available_at(src)) else {
+                    return Ok(None);
+                };
+                self.post_transfer(src, dst, excess, |_, _| (format!("Sweep {} to {}", rule.id, rule.to), format!("Sweep {} from {}", rule.id, rule.from)))
+                    .map(Some)
+            });
+            match result {
+                Ok(None) => {}
+                Ok(Some(receipt)) => runs.push(SweepRun { rule: rule.id, result: Ok(receipt) }),
+                Err(e) => runs.push(SweepRun { rule: rule.id, result: Err(e) }),
+            }
+        }
+        runs
+    }
+
+    /// Book a forward for the named account to buy or sell `amount` of a
+    /// foreign currency at `rate` (units of the account's currency per
+    /// unit) on `value_date`. The account's `pin` is required once, here,
+    /// if it is protected; the settlement is posted by `end_of_day`. Fails
+    /// if the account does not exist, the PIN is rejected, the currency is
+    /// not in the catalog, the value date is not after today, or the terms
+    /// are invalid.
+    pub fn book_forward(
+        &mut self,
+        account: &str,
+        side: ForwardSide,
+        amount: Money,
+        rate: Decimal,
+        value_date: Date,
+        pin: Option<&str>,
+    ) -> Result<&ForwardContract, BankError> {
+        self.ensure_writable()?;
+        let acct = self
+            .accounts
+            .iter()
+            .find(|a| a.name == account)
+            .ok_or_else(|| BankError::AccountNotFound(account.to_string()))?;
+        if !acct.verify_pin(pin) {
+            return Err(AccountError::InvalidPin.into());
+        }
+        self.forex.convert(&amount, &acct.currency)?;
+        if value_date <= self.today() {
+            return Err(ForwardError::ValueDateNotInFuture(value_date).into());
+        }
+        let id = self.forwards.len() + 1;
+        let contract = ForwardContract::new(id, account, &acct.currency, side, amount, rate, value_date)?;
+        self.forwards.push(contract);
+        Ok(&self.forwards[id - 1])
+    }
+
+    /// Open forwards marked against today's spot rates, by ID. Fails if a
+    /// forward's currency has left the catalog or a valuation overflows.
+    pub fn forward_valuations(&self) -> Result<Vec<ForwardValuation>, BankError> {
+        self.forwards.iter().filter(|f| !f.settled).map(|f| self.value_forward(f)).collect()
+    }
+
+    fn value_forward(&self, contract: &ForwardContract) -> Result<ForwardValuation, BankError> {
+        let acct = self
+            .accounts
+            .iter()
+            .find(|a| a.name == contract.account)
+            .ok_or_else(|| BankError::AccountNotFound(contract.account.clone()))?;
+        let spot = self.forex.convert(&Money::new(Decimal::ONE, &contract.amount.currency), &acct.currency)?.amount;
+        let value = contract.mark_to_market(spot).ok_or(AccountError::AmountOutOfRange)?;
+        Ok(ForwardValuation {
+            contract: contract.clone(),
+            spot,
+            mark_to_market: Money::new(value.round_dp(acct.minor_unit_dp), &acct.currency),
+        })
+    }
+
+    /// Settle every open forward whose value date, rolled to a business day
+    /// by the bank's calendar, is on or before `today`, by ID: credit (sell) or debit (buy) amount × rate, rounded with the
+    /// bank's rounding policy, memoed "Forward 1: sell 1000 USD at 57.5". A
+    /// refused settlement (e.g. insufficient funds for a buy) leaves the
+    /// forward open, so the next run tries again.
+    pub fn settle_forwards(&mut self, today: Date) -> Vec<ForwardSettlement> {
+        if self.read_only {
+            return Vec::new();
+        }
+        let due: Vec<usize> = (0..self.forwards.len()).filter(|&i| self.forwards[i].is_due(today, &self.calendar)).collect();
+        let mut settlements = Vec::new();
+        for index in due {
+            let contract = self.forwards[index].clone();
+            let result = self.settle_forward(&contract);
+            if result.is_ok() {
+                self.forwards[index].settled = true;
+            }
+            settlements.push(ForwardSettlement { contract: self.forwards[index].clone(), result });
+        }
+        settlements
+    }
+
+    fn settle_forward(&mut self, contract: &ForwardContract) -> Result<Money, BankError> {
+        let currency = self
+            .accounts
+            .iter()
+            .find(|a| a.name == contract.account)
+            .map(|a| a.currency.clone())
+            .ok_or_else(|| BankError::AccountNotFound(contract.account.clone()))?;
+        let exact = contract.settlement_amount().ok_or(AccountError::AmountOutOfRange)?;
+        let (amount, residue) = self.rounding.apply(&Money::new(exact, &currency), self.forex.decimals(&currency));
+        let tx_type = match contract.side {
+            ForwardSide::Buy => TransactionType::Withdraw,
+            ForwardSide::Sell => TransactionType::Deposit,
+        };
+        let memo = format!("Forward {}: {} {} at {}", contract.id, contract.side.name(), contract.amount, contract.rate);
+        self.post_authorized(&contract.account, tx_type, amount.clone(), &memo)?;
+        self.add_residue(&currency, residue);
+        let (amount_in, amount_out, rate) = match contract.side {
+            ForwardSide::Buy => (amount.clone(), contract.amount.clone(), Decimal::ONE.checked_div(contract.rate).unwrap_or(Decimal::ZERO)),
+            ForwardSide::Sell => (contract.amount.clone(), amount.clone(), contract.rate),
+        };
+        let fee = Money::zero(&amount_out.currency);
+        self.log_conversion(Some(&contract.account), amount_in, amount_out, rate, fee);
+        Ok(amount)
+    }
+
+    /// End-of-day job for `today`: on a non-business day, carry the last
+    /// provider closes onto it (see `carry_closes`); settle the forwards due
+    /// (see `settle_forwards`), then make the standing-order transfers due (see
+    /// `run_standing_orders`). On the last day of a month, it then takes the
+    /// month's service charges (see `take_service_charges`) and issues a
+    /// statement of that month for every active account, queued for
+    /// delivery, so the charges are on it.
+    /// A read-only bank does none of it.
+    pub fn end_of_day(&mut self, today: Date) -> EndOfDay {
+        if self.read_only {
+            return EndOfDay { date: today, carried: Vec::new(), forwards: Vec::new(), standing_orders: Vec::new(), sweeps: Vec::new(), service_charges: Vec::new(), statements: 0 };
+        }
+        let carried = self.carry_closes(today);
+        let forwards = self.settle_forwards(today);
+        let standing_orders = self.run_standing_orders(today);
+        let sweeps = self.run_sweeps();
+        let (mut service_charges, mut statements) = (Vec::new(), 0);
+        if today.day == days_in_month(today.year, today.month) {
+            let first = Date { day: 1, ..today };
+            service_charges = self.take_service_charges(first, today);
+            let issued: Vec<Statement> = self.active_accounts().map(|acct| self.statement_for(acct, first, today)).collect();
+            statements = issued.len();
+            self.statements.extend(issued);
+        }
+        EndOfDay { date: today, carried, forwards, standing_orders, sweeps, service_charges, statements }
+    }
+
+    /// On a day that is not a business day of the calendar, carry the last
+    /// close of every currency last set by a named provider onto `today`,
+    /// marked carried (see `Forex::carry_close`): providers publish nothing
+    /// then, so the rate in force is the last business day's, not a stale
+    /// one. A rate already set that day is kept.
+    fn carry_closes(&mut self, today: Date) -> Vec<(String, Decimal)> {
+        if self.calendar.is_business_day(today) {
+            return Vec::new();
+        }
+        let published: Vec<String> = self.forex.currencies_detailed().into_iter().filter(|c| matches!(c.source, Some(RateSource::Provider(_)))).map(|c| c.code).collect();
+        published.into_iter().filter_map(|code| self.forex.carry_close(&code, today).map(|rate| (code, rate))).collect()
+    }
+
+    /// Take each active account's service charges for `first` through
+    /// `last` (see `ServiceCharges`), judged on its average daily balance
+    /// over them: the flat monthly fee, then the charge for falling below
+    /// the maintaining balance. Each is taken only as far as the balance
+    /// covers it, and not at all from an empty account or one not yet
+    /// opened by `last`.
+    pub fn take_service_charges(&mut self, first: Date, last: Date) -> Vec<ServiceChargeRun> {
+        if self.read_only {
+            return Vec::new();
+        }
+        let mut runs = Vec::new();
+        for index in 0..self.accounts.len() {
+            let acct = &self.accounts[index];
+            if acct.is_archived() || acct.service_charges.is_empty() {
+                continue;
+            }
+            let Some(average) = acct.average_daily_balance(first, last) else {
+                continue;
+            };
+            for (memo, charge) in acct.service_charges.due(average.amount) {
+                let amount = charge.min(self.available_at(index).amount);
+                if amount <= Decimal::ZERO {
+                    continue;
+                }
+                let result = self.post_at(index, TransactionType::Withdraw, Money::new(amount, &average.currency), memo).map(|_| Money::new(amount, &average.currency));
+                runs.push(ServiceChargeRun { account: self.accounts[index].name.clone(), memo, average: average.clone(), result });
+            }
+        }
+        runs
+    }
+
+    /// Place a limit order for the named account to convert `amount` into
+    /// `to` once the rate reaches `limit` (units of the account's currency
+    /// per unit of the foreign one), open through `expires` or, without
+    /// it, until cancelled. The account's `pin` is required once, here, if
+    /// it is protected; the conversion is posted by `fill_limit_orders`.
+    /// Fails if the account does not exist, the PIN is rejected, a currency
+    /// is not in the catalog, the terms are invalid, the expiry date is
+    /// past, or spot already meets the limit. IDs are one more than the
+    /// highest in use.
+    pub fn place_limit_order(&mut self, account: &str, amount: Money, to: &str, limit: Decimal, expires: Option<Date>, pin: Option<&str>) -> Result<&LimitOrder, BankError> {
+        self.ensure_writable()?;
+        let acct = self
+            .accounts
+            .iter()
+            .find(|a| a.name == account)
+            .ok_or_else(|| BankError::AccountNotFound(account.to_string()))?;
+        if !acct.verify_pin(pin) {
+            return Err(AccountError::InvalidPin.into());
+        }
+        let id = self.limit_orders.iter().map(|o| o.id).max().unwrap_or(0) + 1;
+        let mut order = LimitOrder::new(id, account, &acct.currency, amount, to, limit)?;
+        order.expires = expires;
+        self.check_limit_order_terms(&order, &acct.currency)?;
+        self.limit_orders.push(order);
+        Ok(&self.limit_orders[self.limit_orders.len() - 1])
+    }
+
+    /// Change an open limit order's terms: the total `amount` to convert
+    /// (in the order's currency, including any part already filled), the
+    /// `limit`, and the expiry date, `None` for good till cancelled. The
+    /// order keeps its ID, currencies, and fills. Fails, changing nothing,
+    /// if the order is not open, the amount is not above what has been
+    /// filled, the limit is not positive or already met by spot, or the
+    /// expiry date is past. The order's moving average, if any, is kept
+    /// (see `confirm_limit_order`).
+    pub fn amend_limit_order(&mut self, id: usize, amount: Decimal, limit: Decimal, expires: Option<Date>) -> Result<&LimitOrder, BankError> {
+        self.ensure_writable()?;
+        let index = self.open_limit_order_index(id)?;
+        let current = &self.limit_orders[index];
+        let currency = self
+            .accounts
+            .iter()
+            .find(|a| a.name == current.account)
+            .map(|a| a.currency.clone())
+            .ok_or_else(|| BankError::AccountNotFound(current.account.clone()))?;
+        if amount <= current.filled_amount {
+            return Err(LimitOrderError::BelowFilled(current.filled_amount).into());
+        }
+        let mut order = LimitOrder::new(id, &current.account, &currency, Money::new(amount, &current.amount.currency), &current.to, limit)?;
+        order.filled_amount = current.filled_amount;
+        order.expires = expires;
+        order.average = current.average;
+        self.check_limit_order_terms(&order, &currency)?;
+        self.limit_orders[index] = order;
+        Ok(&self.limit_orders[index])
+    }
+
+    /// Refuse an order whose expiry date is past or whose limit spot
+    /// already meets.
+    fn check_limit_order_terms(&self, order: &LimitOrder, account_currency: &str) -> Result<(), BankError> {
+        if let Some(last) = order.expires.filter(|_| order.is_expired(self.today())) {
+            return Err(LimitOrderError::ExpiryPassed(last).into());
+        }
+        let spot = self.limit_order_spot(order, account_currency)?;
+        if order.is_reached(account_currency, spot) {
+            return Err(LimitOrderError::LimitReached(spot).into());
+        }
+        Ok(())
+    }
+
+    /// Have an open limit order fill only once spot is also on its side of
+    /// `average` of the pair's closing rates, or, with `None`, as soon as
+    /// the limit is reached (see `LimitOrder`).
+    pub fn confirm_limit_order(&mut self, id: usize, average: Option<MovingAverage>) -> Result<&LimitOrder, BankError> {
+        self.ensure_writable()?;
+        let index = self.open_limit_order_index(id)?;
+        self.limit_orders[index].average = average;
+        Ok(&self.limit_orders[index])
+    }
+
+    /// Remove an open limit order so nothing more of it is filled.
+    pub fn cancel_limit_order(&mut self, id: usize) -> Result<LimitOrder, BankError> {
+        self.ensure_writable()?;
+        let index = self.open_limit_order_index(id)?;
+        Ok(self.limit_orders.remove(index))
+    }
+
+    fn open_limit_order_index(&self, id: usize) -> Result<usize, BankError> {
+        self.limit_orders
+            .iter()
+            .position(|o| o.id == id && !o.filled)
+            .ok_or(BankError::LimitOrderNotFound(id))
+    }
+
+    /// The order book: open limit orders, partly filled ones included,
+    /// with today's spot rate for each, by ID. Orders past their expiry
+    /// date, or whose account or currency has gone, are left out.
+    pub fn open_limit_orders(&self) -> Vec<(&LimitOrder, Decimal)> {
+        let today = self.today();
+        self.limit_orders
+            .iter()
+            .filter(|o| !o.filled && !o.is_expired(today))
+            .filter_map(|o| {
+                let acct = self.accounts.iter().find(|a| a.name == o.account)?;
+                Some((o, self.limit_order_spot(o, &acct.currency).ok()?))
+            })
+            .collect()
+    }
+
+    /// Units of `account_currency` per unit of the order's foreign currency.
+    fn limit_order_spot(&self, order: &LimitOrder, account_currency: &str) -> Result<Decimal, ForexError> {
+        let foreign = Money::new(Decimal::ONE, order.foreign_currency(account_currency));
+        Ok(self.forex.convert(&foreign, account_currency)?.amount)
+    }
+
+    /// Whether `spot` meets the order's limit and, if it has one, its
+    /// moving average; not while the average lacks the closes it needs.
+    fn limit_order_reached(&self, order: &LimitOrder, account_currency: &str, spot: Decimal) -> bool {
+        order.is_reached(account_currency, spot)
+            && order.average.is_none_or(|average| {
+                self.forex
+                    .moving_average_in(order.foreign_currency(account_currency), account_currency, average)
+                    .is_ok_and(|value| order.is_confirmed(account_currency, spot, value))
+            })
+    }
+
+    /// Work the order book after a rate update, by ID. Orders past their
+    /// expiry date are dropped, reported with `LimitOrderError::Expired`.
+    /// Every other open order whose limit spot now meets (and its moving
+    /// average confirms, if it has one) is filled for
+    /// what is left of it, or for as much as the conversion limits still
+    /// allow the account today on either side, rounded down to the
+    /// currency's minor unit; the rest waits for a later update. An inbound
+    /// fill credits the part converted at spot and rounded with the bank's
+    /// rounding policy, an outbound one debits the part, memoed "Limit
+    /// order 1: 100 USD to PHP at 59". A refused fill (e.g. insufficient
+    /// funds, or no room left under a limit) leaves the order open, so the
+    /// next rate update tries again. Filled orders are kept with `filled`
+    /// set.
+    pub fn fill_limit_orders(&mut self) -> Vec<LimitOrderFill> {
+        if self.read_only {
+            return Vec::new();
+        }
+        let today = self.today();
+        let mut fills = Vec::new();
+        let mut index = 0;
+        while index < self.limit_orders.len() {
+            let order = self.limit_orders[index].clone();
+            let currency = self.accounts.iter().find(|a| a.name == order.account).map(|a| a.currency.clone());
+            let spot = currency.as_deref().and_then(|c| self.limit_order_spot(&order, c).ok());
+            if !order.filled && order.is_expired(today) {
+                self.limit_orders.remove(index);
+                if let (Some(spot), Some(last)) = (spot, order.expires) {
+                    let converted = Money::zero(&order.amount.currency);
+                    fills.push(LimitOrderFill { order, spot, converted, result: Err(LimitOrderError::Expired(last).into()) });
+                }
+                continue;
+            }
+            index += 1;
+            let (Some(currency), Some(spot)) = (currency, spot) else {
+                continue;
+            };
+            if order.filled || !self.limit_order_reached(&order, &currency, spot) {
+                continue;
+            }
+            let (converted, result) = match self.fill_limit_order(&order, &currency, spot) {
+                Ok((part, posted)) => {
+                    let filled = &mut self.limit_orders[index - 1];
+                    filled.filled_amount += part.amount;
+                    filled.filled = filled.filled_amount >= filled.amount.amount;
+                    (part, Ok(posted))
+                }
+                Err(e) => (Money::zero(&order.amount.currency), Err(e)),
+            };
+            fills.push(LimitOrderFill { order: self.limit_orders[index - 1].clone(), spot, converted, result });
+        }
+        fills
+    }
+
+    /// Convert as much of what is left of `order` as the conversion limits
+    /// allow, returning the part converted and the amount posted.
+    fn fill_limit_order(&mut self, order: &LimitOrder, currency: &str, spot: Decimal) -> Result<(Money, Money), BankError> {
+        let remaining = order.remaining();
+        let rate = self.forex.convert(&Money::new(Decimal::ONE, &remaining.currency), &order.to)?.amount;
+        let mut part = remaining.amount;
+        if let Some(room) = self.conversion_headroom(Some(&order.account), &remaining.currency) {
+            part = part.min(room);
+        }
+        if let Some(room) = self.conversion_headroom(Some(&order.account), &order.to) {
+            part = part.min(room / rate);
+        }
+        let part = Money::new(part.round_dp_with_strategy(self.forex.decimals(&remaining.currency), RoundingStrategy::ToZero), &remaining.currency);
+        if part.amount <= Decimal::ZERO {
+            // Nothing fits today; report the limit the whole remainder breaks.
+            let out = self.rounding.apply(&self.forex.convert(&remaining, &order.to)?, self.forex.decimals(&order.to)).0;
+            self.check_conversion_limits(Some(&order.account), [&remaining, &out])?;
+        }
+        let exact = self.forex.convert(&part, &order.to)?;
+        let (amount_out, residue) = self.rounding.apply(&exact, self.forex.decimals(&order.to));
+        self.check_conversion_limits(Some(&order.account), [&part, &amount_out])?;
+        let (tx_type, posted, residue) = if order.is_inbound(currency) {
+            (TransactionType::Deposit, amount_out.clone(), residue)
+        } else {
+            (TransactionType::Withdraw, part.clone(), Decimal::ZERO)
+        };
+        let memo = format!("Limit order {}: {} to {} at {}", order.id, part, order.to, spot);
+        self.post_authorized(&order.account, tx_type, posted.clone(), &memo)?;
+        self.add_residue(currency, residue);
+        let fee = Money::zero(&amount_out.currency);
+        self.log_conversion(Some(&order.account), part.clone(), amount_out, rate, fee);
+        Ok((part, posted))
+    }
+
+    /// Open a loan of `principal` (in the account's currency) for the
+    /// named account and disburse it there as a deposit ("Loan {id}
+    /// disbursement"). `annual_rate` is a fraction and `term` the number of
+    /// payments due at `frequency`. The account's `pin` is required if it is
+    /// protected. Returns the new loan; fails, opening nothing, if the
+    /// account does not exist, the PIN is rejected, or the terms are invalid.
+    pub fn open_loan(
+        &mut self,
+        account: &str,
+        principal: Decimal,
+        annual_rate: Decimal,
+        term: u32,
+        frequency: PaymentFrequency,
+        pin: Option<&str>,
+    ) -> Result<&Loan, BankError> {
+        self.ensure_writable()?;
+        let acct = self
+            .accounts
+            .iter()
+            .find(|a| a.name == account)
+            .ok_or_else(|| BankError::AccountNotFound(account.to_string()))?;
+        let id = self.loans.len() + 1;
+        let loan = Loan::new(id, account, Money::new(principal, &acct.currency), acct.minor_unit_dp, annual_rate, term, frequency)?;
+        self.post_transaction(account, TransactionType::Deposit, loan.principal.clone(), &format!("Loan {} disbursement", id), pin)?;
+        self.loans.push(loan);
+        Ok(&self.loans[id - 1])
+    }
+
+    /// Find a loan by ID. Returns `None` if not found.
+    pub fn find_loan(&self, id: usize) -> Option<&Loan> {
+        self.loans.iter().find(|l| l.id == id)
+    }
+
+    /// Withdraw the loan's next scheduled payment from its account ("Loan
+    /// {id} payment {n}/{term}") and return that schedule row. The account's
+    /// `pin` is required if it is protected. Fails if the loan does not
+    /// exist or is paid off, or the account cannot cover the payment.
+    pub fn pay_loan(&mut self, id: usize, pin: Option<&str>) -> Result<AmortizationRow, BankError> {
+        self.ensure_writable()?;
+        let loan = self.find_loan(id).ok_or(BankError::LoanNotFound(id))?;
+        let row = loan.next_payment().ok_or(LoanError::PaidOff(id))?;
+        let (account, memo) = (loan.account.clone(), format!("Loan {} payment {}/{}", id, row.period, loan.term));
+        self.post_transaction(&account, TransactionType::Withdraw, row.payment.clone(), &memo, pin)?;
+        if let Some(loan) = self.loans.iter_mut().find(|l| l.id == id) {
+            loan.payments_made += 1;
+        }
+        Ok(row)
+    }
+
+    /// Format `amount` for display using its currency's symbol and minor-unit
+    /// decimal places and the bank's locale (e.g. "₱1,234,567.89").
+    pub fn format_money(&self, amount: &Money) -> String {
+        format_amount(
+            amount.amount,
+            &self.forex.symbol(&amount.currency),
+            self.forex.decimals(&amount.currency),
+            self.locale,
+        )
+    }
+
+    /// Format Unix seconds for display as local time in the bank's time
+    /// zone, labelled with its offset (e.g. "2026-10-16 14:05 UTC+08:00").
+    pub fn format_timestamp(&self, secs: i64) -> String {
+        self.time_zone.format_timestamp(secs)
+    }
+
+    /// The date Unix seconds fall on in the bank's time zone, for display.
+    /// Limits, statements, and budgets still go by UTC dates.
+    pub fn local_date(&self, secs: i64) -> Date {
+        self.time_zone.date_of(secs)
+    }
+
+    /// Round `amount` to its currency's minor unit with the bank's policy and
+    /// add the discarded fraction to the residue for that currency. Returns
+    /// the rounded amount.
+    pub fn round_and_track(&mut self, amount: &Money) -> Money {
+        let dp = self.forex.decimals(&amount.currency);
+        let (rounded, residue) = self.rounding.apply(amount, dp);
+        self.add_residue(&amount.currency, residue);
+        rounded
+    }
+
+    /// The rounding residue of each currency valued in the base currency at
+    /// transfer rates, with their total: the net effect rounding has had on the
+    /// books. A currency no longer quoted is left out of the total and
+    /// valued as `None`.
+    pub fn rounding_effect(&self) -> (Vec<(String, Decimal, Option<Money>)>, Money) {
+        let base = &self.base_currency.code;
+        let mut total = Decimal::ZERO;
+        let rows = self
+            .rounding_residue
+            .iter()
+            .map(|(code, residue)| {
+                let value = self.forex.convert(&Money::new(*residue, code), base).ok();
+                if let Some(v) = &value {
+                    total += v.amount;
+                }
+                (code.clone(), *residue, value)
+            })
+            .collect();
+        (rows, Money::new(total, base))
+    }
+
+    fn add_residue(&mut self, currency: &str, residue: Decimal) {
+        *self
+            .rounding_residue
+            .entry(currency.to_string())
+            .or_insert(Decimal::ZERO) += residue;
+    }
+
+    /// Credit `days` of daily-compounded interest to the named account as a
+    /// deposit, rounded with the bank's rounding policy, less the
+    /// `withholding_tax_rate` share taken as a "Withholding tax"
+    /// withdrawal right after it. At a negative rate
+    /// the interest is negative and is taken as a "Carrying charge"
+    /// withdrawal instead. Returns the posted amount (possibly zero, and
+    /// negative for a charge); fails if the account does not exist or the
+    /// interest is too large to represent, in which case nothing is posted.
+    pub fn post_interest(&mut self, name: &str, days: usize) -> Result<Money, BankError> {
+        self.ensure_writable()?;
+        let index = self
+            .accounts
+            .iter()
+            .position(|a| a.name == name)
+            .ok_or_else(|| BankError::AccountNotFound(name.to_string()))?;
+        let exact = self.accounts[index].accrued_interest(days, self.today())?;
+        self.credit_interest(index, &exact)
+    }
+
+    /// Credit the interest the named account earned from `since` through
+    /// yesterday, value-dated (see `Account::value_dated_interest`): each
+    /// deposit earns from the day it was made and each withdrawal stops
+    /// earning from the day it was taken, rather than the whole period
+    /// earning on today's balance. Posted, taxed, and rounded as
+    /// `post_interest` does; nothing is posted for a `since` of today or
+    /// later.
+    pub fn post_interest_since(&mut self, name: &str, since: Date) -> Result<Money, BankError> {
+        self.ensure_writable()?;
+        let index = self
+            .accounts
+            .iter()
+            .position(|a| a.name == name)
+            .ok_or_else(|| BankError::AccountNotFound(name.to_string()))?;
+        let exact = self.accounts[index].value_dated_interest(since, self.today())?;
+        self.credit_interest(index, &exact)
+    }
+
+    /// `post_interest_since` for every account, computed and posted as
+    /// `post_interest_all` does.
+    pub fn post_interest_all_since(&mut self, since: Date) -> Result<Vec<(String, Money)>, BankError> {
+        self.ensure_writable()?;
+        let today = self.today();
+        let accrued = parallel::map(&self.accounts, |a| a.value_dated_interest(since, today))
+            .into_iter()
+            .collect::<Result<Vec<_>, _>>()?;
+        accrued
+            .iter()
+            .enumerate()
+            .map(|(index, exact)| Ok((self.accounts[index].name.clone(), self.credit_interest(index, exact)?)))
+            .collect()
+    }
+
+    /// `post_interest` for every account, returning each account's name
+    /// and posted amount in account order. The accruals are computed first,
+    /// across threads with the `parallel` feature; if any overflows nothing
+    /// is posted. Postings, events, and rounding residue then follow in
+    /// account order, the same as posting one account at a time.
+    pub fn post_interest_all(&mut self, days: usize) -> Result<Vec<(String, Money)>, BankError> {
+        self.ensure_writable()?;
+        let today = self.today();
+        let accrued = parallel::map(&self.accounts, |a| a.accrued_interest(days, today))
+            .into_iter()
+            .collect::<Result<Vec<_>, _>>()?;
+        accrued
+            .iter()
+            .enumerate()
+            .map(|(index, exact)| Ok((self.accounts[index].name.clone(), self.credit_interest(index, exact)?)))
+            .collect()
+    }
+
+    /// Open `accounts` accounts in the base currency, named `sim-001`,
+    /// `sim-002`, ... (padded to the width of `accounts`, skipping names
+    /// already taken), and drive them through `days`
+    /// days as `profile` describes, for performance evaluation and
+    /// classroom-scale experiments. The accounts open `days` days ago with
+    /// an opening deposit; each day after that posts the deposits,
+    /// withdrawals, and transfers drawn for it, and interest is credited at
+    /// the end of every `interest_every` days and of the last, with its tax
+    /// withheld. Withdrawals and transfers an account cannot cover are
+    /// declined rather than failing the run. Only the simulated accounts
+    /// are touched.
+    ///
+    /// Postings go by index rather than name and interest accrues as
+    /// `post_interest_all` does, so large runs stay fast. They carry the
+    /// historical dates, played on a simulation clock of their own; the
+    /// bank's clock is put back at the end. Returns the run's statistics
+    /// (see `SimulationReport`). Fails if a posting is refused, e.g. by a
+    /// read-only bank, leaving what was added so far.
+    pub fn simulate(&mut self, accounts: usize, days: usize, profile: &SimulationProfile) -> Result<SimulationReport, BankError> {
+        self.ensure_writable()?;
+        let stopwatch = Stopwatch::start();
+        let (clock, simulation) = (self.clock(), self.simulation_clock());
+        let result = self.play_simulation(accounts, days.max(1), profile, &stopwatch);
+        match simulation {
+            Some(simulation) => self.set_simulation_clock(simulation),
+            None => self.set_clock(clock),
+        }
+        result
+    }
+
+    fn play_simulation(&mut self, count: usize, days: usize, profile: &SimulationProfile, stopwatch: &Stopwatch) -> Result<SimulationReport, BankError> {
+        let mut rng = Rng::new(profile.seed);
+        let start = self.today().add_days(-(days as i64));
+        let first_sequence = self.next_sequence();
+        let (base, dp) = (self.base_currency.code.clone(), self.base_currency.decimals);
+        let money = |amount: Decimal| Money::new(amount, &base);
+        // An amount between half and one and a half times `mean`.
+        let draw = |rng: &mut Rng, mean: Decimal| {
+            let scale = Decimal::from_f64(0.5 + rng.next_f64()).unwrap_or(Decimal::ONE);
+            (mean * scale).round_dp_with_strategy(dp, RoundingStrategy::ToZero)
+        };
+
+        let taken: HashSet<&str> = self.accounts.iter().flat_map(|a| std::iter::once(&a.name).chain(&a.aliases)).map(String::as_str).collect();
+        let width = count.to_string().len();
+        let names: Vec<String> = (1..).map(|n| format!("sim-{:0width$}", n)).filter(|n| !taken.contains(n.as_str())).take(count).collect();
+        let first = self.accounts.len();
+        for name in &names {
+            self.push_account(name, &base, dp);
+        }
+        let simulated = first..self.accounts.len();
+
+        let (mut deposits, mut withdrawals, mut transfers, mut declined) = (0, 0, 0, 0);
+        let [mut opening, mut deposited, mut withdrawn, mut transferred, mut interest] = [Decimal::ZERO; 5];
+        self.set_simulation_clock(Arc::new(SimulationClock::new(start.timestamp() + 9 * 3600)));
+        for index in simulated.clone() {
+            let amount = draw(&mut rng, profile.opening);
+            if amount > Decimal::ZERO {
+                self.post_at(index, TransactionType::Deposit, money(amount), "Opening deposit")?;
+                opening += amount;
+            }
+        }
+
+        let mut accrued_days = 0;
+        for day in 1..=days {
+            let date = start.add_days(day as i64);
+            self.set_simulated_time(date.timestamp() + 12 * 3600);
+            for index in simulated.clone() {
+                if rng.next_f64() < profile.deposit_chance {
+                    let amount = draw(&mut rng, profile.deposit_amount);
+                    if amount > Decimal::ZERO {
+                        self.post_at(index, TransactionType::Deposit, money(amount), "Deposit")?;
+                        deposits += 1;
+                        deposited += amount;
+                    }
+                }
+                if rng.next_f64() < profile.withdrawal_chance {
+                    let amount = draw(&mut rng, profile.withdrawal_amount);
+                    if amount > self.accounts[index].get_balance().amount {
+                        declined += 1;
+                    } else if amount > Decimal::ZERO {
+                        self.post_at(index, TransactionType::Withdraw, money(amount), "Withdrawal")?;
+                        withdrawals += 1;
+                        withdrawn += amount;
+                    }
+                }
+                if count > 1 && rng.next_f64() < profile.transfer_chance {
+                    let other = first + (index - first + 1 + rng.next_u64() as usize % (count - 1)) % count;
+                    let amount = draw(&mut rng, profile.transfer_amount);
+                    if amount > self.accounts[index].get_balance().amount {
+                        declined += 1;
+                    } else if amount > Decimal::ZERO {
+                        let (from, to) = (self.accounts[index].name.clone(), self.accounts[other].name.clone());
+                        self.post_transfer(index, other, money(amount), |_, _| (format!("Transfer to {}", to), format!("Transfer from {}", from)))?;
+                        transfers += 1;
+                        transferred += amount;
+                    }
+                }
+            }
+            accrued_days += 1;
+            if accrued_days == profile.interest_every.max(1) || day == days {
+                self.set_simulated_time(date.timestamp() + 23 * 3600);
+                let today = self.today();
+                let accrued = parallel::map(&self.accounts[simulated.clone()], |a| a.accrued_interest(accrued_days, today))
+                    .into_iter()
+                    .collect::<Result<Vec<_>, _>>()?;
+                for (offset, exact) in accrued.iter().enumerate() {
+                    interest += self.credit_interest(first + offset, exact)?.amount;
+                }
+                accrued_days = 0;
+            }
+        }
+
+        let mut balances: Vec<Decimal> = self.accounts[simulated].iter().map(|a| a.get_balance().amount).collect();
+        balances.sort_unstable();
+        let pick = |i: usize| money(balances.get(i).copied().unwrap_or(Decimal::ZERO));
+        Ok(SimulationReport {
+            seed: profile.seed,
+            start,
+            days,
+            deposits,
+            withdrawals,
+            transfers,
+            declined,
+            transactions: self.next_sequence() - first_sequence,
+            opening: money(opening),
+            deposited: money(deposited),
+            withdrawn: money(withdrawn),
+            transferred: money(transferred),
+            interest: money(interest),
+            closing: money(balances.iter().fold(Decimal::ZERO, |sum, b| sum + *b)),
+            lowest: pick(0),
+            median: pick(balances.len() / 2),
+            highest: pick(balances.len().saturating_sub(1)),
+            accounts: names,
+            elapsed: stopwatch.elapsed(),
+        })
+    }
+
+    /// Post `exact` interest to the account at `index`, rounded with the
+    /// bank's rounding policy: a deposit when positive, into its payout
+    /// account if it has an open one, followed there by the tax withheld
+    /// from it, and a withdrawal of the charge when negative.
+    fn credit_interest(&mut self, index: usize, exact: &Money) -> Result<Money, BankError> {
+        let dp = self.forex.decimals(&exact.currency);
+        let (posted, residue) = self.rounding.apply(exact, dp);
+        if !posted.amount.is_zero() {
+            let (tx_type, memo, target) = if posted.amount > Decimal::ZERO {
+                match self.payout_index(index) {
+                    Some(target) => (TransactionType::Deposit, format!("{} from {}", INTEREST_MEMO, self.accounts[index].name), target),
+                    None => (TransactionType::Deposit, INTEREST_MEMO.to_string(), index),
+                }
+            } else {
+                (TransactionType::Withdraw, CARRYING_CHARGE_MEMO.to_string(), index)
+            };
+            let amount = Money::new(posted.amount.abs(), &posted.currency);
+            let now = self.now();
+            let acct = &mut self.accounts[target];
+            let held = acct.get_balance().amount;
+            acct.create_transaction_with_memo(tx_type, amount.clone(), &memo, now)?;
+            self.sequence_last(target);
+            self.track_position(target, tx_type, &amount, held);
+            if tx_type == TransactionType::Deposit {
+                self.withhold_tax(target, &amount, now)?;
+            }
+            let acct = &self.accounts[target];
+            let balance = acct.get_balance();
+            let account = acct.name.clone();
+            self.emit(BankEvent::InterestPosted { account, amount: posted.clone(), balance });
+        }
+        self.add_residue(&posted.currency, residue);
+        Ok(posted)
+    }
+
+    /// Withdraw the tax withheld from `interest`, just credited to the
+    /// account at `index`: `withholding_tax_rate` of it, rounded with the
+    /// bank's rounding policy. Nothing is posted when it rounds to zero.
+    fn withhold_tax(&mut self, index: usize, interest: &Money, now: i64) -> Result<(), BankError> {
+        let dp = self.forex.decimals(&interest.currency);
+        let exact = Money::new(interest.amount * self.withholding_tax_rate, &interest.currency);
+        let (tax, _) = self.rounding.apply(&exact, dp);
+        if tax.amount <= Decimal::ZERO {
+            return Ok(());
+        }
+        let acct = &mut self.accounts[index];
+        let held = acct.get_balance().amount;
+        acct.create_transaction_with_memo(TransactionType::Withdraw, tax.clone(), WITHHOLDING_TAX_MEMO, now)?;
+        self.sequence_last(index);
+        self.track_position(index, TransactionType::Withdraw, &tax, held);
+        Ok(())
+    }
+
+    /// The index of the open, same-currency account the interest of the
+    /// account at `index` is paid into, if it has one.
+    fn payout_index(&self, index: usize) -> Option<usize> {
+        let acct = &self.accounts[index];
+        let payout = acct.interest_payout.as_deref()?;
+        self.accounts
+            .iter()
+            .position(|a| a.name == payout && a.archived.is_none() && a.currency == acct.currency)
+    }
+
+    /// Give the transactions of the account at `index` from `start` on the
+    /// next bank-wide sequence numbers, in posting order.
+    fn sequence(&mut self, index: usize, start: usize) {
+        self.next_sequence = self.accounts[index].transactions.assign_sequences(start, self.next_sequence);
+    }
+
+    /// Number the transaction just posted to the account at `index`.
+    fn sequence_last(&mut self, index: usize) {
+        self.sequence(index, self.accounts[index].transactions.len().saturating_sub(1));
+    }
+
+    /// The sequence number the next posting will get. Numbers start at 1
+    /// and are never reused, even after `restore`.
+    pub fn next_sequence(&self) -> u64 {
+        self.next_sequence
+    }
+
+    /// Continue numbering from `next`, or after the highest number any
+    /// transaction already has, whichever is later. Used when restoring a
+    /// snapshot.
+    pub fn resume_sequence(&mut self, next: u64) {
+        let highest = self.accounts.iter().filter_map(|a| a.transactions.sequences().iter().max()).max().copied().unwrap_or(0);
+        self.next_sequence = next.max(highest + 1);
+    }
+
+    /// Move the cost basis of the foreign-currency account at `index` for
+    /// `amount` just posted to it, valued at today's rate; `held` is the
+    /// balance before. Base-currency accounts, and amounts that cannot be
+    /// valued, leave the position alone.
+    fn track_position(&mut self, index: usize, tx_type: TransactionType, amount: &Money, held: Decimal) {
+        let base = &self.base_currency.code;
+        if self.accounts[index].currency == *base {
+            return;
+        }
+        let Ok(value) = self.forex.convert(amount, base) else {
+            return;
+        };
+        let position = &mut self.accounts[index].position;
+        match tx_type {
+            TransactionType::Deposit => position.acquire(value.amount),
+            TransactionType::Withdraw => position.dispose(amount.amount, held, value.amount),
+        };
+    }
+
+    /// FX profit and loss of the named account against the base currency,
+    /// at today's rate. Fails if the account does not exist, is held in the
+    /// base currency, or its currency has left the catalog.
+    pub fn position_report(&self, name: &str) -> Result<PositionReport, BankError> {
+        let acct = self
+            .accounts
+            .iter()
+            .find(|a| a.name == name)
+            .ok_or_else(|| BankError::AccountNotFound(name.to_string()))?;
+        if acct.currency == self.base_currency.code {
+            return Err(BankError::BaseCurrencyAccount(name.to_string()));
+        }
+        let base = &self.base_currency.code;
+        let dp = self.forex.decimals(base);
+        let balance = acct.get_balance();
+        let spot = self.forex.convert(&Money::new(Decimal::ONE, &acct.currency), base)?.amount;
+        let market_value = self.forex.convert(&balance, base)?.amount;
+        let in_base = |amount: Decimal| Money::new(amount.round_dp(dp), base);
+        Ok(PositionReport {
+            account: acct.name.clone(),
+            balance,
+            spot,
+            market_value: in_base(market_value),
+            cost_basis: in_base(acct.position.cost_basis),
+            unrealized: in_base(acct.position.unrealized(market_value)),
+            realized: in_base(acct.position.realized),
+        })
+    }
+
+    /// Value the named account's holdings on `as_of` in the base currency:
+    /// its balance at the end of that day, and each forward booked on it
+    /// that was still open then (unsettled, or settled on a later value
+    /// date) at its mark-to-market. The catalog keeps no rate history, so
+    /// every holding is valued at today's rates. Fails if the account does
+    /// not exist or a holding cannot be converted.
+    pub fn portfolio_value(&self, account: &str, as_of: Date) -> Result<Portfolio, BankError> {
+        let acct = self
+            .accounts
+            .iter()
+            .find(|a| a.name == account)
+            .ok_or_else(|| BankError::AccountNotFound(account.to_string()))?;
+        self.portfolio_of(acct, as_of)
+    }
+
+    /// Forecast accounts `first` and `second` side by side for `days` days
+    /// from today, each under its own rate schedule and promotions (see
+    /// `Account::get_interest_forecast`), valuing both in the base currency
+    /// at today's rates (rounded to its minor unit) to find where their
+    /// curves cross (see `AccountComparison`). Fails if either account does
+    /// not exist, both are the same account, or `days` is zero.
+    pub fn compare_accounts(&self, first: &str, second: &str, days: usize) -> Result<AccountComparison, BankError> {
+        let find = |name: &str| {
+            self.accounts
+                .iter()
+                .find(|a| a.name == name)
+                .ok_or_else(|| BankError::AccountNotFound(name.to_string()))
+        };
+        let accounts = [find(first)?, find(second)?];
+        if accounts[0].id == accounts[1].id {
+            return Err(ComparisonError::SameAccount(accounts[0].name.clone()).into());
+        }
+        if days == 0 {
+            return Err(ComparisonError::InvalidHorizon.into());
+        }
+        let base = &self.base_currency.code;
+        let dp = self.forex.decimals(base);
+        let today = self.today();
+        let compared = |acct: &Account| -> Result<ComparedAccount, BankError> {
+            let rate = self.forex.convert(&Money::new(Decimal::ONE, &acct.currency), base)?.amount;
+            let mut interest = Decimal::ZERO;
+            let points = acct
+                .get_interest_forecast(days, ForecastStep::Daily, today)?
+                .into_iter()
+                .map(|f| {
+                    interest += f.interest.amount;
+                    ComparisonPoint {
+                        day: f.day,
+                        value: Money::new((f.balance.amount * rate).round_dp(dp), base),
+                        interest: Money::new(interest, &acct.currency),
+                        balance: f.balance,
+                    }
+                })
+                .collect();
+            let opening = acct.get_balance();
+            Ok(ComparedAccount {
+                account: acct.name.clone(),
+                opening_value: Money::new((opening.amount * rate).round_dp(dp), base),
+                opening,
+                annual_interest: acct.rate_on(today),
+                rate,
+                points,
+            })
+        };
+        Ok(AccountComparison::new(today, days, [compared(accounts[0])?, compared(accounts[1])?]))
+    }
+
+    /// A `MonteCarlo` of account `name` over `days` days from today: its
+    /// balance, its current annual rate on its year basis, and the bank's
+    /// withholding tax,
+    /// with a foreign account valued in the base currency from today's
+    /// rate, held fixed until given a model (`MonteCarlo::with_fx_model`).
+    /// Add volatility, paths, a seed, and deposits, then `run` it. Fails if
+    /// the account does not exist or its currency has no rate.
+    pub fn monte_carlo(&self, name: &str, days: usize) -> Result<MonteCarlo, BankError> {
+        let acct = self
+            .accounts
+            .iter()
+            .find(|a| a.name == name)
+            .ok_or_else(|| BankError::AccountNotFound(name.to_string()))?;
+        let today = self.today();
+        let simulation = MonteCarlo::new(acct.get_balance(), acct.minor_unit_dp, acct.rate_on(today), today, days)
+            .with_year_basis(acct.year_basis)
+            .with_tax_rate(self.withholding_tax_rate);
+        let base = &self.base_currency.code;
+        if acct.currency == *base {
+            return Ok(simulation);
+        }
+        Ok(simulation.with_fx(FxPath {
+            base: base.clone(),
+            decimals: self.forex.decimals(base),
+            spot: self.forex.convert(&Money::new(Decimal::ONE, &acct.currency), base)?.amount,
+            model: RateModel::new(Decimal::ZERO, Decimal::ZERO),
+        }))
+    }
+
+    /// `portfolio_value` for every active account, in opening order;
+    /// across threads with the `parallel` feature.
+    pub fn portfolio_values(&self, as_of: Date) -> Result<Vec<Portfolio>, BankError> {
+        let accounts: Vec<&Account> = self.active_accounts().collect();
+        parallel::map(&accounts, |a| self.portfolio_of(a, as_of)).into_iter().collect()
+    }
+
+    fn portfolio_of(&self, acct: &Account, as_of: Date) -> Result<Portfolio, BankError> {
+        let account = acct.name.as_str();
+        let base = &self.base_currency.code;
+        let dp = self.forex.decimals(base);
+        let rate = self.forex.convert(&Money::new(Decimal::ONE, &acct.currency), base)?.amount;
+        let holding = |asset: Asset, amount: Money| -> Result<Holding, BankError> {
+            let value = self.forex.convert(&amount, base)?.amount.round_dp(dp);
+            Ok(Holding { asset, amount, rate, value: Money::new(value, base) })
+        };
+        let mut holdings = vec![holding(Asset::Cash, acct.balance_on(as_of))?];
+        for contract in self.forwards.iter().filter(|f| f.account == account && (!f.settled || f.value_date > as_of)) {
+            holdings.push(holding(Asset::Forward(contract.id), self.value_forward(contract)?.mark_to_market)?);
+        }
+        let total = holdings
+            .iter()
+            .try_fold(Decimal::ZERO, |sum, h| sum.checked_add(h.value.amount))
+            .ok_or(AccountError::AmountOutOfRange)?;
+        Ok(Portfolio { account: account.to_string(), as_of, holdings, total: Money::new(total, base) })
+    }
+
+    /// `position_report` for every active account not held in the base
+    /// currency, in opening order.
+    pub fn position_reports(&self) -> Result<Vec<PositionReport>, BankError> {
+        self.active_accounts()
+            .filter(|a| a.currency != self.base_currency.code)
+            .map(|a| self.position_report(&a.name))
+            .collect()
+    }
+
+    /// Update an existing currency's exchange rate through `Forex::set_rate`,
+    /// record a `RateChanged` event for it and for every basket it moved, and
+    /// fill the limit orders the new rates reach (see `fill_limit_orders`).
+    /// Returns the fills attempted. The rate is attributed to
+    /// `RateSource::Manual`; see `set_rate_from`.
+    pub fn set_rate(&mut self, code: &str, rate: Decimal) -> Result<Vec<LimitOrderFill>, BankError> {
+        self.set_rate_from(code, rate, RateSource::Manual)
+    }
+
+    /// `set_rate`, attributing the rate to `source` (e.g. a provider).
+    pub fn set_rate_from(&mut self, code: &str, rate: Decimal, source: RateSource) -> Result<Vec<LimitOrderFill>, BankError> {
+        self.ensure_writable()?;
+        let old = self.forex.get_rate(code).copied();
+        let baskets: Vec<(String, Decimal)> = self
+            .forex
+            .baskets()
+            .iter()
+            .filter_map(|b| Some((b.code.clone(), *self.forex.get_rate(&b.code)?)))
+            .collect();
+        self.forex.set_rate_from(code, rate, source)?;
+        if let (Some(old), Some(&new)) = (old, self.forex.get_rate(code)) {
+            self.emit(BankEvent::RateChanged { code: code.to_string(), old, new });
+        }
+        for (basket, old) in baskets {
+            if let Some(&new) = self.forex.get_rate(&basket)
+                && new != old
+            {
+                self.emit(BankEvent::RateChanged { code: basket, old, new });
+            }
+        }
+        Ok(self.fill_limit_orders())
+    }
+
+    /// Quote an existing currency in cash at `rate`, or at its transfer rate
+    /// again when `rate` is `None` (see `Forex::set_cash_rate`). Cash rates
+    /// only price over-the-counter cash, so no `RateChanged` event is
+    /// recorded and no limit order is filled.
+    pub fn set_cash_rate(&mut self, code: &str, rate: Option<Decimal>) -> Result<(), BankError> {
+        self.ensure_writable()?;
+        Ok(self.forex.set_cash_rate(code, rate)?)
+    }
+
+    /// Cap conversions from and into `code` at `limit`, replacing any
+    /// earlier cap; an unlimited `limit` removes it. Takes the admin
+    /// passphrase: fails with `InvalidPassphrase` if it is rejected, and
+    /// for a currency outside the catalog.
+    pub fn set_conversion_limit(&mut self, code: &str, limit: ConversionLimit, passphrase: &str) -> Result<(), BankError> {
+        self.ensure_writable()?;
+        if !self.verify_admin(passphrase) {
+            return Err(BankError::InvalidPassphrase);
+        }
+        if self.forex.currency(code).is_none() {
+            return Err(ForexError::UnknownCurrency(code.to_string()).into());
+        }
+        if limit.is_unlimited() {
+            self.compliance.conversion_limits.remove(code);
+        } else {
+            self.compliance.conversion_limits.insert(code.to_string(), limit);
+        }
+        Ok(())
+    }
+
+    /// Put `profile`'s caps on the accounts `target` covers, replacing any
+    /// earlier profile for it; an unlimited `profile` removes it. Takes the
+    /// admin passphrase, failing with `InvalidPassphrase` if it is rejected.
+    pub fn set_limit_profile(&mut self, target: LimitTarget, profile: LimitProfile, passphrase: &str) -> Result<(), BankError> {
+        self.ensure_writable()?;
+        if !self.verify_admin(passphrase) {
+            return Err(BankError::InvalidPassphrase);
+        }
+        if profile.is_unlimited() {
+            self.compliance.limit_profiles.remove(&target);
+        } else {
+            self.compliance.limit_profiles.insert(target, profile);
+        }
+        Ok(())
+    }
+
+    /// The limit profiles the account falls under: its type's, if it has
+    /// one, then the one for its holder's KYC status.
+    pub fn limit_profiles_of(&self, acct: &Account) -> Vec<(&LimitTarget, &LimitProfile)> {
+        let status = self.customers.iter().find(|c| c.owns(acct.id)).map_or(VerificationStatus::Unverified, |c| c.verification);
+        let targets = acct.account_type.clone().map(LimitTarget::AccountType).into_iter().chain([LimitTarget::Kyc(status)]);
+        targets.filter_map(|t| self.compliance.limit_profiles.get_key_value(&t)).collect()
+    }
+
+    /// Run `op` once `passphrase` has unlocked the admin role: the gate for
+    /// what only an admin may start from a front end, such as setting rates,
+    /// fees, or interest, archiving accounts, or running the end of day.
+    /// Fails with `InvalidPassphrase`, without running `op`, if the
+    /// passphrase is rejected.
+    pub fn as_admin<T, E: From<BankError>>(&mut self, passphrase: &str, op: impl FnOnce(&mut Self) -> Result<T, E>) -> Result<T, E> {
+        if !self.verify_admin(passphrase) {
+            return Err(BankError::InvalidPassphrase.into());
+        }
+        op(self)
+    }
+
+    /// Run `op` with the conversion limits lifted, once `passphrase` has
+    /// unlocked the admin role: the override path for a conversion refused
+    /// with `BankError::ConversionLimit`. The limits are back in force when
+    /// `op` returns; what it converted still counts towards the day's
+    /// totals. Fails with `InvalidPassphrase`, without running `op`, if the
+    /// passphrase is rejected.
+    pub fn override_conversion_limits<T>(
+        &mut self,
+        passphrase: &str,
+        op: impl FnOnce(&mut Self) -> Result<T, BankError>,
+    ) -> Result<T, BankError> {
+        if !self.verify_admin(passphrase) {
+            return Err(BankError::InvalidPassphrase);
+        }
+        self.limits_overridden = true;
+        let result = op(self);
+        self.limits_overridden = false;
+        result
+    }
+
+    /// Check `amount`, about to be deposited to or withdrawn from `acct`,
+    /// against the limits for unverified accounts and its limit profiles.
+    fn check_account_limits(&self, acct: &Account, tx_type: TransactionType, amount: &Money) -> Result<(), BankError> {
+        self.check_unverified_limit(acct, amount)?;
+        let kind = match tx_type {
+            TransactionType::Deposit => LimitKind::Deposit,
+            TransactionType::Withdraw => LimitKind::Withdrawal,
+        };
+        self.check_limit_profiles(acct, kind, amount)
+    }
+
+    /// Check `amount` against the `kind` cap of each limit profile `acct`
+    /// falls under: every deposit, withdrawal, transfer, and conversion
+    /// goes through here. A conversion's cap is on the day's total, from
+    /// the conversion log; the others are on the amount alone. The amount
+    /// is valued in the base currency at today's rates.
+    fn check_limit_profiles(&self, acct: &Account, kind: LimitKind, amount: &Money) -> Result<(), BankError> {
+        let base = &self.base_currency.code;
+        let value = |m: &Money| self.forex.convert(m, base).map_or(m.amount, |v| v.amount).round_dp(self.base_currency.decimals);
+        for (target, profile) in self.limit_profiles_of(acct) {
+            let Some(cap) = profile.cap(kind) else {
+                continue;
+            };
+            let amount = value(amount);
+            let used = match kind {
+                LimitKind::Conversion => self.converted_today(&acct.name),
+                _ => Decimal::ZERO,
+            };
+            if used + amount > cap {
+                let breach = LimitExceeded { account: acct.name.clone(), target: target.clone(), kind, currency: base.clone(), limit: cap, used, amount };
+                return Err(BankError::LimitExceeded(Box::new(breach)));
+            }
+        }
+        Ok(())
+    }
+
+    /// What `account` has converted today, in the base currency, from the
+    /// volumes in the conversion log.
+    fn converted_today(&self, account: &str) -> Decimal {
+        let today = self.today();
+        let filter = ConversionFilter { start: Some(today), end: Some(today), ..ConversionFilter::default() };
+        self.forex.conversion_history(&filter).into_iter().filter(|r| r.account.as_deref() == Some(account)).map(|r| r.volume).sum()
+    }
+
+    /// Check `amount`, about to be posted to `acct`, against the limits for
+    /// unverified accounts, unless its holder is verified. The day's total
+    /// is everything moved in or out of the account today, valued in the base
+    /// currency at today's rates.
+    fn check_unverified_limit(&self, acct: &Account, amount: &Money) -> Result<(), BankError> {
+        let compliance = &self.compliance;
+        if compliance.unverified_limit.is_none() && compliance.unverified_daily_limit.is_none() || self.is_account_verified(acct.id) {
+            return Ok(());
+        }
+        let base = &self.base_currency.code;
+        let value = |m: &Money| self.forex.convert(m, base).map_or(m.amount, |v| v.amount);
+        let breach = |period, cap: Decimal, used: Decimal, amount: Decimal| {
+            BankError::UnverifiedLimit(acct.name.clone(), Box::new(LimitBreach { currency: base.clone(), period, limit: cap, used, amount }))
+        };
+        let amount = value(amount).round_dp(self.base_currency.decimals);
+        if let Some(cap) = compliance.unverified_limit.filter(|cap| amount > *cap) {
+            return Err(breach(LimitPeriod::Transaction, cap, Decimal::ZERO, amount));
+        }
+        if let Some(cap) = compliance.unverified_daily_limit {
+            let today = self.today();
+            let posted: Decimal = acct.transactions.iter().filter(|t| t.date() == today).map(|t| t.amount().abs()).sum();
+            let used = value(&Money::new(posted, &acct.currency)).round_dp(self.base_currency.decimals);
+            if used + amount > cap {
+                return Err(breach(LimitPeriod::Day, cap, used, amount));
+            }
+        }
+        Ok(())
+    }
+
+    /// Check a conversion by `account` (`None` for a walk-in) taking
+    /// `legs[0]` out of one currency and paying `legs[1]` into another
+    /// against each currency's conversion limit, unless the limits are
+    /// overridden, and `legs[0]` against the account's limit profiles. The
+    /// day's totals come from the conversion log.
+    fn check_conversion_limits(&self, account: Option<&str>, legs: [&Money; 2]) -> Result<(), BankError> {
+        if self.limits_overridden {
+            return Ok(());
+        }
+        if let Some(acct) = account.and_then(|name| self.find_account(name)) {
+            self.check_limit_profiles(acct, LimitKind::Conversion, legs[0])?;
+        }
+        for amount in legs {
+            let Some(limit) = self.compliance.conversion_limits.get(&amount.currency) else {
+                continue;
+            };
+            let breach = |period, cap: Decimal, used: Decimal| {
+                BankError::ConversionLimit(Box::new(LimitBreach { currency: amount.currency.clone(), period, limit: cap, used, amount: amount.amount }))
+            };
+            if let Some(cap) = limit.per_transaction.filter(|cap| amount.amount > *cap) {
+                return Err(breach(LimitPeriod::Transaction, cap, Decimal::ZERO));
+            }
+            if let Some(cap) = limit.per_day {
+                let used = self.conversions_today(account, &amount.currency);
+                if used + amount.amount > cap {
+                    return Err(breach(LimitPeriod::Day, cap, used));
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// How much more of `currency` `account` may convert today under its
+    /// conversion limit, or `None` if nothing caps it.
+    fn conversion_headroom(&self, account: Option<&str>, currency: &str) -> Option<Decimal> {
+        if self.limits_overridden {
+            return None;
+        }
+        let limit = self.compliance.conversion_limits.get(currency)?;
+        let per_day = limit.per_day.map(|cap| (cap - self.conversions_today(account, currency)).max(Decimal::ZERO));
+        match (limit.per_transaction, per_day) {
+            (Some(a), Some(b)) => Some(a.min(b)),
+            (a, b) => a.or(b),
+        }
+    }
+
+    /// What `account` has converted from and into `currency` today, from
+    /// the conversion log.
+    fn conversions_today(&self, account: Option<&str>, currency: &str) -> Decimal {
+        let today = self.today();
+        let filter = ConversionFilter { start: Some(today), end: Some(today), ..ConversionFilter::default() };
+        self.forex
+            .conversion_history(&filter)
+            .into_iter()
+            .filter(|r| r.account.as_deref() == account)
+            .flat_map(|r| [&r.amount_in, &r.amount_out])
+            .filter(|m| m.currency == currency)
+            .map(|m| m.amount)
+            .sum()
+    }
+
+    /// Rename a currency in the catalog, keeping `base_currency` in step when
+    /// it is the base.
+    pub fn rename_currency(&mut self, code: &str, name: &str) -> Result<(), BankError> {
+        self.ensure_writable()?;
+        self.forex.rename_currency(code, name)?;
+        if self.base_currency.code == code {
+            self.base_currency.name = name.to_string();
+        }
+        Ok(())
+    }
+
+    /// Retire a currency from the catalog and return its last entry. Refused
+    /// for the base currency and while any account is denominated in it.
+    pub fn retire_currency(&mut self, code: &str) -> Result<Currency, BankError> {
+        self.ensure_writable()?;
+        let holders = self.accounts.iter().filter(|a| a.currency == code).count();
+        if holders > 0 && self.forex.get_base_rate() != code {
+            return Err(BankError::CurrencyInUse(code.to_string(), holders));
+        }
+        let retired = self.forex.retire_currency(code)?;
+        self.compliance.conversion_limits.remove(code);
+        Ok(retired)
+    }
+
+    /// Exchange `amount` into `to` at the `rate_type` rates, charging the
+    /// fee for its volume (see `Forex::exchange_at`). The gross payout and
+    /// the fee are each rounded with the bank's rounding policy, tracking
+    /// the residue, and `net` is the rounded gross less the rounded fee, and
+    /// the conversion is logged without an account. Fails when the
+    /// conversion is not possible or exceeds a conversion limit.
+    pub fn settle_conversion(&mut self, amount: &Money, to: &str, rate_type: RateType) -> Result<Conversion, BankError> {
+        self.ensure_writable()?;
+        let mut conversion = self.forex.exchange_at(amount, to, rate_type)?;
+        let dp = self.forex.decimals(to);
+        let net = Money::new(self.rounding.apply(&conversion.gross, dp).0.amount - self.rounding.apply(&conversion.fee, dp).0.amount, to);
+        self.check_conversion_limits(None, [&conversion.source, &net])?;
+        conversion.gross = self.round_and_track(&conversion.gross);
+        conversion.fee = self.round_and_track(&conversion.fee);
+        conversion.net = Money::new(conversion.gross.amount - conversion.fee.amount, to);
+        self.log_conversion(None, conversion.source.clone(), conversion.net.clone(), conversion.rate, conversion.fee.clone());
+        Ok(conversion)
+    }
+
+    /// Put `count` bills or coins of `value` in `code` into the till, e.g.
+    /// the opening float. Fails for an unknown currency.
+    pub fn load_till(&mut self, code: &str, value: Decimal, count: u64) -> Result<(), BankError> {
+        self.ensure_writable()?;
+        if self.forex.currency(code).is_none() {
+            return Err(ForexError::UnknownCurrency(code.to_string()).into());
+        }
+        if value <= Decimal::ZERO {
+            return Err(TillError::NonPositiveAmount.into());
+        }
+        self.till.load(code, value, count);
+        Ok(())
+    }
+
+    /// Deposit `amount` handed over in cash, like `post_transaction`, and
+    /// put it in the till counted out in its currency's denominations.
+    /// Returns the updated balance and the bills and coins taken in; fails
+    /// with `NotCash` if part of the amount is below the smallest
+    /// denomination, in which case nothing is posted.
+    pub fn cash_deposit(&mut self, name: &str, amount: Money, memo: &str, pin: Option<&str>) -> Result<(Money, CashBreakdown), BankError> {
+        self.ensure_writable()?;
+        let denominations = self.forex.currency(&amount.currency).map_or(Vec::new(), |c| c.denominations.clone());
+        let taken = Till::plan_cash_in(&amount, &denominations)?;
+        let balance = self.post_transaction(name, TransactionType::Deposit, amount, memo, pin)?;
+        self.till.put(&taken);
+        Ok((balance, taken))
+    }
+
+    /// Withdraw `amount` in cash, like `post_transaction`, paid out of the
+    /// till (see `Till::plan_payout`). Returns the updated balance and the
+    /// bills and coins paid; fails with `InsufficientCash` if the drawer
+    /// cannot make up the amount, in which case nothing is posted.
+    pub fn cash_withdrawal(&mut self, name: &str, amount: Money, memo: &str, pin: Option<&str>) -> Result<(Money, CashBreakdown), BankError> {
+        self.ensure_writable()?;
+        let paid = self.till.plan_payout(&amount)?;
+        let balance = self.post_transaction(name, TransactionType::Withdraw, amount, memo, pin)?;
+        self.till.take(&paid);
+        Ok((balance, paid))
+    }
+
+    /// A walk-in exchange: take `amount` in cash, settle it into `to` at
+    /// cash rates like `settle_conversion`, and pay the net out of the till. Returns the
+    /// conversion and the bills and coins paid. Fails, changing nothing, if
+    /// either side is not payable in cash or the drawer cannot make up the
+    /// payout.
+    pub fn cash_exchange(&mut self, amount: &Money, to: &str) -> Result<(Conversion, CashBreakdown), BankError> {
+        self.ensure_writable()?;
+        let denominations = self.forex.currency(&amount.currency).map_or(Vec::new(), |c| c.denominations.clone());
+        let taken = Till::plan_cash_in(amount, &denominations)?;
+        let quote = self.forex.exchange_at(amount, to, RateType::Cash)?;
+        let dp = self.forex.decimals(to);
+        let net = Money::new(self.rounding.apply(&quote.gross, dp).0.amount - self.rounding.apply(&quote.fee, dp).0.amount, to);
+        let mut drawer = self.till.clone();
+        drawer.put(&taken);
+        let paid = drawer.plan_payout(&net)?;
+        let conversion = self.settle_conversion(amount, to, RateType::Cash)?;
+        drawer.take(&paid);
+        self.till = drawer;
+        Ok((conversion, paid))
+    }
+
+    /// Balance the till at the end of the day: compare it with `counted`,
+    /// the bills and coins physically in the drawer, then take the count as
+    /// the drawer's contents from now on. Returns the comparison, whose
+    /// discrepancies are the day's overages and shortages.
+    pub fn balance_till(&mut self, counted: Till) -> Result<TillReport, BankError> {
+        self.ensure_writable()?;
+        let report = self.till.balance(&counted);
+        self.till = counted;
+        Ok(report)
+    }
+
+    /// Log an executed conversion, stamped now, in the Forex's history (see
+    /// `Forex::conversion_history`), with its volume in the base currency.
+    fn log_conversion(&mut self, account: Option<&str>, amount_in: Money, amount_out: Money, rate: Decimal, fee: Money) -> usize {
+        let volume = self
+            .forex
+            .convert(&amount_in, &self.base_currency.code)
+            .map_or(amount_in.amount, |m| m.amount);
+        let receipt = self.forex.next_receipt();
+        self.forex.record_conversion(ConversionRecord {
+            receipt,
+            timestamp: self.now(),
+            account: account.map(str::to_string),
+            amount_in,
+            amount_out,
+            rate,
+            fee,
+            volume,
+            teller: self.teller.clone(),
+            customer: self.session.clone(),
+        });
+        receipt
+    }
+
+    /// The exchange receipt numbered `number`, rebuilt from the conversion
+    /// log with the bank's name, locale, and time zone and the currencies'
+    /// decimal places. Fails if no logged conversion has that number.
+    pub fn exchange_receipt(&self, number: usize) -> Result<ExchangeReceipt, BankError> {
+        let record = self.forex.conversion_receipt(number).ok_or(BankError::ReceiptNotFound(number))?;
+        let mut receipt = ExchangeReceipt::new(BANK_NAME, record);
+        receipt.decimals = (self.forex.decimals(&record.amount_in.currency), self.forex.decimals(&record.amount_out.currency));
+        receipt.locale = self.locale;
+        receipt.time_zone = self.time_zone;
+        Ok(receipt)
+    }
+
+    /// Active accounts whose name or an alias matches `query`, ignoring
+    /// case: exactly, as a prefix, or within a few typos (see
+    /// `NameMatch::of`).
+    /// Best matches come first, then account order; each account is listed
+    /// once, by its best-matching name.
+    pub fn search_accounts(&self, query: &str) -> Vec<(&Account, NameMatch)> {
+        let mut hits: Vec<(&Account, NameMatch)> = self
+            .active_accounts()
+            .filter_map(|a| {
+                let best = std::iter::once(&a.name).chain(&a.aliases).filter_map(|name| NameMatch::of(query, name)).min()?;
+                Some((a, best))
+            })
+            .collect();
+        hits.sort_by_key(|(_, m)| *m);
+        hits
+    }
+
+    /// Search every account's transactions. Returns matching
+    /// (account, transaction) pairs in account order, then posting order.
+    pub fn search_transactions(&self, query: &TransactionQuery) -> Vec<(&Account, TransactionRef<'_>)> {
+        self.accounts
+            .iter()
+            .flat_map(|a| a.transactions.iter().map(move |t| (a, t)))
+            .filter(|(_, t)| query.matches(t))
+            .collect()
+    }
+
+    /// Write every account's full history to `dir` (created if missing) as
+    /// `{id}-{name}.csv` with the default `CsvOptions`, replacing existing
+    /// files. Returns the paths written, in account order.
+    pub fn export_all_csv(&self, dir: impl AsRef<Path>) -> io::Result<Vec<PathBuf>> {
+        self.export_all(dir, StatementFormat::Csv)
+    }
+
+    /// What moved through every active account in `month`: opening and
+    /// closing balances, deposits and withdrawals, interest credited, and
+    /// fees charged (see `AccountSummary`). A conversion fee is kept in
+    /// the currency paid out, so one on a conversion out of the account is
+    /// counted back at the conversion's rate, and any other at today's rate.
+    /// Amounts are rounded to the account's minor unit.
+    pub fn monthly_summary(&self, month: Month) -> MonthlySummary {
+        let (first, last) = (month.first_day(), month.last_day());
+        let accounts = self
+            .active_accounts()
+            .map(|acct| {
+                let mut summary = AccountSummary::new(&acct.name, acct.minor_unit_dp, acct.balance_on(first.add_days(-1)));
+                for tx in acct.transactions.iter().filter(|t| (first..=last).contains(&t.date())) {
+                    match tx.tx_type() {
+                        TransactionType::Deposit => summary.inflow.amount += tx.amount(),
+                        TransactionType::Withdraw => summary.outflow.amount += tx.amount(),
+                    }
+                    match (tx.tx_type(), tx.memo) {
+                        (TransactionType::Deposit, INTEREST_MEMO) => summary.interest.amount += tx.amount(),
+                        (TransactionType::Withdraw, CARRYING_CHARGE_MEMO | SERVICE_CHARGE_MEMO | MAINTAINING_BALANCE_MEMO) => summary.fees.amount += tx.amount(),
+                        _ => {}
+                    }
+                }
+                let filter = ConversionFilter { start: Some(first), end: Some(last), account: Some(acct.name.clone()), ..ConversionFilter::default() };
+                for record in self.forex.conversion_history(&filter) {
+                    let fee = if record.fee.currency == acct.currency {
+                        Some(record.fee.amount)
+                    } else if record.amount_in.currency == acct.currency {
+                        record.fee.amount.checked_div(record.rate)
+                    } else {
+                        self.forex.convert(&record.fee, &acct.currency).ok().map(|m| m.amount)
+                    };
+                    summary.fees.amount += fee.unwrap_or(Decimal::ZERO);
+                }
+                summary.fees.amount = summary.fees.amount.round_dp(acct.minor_unit_dp);
+                summary.closing = acct.balance_on(last);
+                summary
+            })
+            .collect();
+        MonthlySummary { month, accounts }
+    }
+
+    /// A printable statement of the named account for `start` through
+    /// `end`, naming its holder and formatted with the bank's locale, time
+    /// zone, and currency symbols. Fails if the account does not exist.
+    pub fn statement(&self, name: &str, start: Date, end: Date) -> Result<Statement, BankError> {
+        let acct = self
+            .accounts
+            .iter()
+            .find(|a| a.name == name)
+            .ok_or_else(|| BankError::AccountNotFound(name.to_string()))?;
+        Ok(self.statement_for(acct, start, end))
+    }
+
+    fn statement_for(&self, acct: &Account, start: Date, end: Date) -> Statement {
+        let mut statement = acct.statement(start, end, self.now());
+        if let Some(customer) = self.customers.iter().find(|c| c.owns(acct.id)) {
+            statement.holder = Some(customer.name.clone());
+            statement.contact = Some(customer.contact.clone());
+        }
+        statement.symbol = self.forex.symbol(&acct.currency);
+        statement.locale = self.locale;
+        statement.time_zone = self.time_zone;
+        statement
+    }
+
+    /// The year-end certificate of the interest the named account earned in
+    /// `year` and the tax withheld from it (see `Account::tax_certificate`),
+    /// with the owning customer's name, address, and identity document and
+    /// the bank's symbols, locale, and time zone.
+    pub fn tax_certificate(&self, name: &str, year: i32) -> Result<TaxCertificate, BankError> {
+        let acct = self
+            .accounts
+            .iter()
+            .find(|a| a.name == name)
+            .ok_or_else(|| BankError::AccountNotFound(name.to_string()))?;
+        let mut certificate = acct.tax_certificate(year, self.now());
+        if let Some(customer) = self.customers.iter().find(|c| c.owns(acct.id)) {
+            certificate.holder = Some(customer.name.clone());
+            certificate.address = Some(customer.address.clone()).filter(|a| !a.is_empty());
+            certificate.identification = customer.identification.as_ref().map(|i| format!("{} {}", i.id_type.name(), i.id_number));
+        }
+        certificate.symbol = self.forex.symbol(&acct.currency);
+        certificate.locale = self.locale;
+        certificate.time_zone = self.time_zone;
+        Ok(certificate)
+    }
+
+    /// Like `export_all_csv`, in any `StatementFormat`; files are named
+    /// `{id}-{name}.{ext}`. HTML statements cover each account's whole
+    /// history, as from `statement`.
+    pub fn export_all(&self, dir: impl AsRef<Path>, format: StatementFormat) -> io::Result<Vec<PathBuf>> {
+        let dir = dir.as_ref();
+        fs::create_dir_all(dir)?;
+        let query = TransactionQuery::default();
+        let mut written = Vec::with_capacity(self.accounts.len());
+        for acct in &self.accounts {
+            let path = dir.join(format!("{}.{}", file_stem(acct.id, &acct.name), format.extension()));
+            let mut out = io::BufWriter::new(fs::File::create(&path)?);
+            match format {
+                StatementFormat::Html => {
+                    let (start, end) = acct.statement_period(&query, self.today());
+                    self.statement_for(acct, start, end).write_html(&mut out)?;
+                }
+                _ => acct.export(&mut out, format, &query, self.now())?,
+            }
+            out.flush()?;
+            written.push(path);
+        }
+        Ok(written)
+    }
+
+    /// Write the whole bank to `dir` (created if missing) as four CSV files
+    /// for spreadsheets, diffs, and grading, returning their paths:
+    /// - `accounts.csv`: each account's id, name, currency, balance, rate in
+    ///   force today, customer id, aliases (`;`-separated), version, and
+    ///   the day it was archived;
+    /// - `transactions.csv`: every transaction in sequence order, with its
+    ///   account, UTC date and time, type, signed amount, currency, memo,
+    ///   and category;
+    /// - `rates.csv`: each catalog currency's transfer and cash rates in the
+    ///   base currency, decimals, rate decimals, symbol, and when and from
+    ///   what source its rate was last set;
+    /// - `settings.csv`: `setting,value` pairs for the base currency,
+    ///   interest, rounding, locale, time zone, compliance thresholds, fee tiers,
+    ///   spreads, conversion limits, and the next sequence number.
+    ///
+    /// All four are taken from the same state, and written under a
+    /// temporary name and renamed only once every file is complete, so a
+    /// failed export leaves the previous bundle in place.
+    pub fn export_bundle(&self, dir: impl AsRef<Path>) -> io::Result<Vec<PathBuf>> {
+        let dir = dir.as_ref();
+        fs::create_dir_all(dir)?;
+        let row = |cells: &[String]| cells.iter().map(|c| csv_field(c, ',')).collect::<Vec<_>>().join(",");
+        let optional = |d: Option<Decimal>| d.map(|d| d.to_string()).unwrap_or_default();
+        let mut files: Vec<(&str, Vec<String>)> = Vec::with_capacity(4);
+
+        let mut accounts = vec![String::from("id,name,currency,balance,annual_interest,customer,aliases,version,archived")];
+        for acct in &self.accounts {
+            let customer = self.customers.iter().find(|c| c.owns(acct.id));
+            accounts.push(row(&[
+                acct.id.to_string(),
+                acct.name.clone(),
+                acct.currency.clone(),
+                format!("{:.*}", acct.minor_unit_dp as usize, acct.get_balance().amount),
+                acct.rate_on(self.today()).to_string(),
+                customer.map(|c| c.id.to_string()).unwrap_or_default(),
+                acct.aliases.join(";"),
+                acct.version.to_string(),
+                acct.archived.map(|d| d.to_string()).unwrap_or_default(),
+            ]));
+        }
+        files.push(("accounts.csv", accounts));
+
+        let mut postings: Vec<_> = self.accounts.iter().flat_map(|a| a.transactions.iter().map(move |t| (a, t))).collect();
+        postings.sort_by_key(|(_, t)| t.sequence);
+        let mut transactions = vec![String::from("sequence,account,date,time,type,amount,currency,memo,category")];
+        for (acct, tx) in postings {
+            transactions.push(row(&[
+                tx.sequence.to_string(),
+                acct.name.clone(),
+                tx.date().to_string(),
+                format_utc_time(tx.timestamp),
+                format!("{:?}", tx.tx_type()).to_lowercase(),
+                format!("{:.*}", acct.minor_unit_dp as usize, tx.amount()),
+                acct.currency.clone(),
+                tx.memo.to_string(),
+                tx.category.unwrap_or_default().to_string(),
+            ]));
+        }
+        files.push(("transactions.csv", transactions));
+
+        let mut rates = vec![String::from("code,name,rate,cash_rate,decimals,rate_decimals,symbol,updated,source")];
+        for c in self.forex.currencies_detailed() {
+            rates.push(row(&[
+                c.code.clone(),
+                c.name.clone(),
+                c.rate.to_string(),
+                optional(c.cash_rate),
+                c.decimals.to_string(),
+                c.rate_decimals.to_string(),
+                c.symbol.clone(),
+                c.updated.map(format_timestamp).unwrap_or_default(),
+                c.source.as_ref().map(RateSource::to_string).unwrap_or_default(),
+            ]));
+        }
+        files.push(("rates.csv", rates));
+
+        let compliance = &self.compliance;
+        let mut settings: Vec<(String, String)> = vec![
+            ("base_currency".into(), self.base_currency.code.clone()),
+            ("annual_interest".into(), self.annual_interest.to_string()),
+            ("withholding_tax_rate".into(), self.withholding_tax_rate.to_string()),
+            ("rounding".into(), format!("{:?}", self.rounding.strategy)),
+            ("locale".into(), self.locale.tag().into()),
+            ("time_zone".into(), self.time_zone.tag()),
+            ("roll_convention".into(), self.calendar.convention.name().into()),
+            ("weekend".into(), self.calendar.weekend.iter().map(Weekday::short_name).collect::<Vec<_>>().join(",")),
+            ("holidays".into(), self.calendar.holidays.iter().map(Holiday::to_string).collect::<Vec<_>>().join(",")),
+            ("large_transaction_threshold".into(), optional(compliance.large_threshold)),
+            ("require_large_confirmation".into(), compliance.require_confirmation.to_string()),
+            ("confirmation_threshold".into(), optional(compliance.confirm_threshold)),
+            ("approval_threshold".into(), optional(compliance.approval_threshold)),
+            ("rate_change_limit".into(), optional(compliance.rate_change_limit)),
+            ("allow_negative_rates".into(), compliance.allow_negative_rates.to_string()),
+            ("unverified_limit".into(), optional(compliance.unverified_limit)),
+            ("unverified_daily_limit".into(), optional(compliance.unverified_daily_limit)),
+        ];
+        settings.extend(self.forex.fee_schedule().tiers().iter().map(|t| (format!("fee_tier:{}", t.from), t.rate.to_string())));
+        settings.extend(self.forex.pair_spreads().iter().map(|s| (format!("spread:{}/{}", s.pair.0, s.pair.1), s.rate.to_string())));
+        settings.extend(compliance.conversion_limits.iter().map(|(code, limit)| {
+            (format!("conversion_limit:{}", code), format!("{}:{}", optional(limit.per_transaction), optional(limit.per_day)))
+        }));
+        settings.extend(compliance.limit_profiles.iter().map(|(target, p)| {
+            (format!("limit_profile:{}", target), format!("{}:{}:{}", optional(p.max_deposit), optional(p.max_withdrawal), optional(p.max_daily_conversion)))
+        }));
+        settings.push(("next_sequence".into(), self.next_sequence().to_string()));
+        let mut lines = vec![String::from("setting,value")];
+        lines.extend(settings.into_iter().map(|(key, value)| row(&[key, value])));
+        files.push(("settings.csv", lines));
+
+        let mut staged = Vec::with_capacity(files.len());
+        for (name, lines) in &files {
+            let tmp = dir.join(format!("{}.tmp", name));
+            let mut out = io::BufWriter::new(fs::File::create(&tmp)?);
+            for line in lines {
+                writeln!(out, "{}", line)?;
+            }
+            out.flush()?;
+            staged.push((tmp, dir.join(name)));
+        }
+        let mut written = Vec::with_capacity(staged.len());
+        for (tmp, path) in staged {
+            fs::rename(&tmp, &path)?;
+            written.push(path);
+        }
+        Ok(written)
+    }
+
+    /// Flagged transactions that have not been reviewed yet.
+    pub fn pending_reviews(&self) -> Vec<&FlaggedTransaction> {
+        self.flagged.iter().filter(|f| !f.reviewed).collect()
+    }
+
+    /// Mark a flagged transaction as reviewed. Fails if no entry has the
+    /// given id.
+    pub fn mark_reviewed(&mut self, id: usize) -> Result<(), BankError> {
+        self.ensure_writable()?;
+        let entry = self
+            .flagged
+            .iter_mut()
+            .find(|f| f.id == id)
+            .ok_or(BankError::FlagNotFound(id))?;
+        entry.reviewed = true;
+        Ok(())
+    }
+
+    /// Withdrawals and transfers still pending, oldest first: all of them,
+    /// or those paying from or into `account`.
+    pub fn pending_transactions(&self, account: Option<&str>) -> Vec<&PendingTransaction> {
+        self.pending.iter().filter(|p| p.is_pending() && account.is_none_or(|a| p.held.involves(a))).collect()
+    }
+
+    /// The named account's balance less its active holds (see
+    /// `Account::available_balance`) and what its pending transactions hold
+    /// back (see `PendingTransaction::hold_on`): what a withdrawal or a
+    /// transfer out can still take. Money pending on its way in is not
+    /// counted until it posts.
+    pub fn available_balance(&self, name: &str) -> Result<Money, BankError> {
+        let index = self.account_index(name).ok_or_else(|| BankError::AccountNotFound(name.to_string()))?;
+        Ok(self.available_at(index))
+    }
+
+    /// Place an authorization hold of `amount` on the named account for
+    /// `reason` through `expiry`, `DEFAULT_HOLD_DAYS` from today when
+    /// `None` (see `Account::place_hold`). It must fit within the available
+    /// balance, what pending transactions hold back included. Protected
+    /// accounts require a matching `pin`.
+    pub fn place_hold(&mut self, name: &str, amount: Money, reason: &str, expiry: Option<Date>, pin: Option<&str>) -> Result<Hold, BankError> {
+        self.ensure_writable()?;
+        let index = self.authorized_account(name, pin)?;
+        self.accounts[index].get_balance().same_currency(&amount).map_err(AccountError::from)?;
+        self.ensure_available(index, amount.amount)?;
+        let today = self.today();
+        let id = self.accounts[index].place_hold(amount, reason, expiry.unwrap_or(today.add_days(DEFAULT_HOLD_DAYS)), today)?;
+        Ok(self.find_hold(index, id)?.clone())
+    }
+
+    /// Release hold `id` on the named account, making what it kept back
+    /// available again; nothing is posted. Protected accounts require a
+    /// matching `pin`.
+    pub fn release_hold(&mut self, name: &str, id: usize, pin: Option<&str>) -> Result<Hold, BankError> {
+        self.ensure_writable()?;
+        let index = self.authorized_account(name, pin)?;
+        self.find_hold(index, id)?;
+        Ok(self.accounts[index].release_hold(id)?)
+    }
+
+    /// Capture hold `id` on the named account: withdraw `amount`, or the
+    /// whole hold when `None`, under the hold's reason, and end the hold,
+    /// releasing any rest. Returns the hold and the updated balance. Fails
+    /// with `CaptureExceedsHold` above the hold, and otherwise as a
+    /// withdrawal does, keeping the hold. Protected accounts require a
+    /// matching `pin`.
+    pub fn capture_hold(&mut self, name: &str, id: usize, amount: Option<Decimal>, pin: Option<&str>) -> Result<(Hold, Money), BankError> {
+        self.ensure_writable()?;
+        let index = self.authorized_account(name, pin)?;
+        let held = self.find_hold(index, id)?.amount.clone();
+        let amount = Money::new(amount.unwrap_or(held.amount), &held.currency);
+        if amount.amount > held.amount {
+            return Err(AccountError::from(HoldError::CaptureExceedsHold { id, held: held.amount }).into());
+        }
+        let hold = self.accounts[index].release_hold(id)?;
+        match self.post_at(index, TransactionType::Withdraw, amount, &hold.reason) {
+            Ok(balance) => Ok((hold, balance)),
+            Err(e) => {
+                let acct = &mut self.accounts[index];
+                acct.holds.push(hold);
+                acct.holds.sort_by_key(|h| h.id);
+                Err(e)
+            }
+        }
+    }
+
+    /// The index of the open account called `name` once `pin` is accepted.
+    fn authorized_account(&self, name: &str, pin: Option<&str>) -> Result<usize, BankError> {
+        let index = self.account_index(name).ok_or_else(|| BankError::AccountNotFound(name.to_string()))?;
+        self.ensure_open(index)?;
+        if !self.accounts[index].verify_pin(pin) {
+            return Err(AccountError::InvalidPin.into());
+        }
+        Ok(index)
+    }
+
+    /// Deposit a cheque for `amount`, numbered `number`, into the named
+    /// account: credit the ledger at once, under the memo "Cheque" and its
+    /// number, but keep it back from the available balance until it clears
+    /// `cheque_clearing_days` business days later on the bank's calendar,
+    /// as the bank's clock tells the days. Until then it can be returned
+    /// (see `return_cheque`). Fails if the number is blank, and otherwise
+    /// as a deposit does. Protected accounts require a matching `pin`.
+    pub fn deposit_cheque(&mut self, name: &str, amount: Money, number: &str, pin: Option<&str>) -> Result<Cheque, BankError> {
+        self.ensure_writable()?;
+        let index = self.authorized_account(name, pin)?;
+        let number = number.trim();
+        if number.is_empty() {
+            return Err(AccountError::from(ChequeError::BlankNumber).into());
+        }
+        self.check_account_limits(&self.accounts[index], TransactionType::Deposit, &amount)?;
+        self.post_at(index, TransactionType::Deposit, amount, &format!("Cheque {}", number))?;
+        let today = self.today();
+        let clears = self.calendar.add_business_days(today, self.cheque_clearing_days);
+        let acct = &mut self.accounts[index];
+        let (sequence, posted) = acct.transactions.last().map_or((0, Decimal::ZERO), |t| (t.sequence, t.amount()));
+        let id = acct.cheques.iter().map(|c| c.id).max().unwrap_or(0) + 1;
+        let cheque = Cheque { id, number: number.to_string(), amount: Money::new(posted, &acct.currency), sequence, deposited: today, clears, returned: None };
+        acct.cheques.push(cheque.clone());
+        Ok(cheque)
+    }
+
+    /// Return cheque `id` on the named account unpaid, e.g. bounced for
+    /// insufficient funds, while it is still clearing: its credit is
+    /// reversed (see `Account::return_cheque`). Returns the cheque and the
+    /// updated balance. Fails with `ChequeError::Cleared` once it has
+    /// cleared.
+    pub fn return_cheque(&mut self, name: &str, id: usize, reason: &str) -> Result<(Cheque, Money), BankError> {
+        self.ensure_writable()?;
+        let index = self.account_index(name).ok_or_else(|| BankError::AccountNotFound(name.to_string()))?;
+        self.ensure_open(index)?;
+        let (today, now) = (self.today(), self.now());
+        let acct = &mut self.accounts[index];
+        let held = acct.get_balance().amount;
+        let cheque = acct.return_cheque(id, reason, today, now)?;
+        let balance = acct.get_balance();
+        let memo = acct.transactions.last().map(|t| t.memo.to_string()).unwrap_or_default();
+        let name = acct.name.clone();
+        self.sequence_last(index);
+        self.track_position(index, TransactionType::Withdraw, &cheque.amount, held);
+        self.emit(BankEvent::TransactionPosted { account: name, tx_type: TransactionType::Withdraw, amount: cheque.amount.clone(), balance: balance.clone(), memo });
+        Ok((cheque, balance))
+    }
+
+    /// Hold `id` on the account at `index`, if it is still active today.
+    fn find_hold(&self, index: usize, id: usize) -> Result<&Hold, BankError> {
+        let today = self.today();
+        self.accounts[index].active_holds(today).find(|h| h.id == id).ok_or_else(|| AccountError::from(HoldError::NotFound(id)).into())
+    }
+
+    /// Post the withdrawal or transfer held as `id`, moving it from
+    /// pending to posted, once `passphrase` has unlocked the admin role. It
+    /// posts as it would have when requested, without the account's PIN
+    /// again, so it can still fail, e.g. on a balance spent since; it then
+    /// stays pending, to be approved later or rejected. Fails with
+    /// `InvalidPassphrase`, `PendingNotFound`, or `PendingError::Decided`
+    /// before posting anything.
+    pub fn approve(&mut self, id: usize, passphrase: &str) -> Result<&PendingTransaction, BankError> {
+        self.ensure_writable()?;
+        let index = self.find_pending(id, passphrase)?;
+        // Posted first, so its own hold does not stand in its way.
+        self.pending[index].post()?;
+        if let Err(e) = self.post_held(self.pending[index].held.clone()) {
+            self.pending[index].state = TransactionState::Pending;
+            return Err(e);
+        }
+        Ok(&self.pending[index])
+    }
+
+    /// Reject the withdrawal or transfer held as `id` for `reason`, once
+    /// `passphrase` has unlocked the admin role; nothing is posted, and
+    /// what it held back is available again. Fails with
+    /// `PendingError::BlankReason` without a reason, and otherwise as
+    /// `approve` does.
+    pub fn reject(&mut self, id: usize, passphrase: &str, reason: &str) -> Result<&PendingTransaction, BankError> {
+        self.ensure_writable()?;
+        let index = self.find_pending(id, passphrase)?;
+        self.pending[index].reject(reason)?;
+        let account = self.pending[index].held.account().to_string();
+        self.emit(BankEvent::ApprovalRejected { id, account, reason: reason.trim().to_string() });
+        Ok(&self.pending[index])
+    }
+
+    /// The position of held transaction `id` once `passphrase` has
+    /// unlocked the admin role.
+    fn find_pending(&self, id: usize, passphrase: &str) -> Result<usize, BankError> {
+        if !self.verify_admin(passphrase) {
+            return Err(BankError::InvalidPassphrase);
+        }
+        self.pending.iter().position(|p| p.id == id).ok_or(BankError::PendingNotFound(id))
+    }
+
+    /// Book `held` as it was asked for.
+    fn post_held(&mut self, held: HeldTransaction) -> Result<(), BankError> {
+        match held {
+            HeldTransaction::Withdrawal { account, amount, memo, category } => {
+                let at = self
+                    .accounts
+                    .iter()
+                    .position(|a| a.name == account)
+                    .ok_or_else(|| BankError::AccountNotFound(account.clone()))?;
+                self.check_account_limits(&self.accounts[at], TransactionType::Withdraw, &amount)?;
+                self.post_at(at, TransactionType::Withdraw, amount, &memo)?;
+                if let Some(category) = category {
+                    let acct = &mut self.accounts[at];
+                    let last = acct.transactions.len().saturating_sub(1);
+                    acct.transactions.set_category(last, Some(&category));
+                }
+            }
+            HeldTransaction::Transfer { from, to, amount } => {
+                let (src, dst) = self.transfer_accounts(&from, &to)?;
+                self.post_transfer(src, dst, amount, |_, _| (format!("Transfer to {}", to), format!("Transfer from {}", from)))?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Check the ledger's invariants, e.g. after loading a snapshot or
+    /// importing histories, and report every violation rather than the
+    /// first:
+    /// - each account's ledger adds up to its balance and never goes below
+    ///   zero (see `Account::verify`), and account names are unique;
+    /// - every alias finds the account it belongs to;
+    /// - every transfer leg has its other leg, same-currency legs for the
+    ///   same amount (see `transfer_violations`);
+    /// - every review-queue entry matches a posting of its account;
+    /// - customers, loans, orders, and forwards name existing accounts.
+    pub fn verify(&self) -> IntegrityReport {
+        let mut violations = Vec::new();
+        for (i, acct) in self.accounts.iter().enumerate() {
+            if self.accounts[..i].iter().any(|a| a.name == acct.name) {
+                violations.push(Violation::DuplicateAccount(acct.name.clone()));
+            }
+            for alias in &acct.aliases {
+                if self.account_index(alias) != Some(i) || acct.aliases.iter().filter(|a| *a == alias).count() > 1 {
+                    violations.push(Violation::ShadowedAlias { account: acct.name.clone(), alias: alias.clone() });
+                }
+            }
+            violations.extend(acct.verify());
+        }
+        violations.extend(self.transfer_violations());
+        for flag in &self.flagged {
+            let posted = self.accounts.iter().find(|a| a.name == flag.account).is_some_and(|a| {
+                a.currency == flag.amount.currency && a.transactions.iter().any(|t| t.tx_type() == flag.tx_type && t.amount() == flag.amount.amount)
+            });
+            if !posted {
+                violations.push(Violation::UnmatchedFlag { id: flag.id, account: flag.account.clone(), tx_type: flag.tx_type, amount: flag.amount.clone() });
+            }
+        }
+        for customer in &self.customers {
+            for id in customer.account_ids.iter().filter(|id| !self.accounts.iter().any(|a| a.id == **id)) {
+                violations.push(Violation::DanglingReference { owner: format!("customer {}", customer.id), account: format!("ID {}", id) });
+            }
+        }
+        let mut references: Vec<(String, &str)> = Vec::new();
+        references.extend(self.loans.iter().map(|l| (format!("loan {}", l.id), l.account.as_str())));
+        for order in &self.standing_orders {
+            references.extend([(format!("standing order {}", order.id), order.from.as_str()), (format!("standing order {}", order.id), order.to.as_str())]);
+        }
+        for sweep in &self.sweeps {
+            references.extend([(format!("sweep {}", sweep.id), sweep.from.as_str()), (format!("sweep {}", sweep.id), sweep.to.as_str())]);
+        }
+        references.extend(self.forwards.iter().map(|f| (format!("forward {}", f.id), f.account.as_str())));
+        references.extend(self.limit_orders.iter().map(|o| (format!("limit order {}", o.id), o.account.as_str())));
+        references.extend(self.accounts.iter().filter_map(|a| Some((format!("interest payout of {}", a.name), a.interest_payout.as_deref()?))));
+        for (owner, account) in references {
+            if !self.accounts.iter().any(|a| a.name == account) {
+                violations.push(Violation::DanglingReference { owner, account: account.to_string() });
+            }
+        }
+        IntegrityReport {
+            accounts: self.accounts.len(),
+            transactions: self.accounts.iter().map(|a| a.transactions.len()).sum(),
+            violations,
+        }
+    }
+
+    /// Transfer legs without their other leg, and same-currency transfers
+    /// whose legs differ. Legs are known by the memos `post_transfer` gives
+    /// them (see `transfer_leg`); between the same two accounts, the n-th
+    /// debit pairs with the n-th credit.
+    fn transfer_violations(&self) -> Vec<Violation> {
+        type Legs = (Vec<(usize, usize)>, Vec<(usize, usize)>);
+        let mut transfers: BTreeMap<(String, String, Option<usize>), Legs> = BTreeMap::new();
+        for (a, acct) in self.accounts.iter().enumerate() {
+            for (i, tx) in acct.transactions.iter().enumerate() {
+                match transfer_leg(tx.memo) {
+                    Some((to, order, true)) if tx.units < 0 => transfers.entry((acct.name.clone(), to, order)).or_default().0.push((a, i)),
+                    Some((from, order, false)) if tx.units > 0 => transfers.entry((from, acct.name.clone(), order)).or_default().1.push((a, i)),
+                    _ => {}
+                }
+            }
+        }
+        let leg = |(a, i): (usize, usize)| {
+            let tx = self.accounts[a].transactions.get(i).expect("leg positions come from the log");
+            (Money::new(tx.amount(), &self.accounts[a].currency), tx.memo.to_string())
+        };
+        let mut violations = Vec::new();
+        for ((from, to, _), (debits, credits)) in transfers {
+            for (debit, credit) in debits.iter().zip(&credits) {
+                let ((debited, _), (credited, _)) = (leg(*debit), leg(*credit));
+                if debited.currency == credited.currency && debited.amount != credited.amount {
+                    violations.push(Violation::UnbalancedTransfer { from: from.clone(), to: to.clone(), debited, credited });
+                }
+            }
+            for &(a, index) in debits.iter().skip(credits.len()).chain(credits.iter().skip(debits.len())) {
+                violations.push(Violation::UnmatchedLeg { account: self.accounts[a].name.clone(), index, memo: leg((a, index)).1 });
+            }
+        }
+        violations
+    }
+
+    /// Save a full in-memory snapshot of the bank under `label`, replacing
+    /// any earlier checkpoint with the same label.
+    pub fn checkpoint(&mut self, label: &str) {
+        let mut snapshot = self.clone();
+        snapshot.checkpoints.clear();
+        self.checkpoints.retain(|(l, _)| l != label);
+        self.checkpoints.push((label.to_string(), snapshot));
+    }
+
+    /// Roll the whole bank back to the checkpoint named `label`. Checkpoints
+    /// themselves are kept so the same label can be restored again, the
+    /// clock keeps its time, and the teller and customer session stay.
+    /// Fails if no such checkpoint exists.
+    pub fn restore(&mut self, label: &str) -> Result<(), BankError> {
+        self.ensure_writable()?;
+        let snapshot = self
+            .checkpoints
+            .iter()
+            .find(|(l, _)| l == label)
+            .map(|(_, b)| b.clone())
+            .ok_or_else(|| BankError::CheckpointNotFound(label.to_string()))?;
+        let checkpoints = std::mem::take(&mut self.checkpoints);
+        let events = std::mem::take(&mut self.events);
+        let statements = std::mem::take(&mut self.statements);
+        let next_sequence = self.next_sequence;
+        let (clock, simulation) = (self.clock(), self.simulation_clock());
+        let (teller, session) = (self.teller.take(), self.session.take());
+        *self = snapshot;
+        (self.teller, self.session) = (teller, session);
+        self.next_sequence = next_sequence;
+        self.set_clock(clock);
+        self.simulation = simulation;
+        self.checkpoints = checkpoints;
+        self.events = events;
+        self.statements = statements;
+        Ok(())
+    }
+
+    /// Labels of the saved checkpoints, oldest first.
+    pub fn checkpoint_labels(&self) -> Vec<&str> {
+        self.checkpoints.iter().map(|(l, _)| l.as_str()).collect()
+    }
+
+    /// Open the bank read-only, or writable again. While it is read-only,
+    /// every fallible operation that would change it fails with
+    /// `BankError::ReadOnly`, the scheduled jobs (`end_of_day` and the
+    /// standing orders, forwards, and limit orders it drives) do nothing,
+    /// and `persist::save` refuses it. `create_customer` cannot fail, so
+    /// front ends must not offer it. The setting is not part of a snapshot.
+    pub fn set_read_only(&mut self, read_only: bool) {
+        self.read_only = read_only;
+    }
+
+    pub fn is_read_only(&self) -> bool {
+        self.read_only
+    }
+
+    /// `Err(BankError::ReadOnly)` if the bank is read-only.
+    pub fn ensure_writable(&self) -> Result<(), BankError> {
+        match self.read_only {
+            true => Err(BankError::ReadOnly),
+            false => Ok(()),
+        }
+    }
+
+    /// Remove and return the events recorded since the last call, oldest
+    /// first. At most `EVENT_LIMIT` are kept between calls.
+    pub fn take_events(&mut self) -> Vec<BankEvent> {
+        std::mem::take(&mut self.events).into()
+    }
+
+    /// Remove and return the month-end statements issued by `end_of_day`
+    /// since the last call, oldest first.
+    pub fn take_statements(&mut self) -> Vec<Statement> {
+        std::mem::take(&mut self.statements)
+    }
+
+    fn emit(&mut self, event: BankEvent) {
+        if self.events.len() == EVENT_LIMIT {
+            self.events.pop_front();
+        }
+        self.events.push_back(event);
+    }
+
+    /// Returns true when withdrawing or transferring `amount`, valued in
+    /// the base currency, needs an admin's approval.
+    fn needs_approval(&self, amount: &Money) -> bool {
+        let base_amount = self.forex.convert(amount, &self.base_currency.code).map_or(amount.amount, |m| m.amount);
+        self.compliance.needs_approval(base_amount)
+    }
+
+    /// Queue `held` for an admin's approval, returning the
+    /// `AwaitingApproval` error that reports it, or `InsufficientFunds`
+    /// when the paying account's available balance cannot cover it.
+    fn hold(&mut self, held: HeldTransaction) -> BankError {
+        let (account, amount) = (held.account().to_string(), held.amount().clone());
+        if let Some(index) = self.account_index(&account) {
+            let code = &self.accounts[index].currency;
+            let debit = self.forex.convert(&amount, code).map_or(amount.amount, |m| m.amount);
+            if let Err(e) = self.ensure_available(index, debit) {
+                return e;
+            }
+        }
+        let id = self.pending.len() + 1;
+        self.pending.push(PendingTransaction::new(id, self.today(), held));
+        self.emit(BankEvent::ApprovalRequested { id, account, amount });
+        BankError::AwaitingApproval(id)
+    }
+
+    /// The available balance of the account at `index` today less what its
+    /// pending transactions hold back, each valued in its currency.
+    fn available_at(&self, index: usize) -> Money {
+        let acct = &self.accounts[index];
+        let held: Decimal = self
+            .pending
+            .iter()
+            .filter_map(|p| p.hold_on(&acct.name))
+            .map(|m| self.forex.convert(m, &acct.currency).map_or(m.amount, |c| c.amount))
+            .sum();
+        Money::new(acct.available_balance(self.today()).amount - held, &acct.currency)
+    }
+
+    /// Fails with `InsufficientFunds` when taking `amount`, in its currency,
+    /// from the account at `index` would reach into its available balance.
+    fn ensure_available(&self, index: usize, amount: Decimal) -> Result<(), BankError> {
+        let available = self.available_at(index);
+        if amount > available.amount {
+            let requested = Money::new(amount, &available.currency);
+            return Err(AccountError::InsufficientFunds { balance: available, requested }.into());
+        }
+        Ok(())
+    }
+
+    /// Queue a large transaction for compliance review.
+    fn flag(&mut self, account: &str, tx_type: TransactionType, amount: Money) {
+        let id = self.flagged.len() + 1;
+        self.flagged.push(FlaggedTransaction { id, account: account.to_string(), tx_type, amount: amount.clone(), reviewed: false });
+        self.emit(BankEvent::LargeTransactionFlagged { id, account: account.to_string(), amount });
+    }
+}
+
+/// The other account named by a transfer leg's memo, the standing order
+/// that made the transfer if any, and whether this is the outgoing leg.
+/// `None` for memos `post_transfer` does not write.
+fn transfer_leg(memo: &str) -> Option<(String, Option<usize>, bool)> {
+    if let Some((to, _)) = memo.strip_prefix("Exchange to ").and_then(|rest| rest.rsplit_once(" at ")) {
+        return Some((to.to_string(), None, true));
+    }
+    if let Some((from, _)) = memo.strip_prefix("Exchange from ").and_then(|rest| rest.rsplit_once(" at ")) {
+        return Some((from.to_string(), None, false));
+    }
+    if let Some(to) = memo.strip_prefix("Transfer to ") {
+        return Some((to.to_string(), None, true));
+    }
+    if let Some(from) = memo.strip_prefix("Transfer from ") {
+        return Some((from.to_string(), None, false));
+    }
+    if let Some((_, rest)) = memo.strip_prefix("Sweep ").and_then(|rest| rest.split_once(' ')) {
+        return match rest.strip_prefix("to ") {
+            Some(to) => Some((to.to_string(), None, true)),
+            None => rest.strip_prefix("from ").map(|from| (from.to_string(), None, false)),
+        };
+    }
+    let (id, rest) = memo.strip_prefix("Standing order ")?.split_once(' ')?;
+    let order = Some(id.parse().ok()?);
+    match rest.strip_prefix("to ") {
+        Some(to) => Some((to.to_string(), order, true)),
+        None => rest.strip_prefix("from ").map(|from| (from.to_string(), order, false)),
     }
 }
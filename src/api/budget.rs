@@ -0,0 +1,75 @@
+use std::fmt;
+
+use crate::api::decimal::Decimal;
+use crate::api::money::Money;
+
+/// Errors raised when a budget envelope is set or looked up.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum BudgetError {
+    /// The monthly limit rounds to zero or below in the account's minor unit.
+    NonPositiveLimit,
+    /// The account already has an envelope for this category.
+    Duplicate(String),
+    NotFound(String),
+}
+
+impl fmt::Display for BudgetError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            BudgetError::NonPositiveLimit => write!(f, "budget limit must be greater than zero"),
+            BudgetError::Duplicate(category) => write!(f, "a budget for {} already exists", category),
+            BudgetError::NotFound(category) => write!(f, "no budget for {}", category),
+        }
+    }
+}
+
+impl std::error::Error for BudgetError {}
+
+/// A monthly spending limit for withdrawals filed under `category`.
+/// `limit` is in the account's currency and starts afresh each calendar
+/// month (UTC).
+#[derive(Debug, Clone)]
+pub struct Envelope {
+    pub category: String,
+    pub limit: Money,
+}
+
+impl Envelope {
+    pub fn new(category: &str, limit: Money) -> Self {
+        Self { category: category.to_string(), limit }
+    }
+}
+
+/// Where an envelope stands in one month.
+/// - `spent`: withdrawals filed under the category that month, less their
+///   reversals.
+/// - `remaining`: what is left of the limit, zero once it is used up.
+/// - `overspent`: how far `spent` is past the limit, zero while within it.
+#[derive(Debug, Clone)]
+pub struct EnvelopeStatus {
+    pub category: String,
+    pub limit: Money,
+    pub spent: Money,
+    pub remaining: Money,
+    pub overspent: Money,
+}
+
+impl EnvelopeStatus {
+    /// The status of `envelope` after `spent` has gone out of it.
+    pub fn new(envelope: &Envelope, spent: Decimal) -> Self {
+        let money = |amount: Decimal| Money::new(amount, &envelope.limit.currency);
+        let spent = spent.max(Decimal::ZERO);
+        let limit = envelope.limit.amount;
+        Self {
+            category: envelope.category.clone(),
+            limit: envelope.limit.clone(),
+            spent: money(spent),
+            remaining: money((limit - spent).max(Decimal::ZERO)),
+            overspent: money((spent - limit).max(Decimal::ZERO)),
+        }
+    }
+
+    pub fn is_overspent(&self) -> bool {
+        self.overspent.amount > Decimal::ZERO
+    }
+}
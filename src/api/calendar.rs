@@ -0,0 +1,167 @@
+use std::fmt;
+
+use crate::api::date::{Date, Weekday};
+use crate::api::format::Locale;
+
+/// Furthest a date is moved looking for a business day; a calendar with no
+/// business day within a year leaves dates where they are.
+const MAX_ROLL_DAYS: i64 = 366;
+
+/// How a scheduled date that is not a business day is moved.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum RollConvention {
+    /// Keep the date as scheduled.
+    Unadjusted,
+    /// Move to the next business day.
+    #[default]
+    Following,
+    /// Move to the next business day unless that is in the next month, in
+    /// which case move to the previous one.
+    ModifiedFollowing,
+    /// Move to the previous business day.
+    Preceding,
+}
+
+impl RollConvention {
+    /// Parse "unadjusted" (or "none"), "following", "modified-following",
+    /// or "preceding" (any case, `_` for `-`).
+    pub fn parse(s: &str) -> Option<Self> {
+        match s.trim().to_lowercase().replace('_', "-").as_str() {
+            "unadjusted" | "none" => Some(RollConvention::Unadjusted),
+            "following" => Some(RollConvention::Following),
+            "modified-following" => Some(RollConvention::ModifiedFollowing),
+            "preceding" => Some(RollConvention::Preceding),
+            _ => None,
+        }
+    }
+
+    /// Lowercase name, as accepted by `parse`.
+    pub fn name(&self) -> &'static str {
+        match self {
+            RollConvention::Unadjusted => "unadjusted",
+            RollConvention::Following => "following",
+            RollConvention::ModifiedFollowing => "modified-following",
+            RollConvention::Preceding => "preceding",
+        }
+    }
+}
+
+/// A day no business is done: the same month and day every year, or one
+/// particular date (e.g. a holiday whose date moves, like Good Friday).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Holiday {
+    Annual { month: u32, day: u32 },
+    Once(Date),
+}
+
+impl Holiday {
+    /// Parse "MM-DD" (every year) or "YYYY-MM-DD" (that date only).
+    pub fn parse(s: &str) -> Option<Self> {
+        let s = s.trim();
+        match s.matches('-').count() {
+            1 => {
+                let (month, day) = s.split_once('-')?;
+                // Checked against a leap year so 02-29 is accepted.
+                let date = Date::new(2000, month.parse().ok()?, day.parse().ok()?)?;
+                Some(Holiday::Annual { month: date.month, day: date.day })
+            }
+            _ => Date::parse(s).map(Holiday::Once),
+        }
+    }
+
+    /// Whether the holiday falls on `date`.
+    pub fn falls_on(&self, date: Date) -> bool {
+        match *self {
+            Holiday::Annual { month, day } => date.month == month && date.day == day,
+            Holiday::Once(once) => once == date,
+        }
+    }
+}
+
+impl fmt::Display for Holiday {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Holiday::Annual { month, day } => write!(f, "{:02}-{:02}", month, day),
+            Holiday::Once(date) => write!(f, "{}", date),
+        }
+    }
+}
+
+/// Which days are business days, and how scheduled dates that are not
+/// are rolled (see `roll`). The bank settles forwards and makes
+/// standing-order transfers on the rolled date; the schedule itself keeps
+/// its unadjusted dates, so a roll never shifts later dates.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BusinessCalendar {
+    pub weekend: Vec<Weekday>,
+    pub holidays: Vec<Holiday>,
+    pub convention: RollConvention,
+}
+
+impl Default for BusinessCalendar {
+    fn default() -> Self {
+        Self::for_locale(Locale::default())
+    }
+}
+
+impl BusinessCalendar {
+    /// Saturday-Sunday weekends and the fixed-date public holidays of the
+    /// locale's country, rolled to the following business day. Holidays
+    /// whose date moves from year to year are left to be added as dated
+    /// `Holiday::Once` entries.
+    pub fn for_locale(locale: Locale) -> Self {
+        let annual: &[(u32, u32)] = match locale {
+            Locale::EnPh => &[(1, 1), (4, 9), (5, 1), (6, 12), (11, 30), (12, 25), (12, 30)],
+            Locale::EnUs => &[(1, 1), (6, 19), (7, 4), (11, 11), (12, 25)],
+            Locale::DeDe => &[(1, 1), (5, 1), (10, 3), (12, 25), (12, 26)],
+            Locale::FrFr => &[(1, 1), (5, 1), (5, 8), (7, 14), (8, 15), (11, 1), (11, 11), (12, 25)],
+        };
+        Self {
+            weekend: vec![Weekday::Saturday, Weekday::Sunday],
+            holidays: annual.iter().map(|&(month, day)| Holiday::Annual { month, day }).collect(),
+            convention: RollConvention::default(),
+        }
+    }
+
+    /// Whether `date` is neither a weekend day nor a holiday.
+    pub fn is_business_day(&self, date: Date) -> bool {
+        !self.weekend.contains(&date.weekday()) && !self.holidays.iter().any(|h| h.falls_on(date))
+    }
+
+    /// The first business day on or after `date`.
+    pub fn next_business_day(&self, date: Date) -> Date {
+        self.search(date, 1)
+    }
+
+    /// The last business day on or before `date`.
+    pub fn previous_business_day(&self, date: Date) -> Date {
+        self.search(date, -1)
+    }
+
+    /// The business day `days` business days after `date`, e.g. the day a
+    /// cheque deposited on `date` clears; `date` itself when `days` is 0.
+    pub fn add_business_days(&self, date: Date, days: u32) -> Date {
+        (0..days).fold(date, |d, _| self.next_business_day(d.add_days(1)))
+    }
+
+    /// `date` moved to a business day by the calendar's convention; a
+    /// business day is returned as is.
+    pub fn roll(&self, date: Date) -> Date {
+        match self.convention {
+            RollConvention::Unadjusted => date,
+            RollConvention::Following => self.next_business_day(date),
+            RollConvention::ModifiedFollowing => {
+                let next = self.next_business_day(date);
+                if next.month == date.month { next } else { self.previous_business_day(date) }
+            }
+            RollConvention::Preceding => self.previous_business_day(date),
+        }
+    }
+
+    fn search(&self, date: Date, step: i64) -> Date {
+        (0..=MAX_ROLL_DAYS)
+            .map(|n| date.add_days(n * step))
+            .find(|&d| self.is_business_day(d))
+            .unwrap_or(date)
+    }
+}
@@ -0,0 +1,70 @@
+use std::fmt;
+
+use crate::api::date::Date;
+use crate::api::money::Money;
+
+/// Business days a deposited cheque takes to clear unless the bank is
+/// given another clearing period.
+pub const DEFAULT_CLEARING_DAYS: u32 = 3;
+
+/// Errors raised when a cheque is deposited or returned.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ChequeError {
+    /// A cheque cannot be deposited without its number.
+    BlankNumber,
+    /// A cheque cannot be returned without a reason.
+    BlankReason,
+    NotFound(usize),
+    /// The cheque (ID given) has cleared, so it can no longer be returned.
+    Cleared(usize),
+    /// The cheque (ID given) was already returned.
+    Returned(usize),
+}
+
+impl fmt::Display for ChequeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ChequeError::BlankNumber => write!(f, "a cheque deposit needs the cheque number"),
+            ChequeError::BlankReason => write!(f, "a returned cheque needs a reason"),
+            ChequeError::NotFound(id) => write!(f, "no cheque with ID {}", id),
+            ChequeError::Cleared(id) => write!(f, "cheque {} has already cleared", id),
+            ChequeError::Returned(id) => write!(f, "cheque {} has already been returned", id),
+        }
+    }
+}
+
+impl std::error::Error for ChequeError {}
+
+/// A cheque deposited on `deposited`: `amount` is credited to the ledger at
+/// once, as the transaction with the bank-wide `sequence` number, but kept
+/// back from the available balance until `clears`. Until then it can be
+/// returned unpaid, which reverses the credit; `returned` is the day and
+/// the reason.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Cheque {
+    pub id: usize,
+    pub number: String,
+    pub amount: Money,
+    pub sequence: u64,
+    pub deposited: Date,
+    pub clears: Date,
+    pub returned: Option<(Date, String)>,
+}
+
+impl Cheque {
+    /// Returns true while the cheque is still clearing on `on`: neither
+    /// returned nor past its clearing day.
+    pub fn is_clearing(&self, on: Date) -> bool {
+        self.returned.is_none() && on < self.clears
+    }
+
+    /// Where the cheque stands on `on`: "clearing", "cleared", or
+    /// "returned".
+    pub fn status(&self, on: Date) -> &'static str {
+        match self.returned {
+            Some(_) => "returned",
+            None if on < self.clears => "clearing",
+            None => "cleared",
+        }
+    }
+}
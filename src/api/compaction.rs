@@ -0,0 +1,168 @@
+use std::fs::OpenOptions;
+use std::io::{BufWriter, Write};
+use std::path::{Path, PathBuf};
+
+use crate::api::account::{Account, Transaction};
+use crate::api::bank::{Bank, BankError};
+use crate::api::date::{format_utc_time, Date};
+use crate::api::decimal::Decimal;
+use crate::api::error::Error;
+use crate::api::money::Money;
+use crate::api::statement::csv_field;
+
+/// Memo of the entry that stands in for an account's compacted
+/// transactions.
+pub const OPENING_BALANCE_MEMO: &str = "Opening balance";
+
+/// Columns of the archived history file.
+const ARCHIVE_COLUMNS: [&str; 9] = ["account", "date", "time", "type", "amount", "currency", "memo", "category", "sequence"];
+
+/// One account's share of a compaction: its first `compacted`
+/// transactions became a single entry for `opening`, their sum.
+#[derive(Debug, Clone)]
+pub struct Compaction {
+    pub account: String,
+    pub compacted: usize,
+    pub opening: Money,
+}
+
+/// What `compact` did: the accounts it compacted, in opening order, the
+/// file their old transactions were appended to, and how many reviewed
+/// flags it dropped because their postings left the ledger.
+#[derive(Debug, Clone)]
+pub struct CompactionReport {
+    pub cutoff: Date,
+    pub archive: PathBuf,
+    pub accounts: Vec<Compaction>,
+    pub reviews_dropped: usize,
+}
+
+impl CompactionReport {
+    /// Transactions moved to the archive across every account.
+    pub fn compacted(&self) -> usize {
+        self.accounts.iter().map(|c| c.compacted).sum()
+    }
+}
+
+/// Roll the transactions posted before `cutoff` into one opening-balance
+/// entry per account, appending them to the CSV file `archive` first, so
+/// ledgers stay small in long-running banks and simulations. Balances do
+/// not change; the opening entry keeps the last rolled-up transaction's
+/// time and sequence number.
+///
+/// Only a prefix of the bank's history is compacted: transactions numbered
+/// before the first one dated on or after `cutoff`, so both legs of a
+/// transfer go together. Postings still waiting for review are kept, with
+/// everything after them, and reviewed flags whose postings were rolled up
+/// are dropped. Archived accounts keep their whole history, and an account
+/// with fewer than two transactions to roll up is left as it is.
+/// Balances and statements before `cutoff` are no longer available from
+/// the bank, only from the archive.
+///
+/// Fails with `CutoffAfterToday` if `cutoff` is after today, since today's
+/// postings count towards the daily limits. If the archive cannot be
+/// written, the ledgers are left untouched.
+pub fn compact(bank: &mut Bank, cutoff: Date, archive: impl AsRef<Path>) -> Result<CompactionReport, Error> {
+    bank.ensure_writable()?;
+    if cutoff > bank.today() {
+        return Err(BankError::CutoffAfterToday(cutoff).into());
+    }
+    let boundary = boundary(bank, cutoff);
+    let counts: Vec<usize> = bank
+        .accounts
+        .iter()
+        .map(|a| match a.is_archived() {
+            true => 0,
+            false => a.transactions.sequences().iter().take_while(|&&s| s < boundary).count(),
+        })
+        .collect();
+
+    let archive = archive.as_ref();
+    let file = OpenOptions::new().create(true).append(true).open(archive)?;
+    let fresh = file.metadata()?.len() == 0;
+    let mut out = BufWriter::new(file);
+    if fresh {
+        writeln!(out, "{}", ARCHIVE_COLUMNS.join(","))?;
+    }
+    for (acct, &count) in bank.accounts.iter().zip(&counts).filter(|(_, count)| **count >= 2) {
+        write_rows(&mut out, acct, count)?;
+    }
+    out.flush()?;
+
+    let mut accounts = Vec::new();
+    for (acct, count) in bank.accounts.iter_mut().zip(counts).filter(|(_, count)| *count >= 2) {
+        let txs: Vec<Transaction> = acct.transactions.iter().map(|t| t.to_transaction()).collect();
+        let (old, kept) = txs.split_at(count);
+        let last = &old[count - 1];
+        let units = old.iter().map(|t| t.units).sum();
+        let opening = Transaction {
+            units,
+            dp: acct.minor_unit_dp,
+            timestamp: last.timestamp,
+            sequence: last.sequence,
+            memo: OPENING_BALANCE_MEMO.to_string(),
+            category: None,
+            tags: Vec::new(),
+        };
+        acct.transactions = std::iter::once(opening).chain(kept.iter().cloned()).collect();
+        acct.invalidate_balance();
+        acct.touch();
+        accounts.push(Compaction {
+            account: acct.name.clone(),
+            compacted: count,
+            opening: Money::new(Decimal::new(units, acct.minor_unit_dp), &acct.currency),
+        });
+    }
+
+    let held = bank.flagged.len();
+    let ledgers = &bank.accounts;
+    bank.flagged.retain(|f| {
+        !f.reviewed
+            || ledgers.iter().any(|a| {
+                a.name == f.account
+                    && a.currency == f.amount.currency
+                    && a.transactions.iter().any(|t| t.tx_type() == f.tx_type && t.amount() == f.amount.amount)
+            })
+    });
+    Ok(CompactionReport { cutoff, archive: archive.to_path_buf(), accounts, reviews_dropped: held - bank.flagged.len() })
+}
+
+/// The lowest sequence number that must stay in the ledger: that of the
+/// first transaction dated on or after `cutoff`, or of a posting that may
+/// be one still waiting for review; `u64::MAX` if there is none.
+fn boundary(bank: &Bank, cutoff: Date) -> u64 {
+    let pending = bank.pending_reviews();
+    bank.accounts
+        .iter()
+        .flat_map(|a| a.transactions.iter().map(move |t| (a, t)))
+        .filter(|(a, t)| {
+            t.date() >= cutoff
+                || pending.iter().any(|f| {
+                    f.account == a.name && f.amount.currency == a.currency && f.tx_type == t.tx_type() && f.amount.amount == t.amount()
+                })
+        })
+        .map(|(_, t)| t.sequence)
+        .min()
+        .unwrap_or(u64::MAX)
+}
+
+/// Append `acct`'s first `count` transactions to the archive, one row each.
+fn write_rows(out: &mut impl Write, acct: &Account, count: usize) -> std::io::Result<()> {
+    let dp = acct.minor_unit_dp as usize;
+    for tx in acct.transactions.iter().take(count) {
+        let cells = [
+            acct.name.clone(),
+            tx.date().to_string(),
+            format_utc_time(tx.timestamp),
+            format!("{:?}", tx.tx_type()).to_lowercase(),
+            format!("{:.*}", dp, tx.amount()),
+            acct.currency.clone(),
+            tx.memo.to_string(),
+            tx.category.unwrap_or_default().to_string(),
+            tx.sequence.to_string(),
+        ];
+        let line: Vec<String> = cells.iter().map(|c| csv_field(c, ',')).collect();
+        writeln!(out, "{}", line.join(","))?;
+    }
+    Ok(())
+}
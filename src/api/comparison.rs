@@ -0,0 +1,130 @@
+use std::cmp::Ordering;
+use std::fmt;
+
+use crate::api::date::Date;
+use crate::api::decimal::Decimal;
+use crate::api::money::Money;
+
+/// Errors raised when two accounts are compared.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ComparisonError {
+    /// Both sides name the same account (given).
+    SameAccount(String),
+    /// The horizon is zero days.
+    InvalidHorizon,
+}
+
+impl fmt::Display for ComparisonError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ComparisonError::SameAccount(name) => write!(f, "cannot compare account {} with itself", name),
+            ComparisonError::InvalidHorizon => write!(f, "a comparison needs at least one day"),
+        }
+    }
+}
+
+impl std::error::Error for ComparisonError {}
+
+/// Where one compared account stands at the end of a day: its projected
+/// balance, the interest earned since the start (both in its currency),
+/// and the balance's `value` in the base currency.
+#[derive(Debug, Clone)]
+pub struct ComparisonPoint {
+    pub day: usize,
+    pub balance: Money,
+    pub interest: Money,
+    pub value: Money,
+}
+
+/// One side of an `AccountComparison`: the account's balance, its value
+/// in the base currency, and its annual rate at the start; the
+/// base-currency units one unit of its currency is worth (`rate`, held
+/// for the whole horizon); and its forecast, `points[i]` being day
+/// `i + 1`.
+#[derive(Debug, Clone)]
+pub struct ComparedAccount {
+    pub account: String,
+    pub opening: Money,
+    pub opening_value: Money,
+    pub annual_interest: Decimal,
+    pub rate: Decimal,
+    pub points: Vec<ComparisonPoint>,
+}
+
+impl ComparedAccount {
+    /// The point at the end of the horizon.
+    pub fn last(&self) -> &ComparisonPoint {
+        self.points.last().expect("a comparison covers at least one day")
+    }
+}
+
+/// Two accounts forecast side by side from `start` (day 1) for `horizon`
+/// days, each under its own settings, from `Bank::compare_accounts`.
+/// Balances are compared by their value in the base currency, so accounts
+/// in different currencies line up. `crossover` is the first day the
+/// account behind draws level with or passes the other, if their curves
+/// meet within the horizon.
+#[derive(Debug, Clone)]
+pub struct AccountComparison {
+    pub start: Date,
+    pub horizon: usize,
+    pub accounts: [ComparedAccount; 2],
+    pub crossover: Option<usize>,
+}
+
+impl AccountComparison {
+    /// Compare `accounts`, whose series cover the same `horizon` days from
+    /// `start`, finding their crossover.
+    pub(crate) fn new(start: Date, horizon: usize, accounts: [ComparedAccount; 2]) -> Self {
+        let mut comparison = Self { start, horizon, accounts, crossover: None };
+        let mut ahead = comparison.order_on(0);
+        for day in 1..=horizon {
+            let order = comparison.order_on(day);
+            if ahead != Ordering::Equal && order != ahead {
+                comparison.crossover = Some(day);
+                break;
+            }
+            ahead = order;
+        }
+        comparison
+    }
+
+    /// Which account is worth more at the end of `day` (0 for the
+    /// start): 0 or 1, or `None` when they are level.
+    pub fn ahead_on(&self, day: usize) -> Option<usize> {
+        match self.order_on(day) {
+            Ordering::Greater => Some(0),
+            Ordering::Less => Some(1),
+            Ordering::Equal => None,
+        }
+    }
+
+    /// The date of `day`, day 1 being `start`.
+    pub fn date_of(&self, day: usize) -> Date {
+        self.start.add_days(day as i64 - 1)
+    }
+
+    /// The first account's value less the second's at the end of `day` (0
+    /// for the start), in the base currency.
+    pub fn gap_on(&self, day: usize) -> Money {
+        let [first, second] = [&self.accounts[0], &self.accounts[1]].map(|a| match day {
+            0 => a.opening_value.clone(),
+            day => a.points[day - 1].value.clone(),
+        });
+        Money::new(first.amount - second.amount, &first.currency)
+    }
+
+    /// Up to `count` evenly spaced days ending on the horizon, plus the
+    /// crossover day, for tables too narrow for every day.
+    pub fn checkpoints(&self, count: usize) -> Vec<usize> {
+        let count = count.clamp(1, self.horizon);
+        let mut days: Vec<usize> = (1..=count).map(|k| self.horizon * k / count).chain(self.crossover).collect();
+        days.sort_unstable();
+        days.dedup();
+        days
+    }
+
+    fn order_on(&self, day: usize) -> Ordering {
+        self.gap_on(day).amount.cmp(&Decimal::ZERO)
+    }
+}
@@ -0,0 +1,157 @@
+use std::collections::BTreeMap;
+use std::fmt;
+
+use crate::api::account::TransactionType;
+use crate::api::decimal::Decimal;
+use crate::api::limit_profile::{LimitProfile, LimitTarget};
+use crate::api::money::Money;
+
+/// Compliance settings applied by the `Bank` when posting transactions.
+/// - `large_threshold`: deposits/withdrawals strictly above this amount are
+///   flagged. `None` disables flagging entirely.
+/// - `require_confirmation`: when true, front-ends should ask the user to
+///   confirm a large transaction before it is posted.
+/// - `confirm_threshold`: withdrawals and transfers strictly above this
+///   amount need an explicit summary-and-confirm step. `None` disables it.
+/// - `approval_threshold`: withdrawals and transfers strictly above this
+///   amount are held until an admin approves them (see
+///   `Bank::approve`). `None` disables the approval queue.
+/// - `rate_change_limit`: overwriting a rate by more than this fraction of
+///   its current value (0.10 = 10%) needs confirmation. `None` disables it.
+/// - `allow_negative_rates`: when true, annual interest rates may be set
+///   below zero, charging balances a carrying cost instead of paying
+///   interest. Off by default.
+/// - `conversion_limits`: caps on the amounts converted from or into a
+///   currency, by code. Currencies without an entry are not capped.
+/// - `unverified_limit`/`unverified_daily_limit`: the most an account whose
+///   holder has not passed KYC may deposit, withdraw, or transfer at once,
+///   and have posted in a day, in the base currency. `None` leaves it
+///   uncapped.
+/// - `limit_profiles`: the deposit, withdrawal, and daily conversion caps
+///   on accounts of a type or whose holder's KYC check stands at a status.
+///   An account under several profiles is held to each of them.
+#[derive(Debug, Clone, Default)]
+pub struct ComplianceSettings {
+    pub large_threshold: Option<Decimal>,
+    pub require_confirmation: bool,
+    pub confirm_threshold: Option<Decimal>,
+    pub approval_threshold: Option<Decimal>,
+    pub rate_change_limit: Option<Decimal>,
+    pub allow_negative_rates: bool,
+    pub conversion_limits: BTreeMap<String, ConversionLimit>,
+    pub unverified_limit: Option<Decimal>,
+    pub unverified_daily_limit: Option<Decimal>,
+    pub limit_profiles: BTreeMap<LimitTarget, LimitProfile>,
+}
+
+impl ComplianceSettings {
+    /// Returns true when `amount` (in the base currency) is above the
+    /// configured threshold.
+    pub fn is_large(&self, amount: Decimal) -> bool {
+        self.large_threshold.is_some_and(|limit| amount > limit)
+    }
+
+    /// Returns true when a withdrawal or transfer of `amount` (in the base
+    /// currency) must be confirmed before it is posted.
+    pub fn needs_confirmation(&self, amount: Decimal) -> bool {
+        self.confirm_threshold.is_some_and(|limit| amount > limit)
+    }
+
+    /// Returns true when a withdrawal or transfer of `amount` (in the base
+    /// currency) must wait for an admin's approval before it is posted.
+    pub fn needs_approval(&self, amount: Decimal) -> bool {
+        self.approval_threshold.is_some_and(|limit| amount > limit)
+    }
+
+    /// Returns true when replacing the rate `old` with `new` moves it by more
+    /// than the configured limit. Any change away from a zero rate counts.
+    pub fn is_large_rate_change(&self, old: Decimal, new: Decimal) -> bool {
+        let Some(limit) = self.rate_change_limit else {
+            return false;
+        };
+        if old.is_zero() {
+            return !new.is_zero();
+        }
+        new.checked_sub(old)
+            .and_then(|delta| delta.abs().checked_div(old.abs()))
+            .is_none_or(|change| change > limit)
+    }
+}
+
+/// The most of one currency that may be converted, in that currency.
+/// - `per_transaction`: the most any one conversion may take from or pay
+///   into the currency.
+/// - `per_day`: the most one account may convert from and into the
+///   currency in a day (UTC). Walk-in conversions, which have no account,
+///   share one daily total.
+///
+/// `None` leaves that period uncapped; a limit of zero stops conversions in
+/// the currency altogether.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ConversionLimit {
+    pub per_transaction: Option<Decimal>,
+    pub per_day: Option<Decimal>,
+}
+
+impl ConversionLimit {
+    /// Returns true when neither period is capped.
+    pub fn is_unlimited(&self) -> bool {
+        self.per_transaction.is_none() && self.per_day.is_none()
+    }
+}
+
+/// The period a `ConversionLimit` applies to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LimitPeriod {
+    Transaction,
+    Day,
+}
+
+impl LimitPeriod {
+    /// Lowercase name: "transaction" or "day".
+    pub fn name(&self) -> &'static str {
+        match self {
+            LimitPeriod::Transaction => "transaction",
+            LimitPeriod::Day => "day",
+        }
+    }
+}
+
+/// An amount refused because it would take `amount` of `currency` past the
+/// `period` limit of `limit`, `used` of it having already gone against the
+/// limit that day (zero for the per-transaction limit).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LimitBreach {
+    pub currency: String,
+    pub period: LimitPeriod,
+    pub limit: Decimal,
+    pub used: Decimal,
+    pub amount: Decimal,
+}
+
+impl fmt::Display for LimitBreach {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let code = &self.currency;
+        match self.period {
+            LimitPeriod::Transaction => {
+                write!(f, "{} {} exceeds the per-transaction limit of {} {}", self.amount, code, self.limit, code)
+            }
+            LimitPeriod::Day => write!(
+                f,
+                "{} {} exceeds the daily limit of {} {} ({} {} already used today)",
+                self.amount, code, self.limit, code, self.used, code
+            ),
+        }
+    }
+}
+
+/// A posted transaction that exceeded the large-transaction threshold and
+/// is waiting in the bank's review queue.
+#[derive(Debug, Clone)]
+pub struct FlaggedTransaction {
+    pub id: usize,
+    pub account: String,
+    pub tx_type: TransactionType,
+    pub amount: Money,
+    pub reviewed: bool,
+}
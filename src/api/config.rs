@@ -0,0 +1,1138 @@
+use std::collections::BTreeMap;
+use std::fs;
+use std::io::{self, ErrorKind};
+use std::path::Path;
+
+use crate::api::account::InterestMethod;
+use crate::api::bank::Bank;
+use crate::api::calendar::{BusinessCalendar, Holiday, RollConvention};
+use crate::api::cheque::DEFAULT_CLEARING_DAYS;
+use crate::api::compliance::ConversionLimit;
+use crate::api::date::{TimeZone, Weekday};
+use crate::api::decimal::{Decimal, RoundingStrategy, SCALE};
+use crate::api::event::EVENT_KINDS;
+use crate::api::fee::{FeeSchedule, FeeTier, PairSpread};
+use crate::api::format::Locale;
+use crate::api::forex::{Forex, RateSource};
+use crate::api::limit_profile::{LimitProfile, LimitTarget};
+use crate::api::rates::YearBasis;
+
+/// Configuration file read at startup when it exists in the working
+/// directory.
+pub const CONFIG_FILE: &str = "forex.toml";
+
+/// Environment variables read by `Config::apply_env`, with the section and
+/// key each one overrides. `FOREX_BASE_CURRENCY` is handled separately
+/// because changing the base re-quotes the whole catalog.
+const ENV_VARS: [(&str, &str, &str); 29] = [
+    ("FOREX_DATA_FILE", "", "data_file"),
+    ("FOREX_JOURNAL_FILE", "", "journal_file"),
+    ("FOREX_TELLER", "", "teller"),
+    ("FOREX_BASE_CURRENCY_NAME", "base_currency", "name"),
+    ("FOREX_ANNUAL_INTEREST", "bank", "annual_interest"),
+    ("FOREX_LARGE_TRANSACTION_THRESHOLD", "bank", "large_transaction_threshold"),
+    ("FOREX_REQUIRE_LARGE_CONFIRMATION", "bank", "require_large_confirmation"),
+    ("FOREX_CONFIRMATION_THRESHOLD", "bank", "confirmation_threshold"),
+    ("FOREX_APPROVAL_THRESHOLD", "bank", "approval_threshold"),
+    ("FOREX_RATE_CHANGE_CONFIRMATION", "bank", "rate_change_confirmation"),
+    ("FOREX_ALLOW_NEGATIVE_RATES", "bank", "allow_negative_rates"),
+    ("FOREX_WITHHOLDING_TAX_RATE", "bank", "withholding_tax_rate"),
+    ("FOREX_YEAR_BASIS", "bank", "year_basis"),
+    ("FOREX_INTEREST_METHOD", "bank", "interest_method"),
+    ("FOREX_INTEREST_GRACE_DAYS", "bank", "interest_grace_days"),
+    ("FOREX_CHEQUE_CLEARING_DAYS", "bank", "cheque_clearing_days"),
+    ("FOREX_CONVERSION_FEES", "bank", "conversion_fees"),
+    ("FOREX_PAIR_SPREADS", "bank", "pair_spreads"),
+    ("FOREX_CONVERSION_LIMITS", "bank", "conversion_limits"),
+    ("FOREX_UNVERIFIED_LIMIT", "bank", "unverified_limit"),
+    ("FOREX_UNVERIFIED_DAILY_LIMIT", "bank", "unverified_daily_limit"),
+    ("FOREX_LIMIT_PROFILES", "bank", "limit_profiles"),
+    ("FOREX_ADMIN_PASSPHRASE", "bank", "admin_passphrase"),
+    ("FOREX_ROUNDING", "bank", "rounding"),
+    ("FOREX_LOCALE", "bank", "locale"),
+    ("FOREX_TIME_ZONE", "bank", "time_zone"),
+    ("FOREX_ROLL_CONVENTION", "bank", "roll_convention"),
+    ("FOREX_WEEKEND", "bank", "weekend"),
+    ("FOREX_HOLIDAYS", "bank", "holidays"),
+];
+
+/// Startup settings for a fresh `Bank`: the currency catalog, interest and
+/// compliance policies, and where the bank state is persisted.
+///
+/// `Config::default()` is the built-in setup; `load`/`parse` read a
+/// `forex.toml` written in a small TOML subset:
+///
+/// ```toml
+/// data_file = "bank.snapshot"
+/// journal_file = "bank.journal"
+/// teller = "Maria Santos"   # named on exchange receipts
+///
+/// [bank]
+/// annual_interest = 0.05
+/// allow_negative_rates = false   # true permits annual_interest below zero
+/// withholding_tax_rate = 0.20    # tax withheld from interest as it is posted
+/// year_basis = "360"             # days a year counts: "365", "actual", or "360"
+/// interest_method = "minimum"    # "daily" balance, "average" daily, or "minimum" monthly
+/// interest_grace_days = 7        # days a deposit waits before it earns interest
+/// cheque_clearing_days = 3       # business days before a deposited cheque is available
+/// conversion_fees = ["0:0.01", "10000:0.005", "100000:0.0025"]
+/// pair_spreads = ["JPY/USD:0.02"]   # replaces the fee tier for a pair
+/// conversion_limits = ["USD:10000:50000", "JPY::2000000"]   # per transaction:per day
+/// large_transaction_threshold = 500_000   # false disables flagging
+/// approval_threshold = 1_000_000          # withdrawals/transfers above wait for an admin
+/// unverified_limit = 50_000               # per transaction, for accounts without KYC
+/// unverified_daily_limit = 100_000
+/// limit_profiles = ["kyc:unverified=20000:10000:5000", "type:business=::2000000"]   # deposit:withdrawal:daily conversion
+/// rounding = "MidpointNearestEven"
+/// locale = "en-PH"
+/// time_zone = "Asia/Manila"   # or an offset such as "UTC+08:00"
+/// roll_convention = "following"   # unadjusted, modified-following, preceding
+/// weekend = ["sat", "sun"]
+/// holidays = ["01-01", "12-25", "2026-04-03"]   # every year, or that date
+///
+/// [base_currency]
+/// code = "PHP"
+/// name = "Philippine Peso"
+/// denominations = [1000, 500, 200, 100, 50, 20, 10, 5, 1, 0.25, 0.05, 0.01]
+///
+/// [[currency]]
+/// code = "USD"
+/// name = "US Dollar"
+/// rate = 58.1130
+/// cash_rate = 57.80
+/// source = "BSP"   # who published the rate
+/// rate_decimals = 4   # digits rates are kept to and shown with
+/// denominations = [100, 50, 20, 10, 5, 1, 0.25, 0.10, 0.05, 0.01]
+///
+/// [notifications]
+/// console = true
+/// file = "events.log"
+/// statements_dir = "statements"
+/// smtp_server = "127.0.0.1:25"      # needs the `smtp` feature
+/// smtp_from = "statements@bank.example"
+///
+/// [[webhook]]
+/// url = "http://127.0.0.1:9000/forex"
+/// events = ["large_transaction_flagged", "rate_threshold_crossed"]
+/// rate_thresholds = ["USD:60"]
+///
+/// [[macro]]
+/// name = "payday"
+/// description = "Deposit pay and sweep the surplus to savings"
+/// params = ["account", "amount"]
+/// commands = ["deposit --account {account} --amount {amount} --memo Payday", "sweep --from {account} --to Savings --above 50000"]
+/// ```
+///
+/// Keys left out keep their default. Any `[[currency]]` table replaces the
+/// built-in catalog as a whole. Unknown sections and keys are errors, so
+/// typos are reported instead of silently ignored.
+#[derive(Debug, Clone)]
+pub struct Config {
+    pub base_currency: BaseCurrencyConfig,
+    pub currencies: Vec<CurrencyConfig>,
+    /// Fraction, e.g. 0.05 = 5%.
+    pub annual_interest: Decimal,
+    pub large_threshold: Option<Decimal>,
+    pub require_large_confirmation: bool,
+    pub confirmation_threshold: Option<Decimal>,
+    /// Withdrawals and transfers above this, in the base currency, wait
+    /// for an admin's approval; none by default.
+    pub approval_threshold: Option<Decimal>,
+    /// Fraction of the current rate, e.g. 0.10 = 10%.
+    pub rate_change_confirmation: Option<Decimal>,
+    /// Accept annual rates below zero (a carrying charge on balances).
+    pub allow_negative_rates: bool,
+    /// Fraction of posted interest withheld as tax; none by default.
+    pub withholding_tax_rate: Decimal,
+    /// Days a year counts when accounts accrue interest.
+    pub year_basis: YearBasis,
+    /// How accounts accrue interest: on each day's balance, or on the
+    /// month's average daily or minimum balance.
+    pub interest_method: InterestMethod,
+    /// Days a deposit waits after it is posted before it earns interest.
+    pub interest_grace_days: u32,
+    /// Business days a deposited cheque takes to clear.
+    pub cheque_clearing_days: u32,
+    /// Fees on exchanges by volume in the base currency; none by default.
+    pub conversion_fees: FeeSchedule,
+    /// Fee rates replacing `conversion_fees` for particular pairs.
+    pub pair_spreads: Vec<PairSpread>,
+    /// Caps on conversions from and into a currency, by code; none by
+    /// default.
+    pub conversion_limits: BTreeMap<String, ConversionLimit>,
+    /// Caps, in the base currency, on accounts whose holder has not passed
+    /// KYC: per transaction and per day.
+    pub unverified_limit: Option<Decimal>,
+    pub unverified_daily_limit: Option<Decimal>,
+    /// Deposit, withdrawal, and daily conversion caps, in the base
+    /// currency, on account types and KYC statuses; none by default.
+    pub limit_profiles: BTreeMap<LimitTarget, LimitProfile>,
+    /// None by default, so no admin operation is allowed until one is set.
+    pub admin_passphrase: Option<String>,
+    pub rounding: RoundingStrategy,
+    pub locale: Locale,
+    /// Zone timestamps are displayed in; they are stored in UTC.
+    pub time_zone: TimeZone,
+    /// How scheduled dates that are not business days are rolled.
+    pub roll_convention: RollConvention,
+    /// Weekend days and holidays; `None` keeps the locale's (see
+    /// `BusinessCalendar::for_locale`).
+    pub weekend: Option<Vec<Weekday>>,
+    pub holidays: Option<Vec<Holiday>>,
+    /// Snapshot file the bank state is loaded from and saved to.
+    pub data_file: String,
+    /// Append every command that changes the bank to this file, in the
+    /// form `--script` runs (`Journal`); none by default.
+    pub journal_file: Option<String>,
+    /// The teller named on the exchange receipts issued here (see
+    /// `Bank::set_teller`); none by default. Unlike the other settings it
+    /// applies to a loaded bank too.
+    pub teller: Option<String>,
+    /// Print bank events to stderr (`ConsoleNotifier`).
+    pub notify_console: bool,
+    /// Append bank events to this file (`FileNotifier`).
+    pub notify_file: Option<String>,
+    /// Write month-end statements to this directory (`FileDelivery`).
+    pub statements_dir: Option<String>,
+    /// E-mail month-end statements through this `host:port` relay
+    /// (`SmtpDelivery`), from `smtp_from`.
+    pub smtp_server: Option<String>,
+    pub smtp_from: String,
+    /// Endpoints notified of bank events; none by default.
+    pub webhooks: Vec<WebhookConfig>,
+    /// Named command sequences run as one; none by default.
+    pub macros: Vec<MacroConfig>,
+}
+
+/// The `[base_currency]` table. `denominations` defaults to the bills and
+/// coins in circulation for `code`.
+#[derive(Debug, Clone)]
+pub struct BaseCurrencyConfig {
+    pub code: String,
+    pub name: String,
+    pub denominations: Option<Vec<Decimal>>,
+}
+
+/// One `[[currency]]` table. `cash_rate` defaults to `rate`,
+/// `rate_decimals` to `DEFAULT_RATE_DP`, and `decimals`, `symbol`, and
+/// `denominations` to the ISO minor unit, conventional sign, and bills and
+/// coins in circulation for `code`.
+/// `source` names who published the rate; without it the rate is
+/// attributed to `RateSource::Catalog`.
+#[derive(Debug, Clone)]
+pub struct CurrencyConfig {
+    pub code: String,
+    pub name: String,
+    pub rate: Decimal,
+    pub cash_rate: Option<Decimal>,
+    pub decimals: Option<u32>,
+    pub rate_decimals: Option<u32>,
+    pub symbol: Option<String>,
+    pub denominations: Option<Vec<Decimal>>,
+    pub source: Option<RateSource>,
+}
+
+/// One `[[webhook]]` table: an `http://` URL that is POSTed a JSON object
+/// for each bank event.
+/// - `events`: the event kinds to send (see `EVENT_KINDS`, plus
+///   `rate_threshold_crossed`); empty sends every kind.
+/// - `rate_thresholds`: `(code, level)` pairs from `"CODE:LEVEL"` entries.
+///   A rate change that moves a currency across its level also sends a
+///   `rate_threshold_crossed` event.
+#[derive(Debug, Clone)]
+pub struct WebhookConfig {
+    pub url: String,
+    pub events: Vec<String>,
+    pub rate_thresholds: Vec<(String, Decimal)>,
+}
+
+impl WebhookConfig {
+    /// Host, port (80 unless given), and path of `url`. Only plain
+    /// `http://` is supported.
+    pub fn endpoint(&self) -> Result<(String, u16, String), String> {
+        let rest = self
+            .url
+            .strip_prefix("http://")
+            .ok_or_else(|| format!("webhook url {} must start with http://", self.url))?;
+        let (authority, path) = match rest.find('/') {
+            Some(i) => (&rest[..i], rest[i..].to_string()),
+            None => (rest, String::from("/")),
+        };
+        let (host, port) = match authority.rsplit_once(':') {
+            Some((host, port)) => (host, port.parse().map_err(|_| format!("invalid port in webhook url {}", self.url))?),
+            None => (authority, 80),
+        };
+        if host.is_empty() {
+            return Err(format!("webhook url {} has no host", self.url));
+        }
+        Ok((host.to_string(), port, path))
+    }
+
+    /// Whether events of `kind` are sent to this webhook.
+    pub fn wants(&self, kind: &str) -> bool {
+        self.events.is_empty() || self.events.iter().any(|e| e == kind)
+    }
+}
+
+/// One `[[macro]]` table: a named sequence of commands, written as
+/// `--script` lines, that runs as one from the console or the command line.
+/// Each `{param}` in `commands` is replaced with the argument given for
+/// that entry of `params`, which are asked for in order.
+#[derive(Debug, Clone)]
+pub struct MacroConfig {
+    pub name: String,
+    pub description: String,
+    pub params: Vec<String>,
+    pub commands: Vec<String>,
+}
+
+impl MacroConfig {
+    /// The parameters as they are written after the name, e.g.
+    /// "ACCOUNT AMOUNT", or "no arguments".
+    pub fn usage(&self) -> String {
+        match self.params.is_empty() {
+            true => String::from("no arguments"),
+            false => self.params.iter().map(|p| p.to_uppercase()).collect::<Vec<_>>().join(" "),
+        }
+    }
+}
+
+/// The `{name}` placeholders in `command`, in order. Braces around anything
+/// but letters, digits, `_`, and `-` are left as text.
+fn placeholders(command: &str) -> Vec<&str> {
+    let mut found = Vec::new();
+    let mut rest = command;
+    while let Some(open) = rest.find('{') {
+        rest = &rest[open + 1..];
+        if let Some(close) = rest.find('}') {
+            let name = &rest[..close];
+            if !name.is_empty() && name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-') {
+                found.push(name);
+            }
+        }
+    }
+    found
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        // Initial exchange rate retrieved from bsp.gov.ph on 10/20/2025
+        let currency = |code: &str, name: &str, rate: Decimal| CurrencyConfig {
+            code: code.to_string(),
+            name: name.to_string(),
+            rate,
+            cash_rate: None,
+            decimals: None,
+            // BSP publishes its reference rates to 4 places.
+            rate_decimals: Some(4),
+            symbol: None,
+            denominations: None,
+            source: Some(RateSource::Provider("BSP".to_string())),
+        };
+        Config {
+            base_currency: BaseCurrencyConfig { code: "PHP".to_string(), name: "Philippine Peso".to_string(), denominations: None },
+            currencies: vec![
+                currency("USD", "US Dollar", Decimal::new(581130, 4)),
+                currency("JPY", "Japanese Yen", Decimal::new(3865, 4)),
+                currency("GBP", "British Pound", Decimal::new(780632, 4)),
+                currency("EUR", "Euro", Decimal::new(677598, 4)),
+                currency("CNY", "Chinese Yuan", Decimal::new(81531, 4)),
+            ],
+            annual_interest: Decimal::new(5, 2),
+            large_threshold: Some(Decimal::from(500_000)),
+            require_large_confirmation: true,
+            confirmation_threshold: Some(Decimal::from(100_000)),
+            approval_threshold: None,
+            rate_change_confirmation: Some(Decimal::new(10, 2)),
+            allow_negative_rates: false,
+            withholding_tax_rate: Decimal::ZERO,
+            year_basis: YearBasis::default(),
+            interest_method: InterestMethod::default(),
+            interest_grace_days: 0,
+            cheque_clearing_days: DEFAULT_CLEARING_DAYS,
+            conversion_fees: FeeSchedule::default(),
+            pair_spreads: Vec::new(),
+            conversion_limits: BTreeMap::new(),
+            unverified_limit: None,
+            unverified_daily_limit: None,
+            limit_profiles: BTreeMap::new(),
+            admin_passphrase: None,
+            rounding: RoundingStrategy::MidpointNearestEven,
+            locale: Locale::EnPh,
+            time_zone: TimeZone::default(),
+            roll_convention: RollConvention::default(),
+            weekend: None,
+            holidays: None,
+            data_file: "bank.snapshot".to_string(),
+            journal_file: None,
+            teller: None,
+            notify_console: false,
+            notify_file: None,
+            statements_dir: None,
+            smtp_server: None,
+            smtp_from: "statements@localhost".to_string(),
+            webhooks: Vec::new(),
+            macros: Vec::new(),
+        }
+    }
+}
+
+impl Config {
+    /// Read a configuration file. Errors name the offending line.
+    pub fn load(path: impl AsRef<Path>) -> io::Result<Config> {
+        Config::parse(&fs::read_to_string(path)?)
+    }
+
+    /// Parse configuration text over the defaults.
+    pub fn parse(text: &str) -> io::Result<Config> {
+        let mut config = Config::default();
+        let mut section = String::new();
+        let mut currencies: Vec<PartialCurrency> = Vec::new();
+        let mut webhooks: Vec<PartialWebhook> = Vec::new();
+        let mut macros: Vec<PartialMacro> = Vec::new();
+
+        for (i, raw) in text.lines().enumerate() {
+            let n = i + 1;
+            let line = strip_comment(raw).trim();
+            if line.is_empty() {
+                continue;
+            }
+            if let Some(name) = line.strip_prefix("[[").and_then(|l| l.strip_suffix("]]")) {
+                section = name.trim().to_string();
+                match section.as_str() {
+                    "currency" => currencies.push(PartialCurrency { line: n, ..PartialCurrency::default() }),
+                    "webhook" => webhooks.push(PartialWebhook { line: n, ..PartialWebhook::default() }),
+                    "macro" => macros.push(PartialMacro { line: n, ..PartialMacro::default() }),
+                    _ => return Err(invalid(&format!("line {}: unknown table array [[{}]]", n, section))),
+                }
+                continue;
+            }
+            if let Some(name) = line.strip_prefix('[').and_then(|l| l.strip_suffix(']')) {
+                section = name.trim().to_string();
+                if !matches!(section.as_str(), "bank" | "base_currency" | "notifications") {
+                    return Err(invalid(&format!("line {}: unknown table [{}]", n, section)));
+                }
+                continue;
+            }
+
+            let (key, value) = line
+                .split_once('=')
+                .ok_or_else(|| invalid(&format!("line {}: expected key = value", n)))?;
+            let key = key.trim();
+            let at = format!("line {}", n);
+            let value = Value::parse(value.trim()).ok_or_else(|| invalid(&format!("{}: invalid value for {}", at, key)))?;
+            if section == "currency" {
+                // A table was pushed when its `[[currency]]` header was read.
+                let entry = currencies.last_mut().expect("currency table");
+                entry.set(key, value, &at)?;
+            } else if section == "webhook" {
+                webhooks.last_mut().expect("webhook table").set(key, value, &at)?;
+            } else if section == "macro" {
+                macros.last_mut().expect("macro table").set(key, value, &at)?;
+            } else {
+                config.set(&section, key, value, &at)?;
+            }
+        }
+
+        if !currencies.is_empty() {
+            config.currencies = currencies.into_iter().map(PartialCurrency::finish).collect::<io::Result<_>>()?;
+        }
+        config.webhooks = webhooks.into_iter().map(PartialWebhook::finish).collect::<io::Result<_>>()?;
+        for partial in macros {
+            let line = partial.line;
+            let entry = partial.finish()?;
+            if config.macros.iter().any(|m| m.name == entry.name) {
+                return Err(invalid(&format!("line {}: macro {} is defined twice", line, entry.name)));
+            }
+            config.macros.push(entry);
+        }
+        config.check_rates()?;
+        config.check_codes()?;
+        Ok(config)
+    }
+
+    /// Layer environment variables over this configuration. `lookup` returns
+    /// a variable's value (`|name| std::env::var(name).ok()` for the process
+    /// environment). `FOREX_BASE_CURRENCY` switches the base with `rebase`;
+    /// every other `FOREX_*` variable in `ENV_VARS` overrides the key of the
+    /// same name. Values are plain text (`FOREX_ANNUAL_INTEREST=0.04`,
+    /// `FOREX_LOCALE=en-US`, `FOREX_LARGE_TRANSACTION_THRESHOLD=false`).
+    pub fn apply_env(&mut self, lookup: impl Fn(&str) -> Option<String>) -> io::Result<()> {
+        if let Some(code) = lookup("FOREX_BASE_CURRENCY") {
+            self.rebase(&code.trim().to_uppercase())
+                .map_err(|e| invalid(&format!("FOREX_BASE_CURRENCY: {}", e)))?;
+        }
+        for (var, section, key) in ENV_VARS {
+            if let Some(value) = lookup(var) {
+                self.set(section, key, Value::Env(value), var)?;
+            }
+        }
+        self.check_rates()?;
+        self.check_codes()
+    }
+
+    /// Refuse a pair spread or conversion limit on a currency outside the
+    /// catalog.
+    fn check_codes(&self) -> io::Result<()> {
+        let known = |code: &str| code == self.base_currency.code || self.currencies.iter().any(|c| c.code == code);
+        if let Some(s) = self.pair_spreads.iter().find(|s| !known(&s.pair.0) || !known(&s.pair.1)) {
+            return Err(invalid(&format!("pair_spreads: {} names a currency that is not in the catalog", s.label())));
+        }
+        match self.conversion_limits.keys().find(|code| !known(code)) {
+            Some(code) => Err(invalid(&format!("conversion_limits: {} is not in the catalog", code))),
+            None => Ok(()),
+        }
+    }
+
+    /// Refuse an `annual_interest` of -100% or less, or below zero without
+    /// `allow_negative_rates`, as `Bank::check_interest_rate` would, and a
+    /// `withholding_tax_rate` outside 0 to 1.
+    fn check_rates(&self) -> io::Result<()> {
+        if self.withholding_tax_rate < Decimal::ZERO || self.withholding_tax_rate > Decimal::ONE {
+            return Err(invalid(&format!("withholding_tax_rate {} must be from 0 to 1", self.withholding_tax_rate)));
+        }
+        if self.annual_interest <= -Decimal::ONE {
+            return Err(invalid(&format!("annual_interest {} must be above -1 (-100%)", self.annual_interest)));
+        }
+        if self.annual_interest < Decimal::ZERO && !self.allow_negative_rates {
+            return Err(invalid(&format!(
+                "annual_interest {} is below zero; set allow_negative_rates = true to allow it",
+                self.annual_interest
+            )));
+        }
+        Ok(())
+    }
+
+    /// Make `code`, a currency already in the catalog, the base currency.
+    /// Every rate is re-quoted against it and the old base joins the catalog
+    /// at its equivalent rate, so conversions between any two currencies are
+    /// unchanged up to rounding. Cash rates are re-quoted against the new
+    /// base's cash rate. Re-quoted rates are kept to `DEFAULT_RATE_DP`
+    /// places, since a precision set for the old base may not suit the new.
+    pub fn rebase(&mut self, code: &str) -> Result<(), String> {
+        if self.base_currency.code == code {
+            return Ok(());
+        }
+        let pos = self
+            .currencies
+            .iter()
+            .position(|c| c.code == code)
+            .ok_or_else(|| format!("{} is not in the currency catalog", code))?;
+        let new_base = self.currencies.remove(pos);
+        let requote = |rate: Decimal| {
+            rate.checked_div(new_base.rate)
+                .ok_or_else(|| format!("cannot re-quote rates against {} at rate {}", code, new_base.rate))
+        };
+        let requote_cash = |rate: Decimal| {
+            let cash_base = new_base.cash_rate.unwrap_or(new_base.rate);
+            rate.checked_div(cash_base)
+                .ok_or_else(|| format!("cannot re-quote cash rates against {} at rate {}", code, cash_base))
+        };
+        for c in &mut self.currencies {
+            if c.cash_rate.is_some() || new_base.cash_rate.is_some() {
+                c.cash_rate = Some(requote_cash(c.cash_rate.unwrap_or(c.rate))?);
+            }
+            c.rate = requote(c.rate)?;
+            c.rate_decimals = None;
+        }
+        let old_base = std::mem::replace(
+            &mut self.base_currency,
+            BaseCurrencyConfig { code: new_base.code.clone(), name: new_base.name.clone(), denominations: new_base.denominations.clone() },
+        );
+        self.currencies.push(CurrencyConfig {
+            code: old_base.code,
+            name: old_base.name,
+            rate: requote(Decimal::ONE)?,
+            cash_rate: new_base.cash_rate.map(|_| requote_cash(Decimal::ONE)).transpose()?,
+            decimals: None,
+            rate_decimals: None,
+            symbol: None,
+            denominations: old_base.denominations,
+            source: new_base.source,
+        });
+        Ok(())
+    }
+
+    /// Apply one `key = value` from `section` ("" for the top level). `at`
+    /// says where the value came from for error messages.
+    fn set(&mut self, section: &str, key: &str, value: Value, at: &str) -> io::Result<()> {
+        match (section, key) {
+            ("", "data_file") => self.data_file = value.text(at, key)?,
+            ("", "journal_file") => self.journal_file = Some(value.text(at, key)?).filter(|f| !f.is_empty()),
+            ("", "teller") => self.teller = Some(value.text(at, key)?).filter(|t| !t.trim().is_empty()),
+            ("bank", "annual_interest") => self.annual_interest = value.number(at, key)?,
+            ("bank", "large_transaction_threshold") => self.large_threshold = value.optional_number(at, key)?,
+            ("bank", "require_large_confirmation") => self.require_large_confirmation = value.flag(at, key)?,
+            ("bank", "confirmation_threshold") => self.confirmation_threshold = value.optional_number(at, key)?,
+            ("bank", "approval_threshold") => self.approval_threshold = value.optional_number(at, key)?,
+            ("bank", "rate_change_confirmation") => self.rate_change_confirmation = value.optional_number(at, key)?,
+            ("bank", "allow_negative_rates") => self.allow_negative_rates = value.flag(at, key)?,
+            ("bank", "withholding_tax_rate") => self.withholding_tax_rate = value.number(at, key)?,
+            ("bank", "year_basis") => {
+                let name = match value {
+                    Value::Num(days) => days.to_string(),
+                    value => value.text(at, key)?,
+                };
+                self.year_basis = YearBasis::parse(&name)
+                    .ok_or_else(|| invalid(&format!("{}: unknown year basis {} (expected \"365\", \"actual\", or \"360\")", at, name)))?;
+            }
+            ("bank", "interest_method") => {
+                let name = value.text(at, key)?;
+                self.interest_method = InterestMethod::parse(&name)
+                    .ok_or_else(|| invalid(&format!("{}: unknown interest method {} (expected \"daily\", \"average\", or \"minimum\")", at, name)))?;
+            }
+            ("bank", "interest_grace_days") => {
+                let days = value.number(at, key)?;
+                self.interest_grace_days = days.to_string().parse().map_err(|_| invalid(&format!("{}: invalid interest_grace_days {} (expected whole days)", at, days)))?;
+            }
+            ("bank", "cheque_clearing_days") => {
+                let days = value.number(at, key)?;
+                self.cheque_clearing_days = days.to_string().parse().map_err(|_| invalid(&format!("{}: invalid cheque_clearing_days {} (expected whole days)", at, days)))?;
+            }
+            ("bank", "unverified_limit") => self.unverified_limit = value.optional_number(at, key)?,
+            ("bank", "unverified_daily_limit") => self.unverified_daily_limit = value.optional_number(at, key)?,
+            ("bank", "conversion_fees") => {
+                let tiers = value
+                    .list(at, key)?
+                    .iter()
+                    .map(|entry| {
+                        entry
+                            .split_once(':')
+                            .and_then(|(from, rate)| Some(FeeTier { from: from.trim().parse().ok()?, rate: rate.trim().parse().ok()? }))
+                            .ok_or_else(|| invalid(&format!("{}: invalid fee tier {} (expected \"FROM:RATE\")", at, entry)))
+                    })
+                    .collect::<io::Result<_>>()?;
+                self.conversion_fees = FeeSchedule::new(tiers).map_err(|e| invalid(&format!("{}: {}", at, e)))?;
+            }
+            ("bank", "pair_spreads") => {
+                self.pair_spreads = value
+                    .list(at, key)?
+                    .iter()
+                    .map(|entry| {
+                        entry
+                            .split_once(':')
+                            .and_then(|(pair, rate)| {
+                                let (a, b) = pair.split_once('/')?;
+                                let (a, b) = (a.trim().to_uppercase(), b.trim().to_uppercase());
+                                let rate: Decimal = rate.trim().parse().ok()?;
+                                (a != b && rate >= Decimal::ZERO && rate < Decimal::ONE).then(|| PairSpread { pair: if a <= b { (a, b) } else { (b, a) }, rate })
+                            })
+                            .ok_or_else(|| invalid(&format!("{}: invalid pair spread {} (expected \"CODE/CODE:RATE\" with two different codes and a rate from 0 up to 1)", at, entry)))
+                    })
+                    .collect::<io::Result<_>>()?;
+            }
+            ("bank", "conversion_limits") => {
+                self.conversion_limits = value
+                    .list(at, key)?
+                    .iter()
+                    .map(|entry| {
+                        conversion_limit(entry).ok_or_else(|| {
+                            invalid(&format!("{}: invalid conversion limit {} (expected \"CODE:PER_TRANSACTION:PER_DAY\", either amount blank for none)", at, entry))
+                        })
+                    })
+                    .collect::<io::Result<_>>()?;
+            }
+            ("bank", "limit_profiles") => {
+                self.limit_profiles = value
+                    .list(at, key)?
+                    .iter()
+                    .map(|entry| {
+                        limit_profile(entry).ok_or_else(|| {
+                            invalid(&format!("{}: invalid limit profile {} (expected \"type:NAME=DEPOSIT:WITHDRAWAL:DAILY_CONVERSION\" or \"kyc:STATUS=...\", any amount blank for none)", at, entry))
+                        })
+                    })
+                    .collect::<io::Result<_>>()?;
+            }
+            ("bank", "admin_passphrase") => self.admin_passphrase = Some(value.text(at, key)?).filter(|p| !p.is_empty()),
+            ("bank", "rounding") => {
+                let name = value.text(at, key)?;
+                self.rounding = RoundingStrategy::parse(&name)
+                    .ok_or_else(|| invalid(&format!("{}: unknown rounding strategy {}", at, name)))?;
+            }
+            ("bank", "locale") => {
+                let tag = value.text(at, key)?;
+                self.locale = Locale::parse(&tag).ok_or_else(|| invalid(&format!("{}: unknown locale {}", at, tag)))?;
+            }
+            ("bank", "time_zone") => {
+                let name = value.text(at, key)?;
+                self.time_zone = TimeZone::parse(&name).ok_or_else(|| invalid(&format!("{}: unknown time zone {}", at, name)))?;
+            }
+            ("bank", "roll_convention") => {
+                let name = value.text(at, key)?;
+                self.roll_convention =
+                    RollConvention::parse(&name).ok_or_else(|| invalid(&format!("{}: unknown roll convention {}", at, name)))?;
+            }
+            ("bank", "weekend") => {
+                self.weekend = Some(
+                    value
+                        .list(at, key)?
+                        .iter()
+                        .map(|day| Weekday::parse(day).ok_or_else(|| invalid(&format!("{}: unknown weekday {}", at, day))))
+                        .collect::<io::Result<_>>()?,
+                );
+            }
+            ("bank", "holidays") => {
+                self.holidays = Some(
+                    value
+                        .list(at, key)?
+                        .iter()
+                        .map(|h| Holiday::parse(h).ok_or_else(|| invalid(&format!("{}: invalid holiday {} (expected MM-DD or YYYY-MM-DD)", at, h))))
+                        .collect::<io::Result<_>>()?,
+                );
+            }
+            ("notifications", "console") => self.notify_console = value.flag(at, key)?,
+            ("notifications", "file") => self.notify_file = Some(value.text(at, key)?).filter(|f| !f.is_empty()),
+            ("notifications", "statements_dir") => self.statements_dir = Some(value.text(at, key)?).filter(|d| !d.is_empty()),
+            ("notifications", "smtp_server") => self.smtp_server = Some(value.text(at, key)?).filter(|s| !s.is_empty()),
+            ("notifications", "smtp_from") => self.smtp_from = value.text(at, key)?,
+            ("base_currency", "code") => self.base_currency.code = value.text(at, key)?.to_uppercase(),
+            ("base_currency", "name") => self.base_currency.name = value.text(at, key)?,
+            ("base_currency", "denominations") => self.base_currency.denominations = Some(value.numbers(at, key)?),
+            _ => return Err(invalid(&format!("{}: unknown key {} in {}", at, key, section_label(section)))),
+        }
+        Ok(())
+    }
+
+    /// A fresh bank with no accounts, set up from this configuration.
+    pub fn build_bank(&self) -> Bank {
+        let mut forex = Forex::builder().set_base_currency(&self.base_currency.code, &self.base_currency.name);
+        if let Some(denominations) = &self.base_currency.denominations {
+            forex = forex.set_denominations(&self.base_currency.code, denominations.clone());
+        }
+        for c in &self.currencies {
+            forex = forex.create_currency(&c.code, &c.name, c.rate).set_cash_rate(&c.code, c.cash_rate);
+            if let Some(source) = &c.source {
+                forex = forex.set_rate_source(&c.code, Some(source.clone()));
+            }
+            if let Some(dp) = c.decimals {
+                forex = forex.set_decimals(&c.code, dp);
+            }
+            if let Some(dp) = c.rate_decimals {
+                forex = forex.set_rate_decimals(&c.code, dp);
+            }
+            if let Some(symbol) = &c.symbol {
+                forex = forex.set_symbol(&c.code, symbol);
+            }
+            if let Some(denominations) = &c.denominations {
+                forex = forex.set_denominations(&c.code, denominations.clone());
+            }
+        }
+
+        forex = forex.set_fee_schedule(self.conversion_fees.clone());
+        for s in &self.pair_spreads {
+            forex = forex.set_pair_spread(&s.pair.0, &s.pair.1, s.rate);
+        }
+        let mut builder = Bank::builder()
+            .set_forex(forex.build())
+            .set_annual_interest(self.annual_interest)
+            .set_require_large_confirmation(self.require_large_confirmation)
+            .set_allow_negative_rates(self.allow_negative_rates)
+            .set_withholding_tax_rate(self.withholding_tax_rate)
+            .set_year_basis(self.year_basis)
+            .set_interest_method(self.interest_method)
+            .set_interest_grace_days(self.interest_grace_days)
+            .set_cheque_clearing_days(self.cheque_clearing_days)
+            .set_rounding(self.rounding)
+            .set_locale(self.locale)
+            .set_time_zone(self.time_zone)
+            .set_calendar(self.calendar());
+        if let Some(amount) = self.large_threshold {
+            builder = builder.set_large_transaction_threshold(amount);
+        }
+        if let Some(amount) = self.confirmation_threshold {
+            builder = builder.set_confirmation_threshold(amount);
+        }
+        if let Some(amount) = self.approval_threshold {
+            builder = builder.set_approval_threshold(amount);
+        }
+        if let Some(fraction) = self.rate_change_confirmation {
+            builder = builder.set_rate_change_confirmation(fraction);
+        }
+        if let Some(amount) = self.unverified_limit {
+            builder = builder.set_unverified_limit(amount);
+        }
+        if let Some(amount) = self.unverified_daily_limit {
+            builder = builder.set_unverified_daily_limit(amount);
+        }
+        for (code, limit) in &self.conversion_limits {
+            builder = builder.set_conversion_limit(code, *limit);
+        }
+        for (target, profile) in &self.limit_profiles {
+            builder = builder.set_limit_profile(target.clone(), *profile);
+        }
+        if let Some(passphrase) = &self.admin_passphrase {
+            builder = builder.set_admin_passphrase(passphrase);
+        }
+        builder.build()
+    }
+
+    /// The business-day calendar for the configured locale, with the
+    /// configured convention, weekend, and holidays.
+    pub fn calendar(&self) -> BusinessCalendar {
+        let mut calendar = BusinessCalendar::for_locale(self.locale);
+        calendar.convention = self.roll_convention;
+        if let Some(weekend) = &self.weekend {
+            calendar.weekend = weekend.clone();
+        }
+        if let Some(holidays) = &self.holidays {
+            calendar.holidays = holidays.clone();
+        }
+        calendar
+    }
+}
+
+/// A `[[currency]]` table while its keys are still being read.
+#[derive(Default)]
+struct PartialCurrency {
+    line: usize,
+    code: Option<String>,
+    name: Option<String>,
+    rate: Option<Decimal>,
+    cash_rate: Option<Decimal>,
+    decimals: Option<u32>,
+    rate_decimals: Option<u32>,
+    symbol: Option<String>,
+    denominations: Option<Vec<Decimal>>,
+    source: Option<RateSource>,
+}
+
+impl PartialCurrency {
+    fn set(&mut self, key: &str, value: Value, at: &str) -> io::Result<()> {
+        match key {
+            "code" => self.code = Some(value.text(at, key)?.to_uppercase()),
+            "name" => self.name = Some(value.text(at, key)?),
+            "rate" | "cash_rate" => {
+                let rate = value.number(at, key)?;
+                if rate <= Decimal::ZERO {
+                    return Err(invalid(&format!("{}: {} must be greater than zero, got {}", at, key, rate)));
+                }
+                match key {
+                    "rate" => self.rate = Some(rate),
+                    _ => self.cash_rate = Some(rate),
+                }
+            }
+            "decimals" => {
+                let dp = value.number(at, key)?;
+                self.decimals = Some(
+                    dp.to_string()
+                        .parse()
+                        .ok()
+                        .filter(|&dp| dp <= SCALE)
+                        .ok_or_else(|| invalid(&format!("{}: invalid decimals {} (0 to {})", at, dp, SCALE)))?,
+                );
+            }
+            "rate_decimals" => {
+                let dp = value.number(at, key)?;
+                self.rate_decimals = Some(
+                    dp.to_string()
+                        .parse()
+                        .ok()
+                        .filter(|&dp| dp <= SCALE)
+                        .ok_or_else(|| invalid(&format!("{}: invalid rate_decimals {} (0 to {})", at, dp, SCALE)))?,
+                );
+            }
+            "symbol" => self.symbol = Some(value.text(at, key)?),
+            "denominations" => self.denominations = Some(value.numbers(at, key)?),
+            "source" => self.source = RateSource::parse(&value.text(at, key)?),
+            _ => return Err(invalid(&format!("{}: unknown key {} in [[currency]]", at, key))),
+        }
+        Ok(())
+    }
+
+    fn finish(self) -> io::Result<CurrencyConfig> {
+        let missing = |key: &str| invalid(&format!("line {}: [[currency]] is missing {}", self.line, key));
+        Ok(CurrencyConfig {
+            code: self.code.clone().ok_or_else(|| missing("code"))?,
+            name: self.name.clone().ok_or_else(|| missing("name"))?,
+            rate: self.rate.ok_or_else(|| missing("rate"))?,
+            cash_rate: self.cash_rate,
+            decimals: self.decimals,
+            rate_decimals: self.rate_decimals,
+            symbol: self.symbol.clone(),
+            denominations: self.denominations.clone(),
+            source: self.source.clone(),
+        })
+    }
+}
+
+/// A `[[webhook]]` table while its keys are still being read.
+#[derive(Default)]
+struct PartialWebhook {
+    line: usize,
+    url: Option<String>,
+    events: Vec<String>,
+    rate_thresholds: Vec<(String, Decimal)>,
+}
+
+impl PartialWebhook {
+    fn set(&mut self, key: &str, value: Value, at: &str) -> io::Result<()> {
+        match key {
+            "url" => self.url = Some(value.text(at, key)?),
+            "events" => {
+                self.events = value.list(at, key)?;
+                if let Some(bad) = self.events.iter().find(|e| !EVENT_KINDS.contains(&e.as_str()) && *e != "rate_threshold_crossed") {
+                    return Err(invalid(&format!("{}: unknown event {}", at, bad)));
+                }
+            }
+            "rate_thresholds" => {
+                self.rate_thresholds = value
+                    .list(at, key)?
+                    .iter()
+                    .map(|entry| {
+                        entry
+                            .split_once(':')
+                            .and_then(|(code, level)| Some((code.trim().to_uppercase(), level.trim().parse().ok()?)))
+                            .ok_or_else(|| invalid(&format!("{}: invalid rate threshold {} (expected \"CODE:LEVEL\")", at, entry)))
+                    })
+                    .collect::<io::Result<_>>()?;
+            }
+            _ => return Err(invalid(&format!("{}: unknown key {} in [[webhook]]", at, key))),
+        }
+        Ok(())
+    }
+
+    fn finish(self) -> io::Result<WebhookConfig> {
+        let url = self.url.ok_or_else(|| invalid(&format!("line {}: [[webhook]] is missing url", self.line)))?;
+        let hook = WebhookConfig { url, events: self.events, rate_thresholds: self.rate_thresholds };
+        hook.endpoint().map_err(|e| invalid(&format!("line {}: {}", self.line, e)))?;
+        Ok(hook)
+    }
+}
+
+/// A `[[macro]]` table while its keys are still being read.
+#[derive(Default)]
+struct PartialMacro {
+    line: usize,
+    name: Option<String>,
+    description: String,
+    params: Vec<String>,
+    commands: Vec<String>,
+}
+
+impl PartialMacro {
+    fn set(&mut self, key: &str, value: Value, at: &str) -> io::Result<()> {
+        match key {
+            "name" => self.name = Some(value.text(at, key)?),
+            "description" => self.description = value.text(at, key)?,
+            "params" => self.params = value.list(at, key)?,
+            "commands" => self.commands = value.list(at, key)?,
+            _ => return Err(invalid(&format!("{}: unknown key {} in [[macro]]", at, key))),
+        }
+        Ok(())
+    }
+
+    /// Check that the macro has a one-word name and at least one command,
+    /// and that its placeholders and parameters match up.
+    fn finish(self) -> io::Result<MacroConfig> {
+        let at = format!("line {}", self.line);
+        let name = self.name.ok_or_else(|| invalid(&format!("{}: [[macro]] is missing name", at)))?;
+        if name.is_empty() || name.starts_with('-') || name.contains(char::is_whitespace) {
+            return Err(invalid(&format!("{}: macro name {:?} must be one word", at, name)));
+        }
+        if self.commands.is_empty() {
+            return Err(invalid(&format!("{}: macro {} has no commands", at, name)));
+        }
+        for (i, param) in self.params.iter().enumerate() {
+            if self.params[..i].contains(param) {
+                return Err(invalid(&format!("{}: macro {} lists param {} twice", at, name, param)));
+            }
+            if !self.commands.iter().any(|c| placeholders(c).contains(&param.as_str())) {
+                return Err(invalid(&format!("{}: macro {} never uses param {}", at, name, param)));
+            }
+        }
+        if let Some(unknown) = self.commands.iter().flat_map(|c| placeholders(c)).find(|p| !self.params.iter().any(|param| param == p)) {
+            return Err(invalid(&format!("{}: macro {} uses {{{}}}, which is not in params", at, name, unknown)));
+        }
+        Ok(MacroConfig { name, description: self.description, params: self.params, commands: self.commands })
+    }
+}
+
+/// A TOML scalar (basic string, number, or boolean), a one-line array of
+/// scalars, or untyped text from an environment variable that is read as
+/// whatever the key expects.
+enum Value {
+    Str(String),
+    Num(Decimal),
+    Bool(bool),
+    List(Vec<Value>),
+    Env(String),
+}
+
+impl Value {
+    fn parse(s: &str) -> Option<Value> {
+        if let Some(body) = s.strip_prefix('[').and_then(|b| b.strip_suffix(']')) {
+            return split_items(body)?.iter().map(|item| Value::parse(item)).collect::<Option<_>>().map(Value::List);
+        }
+        if let Some(body) = s.strip_prefix('"').and_then(|b| b.strip_suffix('"')) {
+            return unquote(body).map(Value::Str);
+        }
+        match s {
+            "true" => Some(Value::Bool(true)),
+            "false" => Some(Value::Bool(false)),
+            _ => s.replace('_', "").parse().ok().map(Value::Num),
+        }
+    }
+
+    fn text(self, at: &str, key: &str) -> io::Result<String> {
+        match self {
+            Value::Str(s) | Value::Env(s) => Ok(s),
+            _ => Err(invalid(&format!("{}: {} must be a string", at, key))),
+        }
+    }
+
+    fn number(self, at: &str, key: &str) -> io::Result<Decimal> {
+        match self {
+            Value::Num(d) => Ok(d),
+            Value::Env(s) => s.trim().parse().map_err(|_| invalid(&format!("{}: {} must be a number", at, key))),
+            _ => Err(invalid(&format!("{}: {} must be a number", at, key))),
+        }
+    }
+
+    /// A number, or `false` to turn the setting off.
+    fn optional_number(self, at: &str, key: &str) -> io::Result<Option<Decimal>> {
+        match self {
+            Value::Bool(false) => Ok(None),
+            Value::Env(s) if s.trim() == "false" => Ok(None),
+            Value::Num(_) | Value::Env(_) => self.number(at, key).map(Some),
+            _ => Err(invalid(&format!("{}: {} must be a number or false", at, key))),
+        }
+    }
+
+    /// Strings from an array; comma-separated when from the environment.
+    fn list(self, at: &str, key: &str) -> io::Result<Vec<String>> {
+        match self {
+            Value::List(items) => items.into_iter().map(|item| item.text(at, key)).collect(),
+            Value::Env(s) => Ok(s.split(',').map(|item| item.trim().to_string()).filter(|item| !item.is_empty()).collect()),
+            _ => Err(invalid(&format!("{}: {} must be an array of strings", at, key))),
+        }
+    }
+
+    /// Positive numbers from an array; comma-separated when from the
+    /// environment.
+    fn numbers(self, at: &str, key: &str) -> io::Result<Vec<Decimal>> {
+        let items = match self {
+            Value::List(items) => items.into_iter().map(|item| item.number(at, key)).collect::<io::Result<Vec<_>>>()?,
+            Value::Env(s) => s.split(',').map(|item| Value::Env(item.to_string()).number(at, key)).collect::<io::Result<_>>()?,
+            _ => return Err(invalid(&format!("{}: {} must be an array of numbers", at, key))),
+        };
+        match items.iter().find(|d| **d <= Decimal::ZERO) {
+            Some(bad) => Err(invalid(&format!("{}: {} must be greater than zero, not {}", at, key, bad))),
+            None => Ok(items),
+        }
+    }
+
+    fn flag(self, at: &str, key: &str) -> io::Result<bool> {
+        match self {
+            Value::Bool(b) => Ok(b),
+            Value::Env(s) if matches!(s.trim(), "true" | "false") => Ok(s.trim() == "true"),
+            _ => Err(invalid(&format!("{}: {} must be true or false", at, key))),
+        }
+    }
+}
+
+/// Drop a trailing `# comment` that is not inside a string.
+fn strip_comment(line: &str) -> &str {
+    let mut in_string = false;
+    let mut escaped = false;
+    for (i, c) in line.char_indices() {
+        match c {
+            _ if escaped => escaped = false,
+            '\\' if in_string => escaped = true,
+            '"' => in_string = !in_string,
+            '#' if !in_string => return &line[..i],
+            _ => {}
+        }
+    }
+    line
+}
+
+/// Split the inside of a one-line array on the commas outside strings,
+/// allowing a trailing comma. `None` for a nested array.
+fn split_items(body: &str) -> Option<Vec<&str>> {
+    let mut items = Vec::new();
+    let mut start = 0;
+    let mut in_string = false;
+    let mut escaped = false;
+    for (i, c) in body.char_indices() {
+        match c {
+            _ if escaped => escaped = false,
+            '\\' if in_string => escaped = true,
+            '"' => in_string = !in_string,
+            '[' | ']' if !in_string => return None,
+            ',' if !in_string => {
+                items.push(body[start..i].trim());
+                start = i + 1;
+            }
+            _ => {}
+        }
+    }
+    let last = body[start..].trim();
+    if !last.is_empty() {
+        items.push(last);
+    }
+    Some(items)
+}
+
+/// Resolve the escapes of a TOML basic string body.
+fn unquote(body: &str) -> Option<String> {
+    let mut out = String::with_capacity(body.len());
+    let mut chars = body.chars();
+    while let Some(c) = chars.next() {
+        match c {
+            '\\' => out.push(match chars.next()? {
+                'n' => '\n',
+                't' => '\t',
+                '"' => '"',
+                '\\' => '\\',
+                _ => return None,
+            }),
+            '"' => return None,
+            c => out.push(c),
+        }
+    }
+    Some(out)
+}
+
+fn section_label(section: &str) -> String {
+    match section {
+        "" => "the top level".to_string(),
+        "currency" => "[[currency]]".to_string(),
+        other => format!("[{}]", other),
+    }
+}
+
+/// One `conversion_limits` entry, "CODE:PER_TRANSACTION:PER_DAY", either
+/// amount blank for no cap in that period.
+fn conversion_limit(entry: &str) -> Option<(String, ConversionLimit)> {
+    let cap = |raw: &str| match raw.trim() {
+        "" => Some(None),
+        raw => raw.parse().ok().filter(|cap| *cap >= Decimal::ZERO).map(Some),
+    };
+    let mut parts = entry.split(':');
+    let (code, per_transaction, per_day) = (parts.next()?.trim(), parts.next()?, parts.next()?);
+    if code.is_empty() || parts.next().is_some() {
+        return None;
+    }
+    Some((code.to_uppercase(), ConversionLimit { per_transaction: cap(per_transaction)?, per_day: cap(per_day)? }))
+}
+
+/// One `limit_profiles` entry, "TARGET=DEPOSIT:WITHDRAWAL:DAILY_CONVERSION"
+/// (see `LimitTarget::parse`), any amount blank for no cap on it.
+fn limit_profile(entry: &str) -> Option<(LimitTarget, LimitProfile)> {
+    let cap = |raw: &str| match raw.trim() {
+        "" => Some(None),
+        raw => raw.parse().ok().filter(|cap| *cap >= Decimal::ZERO).map(Some),
+    };
+    let (target, caps) = entry.split_once('=')?;
+    let mut parts = caps.split(':');
+    let (deposit, withdrawal, conversion) = (parts.next()?, parts.next()?, parts.next()?);
+    if parts.next().is_some() {
+        return None;
+    }
+    let profile = LimitProfile { max_deposit: cap(deposit)?, max_withdrawal: cap(withdrawal)?, max_daily_conversion: cap(conversion)? };
+    Some((LimitTarget::parse(target)?, profile))
+}
+
+fn invalid(msg: &str) -> io::Error {
+    io::Error::new(ErrorKind::InvalidData, msg.to_string())
+}
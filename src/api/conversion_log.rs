@@ -0,0 +1,77 @@
+use crate::api::date::Date;
+use crate::api::decimal::Decimal;
+use crate::api::money::Money;
+
+/// One executed conversion in the `Forex` log, recorded by the bank when it
+/// posts one: a cross-currency transfer, standing order, or exchange, a
+/// limit-order fill, a forward settlement, or `Bank::settle_conversion`.
+/// - `timestamp`: when it ran (Unix seconds, UTC, on the bank's clock).
+/// - `account`: the account that initiated it; `None` for
+///   `settle_conversion`, which touches no account.
+/// - `amount_in`/`amount_out`: what was converted and what was paid out,
+///   net of the fee, both as posted.
+/// - `rate`: units of `amount_out`'s currency per unit of `amount_in`'s.
+/// - `fee`: the fee kept, in `amount_out`'s currency.
+/// - `volume`: `amount_in` in the base currency at the time, the amount fee
+///   tiers and turnover are measured in.
+/// - `receipt`: its exchange receipt number, counting up from 1 (see
+///   `Bank::exchange_receipt`).
+/// - `teller`/`customer`: the teller at the counter and the customer whose
+///   session it ran in, when known (see `Bank::set_teller` and
+///   `Bank::log_in`).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ConversionRecord {
+    pub receipt: usize,
+    pub timestamp: i64,
+    pub account: Option<String>,
+    pub amount_in: Money,
+    pub amount_out: Money,
+    pub rate: Decimal,
+    pub fee: Money,
+    pub volume: Decimal,
+    pub teller: Option<String>,
+    pub customer: Option<String>,
+}
+
+impl ConversionRecord {
+    /// The day (UTC) the conversion ran.
+    pub fn date(&self) -> Date {
+        Date::from_timestamp(self.timestamp)
+    }
+}
+
+/// Filter for `Forex::conversion_history`. Every field is optional; unset
+/// fields match everything, and set fields must all match.
+/// - `start`/`end`: inclusive bounds on the date (UTC).
+/// - `account`: the initiating account, by exact name.
+/// - `currency`: either side of the pair.
+#[derive(Debug, Clone, Default)]
+pub struct ConversionFilter {
+    pub start: Option<Date>,
+    pub end: Option<Date>,
+    pub account: Option<String>,
+    pub currency: Option<String>,
+}
+
+impl ConversionFilter {
+    /// Returns true if `record` satisfies every filter set.
+    pub fn matches(&self, record: &ConversionRecord) -> bool {
+        let date = record.date();
+        self.start.is_none_or(|s| date >= s)
+            && self.end.is_none_or(|e| date <= e)
+            && self.account.as_ref().is_none_or(|a| record.account.as_ref() == Some(a))
+            && self
+                .currency
+                .as_ref()
+                .is_none_or(|c| record.amount_in.currency == *c || record.amount_out.currency == *c)
+    }
+}
+
+/// FX turnover on one day, from `Forex::daily_turnover`: how many
+/// conversions ran and their total `volume` in the base currency.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DailyTurnover {
+    pub date: Date,
+    pub conversions: usize,
+    pub volume: Decimal,
+}
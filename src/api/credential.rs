@@ -0,0 +1,263 @@
+//! Salted, slow hashes for account PINs and the admin passphrase.
+//!
+//! The threat is someone who gets hold of a snapshot or journal and tries
+//! to recover the secrets offline; a 4-6 digit PIN falls to any fast hash,
+//! so each guess is made to cost `ITERATIONS` rounds of PBKDF2-HMAC-SHA256
+//! (RFC 8018) with a 16-byte random salt. Digests are compared in constant
+//! time, so a front end's response time says nothing about how close a
+//! guess came. Online guessing is not throttled here.
+
+use std::collections::hash_map::RandomState;
+use std::fs::File;
+use std::hash::{BuildHasher, Hasher};
+use std::io::Read;
+
+/// PBKDF2 rounds for a new credential: OWASP's figure for
+/// PBKDF2-HMAC-SHA256. Stored with the hash, so it can be raised without
+/// breaking older credentials; tests use far fewer to stay quick.
+const ITERATIONS: u32 = if cfg!(test) { 1_000 } else { 600_000 };
+
+/// Tag in front of a PBKDF2 hash: `pbkdf2-sha256$ITERATIONS$HEX`.
+const SCHEME: &str = "pbkdf2-sha256";
+
+/// Rounds of the iterated SHA-256 that hashes without a scheme tag use.
+/// Such hashes still verify, and are replaced when the secret is next set.
+const LEGACY_ROUNDS: usize = 1_000;
+
+/// Salted, hashed PIN/passphrase attached to an account. The plain secret is
+/// never stored; only the hex-encoded salt and the tagged digest are kept.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Credential {
+    pub salt: String,
+    pub hash: String,
+}
+
+impl Credential {
+    /// Create a credential for `secret` with a fresh random salt.
+    pub fn new(secret: &str) -> Self {
+        let salt: String = random_salt().iter().map(|b| format!("{:02x}", b)).collect();
+        let hash = hash_secret(&salt, secret);
+        Self { salt, hash }
+    }
+
+    /// Returns true if `secret` hashes to the stored digest. Digests are
+    /// compared in constant time.
+    pub fn verify(&self, secret: &str) -> bool {
+        let expected = match self.hash.split('$').collect::<Vec<_>>()[..] {
+            [SCHEME, iterations, _] => match iterations.parse() {
+                Ok(iterations) if iterations > 0 => pbkdf2_hash(&self.salt, secret, iterations),
+                _ => return false,
+            },
+            [_] => legacy_hash(&self.salt, secret),
+            _ => return false,
+        };
+        constant_time_eq(expected.as_bytes(), self.hash.as_bytes())
+    }
+}
+
+/// Hash `secret` with `salt` by PBKDF2-HMAC-SHA256 at `ITERATIONS` rounds,
+/// as `pbkdf2-sha256$ITERATIONS$HEX`.
+pub fn hash_secret(salt: &str, secret: &str) -> String {
+    pbkdf2_hash(salt, secret, ITERATIONS)
+}
+
+fn pbkdf2_hash(salt: &str, secret: &str, iterations: u32) -> String {
+    format!("{}${}${}", SCHEME, iterations, hex(&pbkdf2_sha256(secret.as_bytes(), salt.as_bytes(), iterations)))
+}
+
+/// The iterated SHA-256 hashes were made with before PBKDF2.
+fn legacy_hash(salt: &str, secret: &str) -> String {
+    let mut digest = sha256(format!("{}:{}", salt, secret).as_bytes());
+    for _ in 1..LEGACY_ROUNDS {
+        let mut input = digest.to_vec();
+        input.extend_from_slice(salt.as_bytes());
+        digest = sha256(&input);
+    }
+    hex(&digest)
+}
+
+fn hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+/// Equal-length inputs are compared without stopping at the first
+/// difference; the length of a digest is no secret.
+fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    a.len() == b.len() && a.iter().zip(b).fold(0, |diff, (x, y)| diff | (x ^ y)) == 0
+}
+
+/// 16 bytes from the OS random source. Where there is none to open (e.g.
+/// wasm32 or Windows), std's `RandomState` keys stand in; they are random
+/// per process but not meant as a cryptographic source.
+fn random_salt() -> [u8; 16] {
+    let mut salt = [0; 16];
+    if File::open("/dev/urandom").and_then(|mut f| f.read_exact(&mut salt)).is_err() {
+        for half in salt.chunks_mut(8) {
+            half.copy_from_slice(&RandomState::new().build_hasher().finish().to_be_bytes());
+        }
+    }
+    salt
+}
+
+/// PBKDF2 (RFC 8018) with HMAC-SHA256, for a single 32-byte block.
+fn pbkdf2_sha256(password: &[u8], salt: &[u8], iterations: u32) -> [u8; 32] {
+    let hmac = Hmac::new(password);
+    let mut block = salt.to_vec();
+    block.extend_from_slice(&1u32.to_be_bytes());
+    let mut u = hmac.mac(&block);
+    let mut out = u;
+    for _ in 1..iterations {
+        u = hmac.mac(&u);
+        for (o, x) in out.iter_mut().zip(u) {
+            *o ^= x;
+        }
+    }
+    out
+}
+
+/// HMAC-SHA256 (RFC 2104) with the keyed inner and outer states computed
+/// once, so each MAC of a short message costs two compressions.
+struct Hmac {
+    inner: [u32; 8],
+    outer: [u32; 8],
+}
+
+impl Hmac {
+    fn new(key: &[u8]) -> Self {
+        let mut block = [0u8; 64];
+        if key.len() > 64 {
+            block[..32].copy_from_slice(&sha256(key));
+        } else {
+            block[..key.len()].copy_from_slice(key);
+        }
+        let keyed = |pad: u8| {
+            let mut state = IV;
+            compress(&mut state, &block.map(|b| b ^ pad));
+            state
+        };
+        Self { inner: keyed(0x36), outer: keyed(0x5c) }
+    }
+
+    fn mac(&self, message: &[u8]) -> [u8; 32] {
+        let inner = finish(self.inner, 64, message);
+        finish(self.outer, 64, &inner)
+    }
+}
+
+const K: [u32; 64] = [
+    0x428a2f98, 0x71374491, 0xb5c0fbcf, 0xe9b5dba5, 0x3956c25b, 0x59f111f1, 0x923f82a4, 0xab1c5ed5,
+    0xd807aa98, 0x12835b01, 0x243185be, 0x550c7dc3, 0x72be5d74, 0x80deb1fe, 0x9bdc06a7, 0xc19bf174,
+    0xe49b69c1, 0xefbe4786, 0x0fc19dc6, 0x240ca1cc, 0x2de92c6f, 0x4a7484aa, 0x5cb0a9dc, 0x76f988da,
+    0x983e5152, 0xa831c66d, 0xb00327c8, 0xbf597fc7, 0xc6e00bf3, 0xd5a79147, 0x06ca6351, 0x14292967,
+    0x27b70a85, 0x2e1b2138, 0x4d2c6dfc, 0x53380d13, 0x650a7354, 0x766a0abb, 0x81c2c92e, 0x92722c85,
+    0xa2bfe8a1, 0xa81a664b, 0xc24b8b70, 0xc76c51a3, 0xd192e819, 0xd6990624, 0xf40e3585, 0x106aa070,
+    0x19a4c116, 0x1e376c08, 0x2748774c, 0x34b0bcb5, 0x391c0cb3, 0x4ed8aa4a, 0x5b9cca4f, 0x682e6ff3,
+    0x748f82ee, 0x78a5636f, 0x84c87814, 0x8cc70208, 0x90befffa, 0xa4506ceb, 0xbef9a3f7, 0xc67178f2,
+];
+
+const IV: [u32; 8] = [0x6a09e667, 0xbb67ae85, 0x3c6ef372, 0xa54ff53a, 0x510e527f, 0x9b05688c, 0x1f83d9ab, 0x5be0cd19];
+
+/// Minimal SHA-256 (FIPS 180-4) so the crate stays dependency-free.
+fn sha256(data: &[u8]) -> [u8; 32] {
+    finish(IV, 0, data)
+}
+
+/// Hash the rest of a message, `data`, from `state`, the state after its
+/// first `prefix` bytes (a multiple of 64), and return the digest.
+fn finish(mut state: [u32; 8], prefix: u64, data: &[u8]) -> [u8; 32] {
+    let bit_len = (prefix + data.len() as u64).wrapping_mul(8);
+    let mut blocks = data.chunks_exact(64);
+    for block in &mut blocks {
+        compress(&mut state, block.try_into().expect("64-byte block"));
+    }
+    let rest = blocks.remainder();
+    let mut tail = [0u8; 128];
+    tail[..rest.len()].copy_from_slice(rest);
+    tail[rest.len()] = 0x80;
+    let len = if rest.len() < 56 { 64 } else { 128 };
+    tail[len - 8..len].copy_from_slice(&bit_len.to_be_bytes());
+    for block in tail[..len].chunks_exact(64) {
+        compress(&mut state, block.try_into().expect("64-byte block"));
+    }
+
+    let mut out = [0u8; 32];
+    for (i, word) in state.iter().enumerate() {
+        out[i * 4..i * 4 + 4].copy_from_slice(&word.to_be_bytes());
+    }
+    out
+}
+
+fn compress(h: &mut [u32; 8], chunk: &[u8; 64]) {
+    let mut w = [0u32; 64];
+    for (i, word) in chunk.chunks(4).enumerate() {
+        w[i] = u32::from_be_bytes([word[0], word[1], word[2], word[3]]);
+    }
+    for i in 16..64 {
+        let s0 = w[i - 15].rotate_right(7) ^ w[i - 15].rotate_right(18) ^ (w[i - 15] >> 3);
+        let s1 = w[i - 2].rotate_right(17) ^ w[i - 2].rotate_right(19) ^ (w[i - 2] >> 10);
+        w[i] = w[i - 16]
+            .wrapping_add(s0)
+            .wrapping_add(w[i - 7])
+            .wrapping_add(s1);
+    }
+
+    let [mut a, mut b, mut c, mut d, mut e, mut f, mut g, mut hh] = *h;
+    for i in 0..64 {
+        let s1 = e.rotate_right(6) ^ e.rotate_right(11) ^ e.rotate_right(25);
+        let ch = (e & f) ^ (!e & g);
+        let t1 = hh
+            .wrapping_add(s1)
+            .wrapping_add(ch)
+            .wrapping_add(K[i])
+            .wrapping_add(w[i]);
+        let s0 = a.rotate_right(2) ^ a.rotate_right(13) ^ a.rotate_right(22);
+        let maj = (a & b) ^ (a & c) ^ (b & c);
+        let t2 = s0.wrapping_add(maj);
+        hh = g;
+        g = f;
+        f = e;
+        e = d.wrapping_add(t1);
+        d = c;
+        c = b;
+        b = a;
+        a = t1.wrapping_add(t2);
+    }
+
+    for (slot, v) in h.iter_mut().zip([a, b, c, d, e, f, g, hh]) {
+        *slot = slot.wrapping_add(v);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sha256_matches_the_fips_examples() {
+        assert_eq!(hex(&sha256(b"abc")), "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad");
+        let two_blocks = b"abcdbcdecdefdefgefghfghighijhijkijkljklmklmnlmnomnopnopq";
+        assert_eq!(hex(&sha256(two_blocks)), "248d6a61d20638b8e5c026930c3e6039a33ce45964ff2167f6ecedd419db06c1");
+    }
+
+    #[test]
+    fn pbkdf2_matches_the_published_vectors() {
+        assert_eq!(hex(&pbkdf2_sha256(b"password", b"salt", 1)), "120fb6cffcf8b32c43e7225256c4f837a86548c92ccc35480805987cb70be17b");
+        assert_eq!(hex(&pbkdf2_sha256(b"password", b"salt", 4096)), "c5e478d59288c841aa530db6845c4c8d962893a001ce4e11a4963873aa98134a");
+    }
+
+    #[test]
+    fn credentials_verify_only_their_secret() {
+        let cred = Credential::new("1234");
+        assert!(cred.hash.starts_with("pbkdf2-sha256$"));
+        assert!(cred.verify("1234"));
+        assert!(!cred.verify("1235"));
+        assert_ne!(Credential::new("1234").salt, cred.salt);
+    }
+
+    #[test]
+    fn legacy_hashes_still_verify() {
+        let cred = Credential { salt: String::from("00112233aabbccdd"), hash: legacy_hash("00112233aabbccdd", "1234") };
+        assert!(cred.verify("1234"));
+        assert!(!cred.verify("4321"));
+    }
+}
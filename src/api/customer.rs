@@ -0,0 +1,123 @@
+/// Customer (account holder) model. A customer is registered once and can
+/// own several accounts, referenced by account ID.
+/// - `id`: bank-assigned identifier, starting at 1.
+/// - `contact`: free-form contact information (phone, email, address).
+/// - `identification`: the identity document on file, if any.
+/// - `address`: residential address, as declared for KYC.
+/// - `verification`: where the customer's KYC check stands. Accounts are
+///   only verified once their holder is (see `Bank::is_account_verified`).
+#[derive(Debug, Clone)]
+pub struct Customer {
+    pub id: usize,
+    pub name: String,
+    pub contact: String,
+    pub account_ids: Vec<usize>,
+    pub identification: Option<Identification>,
+    pub address: String,
+    pub verification: VerificationStatus,
+}
+
+impl Customer {
+    /// Create a customer without any accounts or KYC details.
+    pub fn new(id: usize, name: &str, contact: &str) -> Self {
+        Self {
+            id,
+            name: name.to_string(),
+            contact: contact.to_string(),
+            account_ids: Vec::new(),
+            identification: None,
+            address: String::new(),
+            verification: VerificationStatus::default(),
+        }
+    }
+
+    /// Returns true if the customer owns the account with `account_id`.
+    pub fn owns(&self, account_id: usize) -> bool {
+        self.account_ids.contains(&account_id)
+    }
+
+    /// Returns true once the customer has passed the KYC check.
+    pub fn is_verified(&self) -> bool {
+        self.verification == VerificationStatus::Verified
+    }
+}
+
+/// An identity document: its kind and number.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Identification {
+    pub id_type: IdType,
+    pub id_number: String,
+}
+
+/// Kinds of identity document accepted for KYC.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum IdType {
+    Passport,
+    DriversLicense,
+    NationalId,
+    Other,
+}
+
+impl IdType {
+    /// Every kind, in menu order.
+    pub const ALL: [IdType; 4] = [IdType::Passport, IdType::DriversLicense, IdType::NationalId, IdType::Other];
+
+    /// Parse "passport", "drivers_license", "national_id", or "other" (any
+    /// case).
+    pub fn parse(s: &str) -> Option<Self> {
+        match s.trim().to_lowercase().as_str() {
+            "passport" => Some(IdType::Passport),
+            "drivers_license" => Some(IdType::DriversLicense),
+            "national_id" => Some(IdType::NationalId),
+            "other" => Some(IdType::Other),
+            _ => None,
+        }
+    }
+
+    /// Lowercase name, as accepted by `parse`.
+    pub fn name(&self) -> &'static str {
+        match self {
+            IdType::Passport => "passport",
+            IdType::DriversLicense => "drivers_license",
+            IdType::NationalId => "national_id",
+            IdType::Other => "other",
+        }
+    }
+}
+
+/// Where a customer's KYC check stands.
+/// - `Unverified`: no identification has been reviewed; the default.
+/// - `Pending`: identification is on file and waiting for review.
+/// - `Verified`: the identification was checked and accepted.
+/// - `Rejected`: the identification was checked and refused.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord)]
+pub enum VerificationStatus {
+    #[default]
+    Unverified,
+    Pending,
+    Verified,
+    Rejected,
+}
+
+impl VerificationStatus {
+    /// Parse "unverified", "pending", "verified", or "rejected" (any case).
+    pub fn parse(s: &str) -> Option<Self> {
+        match s.trim().to_lowercase().as_str() {
+            "unverified" => Some(VerificationStatus::Unverified),
+            "pending" => Some(VerificationStatus::Pending),
+            "verified" => Some(VerificationStatus::Verified),
+            "rejected" => Some(VerificationStatus::Rejected),
+            _ => None,
+        }
+    }
+
+    /// Lowercase name, as accepted by `parse`.
+    pub fn name(&self) -> &'static str {
+        match self {
+            VerificationStatus::Unverified => "unverified",
+            VerificationStatus::Pending => "pending",
+            VerificationStatus::Verified => "verified",
+            VerificationStatus::Rejected => "rejected",
+        }
+    }
+}
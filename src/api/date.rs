@@ -0,0 +1,411 @@
+use std::fmt;
+use std::sync::atomic::{AtomicI64, Ordering};
+
+/// Seconds in one day; timestamps are Unix seconds (UTC).
+pub const SECS_PER_DAY: i64 = 86_400;
+
+/// Calendar date (proleptic Gregorian) without time zone information.
+/// Used for transaction dates and date-range filters without pulling in chrono.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Date {
+    pub year: i32,
+    pub month: u32,
+    pub day: u32,
+}
+
+impl Date {
+    /// Build a date, returning `None` if the month/day combination is invalid.
+    pub fn new(year: i32, month: u32, day: u32) -> Option<Self> {
+        if !(1..=12).contains(&month) || day < 1 || day > days_in_month(year, month) {
+            return None;
+        }
+        Some(Self { year, month, day })
+    }
+
+    /// Parse an ISO `YYYY-MM-DD` string.
+    pub fn parse(s: &str) -> Option<Self> {
+        let mut parts = s.trim().splitn(3, '-');
+        let year = parts.next()?.parse().ok()?;
+        let month = parts.next()?.parse().ok()?;
+        let day = parts.next()?.parse().ok()?;
+        Self::new(year, month, day)
+    }
+
+    /// Today's date in UTC by the system clock (see `now_timestamp`);
+    /// banks go by their own clock (see `Bank::today`).
+    pub fn today() -> Self {
+        Self::from_timestamp(now_timestamp())
+    }
+
+    /// Date (UTC) containing the Unix timestamp `secs`.
+    pub fn from_timestamp(secs: i64) -> Self {
+        Self::from_days(secs.div_euclid(SECS_PER_DAY))
+    }
+
+    /// Unix timestamp of midnight UTC at the start of this date.
+    pub fn timestamp(&self) -> i64 {
+        self.days() * SECS_PER_DAY
+    }
+
+    /// Days since 1970-01-01 (negative before the epoch).
+    pub fn days(&self) -> i64 {
+        // Howard Hinnant's days_from_civil algorithm.
+        let y = i64::from(self.year) - i64::from(self.month <= 2);
+        let era = y.div_euclid(400);
+        let yoe = y - era * 400;
+        let m = i64::from(self.month);
+        let doy = (153 * (if m > 2 { m - 3 } else { m + 9 }) + 2) / 5 + i64::from(self.day) - 1;
+        let doe = yoe * 365 + yoe / 4 - yoe / 100 + doy;
+        era * 146_097 + doe - 719_468
+    }
+
+    /// Inverse of `days`.
+    pub fn from_days(days: i64) -> Self {
+        let z = days + 719_468;
+        let era = z.div_euclid(146_097);
+        let doe = z - era * 146_097;
+        let yoe = (doe - doe / 1460 + doe / 36_524 - doe / 146_096) / 365;
+        let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+        let mp = (5 * doy + 2) / 153;
+        let day = (doy - (153 * mp + 2) / 5 + 1) as u32;
+        let month = if mp < 10 { mp + 3 } else { mp - 9 } as u32;
+        let year = (yoe + era * 400 + i64::from(month <= 2)) as i32;
+        Self { year, month, day }
+    }
+
+    /// The date `n` days after this one (or before, if negative).
+    pub fn add_days(&self, n: i64) -> Self {
+        Self::from_days(self.days() + n)
+    }
+
+    /// Day of the week this date falls on.
+    pub fn weekday(&self) -> Weekday {
+        // 1970-01-01 was a Thursday.
+        Weekday::ALL[(self.days() + 3).rem_euclid(7) as usize]
+    }
+}
+
+impl fmt::Display for Date {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:04}-{:02}-{:02}", self.year, self.month, self.day)
+    }
+}
+
+/// A calendar month of a year, e.g. 2026-10, for monthly reports.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Month {
+    pub year: i32,
+    pub month: u32,
+}
+
+impl Month {
+    /// Build a month, returning `None` unless `month` is 1-12.
+    pub fn new(year: i32, month: u32) -> Option<Self> {
+        (1..=12).contains(&month).then_some(Self { year, month })
+    }
+
+    /// Parse an ISO `YYYY-MM` string.
+    pub fn parse(s: &str) -> Option<Self> {
+        let (year, month) = s.trim().split_once('-')?;
+        Self::new(year.parse().ok()?, month.parse().ok()?)
+    }
+
+    /// The month `date` falls in.
+    pub fn of(date: Date) -> Self {
+        Self { year: date.year, month: date.month }
+    }
+
+    pub fn first_day(&self) -> Date {
+        Date { year: self.year, month: self.month, day: 1 }
+    }
+
+    pub fn last_day(&self) -> Date {
+        Date { year: self.year, month: self.month, day: days_in_month(self.year, self.month) }
+    }
+}
+
+impl fmt::Display for Month {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:04}-{:02}", self.year, self.month)
+    }
+}
+
+/// Day of the week, Monday first (ISO 8601).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Weekday {
+    Monday,
+    Tuesday,
+    Wednesday,
+    Thursday,
+    Friday,
+    Saturday,
+    Sunday,
+}
+
+impl Weekday {
+    /// Every day of the week, Monday first.
+    pub const ALL: [Weekday; 7] = [
+        Weekday::Monday,
+        Weekday::Tuesday,
+        Weekday::Wednesday,
+        Weekday::Thursday,
+        Weekday::Friday,
+        Weekday::Saturday,
+        Weekday::Sunday,
+    ];
+
+    /// Parse a day name or its first three letters, e.g. "Saturday" or
+    /// "sat" (any case).
+    pub fn parse(s: &str) -> Option<Self> {
+        let s = s.trim().to_lowercase();
+        Self::ALL.into_iter().find(|d| s.len() >= 3 && d.name().starts_with(&s))
+    }
+
+    /// Lowercase three-letter name, as accepted by `parse`.
+    pub fn short_name(&self) -> &'static str {
+        &self.name()[..3]
+    }
+
+    fn name(&self) -> &'static str {
+        match self {
+            Weekday::Monday => "monday",
+            Weekday::Tuesday => "tuesday",
+            Weekday::Wednesday => "wednesday",
+            Weekday::Thursday => "thursday",
+            Weekday::Friday => "friday",
+            Weekday::Saturday => "saturday",
+            Weekday::Sunday => "sunday",
+        }
+    }
+}
+
+/// Returns true for Gregorian leap years.
+pub fn is_leap_year(year: i32) -> bool {
+    (year % 4 == 0 && year % 100 != 0) || year % 400 == 0
+}
+
+/// Number of days in `month` (1-12) of `year`.
+pub fn days_in_month(year: i32, month: u32) -> u32 {
+    match month {
+        1 | 3 | 5 | 7 | 8 | 10 | 12 => 31,
+        4 | 6 | 9 | 11 => 30,
+        2 if is_leap_year(year) => 29,
+        2 => 28,
+        _ => 0,
+    }
+}
+
+/// Where "now" comes from. A `Bank` reads the clock it was given (see
+/// `Bank::set_clock`) for transaction and rate timestamps, interest
+/// accrual, and scheduling, so tests and simulations decide what time it
+/// is. Hosts without a system clock, such as `wasm32-unknown-unknown`,
+/// implement it over the host's (e.g. JavaScript's `Date.now()`).
+pub trait Clock: fmt::Debug + Send + Sync {
+    /// Current Unix timestamp (seconds, UTC).
+    fn now(&self) -> i64;
+
+    /// Today's date in UTC.
+    fn today(&self) -> Date {
+        Date::from_timestamp(self.now())
+    }
+}
+
+/// The operating system's clock (see `now_timestamp`).
+#[derive(Debug, Clone, Copy, Default)]
+pub struct SystemClock;
+
+impl Clock for SystemClock {
+    fn now(&self) -> i64 {
+        now_timestamp()
+    }
+}
+
+/// A clock stopped at one Unix timestamp, e.g. for reproducible tests.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FixedClock(pub i64);
+
+impl Clock for FixedClock {
+    fn now(&self) -> i64 {
+        self.0
+    }
+}
+
+/// A clock that only moves when told to, so simulations (e.g.
+/// `MarketSimulator`) can step days at will and replays can stand it at a
+/// historical time. Shared handles see the same time.
+#[derive(Debug, Default)]
+pub struct SimulationClock {
+    now: AtomicI64,
+}
+
+impl SimulationClock {
+    /// A simulation clock standing at `timestamp`.
+    pub fn new(timestamp: i64) -> Self {
+        Self { now: AtomicI64::new(timestamp) }
+    }
+
+    /// Move the clock forward by `seconds` and return the new time.
+    pub fn advance(&self, seconds: i64) -> i64 {
+        self.now.fetch_add(seconds, Ordering::Relaxed) + seconds
+    }
+
+    /// Stand the clock at `timestamp`.
+    pub fn set(&self, timestamp: i64) {
+        self.now.store(timestamp, Ordering::Relaxed);
+    }
+}
+
+impl Clock for SimulationClock {
+    fn now(&self) -> i64 {
+        self.now.load(Ordering::Relaxed)
+    }
+}
+
+/// Current Unix timestamp (seconds, UTC) from the system clock. Banks read
+/// their own clock instead (see `Bank::now`).
+pub fn now_timestamp() -> i64 {
+    system_timestamp()
+}
+
+#[cfg(not(all(target_arch = "wasm32", target_os = "unknown")))]
+fn system_timestamp() -> i64 {
+    use std::time::{SystemTime, UNIX_EPOCH};
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs() as i64)
+        .unwrap_or(0)
+}
+
+/// `SystemTime::now` panics on `wasm32-unknown-unknown`; there the system
+/// clock stands at the epoch, and hosts supply a `Clock` of their own.
+#[cfg(all(target_arch = "wasm32", target_os = "unknown"))]
+fn system_timestamp() -> i64 {
+    0
+}
+
+/// Format Unix seconds as "YYYY-MM-DD HH:MM UTC".
+pub fn format_timestamp(secs: i64) -> String {
+    let tod = secs.rem_euclid(SECS_PER_DAY);
+    format!("{} {:02}:{:02} UTC", Date::from_timestamp(secs), tod / 3600, tod % 3600 / 60)
+}
+
+/// Format the time of day of Unix seconds as "HH:MM:SSZ" (UTC), as the CSV
+/// exports write it.
+pub fn format_utc_time(secs: i64) -> String {
+    let tod = secs.rem_euclid(SECS_PER_DAY);
+    format!("{:02}:{:02}:{:02}Z", tod / 3600, tod % 3600 / 60, tod % 60)
+}
+
+/// Time zones `TimeZone::parse` knows by name, with their offset from UTC
+/// in minutes. Only zones without daylight saving time are listed, since
+/// the offset is fixed.
+const NAMED_ZONES: [(&str, i32); 14] = [
+    ("UTC", 0),
+    ("Asia/Manila", 480),
+    ("Asia/Singapore", 480),
+    ("Asia/Hong_Kong", 480),
+    ("Asia/Shanghai", 480),
+    ("Asia/Taipei", 480),
+    ("Asia/Kuala_Lumpur", 480),
+    ("Asia/Tokyo", 540),
+    ("Asia/Seoul", 540),
+    ("Asia/Jakarta", 420),
+    ("Asia/Bangkok", 420),
+    ("Asia/Ho_Chi_Minh", 420),
+    ("Asia/Kolkata", 330),
+    ("Asia/Dubai", 240),
+];
+
+/// The time zone timestamps are displayed in: a fixed offset from UTC,
+/// named after an IANA zone when it is one of `NAMED_ZONES`. Timestamps
+/// are always stored in UTC; the zone only changes how they are shown.
+/// Defaults to Asia/Manila (UTC+08:00).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TimeZone {
+    name: Option<&'static str>,
+    offset: i32,
+}
+
+impl Default for TimeZone {
+    fn default() -> Self {
+        Self { name: Some("Asia/Manila"), offset: 480 }
+    }
+}
+
+impl TimeZone {
+    pub const UTC: TimeZone = TimeZone { name: Some("UTC"), offset: 0 };
+
+    /// Parse a zone name from `NAMED_ZONES` (any case), or an offset such
+    /// as "UTC+8", "UTC+05:30", "+08:00", "-0300", or "Z".
+    pub fn parse(s: &str) -> Option<Self> {
+        let s = s.trim();
+        if let Some((name, offset)) = NAMED_ZONES.iter().find(|(name, _)| name.eq_ignore_ascii_case(s)) {
+            return Some(Self { name: Some(name), offset: *offset });
+        }
+        if s.eq_ignore_ascii_case("z") || s.eq_ignore_ascii_case("gmt") {
+            return Some(Self::UTC);
+        }
+        let rest = ["UTC", "GMT"].iter().find_map(|p| s.get(..3).filter(|h| h.eq_ignore_ascii_case(p)).map(|_| &s[3..])).unwrap_or(s);
+        Self::from_offset_minutes(parse_offset(rest)?)
+    }
+
+    /// A zone `minutes` ahead of UTC (behind, if negative); `None` beyond
+    /// ±14 hours.
+    pub fn from_offset_minutes(minutes: i32) -> Option<Self> {
+        (minutes.abs() <= 14 * 60).then_some(Self { name: None, offset: minutes })
+    }
+
+    /// The zone's name if it has one, else its offset ("UTC+05:30");
+    /// accepted back by `parse`.
+    pub fn tag(&self) -> String {
+        self.name.map_or_else(|| self.offset_label(), str::to_string)
+    }
+
+    /// Seconds the zone is ahead of UTC.
+    pub fn offset_seconds(&self) -> i64 {
+        i64::from(self.offset) * 60
+    }
+
+    /// "UTC" for UTC, else the offset as "UTC+08:00".
+    pub fn offset_label(&self) -> String {
+        match self.offset {
+            0 => String::from("UTC"),
+            m => format!("UTC{}{:02}:{:02}", if m < 0 { '-' } else { '+' }, m.abs() / 60, m.abs() % 60),
+        }
+    }
+
+    /// The local date of Unix seconds `secs` in this zone.
+    pub fn date_of(&self, secs: i64) -> Date {
+        Date::from_timestamp(secs + self.offset_seconds())
+    }
+
+    /// Format Unix seconds as local time in this zone, labelled with its
+    /// offset: "YYYY-MM-DD HH:MM UTC+08:00" (or "... UTC").
+    pub fn format_timestamp(&self, secs: i64) -> String {
+        let local = secs + self.offset_seconds();
+        let tod = local.rem_euclid(SECS_PER_DAY);
+        format!("{} {:02}:{:02} {}", Date::from_timestamp(local), tod / 3600, tod % 3600 / 60, self.offset_label())
+    }
+}
+
+impl fmt::Display for TimeZone {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.tag())
+    }
+}
+
+/// Minutes of a signed offset: "+8", "+08", "+08:00", "-0530".
+pub(crate) fn parse_offset(s: &str) -> Option<i32> {
+    let (sign, digits) = match s.as_bytes().first()? {
+        b'+' => (1, &s[1..]),
+        b'-' => (-1, &s[1..]),
+        _ => return None,
+    };
+    let (h, m) = match digits.split_once(':') {
+        Some((h, m)) => (h, m),
+        None if digits.len() == 4 => digits.split_at(2),
+        None => (digits, "0"),
+    };
+    let (h, m): (i32, i32) = (h.parse().ok()?, m.parse().ok()?);
+    ((0..=14).contains(&h) && (0..60).contains(&m)).then_some(sign * (h * 60 + m))
+}
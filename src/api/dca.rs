@@ -0,0 +1,111 @@
+use std::fmt;
+
+use crate::api::decimal::Decimal;
+use crate::api::money::Money;
+
+/// Errors raised when a dollar-cost-averaging simulation is refused.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DcaError {
+    NonPositiveAmount,
+    /// The simulation buys a currency with itself.
+    SameCurrency(String),
+    /// The rate series has no rates.
+    EmptySeries,
+    /// The rate at this 1-based period is zero or negative.
+    NonPositiveRate(usize),
+    /// An amount is too large to represent.
+    Overflow,
+}
+
+impl fmt::Display for DcaError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DcaError::NonPositiveAmount => write!(f, "purchase amount must be greater than zero"),
+            DcaError::SameCurrency(code) => write!(f, "cannot buy {} with itself", code),
+            DcaError::EmptySeries => write!(f, "the rate series is empty"),
+            DcaError::NonPositiveRate(period) => write!(f, "rate for period {} must be greater than zero", period),
+            DcaError::Overflow => write!(f, "simulation is out of range"),
+        }
+    }
+}
+
+impl std::error::Error for DcaError {}
+
+/// One period of a simulation: `spent` bought `units` at `rate`.
+#[derive(Debug, Clone)]
+pub struct DcaPurchase {
+    pub period: usize,
+    pub rate: Decimal,
+    pub spent: Money,
+    pub units: Money,
+}
+
+/// The outcome of buying the same amount every period versus spending the
+/// whole sum at the first rate. Rates are units of the spent currency per
+/// unit of the one bought, like `Forex` rates against the base.
+/// - `invested`: the total spent, the same for both strategies.
+/// - `units` / `lump_sum_units`: what each strategy bought.
+/// - `average_cost`: `invested` / `units`, the rate paid on average; the
+///   lump sum's is the first rate.
+/// - `final_value` / `lump_sum_value`: the units valued at `final_rate`,
+///   the last rate of the series, in the spent currency.
+///
+/// Amounts are unrounded.
+#[derive(Debug, Clone)]
+pub struct DcaSimulation {
+    pub purchases: Vec<DcaPurchase>,
+    pub invested: Money,
+    pub units: Money,
+    pub average_cost: Decimal,
+    pub final_rate: Decimal,
+    pub final_value: Money,
+    pub lump_sum_units: Money,
+    pub lump_sum_value: Money,
+}
+
+impl DcaSimulation {
+    /// Spend `amount` on currency `to` once per rate in `rates`.
+    pub fn run(amount: &Money, to: &str, rates: &[Decimal]) -> Result<Self, DcaError> {
+        if amount.amount <= Decimal::ZERO {
+            return Err(DcaError::NonPositiveAmount);
+        }
+        if amount.currency == to {
+            return Err(DcaError::SameCurrency(to.to_string()));
+        }
+        let (Some(&first), Some(&last)) = (rates.first(), rates.last()) else {
+            return Err(DcaError::EmptySeries);
+        };
+        let bought = |spent: Decimal, rate: Decimal| spent.checked_div(rate).ok_or(DcaError::Overflow);
+
+        let mut purchases = Vec::with_capacity(rates.len());
+        let (mut invested, mut units) = (Decimal::ZERO, Decimal::ZERO);
+        for (i, &rate) in rates.iter().enumerate() {
+            if rate <= Decimal::ZERO {
+                return Err(DcaError::NonPositiveRate(i + 1));
+            }
+            let got = bought(amount.amount, rate)?;
+            invested = invested.checked_add(amount.amount).ok_or(DcaError::Overflow)?;
+            units = units.checked_add(got).ok_or(DcaError::Overflow)?;
+            purchases.push(DcaPurchase { period: i + 1, rate, spent: amount.clone(), units: Money::new(got, to) });
+        }
+        let lump_sum_units = bought(invested, first)?;
+        let value = |held: Decimal| held.checked_mul(last).ok_or(DcaError::Overflow);
+        let spent_in = |v: Decimal| Money::new(v, &amount.currency);
+        Ok(Self {
+            purchases,
+            invested: spent_in(invested),
+            units: Money::new(units, to),
+            average_cost: invested.checked_div(units).ok_or(DcaError::Overflow)?,
+            final_rate: last,
+            final_value: spent_in(value(units)?),
+            lump_sum_units: Money::new(lump_sum_units, to),
+            lump_sum_value: spent_in(value(lump_sum_units)?),
+        })
+    }
+
+    /// How much more (positive) or less the periodic purchases are worth at
+    /// the end than the lump sum.
+    pub fn advantage(&self) -> Money {
+        Money::new(self.final_value.amount - self.lump_sum_value.amount, &self.final_value.currency)
+    }
+}
@@ -0,0 +1,415 @@
+use std::cmp::Ordering;
+use std::fmt;
+use std::iter::Sum;
+use std::ops::{Add, AddAssign, Div, Mul, Neg, Sub, SubAssign};
+use std::str::FromStr;
+
+/// Number of fractional digits kept by every `Decimal`.
+pub const SCALE: u32 = 12;
+const UNIT: i128 = 10i128.pow(SCALE);
+
+/// How `round_dp_with_strategy` resolves digits beyond the kept precision.
+/// Names follow `rust_decimal::RoundingStrategy`.
+/// - `MidpointNearestEven`: banker's rounding (2.345 → 2.34, 2.355 → 2.36).
+/// - `MidpointAwayFromZero`: schoolbook rounding (2.345 → 2.35).
+/// - `ToZero`: truncate.
+/// - `AwayFromZero`: any remainder rounds up in magnitude.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum RoundingStrategy {
+    MidpointNearestEven,
+    #[default]
+    MidpointAwayFromZero,
+    ToZero,
+    AwayFromZero,
+}
+
+impl RoundingStrategy {
+    /// Parse a strategy by its variant name, e.g. "MidpointNearestEven".
+    pub fn parse(name: &str) -> Option<Self> {
+        match name {
+            "MidpointNearestEven" => Some(RoundingStrategy::MidpointNearestEven),
+            "MidpointAwayFromZero" => Some(RoundingStrategy::MidpointAwayFromZero),
+            "ToZero" => Some(RoundingStrategy::ToZero),
+            "AwayFromZero" => Some(RoundingStrategy::AwayFromZero),
+            _ => None,
+        }
+    }
+}
+
+/// Fixed-point decimal number used for all money, rate, and interest math.
+/// Values are stored as an `i128` count of 10^-12 units, so decimal inputs
+/// like "0.10" or "58.1130" are represented exactly and repeated additions
+/// never accumulate binary floating-point error. Products and quotients are
+/// rounded half away from zero to 12 fractional digits.
+///
+/// The API mirrors the subset of `rust_decimal::Decimal` this crate needs,
+/// e.g. `Decimal::new(581130, 4)` is 58.1130.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Decimal {
+    raw: i128,
+}
+
+impl Decimal {
+    pub const ZERO: Decimal = Decimal { raw: 0 };
+    pub const ONE: Decimal = Decimal { raw: UNIT };
+
+    /// Build `num × 10^-scale` (e.g. `Decimal::new(5, 2)` is 0.05).
+    /// Panics if `scale` exceeds the supported 12 fractional digits.
+    pub fn new(num: i64, scale: u32) -> Self {
+        assert!(scale <= SCALE, "scale must be <= {}", SCALE);
+        Self { raw: i128::from(num) * 10i128.pow(SCALE - scale) }
+    }
+
+    /// Convert from `f64`, rounding to 12 fractional digits.
+    /// Returns `None` for NaN/infinite values.
+    pub fn from_f64(value: f64) -> Option<Self> {
+        if !value.is_finite() {
+            return None;
+        }
+        format!("{:.*}", SCALE as usize, value).parse().ok()
+    }
+
+    /// Nearest `f64`; only for display or statistics, never for ledger math.
+    pub fn to_f64(self) -> f64 {
+        self.raw as f64 / UNIT as f64
+    }
+
+    /// Round to `dp` fractional digits, half away from zero.
+    pub fn round_dp(self, dp: u32) -> Self {
+        self.round_dp_with_strategy(dp, RoundingStrategy::MidpointAwayFromZero)
+    }
+
+    /// Round to `dp` fractional digits using `strategy`.
+    pub fn round_dp_with_strategy(self, dp: u32, strategy: RoundingStrategy) -> Self {
+        if dp >= SCALE {
+            return self;
+        }
+        let step = 10i128.pow(SCALE - dp);
+        let q = self.raw / step;
+        let r = self.raw % step;
+        let away = if self.raw < 0 { -1 } else { 1 };
+        let twice = r.unsigned_abs() * 2;
+        let half = step.unsigned_abs();
+        let bump = match strategy {
+            _ if r == 0 => false,
+            RoundingStrategy::ToZero => false,
+            RoundingStrategy::AwayFromZero => true,
+            RoundingStrategy::MidpointAwayFromZero => twice >= half,
+            RoundingStrategy::MidpointNearestEven => twice > half || (twice == half && q % 2 != 0),
+        };
+        Self { raw: (q + if bump { away } else { 0 }) * step }
+    }
+
+    /// Integer count of 10^-dp units (e.g. centavos for `dp = 2`), rounding
+    /// half away from zero. Returns `None` if the result overflows `i64`.
+    pub fn to_minor_units(self, dp: u32) -> Option<i64> {
+        let dp = dp.min(SCALE);
+        let rounded = self.round_dp(dp);
+        i64::try_from(rounded.raw / 10i128.pow(SCALE - dp)).ok()
+    }
+
+    /// Drop the fractional part (round toward zero).
+    pub fn trunc(self) -> Self {
+        Self { raw: self.raw / UNIT * UNIT }
+    }
+
+    pub fn abs(self) -> Self {
+        Self { raw: self.raw.abs() }
+    }
+
+    pub fn is_zero(self) -> bool {
+        self.raw == 0
+    }
+
+    pub fn is_sign_negative(self) -> bool {
+        self.raw < 0
+    }
+
+    pub fn is_sign_positive(self) -> bool {
+        self.raw > 0
+    }
+
+    pub fn min(self, other: Self) -> Self {
+        Ord::min(self, other)
+    }
+
+    pub fn max(self, other: Self) -> Self {
+        Ord::max(self, other)
+    }
+
+    /// Add, returning `None` instead of overflowing.
+    pub fn checked_add(self, rhs: Self) -> Option<Self> {
+        self.raw.checked_add(rhs.raw).map(|raw| Self { raw })
+    }
+
+    /// Subtract, returning `None` instead of overflowing.
+    pub fn checked_sub(self, rhs: Self) -> Option<Self> {
+        self.raw.checked_sub(rhs.raw).map(|raw| Self { raw })
+    }
+
+    /// Multiply, returning `None` instead of panicking on overflow.
+    pub fn checked_mul(self, rhs: Self) -> Option<Self> {
+        signed_mul_div(self.raw, rhs.raw, UNIT).map(|raw| Self { raw })
+    }
+
+    /// Divide, returning `None` instead of panicking on a zero divisor or
+    /// overflow.
+    pub fn checked_div(self, rhs: Self) -> Option<Self> {
+        if rhs.is_zero() {
+            return None;
+        }
+        signed_mul_div(self.raw, UNIT, rhs.raw).map(|raw| Self { raw })
+    }
+
+    /// Raise to a non-negative integer power by repeated squaring.
+    pub fn powu(self, mut exp: u64) -> Self {
+        let mut base = self;
+        let mut acc = Decimal::ONE;
+        while exp > 0 {
+            if exp & 1 == 1 {
+                acc = acc * base;
+            }
+            base = base * base;
+            exp >>= 1;
+        }
+        acc
+    }
+
+    /// `powu`, returning `None` instead of panicking on overflow.
+    pub fn checked_powu(self, mut exp: u64) -> Option<Self> {
+        let mut base = self;
+        let mut acc = Decimal::ONE;
+        while exp > 0 {
+            if exp & 1 == 1 {
+                acc = acc.checked_mul(base)?;
+            }
+            exp >>= 1;
+            if exp > 0 {
+                base = base.checked_mul(base)?;
+            }
+        }
+        Some(acc)
+    }
+}
+
+macro_rules! impl_from_int {
+    ($($t:ty),*) => {$(
+        impl From<$t> for Decimal {
+            fn from(v: $t) -> Self {
+                Self { raw: v as i128 * UNIT }
+            }
+        }
+    )*};
+}
+
+impl_from_int!(i32, i64, u32, u64, usize);
+
+impl Add for Decimal {
+    type Output = Decimal;
+    fn add(self, rhs: Self) -> Self {
+        Self { raw: self.raw + rhs.raw }
+    }
+}
+
+impl Sub for Decimal {
+    type Output = Decimal;
+    fn sub(self, rhs: Self) -> Self {
+        Self { raw: self.raw - rhs.raw }
+    }
+}
+
+impl Mul for Decimal {
+    type Output = Decimal;
+    /// Panics on overflow; use `checked_mul` to handle it.
+    fn mul(self, rhs: Self) -> Self {
+        self.checked_mul(rhs).expect("Decimal overflow")
+    }
+}
+
+impl Div for Decimal {
+    type Output = Decimal;
+    /// Panics on division by zero or overflow, like the primitive integer
+    /// types.
+    fn div(self, rhs: Self) -> Self {
+        assert!(!rhs.is_zero(), "Decimal division by zero");
+        self.checked_div(rhs).expect("Decimal overflow")
+    }
+}
+
+impl Neg for Decimal {
+    type Output = Decimal;
+    fn neg(self) -> Self {
+        Self { raw: -self.raw }
+    }
+}
+
+impl AddAssign for Decimal {
+    fn add_assign(&mut self, rhs: Self) {
+        self.raw += rhs.raw;
+    }
+}
+
+impl SubAssign for Decimal {
+    fn sub_assign(&mut self, rhs: Self) {
+        self.raw -= rhs.raw;
+    }
+}
+
+impl Sum for Decimal {
+    fn sum<I: Iterator<Item = Decimal>>(iter: I) -> Self {
+        iter.fold(Decimal::ZERO, Add::add)
+    }
+}
+
+impl<'a> Sum<&'a Decimal> for Decimal {
+    fn sum<I: Iterator<Item = &'a Decimal>>(iter: I) -> Self {
+        iter.copied().sum()
+    }
+}
+
+/// Error returned when a string is not a plain decimal number.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseDecimalError;
+
+impl fmt::Display for ParseDecimalError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "invalid decimal number")
+    }
+}
+
+impl std::error::Error for ParseDecimalError {}
+
+impl FromStr for Decimal {
+    type Err = ParseDecimalError;
+
+    /// Parse `[+-]digits[.digits]`. Extra fractional digits beyond 12 are
+    /// rounded half away from zero.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim();
+        let (negative, body) = match s.as_bytes().first() {
+            Some(b'-') => (true, &s[1..]),
+            Some(b'+') => (false, &s[1..]),
+            _ => (false, s),
+        };
+        let (int_part, frac_part) = body.split_once('.').unwrap_or((body, ""));
+        if int_part.is_empty() && frac_part.is_empty() {
+            return Err(ParseDecimalError);
+        }
+        if !int_part.bytes().chain(frac_part.bytes()).all(|b| b.is_ascii_digit()) {
+            return Err(ParseDecimalError);
+        }
+
+        let mut raw: i128 = 0;
+        for b in int_part.bytes() {
+            raw = raw
+                .checked_mul(10)
+                .and_then(|r| r.checked_add(i128::from(b - b'0')))
+                .ok_or(ParseDecimalError)?;
+        }
+        raw = raw.checked_mul(UNIT).ok_or(ParseDecimalError)?;
+
+        let mut place = UNIT;
+        for (i, b) in frac_part.bytes().enumerate() {
+            let digit = i128::from(b - b'0');
+            if i < SCALE as usize {
+                place /= 10;
+                raw += digit * place;
+            } else {
+                if digit >= 5 {
+                    raw += 1;
+                }
+                break;
+            }
+        }
+        Ok(Self { raw: if negative { -raw } else { raw } })
+    }
+}
+
+impl fmt::Display for Decimal {
+    /// Formats with the requested precision (`{:.2}`), otherwise with
+    /// trailing fractional zeros trimmed. Width, fill, and alignment flags
+    /// are honored.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let value = match f.precision() {
+            Some(p) => self.round_dp(p as u32),
+            None => *self,
+        };
+        let abs = value.raw.unsigned_abs();
+        let int_part = abs / UNIT as u128;
+        let frac = format!("{:0width$}", abs % UNIT as u128, width = SCALE as usize);
+        let frac = match f.precision() {
+            Some(p) => {
+                let mut digits = frac[..(p.min(SCALE as usize))].to_string();
+                digits.extend(std::iter::repeat_n('0', p.saturating_sub(SCALE as usize)));
+                digits
+            }
+            None => frac.trim_end_matches('0').to_string(),
+        };
+        let body = if frac.is_empty() {
+            int_part.to_string()
+        } else {
+            format!("{}.{}", int_part, frac)
+        };
+        f.pad_integral(!value.is_sign_negative(), "", &body)
+    }
+}
+
+/// Compute `a * b / d` rounded half away from zero, using a 256-bit
+/// intermediate product so large balances times rates cannot overflow early.
+/// Returns `None` if the result does not fit.
+fn signed_mul_div(a: i128, b: i128, d: i128) -> Option<i128> {
+    let negative = (a < 0) ^ (b < 0) ^ (d < 0);
+    let (a, b, d) = (a.unsigned_abs(), b.unsigned_abs(), d.unsigned_abs());
+    let (mut q, r) = match a.checked_mul(b) {
+        Some(p) => (p / d, p % d),
+        None => wide_mul_div(a, b, d)?,
+    };
+    if r >= d - r {
+        q = q.checked_add(1)?;
+    }
+    let q = i128::try_from(q).ok()?;
+    Some(if negative { -q } else { q })
+}
+
+fn wide_mul_div(a: u128, b: u128, d: u128) -> Option<(u128, u128)> {
+    const MASK: u128 = u64::MAX as u128;
+    let (a1, a0) = (a >> 64, a & MASK);
+    let (b1, b0) = (b >> 64, b & MASK);
+
+    let lo = a0 * b0;
+    let mid1 = a0 * b1;
+    let mid2 = a1 * b0;
+    let hi = a1 * b1;
+
+    let (mid, mid_carry) = mid1.overflowing_add(mid2);
+    let (lo, lo_carry) = lo.overflowing_add(mid << 64);
+    let hi = hi + (mid >> 64) + ((mid_carry as u128) << 64) + lo_carry as u128;
+    if hi >= d {
+        return None;
+    }
+
+    let mut rem: u128 = hi;
+    let mut q: u128 = 0;
+    for i in (0..128).rev() {
+        let carry = rem >> 127;
+        rem = (rem << 1) | ((lo >> i) & 1);
+        q <<= 1;
+        if carry == 1 || rem >= d {
+            rem = rem.wrapping_sub(d);
+            q |= 1;
+        }
+    }
+    Some((q, rem))
+}
+
+impl PartialEq<i64> for Decimal {
+    fn eq(&self, other: &i64) -> bool {
+        *self == Decimal::from(*other)
+    }
+}
+
+impl PartialOrd<i64> for Decimal {
+    fn partial_cmp(&self, other: &i64) -> Option<Ordering> {
+        Some(self.cmp(&Decimal::from(*other)))
+    }
+}
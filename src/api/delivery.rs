@@ -0,0 +1,142 @@
+use std::fs;
+use std::io::{self, Write};
+use std::path::PathBuf;
+
+use crate::api::statement::{file_stem, Statement};
+
+#[cfg(feature = "smtp")]
+pub use smtp::SmtpDelivery;
+
+/// A channel that sends printable statements to account holders. Implement
+/// it to add a channel (post, a portal upload) without touching the bank:
+/// subscribe it to an `EventBus`, which hands it every month-end statement
+/// the bank issues.
+pub trait StatementDelivery: Send {
+    /// Deliver one statement. An error is reported by whoever drives the
+    /// bus; the statement is not offered again.
+    fn deliver(&mut self, statement: &Statement) -> io::Result<()>;
+}
+
+/// Writes each statement as HTML to a directory (created if missing),
+/// named `{id}-{name}-{start}-{end}.html`. A statement delivered again
+/// replaces the earlier file.
+#[derive(Debug)]
+pub struct FileDelivery {
+    dir: PathBuf,
+}
+
+impl FileDelivery {
+    pub fn new(dir: impl Into<PathBuf>) -> Self {
+        Self { dir: dir.into() }
+    }
+}
+
+impl StatementDelivery for FileDelivery {
+    fn deliver(&mut self, statement: &Statement) -> io::Result<()> {
+        fs::create_dir_all(&self.dir)?;
+        let name = format!("{}-{}-{}.html", file_stem(statement.account_id, &statement.account), statement.start, statement.end);
+        let mut out = io::BufWriter::new(fs::File::create(self.dir.join(name))?);
+        statement.write_html(&mut out)?;
+        out.flush()
+    }
+}
+
+#[cfg(feature = "smtp")]
+mod smtp {
+    use std::io::{self, BufRead, BufReader, Write};
+    use std::net::TcpStream;
+    use std::time::Duration;
+
+    use super::StatementDelivery;
+    use crate::api::statement::Statement;
+
+    /// Connect, write, and read timeout for one conversation.
+    const TIMEOUT: Duration = Duration::from_secs(10);
+
+    /// E-mails each statement as an HTML message to its holder's contact
+    /// address over plain SMTP (no TLS or authentication), as a local relay
+    /// accepts it; the relay adds the `Date` header. Statements without a
+    /// holder whose contact is an e-mail address are skipped.
+    #[derive(Debug)]
+    pub struct SmtpDelivery {
+        server: String,
+        from: String,
+    }
+
+    impl SmtpDelivery {
+        /// `server` is `host:port`; `from` is the sender address.
+        pub fn new(server: &str, from: &str) -> Self {
+            Self { server: server.to_string(), from: from.to_string() }
+        }
+
+        fn send(&self, to: &str, statement: &Statement) -> io::Result<()> {
+            let stream = TcpStream::connect(&self.server)?;
+            stream.set_read_timeout(Some(TIMEOUT))?;
+            stream.set_write_timeout(Some(TIMEOUT))?;
+            let mut reader = BufReader::new(stream.try_clone()?);
+            let mut stream = stream;
+            expect(&mut reader, 220)?;
+            command(&mut stream, &mut reader, "HELO rust-forex", 250)?;
+            command(&mut stream, &mut reader, &format!("MAIL FROM:<{}>", self.from), 250)?;
+            command(&mut stream, &mut reader, &format!("RCPT TO:<{}>", to), 250)?;
+            command(&mut stream, &mut reader, "DATA", 354)?;
+            let mut html = Vec::new();
+            statement.write_html(&mut html)?;
+            let mut message = format!(
+                "From: <{}>\r\nTo: <{}>\r\nSubject: {} statement for {}, {} to {}\r\nMIME-Version: 1.0\r\nContent-Type: text/html; charset=utf-8\r\n\r\n",
+                self.from,
+                to,
+                statement.bank,
+                statement.account,
+                statement.start,
+                statement.end
+            );
+            // Dot-stuff lines so none ends the message early.
+            for line in String::from_utf8_lossy(&html).lines() {
+                if line.starts_with('.') {
+                    message.push('.');
+                }
+                message.push_str(line);
+                message.push_str("\r\n");
+            }
+            message.push_str(".\r\n");
+            stream.write_all(message.as_bytes())?;
+            expect(&mut reader, 250)?;
+            command(&mut stream, &mut reader, "QUIT", 221)
+        }
+    }
+
+    impl StatementDelivery for SmtpDelivery {
+        fn deliver(&mut self, statement: &Statement) -> io::Result<()> {
+            match statement.contact.as_deref().filter(|c| c.contains('@')) {
+                Some(to) => self.send(to.trim(), statement),
+                None => Ok(()),
+            }
+        }
+    }
+
+    /// Send one command line and expect `code` in reply.
+    fn command(stream: &mut TcpStream, reader: &mut impl BufRead, line: &str, code: u16) -> io::Result<()> {
+        stream.write_all(format!("{}\r\n", line).as_bytes())?;
+        expect(reader, code)
+    }
+
+    /// Read a reply, following continuation lines (`250-...`), and fail
+    /// unless its code is `code`.
+    fn expect(reader: &mut impl BufRead, code: u16) -> io::Result<()> {
+        loop {
+            let mut line = String::new();
+            if reader.read_line(&mut line)? == 0 {
+                return Err(io::Error::new(io::ErrorKind::UnexpectedEof, "SMTP server closed the connection"));
+            }
+            let line = line.trim_end();
+            if line.as_bytes().get(3) == Some(&b'-') {
+                continue;
+            }
+            return match line.get(..3).and_then(|c| c.parse::<u16>().ok()) {
+                Some(got) if got == code => Ok(()),
+                _ => Err(io::Error::other(format!("SMTP server replied {} (expected {})", line, code))),
+            };
+        }
+    }
+}
@@ -0,0 +1,71 @@
+use crate::api::decimal::{Decimal, RoundingStrategy};
+use crate::api::money::Money;
+
+/// Bill and coin values in circulation for `code`, largest first; empty
+/// for currencies without a built-in table.
+pub fn default_denominations(code: &str) -> Vec<Decimal> {
+    let cents: &[i64] = match code {
+        "PHP" => &[100_000, 50_000, 20_000, 10_000, 5_000, 2_000, 1_000, 500, 100, 25, 5, 1],
+        "USD" => &[10_000, 5_000, 2_000, 1_000, 500, 100, 25, 10, 5, 1],
+        "JPY" => &[1_000_000, 500_000, 200_000, 100_000, 50_000, 10_000, 5_000, 1_000, 500, 100],
+        "GBP" => &[5_000, 2_000, 1_000, 500, 200, 100, 50, 20, 10, 5, 2, 1],
+        "EUR" => &[50_000, 20_000, 10_000, 5_000, 2_000, 1_000, 500, 200, 100, 50, 20, 10, 5, 2, 1],
+        "CNY" => &[10_000, 5_000, 2_000, 1_000, 500, 100, 50, 10],
+        _ => &[],
+    };
+    cents.iter().map(|c| Decimal::new(*c, 2)).collect()
+}
+
+/// Sort `denominations` largest first, dropping values of zero or less and
+/// repeats, as `CashBreakdown::new` expects.
+pub fn normalize(mut denominations: Vec<Decimal>) -> Vec<Decimal> {
+    denominations.retain(|d| *d > Decimal::ZERO);
+    denominations.sort_by(|a, b| b.cmp(a));
+    denominations.dedup();
+    denominations
+}
+
+/// `count` bills or coins of `value`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct DenominationCount {
+    pub value: Decimal,
+    pub count: u64,
+}
+
+/// An amount of cash split into bills and coins, as a teller would count
+/// it out. `pieces` lists the denominations used, largest first;
+/// `remainder` is what no denomination covers (below the smallest coin,
+/// or all of an amount of zero or less).
+#[derive(Debug, Clone)]
+pub struct CashBreakdown {
+    pub amount: Money,
+    pub pieces: Vec<DenominationCount>,
+    pub remainder: Money,
+}
+
+impl CashBreakdown {
+    /// Split `amount` greedily over `denominations` (largest first, see
+    /// `normalize`): as many of the largest as fit, then the next, and so
+    /// on. Greedy counting gives the fewest pieces for every built-in
+    /// table.
+    pub fn new(amount: &Money, denominations: &[Decimal]) -> Self {
+        let mut left = amount.amount;
+        let mut pieces = Vec::new();
+        if left > Decimal::ZERO {
+            for value in denominations {
+                let count = (left / *value).round_dp_with_strategy(0, RoundingStrategy::ToZero);
+                // A count too large for an i64 is left in the remainder.
+                if let Some(whole) = count.to_minor_units(0).and_then(|c| u64::try_from(c).ok()).filter(|c| *c > 0) {
+                    left -= count * *value;
+                    pieces.push(DenominationCount { value: *value, count: whole });
+                }
+            }
+        }
+        CashBreakdown { amount: amount.clone(), pieces, remainder: Money::new(left, &amount.currency) }
+    }
+
+    /// Total number of bills and coins.
+    pub fn piece_count(&self) -> u64 {
+        self.pieces.iter().map(|p| p.count).sum()
+    }
+}
@@ -0,0 +1,178 @@
+use std::fmt;
+use std::io;
+
+use crate::api::account::AccountError;
+use crate::api::alert::AlertError;
+use crate::api::bank::BankError;
+use crate::api::dca::DcaError;
+use crate::api::forex::ForexError;
+use crate::api::forward::ForwardError;
+use crate::api::limit_order::LimitOrderError;
+use crate::api::loan::LoanError;
+use crate::api::market::MarketError;
+use crate::api::money::CurrencyMismatch;
+use crate::api::monte_carlo::MonteCarloError;
+use crate::api::paydown::PaydownError;
+use crate::api::scenario::ScenarioError;
+use crate::api::service_charge::ServiceChargeError;
+use crate::api::standing_order::StandingOrderError;
+use crate::api::sweep::SweepError;
+
+/// Top-level error for the crate: every domain error converts into it with
+/// `?`, so callers mixing forex, account, bank, and snapshot operations can
+/// use a single `Result<T, Error>`.
+#[derive(Debug)]
+pub enum Error {
+    Forex(ForexError),
+    Account(AccountError),
+    Bank(BankError),
+    /// A what-if simulation was refused.
+    Simulation(DcaError),
+    /// A scenario comparison was refused.
+    Scenario(ScenarioError),
+    /// A market simulation was refused.
+    Market(MarketError),
+    /// A loan paydown comparison was refused.
+    Paydown(PaydownError),
+    /// A Monte Carlo simulation was refused.
+    MonteCarlo(MonteCarloError),
+    /// Reading or writing a snapshot failed.
+    Io(io::Error),
+}
+
+/// `Result` alias using the crate-wide `Error`.
+pub type Result<T> = std::result::Result<T, Error>;
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::Forex(e) => write!(f, "{}", e),
+            Error::Account(e) => write!(f, "{}", e),
+            Error::Bank(e) => write!(f, "{}", e),
+            Error::Simulation(e) => write!(f, "{}", e),
+            Error::Scenario(e) => write!(f, "{}", e),
+            Error::Market(e) => write!(f, "{}", e),
+            Error::Paydown(e) => write!(f, "{}", e),
+            Error::MonteCarlo(e) => write!(f, "{}", e),
+            Error::Io(e) => write!(f, "{}", e),
+        }
+    }
+}
+
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Error::Forex(e) => Some(e),
+            Error::Account(e) => Some(e),
+            Error::Bank(e) => Some(e),
+            Error::Simulation(e) => Some(e),
+            Error::Scenario(e) => Some(e),
+            Error::Market(e) => Some(e),
+            Error::Paydown(e) => Some(e),
+            Error::MonteCarlo(e) => Some(e),
+            Error::Io(e) => Some(e),
+        }
+    }
+}
+
+impl From<ForexError> for Error {
+    fn from(e: ForexError) -> Self {
+        Error::Forex(e)
+    }
+}
+
+impl From<AccountError> for Error {
+    fn from(e: AccountError) -> Self {
+        Error::Account(e)
+    }
+}
+
+impl From<CurrencyMismatch> for Error {
+    fn from(e: CurrencyMismatch) -> Self {
+        Error::Account(e.into())
+    }
+}
+
+impl From<DcaError> for Error {
+    fn from(e: DcaError) -> Self {
+        Error::Simulation(e)
+    }
+}
+
+impl From<ScenarioError> for Error {
+    fn from(e: ScenarioError) -> Self {
+        Error::Scenario(e)
+    }
+}
+
+impl From<MarketError> for Error {
+    fn from(e: MarketError) -> Self {
+        Error::Market(e)
+    }
+}
+
+impl From<PaydownError> for Error {
+    fn from(e: PaydownError) -> Self {
+        Error::Paydown(e)
+    }
+}
+
+impl From<MonteCarloError> for Error {
+    fn from(e: MonteCarloError) -> Self {
+        Error::MonteCarlo(e)
+    }
+}
+
+impl From<BankError> for Error {
+    fn from(e: BankError) -> Self {
+        Error::Bank(e)
+    }
+}
+
+impl From<LoanError> for Error {
+    fn from(e: LoanError) -> Self {
+        Error::Bank(e.into())
+    }
+}
+
+impl From<ForwardError> for Error {
+    fn from(e: ForwardError) -> Self {
+        Error::Bank(e.into())
+    }
+}
+
+impl From<LimitOrderError> for Error {
+    fn from(e: LimitOrderError) -> Self {
+        Error::Bank(e.into())
+    }
+}
+
+impl From<StandingOrderError> for Error {
+    fn from(e: StandingOrderError) -> Self {
+        Error::Bank(e.into())
+    }
+}
+
+impl From<AlertError> for Error {
+    fn from(e: AlertError) -> Self {
+        Error::Bank(e.into())
+    }
+}
+
+impl From<ServiceChargeError> for Error {
+    fn from(e: ServiceChargeError) -> Self {
+        Error::Bank(e.into())
+    }
+}
+
+impl From<SweepError> for Error {
+    fn from(e: SweepError) -> Self {
+        Error::Bank(e.into())
+    }
+}
+
+impl From<io::Error> for Error {
+    fn from(e: io::Error) -> Self {
+        Error::Io(e)
+    }
+}
@@ -0,0 +1,118 @@
+use std::fmt;
+
+use crate::api::account::TransactionType;
+use crate::api::alert::Alert;
+use crate::api::decimal::Decimal;
+use crate::api::money::Money;
+
+/// Every `BankEvent::kind`, in declaration order.
+pub const EVENT_KINDS: [&str; 12] = [
+    "account_opened",
+    "transaction_posted",
+    "transfer_completed",
+    "interest_posted",
+    "rate_changed",
+    "large_transaction_flagged",
+    "approval_requested",
+    "approval_rejected",
+    "transactions_imported",
+    "session_started",
+    "session_ended",
+    "alert_raised",
+];
+
+/// How many undrained events a `Bank` keeps; older ones are dropped first.
+pub const EVENT_LIMIT: usize = 1000;
+
+/// A change to the bank, recorded by the `Bank` operation that made it and
+/// collected by observers (the HTTP event stream, notifiers) with
+/// `Bank::take_events`. Events are not part of a snapshot.
+/// - `TransactionPosted`: a deposit or withdrawal, including reversals;
+///   `amount` is as recorded, rounded to the account's minor unit.
+/// - `InterestPosted`: `amount` is negative for a carrying charge at a
+///   negative rate.
+/// - `LargeTransactionFlagged`: an entry added to the review queue; `id` is
+///   its `FlaggedTransaction` id.
+/// - `ApprovalRequested` / `ApprovalRejected`: a withdrawal or transfer
+///   held for approval, and an admin's refusal of it with the reason; `id`
+///   is its `PendingTransaction` id. An approved one posts as usual.
+/// - `TransactionsImported`: a `Bank::bulk_load`, in place of one
+///   `TransactionPosted` per row; `rejected` rows were skipped.
+/// - `SessionStarted` / `SessionEnded`: a front end logged a customer in or
+///   out (see `Bank::log_in`), so the event log shows whose session the
+///   operations between them belong to.
+/// - `AlertRaised`: one of the account's alerts came due; raised by the
+///   event bus after the events that caused it (see `alert::evaluate`)
+///   rather than by a `Bank` operation.
+#[derive(Debug, Clone, PartialEq)]
+pub enum BankEvent {
+    AccountOpened { account: String, currency: String },
+    TransactionPosted { account: String, tx_type: TransactionType, amount: Money, balance: Money, memo: String },
+    TransferCompleted { from: String, to: String, debited: Money, credited: Money },
+    InterestPosted { account: String, amount: Money, balance: Money },
+    RateChanged { code: String, old: Decimal, new: Decimal },
+    LargeTransactionFlagged { id: usize, account: String, amount: Money },
+    ApprovalRequested { id: usize, account: String, amount: Money },
+    ApprovalRejected { id: usize, account: String, reason: String },
+    TransactionsImported { account: String, imported: usize, rejected: usize, balance: Money },
+    SessionStarted { customer_id: usize, customer: String },
+    SessionEnded { customer_id: usize, customer: String },
+    AlertRaised { account: String, alert: Alert },
+}
+
+impl BankEvent {
+    /// Stable snake_case name for the event, e.g. "rate_changed".
+    pub fn kind(&self) -> &'static str {
+        match self {
+            BankEvent::AccountOpened { .. } => "account_opened",
+            BankEvent::TransactionPosted { .. } => "transaction_posted",
+            BankEvent::TransferCompleted { .. } => "transfer_completed",
+            BankEvent::InterestPosted { .. } => "interest_posted",
+            BankEvent::RateChanged { .. } => "rate_changed",
+            BankEvent::LargeTransactionFlagged { .. } => "large_transaction_flagged",
+            BankEvent::ApprovalRequested { .. } => "approval_requested",
+            BankEvent::ApprovalRejected { .. } => "approval_rejected",
+            BankEvent::TransactionsImported { .. } => "transactions_imported",
+            BankEvent::SessionStarted { .. } => "session_started",
+            BankEvent::SessionEnded { .. } => "session_ended",
+            BankEvent::AlertRaised { .. } => "alert_raised",
+        }
+    }
+}
+
+impl fmt::Display for BankEvent {
+    /// One-line English description, e.g. "deposit of 100 PHP to Alice
+    /// (balance 250 PHP)".
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            BankEvent::AccountOpened { account, currency } => write!(f, "account {} opened in {}", account, currency),
+            BankEvent::TransactionPosted { account, tx_type, amount, balance, .. } => {
+                let (kind, direction) = match tx_type {
+                    TransactionType::Deposit => ("deposit", "to"),
+                    TransactionType::Withdraw => ("withdrawal", "from"),
+                };
+                write!(f, "{} of {} {} {} (balance {})", kind, amount, direction, account, balance)
+            }
+            BankEvent::TransferCompleted { from, to, debited, credited } => {
+                write!(f, "transfer of {} from {} to {} (credited {})", debited, from, to, credited)
+            }
+            BankEvent::InterestPosted { account, amount, balance } => {
+                write!(f, "interest of {} posted to {} (balance {})", amount, account, balance)
+            }
+            BankEvent::RateChanged { code, old, new } => write!(f, "{} rate changed from {} to {}", code, old, new),
+            BankEvent::LargeTransactionFlagged { id, account, amount } => {
+                write!(f, "large transaction of {} on {} flagged for review (ID {})", amount, account, id)
+            }
+            BankEvent::ApprovalRequested { id, account, amount } => {
+                write!(f, "{} from {} held for approval (request {})", amount, account, id)
+            }
+            BankEvent::ApprovalRejected { id, account, reason } => write!(f, "request {} from {} rejected: {}", id, account, reason),
+            BankEvent::TransactionsImported { account, imported, rejected, balance } => {
+                write!(f, "{} transaction(s) imported to {}, {} rejected (balance {})", imported, account, rejected, balance)
+            }
+            BankEvent::SessionStarted { customer_id, customer } => write!(f, "session started for {} (customer {})", customer, customer_id),
+            BankEvent::SessionEnded { customer_id, customer } => write!(f, "session ended for {} (customer {})", customer, customer_id),
+            BankEvent::AlertRaised { account, alert } => write!(f, "alert for {}: {}", account, alert),
+        }
+    }
+}
@@ -0,0 +1,148 @@
+use crate::api::decimal::Decimal;
+use crate::api::forex::{ForexError, RateType};
+use crate::api::money::Money;
+
+/// One band of a `FeeSchedule`: conversions worth at least `from` in the
+/// base currency pay `rate` (a fraction, 0.005 = 0.5%) of what they convert
+/// to, until the next band starts.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FeeTier {
+    pub from: Decimal,
+    pub rate: Decimal,
+}
+
+/// Volume-based conversion fees, e.g. 1% below 10,000, 0.5% from 10,000,
+/// and 0.25% from 100,000. The whole conversion pays the rate of the band
+/// its volume falls in; conversions below the first band pay nothing. An
+/// empty schedule, the default, charges no fees.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct FeeSchedule {
+    tiers: Vec<FeeTier>,
+}
+
+impl FeeSchedule {
+    /// A schedule of `tiers` in any order. Fails with `InvalidFeeTier` if a
+    /// threshold is negative or listed twice, or a rate is not from 0 up
+    /// to, but not including, 1 (100%).
+    pub fn new(mut tiers: Vec<FeeTier>) -> Result<Self, ForexError> {
+        tiers.sort_by_key(|t| t.from);
+        let valid = tiers.iter().all(|t| t.from >= Decimal::ZERO && t.rate >= Decimal::ZERO && t.rate < Decimal::ONE)
+            && tiers.windows(2).all(|w| w[0].from != w[1].from);
+        if !valid {
+            return Err(ForexError::InvalidFeeTier);
+        }
+        Ok(Self { tiers })
+    }
+
+    /// The bands, by threshold.
+    pub fn tiers(&self) -> &[FeeTier] {
+        &self.tiers
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.tiers.is_empty()
+    }
+
+    /// The fee rate for a conversion worth `volume` in the base currency.
+    pub fn rate_for(&self, volume: Decimal) -> Decimal {
+        let at = self.tiers.partition_point(|t| t.from <= volume.abs());
+        at.checked_sub(1).map_or(Decimal::ZERO, |i| self.tiers[i].rate)
+    }
+}
+
+/// A fee rate for one currency pair that replaces the `FeeSchedule` tier
+/// for conversions between them, in either direction, e.g. a wider spread
+/// on an exotic currency. `pair` is the two codes in alphabetical order.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PairSpread {
+    pub pair: (String, String),
+    pub rate: Decimal,
+}
+
+impl PairSpread {
+    /// "EUR/USD".
+    pub fn label(&self) -> String {
+        format!("{}/{}", self.pair.0, self.pair.1)
+    }
+}
+
+/// Where a `Conversion`'s fee rate came from.
+/// - `Schedule`: the `FeeSchedule` tier for its volume (no fee when the
+///   schedule is empty or both sides are one currency).
+/// - `PairSpread`: the `PairSpread` set for its two currencies.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FeeBasis {
+    Schedule,
+    PairSpread,
+}
+
+impl FeeBasis {
+    /// Lowercase name: "schedule" or "pair_spread".
+    pub fn name(&self) -> &'static str {
+        match self {
+            FeeBasis::Schedule => "schedule",
+            FeeBasis::PairSpread => "pair_spread",
+        }
+    }
+}
+
+/// One hop of a conversion's route (see `Forex::route`): `from` converted
+/// at `rate`, in units of `to`'s currency per unit of `from`'s, to `to`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ConversionLeg {
+    pub from: Money,
+    pub to: Money,
+    pub rate: Decimal,
+}
+
+/// An exchange with its fee itemized, from `Forex::exchange`: `source`
+/// converts at `rate` (units of the destination per unit of the source,
+/// from the `rate_type` rates) to `gross`, of which `fee` (`fee_rate` of
+/// it, from `fee_basis`) is kept and `net` paid out. `legs` is the route
+/// from `source` to `gross`, through the base currency unless either side
+/// is the base. All amounts are exact; `Bank::settle_conversion` rounds
+/// them.
+#[derive(Debug, Clone)]
+pub struct Conversion {
+    pub source: Money,
+    pub rate_type: RateType,
+    pub rate: Decimal,
+    pub gross: Money,
+    pub fee_rate: Decimal,
+    pub fee_basis: FeeBasis,
+    pub fee: Money,
+    pub net: Money,
+    pub legs: Vec<ConversionLeg>,
+}
+
+/// What a conversion would cost, from `Forex::preview`, without executing
+/// it. `source` would convert to `net` after two costs, both in the
+/// destination currency:
+/// - `margin`: what converting at the `rate_type` rate (`applied_rate`)
+///   gives up against the mid-market transfer rate (`mid_rate`); zero at
+///   transfer rates, and negative if the applied rate is the better one;
+/// - `fee`: `fee_rate` of the converted amount, from `fee_basis`.
+///
+/// Rates are units of the destination per unit of the source, and
+/// `effective_rate` is what the customer gets all in (`net` per unit). All
+/// amounts are exact; `Bank::settle_conversion` rounds them.
+#[derive(Debug, Clone)]
+pub struct ConversionPreview {
+    pub source: Money,
+    pub rate_type: RateType,
+    pub mid_rate: Decimal,
+    pub applied_rate: Decimal,
+    pub margin: Money,
+    pub fee_rate: Decimal,
+    pub fee_basis: FeeBasis,
+    pub fee: Money,
+    pub net: Money,
+    pub effective_rate: Decimal,
+}
+
+impl ConversionPreview {
+    /// The margin and the fee together.
+    pub fn total_cost(&self) -> Money {
+        Money::new(self.margin.amount + self.fee.amount, &self.fee.currency)
+    }
+}
@@ -1,65 +1,1255 @@
-use std::collections::HashMap;
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::fmt;
+use std::marker::PhantomData;
+use std::sync::Arc;
+
+use crate::api::conversion_log::{ConversionFilter, ConversionRecord, DailyTurnover};
+use crate::api::date::{Clock, Date, SystemClock};
+use crate::api::decimal::{Decimal, SCALE};
+use crate::api::denomination::{default_denominations, normalize, CashBreakdown};
+use crate::api::fee::{Conversion, ConversionLeg, ConversionPreview, FeeBasis, FeeSchedule, PairSpread};
+use crate::api::money::Money;
+use crate::api::moving_average::{AveragedClose, MovingAverage};
+use crate::api::rate_stats::{RateStats, RateTrend, TREND_DAYS};
+use crate::api::volatility::{self, RateVolatility};
 
 /// Currency value object used by the Forex catalog.
 /// - `code`: short identifier like "USD", "PHP".
 /// - `name`: human-friendly full name (e.g., "United States Dollar").
-/// - `rate`: price of 1 unit of this currency expressed in the base currency.
+/// - `rate`: price of 1 unit of this currency expressed in the base currency,
+///   the rate account transfers and exchanges convert at.
+/// - `cash_rate`: the price of 1 unit in bills and coins over the counter,
+///   when quoted apart from `rate`; `None` uses `rate` for cash too.
+/// - `decimals`: number of minor-unit decimal places (2 for PHP, 0 for JPY).
+/// - `rate_decimals`: fractional digits `rate` and `cash_rate` are kept to
+///   and shown with; every rate set is rounded to it, half away from zero.
+/// - `symbol`: display symbol such as "₱" or "$", used when formatting amounts.
+/// - `denominations`: bill and coin values, largest first, for counting out
+///   cash (see `Forex::cash_breakdown`); empty if none are known.
+/// - `updated`: when the rate was last set (Unix seconds, UTC); `None` if
+///   unknown, e.g. for currencies restored from an older snapshot.
+/// - `source`: who supplied the rate set at `updated`; `None` if unknown,
+///   likewise.
 #[derive(Debug, Clone)]
 pub struct Currency {
     pub code: String,
     pub name: String,
-    pub rate: f64,
+    pub rate: Decimal,
+    pub cash_rate: Option<Decimal>,
+    pub decimals: u32,
+    pub rate_decimals: u32,
+    pub symbol: String,
+    pub denominations: Vec<Decimal>,
+    pub updated: Option<i64>,
+    pub source: Option<RateSource>,
+}
+
+impl Currency {
+    /// The rate of this currency for `rate_type`.
+    pub fn rate_of(&self, rate_type: RateType) -> Decimal {
+        match rate_type {
+            RateType::Transfer => self.rate,
+            RateType::Cash => self.cash_rate.unwrap_or(self.rate),
+        }
+    }
+
+    /// `rate` written with this currency's `rate_decimals`, e.g. "58.1130".
+    pub fn format_rate(&self, rate: Decimal) -> String {
+        format!("{:.*}", self.rate_decimals as usize, rate)
+    }
+}
+
+/// Which of a currency's rates a conversion uses. Banks quote physical cash
+/// apart from money moved between accounts, usually at a wider spread.
+/// - `Transfer`: `Currency::rate`, for account transfers and exchanges.
+/// - `Cash`: `Currency::cash_rate`, for bills and coins over the counter;
+///   a currency without one falls back to its transfer rate.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum RateType {
+    #[default]
+    Transfer,
+    Cash,
+}
+
+impl RateType {
+    /// Parse "transfer" or "cash" (any case).
+    pub fn parse(s: &str) -> Option<Self> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "transfer" => Some(RateType::Transfer),
+            "cash" => Some(RateType::Cash),
+            _ => None,
+        }
+    }
+
+    /// Lowercase name, as accepted by `parse`.
+    pub fn name(&self) -> &'static str {
+        match self {
+            RateType::Transfer => "transfer",
+            RateType::Cash => "cash",
+        }
+    }
+}
+
+/// Where a currency's current rate came from.
+/// - `Catalog`: the catalog the bank was set up with (`forex.toml` or the
+///   built-in rates) when it does not name a provider.
+/// - `Manual`: entered by hand at the console, command line, or API.
+/// - `Provider`: a named publisher such as "BSP" or "ECB".
+/// - `Replay`: a historical rate file fed in by `RateReplay`.
+/// - `Simulation`: a random walk of `MarketSimulator`.
+/// - `Basket`: priced from a basket's components.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RateSource {
+    Catalog,
+    Manual,
+    Provider(String),
+    Replay,
+    Simulation,
+    Basket,
+}
+
+impl RateSource {
+    /// Parse a source name: "catalog", "manual", "replay", "simulation",
+    /// or "basket" (any case), and any other text as a provider's name.
+    /// Blank text is no source.
+    pub fn parse(s: &str) -> Option<Self> {
+        let s = s.trim();
+        Some(match s.to_ascii_lowercase().as_str() {
+            "" => return None,
+            "catalog" => RateSource::Catalog,
+            "manual" => RateSource::Manual,
+            "replay" => RateSource::Replay,
+            "simulation" => RateSource::Simulation,
+            "basket" => RateSource::Basket,
+            _ => RateSource::Provider(s.to_string()),
+        })
+    }
+
+    /// The name accepted back by `parse`: lowercase, or the provider's
+    /// name as given.
+    pub fn name(&self) -> &str {
+        match self {
+            RateSource::Catalog => "catalog",
+            RateSource::Manual => "manual",
+            RateSource::Provider(name) => name,
+            RateSource::Replay => "replay",
+            RateSource::Simulation => "simulation",
+            RateSource::Basket => "basket",
+        }
+    }
+}
+
+impl fmt::Display for RateSource {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+/// Decimal places a basket's rate is kept to; the quantities behind it are
+/// exact only to the `Decimal` scale, so the sum is rounded to hide the dust.
+pub const BASKET_RATE_DP: u32 = 6;
+
+/// Fractional digits a currency's rate is kept to unless configured
+/// otherwise (see `Currency::rate_decimals`); baskets keep `BASKET_RATE_DP`.
+pub const DEFAULT_RATE_DP: u32 = 6;
+
+/// The most daily closing rates `Forex` keeps per currency, about ten
+/// years' worth; older ones are dropped as new days come in.
+pub const RATE_HISTORY_DAYS: usize = 3_660;
+
+/// A synthetic currency worth a fixed `quantity` of each component currency
+/// per unit, quoted against the base like any other catalog entry. The
+/// quantities are fixed by `Forex::define_basket` so that one unit is worth
+/// one unit of the base currency on that day, split by the given weights;
+/// afterwards the basket's rate follows its components' rates.
+#[derive(Debug, Clone)]
+pub struct Basket {
+    pub code: String,
+    pub components: Vec<(String, Decimal)>,
+}
+
+/// One component of a `BasketQuote`: the `quantity` of `code` in a basket
+/// unit and its `weight`, the fraction of the basket's value it makes up at
+/// today's rates.
+#[derive(Debug, Clone)]
+pub struct BasketShare {
+    pub code: String,
+    pub quantity: Decimal,
+    pub weight: Decimal,
+}
+
+/// A basket at today's rates: its catalog entry and its components' shares.
+/// The weights drift from those it was defined with as rates move.
+#[derive(Debug, Clone)]
+pub struct BasketQuote {
+    pub currency: Currency,
+    pub shares: Vec<BasketShare>,
+}
+
+/// Errors raised by exchange-rate operations.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ForexError {
+    /// The currency code is not in the catalog.
+    UnknownCurrency(String),
+    /// The currency's rate is zero (or, for a new currency, below zero), so
+    /// nothing can be converted into it.
+    ZeroRate(String),
+    /// The base currency's rate is fixed at 1 and cannot be changed.
+    BaseCurrencyRate(String),
+    /// The base currency cannot be removed from the catalog.
+    RetireBaseCurrency(String),
+    /// A currency with this code is already registered.
+    DuplicateCurrency(String),
+    /// Currency codes are three ASCII letters.
+    InvalidCurrencyCode(String),
+    /// A basket's rate follows its components and cannot be set.
+    BasketRate(String),
+    /// The currency (first) is a component of the basket (second).
+    CurrencyInBasket(String, String),
+    /// A basket component is itself a basket, or is listed twice.
+    InvalidBasketComponent(String),
+    /// Basket weights must be positive and add up to 1.
+    BasketWeights,
+    /// A fee tier has a negative or repeated threshold, or a rate outside
+    /// 0 to 100%.
+    InvalidFeeTier,
+    /// A pair spread names one currency twice, or has a rate outside 0 to
+    /// 100%.
+    InvalidSpread,
+    /// Rates can be kept to at most `SCALE` fractional digits.
+    InvalidRatePrecision(u32),
+    /// The currency has rates on record on fewer days than the measure
+    /// needs (given).
+    NotEnoughHistory(String, usize),
+    /// The currency has no closing rate on record from the first date to
+    /// the second.
+    NoRatesInPeriod(String, Date, Date),
+    /// The converted amount is too large to represent.
+    Overflow,
+}
+
+impl fmt::Display for ForexError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ForexError::UnknownCurrency(code) => write!(f, "unknown currency {}", code),
+            ForexError::ZeroRate(code) => write!(f, "exchange rate for {} is not above zero", code),
+            ForexError::BaseCurrencyRate(code) => write!(f, "{} is the base currency; its rate is fixed", code),
+            ForexError::RetireBaseCurrency(code) => write!(f, "{} is the base currency and cannot be retired", code),
+            ForexError::DuplicateCurrency(code) => write!(f, "currency {} is already registered", code),
+            ForexError::InvalidCurrencyCode(code) => write!(f, "'{}' is not a currency code (use three letters, e.g. AUD)", code),
+            ForexError::BasketRate(code) => write!(f, "{} is a basket; its rate follows its components", code),
+            ForexError::CurrencyInBasket(code, basket) => write!(f, "{} is a component of basket {}", code, basket),
+            ForexError::InvalidBasketComponent(code) => write!(f, "{} cannot be a basket component more than once, or be a basket itself", code),
+            ForexError::BasketWeights => write!(f, "basket weights must be positive and add up to 100%"),
+            ForexError::InvalidFeeTier => write!(f, "fee tiers need distinct thresholds of zero or more and rates from 0 up to 100%"),
+            ForexError::InvalidSpread => write!(f, "a pair spread needs two different currencies and a rate from 0 up to 100%"),
+            ForexError::InvalidRatePrecision(dp) => write!(f, "rates can be kept to at most {} decimal places, not {}", SCALE, dp),
+            ForexError::NoRatesInPeriod(code, start, end) => write!(f, "no closing rates on record for {} from {} to {}", code, start, end),
+            ForexError::NotEnoughHistory(code, days) => write!(f, "{} needs rates on record on at least {} days", code, days),
+            ForexError::Overflow => write!(f, "converted amount is out of range"),
+        }
+    }
+}
+
+impl std::error::Error for ForexError {}
+
+/// ISO 4217 minor-unit decimal places for `code`; 2 unless known otherwise.
+pub fn default_decimals(code: &str) -> u32 {
+    match code {
+        "JPY" | "KRW" | "VND" | "IDR" | "CLP" | "ISK" => 0,
+        "BHD" | "KWD" | "OMR" | "JOD" | "TND" => 3,
+        _ => 2,
+    }
+}
+
+/// Conventional display symbol for `code`; falls back to the code itself for
+/// currencies without a well-known symbol.
+pub fn default_symbol(code: &str) -> String {
+    match code {
+        "PHP" => "₱",
+        "USD" => "$",
+        "JPY" | "CNY" => "¥",
+        "GBP" => "£",
+        "EUR" => "€",
+        "KRW" => "₩",
+        "INR" => "₹",
+        _ => code,
+    }
+    .to_string()
 }
 
 /// In-memory Forex calculator and registry of currencies.
 /// This module only handles exchange rates and does not interact with accounts.
-#[derive(Debug)]
+/// Build one with `Forex::builder()`; the base currency is always registered.
+/// `fees` is charged on exchanges (see `exchange`), not on `convert`;
+/// `spreads` replaces its rate for particular currency pairs, keyed by the
+/// pair's codes in alphabetical order.
+/// `conversions` logs the conversions the bank has executed, oldest first;
+/// quotes are not logged. Rates are stamped with the time on `clock`,
+/// which a `Bank` keeps in step with its own. `history` keeps each
+/// currency's closing rate for every day one was set, oldest first, up to
+/// `RATE_HISTORY_DAYS` of them; `carried` marks the closes in it carried
+/// over a non-trading day from an earlier one (see `carry_close`).
+#[derive(Debug, Clone)]
 pub struct Forex {
     catalog: HashMap<String, Currency>,
     base_currency: String,
+    baskets: Vec<Basket>,
+    fees: FeeSchedule,
+    spreads: BTreeMap<(String, String), Decimal>,
+    conversions: Vec<ConversionRecord>,
+    history: BTreeMap<String, Vec<(Date, Decimal)>>,
+    carried: BTreeMap<String, BTreeSet<Date>>,
+    clock: Arc<dyn Clock>,
+}
+
+/// The key of the pair `a`/`b` in `Forex::spreads`, either way round.
+fn pair_key(a: &str, b: &str) -> (String, String) {
+    if a <= b { (a.to_string(), b.to_string()) } else { (b.to_string(), a.to_string()) }
+}
+
+/// Typestate marker: the `ForexBuilder` has no base currency yet.
+#[derive(Debug, Clone, Copy)]
+pub struct NoBase;
+
+/// Typestate marker: the `ForexBuilder` has a base currency and can `build`.
+#[derive(Debug, Clone, Copy)]
+pub struct WithBase;
+
+/// Builder for `Forex`. `build()` only exists once `set_base_currency` has
+/// been called, so a `Forex` without a registered base cannot be created.
+///
+/// Builder-style API note: methods take and return the builder for chaining
+/// (fluent style similar to Java). In Rust, returning it passes ownership
+/// back to the caller so you can write:
+/// `Forex::builder().set_base_currency("PHP", "Philippine Peso").create_currency(...).build()`.
+#[derive(Debug, Clone)]
+pub struct ForexBuilder<B> {
+    catalog: HashMap<String, Currency>,
+    base_currency: String,
+    baskets: Vec<Basket>,
+    fees: FeeSchedule,
+    spreads: BTreeMap<(String, String), Decimal>,
+    clock: Arc<dyn Clock>,
+    state: PhantomData<B>,
+}
+
+impl<B> ForexBuilder<B> {
+    /// Register a currency with a full name and initial rate.
+    /// Its minor-unit decimal places, symbol, and denominations default to
+    /// the ISO 4217 value, the conventional sign, and the bills and coins in
+    /// circulation for `code`; its rate is kept to `DEFAULT_RATE_DP` places.
+    pub fn create_currency(mut self, code: &str, name: &str, rate: Decimal) -> Self {
+        let currency = Currency {
+            code: code.to_string(),
+            name: name.to_string(),
+            rate,
+            cash_rate: None,
+            decimals: default_decimals(code),
+            rate_decimals: DEFAULT_RATE_DP,
+            symbol: default_symbol(code),
+            denominations: default_denominations(code),
+            updated: Some(self.clock.now()),
+            source: Some(RateSource::Catalog),
+        };
+        self.catalog.insert(currency.code.clone(), currency);
+        self
+    }
+
+    /// Stamp rates with the time on `clock` instead of the system clock.
+    /// Call it before registering currencies to stamp those too.
+    pub fn set_clock(mut self, clock: Arc<dyn Clock>) -> Self {
+        self.clock = clock;
+        self
+    }
+
+    /// Override the minor-unit decimal places of `code`.
+    pub fn set_decimals(mut self, code: &str, decimals: u32) -> Self {
+        if let Some(curr) = self.catalog.get_mut(code) {
+            curr.decimals = decimals;
+        }
+        self
+    }
+
+    /// Keep the rates of `code` to `dp` fractional digits (at most `SCALE`);
+    /// `build` rounds them to it.
+    pub fn set_rate_decimals(mut self, code: &str, dp: u32) -> Self {
+        if let Some(curr) = self.catalog.get_mut(code) {
+            curr.rate_decimals = dp.min(SCALE);
+        }
+        self
+    }
+
+    /// Override the display symbol of `code`.
+    pub fn set_symbol(mut self, code: &str, symbol: &str) -> Self {
+        if let Some(curr) = self.catalog.get_mut(code) {
+            curr.symbol = symbol.to_string();
+        }
+        self
+    }
+
+    /// Override the bill and coin values of `code`, in any order.
+    pub fn set_denominations(mut self, code: &str, denominations: Vec<Decimal>) -> Self {
+        if let Some(curr) = self.catalog.get_mut(code) {
+            curr.denominations = normalize(denominations);
+        }
+        self
+    }
+
+    /// Quote `code` in cash at `rate` (`None` = at its transfer rate).
+    pub fn set_cash_rate(mut self, code: &str, rate: Option<Decimal>) -> Self {
+        if let Some(curr) = self.catalog.get_mut(code) {
+            curr.cash_rate = rate;
+        }
+        self
+    }
+
+    /// Override when the rate of `code` was last set (`None` = unknown).
+    pub fn set_updated(mut self, code: &str, updated: Option<i64>) -> Self {
+        if let Some(curr) = self.catalog.get_mut(code) {
+            curr.updated = updated;
+        }
+        self
+    }
+
+    /// Override who supplied the rate of `code` (`None` = unknown); it is
+    /// `RateSource::Catalog` when registered.
+    pub fn set_rate_source(mut self, code: &str, source: Option<RateSource>) -> Self {
+        if let Some(curr) = self.catalog.get_mut(code) {
+            curr.source = source;
+        }
+        self
+    }
+
+    /// Mark the registered currency `code` as a basket of `components`
+    /// (currency, quantity per unit), e.g. when restoring a snapshot. Its
+    /// rate is recomputed by `build`.
+    pub fn create_basket(mut self, code: &str, components: Vec<(String, Decimal)>) -> Self {
+        self.baskets.push(Basket { code: code.to_string(), components });
+        self
+    }
+
+    /// Charge `fees` on exchanges.
+    pub fn set_fee_schedule(mut self, fees: FeeSchedule) -> Self {
+        self.fees = fees;
+        self
+    }
+
+    /// Charge `rate` instead of the fee schedule's on exchanges between `a`
+    /// and `b`. Pairs with an unregistered currency are dropped by `build`.
+    pub fn set_pair_spread(mut self, a: &str, b: &str, rate: Decimal) -> Self {
+        self.spreads.insert(pair_key(a, b), rate);
+        self
+    }
+}
+
+impl ForexBuilder<NoBase> {
+    /// Register `code` as the base currency at a rate of 1. Every other rate
+    /// is the price of one unit of that currency in this one.
+    pub fn set_base_currency(self, code: &str, name: &str) -> ForexBuilder<WithBase> {
+        let built = self.create_currency(code, name, Decimal::ONE);
+        ForexBuilder {
+            catalog: built.catalog,
+            base_currency: code.to_string(),
+            baskets: built.baskets,
+            fees: built.fees,
+            spreads: built.spreads,
+            clock: built.clock,
+            state: PhantomData,
+        }
+    }
+}
+
+impl ForexBuilder<WithBase> {
+    /// Finish the catalog. Rates are rounded to their currency's
+    /// `rate_decimals`. The base currency's rate is pinned to 1 even if it
+    /// was re-registered afterwards, and baskets are priced from their
+    /// components; baskets that are not registered currencies are dropped,
+    /// as are spreads on such pairs.
+    pub fn build(mut self) -> Forex {
+        for curr in self.catalog.values_mut() {
+            curr.rate = curr.rate.round_dp(curr.rate_decimals);
+            curr.cash_rate = curr.cash_rate.map(|r| r.round_dp(curr.rate_decimals));
+        }
+        if let Some(base) = self.catalog.get_mut(&self.base_currency) {
+            base.rate = Decimal::ONE;
+            base.cash_rate = None;
+        }
+        let catalog = &self.catalog;
+        self.baskets.retain(|b| catalog.contains_key(&b.code));
+        self.baskets.sort_by(|a, b| a.code.cmp(&b.code));
+        self.spreads.retain(|(a, b), _| catalog.contains_key(a) && catalog.contains_key(b));
+        let mut forex = Forex {
+            catalog: self.catalog,
+            base_currency: self.base_currency,
+            baskets: self.baskets,
+            fees: self.fees,
+            spreads: self.spreads,
+            conversions: Vec::new(),
+            history: BTreeMap::new(),
+            carried: BTreeMap::new(),
+            clock: self.clock,
+        };
+        for (code, rate) in forex.basket_rates().into_iter().flatten() {
+            if let Some(curr) = forex.catalog.get_mut(&code) {
+                curr.rate = rate;
+                curr.source = Some(RateSource::Basket);
+            }
+        }
+        forex
+    }
 }
 
 impl Forex {
-    /// Builder-style API note: Some methods take and return `Self` for chaining
-    /// (fluent style similar to Java). In Rust, returning `Self` passes
-    /// ownership back to the caller so you can write:
-    /// `Forex::new().create_currency(...).set_base_rate("PHP")`.
-    pub fn new() -> Self {
-        Forex {
+    /// Start building a `Forex` with an empty catalog.
+    pub fn builder() -> ForexBuilder<NoBase> {
+        ForexBuilder {
             catalog: HashMap::new(),
             base_currency: String::new(),
+            baskets: Vec::new(),
+            fees: FeeSchedule::default(),
+            spreads: BTreeMap::new(),
+            clock: Arc::new(SystemClock),
+            state: PhantomData,
         }
     }
 
-    /// Builder method: registers a currency with a full name and initial rate.
-    /// Returns the updated `Forex` so you can chain more calls.
-    pub fn create_currency(mut self, code: &str, name: &str, rate: f64) -> Self {
-        let currency = Currency { code: code.to_string(), name: name.to_string(), rate: rate };
-        self.catalog.insert(currency.code.clone(), currency);
-        self
+    /// Update the exchange rate for an existing currency `code`, rounded to
+    /// its `rate_decimals`, and stamp it with the current time, repricing
+    /// the baskets that hold it. Fails if `code` is the base currency, a
+    /// basket, or is not registered, if the rate rounds to zero, or if a
+    /// basket's new rate overflows. The rate is attributed to
+    /// `RateSource::Manual`; see `set_rate_from`.
+    pub fn set_rate(&mut self, code: &str, rate: Decimal) -> Result<(), ForexError> {
+        self.set_rate_from(code, rate, RateSource::Manual)
     }
 
-    /// Update the exchange rate for an existing currency `code`.
-    /// - If the currency exists, its rate is updated.
-    pub fn set_rate(&mut self, code: &str, rate: f64) {
+    /// `set_rate`, attributing the rate to `source`.
+    pub fn set_rate_from(&mut self, code: &str, rate: Decimal, source: RateSource) -> Result<(), ForexError> {
         if self.base_currency == code {
-            return;
+            return Err(ForexError::BaseCurrencyRate(code.to_string()));
         }
-        if let Some(curr) = self.catalog.get_mut(code) {
-            curr.rate = rate;
+        self.replace_rate(code, rate, Some(self.clock.now()), Some(source))
+    }
+
+    /// Quote the existing currency `code` in cash at `rate`, rounded to its
+    /// `rate_decimals`, or at its transfer rate again when `rate` is `None`,
+    /// and stamp it with the current time as entered by hand. Fails if
+    /// `code` is the base currency, a basket (priced only from its
+    /// components), or is not registered, or if the rate rounds to zero.
+    pub fn set_cash_rate(&mut self, code: &str, rate: Option<Decimal>) -> Result<(), ForexError> {
+        if self.base_currency == code {
+            return Err(ForexError::BaseCurrencyRate(code.to_string()));
+        }
+        if self.basket(code).is_some() {
+            return Err(ForexError::BasketRate(code.to_string()));
+        }
+        let curr = self
+            .catalog
+            .get_mut(code)
+            .ok_or_else(|| ForexError::UnknownCurrency(code.to_string()))?;
+        let rounded = rate.map(|r| r.round_dp(curr.rate_decimals));
+        if rounded.is_some_and(Decimal::is_zero) && rate.is_some_and(|r| !r.is_zero()) {
+            return Err(ForexError::ZeroRate(code.to_string()));
         }
+        curr.cash_rate = rounded;
+        curr.updated = Some(self.clock.now());
+        curr.source = Some(RateSource::Manual);
+        Ok(())
+    }
+
+    /// Set the rate of the non-basket currency `code`, rounded to its
+    /// `rate_decimals`, then reprice every basket holding it, putting the
+    /// old rate back if one overflows.
+    fn replace_rate(&mut self, code: &str, rate: Decimal, updated: Option<i64>, source: Option<RateSource>) -> Result<(), ForexError> {
+        if self.basket(code).is_some() {
+            return Err(ForexError::BasketRate(code.to_string()));
+        }
+        let curr = self
+            .catalog
+            .get_mut(code)
+            .ok_or_else(|| ForexError::UnknownCurrency(code.to_string()))?;
+        let rounded = rate.round_dp(curr.rate_decimals);
+        if rounded.is_zero() && !rate.is_zero() {
+            return Err(ForexError::ZeroRate(code.to_string()));
+        }
+        let previous = (curr.rate, curr.updated, curr.source.take());
+        curr.rate = rounded;
+        curr.updated = updated;
+        curr.source = source;
+        let Some(repriced) = self.basket_rates() else {
+            if let Some(curr) = self.catalog.get_mut(code) {
+                (curr.rate, curr.updated, curr.source) = previous;
+            }
+            return Err(ForexError::Overflow);
+        };
+        let date = updated.map(Date::from_timestamp);
+        if let Some(date) = date {
+            self.record_close(code, date, rounded);
+        }
+        for (basket, basket_rate) in repriced {
+            let holds = self.basket(&basket).is_some_and(|b| b.components.iter().any(|(c, _)| c == code));
+            if let Some(curr) = self.catalog.get_mut(&basket)
+                && holds
+            {
+                curr.rate = basket_rate;
+                curr.updated = updated;
+                curr.source = Some(RateSource::Basket);
+                if let Some(date) = date {
+                    self.record_close(&basket, date, basket_rate);
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Every basket's rate from its components' current rates, or `None`
+    /// if one overflows.
+    fn basket_rates(&self) -> Option<Vec<(String, Decimal)>> {
+        self.baskets
+            .iter()
+            .map(|b| {
+                let mut rate = Decimal::ZERO;
+                for (code, quantity) in &b.components {
+                    let component = self.get_rate(code).copied().unwrap_or(Decimal::ZERO);
+                    rate = rate.checked_add(quantity.checked_mul(component)?)?;
+                }
+                Some((b.code.clone(), rate.round_dp(self.rate_decimals(&b.code))))
+            })
+            .collect()
+    }
+
+    /// Register the basket `code` of registered, non-basket currencies, each
+    /// given with its share of the basket's value (`weights` must be positive
+    /// and add up to 1). One unit is worth one unit of the base currency
+    /// today; the quantity of each component is fixed from today's rates.
+    pub fn define_basket(&mut self, code: &str, name: &str, weights: &[(String, Decimal)]) -> Result<&Basket, ForexError> {
+        if weights.is_empty()
+            || weights.iter().any(|(_, w)| *w <= Decimal::ZERO)
+            || weights.iter().map(|(_, w)| *w).sum::<Decimal>() != Decimal::ONE
+        {
+            return Err(ForexError::BasketWeights);
+        }
+        let mut components = Vec::with_capacity(weights.len());
+        for (i, (component, weight)) in weights.iter().enumerate() {
+            let component = component.trim().to_ascii_uppercase();
+            let rate = self
+                .get_rate(&component)
+                .copied()
+                .ok_or_else(|| ForexError::UnknownCurrency(component.clone()))?;
+            if self.basket(&component).is_some() || weights[..i].iter().any(|(c, _)| c.trim().eq_ignore_ascii_case(&component)) {
+                return Err(ForexError::InvalidBasketComponent(component));
+            }
+            if rate.is_zero() {
+                return Err(ForexError::ZeroRate(component));
+            }
+            let quantity = weight.checked_div(rate).ok_or(ForexError::Overflow)?;
+            components.push((component, quantity));
+        }
+        self.add_currency(code, name, Decimal::ONE)?;
+        let code = code.trim().to_ascii_uppercase();
+        if let Some(curr) = self.catalog.get_mut(&code) {
+            curr.rate_decimals = BASKET_RATE_DP;
+            curr.source = Some(RateSource::Basket);
+        }
+        let at = self.baskets.partition_point(|b| b.code < code);
+        self.baskets.insert(at, Basket { code, components });
+        Ok(&self.baskets[at])
+    }
+
+    /// The basket registered as `code`, if it is one.
+    pub fn basket(&self, code: &str) -> Option<&Basket> {
+        self.baskets.iter().find(|b| b.code == code)
+    }
+
+    /// Every basket, sorted by code.
+    pub fn baskets(&self) -> &[Basket] {
+        &self.baskets
+    }
+
+    /// The basket `code` with each component's share of its value today, or
+    /// `None` if `code` is not a basket.
+    pub fn basket_quote(&self, code: &str) -> Option<BasketQuote> {
+        let basket = self.basket(code)?;
+        let values: Vec<Decimal> = basket
+            .components
+            .iter()
+            .map(|(c, quantity)| quantity.checked_mul(self.get_rate(c).copied().unwrap_or(Decimal::ZERO)).unwrap_or(Decimal::ZERO))
+            .collect();
+        let total: Decimal = values.iter().sum();
+        let shares = basket
+            .components
+            .iter()
+            .zip(values)
+            .map(|((c, quantity), value)| BasketShare {
+                code: c.clone(),
+                quantity: *quantity,
+                weight: value.checked_div(total).unwrap_or(Decimal::ZERO),
+            })
+            .collect();
+        Some(BasketQuote { currency: self.currency(code)?.clone(), shares })
+    }
+
+    /// `basket_quote` for every basket, sorted by code.
+    pub fn basket_quotes(&self) -> Vec<BasketQuote> {
+        self.baskets.iter().filter_map(|b| self.basket_quote(&b.code)).collect()
+    }
+
+    /// Register a new currency at runtime, priced at `rate` units of the
+    /// base currency (kept to `DEFAULT_RATE_DP` places) and stamped with the
+    /// current time as entered by hand. `code` must be three ASCII letters
+    /// (stored uppercase) that are not already registered, and `rate` must
+    /// be above zero at those places.
+    pub fn add_currency(&mut self, code: &str, name: &str, rate: Decimal) -> Result<(), ForexError> {
+        let code = code.trim().to_ascii_uppercase();
+        if code.len() != 3 || !code.chars().all(|c| c.is_ascii_alphabetic()) {
+            return Err(ForexError::InvalidCurrencyCode(code));
+        }
+        if self.catalog.contains_key(&code) {
+            return Err(ForexError::DuplicateCurrency(code));
+        }
+        if rate.round_dp(DEFAULT_RATE_DP) <= Decimal::ZERO {
+            return Err(ForexError::ZeroRate(code));
+        }
+        let currency = Currency {
+            name: name.to_string(),
+            rate: rate.round_dp(DEFAULT_RATE_DP),
+            cash_rate: None,
+            decimals: default_decimals(&code),
+            rate_decimals: DEFAULT_RATE_DP,
+            symbol: default_symbol(&code),
+            denominations: default_denominations(&code),
+            updated: Some(self.clock.now()),
+            source: Some(RateSource::Manual),
+            code: code.clone(),
+        };
+        self.record_close(&code, self.clock.today(), currency.rate);
+        self.catalog.insert(code, currency);
+        Ok(())
+    }
+
+    /// Change the display name of `code`. Fails if it is not registered.
+    pub fn rename_currency(&mut self, code: &str, name: &str) -> Result<(), ForexError> {
+        let curr = self
+            .catalog
+            .get_mut(code)
+            .ok_or_else(|| ForexError::UnknownCurrency(code.to_string()))?;
+        curr.name = name.to_string();
+        Ok(())
+    }
+
+    /// Remove `code` from the catalog and return its last entry. The base
+    /// currency and basket components cannot be retired; retiring a basket
+    /// drops its composition too, and its pair spreads go with it.
+    pub fn retire_currency(&mut self, code: &str) -> Result<Currency, ForexError> {
+        if self.base_currency == code {
+            return Err(ForexError::RetireBaseCurrency(code.to_string()));
+        }
+        if let Some(basket) = self.baskets.iter().find(|b| b.components.iter().any(|(c, _)| c == code)) {
+            return Err(ForexError::CurrencyInBasket(code.to_string(), basket.code.clone()));
+        }
+        let retired = self
+            .catalog
+            .remove(code)
+            .ok_or_else(|| ForexError::UnknownCurrency(code.to_string()))?;
+        self.baskets.retain(|b| b.code != code);
+        self.spreads.retain(|(a, b), _| a != code && b != code);
+        self.history.remove(code);
+        self.carried.remove(code);
+        Ok(retired)
+    }
+
+    /// Put back a rate captured earlier (e.g. from `currencies_detailed`),
+    /// including its last-updated time and source, to undo a `set_rate`.
+    /// Closing rates recorded since, for it and the baskets holding it, are
+    /// forgotten. Fails if the currency is the base currency or is no
+    /// longer registered.
+    pub fn revert_rate(&mut self, previous: &Currency) -> Result<(), ForexError> {
+        if self.base_currency == previous.code {
+            return Err(ForexError::BaseCurrencyRate(previous.code.clone()));
+        }
+        let since = previous.updated.map(Date::from_timestamp);
+        let baskets = self.baskets.iter().filter(|b| b.components.iter().any(|(c, _)| *c == previous.code)).map(|b| b.code.clone());
+        for code in std::iter::once(previous.code.clone()).chain(baskets.collect::<Vec<_>>()) {
+            if let Some(series) = self.history.get_mut(&code) {
+                series.retain(|(date, _)| since.is_some_and(|since| *date <= since));
+            }
+        }
+        self.replace_rate(&previous.code, previous.rate, previous.updated, previous.source.clone())
+    }
+
+    /// Record `rate` as the close of `code` on `date`, replacing one already
+    /// recorded that day (carried or not), and drop the oldest once there
+    /// are more than `RATE_HISTORY_DAYS`. Setting a rate records it; this is
+    /// for restoring a saved history or loading one from elsewhere.
+    pub fn record_close(&mut self, code: &str, date: Date, rate: Decimal) {
+        let series = self.history.entry(code.to_string()).or_default();
+        match series.binary_search_by(|(d, _)| d.cmp(&date)) {
+            Ok(i) => series[i].1 = rate,
+            Err(i) => series.insert(i, (date, rate)),
+        }
+        if series.len() > RATE_HISTORY_DAYS {
+            series.drain(..series.len() - RATE_HISTORY_DAYS);
+        }
+        let first = series[0].0;
+        if let Some(carried) = self.carried.get_mut(code) {
+            carried.remove(&date);
+            carried.retain(|d| *d >= first);
+        }
+    }
+
+    /// `record_close`, marking the close as carried from an earlier day
+    /// rather than published on `date` (see `carry_close`).
+    pub fn record_carried_close(&mut self, code: &str, date: Date, rate: Decimal) {
+        self.record_close(code, date, rate);
+        if self.rate_history(code).first().is_some_and(|(first, _)| *first <= date) {
+            self.carried.entry(code.to_string()).or_default().insert(date);
+        }
+    }
+
+    /// Carry `code`'s last close before `date` onto `date`, marked carried,
+    /// for a day its provider publishes nothing (a weekend or holiday), and
+    /// return the rate carried. `None`, recording nothing, if `date` already
+    /// has a close or there is none before it.
+    pub fn carry_close(&mut self, code: &str, date: Date) -> Option<Decimal> {
+        let series = self.rate_history(code);
+        let at = series.partition_point(|(d, _)| *d < date);
+        if series.get(at).is_some_and(|(d, _)| *d == date) {
+            return None;
+        }
+        let rate = series.get(at.checked_sub(1)?)?.1;
+        self.record_carried_close(code, date, rate);
+        Some(rate)
+    }
+
+    /// Whether `code`'s close on `date` was carried from an earlier day.
+    pub fn is_carried(&self, code: &str, date: Date) -> bool {
+        self.carried.get(code).is_some_and(|carried| carried.contains(&date))
+    }
+
+    /// The closing rates on record for `code`, oldest first, carried ones
+    /// included (see `is_carried`); empty for the base currency, whose rate
+    /// never moves, and unknown codes.
+    pub fn rate_history(&self, code: &str) -> &[(Date, Decimal)] {
+        self.history.get(code).map_or(&[], Vec::as_slice)
+    }
+
+    /// `rate_history` without the carried closes: the days a rate was
+    /// actually set. Statistics are taken over these, so a weekend carried
+    /// at Friday's rate adds no days without a move.
+    fn observed_history(&self, code: &str) -> Vec<(Date, Decimal)> {
+        self.rate_history(code).iter().filter(|(date, _)| !self.is_carried(code, *date)).copied().collect()
+    }
+
+    /// How much `code`'s rate has moved over its last `window` daily
+    /// returns, i.e. its last `window + 1` closing rates on record, carried
+    /// ones left out (see `RateVolatility`). Fails if `code` is not
+    /// registered, or has rates on fewer than three days in the window (two
+    /// returns).
+    pub fn volatility(&self, code: &str, window: usize) -> Result<RateVolatility, ForexError> {
+        if !self.catalog.contains_key(code) {
+            return Err(ForexError::UnknownCurrency(code.to_string()));
+        }
+        let series = self.observed_history(code);
+        let recent = &series[series.len().saturating_sub(window.saturating_add(1))..];
+        volatility::of_series(code, recent).ok_or_else(|| ForexError::NotEnoughHistory(code.to_string(), 3))
+    }
+
+    /// The open, close, high, low, and average of `code`'s closing rates
+    /// from `start` through `end`, carried ones left out (see `RateStats`).
+    /// Fails if `code` is not registered or has no close on record in the
+    /// period.
+    pub fn stats(&self, code: &str, start: Date, end: Date) -> Result<RateStats, ForexError> {
+        if !self.catalog.contains_key(code) {
+            return Err(ForexError::UnknownCurrency(code.to_string()));
+        }
+        let series = self.observed_history(code);
+        let from = series.partition_point(|(date, _)| *date < start);
+        let to = series.partition_point(|(date, _)| *date <= end).max(from);
+        RateStats::of_series(code, &series[from..to], self.rate_decimals(code)).ok_or_else(|| ForexError::NoRatesInPeriod(code.to_string(), start, end))
+    }
+
+    /// Where the rate from `from` to `to` stands today against the pair's
+    /// last close before today and its mean over the last `TREND_DAYS`
+    /// closes, taken from both currencies' histories (see `RateTrend`).
+    /// Fails if either currency is not registered or `to` has a zero rate.
+    pub fn trend(&self, from: &str, to: &str) -> Result<RateTrend, ForexError> {
+        let rate = self.convert(&Money::new(Decimal::ONE, from), to)?.amount;
+        let closes = self.cross_history(from, to);
+        let today = self.clock.today();
+        let before = closes.partition_point(|(date, _)| *date < today);
+        let rates: Vec<Decimal> = closes.iter().map(|(_, rate)| *rate).collect();
+        Ok(RateTrend {
+            from: from.to_string(),
+            to: to.to_string(),
+            rate,
+            previous: before.checked_sub(1).map(|i| closes[i]),
+            average: MovingAverage::simple(TREND_DAYS).of(&rates),
+        })
+    }
+
+    /// `stats` of every currency with a close on record from `start`
+    /// through `end`, by code; either bound left out reaches the
+    /// currency's first or last close.
+    pub fn rate_summary(&self, start: Option<Date>, end: Option<Date>) -> Vec<RateStats> {
+        self.history
+            .iter()
+            .filter_map(|(code, series)| {
+                let (first, last) = (series.first()?.0, series.last()?.0);
+                self.stats(code, start.unwrap_or(first), end.unwrap_or(last)).ok()
+            })
+            .collect()
+    }
+
+    /// The simple moving average of `code`'s last `days` closing rates.
+    pub fn sma(&self, code: &str, days: usize) -> Result<Decimal, ForexError> {
+        self.moving_average(code, MovingAverage::simple(days))
+    }
+
+    /// The exponential moving average of `code`'s closing rates over
+    /// `days` days.
+    pub fn ema(&self, code: &str, days: usize) -> Result<Decimal, ForexError> {
+        self.moving_average(code, MovingAverage::exponential(days))
+    }
+
+    /// `average` of `code`'s closing rates on record, in the base currency
+    /// (see `MovingAverage`), rounded to the currency's `rate_decimals`.
+    /// Fails if `code` is not registered or has closes on fewer than
+    /// `average.days` days.
+    pub fn moving_average(&self, code: &str, average: MovingAverage) -> Result<Decimal, ForexError> {
+        Ok(self.moving_average_in(code, &self.base_currency, average)?.round_dp(self.rate_decimals(code)))
+    }
+
+    /// `average` of the price of `code` in `quote` (units of `quote` per
+    /// unit of `code`), taken on the days both have a close on record; the
+    /// base currency is 1 on every day. Unrounded; this is what a limit
+    /// order's `average` is checked against.
+    pub fn moving_average_in(&self, code: &str, quote: &str, average: MovingAverage) -> Result<Decimal, ForexError> {
+        for c in [code, quote] {
+            if !self.catalog.contains_key(c) {
+                return Err(ForexError::UnknownCurrency(c.to_string()));
+            }
+        }
+        let closes: Vec<Decimal> = self.cross_history(code, quote).into_iter().map(|(_, rate)| rate).collect();
+        average.of(&closes).ok_or_else(|| ForexError::NotEnoughHistory(code.to_string(), average.days.max(1)))
+    }
+
+    /// `code`'s closing rates on record, oldest first, each with its
+    /// `sma_days`-day simple and `ema_days`-day exponential moving average
+    /// as of that day, rounded to its `rate_decimals`. The averages are
+    /// taken over the closes that were not carried; a carried close shows
+    /// those of the day it was carried from. Fails if `code` is not
+    /// registered.
+    pub fn averaged_history(&self, code: &str, sma_days: usize, ema_days: usize) -> Result<Vec<AveragedClose>, ForexError> {
+        if !self.catalog.contains_key(code) {
+            return Err(ForexError::UnknownCurrency(code.to_string()));
+        }
+        let closes: Vec<Decimal> = self.observed_history(code).into_iter().map(|(_, rate)| rate).collect();
+        let sma = MovingAverage::simple(sma_days).series(&closes);
+        let ema = MovingAverage::exponential(ema_days).series(&closes);
+        let dp = self.rate_decimals(code);
+        let mut observed = 0;
+        Ok(self
+            .rate_history(code)
+            .iter()
+            .map(|&(date, rate)| {
+                let carried = self.is_carried(code, date);
+                observed += usize::from(!carried);
+                let average = |series: &[Option<Decimal>]| observed.checked_sub(1).and_then(|i| series[i]).map(|v| v.round_dp(dp));
+                AveragedClose { date, rate, carried, sma: average(&sma), ema: average(&ema) }
+            })
+            .collect())
+    }
+
+    /// The closing prices of `code` in `quote`, oldest first, on the days
+    /// both have a close on record that was not carried (every such day of
+    /// the other's for the base currency, whose rate is always 1). Empty if
+    /// either is unknown, or both are the base.
+    pub fn cross_history(&self, code: &str, quote: &str) -> Vec<(Date, Decimal)> {
+        let base = self.base_currency.as_str();
+        match (code == base, quote == base) {
+            (_, true) => self.observed_history(code),
+            (true, false) => self.observed_history(quote).iter().filter(|(_, rate)| !rate.is_zero()).map(|&(date, rate)| (date, Decimal::ONE / rate)).collect(),
+            (false, false) => {
+                let quotes = self.observed_history(quote);
+                self.observed_history(code)
+                    .iter()
+                    .filter_map(|&(date, rate)| {
+                        let i = quotes.binary_search_by(|(d, _)| d.cmp(&date)).ok()?;
+                        (!quotes[i].1.is_zero()).then(|| (date, rate / quotes[i].1))
+                    })
+                    .collect()
+            }
+        }
+    }
+
+    /// Display symbol for `code`, falling back to the conventional symbol for
+    /// currencies not in the catalog.
+    pub fn symbol(&self, code: &str) -> String {
+        self.catalog
+            .get(code)
+            .map_or_else(|| default_symbol(code), |c| c.symbol.clone())
+    }
+
+    /// Minor-unit decimal places for `code`, falling back to the ISO default
+    /// for currencies not in the catalog.
+    pub fn decimals(&self, code: &str) -> u32 {
+        self.catalog
+            .get(code)
+            .map_or_else(|| default_decimals(code), |c| c.decimals)
+    }
+
+    /// Fractional digits the rates of `code` are kept to, falling back to
+    /// `DEFAULT_RATE_DP` for currencies not in the catalog.
+    pub fn rate_decimals(&self, code: &str) -> u32 {
+        self.catalog.get(code).map_or(DEFAULT_RATE_DP, |c| c.rate_decimals)
+    }
+
+    /// Keep the rates of `code` to `dp` fractional digits from now on,
+    /// rounding its current rates to them. Fails if `code` is not
+    /// registered, if `dp` is more than `SCALE`, or if a rate would round
+    /// to zero; nothing changes then.
+    pub fn set_rate_decimals(&mut self, code: &str, dp: u32) -> Result<(), ForexError> {
+        if dp > SCALE {
+            return Err(ForexError::InvalidRatePrecision(dp));
+        }
+        let curr = self
+            .catalog
+            .get_mut(code)
+            .ok_or_else(|| ForexError::UnknownCurrency(code.to_string()))?;
+        let vanishes = |r: Decimal| !r.is_zero() && r.round_dp(dp).is_zero();
+        if vanishes(curr.rate) || curr.cash_rate.is_some_and(vanishes) {
+            return Err(ForexError::ZeroRate(code.to_string()));
+        }
+        let (rate, cash_rate) = (curr.rate.round_dp(dp), curr.cash_rate.map(|r| r.round_dp(dp)));
+        (curr.rate, curr.cash_rate, curr.rate_decimals) = (rate, cash_rate, dp);
+        Ok(())
+    }
+
+    /// `amount` counted out in the bills and coins of its currency, e.g.
+    /// after an exchange paid in cash. A currency with no denominations, or
+    /// not in the catalog, leaves all of it in the remainder.
+    pub fn cash_breakdown(&self, amount: &Money) -> CashBreakdown {
+        let denominations = self.catalog.get(&amount.currency).map_or(&[][..], |c| &c.denominations);
+        CashBreakdown::new(amount, denominations)
+    }
+
+    /// The catalog entry for `code`, if registered.
+    pub fn currency(&self, code: &str) -> Option<&Currency> {
+        self.catalog.get(code)
     }
 
     /// Get a reference to the rate for `code` if present.
-    pub fn get_rate(&self, code: &str) -> Option<&f64> {
+    pub fn get_rate(&self, code: &str) -> Option<&Decimal> {
         self.catalog.get(code).map(|c| &c.rate)
     }
 
-    /// Builder method: sets the base currency code for this `Forex` and returns
-    /// the updated instance for chaining.
-    pub fn set_base_rate(mut self, code: &str) -> Self {
-        self.base_currency = code.to_string();
-        self
+    /// The `rate_type` rate for `code` if present (see `Currency::rate_of`).
+    pub fn get_rate_of(&self, code: &str, rate_type: RateType) -> Option<Decimal> {
+        self.catalog.get(code).map(|c| c.rate_of(rate_type))
+    }
+
+    /// Convert `amount` into the currency `to` via the base currency at
+    /// transfer rates: `amount × rate(src) / rate(dst)`. Fails if either
+    /// currency is unknown, the destination rate is zero, or the result
+    /// overflows.
+    pub fn convert(&self, amount: &Money, to: &str) -> Result<Money, ForexError> {
+        self.convert_at(amount, to, RateType::Transfer)
+    }
+
+    /// `convert` at either currency's `rate_type` rates.
+    pub fn convert_at(&self, amount: &Money, to: &str, rate_type: RateType) -> Result<Money, ForexError> {
+        if amount.currency == to {
+            return Ok(amount.clone());
+        }
+        let rate = |code: &str| {
+            self.get_rate_of(code, rate_type)
+                .ok_or_else(|| ForexError::UnknownCurrency(code.to_string()))
+        };
+        let src_rate = rate(&amount.currency)?;
+        let dst_rate = rate(to)?;
+        if dst_rate.is_zero() {
+            return Err(ForexError::ZeroRate(to.to_string()));
+        }
+        let converted = src_rate
+            .checked_div(dst_rate)
+            .and_then(|factor| amount.amount.checked_mul(factor))
+            .ok_or(ForexError::Overflow)?;
+        Ok(Money::new(converted, to))
+    }
+
+    /// The legs converting `amount` into `to` at the `rate_type` rates goes
+    /// through: into the base currency at the source's rate, then out of it
+    /// at the destination's, or a single leg when either side is the base.
+    /// The last leg ends at exactly what `convert_at` gives, so the legs
+    /// show how that figure was reached. Empty when both sides are one
+    /// currency; fails as `convert_at` does.
+    pub fn route(&self, amount: &Money, to: &str, rate_type: RateType) -> Result<Vec<ConversionLeg>, ForexError> {
+        if amount.currency == to {
+            return Ok(Vec::new());
+        }
+        let mut stops = vec![amount.currency.as_str()];
+        if amount.currency != self.base_currency && to != self.base_currency {
+            stops.push(&self.base_currency);
+        }
+        stops.push(to);
+        let mut legs: Vec<ConversionLeg> = Vec::with_capacity(stops.len() - 1);
+        for hop in stops.windows(2) {
+            let from = legs.last().map_or_else(|| amount.clone(), |leg| leg.to.clone());
+            let rate = self.convert_at(&Money::new(Decimal::ONE, hop[0]), hop[1], rate_type)?.amount;
+            legs.push(ConversionLeg { to: self.convert_at(&from, hop[1], rate_type)?, from, rate });
+        }
+        if let Some(last) = legs.last_mut() {
+            last.to = self.convert_at(amount, to, rate_type)?;
+        }
+        Ok(legs)
+    }
+
+    /// Convert `amount` into `to` as `convert` does and charge the fee for
+    /// its volume in the base currency, itemized in the result. Converting
+    /// into the same currency is free. Fails as `convert` does.
+    pub fn exchange(&self, amount: &Money, to: &str) -> Result<Conversion, ForexError> {
+        self.exchange_at(amount, to, RateType::Transfer)
+    }
+
+    /// `exchange` at either currency's `rate_type` rates. The fee's volume
+    /// is still valued at transfer rates, so a quote's tier does not depend
+    /// on how it is paid. A pair spread on the two currencies replaces the
+    /// tier's rate.
+    pub fn exchange_at(&self, amount: &Money, to: &str, rate_type: RateType) -> Result<Conversion, ForexError> {
+        let gross = self.convert_at(amount, to, rate_type)?;
+        let rate = self.convert_at(&Money::new(Decimal::ONE, &amount.currency), to, rate_type)?.amount;
+        let (fee_rate, fee_basis) = self.fee_rate(&amount.currency, to, self.convert(amount, &self.base_currency)?.amount);
+        let fee = gross.amount.checked_mul(fee_rate).ok_or(ForexError::Overflow)?;
+        Ok(Conversion {
+            source: amount.clone(),
+            rate_type,
+            rate,
+            net: Money::new(gross.amount - fee, to),
+            fee: Money::new(fee, to),
+            legs: self.route(amount, to, rate_type)?,
+            gross,
+            fee_rate,
+            fee_basis,
+        })
+    }
+
+    /// Break down converting `amount` of `src` into `dst` at transfer rates
+    /// into its mid-market rate, applied rate, margin, fee, and net payout,
+    /// without executing or logging anything. Fails as `convert` does.
+    pub fn preview(&self, src: &str, dst: &str, amount: Decimal) -> Result<ConversionPreview, ForexError> {
+        self.preview_at(src, dst, amount, RateType::Transfer)
+    }
+
+    /// `preview` at either currency's `rate_type` rates; at cash rates the
+    /// margin is what the cash rates give up against the transfer rates.
+    pub fn preview_at(&self, src: &str, dst: &str, amount: Decimal, rate_type: RateType) -> Result<ConversionPreview, ForexError> {
+        let source = Money::new(amount, src);
+        let quote = self.exchange_at(&source, dst, rate_type)?;
+        let at_mid = self.convert(&source, dst)?;
+        let mid_rate = self.convert(&Money::new(Decimal::ONE, src), dst)?.amount;
+        let margin = at_mid.amount.checked_sub(quote.gross.amount).ok_or(ForexError::Overflow)?;
+        Ok(ConversionPreview {
+            effective_rate: quote.net.amount.checked_div(amount).unwrap_or(quote.rate),
+            source,
+            rate_type,
+            mid_rate,
+            applied_rate: quote.rate,
+            margin: Money::new(margin, dst),
+            fee_rate: quote.fee_rate,
+            fee_basis: quote.fee_basis,
+            fee: quote.fee,
+            net: quote.net,
+        })
+    }
+
+    /// The fees charged on exchanges.
+    pub fn fee_schedule(&self) -> &FeeSchedule {
+        &self.fees
+    }
+
+    /// Replace the fees charged on exchanges from now on.
+    pub fn set_fee_schedule(&mut self, fees: FeeSchedule) {
+        self.fees = fees;
+    }
+
+    /// Charge `rate` (a fraction) on exchanges between `a` and `b`, either
+    /// way, instead of the fee schedule's tier, replacing any spread the
+    /// pair had. Fails if either currency is not registered, they are the
+    /// same, or `rate` is not from 0 up to, but not including, 1.
+    pub fn set_pair_spread(&mut self, a: &str, b: &str, rate: Decimal) -> Result<(), ForexError> {
+        for code in [a, b] {
+            self.currency(code).ok_or_else(|| ForexError::UnknownCurrency(code.to_string()))?;
+        }
+        if a == b || rate < Decimal::ZERO || rate >= Decimal::ONE {
+            return Err(ForexError::InvalidSpread);
+        }
+        self.spreads.insert(pair_key(a, b), rate);
+        Ok(())
+    }
+
+    /// The fee rate for converting `from` into `to`, worth `volume` in the
+    /// base currency, and where it comes from: the pair's spread if it has
+    /// one, else the fee schedule's tier. Converting into the same currency
+    /// is free.
+    pub fn fee_rate(&self, from: &str, to: &str, volume: Decimal) -> (Decimal, FeeBasis) {
+        if from == to {
+            (Decimal::ZERO, FeeBasis::Schedule)
+        } else if let Some(spread) = self.pair_spread(from, to) {
+            (spread, FeeBasis::PairSpread)
+        } else {
+            (self.fees.rate_for(volume), FeeBasis::Schedule)
+        }
+    }
+
+    /// Stop overriding the fee schedule for `a`/`b`; returns the spread it
+    /// had, if any.
+    pub fn remove_pair_spread(&mut self, a: &str, b: &str) -> Option<Decimal> {
+        self.spreads.remove(&pair_key(a, b))
+    }
+
+    /// The spread charged on exchanges between `a` and `b`, if one is set.
+    pub fn pair_spread(&self, a: &str, b: &str) -> Option<Decimal> {
+        self.spreads.get(&pair_key(a, b)).copied()
+    }
+
+    /// Every pair spread, by pair.
+    pub fn pair_spreads(&self) -> Vec<PairSpread> {
+        self.spreads.iter().map(|(pair, rate)| PairSpread { pair: pair.clone(), rate: *rate }).collect()
+    }
+
+    /// Log an executed conversion. The log is kept in the order recorded.
+    pub fn record_conversion(&mut self, record: ConversionRecord) {
+        self.conversions.push(record);
+    }
+
+    /// The number the next logged conversion's receipt takes: one past the
+    /// last.
+    pub fn next_receipt(&self) -> usize {
+        self.conversions.last().map_or(1, |r| r.receipt + 1)
+    }
+
+    /// The logged conversion with receipt `number`, if any.
+    pub fn conversion_receipt(&self, number: usize) -> Option<&ConversionRecord> {
+        self.conversions.iter().find(|r| r.receipt == number)
+    }
+
+    /// The logged conversions matching `filter`, oldest first.
+    pub fn conversion_history(&self, filter: &ConversionFilter) -> Vec<&ConversionRecord> {
+        self.conversions.iter().filter(|r| filter.matches(r)).collect()
+    }
+
+    /// FX turnover of the conversions matching `filter`, one entry per day
+    /// with any, by date.
+    pub fn daily_turnover(&self, filter: &ConversionFilter) -> Vec<DailyTurnover> {
+        let mut days: BTreeMap<_, DailyTurnover> = BTreeMap::new();
+        for record in self.conversion_history(filter) {
+            let date = record.date();
+            let day = days.entry(date).or_insert(DailyTurnover { date, conversions: 0, volume: Decimal::ZERO });
+            day.conversions += 1;
+            day.volume += record.volume;
+        }
+        days.into_values().collect()
     }
 
     /// Return the current base currency code (e.g., "PHP").
@@ -67,6 +1257,17 @@ impl Forex {
         &self.base_currency
     }
 
+    /// Stamp rates set from now on with the time on `clock`.
+    pub fn set_clock(&mut self, clock: Arc<dyn Clock>) {
+        self.clock = clock;
+    }
+
+    /// The base currency. Always present: the builder registers it and the
+    /// catalog never drops it.
+    pub fn base_currency(&self) -> &Currency {
+        &self.catalog[&self.base_currency]
+    }
+
     /// Return a sorted list of all currencies with their code, name, and rate.
     pub fn currencies_detailed(&self) -> Vec<Currency> {
         let mut list: Vec<Currency> = self
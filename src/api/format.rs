@@ -0,0 +1,351 @@
+use std::fmt;
+
+use crate::api::decimal::Decimal;
+
+/// Number-formatting conventions for displaying money.
+/// - `EnPh` / `EnUs`: "₱1,234,567.89" / "$1,234,567.89"
+/// - `DeDe`: "1.234.567,89 €"
+/// - `FrFr`: "1 234 567,89 €" (narrow no-break space grouping)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Locale {
+    #[default]
+    EnPh,
+    EnUs,
+    DeDe,
+    FrFr,
+}
+
+impl Locale {
+    /// Parse a locale tag such as "en-PH", "de_DE", or "fr".
+    pub fn parse(tag: &str) -> Option<Self> {
+        match tag.to_ascii_lowercase().replace('_', "-").as_str() {
+            "en-ph" | "fil" | "fil-ph" | "ph" => Some(Locale::EnPh),
+            "en-us" | "en" | "us" => Some(Locale::EnUs),
+            "de-de" | "de" => Some(Locale::DeDe),
+            "fr-fr" | "fr" => Some(Locale::FrFr),
+            _ => None,
+        }
+    }
+
+    /// Canonical tag for this locale, accepted back by `parse`.
+    pub fn tag(self) -> &'static str {
+        match self {
+            Locale::EnPh => "en-PH",
+            Locale::EnUs => "en-US",
+            Locale::DeDe => "de-DE",
+            Locale::FrFr => "fr-FR",
+        }
+    }
+
+    fn group_separator(self) -> &'static str {
+        match self {
+            Locale::EnPh | Locale::EnUs => ",",
+            Locale::DeDe => ".",
+            Locale::FrFr => "\u{202f}",
+        }
+    }
+
+    fn decimal_separator(self) -> &'static str {
+        match self {
+            Locale::EnPh | Locale::EnUs => ".",
+            Locale::DeDe | Locale::FrFr => ",",
+        }
+    }
+
+    /// Characters that may group digits in typed amounts: the display
+    /// separator, plus ordinary and no-break spaces where it is one.
+    fn group_separators(self) -> &'static [char] {
+        match self {
+            Locale::EnPh | Locale::EnUs => &[','],
+            Locale::DeDe => &['.'],
+            Locale::FrFr => &['\u{202f}', '\u{a0}', ' '],
+        }
+    }
+
+    fn symbol_after(self) -> bool {
+        matches!(self, Locale::DeDe | Locale::FrFr)
+    }
+}
+
+/// Format `amount` with `decimals` fractional digits, the locale's digit
+/// grouping and decimal separator, and `symbol` placed per the locale.
+/// An empty `symbol` yields just the grouped number.
+pub fn format_amount(amount: Decimal, symbol: &str, decimals: u32, locale: Locale) -> String {
+    let rounded = amount.round_dp(decimals);
+    let plain = format!("{:.*}", decimals as usize, rounded.abs());
+    let (int_part, frac_part) = plain.split_once('.').unwrap_or((&plain, ""));
+
+    let mut grouped = String::new();
+    for (i, c) in int_part.chars().enumerate() {
+        if i > 0 && (int_part.len() - i) % 3 == 0 {
+            grouped.push_str(locale.group_separator());
+        }
+        grouped.push(c);
+    }
+    if !frac_part.is_empty() {
+        grouped.push_str(locale.decimal_separator());
+        grouped.push_str(frac_part);
+    }
+
+    let sign = if rounded.is_sign_negative() { "-" } else { "" };
+    match (symbol.is_empty(), locale.symbol_after()) {
+        (true, _) => format!("{}{}", sign, grouped),
+        (false, true) => format!("{}{} {}", sign, grouped, symbol),
+        (false, false) => format!("{}{}{}", sign, symbol, grouped),
+    }
+}
+
+/// Why a typed amount could not be read by `parse_amount`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum AmountError {
+    Empty,
+    /// A character (given) that has no place in an amount.
+    UnexpectedCharacter(char),
+    /// No digits, e.g. only a currency symbol.
+    NoDigits,
+    /// Digit groups that are not three digits each after the first, or a
+    /// group separator after the decimal separator.
+    Grouping,
+    /// More than one decimal separator (given).
+    DecimalSeparators(char),
+    /// The exponent after "e" is not a whole number.
+    Exponent,
+    /// The amount is too large or too precise to represent.
+    OutOfRange,
+    /// An expression divides by zero.
+    DivisionByZero,
+    /// An expression's parentheses do not pair up.
+    UnbalancedParentheses,
+    /// An operator has no amount on one side, as in "5+".
+    MissingOperand,
+    /// Two amounts with no operator between them, as in "2(3)".
+    MissingOperator,
+}
+
+impl fmt::Display for AmountError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            AmountError::Empty => write!(f, "no amount given"),
+            AmountError::UnexpectedCharacter(c) => write!(f, "unexpected character '{}'", c),
+            AmountError::NoDigits => write!(f, "no digits in the amount"),
+            AmountError::Grouping => write!(f, "digit groups must be three digits each, e.g. 1,000,000"),
+            AmountError::DecimalSeparators(c) => write!(f, "more than one decimal separator '{}'", c),
+            AmountError::Exponent => write!(f, "the exponent must be a whole number, e.g. 1e3"),
+            AmountError::OutOfRange => write!(f, "the amount is out of range"),
+            AmountError::DivisionByZero => write!(f, "cannot divide by zero"),
+            AmountError::UnbalancedParentheses => write!(f, "the parentheses do not match"),
+            AmountError::MissingOperand => write!(f, "an operator is missing an amount on one side"),
+            AmountError::MissingOperator => write!(f, "two amounts need an operator between them"),
+        }
+    }
+}
+
+impl std::error::Error for AmountError {}
+
+/// Read an amount typed the way people write money, the inverse of
+/// `format_amount`: digits grouped and separated per `locale`
+/// ("1,000.50", or "1.000,50" for `DeDe`), an optional sign, a currency
+/// symbol or code before or after ("₱1000", "1000 PHP"), a `k` or `m`
+/// suffix for thousands or millions ("1k", "2.5m"), and an exponent
+/// ("1e3"). Any symbol or code is dropped, not checked against a currency.
+pub fn parse_amount(input: &str, locale: Locale) -> Result<Decimal, AmountError> {
+    let input = input.trim();
+    if input.is_empty() {
+        return Err(AmountError::Empty);
+    }
+    let (group, decimal) = (locale.group_separators(), locale.decimal_separator().chars().next().unwrap_or('.'));
+    let is_currency = |c: char| !(c.is_ascii_digit() || c == decimal || group.contains(&c) || matches!(c, '-' | '+'));
+    // A symbol ("₱", "US$") or a three-letter code ("PHP"), but not a stray
+    // letter such as the "x" of "1x".
+    let check_currency = |affix: &str| {
+        let affix = affix.trim();
+        match !affix.is_empty() && affix.chars().all(char::is_alphabetic) && affix.chars().count() != 3 {
+            true => Err(match affix.chars().next() {
+                Some('e' | 'E') => AmountError::Exponent,
+                c => AmountError::UnexpectedCharacter(c.unwrap_or(' ')),
+            }),
+            false => Ok(()),
+        }
+    };
+
+    // A sign may come before or after a leading symbol: "-₱5" or "₱-5".
+    let (mut negative, mut signed) = (false, false);
+    let mut body = input;
+    for _ in 0..2 {
+        let rest = body.trim_start_matches(is_currency);
+        check_currency(&body[..body.len() - rest.len()])?;
+        body = rest.trim_start();
+        if signed {
+            break;
+        }
+        if let Some(sign @ ('-' | '+')) = body.chars().next() {
+            (negative, signed) = (sign == '-', true);
+            body = &body[1..];
+        }
+    }
+    let tail_start = body.rfind(|c: char| c.is_ascii_digit()).map_or(0, |i| i + 1);
+    let (number, tail) = body.split_at(tail_start);
+    let multiplier = match tail.trim().to_lowercase().as_str() {
+        "k" => Decimal::from(1_000),
+        "m" => Decimal::from(1_000_000),
+        tail if tail.chars().all(is_currency) => {
+            check_currency(tail)?;
+            Decimal::ONE
+        }
+        tail => return Err(AmountError::UnexpectedCharacter(tail.chars().find(|c| !is_currency(*c)).unwrap_or(' '))),
+    };
+    let (mantissa, exponent) = match number.split_once(['e', 'E']) {
+        Some((mantissa, exponent)) => (mantissa, Some(exponent.parse::<i32>().map_err(|_| AmountError::Exponent)?)),
+        None => (number, None),
+    };
+    if let Some(c) = mantissa.chars().find(|c| !(c.is_ascii_digit() || *c == decimal || group.contains(c))) {
+        return Err(AmountError::UnexpectedCharacter(c));
+    }
+    if !mantissa.chars().any(|c| c.is_ascii_digit()) {
+        return Err(AmountError::NoDigits);
+    }
+    let (int_part, frac_part) = mantissa.split_once(decimal).unwrap_or((mantissa, ""));
+    if frac_part.contains(decimal) {
+        return Err(AmountError::DecimalSeparators(decimal));
+    }
+    if frac_part.contains(group) {
+        return Err(AmountError::Grouping);
+    }
+    let groups: Vec<&str> = int_part.split(group).collect();
+    if groups.len() > 1 && (!(1..=3).contains(&groups[0].len()) || groups[1..].iter().any(|g| g.len() != 3)) {
+        return Err(AmountError::Grouping);
+    }
+    let mut amount = format!("{}.{}", groups.concat(), frac_part).parse::<Decimal>().map_err(|_| AmountError::OutOfRange)?;
+    if let Some(exponent) = exponent {
+        let scale = Decimal::from(10).checked_powu(u64::from(exponent.unsigned_abs())).ok_or(AmountError::OutOfRange)?;
+        amount = match exponent < 0 {
+            true => amount.checked_div(scale),
+            false => amount.checked_mul(scale),
+        }
+        .ok_or(AmountError::OutOfRange)?;
+    }
+    let amount = amount.checked_mul(multiplier).ok_or(AmountError::OutOfRange)?;
+    Ok(if negative { -amount } else { amount })
+}
+
+/// One piece of an expression read by `evaluate_amount`.
+#[derive(Debug, Clone, Copy)]
+enum Token {
+    Amount(Decimal),
+    /// `+`, `-`, `*`, or `/`.
+    Operator(char),
+    Open,
+    Close,
+}
+
+/// Evaluate `input` as simple arithmetic over amounts, so a teller can
+/// type a computed total: `+`, `-`, `*` (or `×`), `/` (or `÷`), and
+/// parentheses, with the usual precedence, e.g. "1500+350*2" is 2200.
+/// Each amount is read by `parse_amount`, so "₱1,000 + 2k" works too. An
+/// input that is a single amount is read as one first, so "₱-5" keeps its
+/// sign. Division is exact to the `Decimal` scale.
+pub fn evaluate_amount(input: &str, locale: Locale) -> Result<Decimal, AmountError> {
+    let single = parse_amount(input, locale);
+    if single.is_ok() || !input.contains(['+', '-', '*', '/', '×', '÷', '(', ')']) {
+        return single;
+    }
+    let tokens = tokenize(input, locale)?;
+    let mut at = 0;
+    let value = sum(&tokens, &mut at)?;
+    match tokens.get(at) {
+        None => Ok(value),
+        Some(Token::Close) => Err(AmountError::UnbalancedParentheses),
+        Some(_) => Err(AmountError::MissingOperator),
+    }
+}
+
+/// Split an expression into amounts, operators, and parentheses. A sign
+/// right after an exponent's "e" belongs to the amount ("1e-3").
+fn tokenize(input: &str, locale: Locale) -> Result<Vec<Token>, AmountError> {
+    let mut tokens = Vec::new();
+    let mut amount = String::new();
+    let flush = |amount: &mut String, tokens: &mut Vec<Token>| -> Result<(), AmountError> {
+        if !amount.trim().is_empty() {
+            tokens.push(Token::Amount(parse_amount(amount, locale)?));
+        }
+        amount.clear();
+        Ok(())
+    };
+    for c in input.chars() {
+        let in_exponent = {
+            let mut before = amount.trim_end().chars().rev();
+            matches!(before.next(), Some('e' | 'E')) && before.next().is_some_and(|d| d.is_ascii_digit())
+        };
+        let token = match c {
+            '+' | '-' if in_exponent => None,
+            '+' | '-' | '*' | '/' => Some(Token::Operator(c)),
+            '×' => Some(Token::Operator('*')),
+            '÷' => Some(Token::Operator('/')),
+            '(' => Some(Token::Open),
+            ')' => Some(Token::Close),
+            _ => None,
+        };
+        match token {
+            Some(token) => {
+                flush(&mut amount, &mut tokens)?;
+                tokens.push(token);
+            }
+            None => amount.push(c),
+        }
+    }
+    flush(&mut amount, &mut tokens)?;
+    Ok(tokens)
+}
+
+/// Terms added and subtracted, from `tokens[*at]`.
+fn sum(tokens: &[Token], at: &mut usize) -> Result<Decimal, AmountError> {
+    let mut value = product(tokens, at)?;
+    while let Some(&Token::Operator(op @ ('+' | '-'))) = tokens.get(*at) {
+        *at += 1;
+        let rhs = product(tokens, at)?;
+        value = match op {
+            '+' => value.checked_add(rhs),
+            _ => value.checked_sub(rhs),
+        }
+        .ok_or(AmountError::OutOfRange)?;
+    }
+    Ok(value)
+}
+
+/// Factors multiplied and divided, from `tokens[*at]`.
+fn product(tokens: &[Token], at: &mut usize) -> Result<Decimal, AmountError> {
+    let mut value = factor(tokens, at)?;
+    while let Some(&Token::Operator(op @ ('*' | '/'))) = tokens.get(*at) {
+        *at += 1;
+        let rhs = factor(tokens, at)?;
+        value = match op {
+            '*' => value.checked_mul(rhs),
+            _ if rhs.is_zero() => return Err(AmountError::DivisionByZero),
+            _ => value.checked_div(rhs),
+        }
+        .ok_or(AmountError::OutOfRange)?;
+    }
+    Ok(value)
+}
+
+/// An amount, a signed factor, or a parenthesized sum.
+fn factor(tokens: &[Token], at: &mut usize) -> Result<Decimal, AmountError> {
+    let token = tokens.get(*at).copied();
+    *at += 1;
+    match token {
+        Some(Token::Amount(amount)) => Ok(amount),
+        Some(Token::Operator('-')) => Ok(-factor(tokens, at)?),
+        Some(Token::Operator('+')) => factor(tokens, at),
+        Some(Token::Open) => {
+            let value = sum(tokens, at)?;
+            match tokens.get(*at) {
+                Some(Token::Close) => {
+                    *at += 1;
+                    Ok(value)
+                }
+                _ => Err(AmountError::UnbalancedParentheses),
+            }
+        }
+        _ => Err(AmountError::MissingOperand),
+    }
+}
@@ -0,0 +1,149 @@
+use std::fmt;
+
+use crate::api::bank::BankError;
+use crate::api::calendar::BusinessCalendar;
+use crate::api::date::Date;
+use crate::api::decimal::Decimal;
+use crate::api::money::Money;
+
+/// Whether the account buys or sells the foreign currency.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ForwardSide {
+    Buy,
+    Sell,
+}
+
+impl ForwardSide {
+    /// Parse "buy" or "sell" (any case).
+    pub fn parse(s: &str) -> Option<Self> {
+        match s.trim().to_lowercase().as_str() {
+            "buy" => Some(ForwardSide::Buy),
+            "sell" => Some(ForwardSide::Sell),
+            _ => None,
+        }
+    }
+
+    /// Lowercase name, as accepted by `parse`.
+    pub fn name(&self) -> &'static str {
+        match self {
+            ForwardSide::Buy => "buy",
+            ForwardSide::Sell => "sell",
+        }
+    }
+}
+
+/// Errors raised when a forward contract is booked.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ForwardError {
+    NonPositiveAmount,
+    NonPositiveRate,
+    /// The foreign currency is the account's own currency.
+    SameCurrency(String),
+    /// The value date is today or already past.
+    ValueDateNotInFuture(Date),
+}
+
+impl fmt::Display for ForwardError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ForwardError::NonPositiveAmount => write!(f, "forward amount must be greater than zero"),
+            ForwardError::NonPositiveRate => write!(f, "forward rate must be greater than zero"),
+            ForwardError::SameCurrency(code) => write!(f, "cannot book a {} forward on a {} account", code, code),
+            ForwardError::ValueDateNotInFuture(date) => write!(f, "value date {} is not in the future", date),
+        }
+    }
+}
+
+impl std::error::Error for ForwardError {}
+
+/// An agreement, booked for the account named `account`, to exchange
+/// `amount` of a foreign currency at `rate` on `value_date`. `rate` is in
+/// units of the account's currency per unit of `amount.currency`, like the
+/// rates in `Forex`. On the value date the account is credited (`Sell`) or
+/// debited (`Buy`) `amount` × `rate`; the foreign leg is delivered outside
+/// the bank. `id` is assigned by the `Bank`, and `settled` is set once the
+/// settlement has been posted.
+#[derive(Debug, Clone)]
+pub struct ForwardContract {
+    pub id: usize,
+    pub account: String,
+    pub side: ForwardSide,
+    pub amount: Money,
+    pub rate: Decimal,
+    pub value_date: Date,
+    pub settled: bool,
+}
+
+impl ForwardContract {
+    /// Book a forward for an account held in `account_currency`. Fails if
+    /// the amount or rate is not positive or the foreign currency is the
+    /// account's own; `Bank::book_forward` also requires a value date after
+    /// today.
+    pub fn new(
+        id: usize,
+        account: &str,
+        account_currency: &str,
+        side: ForwardSide,
+        amount: Money,
+        rate: Decimal,
+        value_date: Date,
+    ) -> Result<Self, ForwardError> {
+        if amount.amount <= Decimal::ZERO {
+            return Err(ForwardError::NonPositiveAmount);
+        }
+        if rate <= Decimal::ZERO {
+            return Err(ForwardError::NonPositiveRate);
+        }
+        if amount.currency == account_currency {
+            return Err(ForwardError::SameCurrency(amount.currency));
+        }
+        Ok(Self { id, account: account.to_string(), side, amount, rate, value_date, settled: false })
+    }
+
+    /// The date the forward settles: its value date rolled to a business
+    /// day of `calendar`.
+    pub fn settlement_date(&self, calendar: &BusinessCalendar) -> Date {
+        calendar.roll(self.value_date)
+    }
+
+    /// Whether the forward is still open and settles on or before `today`.
+    pub fn is_due(&self, today: Date, calendar: &BusinessCalendar) -> bool {
+        !self.settled && self.settlement_date(calendar) <= today
+    }
+
+    /// The account-currency amount exchanged at the agreed rate, unrounded.
+    /// Returns `None` on overflow.
+    pub fn settlement_amount(&self) -> Option<Decimal> {
+        self.amount.amount.checked_mul(self.rate)
+    }
+
+    /// Gain (positive) or loss to the account from holding the forward
+    /// rather than exchanging at `spot`, in account-currency units per the
+    /// whole contract: amount × (spot − rate) when buying, amount × (rate −
+    /// spot) when selling. Undiscounted. Returns `None` on overflow.
+    pub fn mark_to_market(&self, spot: Decimal) -> Option<Decimal> {
+        let difference = match self.side {
+            ForwardSide::Buy => spot.checked_sub(self.rate)?,
+            ForwardSide::Sell => self.rate.checked_sub(spot)?,
+        };
+        self.amount.amount.checked_mul(difference)
+    }
+}
+
+/// A forward marked against the current spot rate. `spot` is in the same
+/// units as the contract's `rate`, and `mark_to_market` is in the account's
+/// currency, rounded to its minor unit.
+#[derive(Debug, Clone)]
+pub struct ForwardValuation {
+    pub contract: ForwardContract,
+    pub spot: Decimal,
+    pub mark_to_market: Money,
+}
+
+/// One settlement attempted by the end-of-day job: the amount posted to
+/// the account, or why it was refused (the forward then stays open).
+#[derive(Debug, Clone)]
+pub struct ForwardSettlement {
+    pub contract: ForwardContract,
+    pub result: Result<Money, BankError>,
+}
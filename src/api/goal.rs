@@ -0,0 +1,115 @@
+use std::fmt;
+
+use crate::api::account::DAY_COUNT_BASIS;
+use crate::api::date::Date;
+use crate::api::decimal::{Decimal, RoundingStrategy};
+use crate::api::loan::PaymentFrequency;
+use crate::api::money::Money;
+use crate::api::rates;
+
+/// Errors raised when a savings goal is set or looked up.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum GoalError {
+    /// The target rounds to zero or below in the account's minor unit.
+    NonPositiveTarget,
+    /// The target date is today or already past.
+    DateNotInFuture(Date),
+    /// The account already has a goal with this name.
+    Duplicate(String),
+    NotFound(String),
+}
+
+impl fmt::Display for GoalError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            GoalError::NonPositiveTarget => write!(f, "goal target must be greater than zero"),
+            GoalError::DateNotInFuture(date) => write!(f, "goal date {} is not in the future", date),
+            GoalError::Duplicate(name) => write!(f, "a goal named {} already exists", name),
+            GoalError::NotFound(name) => write!(f, "no goal named {}", name),
+        }
+    }
+}
+
+impl std::error::Error for GoalError {}
+
+/// An amount an account's holder wants to have saved by `target_date`.
+/// `target` is in the account's currency. Goals are tracked against the
+/// whole account balance, so several goals on one account each count the
+/// same money.
+#[derive(Debug, Clone)]
+pub struct SavingsGoal {
+    pub name: String,
+    pub target: Money,
+    pub target_date: Date,
+}
+
+/// Where a goal stands on a given day.
+/// - `saved`: the balance counted toward the goal, at most the target.
+/// - `fraction`: `saved` / target, from 0 to 1.
+/// - `periods_left`: whole contribution periods before the target date
+///   (at least 1 while the date is ahead, 0 once it has passed).
+/// - `contribution`: the deposit needed at the end of each of those
+///   periods, with the account's interest, to reach the target; rounded up
+///   to the minor unit and zero once the goal is reached. After the date
+///   it is the whole shortfall.
+#[derive(Debug, Clone)]
+pub struct GoalProgress {
+    pub saved: Money,
+    pub fraction: Decimal,
+    pub periods_left: u32,
+    pub contribution: Money,
+}
+
+impl GoalProgress {
+    pub fn is_reached(&self) -> bool {
+        self.fraction >= Decimal::ONE
+    }
+}
+
+impl SavingsGoal {
+    pub fn new(name: &str, target: Money, target_date: Date) -> Self {
+        Self { name: name.to_string(), target, target_date }
+    }
+
+    /// Progress on `today` for an account holding `balance` at
+    /// `annual_interest`, contributing at `frequency`. Interest is
+    /// compounded once per period at `annual_interest` / periods per year,
+    /// and amounts are rounded to `dp` decimal places. Returns `None` if
+    /// the projection overflows.
+    ///
+    /// Contribution = (Target − Balance × (1 + r)^n) × r / ((1 + r)^n − 1),
+    /// or (Target − Balance) / n at 0%. At a negative rate the balance and
+    /// each contribution shrink every period, so more is needed.
+    pub fn progress(&self, balance: &Money, annual_interest: Decimal, frequency: PaymentFrequency, today: Date, dp: u32) -> Option<GoalProgress> {
+        let money = |amount: Decimal| Money::new(amount, &self.target.currency);
+        let target = self.target.amount;
+        let held = balance.amount.max(Decimal::ZERO);
+        let saved = held.min(target);
+        let fraction = if target > Decimal::ZERO { saved.checked_div(target)? } else { Decimal::ONE };
+
+        let days = self.target_date.days() - today.days();
+        let ppy = frequency.periods_per_year();
+        let periods_left = if days <= 0 { 0 } else { u32::try_from(days * i64::from(ppy) / i64::from(DAY_COUNT_BASIS)).unwrap_or(u32::MAX).max(1) };
+
+        let contribution = if held >= target {
+            Decimal::ZERO
+        } else if periods_left == 0 {
+            target - held
+        } else {
+            let r = rates::periodic(annual_interest, ppy);
+            let growth = (0..periods_left).try_fold(Decimal::ONE, |acc, _| acc.checked_mul(Decimal::ONE + r))?;
+            let shortfall = target.checked_sub(held.checked_mul(growth)?)?.max(Decimal::ZERO);
+            if r.is_zero() {
+                shortfall.checked_div(Decimal::from(periods_left))?
+            } else {
+                shortfall.checked_mul(r)?.checked_div(growth.checked_sub(Decimal::ONE)?)?
+            }
+        };
+        Some(GoalProgress {
+            saved: money(saved),
+            fraction,
+            periods_left,
+            contribution: money(contribution.round_dp_with_strategy(dp, RoundingStrategy::AwayFromZero)),
+        })
+    }
+}
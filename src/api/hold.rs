@@ -0,0 +1,55 @@
+use std::fmt;
+
+use crate::api::date::Date;
+use crate::api::decimal::Decimal;
+use crate::api::money::Money;
+
+/// Days a hold lasts unless given an expiry.
+pub const DEFAULT_HOLD_DAYS: i64 = 7;
+
+/// Errors raised when a hold is placed, released, or captured.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum HoldError {
+    /// A hold cannot be placed without a reason.
+    BlankReason,
+    /// The expiry (given) is before today.
+    ExpiryPassed(Date),
+    NotFound(usize),
+    /// A capture cannot take more than the hold (ID given) keeps back,
+    /// `held` in the account's currency.
+    CaptureExceedsHold { id: usize, held: Decimal },
+}
+
+impl fmt::Display for HoldError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            HoldError::BlankReason => write!(f, "a hold needs a reason"),
+            HoldError::ExpiryPassed(date) => write!(f, "hold expiry {} is already past", date),
+            HoldError::NotFound(id) => write!(f, "no hold with ID {}", id),
+            HoldError::CaptureExceedsHold { id, held } => write!(f, "hold {} keeps back only {}", id, held),
+        }
+    }
+}
+
+impl std::error::Error for HoldError {}
+
+/// An authorization hold: `amount`, in the account's currency, kept back
+/// from its available balance for `reason` from `placed` through
+/// `expiry`, without posting anything. It ends when released, when
+/// captured as a withdrawal, or when it lapses after `expiry`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Hold {
+    pub id: usize,
+    pub amount: Money,
+    pub reason: String,
+    pub placed: Date,
+    pub expiry: Date,
+}
+
+impl Hold {
+    /// Returns true while the hold keeps money back on `on`: through its
+    /// expiry day.
+    pub fn is_active(&self, on: Date) -> bool {
+        on <= self.expiry
+    }
+}
@@ -0,0 +1,64 @@
+use std::collections::VecDeque;
+
+use crate::api::bank::TransferReceipt;
+use crate::api::money::Money;
+
+/// How many idempotency keys the bank remembers; past this, the oldest key
+/// is forgotten and a retry with it posts again.
+pub const IDEMPOTENCY_KEYS_KEPT: usize = 1000;
+
+/// What a keyed request did the first time, returned again on retries.
+/// - `Posted`: the balance `Bank::post_transaction_once` returned.
+/// - `Transferred`: the receipt `Bank::transfer_once` returned.
+#[derive(Debug, Clone)]
+pub enum IdempotentResult {
+    Posted(Money),
+    Transferred(Box<TransferReceipt>),
+}
+
+/// One remembered key: the caller's `key`, the `request` it was first used
+/// for (e.g. "deposit Alice 100 PHP"), and its result.
+#[derive(Debug, Clone)]
+pub struct IdempotencyRecord {
+    pub key: String,
+    pub request: String,
+    pub result: IdempotentResult,
+}
+
+/// The most recent `IDEMPOTENCY_KEYS_KEPT` keys, oldest first. Only
+/// requests that succeeded are remembered, so a failed one can be retried
+/// with the same key.
+#[derive(Debug, Clone, Default)]
+pub struct IdempotencyStore {
+    records: VecDeque<IdempotencyRecord>,
+}
+
+impl IdempotencyStore {
+    /// The record for `key`, if it is still remembered.
+    pub fn get(&self, key: &str) -> Option<&IdempotencyRecord> {
+        self.records.iter().find(|r| r.key == key)
+    }
+
+    /// Remember `record`, forgetting the oldest key once more than
+    /// `IDEMPOTENCY_KEYS_KEPT` are held.
+    pub fn insert(&mut self, record: IdempotencyRecord) {
+        self.records.retain(|r| r.key != record.key);
+        self.records.push_back(record);
+        while self.records.len() > IDEMPOTENCY_KEYS_KEPT {
+            self.records.pop_front();
+        }
+    }
+
+    /// The remembered keys, oldest first.
+    pub fn iter(&self) -> impl Iterator<Item = &IdempotencyRecord> {
+        self.records.iter()
+    }
+
+    pub fn len(&self) -> usize {
+        self.records.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.records.is_empty()
+    }
+}
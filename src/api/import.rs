@@ -0,0 +1,385 @@
+use std::collections::BTreeMap;
+use std::fmt;
+use std::io::{self, BufRead};
+
+use crate::api::account::{AccountError, TransactionType};
+use crate::api::bank::BankError;
+use crate::api::date::{parse_offset, Date};
+use crate::api::decimal::Decimal;
+use crate::api::money::Money;
+
+/// One transaction to import as it was originally posted: the unsigned
+/// `amount`, when (`timestamp`, Unix seconds UTC), and how it was filed.
+#[derive(Debug, Clone)]
+pub struct ImportRow {
+    pub tx_type: TransactionType,
+    pub amount: Money,
+    pub timestamp: i64,
+    pub memo: String,
+    pub category: Option<String>,
+}
+
+/// Why an import row was skipped.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ImportFailure {
+    /// The row could not be read, e.g. a bad date or amount.
+    Malformed(String),
+    /// The account refused the transaction, e.g. insufficient funds.
+    Rejected(AccountError),
+    /// The bank refused the row, e.g. an account name already taken or an
+    /// archived account.
+    Refused(BankError),
+}
+
+/// A skipped row; `row` counts data rows from 1, not counting the header.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ImportError {
+    pub row: usize,
+    pub failure: ImportFailure,
+}
+
+impl fmt::Display for ImportFailure {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ImportFailure::Malformed(reason) => write!(f, "{}", reason),
+            ImportFailure::Rejected(e) => write!(f, "{}", e),
+            ImportFailure::Refused(e) => write!(f, "{}", e),
+        }
+    }
+}
+
+impl fmt::Display for ImportError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "row {}: {}", self.row, self.failure)
+    }
+}
+
+/// The outcome of an import: how many rows were posted and why the others
+/// were skipped, in row order.
+#[derive(Debug, Clone, Default)]
+pub struct ImportReport {
+    pub imported: usize,
+    pub errors: Vec<ImportError>,
+}
+
+/// The outcome of `Bank::import_accounts_csv`: the accounts opened and why
+/// the other rows were skipped, in row order.
+#[derive(Debug, Clone, Default)]
+pub struct AccountImportReport {
+    pub opened: Vec<String>,
+    pub errors: Vec<ImportError>,
+}
+
+/// The outcome of `Bank::import_transactions_csv`: how many rows each
+/// account took, in the order the accounts first appear, and the skipped
+/// rows in row order, each with the account it named.
+#[derive(Debug, Clone, Default)]
+pub struct LedgerImportReport {
+    pub imported: Vec<(String, usize)>,
+    pub errors: Vec<(String, ImportError)>,
+}
+
+impl LedgerImportReport {
+    /// Rows posted across every account.
+    pub fn total(&self) -> usize {
+        self.imported.iter().map(|(_, n)| n).sum()
+    }
+}
+
+/// Reads import rows from CSV as written by `Account::export_csv`, one
+/// record at a time. The header names the columns, in any order:
+/// - required: `date` (`YYYY-MM-DD`), `type` (`deposit`/`withdraw`), and
+///   `amount` (unsigned);
+/// - optional: `time` (`HH:MM:SS`, UTC unless it ends in an offset such
+///   as `Z` or `+08:00`, which also shifts `date`), `currency` (must be the
+///   account's), `memo`, and `category`. Other columns, such as
+///   `balance`, are ignored.
+///
+/// Quoted fields may hold the delimiter, doubled quotes, and line breaks.
+/// A row that cannot be read is yielded as `Err` with the reason and the
+/// rest still follow; a read error ends the rows.
+pub struct CsvRows<R> {
+    lines: io::Lines<R>,
+    delimiter: char,
+    currency: String,
+    columns: Columns,
+    done: bool,
+}
+
+/// Positions of the known transaction columns in the header.
+struct Columns {
+    date: usize,
+    time: Option<usize>,
+    tx_type: usize,
+    amount: usize,
+    currency: Option<usize>,
+    memo: Option<usize>,
+    category: Option<usize>,
+}
+
+impl<R: BufRead> CsvRows<R> {
+    /// Read the header of `reader`. Rows are in `currency`, the account's.
+    /// Fails with `InvalidData` if the input is empty or the header lacks a
+    /// required column.
+    pub fn new(reader: R, currency: &str, delimiter: char) -> io::Result<Self> {
+        let mut lines = reader.lines();
+        let header = Header::read(&mut lines, delimiter)?;
+        let columns = Columns::find(&header)?;
+        Ok(Self { lines, delimiter, currency: currency.to_string(), columns, done: false })
+    }
+}
+
+impl<R: BufRead> Iterator for CsvRows<R> {
+    type Item = Result<ImportRow, String>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let record = next_record(&mut self.lines, self.delimiter, &mut self.done)?;
+        Some(record.and_then(|record| self.columns.parse(&record, &self.currency)))
+    }
+}
+
+/// Reads transactions for several accounts from CSV, such as the
+/// `transactions.csv` of `Bank::export_bundle`: the columns of `CsvRows`
+/// plus a required `account` column naming each row's account. Rows
+/// without a currency are in their account's, looked up in the map given
+/// to `new`. Each row is yielded with its account name (blank if the file
+/// could not be read) and is read like in `CsvRows`.
+pub struct LedgerRows<R> {
+    lines: io::Lines<R>,
+    delimiter: char,
+    currencies: BTreeMap<String, String>,
+    account: usize,
+    columns: Columns,
+    done: bool,
+}
+
+impl<R: BufRead> LedgerRows<R> {
+    /// Read the header of `reader`. `currencies` maps account names to
+    /// their currency codes. Fails with `InvalidData` if the input is empty
+    /// or the header lacks a required column.
+    pub fn new(reader: R, currencies: BTreeMap<String, String>, delimiter: char) -> io::Result<Self> {
+        let mut lines = reader.lines();
+        let header = Header::read(&mut lines, delimiter)?;
+        let account = header.required("account")?;
+        let columns = Columns::find(&header)?;
+        Ok(Self { lines, delimiter, currencies, account, columns, done: false })
+    }
+}
+
+impl<R: BufRead> Iterator for LedgerRows<R> {
+    type Item = (String, Result<ImportRow, String>);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        match next_record(&mut self.lines, self.delimiter, &mut self.done)? {
+            Ok(record) => {
+                let account = record.get(self.account).map_or("", |s| s.trim()).to_string();
+                let currency = self.currencies.get(&account).map_or("", String::as_str);
+                let row = match account.is_empty() {
+                    true => Err(String::from("blank account name")),
+                    false => self.columns.parse(&record, currency),
+                };
+                Some((account, row))
+            }
+            Err(reason) => Some((String::new(), Err(reason))),
+        }
+    }
+}
+
+/// One account to open, as read by `AccountRows`. `currency` defaults to
+/// the base currency and `annual_interest` to the bank's rate.
+#[derive(Debug, Clone)]
+pub struct AccountRow {
+    pub name: String,
+    pub currency: Option<String>,
+    pub annual_interest: Option<Decimal>,
+    pub aliases: Vec<String>,
+}
+
+/// Reads accounts to open from CSV, such as the `accounts.csv` of
+/// `Bank::export_bundle`. The header names the columns, in any order:
+/// `name` is required; `currency`, `annual_interest` (a fraction, e.g.
+/// `0.05`), and `aliases` (separated by `;`) are optional. Other columns,
+/// such as `balance`, are ignored. Records are read like in `CsvRows`.
+pub struct AccountRows<R> {
+    lines: io::Lines<R>,
+    delimiter: char,
+    name: usize,
+    currency: Option<usize>,
+    annual_interest: Option<usize>,
+    aliases: Option<usize>,
+    done: bool,
+}
+
+impl<R: BufRead> AccountRows<R> {
+    /// Read the header of `reader`. Fails with `InvalidData` if the input
+    /// is empty or the header has no `name` column.
+    pub fn new(reader: R, delimiter: char) -> io::Result<Self> {
+        let mut lines = reader.lines();
+        let header = Header::read(&mut lines, delimiter)?;
+        Ok(Self {
+            name: header.required("name")?,
+            currency: header.find("currency"),
+            annual_interest: header.find("annual_interest"),
+            aliases: header.find("aliases"),
+            lines,
+            delimiter,
+            done: false,
+        })
+    }
+
+    fn parse(&self, record: &[String]) -> Result<AccountRow, String> {
+        let cell = |i: usize| record.get(i).map_or("", |s| s.trim());
+        let optional = |i: Option<usize>| i.map(cell).filter(|s| !s.is_empty());
+        let name = cell(self.name);
+        if name.is_empty() {
+            return Err(String::from("blank account name"));
+        }
+        let annual_interest = match optional(self.annual_interest) {
+            None => None,
+            Some(raw) => Some(raw.parse().map_err(|_| format!("invalid annual_interest {}", raw))?),
+        };
+        Ok(AccountRow {
+            name: name.to_string(),
+            currency: optional(self.currency).map(str::to_uppercase),
+            annual_interest,
+            aliases: optional(self.aliases).map_or(Vec::new(), |raw| raw.split(';').map(str::trim).filter(|a| !a.is_empty()).map(str::to_string).collect()),
+        })
+    }
+}
+
+impl<R: BufRead> Iterator for AccountRows<R> {
+    type Item = Result<AccountRow, String>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let record = next_record(&mut self.lines, self.delimiter, &mut self.done)?;
+        Some(record.and_then(|record| self.parse(&record)))
+    }
+}
+
+/// A CSV header row, for finding columns by name.
+struct Header(Vec<String>);
+
+impl Header {
+    /// Read the first record of `lines`; fails if there is none.
+    fn read<R: BufRead>(lines: &mut io::Lines<R>, delimiter: char) -> io::Result<Self> {
+        read_record(lines, delimiter)?.map(Header).ok_or_else(|| invalid("the CSV file is empty"))
+    }
+
+    fn find(&self, name: &str) -> Option<usize> {
+        self.0.iter().position(|h| h.trim().eq_ignore_ascii_case(name))
+    }
+
+    fn required(&self, name: &str) -> io::Result<usize> {
+        self.find(name).ok_or_else(|| invalid(&format!("the CSV header has no {} column", name)))
+    }
+}
+
+impl Columns {
+    fn find(header: &Header) -> io::Result<Self> {
+        Ok(Self {
+            date: header.required("date")?,
+            time: header.find("time"),
+            tx_type: header.required("type")?,
+            amount: header.required("amount")?,
+            currency: header.find("currency"),
+            memo: header.find("memo"),
+            category: header.find("category"),
+        })
+    }
+
+    /// Read one record; a row without a currency is in `currency`.
+    fn parse(&self, record: &[String], currency: &str) -> Result<ImportRow, String> {
+        let cell = |i: usize| record.get(i).map_or("", |s| s.trim());
+        let optional = |i: Option<usize>| i.map(cell).filter(|s| !s.is_empty());
+
+        let date = Date::parse(cell(self.date)).ok_or_else(|| format!("invalid date {}", cell(self.date)))?;
+        let seconds = match optional(self.time) {
+            None => 0,
+            Some(raw) => parse_time(raw).ok_or_else(|| format!("invalid time {}", raw))?,
+        };
+        let tx_type = match cell(self.tx_type).to_lowercase().as_str() {
+            "deposit" => TransactionType::Deposit,
+            "withdraw" | "withdrawal" => TransactionType::Withdraw,
+            other => return Err(format!("invalid type {} (expected deposit or withdraw)", other)),
+        };
+        let amount: Decimal = cell(self.amount).parse().map_err(|_| format!("invalid amount {}", cell(self.amount)))?;
+        let currency = optional(self.currency).map_or_else(|| currency.to_string(), str::to_uppercase);
+        Ok(ImportRow {
+            tx_type,
+            amount: Money::new(amount, &currency),
+            timestamp: date.timestamp() + seconds,
+            memo: optional(self.memo).unwrap_or_default().to_string(),
+            category: optional(self.category).map(str::to_string),
+        })
+    }
+}
+
+/// The next record with any non-blank field, skipping blank lines. A read
+/// error is returned once as `Err` and ends the rows, setting `done`.
+fn next_record<R: BufRead>(lines: &mut io::Lines<R>, delimiter: char, done: &mut bool) -> Option<Result<Vec<String>, String>> {
+    while !*done {
+        match read_record(lines, delimiter) {
+            Ok(None) => *done = true,
+            Ok(Some(record)) if record.iter().all(|f| f.trim().is_empty()) => continue,
+            Ok(Some(record)) => return Some(Ok(record)),
+            Err(e) => {
+                *done = true;
+                return Some(Err(format!("cannot read the file: {}", e)));
+            }
+        }
+    }
+    None
+}
+
+/// The next CSV record, joining lines while a quoted field is open.
+pub(crate) fn read_record<R: BufRead>(lines: &mut io::Lines<R>, delimiter: char) -> io::Result<Option<Vec<String>>> {
+    let Some(first) = lines.next().transpose()? else {
+        return Ok(None);
+    };
+    let (mut fields, mut field, mut quoted) = (Vec::new(), String::new(), false);
+    let mut line = first;
+    loop {
+        let mut chars = line.trim_end_matches('\r').chars().peekable();
+        while let Some(ch) = chars.next() {
+            match ch {
+                '"' if quoted && chars.peek() == Some(&'"') => {
+                    field.push('"');
+                    chars.next();
+                }
+                '"' => quoted = !quoted,
+                c if c == delimiter && !quoted => fields.push(std::mem::take(&mut field)),
+                c => field.push(c),
+            }
+        }
+        if !quoted {
+            break;
+        }
+        match lines.next().transpose()? {
+            Some(next) => {
+                field.push('\n');
+                line = next;
+            }
+            None => break,
+        }
+    }
+    fields.push(field);
+    Ok(Some(fields))
+}
+
+/// Seconds from UTC midnight of the row's date to an `HH:MM:SS` (or
+/// `HH:MM`) time, which is UTC unless it ends in `Z` or an offset such as
+/// `+08:00`.
+fn parse_time(raw: &str) -> Option<i64> {
+    let (clock, offset) = match raw.find(['Z', 'z', '+', '-']) {
+        Some(at) if raw[at..].eq_ignore_ascii_case("z") => (&raw[..at], 0),
+        Some(at) => (&raw[..at], parse_offset(&raw[at..])?),
+        None => (raw, 0),
+    };
+    let mut parts = clock.split(':').map(|p| p.parse::<i64>().ok());
+    let (h, m, s) = (parts.next()??, parts.next()??, parts.next().unwrap_or(Some(0))?);
+    ((0..24).contains(&h) && (0..60).contains(&m) && (0..60).contains(&s)).then_some(h * 3600 + m * 60 + s - i64::from(offset) * 60)
+}
+
+fn invalid(reason: &str) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, reason.to_string())
+}
@@ -0,0 +1,83 @@
+use std::io;
+use std::sync::{Arc, Mutex, MutexGuard, PoisonError};
+
+use crate::api::date::now_timestamp;
+use crate::api::event::BankEvent;
+use crate::api::notify::Notifier;
+
+/// How many messages an `Inbox` keeps; the oldest are dropped first.
+pub const INBOX_LIMIT: usize = 500;
+
+/// One event kept in an `Inbox`: its number, when it arrived (Unix
+/// seconds), and whether it has been shown.
+#[derive(Debug, Clone, PartialEq)]
+pub struct InboxMessage {
+    pub id: usize,
+    pub received: i64,
+    pub event: BankEvent,
+    pub read: bool,
+}
+
+#[derive(Debug, Default)]
+struct Messages {
+    messages: Vec<InboxMessage>,
+    next_id: usize,
+}
+
+/// A notifier that keeps the alerts and large-transaction flags published
+/// on an `EventBus` until they are dismissed, so a front end can show them
+/// when the user is ready rather than as they scroll past. Clones share the
+/// same messages: subscribe one to the bus and read from another. Messages
+/// are numbered from 1 in order of arrival and last as long as the inbox.
+#[derive(Debug, Clone, Default)]
+pub struct Inbox {
+    shared: Arc<Mutex<Messages>>,
+}
+
+impl Inbox {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Every message kept, oldest first.
+    pub fn messages(&self) -> Vec<InboxMessage> {
+        self.lock().messages.clone()
+    }
+
+    /// Mark the messages numbered `ids` as read.
+    pub fn mark_read(&self, ids: &[usize]) {
+        for message in self.lock().messages.iter_mut().filter(|m| ids.contains(&m.id)) {
+            message.read = true;
+        }
+    }
+
+    /// Remove the messages numbered `ids`, returning how many there were.
+    pub fn dismiss(&self, ids: &[usize]) -> usize {
+        let mut shared = self.lock();
+        let before = shared.messages.len();
+        shared.messages.retain(|m| !ids.contains(&m.id));
+        before - shared.messages.len()
+    }
+
+    fn lock(&self) -> MutexGuard<'_, Messages> {
+        self.shared.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+}
+
+impl Notifier for Inbox {
+    /// Keep `AlertRaised`, `LargeTransactionFlagged`, and
+    /// `ApprovalRequested` events; the rest are passed over.
+    fn notify(&mut self, event: &BankEvent) -> io::Result<()> {
+        if !matches!(event, BankEvent::AlertRaised { .. } | BankEvent::LargeTransactionFlagged { .. } | BankEvent::ApprovalRequested { .. }) {
+            return Ok(());
+        }
+        let mut shared = self.lock();
+        if shared.messages.len() == INBOX_LIMIT {
+            shared.messages.remove(0);
+        }
+        shared.next_id += 1;
+        let id = shared.next_id;
+        shared.messages.push(InboxMessage { id, received: now_timestamp(), event: event.clone(), read: false });
+        Ok(())
+    }
+}
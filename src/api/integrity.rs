@@ -0,0 +1,104 @@
+use std::fmt;
+
+use crate::api::account::TransactionType;
+use crate::api::money::Money;
+
+/// A broken ledger invariant found by `Bank::verify`. `index` is a
+/// transaction's 0-based position in its account's history.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Violation {
+    /// Two accounts share a name, so only the first can be reached by it.
+    DuplicateAccount(String),
+    /// An account's alias is also an account's name or an earlier alias,
+    /// so it finds another account or the earlier one.
+    ShadowedAlias { account: String, alias: String },
+    /// A transaction is recorded at other than the account's minor unit,
+    /// so it does not add up with the rest.
+    MinorUnitMismatch { account: String, index: usize, dp: u32 },
+    /// The transactions' sum does not fit in the ledger's range.
+    SumOverflow(String),
+    /// The running balance the account keeps disagrees with the sum of its
+    /// transactions.
+    BalanceMismatch { account: String, recorded: Money, summed: Money },
+    /// The running balance went below zero, the lowest an account may
+    /// hold, with this transaction. Reported once per account.
+    BelowMinimum { account: String, index: usize, balance: Money },
+    /// One leg of a transfer, standing-order transfer, or sweep has no matching
+    /// leg in the other account.
+    UnmatchedLeg { account: String, index: usize, memo: String },
+    /// A transfer's two legs in the same currency differ.
+    UnbalancedTransfer { from: String, to: String, debited: Money, credited: Money },
+    /// A review-queue entry matches no posting of its account.
+    UnmatchedFlag { id: usize, account: String, tx_type: TransactionType, amount: Money },
+    /// A customer, loan, order, sweep, forward, or interest payout names an
+    /// account that does not exist; `owner` says which, e.g. "loan 2".
+    DanglingReference { owner: String, account: String },
+}
+
+impl Violation {
+    /// Stable snake_case name for the violation, e.g. "below_minimum".
+    pub fn kind(&self) -> &'static str {
+        match self {
+            Violation::DuplicateAccount(_) => "duplicate_account",
+            Violation::ShadowedAlias { .. } => "shadowed_alias",
+            Violation::MinorUnitMismatch { .. } => "minor_unit_mismatch",
+            Violation::SumOverflow(_) => "sum_overflow",
+            Violation::BalanceMismatch { .. } => "balance_mismatch",
+            Violation::BelowMinimum { .. } => "below_minimum",
+            Violation::UnmatchedLeg { .. } => "unmatched_leg",
+            Violation::UnbalancedTransfer { .. } => "unbalanced_transfer",
+            Violation::UnmatchedFlag { .. } => "unmatched_flag",
+            Violation::DanglingReference { .. } => "dangling_reference",
+        }
+    }
+}
+
+impl fmt::Display for Violation {
+    /// One-line English description; transactions are numbered from 1, as
+    /// in the history.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Violation::DuplicateAccount(name) => write!(f, "more than one account is named {}", name),
+            Violation::ShadowedAlias { account, alias } => write!(f, "{} alias {} does not reach it", account, alias),
+            Violation::MinorUnitMismatch { account, index, dp } => {
+                write!(f, "{} transaction {} is recorded to {} decimal place(s), not the account's", account, index + 1, dp)
+            }
+            Violation::SumOverflow(account) => write!(f, "{} transactions add up to more than the ledger can hold", account),
+            Violation::BalanceMismatch { account, recorded, summed } => {
+                write!(f, "{} balance is {} but its transactions add up to {}", account, recorded, summed)
+            }
+            Violation::BelowMinimum { account, index, balance } => {
+                write!(f, "{} went below zero to {} at transaction {}", account, balance, index + 1)
+            }
+            Violation::UnmatchedLeg { account, index, memo } => {
+                write!(f, "{} transaction {} ({}) has no matching leg", account, index + 1, memo)
+            }
+            Violation::UnbalancedTransfer { from, to, debited, credited } => {
+                write!(f, "transfer from {} to {} debited {} but credited {}", from, to, debited, credited)
+            }
+            Violation::UnmatchedFlag { id, account, tx_type, amount } => {
+                let kind = match tx_type {
+                    TransactionType::Deposit => "deposit",
+                    TransactionType::Withdraw => "withdrawal",
+                };
+                write!(f, "flagged transaction {}, a {} of {} on {}, matches no posting", id, kind, amount, account)
+            }
+            Violation::DanglingReference { owner, account } => write!(f, "{} refers to missing account {}", owner, account),
+        }
+    }
+}
+
+/// What `Bank::verify` checked and the violations it found, in account
+/// order, then transfers, the review queue, and references.
+#[derive(Debug, Clone, Default)]
+pub struct IntegrityReport {
+    pub accounts: usize,
+    pub transactions: usize,
+    pub violations: Vec<Violation>,
+}
+
+impl IntegrityReport {
+    pub fn is_clean(&self) -> bool {
+        self.violations.is_empty()
+    }
+}
@@ -0,0 +1,301 @@
+use std::ops::Range;
+
+use crate::api::account::{Transaction, TransactionType};
+use crate::api::date::Date;
+use crate::api::decimal::Decimal;
+
+/// An account's transactions in posting order, stored column by column
+/// (struct of arrays) rather than as a `Vec<Transaction>`:
+/// - `units`, `dps`, `timestamps`, and `sequences` are parallel vectors,
+///   so sums and date filters run over plain `i64` slices;
+/// - memos share one string buffer, `memo_ends[i]` being where memo `i`
+///   stops, instead of one heap allocation each;
+/// - categories are interned: `category_ids[i]` is 0 for none, else a
+///   1-based index into `category_names`;
+/// - tags are interned too, into `tag_names`, and share one id buffer:
+///   `tag_ends[i]` is where transaction `i`'s ids in `tag_ids` stop.
+///
+/// Read transactions through `get`, `last`, or `iter`, which yield
+/// borrowed `TransactionRef` views; `push` and `pop` take and give back
+/// owned `Transaction`s.
+#[derive(Debug, Clone, Default)]
+pub struct TransactionLog {
+    units: Vec<i64>,
+    dps: Vec<u32>,
+    timestamps: Vec<i64>,
+    sequences: Vec<u64>,
+    memo_text: String,
+    memo_ends: Vec<usize>,
+    category_ids: Vec<u32>,
+    category_names: Vec<String>,
+    tag_ids: Vec<u32>,
+    tag_ends: Vec<usize>,
+    tag_names: Vec<String>,
+}
+
+/// A transaction read in place from a `TransactionLog`. It has the same
+/// fields and methods as `Transaction`, with the memo, category, and tags
+/// borrowed; `to_transaction` makes an owned copy.
+#[derive(Debug, Clone, Copy)]
+pub struct TransactionRef<'a> {
+    pub units: i64,
+    pub dp: u32,
+    pub timestamp: i64,
+    pub sequence: u64,
+    pub memo: &'a str,
+    pub category: Option<&'a str>,
+    pub tags: Tags<'a>,
+}
+
+/// A transaction's tags, borrowed from its `TransactionLog`.
+#[derive(Debug, Clone, Copy)]
+pub struct Tags<'a> {
+    ids: &'a [u32],
+    names: &'a [String],
+}
+
+impl<'a> Tags<'a> {
+    /// The tags in the order they were given.
+    pub fn iter(&self) -> impl Iterator<Item = &'a str> + use<'a> {
+        let names = self.names;
+        self.ids.iter().map(move |&id| names[id as usize - 1].as_str())
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.ids.is_empty()
+    }
+
+    pub fn contains(&self, tag: &str) -> bool {
+        self.iter().any(|t| t == tag)
+    }
+
+    /// The tags joined with ", ", or "" without any.
+    pub fn join(&self) -> String {
+        self.iter().collect::<Vec<_>>().join(", ")
+    }
+}
+
+impl TransactionRef<'_> {
+    /// Signed value applied to the balance.
+    pub fn value(&self) -> Decimal {
+        Decimal::new(self.units, self.dp)
+    }
+
+    /// Transaction type implied by the sign of the stored value.
+    pub fn tx_type(&self) -> TransactionType {
+        if self.units < 0 { TransactionType::Withdraw } else { TransactionType::Deposit }
+    }
+
+    /// Unsigned amount of the transaction.
+    pub fn amount(&self) -> Decimal {
+        self.value().abs()
+    }
+
+    /// Calendar date (UTC) the transaction was posted.
+    pub fn date(&self) -> Date {
+        Date::from_timestamp(self.timestamp)
+    }
+
+    pub fn to_transaction(&self) -> Transaction {
+        Transaction {
+            units: self.units,
+            dp: self.dp,
+            timestamp: self.timestamp,
+            sequence: self.sequence,
+            memo: self.memo.to_string(),
+            category: self.category.map(str::to_string),
+            tags: self.tags.iter().map(str::to_string).collect(),
+        }
+    }
+}
+
+impl TransactionLog {
+    pub fn len(&self) -> usize {
+        self.units.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.units.is_empty()
+    }
+
+    pub fn push(&mut self, tx: Transaction) {
+        self.units.push(tx.units);
+        self.dps.push(tx.dp);
+        self.timestamps.push(tx.timestamp);
+        self.sequences.push(tx.sequence);
+        self.memo_text.push_str(&tx.memo);
+        self.memo_ends.push(self.memo_text.len());
+        let category = tx.category.as_deref().map_or(0, |name| intern(&mut self.category_names, name));
+        self.category_ids.push(category);
+        for tag in &tx.tags {
+            let id = intern(&mut self.tag_names, tag);
+            self.tag_ids.push(id);
+        }
+        self.tag_ends.push(self.tag_ids.len());
+    }
+
+    /// Remove and return the last transaction. Its category and tags stay
+    /// interned for reuse.
+    pub fn pop(&mut self) -> Option<Transaction> {
+        let tx = self.last()?.to_transaction();
+        self.units.pop();
+        self.dps.pop();
+        self.timestamps.pop();
+        self.sequences.pop();
+        self.memo_ends.pop();
+        self.memo_text.truncate(self.memo_ends.last().copied().unwrap_or(0));
+        self.category_ids.pop();
+        self.tag_ends.pop();
+        self.tag_ids.truncate(self.tag_ends.last().copied().unwrap_or(0));
+        Some(tx)
+    }
+
+    pub fn get(&self, index: usize) -> Option<TransactionRef<'_>> {
+        (index < self.len()).then(|| self.at(index))
+    }
+
+    pub fn last(&self) -> Option<TransactionRef<'_>> {
+        self.len().checked_sub(1).map(|index| self.at(index))
+    }
+
+    pub fn iter(&self) -> Iter<'_> {
+        Iter { log: self, range: 0..self.len() }
+    }
+
+    /// File the transaction at `index` under `category`, or under none.
+    /// Returns false if there is no such transaction.
+    pub fn set_category(&mut self, index: usize, category: Option<&str>) -> bool {
+        if index >= self.len() {
+            return false;
+        }
+        self.category_ids[index] = category.map_or(0, |name| intern(&mut self.category_names, name));
+        true
+    }
+
+    /// Replace the tags of the transaction at `index` with `tags`, an empty
+    /// slice removing them. Returns false if there is no such transaction.
+    pub fn set_tags(&mut self, index: usize, tags: &[String]) -> bool {
+        if index >= self.len() {
+            return false;
+        }
+        let ids: Vec<u32> = tags.iter().map(|tag| intern(&mut self.tag_names, tag)).collect();
+        let (start, end) = (self.tag_start(index), self.tag_ends[index]);
+        let added = ids.len();
+        self.tag_ids.splice(start..end, ids);
+        // Every later end is at or past `end`, so this cannot underflow.
+        for tag_end in &mut self.tag_ends[index..] {
+            *tag_end = *tag_end - (end - start) + added;
+        }
+        true
+    }
+
+    /// Number the transactions from `start` on consecutively from `next`,
+    /// returning the number after the last one given.
+    pub fn assign_sequences(&mut self, start: usize, mut next: u64) -> u64 {
+        for sequence in self.sequences.iter_mut().skip(start) {
+            *sequence = next;
+            next += 1;
+        }
+        next
+    }
+
+    /// Every transaction's signed value in minor units, in posting order.
+    pub fn units(&self) -> &[i64] {
+        &self.units
+    }
+
+    /// Every transaction's posting time (Unix seconds, UTC), in posting
+    /// order.
+    pub fn timestamps(&self) -> &[i64] {
+        &self.timestamps
+    }
+
+    /// Every transaction's bank-wide sequence number, in posting order.
+    pub fn sequences(&self) -> &[u64] {
+        &self.sequences
+    }
+
+    /// The sum of every transaction's minor units.
+    pub fn sum_units(&self) -> i64 {
+        self.units.iter().sum()
+    }
+
+    fn at(&self, index: usize) -> TransactionRef<'_> {
+        let start = if index == 0 { 0 } else { self.memo_ends[index - 1] };
+        let category = match self.category_ids[index] {
+            0 => None,
+            id => Some(self.category_names[id as usize - 1].as_str()),
+        };
+        TransactionRef {
+            units: self.units[index],
+            dp: self.dps[index],
+            timestamp: self.timestamps[index],
+            sequence: self.sequences[index],
+            memo: &self.memo_text[start..self.memo_ends[index]],
+            category,
+            tags: Tags { ids: &self.tag_ids[self.tag_start(index)..self.tag_ends[index]], names: &self.tag_names },
+        }
+    }
+
+    fn tag_start(&self, index: usize) -> usize {
+        if index == 0 { 0 } else { self.tag_ends[index - 1] }
+    }
+}
+
+/// The 1-based id of `name` in `names`, adding it if new.
+fn intern(names: &mut Vec<String>, name: &str) -> u32 {
+    let index = match names.iter().position(|n| n == name) {
+        Some(index) => index,
+        None => {
+            names.push(name.to_string());
+            names.len() - 1
+        }
+    };
+    u32::try_from(index + 1).expect("fewer than u32::MAX names")
+}
+
+/// Iterator over a `TransactionLog`'s transactions, oldest first.
+#[derive(Debug, Clone)]
+pub struct Iter<'a> {
+    log: &'a TransactionLog,
+    range: Range<usize>,
+}
+
+impl<'a> Iterator for Iter<'a> {
+    type Item = TransactionRef<'a>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.range.next().map(|index| self.log.at(index))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.range.size_hint()
+    }
+}
+
+impl DoubleEndedIterator for Iter<'_> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        self.range.next_back().map(|index| self.log.at(index))
+    }
+}
+
+impl ExactSizeIterator for Iter<'_> {}
+
+impl<'a> IntoIterator for &'a TransactionLog {
+    type Item = TransactionRef<'a>;
+    type IntoIter = Iter<'a>;
+
+    fn into_iter(self) -> Iter<'a> {
+        self.iter()
+    }
+}
+
+impl FromIterator<Transaction> for TransactionLog {
+    fn from_iter<I: IntoIterator<Item = Transaction>>(txs: I) -> Self {
+        let mut log = TransactionLog::default();
+        for tx in txs {
+            log.push(tx);
+        }
+        log
+    }
+}
@@ -0,0 +1,145 @@
+use std::fmt;
+
+use crate::api::bank::BankError;
+use crate::api::date::Date;
+use crate::api::decimal::Decimal;
+use crate::api::money::Money;
+use crate::api::moving_average::MovingAverage;
+
+/// Errors raised when a limit order is placed, amended, or expires.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum LimitOrderError {
+    NonPositiveAmount,
+    NonPositiveRate,
+    /// The order converts a currency into itself.
+    SameCurrency(String),
+    /// Neither side of the order is the account's currency (given).
+    AccountCurrencyNotInvolved(String),
+    /// The spot rate (given) already meets the limit.
+    LimitReached(Decimal),
+    /// The expiry date (given) is already past.
+    ExpiryPassed(Date),
+    /// An amendment would leave the order for less than the amount
+    /// (given) already converted.
+    BelowFilled(Decimal),
+    /// The order's expiry date (given) passed before its limit was reached.
+    Expired(Date),
+}
+
+impl fmt::Display for LimitOrderError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            LimitOrderError::NonPositiveAmount => write!(f, "limit order amount must be greater than zero"),
+            LimitOrderError::NonPositiveRate => write!(f, "limit rate must be greater than zero"),
+            LimitOrderError::SameCurrency(code) => write!(f, "cannot convert {} into itself", code),
+            LimitOrderError::AccountCurrencyNotInvolved(code) => write!(f, "a limit order must convert to or from the account's currency ({})", code),
+            LimitOrderError::LimitReached(spot) => write!(f, "the rate is already {}; convert now instead", spot),
+            LimitOrderError::ExpiryPassed(date) => write!(f, "expiry date {} has already passed", date),
+            LimitOrderError::BelowFilled(filled) => write!(f, "{} of the order is already filled", filled),
+            LimitOrderError::Expired(date) => write!(f, "the order expired on {}", date),
+        }
+    }
+}
+
+impl std::error::Error for LimitOrderError {}
+
+/// A standing instruction for the account named `account` to convert
+/// `amount` into currency `to` once the rate reaches `limit`. One side is
+/// the account's currency and the other a foreign currency, whose leg is
+/// delivered outside the bank. `limit` is in units of the account's
+/// currency per unit of the foreign one, like `Forex` rates and forward
+/// rates: an order converting foreign currency in fills when spot rises to
+/// `limit` or above, one converting it out when spot falls to `limit` or
+/// below. `id` is assigned by the `Bank`.
+///
+/// An order may be filled in parts, when the conversion limits leave room
+/// for only some of it on a day: `filled_amount` is how much of `amount`
+/// has been converted so far, and `filled` is set once all of it has. An
+/// order with an `expires` date stays open through that day and is
+/// dropped on the first rate update after it; without one it is good till
+/// cancelled.
+///
+/// With an `average`, reaching the limit is not enough: spot must also be
+/// on the order's side of that moving average of the pair's closing rates
+/// (at or above it converting foreign currency in, at or below it
+/// converting it out), so an order waits out a rate that has only briefly
+/// touched the limit. Until the pair has enough closes on record, such an
+/// order does not fill.
+#[derive(Debug, Clone)]
+pub struct LimitOrder {
+    pub id: usize,
+    pub account: String,
+    pub amount: Money,
+    pub to: String,
+    pub limit: Decimal,
+    pub filled: bool,
+    pub filled_amount: Decimal,
+    pub expires: Option<Date>,
+    pub average: Option<MovingAverage>,
+}
+
+impl LimitOrder {
+    /// Place an order for an account held in `account_currency`. Fails if
+    /// the amount or limit is not positive, or the order does not convert
+    /// between the account's currency and another.
+    pub fn new(id: usize, account: &str, account_currency: &str, amount: Money, to: &str, limit: Decimal) -> Result<Self, LimitOrderError> {
+        if amount.amount <= Decimal::ZERO {
+            return Err(LimitOrderError::NonPositiveAmount);
+        }
+        if limit <= Decimal::ZERO {
+            return Err(LimitOrderError::NonPositiveRate);
+        }
+        if amount.currency == to {
+            return Err(LimitOrderError::SameCurrency(amount.currency));
+        }
+        if amount.currency != account_currency && to != account_currency {
+            return Err(LimitOrderError::AccountCurrencyNotInvolved(account_currency.to_string()));
+        }
+        Ok(Self { id, account: account.to_string(), amount, to: to.to_string(), limit, filled: false, filled_amount: Decimal::ZERO, expires: None, average: None })
+    }
+
+    /// What is left of `amount` to convert.
+    pub fn remaining(&self) -> Money {
+        Money::new(self.amount.amount - self.filled_amount, &self.amount.currency)
+    }
+
+    /// Whether the order's last day is before `today`.
+    pub fn is_expired(&self, today: Date) -> bool {
+        self.expires.is_some_and(|last| last < today)
+    }
+
+    /// Whether the order converts foreign currency into the account (a
+    /// credit) rather than out of it (a debit).
+    pub fn is_inbound(&self, account_currency: &str) -> bool {
+        self.to == account_currency
+    }
+
+    /// The foreign side of the order.
+    pub fn foreign_currency(&self, account_currency: &str) -> &str {
+        if self.is_inbound(account_currency) { &self.amount.currency } else { &self.to }
+    }
+
+    /// Whether `spot` (account currency per foreign unit) meets the limit.
+    pub fn is_reached(&self, account_currency: &str, spot: Decimal) -> bool {
+        if self.is_inbound(account_currency) { spot >= self.limit } else { spot <= self.limit }
+    }
+
+    /// Whether `spot` is on the order's side of `average`, the value of
+    /// the order's moving average today.
+    pub fn is_confirmed(&self, account_currency: &str, spot: Decimal, average: Decimal) -> bool {
+        if self.is_inbound(account_currency) { spot >= average } else { spot <= average }
+    }
+}
+
+/// One fill attempted after a rate update: the order as it stands
+/// afterwards, the spot rate that reached its limit, the part of the
+/// order's amount converted (zero if none), and the amount posted to the
+/// account or why it was refused. A refused order stays open, except one
+/// that has expired (`LimitOrderError::Expired`), which is dropped.
+#[derive(Debug, Clone)]
+pub struct LimitOrderFill {
+    pub order: LimitOrder,
+    pub spot: Decimal,
+    pub converted: Money,
+    pub result: Result<Money, BankError>,
+}
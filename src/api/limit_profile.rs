@@ -0,0 +1,127 @@
+use std::fmt;
+
+use crate::api::customer::VerificationStatus;
+use crate::api::decimal::Decimal;
+
+/// What a limit profile is attached to: every account of a type, or every
+/// account whose holder's KYC check stands at a status. Accounts without
+/// a holder count as `Unverified`.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+pub enum LimitTarget {
+    AccountType(String),
+    Kyc(VerificationStatus),
+}
+
+impl LimitTarget {
+    /// Parse "type:NAME" or "kyc:STATUS" (e.g. "type:savings",
+    /// "kyc:unverified"). Type names are kept in lowercase.
+    pub fn parse(s: &str) -> Option<Self> {
+        let (kind, value) = s.trim().split_once(':')?;
+        match kind.trim().to_lowercase().as_str() {
+            "type" => Some(LimitTarget::AccountType(account_type(value)?)),
+            "kyc" => VerificationStatus::parse(value).map(LimitTarget::Kyc),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for LimitTarget {
+    /// As accepted by `parse`.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            LimitTarget::AccountType(name) => write!(f, "type:{}", name),
+            LimitTarget::Kyc(status) => write!(f, "kyc:{}", status.name()),
+        }
+    }
+}
+
+/// An account type name as stored: trimmed and lowercase, `None` if blank
+/// or holding a colon.
+pub fn account_type(name: &str) -> Option<String> {
+    let name = name.trim().to_lowercase();
+    (!name.is_empty() && !name.contains(':')).then_some(name)
+}
+
+/// The caps a `LimitTarget` puts on its accounts, in the base currency.
+/// - `max_deposit`: the most any one deposit, or transfer in, may credit.
+/// - `max_withdrawal`: the most any one withdrawal, or transfer out, may
+///   debit.
+/// - `max_daily_conversion`: the most the account may convert in a day
+///   (UTC), valued on the side it converts from.
+///
+/// `None` leaves that movement uncapped; a cap of zero stops it.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct LimitProfile {
+    pub max_deposit: Option<Decimal>,
+    pub max_withdrawal: Option<Decimal>,
+    pub max_daily_conversion: Option<Decimal>,
+}
+
+impl LimitProfile {
+    /// Returns true when nothing is capped.
+    pub fn is_unlimited(&self) -> bool {
+        self.max_deposit.is_none() && self.max_withdrawal.is_none() && self.max_daily_conversion.is_none()
+    }
+
+    /// The cap on `kind`, if any.
+    pub fn cap(&self, kind: LimitKind) -> Option<Decimal> {
+        match kind {
+            LimitKind::Deposit => self.max_deposit,
+            LimitKind::Withdrawal => self.max_withdrawal,
+            LimitKind::Conversion => self.max_daily_conversion,
+        }
+    }
+}
+
+/// The movement a `LimitProfile` cap applies to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LimitKind {
+    Deposit,
+    Withdrawal,
+    Conversion,
+}
+
+impl LimitKind {
+    /// Lowercase name: "deposit", "withdrawal", or "conversion".
+    pub fn name(&self) -> &'static str {
+        match self {
+            LimitKind::Deposit => "deposit",
+            LimitKind::Withdrawal => "withdrawal",
+            LimitKind::Conversion => "conversion",
+        }
+    }
+}
+
+/// An amount refused because it is above the `kind` cap of `limit` that
+/// the profile for `target` puts on `account`, `used` of it having already
+/// been converted that day (zero for deposits and withdrawals). Amounts
+/// are in `currency`, the base currency.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LimitExceeded {
+    pub account: String,
+    pub target: LimitTarget,
+    pub kind: LimitKind,
+    pub currency: String,
+    pub limit: Decimal,
+    pub used: Decimal,
+    pub amount: Decimal,
+}
+
+impl fmt::Display for LimitExceeded {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let code = &self.currency;
+        let holder = match &self.target {
+            LimitTarget::AccountType(name) => format!("{} accounts", name),
+            LimitTarget::Kyc(status) => format!("{} customers", status.name()),
+        };
+        match self.kind {
+            LimitKind::Conversion => write!(
+                f,
+                "converting {} {} from {} exceeds the daily conversion limit of {} {} for {} ({} {} already converted today)",
+                self.amount, code, self.account, self.limit, code, holder, self.used, code
+            ),
+            LimitKind::Deposit => write!(f, "a deposit of {} {} to {} exceeds the deposit limit of {} {} for {}", self.amount, code, self.account, self.limit, code, holder),
+            LimitKind::Withdrawal => write!(f, "a withdrawal of {} {} from {} exceeds the withdrawal limit of {} {} for {}", self.amount, code, self.account, self.limit, code, holder),
+        }
+    }
+}
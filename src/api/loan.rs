@@ -0,0 +1,217 @@
+use std::fmt;
+
+use crate::api::decimal::Decimal;
+use crate::api::money::Money;
+use crate::api::rates;
+
+/// Longest term accepted, in payments (100 years of monthly payments).
+pub const MAX_TERM: u32 = 1200;
+
+/// How often loan payments fall due.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PaymentFrequency {
+    Weekly,
+    Biweekly,
+    Monthly,
+    Quarterly,
+    Annually,
+}
+
+impl PaymentFrequency {
+    /// Parse "weekly", "biweekly", "monthly", "quarterly", or "annually"
+    /// (any case).
+    pub fn parse(s: &str) -> Option<Self> {
+        match s.trim().to_lowercase().as_str() {
+            "weekly" => Some(PaymentFrequency::Weekly),
+            "biweekly" => Some(PaymentFrequency::Biweekly),
+            "monthly" => Some(PaymentFrequency::Monthly),
+            "quarterly" => Some(PaymentFrequency::Quarterly),
+            "annually" => Some(PaymentFrequency::Annually),
+            _ => None,
+        }
+    }
+
+    /// Payments per year; the periodic rate is the annual rate divided by
+    /// this.
+    pub fn periods_per_year(&self) -> u32 {
+        match self {
+            PaymentFrequency::Weekly => 52,
+            PaymentFrequency::Biweekly => 26,
+            PaymentFrequency::Monthly => 12,
+            PaymentFrequency::Quarterly => 4,
+            PaymentFrequency::Annually => 1,
+        }
+    }
+
+    /// Lowercase name, as accepted by `parse`.
+    pub fn name(&self) -> &'static str {
+        match self {
+            PaymentFrequency::Weekly => "weekly",
+            PaymentFrequency::Biweekly => "biweekly",
+            PaymentFrequency::Monthly => "monthly",
+            PaymentFrequency::Quarterly => "quarterly",
+            PaymentFrequency::Annually => "annually",
+        }
+    }
+}
+
+/// Errors raised when a loan is set up or paid.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum LoanError {
+    /// The principal rounds to zero or below in the currency's minor unit.
+    NonPositivePrincipal,
+    NegativeRate,
+    /// The term is zero or longer than `MAX_TERM` payments.
+    InvalidTerm(u32),
+    /// The level payment rounds to zero, or the schedule does not fit in a
+    /// `Decimal`.
+    OutOfRange,
+    /// Every scheduled payment has been made.
+    PaidOff(usize),
+}
+
+impl fmt::Display for LoanError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            LoanError::NonPositivePrincipal => write!(f, "loan principal must be greater than zero"),
+            LoanError::NegativeRate => write!(f, "loan interest rate cannot be negative"),
+            LoanError::InvalidTerm(term) => write!(f, "invalid loan term {} (expected 1-{} payments)", term, MAX_TERM),
+            LoanError::OutOfRange => write!(f, "loan amounts are out of range"),
+            LoanError::PaidOff(id) => write!(f, "loan {} is already paid off", id),
+        }
+    }
+}
+
+impl std::error::Error for LoanError {}
+
+/// One period of an amortization schedule: the payment due, how it splits
+/// into interest and principal, and the principal still owed afterwards.
+#[derive(Debug, Clone)]
+pub struct AmortizationRow {
+    pub period: u32,
+    pub payment: Money,
+    pub principal: Money,
+    pub interest: Money,
+    pub remaining: Money,
+}
+
+/// Fixed-rate amortizing loan drawn into, and repaid from, the account
+/// named `account`. `principal` is in that account's currency and
+/// `minor_unit_dp` is the currency's decimal places, to which every
+/// scheduled amount is rounded. `annual_rate` is a fraction (0.06 = 6%),
+/// `term` the number of payments, and `payments_made` how many of them
+/// have been posted. `id` is assigned by the `Bank`.
+#[derive(Debug, Clone)]
+pub struct Loan {
+    pub id: usize,
+    pub account: String,
+    pub principal: Money,
+    pub minor_unit_dp: u32,
+    pub annual_rate: Decimal,
+    pub term: u32,
+    pub frequency: PaymentFrequency,
+    pub payments_made: u32,
+}
+
+impl Loan {
+    /// Set up a loan with no payments made. The principal is rounded to
+    /// the currency's minor unit. Fails if it rounds to zero, the rate is
+    /// negative, the term is outside 1-`MAX_TERM`, or the schedule cannot
+    /// be computed.
+    pub fn new(
+        id: usize,
+        account: &str,
+        principal: Money,
+        minor_unit_dp: u32,
+        annual_rate: Decimal,
+        term: u32,
+        frequency: PaymentFrequency,
+    ) -> Result<Self, LoanError> {
+        let principal = principal.round_dp(minor_unit_dp);
+        if principal.amount <= Decimal::ZERO {
+            return Err(LoanError::NonPositivePrincipal);
+        }
+        if annual_rate < Decimal::ZERO {
+            return Err(LoanError::NegativeRate);
+        }
+        if term == 0 || term > MAX_TERM {
+            return Err(LoanError::InvalidTerm(term));
+        }
+        let loan = Self { id, account: account.to_string(), principal, minor_unit_dp, annual_rate, term, frequency, payments_made: 0 };
+        loan.payment()?;
+        Ok(loan)
+    }
+
+    /// Interest rate per payment period.
+    pub fn periodic_rate(&self) -> Decimal {
+        rates::periodic(self.annual_rate, self.frequency.periods_per_year())
+    }
+
+    /// The level payment that repays the principal with interest over the
+    /// term, rounded to the minor unit:
+    /// Payment = P × r × (1 + r)^n / ((1 + r)^n − 1), or P / n at 0%.
+    pub fn payment(&self) -> Result<Money, LoanError> {
+        let (p, r, n) = (self.principal.amount, self.periodic_rate(), self.term);
+        let exact = if r.is_zero() {
+            p.checked_div(Decimal::from(n))
+        } else {
+            let growth = (0..n).try_fold(Decimal::ONE, |acc, _| acc.checked_mul(Decimal::ONE + r));
+            growth.and_then(|g| p.checked_mul(r)?.checked_mul(g)?.checked_div(g.checked_sub(Decimal::ONE)?))
+        };
+        let payment = exact.ok_or(LoanError::OutOfRange)?.round_dp(self.minor_unit_dp);
+        if payment <= Decimal::ZERO {
+            return Err(LoanError::OutOfRange);
+        }
+        Ok(Money::new(payment, &self.principal.currency))
+    }
+
+    /// Every payment period from the first: each period's interest is the
+    /// remaining principal times the periodic rate, rounded to the minor
+    /// unit, and the rest of the payment repays principal. The last payment
+    /// is adjusted to clear what is left, so the schedule may end early when
+    /// rounding pays the loan off sooner.
+    pub fn amortization_schedule(&self) -> Vec<AmortizationRow> {
+        let Ok(level) = self.payment() else { return Vec::new() };
+        let (r, dp) = (self.periodic_rate(), self.minor_unit_dp);
+        let money = |amount: Decimal| Money::new(amount, &self.principal.currency);
+        let mut remaining = self.principal.amount;
+        let mut rows = Vec::new();
+        for period in 1..=self.term {
+            if remaining <= Decimal::ZERO {
+                break;
+            }
+            let interest = (remaining * r).round_dp(dp);
+            let principal = if period == self.term { remaining } else { (level.amount - interest).min(remaining) };
+            remaining -= principal;
+            rows.push(AmortizationRow {
+                period,
+                payment: money(principal + interest),
+                principal: money(principal),
+                interest: money(interest),
+                remaining: money(remaining),
+            });
+        }
+        rows
+    }
+
+    /// The next payment due, or `None` once the loan is paid off.
+    pub fn next_payment(&self) -> Option<AmortizationRow> {
+        self.amortization_schedule().into_iter().nth(self.payments_made as usize)
+    }
+
+    /// Principal still owed after the payments made so far.
+    pub fn outstanding(&self) -> Money {
+        match self.payments_made.checked_sub(1) {
+            None => self.principal.clone(),
+            Some(last) => self
+                .amortization_schedule()
+                .into_iter()
+                .nth(last as usize)
+                .map_or_else(|| Money::zero(&self.principal.currency), |row| row.remaining),
+        }
+    }
+
+    pub fn is_paid_off(&self) -> bool {
+        self.next_payment().is_none()
+    }
+}
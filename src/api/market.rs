@@ -0,0 +1,220 @@
+use std::collections::BTreeMap;
+use std::fmt;
+
+use crate::api::account::DAY_COUNT_BASIS;
+use crate::api::bank::{Bank, BankError, EndOfDay};
+use crate::api::date::{Date, SECS_PER_DAY};
+use crate::api::decimal::Decimal;
+use crate::api::forex::RateSource;
+use crate::api::limit_order::LimitOrderFill;
+
+/// Errors raised when a market simulation is refused.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum MarketError {
+    /// The named currency's model has a negative volatility.
+    NegativeVolatility(String),
+    /// A model names the base currency, a basket, or a code not in the
+    /// catalog; only plain currencies have rates of their own.
+    NotSimulated(String),
+    /// Recording a simulated rate failed.
+    Bank(BankError),
+}
+
+impl fmt::Display for MarketError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            MarketError::NegativeVolatility(code) => write!(f, "volatility for {} must not be negative", code),
+            MarketError::NotSimulated(code) => write!(f, "{} has no rate of its own to simulate", code),
+            MarketError::Bank(e) => write!(f, "{}", e),
+        }
+    }
+}
+
+impl std::error::Error for MarketError {}
+
+impl From<BankError> for MarketError {
+    fn from(e: BankError) -> Self {
+        MarketError::Bank(e)
+    }
+}
+
+/// A small seedable pseudo-random generator (SplitMix64): the same seed
+/// always gives the same sequence, on every platform. Not for secrets.
+#[derive(Debug, Clone)]
+pub struct Rng {
+    state: u64,
+}
+
+impl Rng {
+    pub fn new(seed: u64) -> Self {
+        Self { state: seed }
+    }
+
+    pub fn next_u64(&mut self) -> u64 {
+        self.state = self.state.wrapping_add(0x9E37_79B9_7F4A_7C15);
+        let mut z = self.state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        z ^ (z >> 31)
+    }
+
+    /// Uniform in [0, 1), from the top 53 bits.
+    pub fn next_f64(&mut self) -> f64 {
+        (self.next_u64() >> 11) as f64 / (1u64 << 53) as f64
+    }
+
+    /// Standard normal (mean 0, variance 1), by the Box-Muller transform.
+    pub fn next_normal(&mut self) -> f64 {
+        let u1 = 1.0 - self.next_f64();
+        let u2 = self.next_f64();
+        (-2.0 * u1.ln()).sqrt() * (std::f64::consts::TAU * u2).cos()
+    }
+}
+
+/// How a rate moves: geometric Brownian motion with an annual `drift` and
+/// `volatility`, both as fractions (0.02 = 2%). Each day the rate is
+/// multiplied by exp((drift − volatility² / 2) × dt + volatility × √dt × Z),
+/// where dt = 1 / `DAY_COUNT_BASIS` and Z is standard normal, so it stays
+/// positive and grows by `drift` a year on average.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RateModel {
+    pub drift: Decimal,
+    pub volatility: Decimal,
+}
+
+impl RateModel {
+    pub fn new(drift: Decimal, volatility: Decimal) -> Self {
+        Self { drift, volatility }
+    }
+
+    /// The rate after one day from `rate`, given the standard normal draw
+    /// `z`, rounded to the currency's `dp` rate decimals and never below its
+    /// smallest step.
+    fn next_rate(&self, rate: Decimal, z: f64, dp: u32) -> Option<Decimal> {
+        let next = Decimal::from_f64(rate.to_f64() * self.daily_factor(z))?.round_dp(dp);
+        Some(next.max(Decimal::new(1, dp)))
+    }
+
+    /// What one day multiplies a rate by, given the standard normal draw
+    /// `z`.
+    pub(crate) fn daily_factor(&self, z: f64) -> f64 {
+        let dt = 1.0 / DAY_COUNT_BASIS as f64;
+        let (mu, sigma) = (self.drift.to_f64(), self.volatility.to_f64());
+        ((mu - sigma * sigma / 2.0) * dt + sigma * dt.sqrt() * z).exp()
+    }
+}
+
+/// One currency's rate before and after a simulated day.
+#[derive(Debug, Clone)]
+pub struct RateMove {
+    pub code: String,
+    pub old: Decimal,
+    pub new: Decimal,
+}
+
+/// What one simulated day did: the rates moved, the limit orders they
+/// reached, then the bank's end-of-day run for `date`.
+#[derive(Debug, Clone)]
+pub struct MarketStep {
+    pub date: Date,
+    pub moves: Vec<RateMove>,
+    pub fills: Vec<LimitOrderFill>,
+    pub end_of_day: EndOfDay,
+}
+
+/// Moves a bank's exchange rates day by day as random walks, for watching
+/// conversions and FX profit and loss under changing markets. Each
+/// currency follows its own `RateModel`, else the default one; currencies
+/// with neither keep their rate. The base currency is fixed at 1 and
+/// baskets follow their components.
+///
+/// Runs are reproducible: the same seed, models, and starting rates give
+/// the same rates, drawn in currency-code order.
+#[derive(Debug, Clone)]
+pub struct MarketSimulator {
+    rng: Rng,
+    default_model: Option<RateModel>,
+    models: BTreeMap<String, RateModel>,
+}
+
+impl MarketSimulator {
+    /// A simulator drawing from `seed`, with no models yet.
+    pub fn new(seed: u64) -> Self {
+        Self { rng: Rng::new(seed), default_model: None, models: BTreeMap::new() }
+    }
+
+    /// Move every currency without a model of its own by `model`.
+    pub fn with_default(mut self, model: RateModel) -> Self {
+        self.default_model = Some(model);
+        self
+    }
+
+    /// Move `code` by `model`.
+    pub fn with_model(mut self, code: &str, model: RateModel) -> Self {
+        self.models.insert(code.to_uppercase(), model);
+        self
+    }
+
+    /// Simulate one day: advance the bank's simulation clock (see
+    /// `Bank::advance_clock`) by a day, record each currency's new rate
+    /// with `Bank::set_rate_from`, attributed to `RateSource::Simulation`
+    /// (filling limit orders and emitting `RateChanged` events), then run `Bank::end_of_day` for the new date
+    /// so forwards settle and standing orders run on simulated time.
+    ///
+    /// Nothing changes if a model is refused. A rate that cannot be
+    /// recorded ends the day early with an error, leaving the rates already
+    /// recorded in place.
+    pub fn step(&mut self, bank: &mut Bank) -> Result<MarketStep, MarketError> {
+        let plan = self.plan(bank)?;
+        let moves: Vec<RateMove> = plan
+            .into_iter()
+            .map(|(code, model, old)| {
+                let z = self.rng.next_normal();
+                let new = model.next_rate(old, z, bank.forex.rate_decimals(&code)).unwrap_or(old);
+                RateMove { code, old, new }
+            })
+            .collect();
+        let date = Date::from_timestamp(bank.advance_clock(SECS_PER_DAY));
+        let mut fills = Vec::new();
+        for m in &moves {
+            fills.extend(bank.set_rate_from(&m.code, m.new, RateSource::Simulation)?);
+        }
+        let end_of_day = bank.end_of_day(date);
+        Ok(MarketStep { date, moves, fills, end_of_day })
+    }
+
+    /// `days` steps in a row, stopping at the first error.
+    pub fn run(&mut self, bank: &mut Bank, days: usize) -> Result<Vec<MarketStep>, MarketError> {
+        (0..days).map(|_| self.step(bank)).collect()
+    }
+
+    /// The currencies to move, in code order, each with its model and
+    /// current rate. Fails if a model is invalid or names a currency
+    /// without a rate of its own.
+    fn plan(&self, bank: &Bank) -> Result<Vec<(String, RateModel, Decimal)>, MarketError> {
+        let simulated = |code: &str| bank.forex.base_currency().code != code && bank.forex.basket(code).is_none();
+        if let Some(model) = &self.default_model
+            && model.volatility < Decimal::ZERO
+        {
+            return Err(MarketError::NegativeVolatility(String::from("the default model")));
+        }
+        for (code, model) in &self.models {
+            if bank.forex.currency(code).is_none() || !simulated(code) {
+                return Err(MarketError::NotSimulated(code.clone()));
+            }
+            if model.volatility < Decimal::ZERO {
+                return Err(MarketError::NegativeVolatility(code.clone()));
+            }
+        }
+        Ok(bank
+            .forex
+            .currencies_detailed()
+            .into_iter()
+            .filter(|c| simulated(&c.code))
+            .filter_map(|c| {
+                let model = self.models.get(&c.code).or(self.default_model.as_ref())?;
+                Some((c.code, *model, c.rate))
+            })
+            .collect())
+    }
+}
@@ -0,0 +1,132 @@
+use std::cmp::Ordering;
+use std::fmt;
+use std::ops::{Add, Mul, Neg, Sub};
+
+use crate::api::decimal::Decimal;
+
+/// An amount tagged with the currency it is denominated in.
+/// Arithmetic between two `Money` values requires matching currencies:
+/// the `checked_*` methods return `CurrencyMismatch`, and the operators
+/// panic, so adding PHP to USD can never silently produce a number.
+/// Comparing different currencies yields no ordering (`partial_cmp` is `None`).
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Money {
+    pub amount: Decimal,
+    pub currency: String,
+}
+
+/// Error returned when combining amounts in different currencies.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CurrencyMismatch {
+    pub left: String,
+    pub right: String,
+}
+
+impl fmt::Display for CurrencyMismatch {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "currency mismatch: {} vs {}", self.left, self.right)
+    }
+}
+
+impl std::error::Error for CurrencyMismatch {}
+
+impl Money {
+    pub fn new(amount: Decimal, currency: &str) -> Self {
+        Self { amount, currency: currency.to_string() }
+    }
+
+    /// Zero in the given currency.
+    pub fn zero(currency: &str) -> Self {
+        Self::new(Decimal::ZERO, currency)
+    }
+
+    pub fn is_zero(&self) -> bool {
+        self.amount.is_zero()
+    }
+
+    pub fn is_sign_negative(&self) -> bool {
+        self.amount.is_sign_negative()
+    }
+
+    pub fn abs(&self) -> Self {
+        Self::new(self.amount.abs(), &self.currency)
+    }
+
+    /// Round the amount to `dp` fractional digits.
+    pub fn round_dp(&self, dp: u32) -> Self {
+        Self::new(self.amount.round_dp(dp), &self.currency)
+    }
+
+    /// Add amounts of the same currency.
+    pub fn checked_add(&self, rhs: &Money) -> Result<Money, CurrencyMismatch> {
+        self.same_currency(rhs)?;
+        Ok(Self::new(self.amount + rhs.amount, &self.currency))
+    }
+
+    /// Subtract amounts of the same currency.
+    pub fn checked_sub(&self, rhs: &Money) -> Result<Money, CurrencyMismatch> {
+        self.same_currency(rhs)?;
+        Ok(Self::new(self.amount - rhs.amount, &self.currency))
+    }
+
+    /// Ok when both amounts share a currency.
+    pub fn same_currency(&self, other: &Money) -> Result<(), CurrencyMismatch> {
+        if self.currency == other.currency {
+            Ok(())
+        } else {
+            Err(CurrencyMismatch { left: self.currency.clone(), right: other.currency.clone() })
+        }
+    }
+}
+
+impl Add for Money {
+    type Output = Money;
+    /// Panics on a currency mismatch; use `checked_add` to handle it.
+    fn add(self, rhs: Money) -> Money {
+        self.checked_add(&rhs).unwrap_or_else(|e| panic!("{}", e))
+    }
+}
+
+impl Sub for Money {
+    type Output = Money;
+    /// Panics on a currency mismatch; use `checked_sub` to handle it.
+    fn sub(self, rhs: Money) -> Money {
+        self.checked_sub(&rhs).unwrap_or_else(|e| panic!("{}", e))
+    }
+}
+
+impl Mul<Decimal> for Money {
+    type Output = Money;
+    /// Scale an amount (e.g. by an interest rate); the currency is kept.
+    fn mul(self, rhs: Decimal) -> Money {
+        Money { amount: self.amount * rhs, currency: self.currency }
+    }
+}
+
+impl Neg for Money {
+    type Output = Money;
+    fn neg(self) -> Money {
+        Money { amount: -self.amount, currency: self.currency }
+    }
+}
+
+impl PartialOrd for Money {
+    fn partial_cmp(&self, other: &Money) -> Option<Ordering> {
+        if self.currency == other.currency {
+            Some(self.amount.cmp(&other.amount))
+        } else {
+            None
+        }
+    }
+}
+
+impl fmt::Display for Money {
+    /// Formats as "<amount> <code>", passing precision through to the amount
+    /// (e.g. `{:.2}` renders "1000.00 PHP").
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match f.precision() {
+            Some(p) => write!(f, "{:.*} {}", p, self.amount, self.currency),
+            None => write!(f, "{} {}", self.amount, self.currency),
+        }
+    }
+}
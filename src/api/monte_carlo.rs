@@ -0,0 +1,305 @@
+use std::fmt;
+
+use crate::api::account::DAY_COUNT_BASIS;
+use crate::api::date::Date;
+use crate::api::decimal::Decimal;
+use crate::api::market::{RateModel, Rng};
+use crate::api::money::Money;
+use crate::api::parallel;
+use crate::api::rates::YearBasis;
+
+/// Paths a `MonteCarlo` runs unless told otherwise.
+pub const DEFAULT_PATHS: usize = 1_000;
+/// The most paths one run takes.
+pub const MAX_PATHS: usize = 1_000_000;
+/// The percentiles reports show, from pessimistic to optimistic.
+pub const PERCENTILES: [u32; 5] = [5, 25, 50, 75, 95];
+
+/// Errors raised when a Monte Carlo run is refused.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum MonteCarloError {
+    /// Zero paths, or more than `MAX_PATHS`.
+    InvalidPaths,
+    /// The horizon is zero days.
+    InvalidHorizon,
+    /// The interest rate or exchange rate volatility is negative.
+    NegativeVolatility,
+    /// Deposits come every zero days.
+    InvalidDepositInterval,
+    /// A path's balance grew too large to represent.
+    OutOfRange,
+}
+
+impl fmt::Display for MonteCarloError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            MonteCarloError::InvalidPaths => write!(f, "a simulation needs 1 to {} paths", MAX_PATHS),
+            MonteCarloError::InvalidHorizon => write!(f, "a simulation needs at least one day"),
+            MonteCarloError::NegativeVolatility => write!(f, "volatility cannot be negative"),
+            MonteCarloError::InvalidDepositInterval => write!(f, "deposits must come at least one day apart"),
+            MonteCarloError::OutOfRange => write!(f, "simulation is out of range"),
+        }
+    }
+}
+
+impl std::error::Error for MonteCarloError {}
+
+/// The exchange rate a `MonteCarlo` values a foreign balance at: `spot`
+/// base-currency units per unit of the balance's currency today, moving
+/// along each path by `model`. `decimals` is the base currency's.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FxPath {
+    pub base: String,
+    pub decimals: u32,
+    pub spot: Decimal,
+    pub model: RateModel,
+}
+
+/// Irregular deposits along each path: every `every` days, with
+/// probability `chance`, one of between half and one and a half times
+/// `amount`, in the balance's currency.
+#[derive(Debug, Clone, PartialEq)]
+pub struct DepositBehavior {
+    pub every: usize,
+    pub chance: f64,
+    pub amount: Decimal,
+}
+
+/// A Monte Carlo simulation of where a balance may end up after `days`
+/// days from `start` (day 1), over `paths` randomized paths. On each day of
+/// a path the balance earns a day's interest at the path's annual rate, on
+/// the days of that day's year by `year_basis` (less `tax_rate` of
+/// positive interest, as withholding tax takes it), then the rate takes a
+/// random step of `rate_volatility` a year (an absolute amount: 0.01 is
+/// one percentage point), never below zero unless it started there; with
+/// `fx`, the exchange rate moves as `RateModel` describes; and with
+/// `deposits`, a deposit may come in.
+///
+/// Each path draws from its own generator, seeded from `seed`, so a run
+/// is reproducible and the same with or without the `parallel` feature.
+/// Build one with `MonteCarlo::new` or `Bank::monte_carlo`, then `run` it.
+#[derive(Debug, Clone, PartialEq)]
+pub struct MonteCarlo {
+    pub seed: u64,
+    pub paths: usize,
+    pub days: usize,
+    pub opening: Money,
+    /// The opening balance's currency's decimal places.
+    pub decimals: u32,
+    pub annual_rate: Decimal,
+    pub start: Date,
+    pub year_basis: YearBasis,
+    pub rate_volatility: Decimal,
+    pub tax_rate: Decimal,
+    pub fx: Option<FxPath>,
+    pub deposits: Option<DepositBehavior>,
+}
+
+impl MonteCarlo {
+    /// `DEFAULT_PATHS` paths of `days` days from `opening` at a fixed
+    /// `annual_rate` on the `Fixed365` basis, day 1 being `start`, untaxed,
+    /// with no exchange rate or deposits, drawn from seed 0.
+    pub fn new(opening: Money, decimals: u32, annual_rate: Decimal, start: Date, days: usize) -> Self {
+        Self {
+            seed: 0,
+            paths: DEFAULT_PATHS,
+            days,
+            opening,
+            decimals,
+            annual_rate,
+            start,
+            year_basis: YearBasis::Fixed365,
+            rate_volatility: Decimal::ZERO,
+            tax_rate: Decimal::ZERO,
+            fx: None,
+            deposits: None,
+        }
+    }
+
+    pub fn with_seed(mut self, seed: u64) -> Self {
+        self.seed = seed;
+        self
+    }
+
+    pub fn with_paths(mut self, paths: usize) -> Self {
+        self.paths = paths;
+        self
+    }
+
+    /// Accrue interest on `basis` as an account does.
+    pub fn with_year_basis(mut self, basis: YearBasis) -> Self {
+        self.year_basis = basis;
+        self
+    }
+
+    pub fn with_rate_volatility(mut self, volatility: Decimal) -> Self {
+        self.rate_volatility = volatility;
+        self
+    }
+
+    pub fn with_tax_rate(mut self, tax_rate: Decimal) -> Self {
+        self.tax_rate = tax_rate;
+        self
+    }
+
+    pub fn with_fx(mut self, fx: FxPath) -> Self {
+        self.fx = Some(fx);
+        self
+    }
+
+    /// Move the exchange rate by `model`; no effect without `fx`.
+    pub fn with_fx_model(mut self, model: RateModel) -> Self {
+        if let Some(fx) = &mut self.fx {
+            fx.model = model;
+        }
+        self
+    }
+
+    pub fn with_deposits(mut self, deposits: DepositBehavior) -> Self {
+        self.deposits = Some(deposits);
+        self
+    }
+
+    /// Run every path and collect where they end. Fails if the paths or
+    /// horizon are out of bounds, a volatility is negative, deposits come
+    /// every zero days, or a balance grows out of range.
+    pub fn run(&self) -> Result<MonteCarloOutcome, MonteCarloError> {
+        if self.paths == 0 || self.paths > MAX_PATHS {
+            return Err(MonteCarloError::InvalidPaths);
+        }
+        if self.days == 0 {
+            return Err(MonteCarloError::InvalidHorizon);
+        }
+        if self.rate_volatility < Decimal::ZERO || self.fx.as_ref().is_some_and(|fx| fx.model.volatility < Decimal::ZERO) {
+            return Err(MonteCarloError::NegativeVolatility);
+        }
+        if self.deposits.as_ref().is_some_and(|d| d.every == 0) {
+            return Err(MonteCarloError::InvalidDepositInterval);
+        }
+        let mut seeder = Rng::new(self.seed);
+        let seeds: Vec<u64> = (0..self.paths).map(|_| seeder.next_u64()).collect();
+        let ends = parallel::map(&seeds, |seed| self.path(*seed));
+
+        let decimal = |x: f64, dp: u32| Decimal::from_f64(x).map(|d| d.round_dp(dp)).ok_or(MonteCarloError::OutOfRange);
+        let currency = &self.opening.currency;
+        let mut balances = Vec::with_capacity(self.paths);
+        let mut values = Vec::with_capacity(if self.fx.is_some() { self.paths } else { 0 });
+        let mut deposited = 0.0;
+        for (balance, value, deposits) in ends {
+            balances.push(decimal(balance, self.decimals)?);
+            if let Some(fx) = &self.fx {
+                values.push(decimal(value, fx.decimals)?);
+            }
+            deposited += deposits;
+        }
+        Ok(MonteCarloOutcome {
+            seed: self.seed,
+            paths: self.paths,
+            days: self.days,
+            opening: self.opening.clone(),
+            opening_value: self.fx.as_ref().map(|fx| Money::new((self.opening.amount * fx.spot).round_dp(fx.decimals), &fx.base)),
+            balance: Distribution::new(currency, self.decimals, balances),
+            value: self.fx.as_ref().map(|fx| Distribution::new(&fx.base, fx.decimals, values)),
+            deposited: Money::new(decimal(deposited / self.paths as f64, self.decimals)?, currency),
+        })
+    }
+
+    /// One path from `seed`: the ending balance, its value in the base
+    /// currency (the balance itself without `fx`), and what was deposited.
+    fn path(&self, seed: u64) -> (f64, f64, f64) {
+        let mut rng = Rng::new(seed);
+        let kept = 1.0 - self.tax_rate.to_f64();
+        // Volatility is annualized over `DAY_COUNT_BASIS` days whatever the
+        // year basis, as `RateVolatility::annualized` is.
+        let step = self.rate_volatility.to_f64() / f64::from(DAY_COUNT_BASIS).sqrt();
+        let floor = self.annual_rate.to_f64().min(0.0);
+        let mut rate = self.annual_rate.to_f64();
+        let mut balance = self.opening.amount.to_f64();
+        let mut spot = self.fx.as_ref().map_or(1.0, |fx| fx.spot.to_f64());
+        let mut deposited = 0.0;
+        for day in 1..=self.days {
+            let year = self.start.add_days(day as i64 - 1).year;
+            let interest = balance * rate / f64::from(self.year_basis.days(year));
+            balance += if interest > 0.0 { interest * kept } else { interest };
+            if step > 0.0 {
+                rate = (rate + step * rng.next_normal()).max(floor);
+            }
+            if let Some(fx) = &self.fx {
+                spot *= fx.model.daily_factor(rng.next_normal());
+            }
+            if let Some(deposits) = &self.deposits
+                && day % deposits.every == 0
+                && rng.next_f64() < deposits.chance
+            {
+                let amount = deposits.amount.to_f64() * (0.5 + rng.next_f64());
+                balance += amount;
+                deposited += amount;
+            }
+        }
+        (balance, balance * spot, deposited)
+    }
+}
+
+/// Where the paths of a `MonteCarlo` ended, from `MonteCarlo::run`: the
+/// spread of ending balances, in the opening balance's currency, and for a
+/// foreign balance the spread of their values in the base currency (each
+/// at its own path's exchange rate). `deposited` is the mean deposited per
+/// path.
+#[derive(Debug, Clone)]
+pub struct MonteCarloOutcome {
+    pub seed: u64,
+    pub paths: usize,
+    pub days: usize,
+    pub opening: Money,
+    pub opening_value: Option<Money>,
+    pub balance: Distribution,
+    pub value: Option<Distribution>,
+    pub deposited: Money,
+}
+
+/// Outcomes over the paths of a simulation, in `currency` rounded to its
+/// `decimals`, sorted from lowest to highest.
+#[derive(Debug, Clone)]
+pub struct Distribution {
+    pub currency: String,
+    pub decimals: u32,
+    pub outcomes: Vec<Decimal>,
+}
+
+impl Distribution {
+    fn new(currency: &str, decimals: u32, mut outcomes: Vec<Decimal>) -> Self {
+        outcomes.sort_unstable();
+        Self { currency: currency.to_string(), decimals, outcomes }
+    }
+
+    /// The mean outcome, rounded to `decimals`.
+    pub fn mean(&self) -> Money {
+        let sum: Decimal = self.outcomes.iter().sum();
+        Money::new((sum / Decimal::from(self.outcomes.len().max(1))).round_dp(self.decimals), &self.currency)
+    }
+
+    /// The outcome `p` percent of paths end at or below (nearest rank;
+    /// `p` above 100 counts as 100).
+    pub fn percentile(&self, p: u32) -> Money {
+        let rank = (self.outcomes.len() * p.min(100) as usize).div_ceil(100);
+        self.at(rank.saturating_sub(1))
+    }
+
+    pub fn min(&self) -> Money {
+        self.at(0)
+    }
+
+    pub fn max(&self) -> Money {
+        self.at(self.outcomes.len().saturating_sub(1))
+    }
+
+    /// The share of paths, from 0 to 1, that end below `amount`.
+    pub fn chance_below(&self, amount: Decimal) -> f64 {
+        let below = self.outcomes.partition_point(|o| *o < amount);
+        below as f64 / self.outcomes.len().max(1) as f64
+    }
+
+    fn at(&self, index: usize) -> Money {
+        Money::new(self.outcomes.get(index).copied().unwrap_or(Decimal::ZERO), &self.currency)
+    }
+}
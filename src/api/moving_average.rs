@@ -0,0 +1,119 @@
+use std::fmt;
+
+use crate::api::date::Date;
+use crate::api::decimal::Decimal;
+
+/// Closing rates a moving average spans unless told otherwise.
+pub const DEFAULT_DAYS: usize = 20;
+
+/// One closing rate of a currency with its simple and exponential moving
+/// averages as of that day, from `Forex::averaged_history`; an average is
+/// `None` until there are enough closes for it. `carried` is set when the
+/// close was carried over a non-trading day (see `Forex::carry_close`).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AveragedClose {
+    pub date: Date,
+    pub rate: Decimal,
+    pub carried: bool,
+    pub sma: Option<Decimal>,
+    pub ema: Option<Decimal>,
+}
+
+/// How a `MovingAverage` weighs the closes it spans.
+/// - `Simple`: all alike, their plain mean.
+/// - `Exponential`: each day's weight shrinks by a factor of
+///   1 - 2 / (days + 1) as it ages, so recent closes count most.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AverageKind {
+    Simple,
+    Exponential,
+}
+
+impl AverageKind {
+    /// Parse "sma" or "ema" (any case).
+    pub fn parse(s: &str) -> Option<Self> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "sma" => Some(AverageKind::Simple),
+            "ema" => Some(AverageKind::Exponential),
+            _ => None,
+        }
+    }
+
+    /// Lowercase name, as accepted by `parse`.
+    pub fn name(&self) -> &'static str {
+        match self {
+            AverageKind::Simple => "sma",
+            AverageKind::Exponential => "ema",
+        }
+    }
+}
+
+/// A moving average of a currency's daily closing rates over `days`
+/// closes, written `sma:20` or `ema:20`. The simple average is the mean of
+/// the last `days` closes; the exponential one starts from the simple
+/// average of the first `days` closes on record and moves 2 / (days + 1)
+/// of the way to each later close. Either needs `days` closes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct MovingAverage {
+    pub kind: AverageKind,
+    pub days: usize,
+}
+
+impl MovingAverage {
+    pub fn simple(days: usize) -> Self {
+        Self { kind: AverageKind::Simple, days }
+    }
+
+    pub fn exponential(days: usize) -> Self {
+        Self { kind: AverageKind::Exponential, days }
+    }
+
+    /// Parse `KIND:DAYS`, e.g. "sma:20" or "EMA:50", with at least one day.
+    pub fn parse(s: &str) -> Option<Self> {
+        let (kind, days) = s.trim().split_once(':')?;
+        let days = days.trim().parse().ok().filter(|&days| days > 0)?;
+        Some(Self { kind: AverageKind::parse(kind)?, days })
+    }
+
+    /// The average as of each of `closes` (oldest first), `None` for the
+    /// first `days - 1`, before there are enough.
+    pub fn series(&self, closes: &[Decimal]) -> Vec<Option<Decimal>> {
+        let days = self.days.max(1);
+        let count = Decimal::from(days);
+        let mut averages = Vec::with_capacity(closes.len());
+        let mut sum = Decimal::ZERO;
+        let mut last = None;
+        for (i, &close) in closes.iter().enumerate() {
+            sum += close;
+            if i >= days {
+                sum -= closes[i - days];
+            }
+            last = match (self.kind, last) {
+                _ if i + 1 < days => None,
+                (AverageKind::Exponential, Some(previous)) => Some(previous + (close - previous) * Decimal::from(2u64) / Decimal::from(days + 1)),
+                _ => Some(sum / count),
+            };
+            averages.push(last);
+        }
+        averages
+    }
+
+    /// The average as of the last of `closes` (oldest first); `None` with
+    /// fewer than `days`.
+    pub fn of(&self, closes: &[Decimal]) -> Option<Decimal> {
+        match self.kind {
+            AverageKind::Simple if closes.len() >= self.days.max(1) => {
+                let recent = &closes[closes.len() - self.days.max(1)..];
+                Some(recent.iter().copied().sum::<Decimal>() / Decimal::from(recent.len()))
+            }
+            AverageKind::Simple => None,
+            AverageKind::Exponential => self.series(closes).pop().flatten(),
+        }
+    }
+}
+
+impl fmt::Display for MovingAverage {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}:{}", self.kind.name(), self.days)
+    }
+}
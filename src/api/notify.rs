@@ -0,0 +1,121 @@
+use std::fs::OpenOptions;
+use std::io::{self, Write};
+use std::path::PathBuf;
+
+use crate::api::alert;
+use crate::api::bank::Bank;
+use crate::api::date::{format_timestamp, now_timestamp};
+use crate::api::delivery::StatementDelivery;
+use crate::api::event::BankEvent;
+use crate::api::statement::Statement;
+
+/// An alerting channel for bank events. Implement it to add a channel
+/// (chat, e-mail, a queue) without touching the bank: subscribe it to an
+/// `EventBus`, which hands it every event the bank records.
+pub trait Notifier: Send {
+    /// Deliver one event. An error is reported by whoever drives the bus;
+    /// the event is not offered again.
+    fn notify(&mut self, event: &BankEvent) -> io::Result<()>;
+}
+
+/// Prints each event to stderr as "[kind] description", so alerts show up
+/// next to, not inside, a program's regular output.
+#[derive(Debug, Default)]
+pub struct ConsoleNotifier;
+
+impl Notifier for ConsoleNotifier {
+    fn notify(&mut self, event: &BankEvent) -> io::Result<()> {
+        writeln!(io::stderr(), "[{}] {}", event.kind(), event)
+    }
+}
+
+/// Appends each event to a log file as a tab-separated line: time (UTC),
+/// kind, and description. The file is created if missing.
+#[derive(Debug)]
+pub struct FileNotifier {
+    path: PathBuf,
+}
+
+impl FileNotifier {
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        Self { path: path.into() }
+    }
+}
+
+impl Notifier for FileNotifier {
+    fn notify(&mut self, event: &BankEvent) -> io::Result<()> {
+        let mut file = OpenOptions::new().create(true).append(true).open(&self.path)?;
+        writeln!(file, "{}\t{}\t{}", format_timestamp(now_timestamp()), event.kind(), event)
+    }
+}
+
+/// The notifiers that bank events are published to, and the channels
+/// month-end statements are delivered through. Front ends drain the bank's
+/// queues with `drain` after each operation.
+#[derive(Default)]
+pub struct EventBus {
+    notifiers: Vec<Box<dyn Notifier>>,
+    deliveries: Vec<Box<dyn StatementDelivery>>,
+}
+
+impl EventBus {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add a notifier; it receives events published from now on.
+    pub fn subscribe(&mut self, notifier: Box<dyn Notifier>) {
+        self.notifiers.push(notifier);
+    }
+
+    /// Add a statement channel; it receives statements delivered from now
+    /// on.
+    pub fn subscribe_statements(&mut self, delivery: Box<dyn StatementDelivery>) {
+        self.deliveries.push(delivery);
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.notifiers.is_empty() && self.deliveries.is_empty()
+    }
+
+    /// Hand each event, in order, to every notifier. A notifier that fails
+    /// does not stop the others; the failures are returned.
+    pub fn publish(&mut self, events: &[BankEvent]) -> Vec<io::Error> {
+        let mut failures = Vec::new();
+        for event in events {
+            for notifier in &mut self.notifiers {
+                if let Err(e) = notifier.notify(event) {
+                    failures.push(e);
+                }
+            }
+        }
+        failures
+    }
+
+    /// Hand each statement, in order, to every statement channel. A channel
+    /// that fails does not stop the others; the failures are returned.
+    pub fn deliver(&mut self, statements: &[Statement]) -> Vec<io::Error> {
+        let mut failures = Vec::new();
+        for statement in statements {
+            for delivery in &mut self.deliveries {
+                if let Err(e) = delivery.deliver(statement) {
+                    failures.push(io::Error::new(e.kind(), format!("statement for {}: {}", statement.account, e)));
+                }
+            }
+        }
+        failures
+    }
+
+    /// Take the bank's pending events (see `Bank::take_events`), check
+    /// them against the accounts' alerts (see `alert::evaluate`), and
+    /// publish them with the alerts they raise, then take its month-end
+    /// statements (see `Bank::take_statements`) and deliver them.
+    pub fn drain(&mut self, bank: &mut Bank) -> Vec<io::Error> {
+        let mut events = bank.take_events();
+        events.extend(alert::evaluate(bank, &events));
+        let mut failures = self.publish(&events);
+        let statements = bank.take_statements();
+        failures.extend(self.deliver(&statements));
+        failures
+    }
+}
@@ -0,0 +1,40 @@
+//! Bank-wide work split across threads with the `parallel` feature.
+//!
+//! Only pure per-item computations run here; callers apply the results
+//! (posting, events, audit records) afterwards in item order, so the
+//! outcome is the same with or without the feature.
+
+/// Below this many items the work is done on the calling thread even with
+/// the `parallel` feature, since spawning would cost more than it saves.
+#[cfg(feature = "parallel")]
+const MIN_PARALLEL_ITEMS: usize = 64;
+
+/// `f` applied to every item, results in the order of `items`. With the
+/// `parallel` feature, the items are split into one contiguous chunk per
+/// available core and each chunk runs on its own scoped thread.
+#[cfg(feature = "parallel")]
+pub fn map<T: Sync, R: Send>(items: &[T], f: impl Fn(&T) -> R + Sync) -> Vec<R> {
+    let threads = std::thread::available_parallelism().map_or(1, |n| n.get());
+    if threads < 2 || items.len() < MIN_PARALLEL_ITEMS {
+        return items.iter().map(f).collect();
+    }
+    let chunk = items.len().div_ceil(threads);
+    let f = &f;
+    std::thread::scope(|scope| {
+        let workers: Vec<_> = items
+            .chunks(chunk)
+            .map(|part| scope.spawn(move || part.iter().map(f).collect::<Vec<R>>()))
+            .collect();
+        workers
+            .into_iter()
+            .flat_map(|w| w.join().unwrap_or_else(|panic| std::panic::resume_unwind(panic)))
+            .collect()
+    })
+}
+
+/// `f` applied to every item, results in the order of `items`. Without the
+/// `parallel` feature this runs on the calling thread.
+#[cfg(not(feature = "parallel"))]
+pub fn map<T: Sync, R: Send>(items: &[T], f: impl Fn(&T) -> R + Sync) -> Vec<R> {
+    items.iter().map(f).collect()
+}
@@ -0,0 +1,147 @@
+use std::cmp::Ordering;
+use std::fmt;
+
+use crate::api::account::DAY_COUNT_BASIS;
+use crate::api::decimal::Decimal;
+use crate::api::loan::Loan;
+use crate::api::money::Money;
+use crate::api::rates::{self, Convention};
+
+/// Errors raised when a paydown comparison is refused.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PaydownError {
+    NonPositiveAmount,
+    /// The amount is more than the loan still owes.
+    AboveOutstanding,
+    /// Every scheduled payment has been made.
+    PaidOff,
+    /// The horizon is zero days.
+    InvalidHorizon,
+    /// A rate or amount is too large to represent.
+    OutOfRange,
+}
+
+impl fmt::Display for PaydownError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PaydownError::NonPositiveAmount => write!(f, "amount must be greater than zero"),
+            PaydownError::AboveOutstanding => write!(f, "amount is more than the loan still owes"),
+            PaydownError::PaidOff => write!(f, "the loan is already paid off"),
+            PaydownError::InvalidHorizon => write!(f, "the horizon must be at least one day"),
+            PaydownError::OutOfRange => write!(f, "paydown comparison is out of range"),
+        }
+    }
+}
+
+impl std::error::Error for PaydownError {}
+
+/// Which use of a sum comes out ahead in a `PaydownComparison`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PaydownChoice {
+    PayDown,
+    Deposit,
+    /// Both come to the same amount.
+    Either,
+}
+
+impl PaydownChoice {
+    /// Lowercase name: "pay_down", "deposit", or "either".
+    pub fn name(&self) -> &'static str {
+        match self {
+            PaydownChoice::PayDown => "pay_down",
+            PaydownChoice::Deposit => "deposit",
+            PaydownChoice::Either => "either",
+        }
+    }
+}
+
+/// Paying `amount` off a loan now against depositing it for `days` days,
+/// from `PaydownComparison::run`. Both sides are compared through their
+/// effective annual yields (see `rates::effective`), so a loan charged
+/// monthly and a deposit credited daily line up.
+/// - `loan_rate` / `loan_apy`: the loan's nominal rate and its yield at
+///   its payment frequency. Paying down saves that yield on `amount` for
+///   as long as the loan runs.
+/// - `deposit_rate` / `deposit_apy`: the deposit's nominal rate, and its
+///   yield compounded daily after `tax_rate` is withheld from each credit.
+/// - `interest_saved` / `interest_earned`: what each side is worth over
+///   the horizon, rounded to the loan currency's minor unit.
+/// - `break_even_rate`: the nominal deposit rate, before tax, at which
+///   both come out the same; `None` when all the interest is withheld.
+///
+/// `days` is the horizon asked for, cut short at the loan's last payment.
+#[derive(Debug, Clone)]
+pub struct PaydownComparison {
+    pub amount: Money,
+    pub days: usize,
+    pub loan_rate: Decimal,
+    pub loan_apy: Decimal,
+    pub deposit_rate: Decimal,
+    pub tax_rate: Decimal,
+    pub deposit_apy: Decimal,
+    pub interest_saved: Money,
+    pub interest_earned: Money,
+    pub break_even_rate: Option<Decimal>,
+}
+
+impl PaydownComparison {
+    /// Compare paying `amount` (in the loan's currency) off `loan` with
+    /// depositing it at `deposit_rate`, less `tax_rate` withheld, for
+    /// `days` days.
+    pub fn run(loan: &Loan, amount: &Money, deposit_rate: Decimal, tax_rate: Decimal, days: usize) -> Result<Self, PaydownError> {
+        if amount.amount <= Decimal::ZERO {
+            return Err(PaydownError::NonPositiveAmount);
+        }
+        if days == 0 {
+            return Err(PaydownError::InvalidHorizon);
+        }
+        let periods_left = loan.amortization_schedule().len().saturating_sub(loan.payments_made as usize);
+        if periods_left == 0 {
+            return Err(PaydownError::PaidOff);
+        }
+        if amount.amount > loan.outstanding().amount {
+            return Err(PaydownError::AboveOutstanding);
+        }
+        let periods_per_year = loan.frequency.periods_per_year();
+        let days = days.min(periods_left * DAY_COUNT_BASIS as usize / periods_per_year as usize);
+        let daily = Convention::Compounded(DAY_COUNT_BASIS);
+        let loan_apy = rates::effective(loan.annual_rate, Convention::Compounded(periods_per_year)).ok_or(PaydownError::OutOfRange)?;
+        let kept = Decimal::ONE - tax_rate;
+        let deposit_apy = rates::effective(deposit_rate * kept, daily).ok_or(PaydownError::OutOfRange)?;
+        let interest = |apy: Decimal| -> Result<Money, PaydownError> {
+            let growth = rates::growth(apy, days).ok_or(PaydownError::OutOfRange)?;
+            let earned = amount.amount.checked_mul(growth - Decimal::ONE).ok_or(PaydownError::OutOfRange)?;
+            Ok(Money::new(earned.round_dp(loan.minor_unit_dp), &amount.currency))
+        };
+        Ok(Self {
+            amount: amount.clone(),
+            days,
+            loan_rate: loan.annual_rate,
+            loan_apy,
+            deposit_rate,
+            tax_rate,
+            deposit_apy,
+            interest_saved: interest(loan_apy)?,
+            interest_earned: interest(deposit_apy)?,
+            break_even_rate: match kept > Decimal::ZERO {
+                true => rates::from_effective(loan_apy, daily).and_then(|rate| rate.checked_div(kept)),
+                false => None,
+            },
+        })
+    }
+
+    /// How much more (positive) or less paying down saves than depositing
+    /// earns.
+    pub fn advantage(&self) -> Money {
+        Money::new(self.interest_saved.amount - self.interest_earned.amount, &self.amount.currency)
+    }
+
+    /// The side that comes out ahead over the horizon.
+    pub fn better(&self) -> PaydownChoice {
+        match self.advantage().amount.cmp(&Decimal::ZERO) {
+            Ordering::Greater => PaydownChoice::PayDown,
+            Ordering::Less => PaydownChoice::Deposit,
+            Ordering::Equal => PaydownChoice::Either,
+        }
+    }
+}
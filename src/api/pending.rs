@@ -0,0 +1,150 @@
+use std::fmt;
+
+use crate::api::date::Date;
+use crate::api::money::Money;
+
+/// Errors raised when a pending transaction is moved out of `Pending`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PendingError {
+    /// The transaction (ID given) was already posted or rejected.
+    Decided(usize),
+    /// A transaction cannot be rejected without a reason.
+    BlankReason,
+}
+
+impl fmt::Display for PendingError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PendingError::Decided(id) => write!(f, "held transaction {} has already been posted or rejected", id),
+            PendingError::BlankReason => write!(f, "a rejection needs a reason"),
+        }
+    }
+}
+
+impl std::error::Error for PendingError {}
+
+/// Where a transaction stands. It starts `Pending` and moves once, to
+/// `Posted` or to `Rejected` with the reason; neither can be left again.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TransactionState {
+    Pending,
+    Posted,
+    Rejected(String),
+}
+
+impl TransactionState {
+    /// Lowercase name: "pending", "posted", or "rejected".
+    pub fn name(&self) -> &'static str {
+        match self {
+            TransactionState::Pending => "pending",
+            TransactionState::Posted => "posted",
+            TransactionState::Rejected(_) => "rejected",
+        }
+    }
+}
+
+/// A withdrawal or transfer held back from the ledger, with what it will
+/// post: `category` files a withdrawal under a budget envelope.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum HeldTransaction {
+    Withdrawal { account: String, amount: Money, memo: String, category: Option<String> },
+    Transfer { from: String, to: String, amount: Money },
+}
+
+impl HeldTransaction {
+    /// Lowercase name: "withdrawal" or "transfer".
+    pub fn name(&self) -> &'static str {
+        match self {
+            HeldTransaction::Withdrawal { .. } => "withdrawal",
+            HeldTransaction::Transfer { .. } => "transfer",
+        }
+    }
+
+    /// The account the money leaves.
+    pub fn account(&self) -> &str {
+        match self {
+            HeldTransaction::Withdrawal { account, .. } => account,
+            HeldTransaction::Transfer { from, .. } => from,
+        }
+    }
+
+    pub fn amount(&self) -> &Money {
+        match self {
+            HeldTransaction::Withdrawal { amount, .. } | HeldTransaction::Transfer { amount, .. } => amount,
+        }
+    }
+
+    /// Returns true when the money leaves or reaches `account`.
+    pub fn involves(&self, account: &str) -> bool {
+        match self {
+            HeldTransaction::Withdrawal { account: a, .. } => a == account,
+            HeldTransaction::Transfer { from, to, .. } => from == account || to == account,
+        }
+    }
+}
+
+impl fmt::Display for HeldTransaction {
+    /// e.g. "withdrawal of 150000 PHP from Alice" or "transfer of 150000
+    /// PHP from Alice to Bob".
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            HeldTransaction::Withdrawal { account, amount, .. } => write!(f, "withdrawal of {} from {}", amount, account),
+            HeldTransaction::Transfer { from, to, amount } => write!(f, "transfer of {} from {} to {}", amount, from, to),
+        }
+    }
+}
+
+/// A withdrawal or transfer queued on `requested` instead of posted, e.g.
+/// one above the approval threshold. While `Pending` its amount is held
+/// back from the paying account's available balance, and nothing reaches
+/// the receiving one; decided entries stay in the bank's queue as its
+/// record.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PendingTransaction {
+    pub id: usize,
+    pub requested: Date,
+    pub held: HeldTransaction,
+    pub state: TransactionState,
+}
+
+impl PendingTransaction {
+    pub fn new(id: usize, requested: Date, held: HeldTransaction) -> Self {
+        Self { id, requested, held, state: TransactionState::Pending }
+    }
+
+    pub fn is_pending(&self) -> bool {
+        self.state == TransactionState::Pending
+    }
+
+    /// Move from `Pending` to `Posted`, once its postings are booked.
+    pub fn post(&mut self) -> Result<(), PendingError> {
+        self.ensure_pending()?;
+        self.state = TransactionState::Posted;
+        Ok(())
+    }
+
+    /// Move from `Pending` to `Rejected` for `reason`, which must not be
+    /// blank.
+    pub fn reject(&mut self, reason: &str) -> Result<(), PendingError> {
+        self.ensure_pending()?;
+        let reason = reason.trim();
+        if reason.is_empty() {
+            return Err(PendingError::BlankReason);
+        }
+        self.state = TransactionState::Rejected(reason.to_string());
+        Ok(())
+    }
+
+    /// What it holds back from `account`'s available balance: its amount
+    /// while pending, if the money would leave that account.
+    pub fn hold_on(&self, account: &str) -> Option<&Money> {
+        (self.is_pending() && self.held.account() == account).then(|| self.held.amount())
+    }
+
+    fn ensure_pending(&self) -> Result<(), PendingError> {
+        match self.is_pending() {
+            true => Ok(()),
+            false => Err(PendingError::Decided(self.id)),
+        }
+    }
+}
@@ -0,0 +1,1435 @@
+use std::fs;
+use std::io::{self, ErrorKind};
+use std::path::Path;
+
+use crate::api::account::{Account, InterestMethod, Promotion, Transaction, TransactionType};
+use crate::api::alert::AlertSettings;
+use crate::api::bank::{Bank, TransferReceipt};
+use crate::api::budget::Envelope;
+use crate::api::calendar::{BusinessCalendar, Holiday, RollConvention};
+use crate::api::cheque::Cheque;
+use crate::api::compliance::{ConversionLimit, FlaggedTransaction};
+use crate::api::conversion_log::{ConversionFilter, ConversionRecord};
+use crate::api::credential::Credential;
+use crate::api::customer::{Customer, IdType, Identification, VerificationStatus};
+use crate::api::date::{Date, TimeZone, Weekday};
+use crate::api::decimal::{Decimal, RoundingStrategy, SCALE};
+use crate::api::denomination::default_denominations;
+use crate::api::fee::{ConversionLeg, FeeSchedule, FeeTier};
+use crate::api::forex::{default_symbol, Forex, RateSource, DEFAULT_RATE_DP};
+use crate::api::format::Locale;
+use crate::api::forward::{ForwardContract, ForwardSide};
+use crate::api::goal::SavingsGoal;
+use crate::api::hold::Hold;
+use crate::api::idempotency::{IdempotencyRecord, IdempotentResult};
+use crate::api::limit_order::LimitOrder;
+use crate::api::limit_profile::{LimitProfile, LimitTarget};
+use crate::api::loan::{Loan, PaymentFrequency};
+use crate::api::money::Money;
+use crate::api::moving_average::MovingAverage;
+use crate::api::pending::{HeldTransaction, PendingTransaction, TransactionState};
+use crate::api::position::CurrencyPosition;
+use crate::api::rates::YearBasis;
+use crate::api::service_charge::{MaintainingBalance, ServiceCharges};
+use crate::api::standing_order::StandingOrder;
+use crate::api::sweep::SweepRule;
+
+/// Plain-text snapshot format for a whole `Bank`.
+/// Each line is a record tag followed by tab-separated fields; text fields
+/// escape `\`, tab, and newline so names may contain any character.
+/// Accounts are followed by their `tx` lines, whose amounts are integer
+/// minor units so a save/load round trip is exact. In-memory checkpoints are not
+/// part of a snapshot.
+///
+/// The first record is `version`, the schema version the file was written
+/// with. Files from releases before versioning have no such record and are
+/// treated as version 1. On load, older files are upgraded one version at a
+/// time by `MIGRATIONS` before being decoded, so only the current schema is
+/// ever interpreted.
+const HEADER: &str = "# rust_forex bank snapshot";
+
+/// Schema version written by `encode`.
+pub const SCHEMA_VERSION: u32 = 53;
+
+/// One snapshot line: its 1-based line number and raw (still escaped)
+/// tab-separated fields, the first being the record tag.
+struct Record {
+    line: usize,
+    fields: Vec<String>,
+}
+
+impl Record {
+    fn tag(&self) -> &str {
+        self.fields.first().map_or("", String::as_str)
+    }
+
+    fn field(&self, i: usize) -> io::Result<&str> {
+        self.fields
+            .get(i)
+            .map(String::as_str)
+            .ok_or_else(|| invalid(&format!("line {}: missing field {}", self.line, i)))
+    }
+}
+
+/// `MIGRATIONS[i]` upgrades the records of a version `i + 1` snapshot to
+/// version `i + 2`. Append a step whenever `SCHEMA_VERSION` is bumped.
+const MIGRATIONS: [fn(&mut Vec<Record>); (SCHEMA_VERSION - 1) as usize] = [migrate_v1_to_v2, migrate_v2_to_v3, migrate_v3_to_v4, migrate_v4_to_v5, migrate_v5_to_v6, migrate_v6_to_v7, migrate_v7_to_v8, migrate_v8_to_v9, migrate_v9_to_v10, migrate_v10_to_v11, migrate_v11_to_v12, migrate_v12_to_v13, migrate_v13_to_v14, migrate_v14_to_v15, migrate_v15_to_v16, migrate_v16_to_v17, migrate_v17_to_v18, migrate_v18_to_v19, migrate_v19_to_v20, migrate_v20_to_v21, migrate_v21_to_v22, migrate_v22_to_v23, migrate_v23_to_v24, migrate_v24_to_v25, migrate_v25_to_v26, migrate_v26_to_v27, migrate_v27_to_v28, migrate_v28_to_v29, migrate_v29_to_v30, migrate_v30_to_v31, migrate_v31_to_v32, migrate_v32_to_v33, migrate_v33_to_v34, migrate_v34_to_v35, migrate_v35_to_v36, migrate_v36_to_v37, migrate_v37_to_v38, migrate_v38_to_v39, migrate_v39_to_v40, migrate_v40_to_v41, migrate_v41_to_v42, migrate_v42_to_v43, migrate_v43_to_v44, migrate_v44_to_v45, migrate_v45_to_v46, migrate_v46_to_v47, migrate_v47_to_v48, migrate_v48_to_v49, migrate_v49_to_v50, migrate_v50_to_v51, migrate_v51_to_v52, migrate_v52_to_v53];
+
+/// v2 added a display symbol to `currency` records and dropped the separate
+/// `base_currency` record (the bank's base is the Forex base).
+fn migrate_v1_to_v2(records: &mut Vec<Record>) {
+    records.retain(|r| r.tag() != "base_currency");
+    for r in records.iter_mut().filter(|r| r.tag() == "currency") {
+        if r.fields.len() == 5 {
+            let symbol = default_symbol(&unesc(&r.fields[1]));
+            r.fields.push(esc(&symbol));
+        }
+    }
+}
+
+/// v3 added the rate's last-updated timestamp to `currency` records; older
+/// rates get an empty field (unknown).
+#[allow(clippy::ptr_arg)] // every entry in `MIGRATIONS` shares one signature
+fn migrate_v2_to_v3(records: &mut Vec<Record>) {
+    for r in records.iter_mut().filter(|r| r.tag() == "currency") {
+        r.fields.push(String::new());
+    }
+}
+
+/// v4 added the confirmation threshold and rate-change limit to the
+/// `compliance` record; older snapshots get empty fields (disabled).
+#[allow(clippy::ptr_arg)] // every entry in `MIGRATIONS` shares one signature
+fn migrate_v3_to_v4(records: &mut Vec<Record>) {
+    for r in records.iter_mut().filter(|r| r.tag() == "compliance") {
+        r.fields.extend([String::new(), String::new()]);
+    }
+}
+
+/// v5 added `loan` records; older snapshots have no loans, so there is
+/// nothing to rewrite.
+#[allow(clippy::ptr_arg)] // every entry in `MIGRATIONS` shares one signature
+fn migrate_v4_to_v5(_records: &mut Vec<Record>) {}
+
+/// v6 added `goal` records after an account's transactions; older
+/// snapshots have no goals.
+#[allow(clippy::ptr_arg)] // every entry in `MIGRATIONS` shares one signature
+fn migrate_v5_to_v6(_records: &mut Vec<Record>) {}
+
+/// v7 added `order` records; older snapshots have no standing orders.
+#[allow(clippy::ptr_arg)] // every entry in `MIGRATIONS` shares one signature
+fn migrate_v6_to_v7(_records: &mut Vec<Record>) {}
+
+/// v8 added `forward` records; older snapshots have no forwards.
+#[allow(clippy::ptr_arg)] // every entry in `MIGRATIONS` shares one signature
+fn migrate_v7_to_v8(_records: &mut Vec<Record>) {}
+
+/// v9 added `limit` records; older snapshots have no limit orders.
+#[allow(clippy::ptr_arg)] // every entry in `MIGRATIONS` shares one signature
+fn migrate_v8_to_v9(_records: &mut Vec<Record>) {}
+
+/// v10 added `position` records; older snapshots start every position at
+/// zero cost.
+#[allow(clippy::ptr_arg)] // every entry in `MIGRATIONS` shares one signature
+fn migrate_v9_to_v10(_records: &mut Vec<Record>) {}
+
+/// v11 added `basket` records; older snapshots have no baskets.
+#[allow(clippy::ptr_arg)] // every entry in `MIGRATIONS` shares one signature
+fn migrate_v10_to_v11(_records: &mut Vec<Record>) {}
+
+/// v12 added the budget category to `tx` records and `envelope` records
+/// after an account's goals; older transactions get an empty field
+/// (uncategorized).
+#[allow(clippy::ptr_arg)] // every entry in `MIGRATIONS` shares one signature
+fn migrate_v11_to_v12(records: &mut Vec<Record>) {
+    for r in records.iter_mut().filter(|r| r.tag() == "tx") {
+        r.fields.push(String::new());
+    }
+}
+
+/// v13 added `interest` records, an account's rate changes, after its
+/// transactions; an older account's `annual_interest` stays its only rate.
+#[allow(clippy::ptr_arg)] // every entry in `MIGRATIONS` shares one signature
+fn migrate_v12_to_v13(_records: &mut Vec<Record>) {}
+
+/// v14 added an optional `promotion` record, an account's bonus rate, after
+/// its rate changes; older accounts have none.
+#[allow(clippy::ptr_arg)] // every entry in `MIGRATIONS` shares one signature
+fn migrate_v13_to_v14(_records: &mut Vec<Record>) {}
+
+/// v15 added whether negative interest rates are allowed to `compliance`
+/// records; older banks did not allow them.
+#[allow(clippy::ptr_arg)] // every entry in `MIGRATIONS` shares one signature
+fn migrate_v14_to_v15(records: &mut Vec<Record>) {
+    for r in records.iter_mut().filter(|r| r.tag() == "compliance") {
+        r.fields.push(String::from("false"));
+    }
+}
+
+/// v16 added `fee_tier` records, the conversion fee schedule; older banks
+/// charged no fees.
+#[allow(clippy::ptr_arg)] // every entry in `MIGRATIONS` shares one signature
+fn migrate_v15_to_v16(_records: &mut Vec<Record>) {}
+
+/// v17 added `conversion` records, the log of executed conversions; older
+/// banks start with an empty log.
+#[allow(clippy::ptr_arg)] // every entry in `MIGRATIONS` shares one signature
+fn migrate_v16_to_v17(_records: &mut Vec<Record>) {}
+
+/// v18 added bill and coin denominations to `currency` records; older
+/// currencies get the built-in table for their code.
+#[allow(clippy::ptr_arg)] // every entry in `MIGRATIONS` shares one signature
+fn migrate_v17_to_v18(records: &mut Vec<Record>) {
+    for r in records.iter_mut().filter(|r| r.tag() == "currency") {
+        let denominations = default_denominations(&unesc(&r.fields[1]));
+        r.fields.push(denominations.iter().map(Decimal::to_string).collect::<Vec<_>>().join(","));
+    }
+}
+
+/// v19 added `till` records, the bills and coins in the teller's drawer;
+/// older banks start with an empty till.
+#[allow(clippy::ptr_arg)] // every entry in `MIGRATIONS` shares one signature
+fn migrate_v18_to_v19(_records: &mut Vec<Record>) {}
+
+/// v20 added an optional cash rate to `currency` records; older currencies
+/// are quoted in cash at their transfer rate.
+#[allow(clippy::ptr_arg)] // every entry in `MIGRATIONS` shares one signature
+fn migrate_v19_to_v20(records: &mut Vec<Record>) {
+    for r in records.iter_mut().filter(|r| r.tag() == "currency") {
+        r.fields.push(String::new());
+    }
+}
+
+/// v21 added `spread` records, fee rates for particular currency pairs;
+/// older catalogs charge every pair by the fee schedule.
+#[allow(clippy::ptr_arg)] // every entry in `MIGRATIONS` shares one signature
+fn migrate_v20_to_v21(_records: &mut Vec<Record>) {}
+
+/// v22 added `conversion_limit` records, per-currency caps on conversions;
+/// older banks convert any amount.
+#[allow(clippy::ptr_arg)] // every entry in `MIGRATIONS` shares one signature
+fn migrate_v21_to_v22(_records: &mut Vec<Record>) {}
+
+/// v23 added KYC details (ID type and number, address, verification
+/// status) to `customer` records and the limits for unverified accounts to
+/// `compliance`; older customers are unverified, with nothing on file, and
+/// older banks leave unverified accounts uncapped.
+#[allow(clippy::ptr_arg)] // every entry in `MIGRATIONS` shares one signature
+fn migrate_v22_to_v23(records: &mut Vec<Record>) {
+    for r in records.iter_mut() {
+        match r.tag() {
+            "customer" => r.fields.extend([String::new(), String::new(), String::new(), "unverified".to_string()]),
+            "compliance" => r.fields.extend([String::new(), String::new()]),
+            _ => {}
+        }
+    }
+}
+
+/// v24 added `alias` records, alternate names for the account before
+/// them; older accounts have none.
+#[allow(clippy::ptr_arg)] // every entry in `MIGRATIONS` shares one signature
+fn migrate_v23_to_v24(_records: &mut Vec<Record>) {}
+
+/// v25 added a bank-wide sequence number to `tx` records and the
+/// `sequence` record; older transactions are numbered by posting time,
+/// ties in the order they were saved.
+#[allow(clippy::ptr_arg)] // every entry in `MIGRATIONS` shares one signature
+fn migrate_v24_to_v25(records: &mut Vec<Record>) {
+    let mut txs: Vec<(i64, usize)> = records
+        .iter()
+        .enumerate()
+        .filter(|(_, r)| r.tag() == "tx")
+        .map(|(i, r)| (r.fields.get(3).and_then(|t| t.parse().ok()).unwrap_or(0), i))
+        .collect();
+    txs.sort();
+    for (sequence, (_, i)) in (1u64..).zip(txs) {
+        records[i].fields.push(sequence.to_string());
+    }
+}
+
+/// v26 added `idempotency` records, the keys of recent postings and what
+/// they returned; older banks remember none.
+#[allow(clippy::ptr_arg)] // every entry in `MIGRATIONS` shares one signature
+fn migrate_v25_to_v26(_records: &mut Vec<Record>) {}
+
+/// v27 added each account's version to `account` records; older accounts
+/// start at 0.
+#[allow(clippy::ptr_arg)] // every entry in `MIGRATIONS` shares one signature
+fn migrate_v26_to_v27(records: &mut Vec<Record>) {
+    for r in records.iter_mut().filter(|r| r.tag() == "account") {
+        r.fields.push(String::from("0"));
+    }
+}
+
+/// v28 added the day an account was archived to `account` records; older
+/// accounts are all active.
+#[allow(clippy::ptr_arg)] // every entry in `MIGRATIONS` shares one signature
+fn migrate_v27_to_v28(records: &mut Vec<Record>) {
+    for r in records.iter_mut().filter(|r| r.tag() == "account") {
+        r.fields.push(String::new());
+    }
+}
+
+/// v29 added the `time_zone` record, the zone timestamps are displayed in;
+/// older banks display them in the default, Asia/Manila.
+#[allow(clippy::ptr_arg)] // every entry in `MIGRATIONS` shares one signature
+fn migrate_v28_to_v29(_records: &mut Vec<Record>) {}
+
+/// v30 added the `calendar` record, the business days scheduled dates are
+/// rolled to; older banks get the default calendar for their locale.
+fn migrate_v29_to_v30(records: &mut Vec<Record>) {
+    let locale = records.iter().find(|r| r.tag() == "locale");
+    let line = locale.map_or(0, |r| r.line);
+    let locale = locale.and_then(|r| r.fields.get(1)).and_then(|tag| Locale::parse(tag)).unwrap_or_default();
+    records.push(Record { line, fields: calendar_fields(&BusinessCalendar::for_locale(locale)) });
+}
+
+/// v31 added who supplied each rate to `currency` records; older rates
+/// have no known source.
+#[allow(clippy::ptr_arg)] // every entry in `MIGRATIONS` shares one signature
+fn migrate_v30_to_v31(records: &mut Vec<Record>) {
+    for r in records.iter_mut().filter(|r| r.tag() == "currency") {
+        r.fields.push(String::new());
+    }
+}
+
+/// v32 added the fractional digits each currency's rates are kept to, to
+/// `currency` records; older rates keep every digit they were saved with,
+/// and at least `DEFAULT_RATE_DP`.
+#[allow(clippy::ptr_arg)] // every entry in `MIGRATIONS` shares one signature
+fn migrate_v31_to_v32(records: &mut Vec<Record>) {
+    for r in records.iter_mut().filter(|r| r.tag() == "currency") {
+        let digits = [3, 8]
+            .iter()
+            .filter_map(|&i| r.fields.get(i))
+            .map(|rate| rate.split_once('.').map_or(0, |(_, fraction)| fraction.len() as u32))
+            .max()
+            .unwrap_or(0);
+        r.fields.push(digits.max(DEFAULT_RATE_DP).to_string());
+    }
+}
+
+/// v33 added the legs of a converting transfer to `transferred`
+/// idempotency records; older receipts have none.
+#[allow(clippy::ptr_arg)] // every entry in `MIGRATIONS` shares one signature
+fn migrate_v32_to_v33(records: &mut Vec<Record>) {
+    for r in records.iter_mut().filter(|r| r.tag() == "idempotency" && r.fields.get(3).is_some_and(|kind| kind == "transferred")) {
+        r.fields.push(String::new());
+    }
+}
+
+/// v34 added the account interest is paid into to `account` records;
+/// older accounts keep their interest.
+#[allow(clippy::ptr_arg)] // every entry in `MIGRATIONS` shares one signature
+fn migrate_v33_to_v34(records: &mut Vec<Record>) {
+    for r in records.iter_mut().filter(|r| r.tag() == "account") {
+        r.fields.push(String::new());
+    }
+}
+
+/// v35 added `sweep` records, the bank's sweep rules; older banks have
+/// none.
+#[allow(clippy::ptr_arg)] // every entry in `MIGRATIONS` shares one signature
+fn migrate_v34_to_v35(_records: &mut Vec<Record>) {}
+
+/// v36 added `autoconvert` records, the currencies an account converts
+/// deposits from; older accounts convert none.
+#[allow(clippy::ptr_arg)] // every entry in `MIGRATIONS` shares one signature
+fn migrate_v35_to_v36(_records: &mut Vec<Record>) {}
+
+/// v37 added the amount filled so far and the expiry date to `limit`
+/// records; older orders were filled in one go or not at all, and are
+/// good till cancelled.
+#[allow(clippy::ptr_arg)] // every entry in `MIGRATIONS` shares one signature
+fn migrate_v36_to_v37(records: &mut Vec<Record>) {
+    for r in records.iter_mut().filter(|r| r.tag() == "limit") {
+        let filled = if r.fields.get(7).is_some_and(|f| f == "true") { r.fields.get(3).cloned().unwrap_or_default() } else { String::from("0") };
+        r.fields.extend([filled, String::new()]);
+    }
+}
+
+/// v38 added `alerts` records, an account's alert settings; older
+/// accounts have no alerts.
+#[allow(clippy::ptr_arg)] // every entry in `MIGRATIONS` shares one signature
+fn migrate_v37_to_v38(_records: &mut Vec<Record>) {}
+
+/// v39 added a transaction's tags to `tx` records, comma-separated; older
+/// transactions get an empty field (untagged).
+#[allow(clippy::ptr_arg)] // every entry in `MIGRATIONS` shares one signature
+fn migrate_v38_to_v39(records: &mut Vec<Record>) {
+    for r in records.iter_mut().filter(|r| r.tag() == "tx") {
+        r.fields.push(String::new());
+    }
+}
+
+/// v40 added the `withholding_tax` record, the share of interest withheld
+/// as tax; older banks withhold none.
+#[allow(clippy::ptr_arg)] // every entry in `MIGRATIONS` shares one signature
+fn migrate_v39_to_v40(_records: &mut Vec<Record>) {}
+
+/// v41 added `rate_history` records, each currency's daily closing rates;
+/// older banks start with no history.
+#[allow(clippy::ptr_arg)] // every entry in `MIGRATIONS` shares one signature
+fn migrate_v40_to_v41(_records: &mut Vec<Record>) {}
+
+/// v42 added the moving average that confirms a limit order to `limit`
+/// records; older orders fill on their limit alone.
+#[allow(clippy::ptr_arg)] // every entry in `MIGRATIONS` shares one signature
+fn migrate_v41_to_v42(records: &mut Vec<Record>) {
+    for r in records.iter_mut().filter(|r| r.tag() == "limit") {
+        r.fields.push(String::new());
+    }
+}
+
+/// v43 added the `year_basis` record, the basis new accounts accrue
+/// interest on, and `basis` records for accounts off the default; older
+/// banks count 365 days a year throughout.
+#[allow(clippy::ptr_arg)] // every entry in `MIGRATIONS` shares one signature
+fn migrate_v42_to_v43(_records: &mut Vec<Record>) {}
+
+/// v44 added the `interest_method` record, the method new accounts accrue
+/// interest by, and `method` records for accounts off the default; older
+/// banks accrue on each day's balance throughout.
+#[allow(clippy::ptr_arg)] // every entry in `MIGRATIONS` shares one signature
+fn migrate_v43_to_v44(_records: &mut Vec<Record>) {}
+
+/// v45 added the `interest_grace_days` record, the days new accounts'
+/// deposits wait before earning, and `grace` records for accounts with
+/// their own; older banks' deposits earn from the day they are posted.
+#[allow(clippy::ptr_arg)] // every entry in `MIGRATIONS` shares one signature
+fn migrate_v44_to_v45(_records: &mut Vec<Record>) {}
+
+/// v46 added `charges` records, an account's monthly service charge and
+/// maintaining balance; older accounts have none.
+#[allow(clippy::ptr_arg)] // every entry in `MIGRATIONS` shares one signature
+fn migrate_v45_to_v46(_records: &mut Vec<Record>) {}
+
+/// v47 added the approval threshold to the `compliance` record, and
+/// `approval` records, the withdrawals and transfers held for approval;
+/// older banks hold nothing back.
+#[allow(clippy::ptr_arg)] // every entry in `MIGRATIONS` shares one signature
+fn migrate_v46_to_v47(records: &mut Vec<Record>) {
+    for r in records.iter_mut().filter(|r| r.tag() == "compliance") {
+        r.fields.push(String::new());
+    }
+}
+
+/// v48 renamed `approval` records to `pending`, the held transactions, and
+/// their `approved` state to `posted`.
+#[allow(clippy::ptr_arg)] // every entry in `MIGRATIONS` shares one signature
+fn migrate_v47_to_v48(records: &mut Vec<Record>) {
+    for r in records.iter_mut().filter(|r| r.tag() == "approval") {
+        r.fields[0] = String::from("pending");
+        if let Some(state) = r.fields.get_mut(10).filter(|s| *s == "approved") {
+            *state = String::from("posted");
+        }
+    }
+}
+
+/// v49 added `hold` records, an account's authorization holds; older
+/// accounts have none.
+#[allow(clippy::ptr_arg)] // every entry in `MIGRATIONS` shares one signature
+fn migrate_v48_to_v49(_records: &mut Vec<Record>) {}
+
+/// v50 added the `cheque_clearing_days` record and `cheque` records, an
+/// account's deposited cheques; older banks clear in
+/// `DEFAULT_CLEARING_DAYS` and have no cheques.
+#[allow(clippy::ptr_arg)] // every entry in `MIGRATIONS` shares one signature
+fn migrate_v49_to_v50(_records: &mut Vec<Record>) {}
+
+/// v51 added `limit_profile` records, the limits on account types and KYC
+/// statuses, and `account_type` records; older banks have neither.
+#[allow(clippy::ptr_arg)] // every entry in `MIGRATIONS` shares one signature
+fn migrate_v50_to_v51(_records: &mut Vec<Record>) {}
+
+/// v52 added the exchange receipt number, teller, and customer session to
+/// `conversion` records, and the receipt number to `transferred`
+/// idempotency records; older conversions are numbered in log order, with
+/// no teller and a staff session.
+#[allow(clippy::ptr_arg)] // every entry in `MIGRATIONS` shares one signature
+fn migrate_v51_to_v52(records: &mut Vec<Record>) {
+    for (i, r) in records.iter_mut().filter(|r| r.tag() == "conversion").enumerate() {
+        r.fields.extend([(i + 1).to_string(), String::new(), String::new()]);
+    }
+    for r in records.iter_mut().filter(|r| r.tag() == "idempotency" && r.fields.get(3).is_some_and(|kind| kind == "transferred")) {
+        r.fields.push(String::new());
+    }
+}
+
+/// v53 marks closes in `rate_history` records carried over a non-trading
+/// day with a trailing `:carried`; older closes were all published.
+#[allow(clippy::ptr_arg)] // every entry in `MIGRATIONS` shares one signature
+fn migrate_v52_to_v53(_records: &mut Vec<Record>) {}
+
+/// A receipt's conversion legs as one field: `CODE:AMOUNT>CODE:AMOUNT@RATE`
+/// per leg, comma-separated.
+fn legs_field(legs: &[ConversionLeg]) -> String {
+    legs.iter()
+        .map(|l| format!("{}:{}>{}:{}@{}", l.from.currency, l.from.amount, l.to.currency, l.to.amount, l.rate))
+        .collect::<Vec<_>>()
+        .join(",")
+}
+
+/// The legs written by `legs_field`.
+fn parse_legs(field: &str) -> io::Result<Vec<ConversionLeg>> {
+    let money = |part: &str| -> io::Result<Money> {
+        let (code, amount) = part.split_once(':').ok_or_else(|| invalid(&format!("invalid conversion leg amount {}", part)))?;
+        Ok(Money::new(num(amount)?, code))
+    };
+    field
+        .split(',')
+        .filter(|leg| !leg.is_empty())
+        .map(|leg| {
+            let (amounts, rate) = leg.split_once('@').ok_or_else(|| invalid(&format!("invalid conversion leg {}", leg)))?;
+            let (from, to) = amounts.split_once('>').ok_or_else(|| invalid(&format!("invalid conversion leg {}", leg)))?;
+            Ok(ConversionLeg { from: money(from)?, to: money(to)?, rate: num(rate)? })
+        })
+        .collect()
+}
+
+/// The fields of a `calendar` record: the roll convention, then the
+/// weekend days and the holidays, each comma-separated.
+fn calendar_fields(calendar: &BusinessCalendar) -> Vec<String> {
+    vec![
+        "calendar".into(),
+        calendar.convention.name().into(),
+        calendar.weekend.iter().map(Weekday::short_name).collect::<Vec<_>>().join(","),
+        calendar.holidays.iter().map(Holiday::to_string).collect::<Vec<_>>().join(","),
+    ]
+}
+
+/// Serialize the bank state into the snapshot text format.
+pub fn encode(bank: &Bank) -> String {
+    let mut out = vec![HEADER.to_string()];
+    let mut line = |fields: Vec<String>| out.push(fields.join("\t"));
+
+    line(vec!["version".into(), SCHEMA_VERSION.to_string()]);
+    line(vec!["forex_base".into(), esc(bank.forex.get_base_rate())]);
+    for c in bank.forex.currencies_detailed() {
+        line(vec!["currency".into(), esc(&c.code), esc(&c.name), c.rate.to_string(), c.decimals.to_string(), esc(&c.symbol), c.updated.map(|t| t.to_string()).unwrap_or_default(), c.denominations.iter().map(Decimal::to_string).collect::<Vec<_>>().join(","), c.cash_rate.map(|r| r.to_string()).unwrap_or_default(), c.source.as_ref().map(|s| esc(s.name())).unwrap_or_default(), c.rate_decimals.to_string()]);
+    }
+    for b in bank.forex.baskets() {
+        let components: Vec<String> = b.components.iter().map(|(code, quantity)| format!("{}:{}", code, quantity)).collect();
+        line(vec!["basket".into(), esc(&b.code), esc(&components.join(","))]);
+    }
+    for t in bank.forex.fee_schedule().tiers() {
+        line(vec!["fee_tier".into(), t.from.to_string(), t.rate.to_string()]);
+    }
+    for s in bank.forex.pair_spreads() {
+        line(vec!["spread".into(), esc(&s.pair.0), esc(&s.pair.1), s.rate.to_string()]);
+    }
+    for c in bank.forex.currencies_detailed() {
+        let history = bank.forex.rate_history(&c.code);
+        if !history.is_empty() {
+            let closes: Vec<String> = history
+                .iter()
+                .map(|(date, rate)| match bank.forex.is_carried(&c.code, *date) {
+                    true => format!("{}:{}:carried", date, rate),
+                    false => format!("{}:{}", date, rate),
+                })
+                .collect();
+            line(vec!["rate_history".into(), esc(&c.code), closes.join(",")]);
+        }
+    }
+    for c in bank.forex.conversion_history(&ConversionFilter::default()) {
+        line(vec![
+            "conversion".into(),
+            c.timestamp.to_string(),
+            esc(c.account.as_deref().unwrap_or_default()),
+            c.amount_in.amount.to_string(),
+            esc(&c.amount_in.currency),
+            c.amount_out.amount.to_string(),
+            esc(&c.amount_out.currency),
+            c.rate.to_string(),
+            c.fee.amount.to_string(),
+            c.volume.to_string(),
+            c.receipt.to_string(),
+            esc(c.teller.as_deref().unwrap_or_default()),
+            esc(c.customer.as_deref().unwrap_or_default()),
+        ]);
+    }
+    line(vec!["annual_interest".into(), bank.annual_interest.to_string()]);
+    line(vec!["withholding_tax".into(), bank.withholding_tax_rate.to_string()]);
+    line(vec!["year_basis".into(), bank.year_basis.name().into()]);
+    line(vec!["interest_method".into(), bank.interest_method.name().into()]);
+    line(vec!["interest_grace_days".into(), bank.interest_grace_days.to_string()]);
+    line(vec!["cheque_clearing_days".into(), bank.cheque_clearing_days.to_string()]);
+    line(vec!["sequence".into(), bank.next_sequence().to_string()]);
+    line(vec![
+        "compliance".into(),
+        bank.compliance.large_threshold.map(|t| t.to_string()).unwrap_or_default(),
+        bank.compliance.require_confirmation.to_string(),
+        bank.compliance.confirm_threshold.map(|t| t.to_string()).unwrap_or_default(),
+        bank.compliance.rate_change_limit.map(|t| t.to_string()).unwrap_or_default(),
+        bank.compliance.allow_negative_rates.to_string(),
+        bank.compliance.unverified_limit.map(|t| t.to_string()).unwrap_or_default(),
+        bank.compliance.unverified_daily_limit.map(|t| t.to_string()).unwrap_or_default(),
+        bank.compliance.approval_threshold.map(|t| t.to_string()).unwrap_or_default(),
+    ]);
+    for (code, limit) in &bank.compliance.conversion_limits {
+        line(vec![
+            "conversion_limit".into(),
+            esc(code),
+            limit.per_transaction.map(|l| l.to_string()).unwrap_or_default(),
+            limit.per_day.map(|l| l.to_string()).unwrap_or_default(),
+        ]);
+    }
+    for (target, profile) in &bank.compliance.limit_profiles {
+        line(vec![
+            "limit_profile".into(),
+            esc(&target.to_string()),
+            profile.max_deposit.map(|l| l.to_string()).unwrap_or_default(),
+            profile.max_withdrawal.map(|l| l.to_string()).unwrap_or_default(),
+            profile.max_daily_conversion.map(|l| l.to_string()).unwrap_or_default(),
+        ]);
+    }
+    line(vec!["rounding".into(), format!("{:?}", bank.rounding.strategy)]);
+    line(vec!["locale".into(), bank.locale.tag().into()]);
+    line(vec!["time_zone".into(), esc(&bank.time_zone.tag())]);
+    line(calendar_fields(&bank.calendar));
+    for (code, residue) in &bank.rounding_residue {
+        line(vec!["residue".into(), esc(code), residue.to_string()]);
+    }
+    if let Some(cred) = &bank.admin_credential {
+        line(vec!["admin".into(), cred.salt.clone(), cred.hash.clone()]);
+    }
+    for c in &bank.customers {
+        let ids: Vec<String> = c.account_ids.iter().map(|id| id.to_string()).collect();
+        let (id_type, id_number) = c.identification.as_ref().map_or(("", ""), |i| (i.id_type.name(), i.id_number.as_str()));
+        line(vec![
+            "customer".into(),
+            c.id.to_string(),
+            esc(&c.name),
+            esc(&c.contact),
+            ids.join(","),
+            id_type.into(),
+            esc(id_number),
+            esc(&c.address),
+            c.verification.name().into(),
+        ]);
+    }
+    for a in &bank.accounts {
+        let (salt, hash) = match &a.credential {
+            Some(cred) => (cred.salt.clone(), cred.hash.clone()),
+            None => (String::new(), String::new()),
+        };
+        line(vec![
+            "account".into(),
+            a.id.to_string(),
+            esc(&a.name),
+            esc(&a.currency),
+            a.minor_unit_dp.to_string(),
+            a.annual_interest.to_string(),
+            salt,
+            hash,
+            a.version.to_string(),
+            a.archived.map(|d| d.to_string()).unwrap_or_default(),
+            esc(a.interest_payout.as_deref().unwrap_or_default()),
+        ]);
+        for t in &a.transactions {
+            line(vec!["tx".into(), t.units.to_string(), t.dp.to_string(), t.timestamp.to_string(), esc(t.memo), esc(t.category.unwrap_or_default()), t.sequence.to_string(), esc(&t.tags.iter().collect::<Vec<_>>().join(","))]);
+        }
+        for c in &a.rate_changes {
+            line(vec!["interest".into(), c.effective.to_string(), c.annual_interest.to_string()]);
+        }
+        if let Some(p) = &a.promotion {
+            line(vec!["promotion".into(), p.bonus.to_string(), p.start.to_string(), p.end.to_string()]);
+        }
+        for g in &a.goals {
+            line(vec!["goal".into(), esc(&g.name), g.target.amount.to_string(), g.target_date.to_string()]);
+        }
+        for e in &a.envelopes {
+            line(vec!["envelope".into(), esc(&e.category), e.limit.amount.to_string()]);
+        }
+        for alias in &a.aliases {
+            line(vec!["alias".into(), esc(alias)]);
+        }
+        for code in &a.auto_convert {
+            line(vec!["autoconvert".into(), esc(code)]);
+        }
+        if a.alerts != AlertSettings::default() {
+            let opt = |v: Option<Decimal>| v.map(|d| d.to_string()).unwrap_or_default();
+            line(vec![
+                "alerts".into(),
+                opt(a.alerts.balance_below),
+                opt(a.alerts.withdrawal_above),
+                a.alerts.inactive_days.map(|d| d.to_string()).unwrap_or_default(),
+                a.alerts.below_raised.to_string(),
+                a.alerts.inactive_raised.map(|d| d.to_string()).unwrap_or_default(),
+            ]);
+        }
+        if !a.service_charges.is_empty() {
+            let opt = |v: Option<Decimal>| v.map(|d| d.to_string()).unwrap_or_default();
+            let maintaining = a.service_charges.maintaining;
+            line(vec![
+                "charges".into(),
+                opt(a.service_charges.monthly),
+                opt(maintaining.map(|m| m.balance)),
+                opt(maintaining.map(|m| m.charge)),
+            ]);
+        }
+        for h in &a.holds {
+            line(vec!["hold".into(), h.id.to_string(), h.amount.amount.to_string(), esc(&h.reason), h.placed.to_string(), h.expiry.to_string()]);
+        }
+        for c in &a.cheques {
+            let (returned, reason) = c.returned.as_ref().map_or((String::new(), String::new()), |(on, reason)| (on.to_string(), esc(reason)));
+            line(vec![
+                "cheque".into(),
+                c.id.to_string(),
+                esc(&c.number),
+                c.amount.amount.to_string(),
+                c.sequence.to_string(),
+                c.deposited.to_string(),
+                c.clears.to_string(),
+                returned,
+                reason,
+            ]);
+        }
+        if a.position != CurrencyPosition::default() {
+            line(vec!["position".into(), a.position.cost_basis.to_string(), a.position.realized.to_string()]);
+        }
+        if a.year_basis != YearBasis::default() {
+            line(vec!["basis".into(), a.year_basis.name().into()]);
+        }
+        if a.interest_method != InterestMethod::default() {
+            line(vec!["method".into(), a.interest_method.name().into()]);
+        }
+        if a.interest_grace_days != 0 {
+            line(vec!["grace".into(), a.interest_grace_days.to_string()]);
+        }
+        if let Some(kind) = &a.account_type {
+            line(vec!["account_type".into(), esc(kind)]);
+        }
+    }
+    for l in &bank.loans {
+        line(vec![
+            "loan".into(),
+            l.id.to_string(),
+            esc(&l.account),
+            l.principal.amount.to_string(),
+            esc(&l.principal.currency),
+            l.minor_unit_dp.to_string(),
+            l.annual_rate.to_string(),
+            l.term.to_string(),
+            l.frequency.name().into(),
+            l.payments_made.to_string(),
+        ]);
+    }
+    for s in &bank.sweeps {
+        line(vec!["sweep".into(), s.id.to_string(), esc(&s.from), esc(&s.to), s.threshold.amount.to_string(), esc(&s.threshold.currency)]);
+    }
+    for o in &bank.standing_orders {
+        line(vec![
+            "order".into(),
+            o.id.to_string(),
+            esc(&o.from),
+            esc(&o.to),
+            o.amount.amount.to_string(),
+            esc(&o.amount.currency),
+            o.interval_days.to_string(),
+            o.next.to_string(),
+        ]);
+    }
+    for f in &bank.forwards {
+        line(vec![
+            "forward".into(),
+            f.id.to_string(),
+            esc(&f.account),
+            f.side.name().into(),
+            f.amount.amount.to_string(),
+            esc(&f.amount.currency),
+            f.rate.to_string(),
+            f.value_date.to_string(),
+            f.settled.to_string(),
+        ]);
+    }
+    for o in &bank.limit_orders {
+        line(vec![
+            "limit".into(),
+            o.id.to_string(),
+            esc(&o.account),
+            o.amount.amount.to_string(),
+            esc(&o.amount.currency),
+            esc(&o.to),
+            o.limit.to_string(),
+            o.filled.to_string(),
+            o.filled_amount.to_string(),
+            o.expires.map(|d| d.to_string()).unwrap_or_default(),
+            o.average.map(|a| a.to_string()).unwrap_or_default(),
+        ]);
+    }
+    for code in bank.till.currencies() {
+        for p in bank.till.contents(code) {
+            line(vec!["till".into(), esc(code), p.value.to_string(), p.count.to_string()]);
+        }
+    }
+    for f in &bank.flagged {
+        line(vec![
+            "flag".into(),
+            f.id.to_string(),
+            esc(&f.account),
+            format!("{:?}", f.tx_type),
+            f.amount.amount.to_string(),
+            esc(&f.amount.currency),
+            f.reviewed.to_string(),
+        ]);
+    }
+    for p in &bank.pending {
+        let (kind, account, to, memo, category) = match &p.held {
+            HeldTransaction::Withdrawal { account, memo, category, .. } => ("withdrawal", account, "", memo.as_str(), category.as_deref().unwrap_or_default()),
+            HeldTransaction::Transfer { from, to, .. } => ("transfer", from, to.as_str(), "", ""),
+        };
+        let reason = match &p.state {
+            TransactionState::Rejected(reason) => reason.as_str(),
+            _ => "",
+        };
+        line(vec![
+            "pending".into(),
+            p.id.to_string(),
+            p.requested.to_string(),
+            kind.into(),
+            esc(account),
+            esc(to),
+            p.held.amount().amount.to_string(),
+            esc(&p.held.amount().currency),
+            esc(memo),
+            esc(category),
+            p.state.name().into(),
+            esc(reason),
+        ]);
+    }
+    for r in bank.idempotency.iter() {
+        let mut fields = vec!["idempotency".into(), esc(&r.key), esc(&r.request)];
+        match &r.result {
+            IdempotentResult::Posted(balance) => fields.extend(["posted".into(), balance.amount.to_string(), esc(&balance.currency)]),
+            IdempotentResult::Transferred(t) => fields.extend([
+                "transferred".into(),
+                esc(&t.from),
+                esc(&t.to),
+                t.debited.amount.to_string(),
+                esc(&t.debited.currency),
+                t.credited.amount.to_string(),
+                esc(&t.credited.currency),
+                t.rate.to_string(),
+                t.fee.amount.to_string(),
+                esc(&t.fee.currency),
+                esc(&legs_field(&t.legs)),
+                t.receipt.map(|n| n.to_string()).unwrap_or_default(),
+            ]),
+        }
+        line(fields);
+    }
+    out.join("\n") + "\n"
+}
+
+/// Rebuild a `Bank` from snapshot text produced by `encode`, migrating
+/// snapshots written with an older schema version first.
+pub fn decode(text: &str) -> io::Result<Bank> {
+    let mut lines = text.lines();
+    if lines.next() != Some(HEADER) {
+        return Err(invalid("missing snapshot header"));
+    }
+
+    let mut records: Vec<Record> = lines
+        .enumerate()
+        .filter(|(_, raw)| !raw.is_empty())
+        .map(|(n, raw)| Record { line: n + 2, fields: raw.split('\t').map(String::from).collect() })
+        .collect();
+    let version = match records.iter().find(|r| r.tag() == "version") {
+        Some(r) => r.field(1)?.parse().map_err(|_| invalid(&format!("line {}: invalid version", r.line)))?,
+        None => 1,
+    };
+    if version == 0 || version > SCHEMA_VERSION {
+        return Err(invalid(&format!(
+            "snapshot schema version {} is not supported (this release reads up to {})",
+            version, SCHEMA_VERSION
+        )));
+    }
+    for migrate in &MIGRATIONS[(version - 1) as usize..] {
+        migrate(&mut records);
+    }
+
+    let mut bank = Bank::builder().set_forex(decode_forex(&records)?).build();
+    let mut next_sequence = 0;
+    // Applied once every record is read, since restoring an account's
+    // transactions counts as changing it.
+    let mut versions = Vec::new();
+
+    for r in &records {
+        let n = r.line;
+        let field = |i: usize| r.field(i);
+        match r.tag() {
+            // Read by `decode_forex`.
+            "version" | "forex_base" | "currency" | "basket" | "fee_tier" | "spread" | "rate_history" | "conversion" => {}
+            "annual_interest" => bank.annual_interest = num(field(1)?)?,
+            "withholding_tax" => bank.withholding_tax_rate = num(field(1)?)?,
+            "year_basis" => bank.year_basis = year_basis(field(1)?)?,
+            "interest_method" => bank.interest_method = interest_method(field(1)?)?,
+            "interest_grace_days" => bank.interest_grace_days = count(field(1)?)?,
+            "cheque_clearing_days" => bank.cheque_clearing_days = count(field(1)?)?,
+            "sequence" => next_sequence = sequence(field(1)?)?,
+            "compliance" => {
+                bank.compliance.large_threshold = opt_num(field(1)?)?;
+                bank.compliance.require_confirmation = field(2)? == "true";
+                bank.compliance.confirm_threshold = opt_num(field(3)?)?;
+                bank.compliance.rate_change_limit = opt_num(field(4)?)?;
+                bank.compliance.allow_negative_rates = field(5)? == "true";
+                bank.compliance.unverified_limit = opt_num(field(6)?)?;
+                bank.compliance.unverified_daily_limit = opt_num(field(7)?)?;
+                bank.compliance.approval_threshold = opt_num(field(8)?)?;
+            }
+            "conversion_limit" => {
+                let limit = ConversionLimit { per_transaction: opt_num(field(2)?)?, per_day: opt_num(field(3)?)? };
+                bank.compliance.conversion_limits.insert(unesc(field(1)?), limit);
+            }
+            "limit_profile" => {
+                let raw = unesc(field(1)?);
+                let target = LimitTarget::parse(&raw).ok_or_else(|| invalid(&format!("line {}: unknown limit profile target {}", n, raw)))?;
+                let profile = LimitProfile { max_deposit: opt_num(field(2)?)?, max_withdrawal: opt_num(field(3)?)?, max_daily_conversion: opt_num(field(4)?)? };
+                bank.compliance.limit_profiles.insert(target, profile);
+            }
+            "rounding" => {
+                let name = field(1)?;
+                bank.rounding.strategy = RoundingStrategy::parse(name)
+                    .ok_or_else(|| invalid(&format!("line {}: unknown rounding strategy {}", n, name)))?;
+            }
+            "locale" => {
+                let tag = field(1)?;
+                bank.locale = Locale::parse(tag)
+                    .ok_or_else(|| invalid(&format!("line {}: unknown locale {}", n, tag)))?;
+            }
+            "time_zone" => {
+                let tag = unesc(field(1)?);
+                bank.time_zone = TimeZone::parse(&tag)
+                    .ok_or_else(|| invalid(&format!("line {}: unknown time zone {}", n, tag)))?;
+            }
+            "calendar" => {
+                let name = field(1)?;
+                bank.calendar.convention = RollConvention::parse(name)
+                    .ok_or_else(|| invalid(&format!("line {}: unknown roll convention {}", n, name)))?;
+                bank.calendar.weekend = field(2)?
+                    .split(',')
+                    .filter(|s| !s.is_empty())
+                    .map(|d| Weekday::parse(d).ok_or_else(|| invalid(&format!("line {}: unknown weekday {}", n, d))))
+                    .collect::<io::Result<_>>()?;
+                bank.calendar.holidays = field(3)?
+                    .split(',')
+                    .filter(|s| !s.is_empty())
+                    .map(|h| Holiday::parse(h).ok_or_else(|| invalid(&format!("line {}: invalid holiday {}", n, h))))
+                    .collect::<io::Result<_>>()?;
+            }
+            "residue" => {
+                bank.rounding_residue.insert(unesc(field(1)?), num(field(2)?)?);
+            }
+            "admin" => {
+                bank.admin_credential = Some(Credential { salt: field(1)?.to_string(), hash: field(2)?.to_string() });
+            }
+            "customer" => {
+                let mut c = Customer::new(int(field(1)?)?, &unesc(field(2)?), &unesc(field(3)?));
+                for id in field(4)?.split(',').filter(|s| !s.is_empty()) {
+                    c.account_ids.push(int(id)?);
+                }
+                let id_type = field(5)?;
+                if !id_type.is_empty() {
+                    let id_type = IdType::parse(id_type).ok_or_else(|| invalid(&format!("line {}: unknown ID type {}", n, id_type)))?;
+                    c.identification = Some(Identification { id_type, id_number: unesc(field(6)?) });
+                }
+                c.address = unesc(field(7)?);
+                let status = field(8)?;
+                c.verification = VerificationStatus::parse(status)
+                    .ok_or_else(|| invalid(&format!("line {}: unknown verification status {}", n, status)))?;
+                bank.customers.push(c);
+            }
+            "account" => {
+                let mut a = Account::new(&unesc(field(2)?))
+                    .with_id(int(field(1)?)?)
+                    .with_currency(&unesc(field(3)?))
+                    .with_minor_unit_dp(dp(field(4)?)?)
+                    .with_interest(num(field(5)?)?);
+                let (salt, hash) = (field(6)?, field(7)?);
+                if !salt.is_empty() {
+                    a.credential = Some(Credential { salt: salt.to_string(), hash: hash.to_string() });
+                }
+                versions.push(account_version(field(8)?)?);
+                let archived = field(9)?;
+                if !archived.is_empty() {
+                    a.archived = Some(Date::parse(archived).ok_or_else(|| invalid(&format!("line {}: invalid date {}", n, archived)))?);
+                }
+                a.interest_payout = Some(unesc(field(10)?)).filter(|p| !p.is_empty());
+                bank.accounts.push(a);
+            }
+            "tx" => {
+                let acct = bank
+                    .accounts
+                    .last_mut()
+                    .ok_or_else(|| invalid(&format!("line {}: transaction before any account", n)))?;
+                acct.push_transaction(Transaction {
+                    units: field(1)?.parse().map_err(|_| invalid(&format!("line {}: invalid minor units", n)))?,
+                    dp: dp(field(2)?)?,
+                    timestamp: field(3)?.parse().map_err(|_| invalid(&format!("line {}: invalid timestamp", n)))?,
+                    sequence: sequence(field(6)?)?,
+                    memo: unesc(field(4)?),
+                    category: Some(unesc(field(5)?)).filter(|c| !c.is_empty()),
+                    tags: unesc(field(7)?).split(',').filter(|t| !t.is_empty()).map(str::to_string).collect(),
+                });
+            }
+            "interest" => {
+                let acct = bank
+                    .accounts
+                    .last_mut()
+                    .ok_or_else(|| invalid(&format!("line {}: interest rate before any account", n)))?;
+                let date = field(1)?;
+                let effective = Date::parse(date).ok_or_else(|| invalid(&format!("line {}: invalid date {}", n, date)))?;
+                acct.change_interest(num(field(2)?)?, effective);
+            }
+            "promotion" => {
+                let acct = bank
+                    .accounts
+                    .last_mut()
+                    .ok_or_else(|| invalid(&format!("line {}: promotion before any account", n)))?;
+                let date = |i: usize| -> io::Result<Date> {
+                    let raw = field(i)?;
+                    Date::parse(raw).ok_or_else(|| invalid(&format!("line {}: invalid date {}", n, raw)))
+                };
+                let (start, end) = (date(2)?, date(3)?);
+                if end <= start {
+                    return Err(invalid(&format!("line {}: promotion ends before it starts", n)));
+                }
+                acct.promotion = Some(Promotion { bonus: num(field(1)?)?, start, end });
+            }
+            "goal" => {
+                let acct = bank
+                    .accounts
+                    .last_mut()
+                    .ok_or_else(|| invalid(&format!("line {}: goal before any account", n)))?;
+                let date = field(3)?;
+                acct.goals.push(SavingsGoal {
+                    name: unesc(field(1)?),
+                    target: Money::new(num(field(2)?)?, &acct.currency),
+                    target_date: Date::parse(date).ok_or_else(|| invalid(&format!("line {}: invalid date {}", n, date)))?,
+                });
+            }
+            "envelope" => {
+                let acct = bank
+                    .accounts
+                    .last_mut()
+                    .ok_or_else(|| invalid(&format!("line {}: envelope before any account", n)))?;
+                acct.envelopes.push(Envelope::new(&unesc(field(1)?), Money::new(num(field(2)?)?, &acct.currency)));
+            }
+            "alias" => {
+                let acct = bank
+                    .accounts
+                    .last_mut()
+                    .ok_or_else(|| invalid(&format!("line {}: alias before any account", n)))?;
+                acct.aliases.push(unesc(field(1)?));
+            }
+            "autoconvert" => {
+                let acct = bank
+                    .accounts
+                    .last_mut()
+                    .ok_or_else(|| invalid(&format!("line {}: auto-conversion before any account", n)))?;
+                acct.auto_convert.push(unesc(field(1)?));
+            }
+            "alerts" => {
+                let opt = |i: usize| -> io::Result<Option<Decimal>> {
+                    match field(i)? {
+                        "" => Ok(None),
+                        raw => num(raw).map(Some),
+                    }
+                };
+                let raised = field(5)?;
+                let alerts = AlertSettings {
+                    balance_below: opt(1)?,
+                    withdrawal_above: opt(2)?,
+                    inactive_days: match field(3)? {
+                        "" => None,
+                        raw => Some(raw.parse().map_err(|_| invalid(&format!("line {}: invalid day count {}", n, raw)))?),
+                    },
+                    below_raised: field(4)? == "true",
+                    inactive_raised: match raised {
+                        "" => None,
+                        date => Some(Date::parse(date).ok_or_else(|| invalid(&format!("line {}: invalid date {}", n, date)))?),
+                    },
+                };
+                let acct = bank
+                    .accounts
+                    .last_mut()
+                    .ok_or_else(|| invalid(&format!("line {}: alerts before any account", n)))?;
+                acct.alerts = alerts;
+            }
+            "charges" => {
+                let maintaining = match (opt_num(field(2)?)?, opt_num(field(3)?)?) {
+                    (Some(balance), Some(charge)) => Some(MaintainingBalance { balance, charge }),
+                    (None, None) => None,
+                    _ => return Err(invalid(&format!("line {}: maintaining balance without its charge", n))),
+                };
+                let charges = ServiceCharges::new(opt_num(field(1)?)?, maintaining).map_err(|e| invalid(&format!("line {}: {}", n, e)))?;
+                let acct = bank
+                    .accounts
+                    .last_mut()
+                    .ok_or_else(|| invalid(&format!("line {}: service charges before any account", n)))?;
+                acct.service_charges = charges;
+            }
+            "hold" => {
+                let date = |i: usize| -> io::Result<Date> {
+                    let text = field(i)?;
+                    Date::parse(text).ok_or_else(|| invalid(&format!("line {}: invalid date {}", n, text)))
+                };
+                let (placed, expiry) = (date(4)?, date(5)?);
+                let acct = bank
+                    .accounts
+                    .last_mut()
+                    .ok_or_else(|| invalid(&format!("line {}: hold before any account", n)))?;
+                let amount = Money::new(num(field(2)?)?, &acct.currency);
+                acct.holds.push(Hold { id: int(field(1)?)?, amount, reason: unesc(field(3)?), placed, expiry });
+            }
+            "cheque" => {
+                let date = |i: usize| -> io::Result<Date> {
+                    let text = field(i)?;
+                    Date::parse(text).ok_or_else(|| invalid(&format!("line {}: invalid date {}", n, text)))
+                };
+                let (deposited, clears) = (date(5)?, date(6)?);
+                let returned = match field(7)? {
+                    "" => None,
+                    _ => Some((date(7)?, unesc(field(8)?))),
+                };
+                let acct = bank
+                    .accounts
+                    .last_mut()
+                    .ok_or_else(|| invalid(&format!("line {}: cheque before any account", n)))?;
+                let amount = Money::new(num(field(3)?)?, &acct.currency);
+                acct.cheques.push(Cheque { id: int(field(1)?)?, number: unesc(field(2)?), amount, sequence: sequence(field(4)?)?, deposited, clears, returned });
+            }
+            "position" => {
+                let acct = bank
+                    .accounts
+                    .last_mut()
+                    .ok_or_else(|| invalid(&format!("line {}: position before any account", n)))?;
+                acct.position = CurrencyPosition { cost_basis: num(field(1)?)?, realized: num(field(2)?)? };
+            }
+            "basis" => {
+                let acct = bank
+                    .accounts
+                    .last_mut()
+                    .ok_or_else(|| invalid(&format!("line {}: year basis before any account", n)))?;
+                acct.year_basis = year_basis(field(1)?)?;
+            }
+            "method" => {
+                let acct = bank
+                    .accounts
+                    .last_mut()
+                    .ok_or_else(|| invalid(&format!("line {}: interest method before any account", n)))?;
+                acct.interest_method = interest_method(field(1)?)?;
+            }
+            "grace" => {
+                let acct = bank
+                    .accounts
+                    .last_mut()
+                    .ok_or_else(|| invalid(&format!("line {}: interest grace period before any account", n)))?;
+                acct.interest_grace_days = count(field(1)?)?;
+            }
+            "account_type" => {
+                let acct = bank
+                    .accounts
+                    .last_mut()
+                    .ok_or_else(|| invalid(&format!("line {}: account type before any account", n)))?;
+                acct.account_type = Some(unesc(field(1)?));
+            }
+            "loan" => {
+                let name = field(8)?;
+                let frequency = PaymentFrequency::parse(name)
+                    .ok_or_else(|| invalid(&format!("line {}: unknown payment frequency {}", n, name)))?;
+                bank.loans.push(Loan {
+                    id: int(field(1)?)?,
+                    account: unesc(field(2)?),
+                    principal: Money::new(num(field(3)?)?, &unesc(field(4)?)),
+                    minor_unit_dp: dp(field(5)?)?,
+                    annual_rate: num(field(6)?)?,
+                    term: count(field(7)?)?,
+                    frequency,
+                    payments_made: count(field(9)?)?,
+                });
+            }
+            "order" => {
+                let next = field(7)?;
+                bank.standing_orders.push(StandingOrder {
+                    id: int(field(1)?)?,
+                    from: unesc(field(2)?),
+                    to: unesc(field(3)?),
+                    amount: Money::new(num(field(4)?)?, &unesc(field(5)?)),
+                    interval_days: count(field(6)?)?,
+                    next: Date::parse(next).ok_or_else(|| invalid(&format!("line {}: invalid date {}", n, next)))?,
+                });
+            }
+            "sweep" => bank.sweeps.push(SweepRule {
+                id: int(field(1)?)?,
+                from: unesc(field(2)?),
+                to: unesc(field(3)?),
+                threshold: Money::new(num(field(4)?)?, &unesc(field(5)?)),
+            }),
+            "forward" => {
+                let (side, date) = (field(3)?, field(7)?);
+                bank.forwards.push(ForwardContract {
+                    id: int(field(1)?)?,
+                    account: unesc(field(2)?),
+                    side: ForwardSide::parse(side).ok_or_else(|| invalid(&format!("line {}: unknown forward side {}", n, side)))?,
+                    amount: Money::new(num(field(4)?)?, &unesc(field(5)?)),
+                    rate: num(field(6)?)?,
+                    value_date: Date::parse(date).ok_or_else(|| invalid(&format!("line {}: invalid date {}", n, date)))?,
+                    settled: field(8)? == "true",
+                });
+            }
+            "limit" => {
+                let (expires, average) = (field(9)?, field(10)?);
+                bank.limit_orders.push(LimitOrder {
+                    id: int(field(1)?)?,
+                    account: unesc(field(2)?),
+                    amount: Money::new(num(field(3)?)?, &unesc(field(4)?)),
+                    to: unesc(field(5)?),
+                    limit: num(field(6)?)?,
+                    filled: field(7)? == "true",
+                    filled_amount: num(field(8)?)?,
+                    expires: match expires {
+                        "" => None,
+                        date => Some(Date::parse(date).ok_or_else(|| invalid(&format!("line {}: invalid date {}", n, date)))?),
+                    },
+                    average: match average {
+                        "" => None,
+                        raw => Some(MovingAverage::parse(raw).ok_or_else(|| invalid(&format!("line {}: invalid moving average {}", n, raw)))?),
+                    },
+                });
+            }
+            "till" => bank.till.load(&unesc(field(1)?), num(field(2)?)?, int(field(3)?)? as u64),
+            "flag" => bank.flagged.push(FlaggedTransaction {
+                id: int(field(1)?)?,
+                account: unesc(field(2)?),
+                tx_type: match field(3)? {
+                    "Deposit" => TransactionType::Deposit,
+                    "Withdraw" => TransactionType::Withdraw,
+                    other => return Err(invalid(&format!("line {}: unknown transaction type {}", n, other))),
+                },
+                amount: Money::new(num(field(4)?)?, &unesc(field(5)?)),
+                reviewed: field(6)? == "true",
+            }),
+            "pending" => {
+                let requested = field(2)?;
+                let amount = Money::new(num(field(6)?)?, &unesc(field(7)?));
+                let held = match field(3)? {
+                    "withdrawal" => HeldTransaction::Withdrawal {
+                        account: unesc(field(4)?),
+                        amount,
+                        memo: unesc(field(8)?),
+                        category: Some(unesc(field(9)?)).filter(|c| !c.is_empty()),
+                    },
+                    "transfer" => HeldTransaction::Transfer { from: unesc(field(4)?), to: unesc(field(5)?), amount },
+                    other => return Err(invalid(&format!("line {}: unknown held transaction {}", n, other))),
+                };
+                bank.pending.push(PendingTransaction {
+                    id: int(field(1)?)?,
+                    requested: Date::parse(requested).ok_or_else(|| invalid(&format!("line {}: invalid date {}", n, requested)))?,
+                    held,
+                    state: match field(10)? {
+                        "pending" => TransactionState::Pending,
+                        "posted" => TransactionState::Posted,
+                        "rejected" => TransactionState::Rejected(unesc(field(11)?)),
+                        other => return Err(invalid(&format!("line {}: unknown transaction state {}", n, other))),
+                    },
+                });
+            }
+            "idempotency" => {
+                let money = |amount: usize, code: usize| -> io::Result<Money> { Ok(Money::new(num(field(amount)?)?, &unesc(field(code)?))) };
+                let result = match field(3)? {
+                    "posted" => IdempotentResult::Posted(money(4, 5)?),
+                    "transferred" => IdempotentResult::Transferred(Box::new(TransferReceipt {
+                        from: unesc(field(4)?),
+                        to: unesc(field(5)?),
+                        debited: money(6, 7)?,
+                        credited: money(8, 9)?,
+                        rate: num(field(10)?)?,
+                        fee: money(11, 12)?,
+                        legs: parse_legs(&unesc(field(13)?))?,
+                        receipt: Some(field(14)?).filter(|n| !n.is_empty()).map(int).transpose()?,
+                    })),
+                    other => return Err(invalid(&format!("line {}: unknown idempotent result {}", n, other))),
+                };
+                bank.idempotency.insert(IdempotencyRecord { key: unesc(field(1)?), request: unesc(field(2)?), result });
+            }
+            other => return Err(invalid(&format!("line {}: unknown record {}", n, other))),
+        }
+    }
+
+    for (acct, version) in bank.accounts.iter_mut().zip(versions) {
+        acct.version = version;
+    }
+    bank.resume_sequence(next_sequence);
+    Ok(bank)
+}
+
+/// Build the Forex catalog from the `forex_base`, `currency`, `basket`,
+/// `fee_tier`, and `spread` records, then restore the `rate_history` and
+/// replay the `conversion` log.
+fn decode_forex(records: &[Record]) -> io::Result<Forex> {
+    let base_code = match records.iter().find(|r| r.tag() == "forex_base") {
+        Some(r) => unesc(r.field(1)?),
+        None => return Err(invalid("missing forex_base record")),
+    };
+    let currencies: Vec<&Record> = records.iter().filter(|r| r.tag() == "currency").collect();
+
+    let mut base_name = base_code.clone();
+    for r in &currencies {
+        if unesc(r.field(1)?) == base_code {
+            base_name = unesc(r.field(2)?);
+        }
+    }
+    let mut forex = Forex::builder().set_base_currency(&base_code, &base_name);
+    for r in currencies {
+        let code = unesc(r.field(1)?);
+        forex = forex
+            .create_currency(&code, &unesc(r.field(2)?), num(r.field(3)?)?)
+            .set_decimals(&code, dp(r.field(4)?)?)
+            .set_symbol(&code, &unesc(r.field(5)?))
+            .set_updated(&code, opt_timestamp(r.field(6)?)?)
+            .set_denominations(&code, r.field(7)?.split(',').filter(|d| !d.is_empty()).map(num).collect::<io::Result<_>>()?)
+            .set_cash_rate(&code, opt_num(r.field(8)?)?)
+            .set_rate_source(&code, RateSource::parse(&unesc(r.field(9)?)))
+            .set_rate_decimals(&code, dp(r.field(10)?)?);
+    }
+    for r in records.iter().filter(|r| r.tag() == "basket") {
+        let mut components = Vec::new();
+        for part in unesc(r.field(2)?).split(',') {
+            let (code, quantity) = part
+                .split_once(':')
+                .ok_or_else(|| invalid(&format!("line {}: invalid basket component {}", r.line, part)))?;
+            components.push((code.to_string(), num(quantity)?));
+        }
+        forex = forex.create_basket(&unesc(r.field(1)?), components);
+    }
+    let tiers = records
+        .iter()
+        .filter(|r| r.tag() == "fee_tier")
+        .map(|r| Ok(FeeTier { from: num(r.field(1)?)?, rate: num(r.field(2)?)? }))
+        .collect::<io::Result<Vec<_>>>()?;
+    let fees = FeeSchedule::new(tiers).map_err(|e| invalid(&e.to_string()))?;
+    let mut forex = forex.set_fee_schedule(fees);
+    for r in records.iter().filter(|r| r.tag() == "spread") {
+        forex = forex.set_pair_spread(&unesc(r.field(1)?), &unesc(r.field(2)?), num(r.field(3)?)?);
+    }
+    let mut forex = forex.build();
+    for r in records.iter().filter(|r| r.tag() == "rate_history") {
+        let code = unesc(r.field(1)?);
+        for close in r.field(2)?.split(',').filter(|c| !c.is_empty()) {
+            let (date, rate, carried) = match close.split(':').collect::<Vec<_>>()[..] {
+                [date, rate] => Date::parse(date).map(|date| (date, rate, false)),
+                [date, rate, "carried"] => Date::parse(date).map(|date| (date, rate, true)),
+                _ => None,
+            }
+            .ok_or_else(|| invalid(&format!("line {}: invalid closing rate {}", r.line, close)))?;
+            match carried {
+                true => forex.record_carried_close(&code, date, num(rate)?),
+                false => forex.record_close(&code, date, num(rate)?),
+            }
+        }
+    }
+    for r in records.iter().filter(|r| r.tag() == "conversion") {
+        let timestamp = opt_timestamp(r.field(1)?)?.ok_or_else(|| invalid(&format!("line {}: missing timestamp", r.line)))?;
+        let out_currency = unesc(r.field(6)?);
+        forex.record_conversion(ConversionRecord {
+            receipt: int(r.field(10)?)?,
+            timestamp,
+            account: Some(unesc(r.field(2)?)).filter(|a| !a.is_empty()),
+            amount_in: Money::new(num(r.field(3)?)?, &unesc(r.field(4)?)),
+            amount_out: Money::new(num(r.field(5)?)?, &out_currency),
+            rate: num(r.field(7)?)?,
+            fee: Money::new(num(r.field(8)?)?, &out_currency),
+            volume: num(r.field(9)?)?,
+            teller: Some(unesc(r.field(11)?)).filter(|t| !t.is_empty()),
+            customer: Some(unesc(r.field(12)?)).filter(|c| !c.is_empty()),
+        });
+    }
+    Ok(forex)
+}
+
+/// Write a snapshot of `bank` to `path`. A read-only bank (see
+/// `Bank::set_read_only`) is refused with `PermissionDenied`.
+pub fn save(bank: &Bank, path: impl AsRef<Path>) -> io::Result<()> {
+    if bank.is_read_only() {
+        return Err(io::Error::new(ErrorKind::PermissionDenied, "the bank is open read-only"));
+    }
+    fs::write(path, encode(bank))
+}
+
+/// Load a bank from a snapshot file at `path`.
+pub fn load(path: impl AsRef<Path>) -> io::Result<Bank> {
+    decode(&fs::read_to_string(path)?)
+}
+
+fn invalid(msg: &str) -> io::Error {
+    io::Error::new(ErrorKind::InvalidData, msg.to_string())
+}
+
+fn num(s: &str) -> io::Result<Decimal> {
+    s.parse().map_err(|_| invalid(&format!("invalid number {}", s)))
+}
+
+fn opt_num(s: &str) -> io::Result<Option<Decimal>> {
+    if s.is_empty() {
+        return Ok(None);
+    }
+    num(s).map(Some)
+}
+
+fn int(s: &str) -> io::Result<usize> {
+    s.parse().map_err(|_| invalid(&format!("invalid integer {}", s)))
+}
+
+fn sequence(s: &str) -> io::Result<u64> {
+    s.parse().map_err(|_| invalid(&format!("invalid sequence number {}", s)))
+}
+
+fn year_basis(s: &str) -> io::Result<YearBasis> {
+    YearBasis::parse(s).ok_or_else(|| invalid(&format!("unknown year basis {}", s)))
+}
+
+fn interest_method(s: &str) -> io::Result<InterestMethod> {
+    InterestMethod::parse(s).ok_or_else(|| invalid(&format!("unknown interest method {}", s)))
+}
+
+fn account_version(s: &str) -> io::Result<u64> {
+    s.parse().map_err(|_| invalid(&format!("invalid account version {}", s)))
+}
+
+fn count(s: &str) -> io::Result<u32> {
+    s.parse().map_err(|_| invalid(&format!("invalid count {}", s)))
+}
+
+fn opt_timestamp(s: &str) -> io::Result<Option<i64>> {
+    if s.is_empty() {
+        return Ok(None);
+    }
+    s.parse().map(Some).map_err(|_| invalid(&format!("invalid timestamp {}", s)))
+}
+
+fn dp(s: &str) -> io::Result<u32> {
+    s.parse().ok().filter(|&dp| dp <= SCALE).ok_or_else(|| invalid(&format!("invalid decimal places {} (0 to {})", s, SCALE)))
+}
+
+fn esc(s: &str) -> String {
+    s.replace('\\', "\\\\").replace('\t', "\\t").replace('\n', "\\n")
+}
+
+fn unesc(s: &str) -> String {
+    let mut out = String::with_capacity(s.len());
+    let mut chars = s.chars();
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            out.push(c);
+            continue;
+        }
+        match chars.next() {
+            Some('t') => out.push('\t'),
+            Some('n') => out.push('\n'),
+            Some(other) => out.push(other),
+            None => out.push('\\'),
+        }
+    }
+    out
+}
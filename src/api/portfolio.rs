@@ -0,0 +1,44 @@
+use std::fmt;
+
+use crate::api::date::Date;
+use crate::api::decimal::Decimal;
+use crate::api::money::Money;
+
+/// Something of value held through an account.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Asset {
+    /// The account's balance.
+    Cash,
+    /// An open FX forward, by ID, worth its mark-to-market.
+    Forward(usize),
+}
+
+impl fmt::Display for Asset {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Asset::Cash => write!(f, "cash"),
+            Asset::Forward(id) => write!(f, "forward {}", id),
+        }
+    }
+}
+
+/// One line of a `Portfolio`: `amount` in the currency it is held in,
+/// `rate` in units of the base currency per unit of it, and `value` in the
+/// base currency, rounded to its minor unit.
+#[derive(Debug, Clone)]
+pub struct Holding {
+    pub asset: Asset,
+    pub amount: Money,
+    pub rate: Decimal,
+    pub value: Money,
+}
+
+/// What an account's holdings were worth in the base currency on `as_of`,
+/// asset by asset, with their `total`.
+#[derive(Debug, Clone)]
+pub struct Portfolio {
+    pub account: String,
+    pub as_of: Date,
+    pub holdings: Vec<Holding>,
+    pub total: Money,
+}
@@ -0,0 +1,56 @@
+use crate::api::decimal::Decimal;
+use crate::api::money::Money;
+
+/// What a foreign-currency balance cost, in the bank's base currency, under
+/// the average-cost method. Money coming in adds its base-currency value at
+/// the rate of the day to `cost_basis`; money going out releases the same
+/// share of the cost basis as of the balance it takes, and the difference
+/// between its value at the day's rate and the cost released is added to
+/// `realized`. Both are kept unrounded.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct CurrencyPosition {
+    pub cost_basis: Decimal,
+    pub realized: Decimal,
+}
+
+impl CurrencyPosition {
+    /// Add currency worth `base_value` to the position. Returns `None`,
+    /// changing nothing, on overflow.
+    pub fn acquire(&mut self, base_value: Decimal) -> Option<()> {
+        self.cost_basis = self.cost_basis.checked_add(base_value)?;
+        Some(())
+    }
+
+    /// Take `quantity` out of a balance of `held` (before the withdrawal),
+    /// worth `base_value` at today's rate, and realize the gain or loss.
+    /// Returns `None`, changing nothing, on overflow.
+    pub fn dispose(&mut self, quantity: Decimal, held: Decimal, base_value: Decimal) -> Option<()> {
+        let released = if quantity >= held {
+            self.cost_basis
+        } else {
+            self.cost_basis.checked_mul(quantity)?.checked_div(held)?
+        };
+        self.realized = self.realized.checked_add(base_value.checked_sub(released)?)?;
+        self.cost_basis -= released;
+        Some(())
+    }
+
+    /// Gain (positive) or loss from holding the position at `market_value`.
+    pub fn unrealized(&self, market_value: Decimal) -> Decimal {
+        market_value - self.cost_basis
+    }
+}
+
+/// FX profit and loss of one foreign-currency account. `spot` is units of
+/// the base currency per unit of the account's; the other amounts are in
+/// the base currency, rounded to its minor unit.
+#[derive(Debug, Clone)]
+pub struct PositionReport {
+    pub account: String,
+    pub balance: Money,
+    pub spot: Decimal,
+    pub market_value: Money,
+    pub cost_basis: Money,
+    pub unrealized: Money,
+    pub realized: Money,
+}
@@ -0,0 +1,81 @@
+use crate::api::date::Date;
+use crate::api::decimal::Decimal;
+
+/// Closes `Forex::trend` averages a pair's rate over.
+pub const TREND_DAYS: usize = 7;
+
+/// A currency's closing rates over a period, from `Forex::stats`: the
+/// first and last (`open` and `close`), the highest and lowest, and their
+/// mean (`average`, rounded to the currency's `rate_decimals`), over the
+/// `days` days with a close from `start` to `end`, the first and last of
+/// them. Only daily closes are kept, so `high` and `low` are the highest
+/// and lowest closes, not the extremes within a day.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RateStats {
+    pub code: String,
+    pub start: Date,
+    pub end: Date,
+    pub days: usize,
+    pub open: Decimal,
+    pub close: Decimal,
+    pub high: Decimal,
+    pub low: Decimal,
+    pub average: Decimal,
+}
+
+impl RateStats {
+    /// How far the rate moved over the period, as a fraction of `open`;
+    /// zero if `open` is.
+    pub fn change(&self) -> Decimal {
+        change(self.open, self.close).unwrap_or(Decimal::ZERO)
+    }
+
+    /// The statistics of `series`, closing rates of `code` oldest first,
+    /// with the average rounded to `dp`; `None` if it is empty.
+    pub(crate) fn of_series(code: &str, series: &[(Date, Decimal)], dp: u32) -> Option<Self> {
+        let (&(start, open), &(end, close)) = (series.first()?, series.last()?);
+        let rates = series.iter().map(|(_, rate)| *rate);
+        Some(Self {
+            code: code.to_string(),
+            start,
+            end,
+            days: series.len(),
+            open,
+            close,
+            high: rates.clone().fold(open, Decimal::max),
+            low: rates.clone().fold(open, Decimal::min),
+            average: (rates.sum::<Decimal>() / Decimal::from(series.len())).round_dp(dp),
+        })
+    }
+}
+
+/// How a pair's rate today stands against its recent closes, from
+/// `Forex::trend`: `rate` is units of `to` per unit of `from` now,
+/// `previous` the pair's last close before today, and `average` the mean of
+/// its last `TREND_DAYS` closes (today's included, once a rate has been set
+/// today). Either is `None` without the closes it needs.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RateTrend {
+    pub from: String,
+    pub to: String,
+    pub rate: Decimal,
+    pub previous: Option<(Date, Decimal)>,
+    pub average: Option<Decimal>,
+}
+
+impl RateTrend {
+    /// How far `rate` is from the previous close, as a fraction of it.
+    pub fn vs_previous(&self) -> Option<Decimal> {
+        self.previous.and_then(|(_, previous)| change(previous, self.rate))
+    }
+
+    /// How far `rate` is from the average, as a fraction of it.
+    pub fn vs_average(&self) -> Option<Decimal> {
+        self.average.and_then(|average| change(average, self.rate))
+    }
+}
+
+/// The move from `from` to `to` as a fraction of `from`; `None` from zero.
+fn change(from: Decimal, to: Decimal) -> Option<Decimal> {
+    (!from.is_zero()).then(|| (to - from) / from)
+}
@@ -0,0 +1,192 @@
+use crate::api::account::DAY_COUNT_BASIS;
+use crate::api::date::{is_leap_year, Date};
+use crate::api::decimal::Decimal;
+
+/// Months per year, for `monthly`.
+pub const MONTHS_PER_YEAR: u32 = 12;
+
+/// How an annual interest rate is quoted. All rates are fractions
+/// (0.05 for 5%).
+/// - `Simple`: earned once over the year; the rate is its own yield.
+/// - `Compounded(n)`: a nominal rate credited n times a year at rate / n
+///   a period, e.g. `Compounded(12)` for monthly or `Compounded(365)` for
+///   the daily compounding of `Account::get_interest_forecast`.
+/// - `Continuous`: compounded continuously, growing by e^rate a year.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Convention {
+    Simple,
+    Compounded(u32),
+    Continuous,
+}
+
+/// The rate for one of `periods_per_year` equal periods of the nominal
+/// annual rate `annual`: annual / periods_per_year. Panics if
+/// `periods_per_year` is zero.
+pub fn periodic(annual: Decimal, periods_per_year: u32) -> Decimal {
+    annual / Decimal::from(periods_per_year)
+}
+
+/// The daily rate of a nominal annual rate on the `DAY_COUNT_BASIS`, as
+/// interest accrues throughout the crate.
+pub fn daily(annual: Decimal) -> Decimal {
+    periodic(annual, DAY_COUNT_BASIS)
+}
+
+/// The daily rate of a nominal annual rate on `date`, a day of a year of
+/// `basis.days(date.year)` days, as account interest accrues.
+pub fn daily_on(annual: Decimal, basis: YearBasis, date: Date) -> Decimal {
+    periodic(annual, basis.days(date.year))
+}
+
+/// The days a year counts in an account's daily interest formula, daily
+/// interest being balance × annual rate / days.
+/// - `Fixed365`: 365, leap years included (Actual/365 Fixed), the
+///   `DAY_COUNT_BASIS` and the default.
+/// - `Actual`: 366 in leap years and 365 otherwise (Actual/Actual), so a
+///   leap year's days each earn a little less.
+/// - `Fixed360`: 360 (Actual/360), as money markets count; a full year of
+///   365 days earns 365/360 of the annual rate.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum YearBasis {
+    #[default]
+    Fixed365,
+    Actual,
+    Fixed360,
+}
+
+impl YearBasis {
+    /// Parse "365", "actual" (or "366"), or "360" (any case).
+    pub fn parse(s: &str) -> Option<Self> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "365" => Some(YearBasis::Fixed365),
+            "actual" | "366" => Some(YearBasis::Actual),
+            "360" => Some(YearBasis::Fixed360),
+            _ => None,
+        }
+    }
+
+    /// Lowercase name, as accepted by `parse`.
+    pub fn name(&self) -> &'static str {
+        match self {
+            YearBasis::Fixed365 => "365",
+            YearBasis::Actual => "actual",
+            YearBasis::Fixed360 => "360",
+        }
+    }
+
+    /// The convention's usual label, e.g. "Actual/360".
+    pub fn label(&self) -> &'static str {
+        match self {
+            YearBasis::Fixed365 => "Actual/365 Fixed",
+            YearBasis::Actual => "Actual/Actual",
+            YearBasis::Fixed360 => "Actual/360",
+        }
+    }
+
+    /// Days in `year` on this basis.
+    pub fn days(&self, year: i32) -> u32 {
+        match self {
+            YearBasis::Fixed365 => DAY_COUNT_BASIS,
+            YearBasis::Actual if is_leap_year(year) => 366,
+            YearBasis::Actual => 365,
+            YearBasis::Fixed360 => 360,
+        }
+    }
+
+    /// The years `days` days from `start` span on this basis, each day
+    /// counting 1 / `days` of its own year: under `Actual` a day of 2028
+    /// is 1/366 of a year and a day of 2029 1/365.
+    pub fn years(&self, start: Date, days: usize) -> f64 {
+        let mut years = 0.0;
+        let (mut date, mut left) = (start, days as i64);
+        while left > 0 {
+            let in_year = left.min(Date::new(date.year + 1, 1, 1).map_or(left, |next| next.days() - date.days()));
+            years += in_year as f64 / f64::from(self.days(date.year));
+            date = date.add_days(in_year);
+            left -= in_year;
+        }
+        years
+    }
+}
+
+/// The monthly rate of a nominal annual rate.
+pub fn monthly(annual: Decimal) -> Decimal {
+    periodic(annual, MONTHS_PER_YEAR)
+}
+
+/// The effective annual yield of `rate` quoted under `convention`: how much
+/// one unit grows over a year, less the unit. Returns `None` if a
+/// compounding period's rate is −100% or below, `Compounded(0)`, or a
+/// yield out of range.
+pub fn effective(rate: Decimal, convention: Convention) -> Option<Decimal> {
+    match convention {
+        Convention::Simple => Some(rate),
+        Convention::Compounded(0) => None,
+        Convention::Compounded(n) => {
+            let growth = Decimal::ONE + periodic(rate, n);
+            if growth <= Decimal::ZERO {
+                return None;
+            }
+            Some(growth.checked_powu(u64::from(n))? - Decimal::ONE)
+        }
+        // e^rate − 1 takes a fractional power, so it goes through f64.
+        Convention::Continuous => Decimal::from_f64(rate.to_f64().exp_m1()),
+    }
+}
+
+/// The rate quoted under `convention` whose effective annual yield is
+/// `apy`; the inverse of `effective`. Roots and logarithms go through
+/// `f64`, so the result is good to about 12 significant digits. Returns
+/// `None` if `apy` is −100% or below (except for `Simple`), or for
+/// `Compounded(0)`.
+pub fn from_effective(apy: Decimal, convention: Convention) -> Option<Decimal> {
+    let log_growth = || (apy > -Decimal::ONE).then(|| apy.to_f64().ln_1p());
+    match convention {
+        Convention::Simple => Some(apy),
+        Convention::Compounded(0) => None,
+        Convention::Compounded(n) => {
+            let n = f64::from(n);
+            Decimal::from_f64(n * (log_growth()? / n).exp_m1())
+        }
+        Convention::Continuous => Decimal::from_f64(log_growth()?),
+    }
+}
+
+/// `rate` quoted under `from` restated under `to` with the same effective
+/// annual yield, e.g. 5% compounded monthly is about 4.99% compounded
+/// daily or 4.9896% continuously. Returns `None` where `effective` or
+/// `from_effective` would.
+pub fn convert(rate: Decimal, from: Convention, to: Convention) -> Option<Decimal> {
+    if from == to {
+        return Some(rate);
+    }
+    from_effective(effective(rate, from)?, to)
+}
+
+/// The effective annual yield of growing by the factor `growth` over
+/// `days` days, annualized over `DAY_COUNT_BASIS` days: growth^(365 / days)
+/// − 1. The power goes through `f64`. Returns `None` for zero days, a
+/// growth of zero or below, or a yield out of range.
+pub fn annualize(growth: Decimal, days: usize) -> Option<Decimal> {
+    annualize_years(growth, days as f64 / f64::from(DAY_COUNT_BASIS))
+}
+
+/// `annualize` over a horizon of `years` years, as `YearBasis::years`
+/// counts them: growth^(1 / years) − 1.
+pub fn annualize_years(growth: Decimal, years: f64) -> Option<Decimal> {
+    if years <= 0.0 || growth <= Decimal::ZERO {
+        return None;
+    }
+    Decimal::from_f64(growth.to_f64().powf(1.0 / years) - 1.0)
+}
+
+/// The factor one unit grows by over `days` days at the effective annual
+/// yield `apy`: (1 + apy)^(days / 365); the inverse of `annualize`. The
+/// power goes through `f64`. Returns `None` if `apy` is −100% or below or
+/// the factor is out of range.
+pub fn growth(apy: Decimal, days: usize) -> Option<Decimal> {
+    if apy <= -Decimal::ONE {
+        return None;
+    }
+    Decimal::from_f64((days as f64 / f64::from(DAY_COUNT_BASIS) * apy.to_f64().ln_1p()).exp())
+}
@@ -0,0 +1,105 @@
+use std::fmt;
+
+use crate::api::bank::EXCHANGE_RATE_DP;
+use crate::api::conversion_log::ConversionRecord;
+use crate::api::date::TimeZone;
+use crate::api::decimal::Decimal;
+use crate::api::format::{format_amount, Locale};
+use crate::api::money::Money;
+
+/// Characters across a printed `ExchangeReceipt`.
+pub const RECEIPT_WIDTH: usize = 40;
+
+/// The receipt for one executed conversion, built by
+/// `Bank::exchange_receipt` from its entry in the conversion log, so it
+/// can be printed again by `number` at any time. Amounts are as posted:
+/// `sold` was converted, `fee` kept, and `bought` paid out net of it, in
+/// the `decimals` places of their currencies and grouped per `locale`.
+/// `timestamp` is shown in `time_zone`.
+#[derive(Debug, Clone)]
+pub struct ExchangeReceipt {
+    pub number: usize,
+    pub bank: String,
+    /// When the conversion ran (Unix seconds, UTC).
+    pub timestamp: i64,
+    /// The initiating account; `None` for `Bank::settle_conversion`.
+    pub account: Option<String>,
+    pub sold: Money,
+    pub bought: Money,
+    /// Units of `bought`'s currency per unit of `sold`'s, shown to
+    /// `EXCHANGE_RATE_DP` places.
+    pub rate: Decimal,
+    pub fee: Money,
+    pub teller: Option<String>,
+    /// The customer whose session it ran in, if any.
+    pub customer: Option<String>,
+    /// Decimal places of `sold`'s and `bought`'s currencies.
+    pub decimals: (u32, u32),
+    pub locale: Locale,
+    pub time_zone: TimeZone,
+}
+
+impl ExchangeReceipt {
+    /// The receipt for `record`, with the bank's name. Amounts show two
+    /// decimal places in `Locale::EnPh` and times are in UTC until set.
+    pub fn new(bank: &str, record: &ConversionRecord) -> Self {
+        Self {
+            number: record.receipt,
+            bank: bank.to_string(),
+            timestamp: record.timestamp,
+            account: record.account.clone(),
+            sold: record.amount_in.clone(),
+            bought: record.amount_out.clone(),
+            rate: record.rate,
+            fee: record.fee.clone(),
+            teller: record.teller.clone(),
+            customer: record.customer.clone(),
+            decimals: (2, 2),
+            locale: Locale::EnPh,
+            time_zone: TimeZone::UTC,
+        }
+    }
+
+    /// The currency pair converted, e.g. "PHP/USD".
+    pub fn pair(&self) -> String {
+        format!("{}/{}", self.sold.currency, self.bought.currency)
+    }
+
+    /// What was converted before the fee: `bought` plus `fee`.
+    pub fn gross(&self) -> Money {
+        Money::new(self.bought.amount + self.fee.amount, &self.bought.currency)
+    }
+
+    /// `money` as the receipt shows it, e.g. "1,000.00 PHP".
+    fn format(&self, money: &Money, decimals: u32) -> String {
+        format!("{} {}", format_amount(money.amount, "", decimals, self.locale), money.currency)
+    }
+}
+
+impl fmt::Display for ExchangeReceipt {
+    /// The receipt as printed at the counter: the bank and title centered
+    /// between rules, then one labelled line per detail, `RECEIPT_WIDTH`
+    /// characters across.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let rule = "=".repeat(RECEIPT_WIDTH);
+        let (sold_dp, bought_dp) = self.decimals;
+        writeln!(f, "{}", rule)?;
+        for title in [self.bank.as_str(), "EXCHANGE RECEIPT"] {
+            writeln!(f, "{}", format!("{:^width$}", title, width = RECEIPT_WIDTH).trim_end())?;
+        }
+        writeln!(f, "{}", rule)?;
+        let mut row = |label: &str, value: &str| writeln!(f, "{:<14}{:>width$}", label, value, width = RECEIPT_WIDTH - 14);
+        row("Receipt no.", &format!("{:06}", self.number))?;
+        row("Date", &self.time_zone.format_timestamp(self.timestamp))?;
+        row("Account", self.account.as_deref().unwrap_or("-"))?;
+        row("Pair", &self.pair())?;
+        row("Sold", &self.format(&self.sold, sold_dp))?;
+        row("Rate", &format!("1 {} = {} {}", self.sold.currency, self.rate.round_dp(EXCHANGE_RATE_DP), self.bought.currency))?;
+        row("Converted", &self.format(&self.gross(), bought_dp))?;
+        row("Fee", &self.format(&self.fee, bought_dp))?;
+        row("Received", &self.format(&self.bought, bought_dp))?;
+        row("Teller", self.teller.as_deref().unwrap_or("-"))?;
+        row("Session", self.customer.as_deref().unwrap_or("staff"))?;
+        write!(f, "{}", rule)
+    }
+}
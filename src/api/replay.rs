@@ -0,0 +1,173 @@
+use std::io::{self, BufRead};
+
+use crate::api::bank::Bank;
+use crate::api::date::Date;
+use crate::api::decimal::Decimal;
+use crate::api::forex::RateSource;
+use crate::api::import::read_record;
+use crate::api::market::{MarketError, MarketStep, RateMove};
+
+/// Historical exchange rates, one row per day, read with `read_csv`.
+/// `days[i].1[j]` is the rate of `codes[j]` on `days[i].0` in units of the
+/// base currency, or `None` when it was not quoted that day. Days are in
+/// increasing order.
+#[derive(Debug, Clone, Default)]
+pub struct RateHistory {
+    pub codes: Vec<String>,
+    pub days: Vec<(Date, Vec<Option<Decimal>>)>,
+}
+
+impl RateHistory {
+    /// Read rates from CSV with a `date` column (`YYYY-MM-DD`) and one
+    /// column per currency code, in any order, e.g.
+    /// `date,USD,EUR` / `2024-01-02,55.9,61.4`. Blank cells are days a
+    /// currency was not quoted; blank lines are skipped.
+    ///
+    /// Fails with `InvalidData`, naming the 1-based data row, if a date is
+    /// invalid or not after the one before, or a rate is not positive; and
+    /// if there is no `date` column, no currency, or no rows.
+    pub fn read_csv(reader: impl BufRead, delimiter: char) -> io::Result<Self> {
+        let mut lines = reader.lines();
+        let header = read_record(&mut lines, delimiter)?.ok_or_else(|| invalid("the rate file is empty"))?;
+        let date_column = header
+            .iter()
+            .position(|h| h.trim().eq_ignore_ascii_case("date"))
+            .ok_or_else(|| invalid("the rate file has no date column"))?;
+        let columns: Vec<(usize, String)> = header
+            .iter()
+            .enumerate()
+            .filter(|(i, h)| *i != date_column && !h.trim().is_empty())
+            .map(|(i, h)| (i, h.trim().to_uppercase()))
+            .collect();
+        if columns.is_empty() {
+            return Err(invalid("the rate file has no currency columns"));
+        }
+        let mut history = RateHistory { codes: columns.iter().map(|(_, code)| code.clone()).collect(), days: Vec::new() };
+        let mut row = 0;
+        while let Some(record) = read_record(&mut lines, delimiter)? {
+            if record.iter().all(|f| f.trim().is_empty()) {
+                continue;
+            }
+            row += 1;
+            let cell = |i: usize| record.get(i).map_or("", |s| s.trim());
+            let date = Date::parse(cell(date_column)).ok_or_else(|| invalid(&format!("row {}: invalid date {}", row, cell(date_column))))?;
+            if history.days.last().is_some_and(|(last, _)| *last >= date) {
+                return Err(invalid(&format!("row {}: {} is not after the date before it", row, date)));
+            }
+            let rates = columns
+                .iter()
+                .map(|(i, code)| match cell(*i) {
+                    "" => Ok(None),
+                    raw => match raw.parse::<Decimal>() {
+                        Ok(rate) if rate > Decimal::ZERO => Ok(Some(rate)),
+                        _ => Err(invalid(&format!("row {}: invalid {} rate {}", row, code, raw))),
+                    },
+                })
+                .collect::<io::Result<_>>()?;
+            history.days.push((date, rates));
+        }
+        if history.days.is_empty() {
+            return Err(invalid("the rate file has no rows"));
+        }
+        Ok(history)
+    }
+
+    /// The days from `start` through `end`, either bound open when `None`.
+    pub fn between(&self, start: Option<Date>, end: Option<Date>) -> RateHistory {
+        let days = self
+            .days
+            .iter()
+            .filter(|(date, _)| start.is_none_or(|s| *date >= s) && end.is_none_or(|e| *date <= e))
+            .cloned()
+            .collect();
+        RateHistory { codes: self.codes.clone(), days }
+    }
+
+    /// The rate of `to` in units of `from` (as `DcaSimulation` takes them)
+    /// on each day from the first with a quote for both, carrying each
+    /// currency's last quote forward over days it was not quoted. `base` is
+    /// always 1. Empty if either currency never appears.
+    pub fn cross_rates(&self, from: &str, to: &str, base: &str) -> Vec<(Date, Decimal)> {
+        let column = |code: &str| self.codes.iter().position(|c| c == code);
+        let (from_column, to_column) = (column(from), column(to));
+        let (mut from_rate, mut to_rate) = ((from == base).then_some(Decimal::ONE), (to == base).then_some(Decimal::ONE));
+        let mut series = Vec::new();
+        for (date, rates) in &self.days {
+            if let Some(rate) = from_column.and_then(|i| rates[i]) {
+                from_rate = Some(rate);
+            }
+            if let Some(rate) = to_column.and_then(|i| rates[i]) {
+                to_rate = Some(rate);
+            }
+            if let (Some(f), Some(t)) = (from_rate, to_rate)
+                && let Some(cross) = t.checked_div(f)
+            {
+                series.push((*date, cross));
+            }
+        }
+        series
+    }
+}
+
+/// Feeds a `RateHistory` into a bank one day at a time, for backtesting
+/// limit orders, forwards, and standing orders against real rates. Each
+/// step stands the bank's simulation clock (see `Bank::set_simulated_time`)
+/// at the day's midnight UTC, records the day's quoted rates with
+/// `Bank::set_rate_from`, attributed to `RateSource::Replay` (filling limit
+/// orders), then runs `Bank::end_of_day` for that date.
+///
+/// Postings are stamped with the historical dates, so replay into a bank
+/// whose history does not run past the first day.
+#[derive(Debug, Clone)]
+pub struct RateReplay {
+    history: RateHistory,
+    next: usize,
+}
+
+impl RateReplay {
+    pub fn new(history: RateHistory) -> Self {
+        Self { history, next: 0 }
+    }
+
+    /// Days not yet replayed.
+    pub fn remaining(&self) -> usize {
+        self.history.days.len() - self.next
+    }
+
+    /// Replay the next day, or `None` once every day has been. Fails
+    /// without changing anything if a column is the base currency, a
+    /// basket, or not in the catalog; a rate that cannot be recorded ends
+    /// the day early, leaving the rates already recorded in place.
+    pub fn step(&mut self, bank: &mut Bank) -> Option<Result<MarketStep, MarketError>> {
+        let (date, rates) = self.history.days.get(self.next)?.clone();
+        if let Some(code) = self.history.codes.iter().find(|code| {
+            bank.forex.currency(code).is_none() || bank.forex.base_currency().code == **code || bank.forex.basket(code).is_some()
+        }) {
+            return Some(Err(MarketError::NotSimulated(code.clone())));
+        }
+        self.next += 1;
+        bank.set_simulated_time(date.timestamp());
+        let mut moves = Vec::new();
+        let mut fills = Vec::new();
+        for (code, rate) in self.history.codes.iter().zip(rates) {
+            let Some(new) = rate else { continue };
+            let old = bank.forex.get_rate(code).copied().unwrap_or(new);
+            match bank.set_rate_from(code, new, RateSource::Replay) {
+                Ok(filled) => fills.extend(filled),
+                Err(e) => return Some(Err(e.into())),
+            }
+            moves.push(RateMove { code: code.clone(), old, new });
+        }
+        let end_of_day = bank.end_of_day(date);
+        Some(Ok(MarketStep { date, moves, fills, end_of_day }))
+    }
+
+    /// Replay every remaining day, stopping at the first error.
+    pub fn run(&mut self, bank: &mut Bank) -> Result<Vec<MarketStep>, MarketError> {
+        std::iter::from_fn(|| self.step(bank)).collect()
+    }
+}
+
+fn invalid(reason: &str) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, reason.to_string())
+}
@@ -0,0 +1,19 @@
+/// Operator roles for front-ends driving the bank.
+/// - Teller: registers customers/accounts and transacts on accounts.
+/// - Admin: everything a teller can do, plus changing exchange rates,
+///   interest rates, fees, and reviewing compliance flags.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Role {
+    Teller,
+    Admin,
+}
+
+impl Role {
+    /// Returns true if this role may perform operations restricted to `required`.
+    pub fn allows(self, required: Role) -> bool {
+        match required {
+            Role::Teller => true,
+            Role::Admin => self == Role::Admin,
+        }
+    }
+}
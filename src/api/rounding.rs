@@ -0,0 +1,22 @@
+use crate::api::decimal::{Decimal, RoundingStrategy};
+use crate::api::money::Money;
+
+/// Rounding applied when amounts become real ledger entries: deposits,
+/// withdrawals, interest postings, and settled conversions. Forecasts and
+/// quotes stay unrounded.
+/// `strategy` decides how midpoints/remainders are resolved (e.g. banker's
+/// rounding); the number of digits kept is the currency's minor unit.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct RoundingPolicy {
+    pub strategy: RoundingStrategy,
+}
+
+impl RoundingPolicy {
+    /// Round `amount` to `dp` fractional digits and return
+    /// `(rounded, residue)` where `residue = amount - rounded` is the
+    /// fraction not booked.
+    pub fn apply(&self, amount: &Money, dp: u32) -> (Money, Decimal) {
+        let rounded = amount.amount.round_dp_with_strategy(dp, self.strategy);
+        (Money::new(rounded, &amount.currency), amount.amount - rounded)
+    }
+}
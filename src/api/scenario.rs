@@ -0,0 +1,286 @@
+use std::fmt;
+
+use crate::api::account::DAY_COUNT_BASIS;
+use crate::api::date::Date;
+use crate::api::decimal::Decimal;
+use crate::api::loan::PaymentFrequency;
+use crate::api::money::Money;
+use crate::api::rates::{self, Convention, YearBasis};
+
+/// Errors raised when a scenario comparison is refused.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ScenarioError {
+    /// No scenarios were given.
+    Empty,
+    /// Two scenarios share this name.
+    Duplicate(String),
+    /// The named scenario has a negative interest rate.
+    NegativeRate(String),
+    /// The named scenario contributes zero or less each period.
+    NonPositiveContribution(String),
+    /// The horizon is zero days.
+    InvalidHorizon,
+    /// A balance is too large to represent.
+    Overflow,
+}
+
+impl fmt::Display for ScenarioError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ScenarioError::Empty => write!(f, "no scenarios to compare"),
+            ScenarioError::Duplicate(name) => write!(f, "more than one scenario is named {}", name),
+            ScenarioError::NegativeRate(name) => write!(f, "scenario {} has a negative interest rate", name),
+            ScenarioError::NonPositiveContribution(name) => write!(f, "scenario {} must contribute more than zero", name),
+            ScenarioError::InvalidHorizon => write!(f, "the horizon must be at least one day"),
+            ScenarioError::Overflow => write!(f, "scenario balances are out of range"),
+        }
+    }
+}
+
+impl std::error::Error for ScenarioError {}
+
+/// How accrued interest joins the balance that earns more interest.
+/// Interest accrues daily at the annual rate / the days in the year (an
+/// account's `YearBasis` in `compare`, `DAY_COUNT_BASIS` in `calculate`) in
+/// every mode; the modes differ in when it is credited.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Compounding {
+    /// Credited every day, as `Account::get_interest_forecast` does.
+    Daily,
+    /// Credited at the end of each period of the frequency.
+    Periodic(PaymentFrequency),
+    /// Never credited: only the opening balance and contributions earn.
+    Simple,
+}
+
+impl Compounding {
+    /// Parse "daily", "simple", or a `PaymentFrequency` name (any case).
+    pub fn parse(s: &str) -> Option<Self> {
+        match s.trim().to_lowercase().as_str() {
+            "daily" => Some(Compounding::Daily),
+            "simple" => Some(Compounding::Simple),
+            other => PaymentFrequency::parse(other).map(Compounding::Periodic),
+        }
+    }
+
+    /// Lowercase name, as accepted by `parse`.
+    pub fn name(&self) -> &'static str {
+        match self {
+            Compounding::Daily => "daily",
+            Compounding::Periodic(frequency) => frequency.name(),
+            Compounding::Simple => "simple",
+        }
+    }
+
+    /// Times a year interest is credited: `DAY_COUNT_BASIS` for `Daily`,
+    /// the frequency's periods for `Periodic`, and `None` for `Simple`.
+    pub fn periods_per_year(&self) -> Option<u32> {
+        match self {
+            Compounding::Daily => Some(DAY_COUNT_BASIS),
+            Compounding::Periodic(frequency) => Some(frequency.periods_per_year()),
+            Compounding::Simple => None,
+        }
+    }
+
+    /// How a nominal rate credited this often is quoted.
+    pub fn convention(&self) -> Convention {
+        self.periods_per_year().map_or(Convention::Simple, Convention::Compounded)
+    }
+
+    /// The effective annual yield (APY) of the nominal annual rate
+    /// `nominal` credited this often, both as fractions: (1 + nominal / n)^n
+    /// − 1 for n periods a year, so 5% compounded monthly yields about
+    /// 0.051162. `Simple` earns the nominal rate. Returns `None` if a
+    /// period's rate is −100% or below, or the yield is out of range.
+    pub fn effective_annual_yield(&self, nominal: Decimal) -> Option<Decimal> {
+        rates::effective(nominal, self.convention())
+    }
+
+    /// The nominal annual rate that, credited this often, yields `apy`; the
+    /// inverse of `effective_annual_yield` (see `rates::from_effective`).
+    /// Returns `None` if `apy` is −100% or below.
+    pub fn nominal_rate(&self, apy: Decimal) -> Option<Decimal> {
+        rates::from_effective(apy, self.convention())
+    }
+}
+
+/// A deposit of `amount` at the end of every period of `frequency`.
+#[derive(Debug, Clone, Copy)]
+pub struct Contribution {
+    pub amount: Decimal,
+    pub frequency: PaymentFrequency,
+}
+
+/// One set of what-if assumptions for growing a balance.
+#[derive(Debug, Clone)]
+pub struct Scenario {
+    pub name: String,
+    /// Annual rate as a fraction, e.g. 0.05.
+    pub annual_interest: Decimal,
+    pub compounding: Compounding,
+    pub contribution: Option<Contribution>,
+}
+
+impl Scenario {
+    /// A scenario at `annual_interest`, compounded daily, with no
+    /// contributions.
+    pub fn new(name: &str, annual_interest: Decimal) -> Self {
+        Self { name: name.to_string(), annual_interest, compounding: Compounding::Daily, contribution: None }
+    }
+
+    pub fn with_compounding(mut self, compounding: Compounding) -> Self {
+        self.compounding = compounding;
+        self
+    }
+
+    pub fn with_contribution(mut self, amount: Decimal, frequency: PaymentFrequency) -> Self {
+        self.contribution = Some(Contribution { amount, frequency });
+        self
+    }
+}
+
+/// Where a scenario stands at the end of a day. `interest` and
+/// `contributed` are running totals since day 0; interest accrued but not
+/// yet credited counts in both `interest` and `balance`.
+#[derive(Debug, Clone)]
+pub struct ScenarioPoint {
+    pub day: usize,
+    pub balance: Money,
+    pub interest: Money,
+    pub contributed: Money,
+}
+
+/// One scenario's day-by-day series, `points[i]` being day `i + 1`.
+#[derive(Debug, Clone)]
+pub struct ScenarioSeries {
+    pub scenario: Scenario,
+    pub points: Vec<ScenarioPoint>,
+}
+
+impl ScenarioSeries {
+    /// The point at the end of the horizon.
+    pub fn last(&self) -> &ScenarioPoint {
+        self.points.last().expect("a series covers at least one day")
+    }
+
+    /// Up to `count` evenly spaced days ending on the last, as
+    /// `ScenarioComparison::checkpoints`.
+    pub fn checkpoints(&self, count: usize) -> Vec<usize> {
+        checkpoints(self.points.len(), count)
+    }
+}
+
+/// Every scenario over the same horizon, aligned day by day: each series
+/// has one point per day 1..=`horizon`, so `series[s].points[i]` for all
+/// `s` are the same day.
+#[derive(Debug, Clone)]
+pub struct ScenarioComparison {
+    pub opening: Money,
+    pub horizon: usize,
+    pub series: Vec<ScenarioSeries>,
+}
+
+impl ScenarioComparison {
+    /// Up to `count` evenly spaced days ending on the horizon, for tables
+    /// and charts too narrow for every day.
+    pub fn checkpoints(&self, count: usize) -> Vec<usize> {
+        checkpoints(self.horizon, count)
+    }
+}
+
+fn checkpoints(horizon: usize, count: usize) -> Vec<usize> {
+    let count = count.clamp(1, horizon);
+    (1..=count).map(|k| horizon * k / count).collect()
+}
+
+/// Length of one period of `frequency` in days on the `DAY_COUNT_BASIS`
+/// calendar: 7, 14, 30, 91, or 365.
+fn period_days(frequency: PaymentFrequency) -> usize {
+    (DAY_COUNT_BASIS / frequency.periods_per_year()) as usize
+}
+
+/// Grow `opening`, an account's balance, under each scenario for `horizon`
+/// days, day 1 being `start`, with interest accruing on the account's
+/// `basis`.
+pub fn compare(opening: &Money, scenarios: &[Scenario], horizon: usize, start: Date, basis: YearBasis) -> Result<ScenarioComparison, ScenarioError> {
+    if scenarios.is_empty() {
+        return Err(ScenarioError::Empty);
+    }
+    if horizon == 0 {
+        return Err(ScenarioError::InvalidHorizon);
+    }
+    for (i, s) in scenarios.iter().enumerate() {
+        if scenarios[..i].iter().any(|other| other.name == s.name) {
+            return Err(ScenarioError::Duplicate(s.name.clone()));
+        }
+        if s.annual_interest < Decimal::ZERO {
+            return Err(ScenarioError::NegativeRate(s.name.clone()));
+        }
+        if let Some(c) = s.contribution
+            && c.amount <= Decimal::ZERO
+        {
+            return Err(ScenarioError::NonPositiveContribution(s.name.clone()));
+        }
+    }
+    let series = scenarios
+        .iter()
+        .map(|s| {
+            let daily_rate = |day: usize| rates::daily_on(s.annual_interest, basis, start.add_days(day as i64 - 1));
+            Ok(ScenarioSeries { scenario: s.clone(), points: simulate(opening, s, horizon, daily_rate).ok_or(ScenarioError::Overflow)? })
+        })
+        .collect::<Result<_, ScenarioError>>()?;
+    Ok(ScenarioComparison { opening: opening.clone(), horizon, series })
+}
+
+/// Grow `principal` at `annual_interest` under `compounding` for `horizon`
+/// days, with no account or contributions: the interest calculator. Unlike
+/// `compare`, the rate may be negative, shrinking the principal. The
+/// series' scenario is named after the compounding mode.
+pub fn calculate(principal: &Money, annual_interest: Decimal, compounding: Compounding, horizon: usize) -> Result<ScenarioSeries, ScenarioError> {
+    if horizon == 0 {
+        return Err(ScenarioError::InvalidHorizon);
+    }
+    let scenario = Scenario::new(compounding.name(), annual_interest).with_compounding(compounding);
+    let daily_rate = rates::daily(annual_interest);
+    let points = simulate(principal, &scenario, horizon, |_| daily_rate).ok_or(ScenarioError::Overflow)?;
+    Ok(ScenarioSeries { scenario, points })
+}
+
+/// `scenario`'s day-by-day series, or `None` on overflow. Each day the
+/// earning balance accrues interest at that day's `daily_rate`, due
+/// interest is credited, then any contribution is deposited.
+fn simulate(opening: &Money, scenario: &Scenario, horizon: usize, daily_rate: impl Fn(usize) -> Decimal) -> Option<Vec<ScenarioPoint>> {
+    let money = |amount: Decimal| Money::new(amount, &opening.currency);
+    let credit_every = match scenario.compounding {
+        Compounding::Daily => Some(1),
+        Compounding::Periodic(frequency) => Some(period_days(frequency)),
+        Compounding::Simple => None,
+    };
+    // `earning` is what interest accrues on; `accrued` is interest not yet
+    // credited to it.
+    let (mut earning, mut accrued) = (opening.amount, Decimal::ZERO);
+    let (mut interest, mut contributed) = (Decimal::ZERO, Decimal::ZERO);
+    (1..=horizon)
+        .map(|day| {
+            let today = earning.checked_mul(daily_rate(day))?;
+            accrued = accrued.checked_add(today)?;
+            interest = interest.checked_add(today)?;
+            if credit_every.is_some_and(|every| day % every == 0) {
+                earning = earning.checked_add(accrued)?;
+                accrued = Decimal::ZERO;
+            }
+            if let Some(c) = scenario.contribution
+                && day % period_days(c.frequency) == 0
+            {
+                earning = earning.checked_add(c.amount)?;
+                contributed = contributed.checked_add(c.amount)?;
+            }
+            Some(ScenarioPoint {
+                day,
+                balance: money(earning.checked_add(accrued)?),
+                interest: money(interest),
+                contributed: money(contributed),
+            })
+        })
+        .collect()
+}
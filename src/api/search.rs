@@ -0,0 +1,82 @@
+use crate::api::account::TransactionType;
+use crate::api::date::Date;
+use crate::api::decimal::Decimal;
+use crate::api::ledger::TransactionRef;
+
+/// Filter for `Bank::search_transactions`. Every field is optional; unset
+/// fields match everything, and set fields must all match.
+/// - `min_amount`/`max_amount`: inclusive bounds on the unsigned amount.
+/// - `from`/`to`: inclusive bounds on the posting date (UTC).
+/// - `memo`: case-insensitive substring of the memo.
+#[derive(Debug, Clone, Default)]
+pub struct TransactionQuery {
+    pub min_amount: Option<Decimal>,
+    pub max_amount: Option<Decimal>,
+    pub from: Option<Date>,
+    pub to: Option<Date>,
+    pub tx_type: Option<TransactionType>,
+    pub memo: Option<String>,
+}
+
+impl TransactionQuery {
+    /// Returns true if `tx` satisfies every filter set on the query.
+    pub fn matches(&self, tx: &TransactionRef<'_>) -> bool {
+        let amount = tx.amount();
+        let date = tx.date();
+        self.min_amount.is_none_or(|min| amount >= min)
+            && self.max_amount.is_none_or(|max| amount <= max)
+            && self.from.is_none_or(|from| date >= from)
+            && self.to.is_none_or(|to| date <= to)
+            && self.tx_type.is_none_or(|t| tx.tx_type() == t)
+            && self
+                .memo
+                .as_ref()
+                .is_none_or(|m| tx.memo.to_lowercase().contains(&m.to_lowercase()))
+    }
+}
+
+/// How an account name matched a `Bank::search_accounts` query, best
+/// first: the whole name, ignoring case; the start of it; or within
+/// `Close`'s edit distance of the whole name.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum NameMatch {
+    Exact,
+    Prefix,
+    Close(usize),
+}
+
+impl NameMatch {
+    /// How `name` matches `query`, ignoring case, or `None` if it does not.
+    /// A close match is about a third of the query's length away at most
+    /// (two typos in "alcie"), and always allows one.
+    pub fn of(query: &str, name: &str) -> Option<Self> {
+        let (query, name) = (query.trim().to_lowercase(), name.to_lowercase());
+        if query.is_empty() {
+            return None;
+        }
+        if name == query {
+            return Some(NameMatch::Exact);
+        }
+        if name.starts_with(&query) {
+            return Some(NameMatch::Prefix);
+        }
+        let max = ((query.chars().count() + 1) / 3).max(1);
+        let distance = edit_distance(&query, &name);
+        (distance <= max).then_some(NameMatch::Close(distance))
+    }
+}
+
+/// Levenshtein distance between two short strings, in characters.
+pub fn edit_distance(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut prev: Vec<usize> = (0..=b.len()).collect();
+    for (i, ca) in a.chars().enumerate() {
+        let mut cur = vec![i + 1];
+        for (j, cb) in b.iter().enumerate() {
+            let cost = usize::from(ca != *cb);
+            cur.push((prev[j] + cost).min(prev[j + 1] + 1).min(cur[j] + 1));
+        }
+        prev = cur;
+    }
+    prev[b.len()]
+}
@@ -0,0 +1,224 @@
+use crate::api::account::TransactionType;
+use crate::api::bank::Bank;
+use std::sync::Arc;
+
+use crate::api::date::{Date, SimulationClock};
+use crate::api::decimal::{Decimal, RoundingStrategy};
+use crate::api::error::Error;
+use crate::api::market::{MarketSimulator, MarketStep, RateModel, Rng};
+use crate::api::money::Money;
+
+/// Accounts `DemoData::new` opens unless told otherwise.
+pub const DEFAULT_DEMO_ACCOUNTS: usize = 12;
+/// Days of history `DemoData::new` generates unless told otherwise.
+pub const DEFAULT_DEMO_DAYS: usize = 90;
+
+const FIRST_NAMES: [&str; 20] = [
+    "Ana", "Ben", "Carla", "Dante", "Elena", "Felix", "Gina", "Hugo", "Isla", "Jose", "Kara", "Luis", "Maya", "Nico", "Olga", "Paolo", "Rosa", "Sam", "Tess", "Vic",
+];
+const LAST_NAMES: [&str; 10] = ["Cruz", "Reyes", "Santos", "Tan", "Lim", "Garcia", "Mendoza", "Torres", "Flores", "Ramos"];
+/// Memos of everyday spending.
+const SPENDING: [&str; 7] = ["Groceries", "Dining", "Transport", "Utilities", "Shopping", "Pharmacy", "Mobile load"];
+
+/// Generates demo and benchmark state: `accounts` accounts with `days` days
+/// of history behind them and rates that moved along the way, all drawn
+/// from `seed`, so the same seed on the same starting bank gives the same
+/// state.
+#[derive(Debug, Clone)]
+pub struct DemoData {
+    pub seed: u64,
+    pub accounts: usize,
+    pub days: usize,
+}
+
+/// What `DemoData::populate` added: the accounts opened, in order, the
+/// transactions posted, and the rates of each day from `start` on.
+#[derive(Debug, Clone)]
+pub struct DemoReport {
+    pub seed: u64,
+    pub start: Date,
+    pub accounts: Vec<String>,
+    pub transactions: u64,
+    pub steps: Vec<MarketStep>,
+}
+
+/// One posting of a generated day, carried out in time order.
+enum Event {
+    Salary(usize),
+    Rent(usize),
+    Spend(usize, &'static str),
+    Transfer(usize, usize),
+}
+
+impl DemoData {
+    /// `DEFAULT_DEMO_ACCOUNTS` accounts over `DEFAULT_DEMO_DAYS` days, drawn
+    /// from `seed`.
+    pub fn new(seed: u64) -> Self {
+        Self { seed, accounts: DEFAULT_DEMO_ACCOUNTS, days: DEFAULT_DEMO_DAYS }
+    }
+
+    pub fn with_accounts(mut self, accounts: usize) -> Self {
+        self.accounts = accounts;
+        self
+    }
+
+    /// At least one day is always generated.
+    pub fn with_days(mut self, days: usize) -> Self {
+        self.days = days.max(1);
+        self
+    }
+
+    /// Open the accounts, dated `days` days ago, and play the days up to
+    /// today: each one moves the foreign rates as `simulate` does (10%
+    /// annual volatility, no drift) and runs the end of day, then posts
+    /// that day's salaries (on the 15th and the last of the month), rent
+    /// (on the 1st), everyday spending, and the odd transfer between
+    /// accounts, at times during business hours. About one account in four
+    /// is held in a foreign currency. Withdrawals never overdraw.
+    ///
+    /// Postings carry the historical dates, so populate a bank without
+    /// later history, e.g. a fresh one. "Today" is the bank's; the days are
+    /// played on a simulation clock of their own, and the bank's clock is
+    /// put back at the end. Fails
+    /// if a posting or rate is refused, e.g. by a read-only bank, leaving
+    /// what was added so far.
+    pub fn populate(&self, bank: &mut Bank) -> Result<DemoReport, Error> {
+        bank.ensure_writable()?;
+        let (clock, simulation) = (bank.clock(), bank.simulation_clock());
+        let result = self.play(bank);
+        match simulation {
+            Some(simulation) => bank.set_simulation_clock(simulation),
+            None => bank.set_clock(clock),
+        }
+        result
+    }
+
+    fn play(&self, bank: &mut Bank) -> Result<DemoReport, Error> {
+        let mut rng = Rng::new(self.seed);
+        let today = bank.today();
+        let start = today.add_days(-(self.days as i64));
+        let first_sequence = bank.next_sequence();
+        let base = bank.forex.base_currency().code.clone();
+        let foreign: Vec<String> = bank
+            .forex
+            .currencies_detailed()
+            .into_iter()
+            .filter(|c| c.code != base && bank.forex.basket(&c.code).is_none())
+            .map(|c| c.code)
+            .collect();
+
+        // (name, monthly salary in the base currency)
+        let mut holders = Vec::with_capacity(self.accounts);
+        for _ in 0..self.accounts {
+            let person = format!("{} {}", pick(&mut rng, &FIRST_NAMES), pick(&mut rng, &LAST_NAMES));
+            let code = match foreign.is_empty() || rng.next_f64() >= 0.25 {
+                true => base.clone(),
+                false => pick(&mut rng, &foreign),
+            };
+            let stem = if code == base { person } else { format!("{}-{}", person, code) };
+            let name = (1..).map(|n| if n == 1 { stem.clone() } else { format!("{} {}", stem, n) }).find(|n| bank.find_account(n).is_none()).unwrap_or(stem);
+            bank.create_account_in(&name, &code)?;
+            holders.push((name, 20_000 + (rng.next_u64() % 100) as i64 * 1_000));
+        }
+
+        bank.set_simulation_clock(Arc::new(SimulationClock::new(start.timestamp() + 9 * 3600)));
+        for (name, salary) in &holders {
+            let opening = *salary as f64 * (0.5 + 2.5 * rng.next_f64());
+            if let Some(amount) = in_currency(bank, name, Decimal::from_f64(opening).unwrap_or(Decimal::ZERO)) {
+                bank.post_transaction(name, TransactionType::Deposit, amount, "Opening deposit", None)?;
+            }
+        }
+
+        let mut market = MarketSimulator::new(rng.next_u64()).with_default(RateModel::new(Decimal::ZERO, Decimal::new(10, 2)));
+        let mut steps = Vec::with_capacity(self.days);
+        for day in (1..=self.days as i64).map(|n| start.add_days(n)) {
+            bank.set_simulated_time(day.add_days(-1).timestamp());
+            steps.push(market.step(bank)?);
+            if day == today {
+                break;
+            }
+            let mut events: Vec<(i64, Event)> = Vec::new();
+            let at = |rng: &mut Rng| day.timestamp() + 8 * 3600 + (rng.next_f64() * 12.0 * 3600.0) as i64;
+            for index in 0..holders.len() {
+                if day.day == 15 || day.add_days(1).day == 1 {
+                    events.push((at(&mut rng), Event::Salary(index)));
+                }
+                if day.day == 1 {
+                    events.push((at(&mut rng), Event::Rent(index)));
+                }
+                if rng.next_f64() < 0.35 {
+                    let memo = pick(&mut rng, &SPENDING);
+                    events.push((at(&mut rng), Event::Spend(index, memo)));
+                }
+                if holders.len() > 1 && rng.next_f64() < 0.04 {
+                    let other = (index + 1 + (rng.next_u64() as usize % (holders.len() - 1))) % holders.len();
+                    events.push((at(&mut rng), Event::Transfer(index, other)));
+                }
+            }
+            events.sort_by_key(|(time, _)| *time);
+            for (time, event) in events {
+                bank.set_simulated_time(time);
+                match event {
+                    Event::Salary(i) => {
+                        let (name, salary) = &holders[i];
+                        if let Some(amount) = in_currency(bank, name, Decimal::new(*salary / 2, 0)) {
+                            bank.post_transaction(name, TransactionType::Deposit, amount, "Salary", None)?;
+                        }
+                    }
+                    Event::Rent(i) => {
+                        let (name, salary) = &holders[i];
+                        if let Some(amount) = in_currency(bank, name, Decimal::new(*salary * 3 / 10, 0)).filter(|a| affordable(bank, name, a)) {
+                            bank.post_transaction(name, TransactionType::Withdraw, amount, "Rent", None)?;
+                        }
+                    }
+                    Event::Spend(i, memo) => {
+                        let name = &holders[i].0;
+                        if let Some(amount) = share(bank, name, 0.01 + 0.07 * rng.next_f64()) {
+                            bank.post_transaction(name, TransactionType::Withdraw, amount, memo, None)?;
+                        }
+                    }
+                    Event::Transfer(i, j) => {
+                        let (from, to) = (&holders[i].0, &holders[j].0);
+                        if let Some(amount) = share(bank, from, 0.05 + 0.15 * rng.next_f64()) {
+                            bank.transfer(from, to, amount, None)?;
+                        }
+                    }
+                }
+            }
+        }
+        Ok(DemoReport {
+            seed: self.seed,
+            start,
+            accounts: holders.into_iter().map(|(name, _)| name).collect(),
+            transactions: bank.next_sequence() - first_sequence,
+            steps,
+        })
+    }
+}
+
+fn pick<T: Clone>(rng: &mut Rng, items: &[T]) -> T {
+    items[rng.next_u64() as usize % items.len()].clone()
+}
+
+/// `base` (an amount in the base currency) in the named account's
+/// currency at today's rate, rounded down to its minor unit; `None` if
+/// that leaves nothing.
+fn in_currency(bank: &Bank, name: &str, base: Decimal) -> Option<Money> {
+    let acct = bank.find_account(name)?;
+    let rate = *bank.forex.get_rate(&acct.currency)?;
+    let amount = (base / rate).round_dp_with_strategy(acct.minor_unit_dp, RoundingStrategy::ToZero);
+    (amount > 0).then(|| Money::new(amount, &acct.currency))
+}
+
+/// `fraction` of the named account's balance, rounded down to its minor
+/// unit; `None` if that leaves nothing.
+fn share(bank: &Bank, name: &str, fraction: f64) -> Option<Money> {
+    let acct = bank.find_account(name)?;
+    let balance = acct.get_balance();
+    let amount = (balance.amount * Decimal::from_f64(fraction)?).round_dp_with_strategy(acct.minor_unit_dp, RoundingStrategy::ToZero);
+    (amount > 0).then(|| Money::new(amount, &balance.currency))
+}
+
+fn affordable(bank: &Bank, name: &str, amount: &Money) -> bool {
+    bank.find_account(name).is_some_and(|a| a.get_balance().amount >= amount.amount)
+}
@@ -0,0 +1,82 @@
+use std::fmt;
+
+use crate::api::bank::BankError;
+use crate::api::decimal::Decimal;
+use crate::api::money::Money;
+use crate::api::statement::{MAINTAINING_BALANCE_MEMO, SERVICE_CHARGE_MEMO};
+
+/// Errors raised when an account's service charges are set.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ServiceChargeError {
+    /// A charge (given) is not above zero.
+    NonPositiveCharge(Decimal),
+    /// The maintaining balance (given) is not above zero.
+    NonPositiveMaintaining(Decimal),
+}
+
+impl fmt::Display for ServiceChargeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ServiceChargeError::NonPositiveCharge(charge) => write!(f, "service charge {} must be greater than zero", charge),
+            ServiceChargeError::NonPositiveMaintaining(balance) => write!(f, "maintaining balance {} must be greater than zero", balance),
+        }
+    }
+}
+
+impl std::error::Error for ServiceChargeError {}
+
+/// A maintaining balance: `charge` is taken for a month whose average daily
+/// balance was below `balance`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct MaintainingBalance {
+    pub balance: Decimal,
+    pub charge: Decimal,
+}
+
+/// The recurring fees on an account, in its currency, taken by
+/// `Bank::end_of_day` on the last day of each month: `monthly`, a flat
+/// service charge every month, and `maintaining`, a charge for a month
+/// whose average daily balance fell below the maintaining balance. `None`
+/// turns that fee off. A fee is taken only as far as the balance covers
+/// it, so it never overdraws the account.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ServiceCharges {
+    pub monthly: Option<Decimal>,
+    pub maintaining: Option<MaintainingBalance>,
+}
+
+impl ServiceCharges {
+    /// Fails if a charge or the maintaining balance is not above zero.
+    pub fn new(monthly: Option<Decimal>, maintaining: Option<MaintainingBalance>) -> Result<Self, ServiceChargeError> {
+        if let Some(charge) = monthly.into_iter().chain(maintaining.map(|m| m.charge)).find(|c| *c <= Decimal::ZERO) {
+            return Err(ServiceChargeError::NonPositiveCharge(charge));
+        }
+        if let Some(m) = maintaining.filter(|m| m.balance <= Decimal::ZERO) {
+            return Err(ServiceChargeError::NonPositiveMaintaining(m.balance));
+        }
+        Ok(Self { monthly, maintaining })
+    }
+
+    /// Returns true when no fee is set.
+    pub fn is_empty(&self) -> bool {
+        self.monthly.is_none() && self.maintaining.is_none()
+    }
+
+    /// The fees due for a month whose average daily balance was `average`:
+    /// the memo each is posted under and its amount, in posting order.
+    pub fn due(&self, average: Decimal) -> Vec<(&'static str, Decimal)> {
+        let below = self.maintaining.filter(|m| average < m.balance).map(|m| (MAINTAINING_BALANCE_MEMO, m.charge));
+        self.monthly.map(|charge| (SERVICE_CHARGE_MEMO, charge)).into_iter().chain(below).collect()
+    }
+}
+
+/// One fee taken by `Bank::end_of_day`: the account, the memo it was
+/// posted under, the month's average daily balance, and the amount taken,
+/// or the reason it was refused.
+#[derive(Debug, Clone)]
+pub struct ServiceChargeRun {
+    pub account: String,
+    pub memo: &'static str,
+    pub average: Money,
+    pub result: Result<Money, BankError>,
+}
@@ -0,0 +1,155 @@
+use std::time::Duration;
+
+use crate::api::date::Date;
+use crate::api::decimal::Decimal;
+use crate::api::money::Money;
+
+/// How `Bank::simulate` drives its accounts: the mean opening deposit, and
+/// for each day and account the chance of a deposit, a withdrawal, and a
+/// transfer to another simulated account, with their mean sizes, all in
+/// the base currency. Sizes are drawn evenly between half and one and a
+/// half times the mean. Interest is credited every `interest_every` days.
+/// Everything is drawn from `seed`, so the same profile on the same
+/// starting bank gives the same run.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SimulationProfile {
+    pub seed: u64,
+    pub opening: Decimal,
+    pub deposit_chance: f64,
+    pub deposit_amount: Decimal,
+    pub withdrawal_chance: f64,
+    pub withdrawal_amount: Decimal,
+    pub transfer_chance: f64,
+    pub transfer_amount: Decimal,
+    pub interest_every: usize,
+}
+
+impl SimulationProfile {
+    /// A retail profile drawn from `seed`: 10,000 to open; on a given day
+    /// one account in five takes a deposit of about 2,000, three in ten a
+    /// withdrawal of about 1,000, and one in twenty sends a transfer of
+    /// about 500; interest monthly (every 30 days).
+    pub fn new(seed: u64) -> Self {
+        Self {
+            seed,
+            opening: Decimal::new(10_000, 0),
+            deposit_chance: 0.2,
+            deposit_amount: Decimal::new(2_000, 0),
+            withdrawal_chance: 0.3,
+            withdrawal_amount: Decimal::new(1_000, 0),
+            transfer_chance: 0.05,
+            transfer_amount: Decimal::new(500, 0),
+            interest_every: 30,
+        }
+    }
+
+    pub fn with_opening(mut self, opening: Decimal) -> Self {
+        self.opening = opening;
+        self
+    }
+
+    /// `chance` is clamped to 0..=1.
+    pub fn with_deposits(mut self, chance: f64, amount: Decimal) -> Self {
+        self.deposit_chance = chance.clamp(0.0, 1.0);
+        self.deposit_amount = amount;
+        self
+    }
+
+    /// `chance` is clamped to 0..=1.
+    pub fn with_withdrawals(mut self, chance: f64, amount: Decimal) -> Self {
+        self.withdrawal_chance = chance.clamp(0.0, 1.0);
+        self.withdrawal_amount = amount;
+        self
+    }
+
+    /// `chance` is clamped to 0..=1.
+    pub fn with_transfers(mut self, chance: f64, amount: Decimal) -> Self {
+        self.transfer_chance = chance.clamp(0.0, 1.0);
+        self.transfer_amount = amount;
+        self
+    }
+
+    /// Credit interest every `days` days (at least 1; 1 compounds daily).
+    pub fn with_interest_every(mut self, days: usize) -> Self {
+        self.interest_every = days.max(1);
+        self
+    }
+}
+
+/// What `Bank::simulate` did, from `start` (the day the accounts opened)
+/// over `days` days: the accounts it opened, the postings it made, the
+/// withdrawals and transfers it declined for want of funds, money moved by
+/// kind, and the spread of the closing balances, all in the base
+/// currency. `transactions` counts every posting, including interest, tax
+/// withheld, and both legs of each transfer. `elapsed` is the wall-clock
+/// time the run took (zero on `wasm32-unknown-unknown`).
+#[derive(Debug, Clone)]
+pub struct SimulationReport {
+    pub seed: u64,
+    pub start: Date,
+    pub days: usize,
+    pub accounts: Vec<String>,
+    pub deposits: u64,
+    pub withdrawals: u64,
+    pub transfers: u64,
+    pub declined: u64,
+    pub transactions: u64,
+    pub opening: Money,
+    pub deposited: Money,
+    pub withdrawn: Money,
+    pub transferred: Money,
+    pub interest: Money,
+    pub closing: Money,
+    pub lowest: Money,
+    pub median: Money,
+    pub highest: Money,
+    pub elapsed: Duration,
+}
+
+impl SimulationReport {
+    /// The mean closing balance.
+    pub fn mean(&self) -> Money {
+        let count = Decimal::from(self.accounts.len().max(1) as u64);
+        Money::new(self.closing.amount / count, &self.closing.currency)
+    }
+
+    /// Postings per second of wall-clock time; 0 when the time was not
+    /// measured (see `Stopwatch`).
+    pub fn throughput(&self) -> f64 {
+        match self.elapsed.is_zero() {
+            true => 0.0,
+            false => self.transactions as f64 / self.elapsed.as_secs_f64(),
+        }
+    }
+}
+
+/// Times a simulation run.
+#[cfg(not(all(target_arch = "wasm32", target_os = "unknown")))]
+pub(crate) struct Stopwatch(std::time::Instant);
+
+#[cfg(not(all(target_arch = "wasm32", target_os = "unknown")))]
+impl Stopwatch {
+    pub(crate) fn start() -> Self {
+        Self(std::time::Instant::now())
+    }
+
+    pub(crate) fn elapsed(&self) -> Duration {
+        self.0.elapsed()
+    }
+}
+
+/// `Instant::now` panics on `wasm32-unknown-unknown`; there runs are not
+/// timed and take no time.
+#[cfg(all(target_arch = "wasm32", target_os = "unknown"))]
+pub(crate) struct Stopwatch;
+
+#[cfg(all(target_arch = "wasm32", target_os = "unknown"))]
+impl Stopwatch {
+    pub(crate) fn start() -> Self {
+        Self
+    }
+
+    pub(crate) fn elapsed(&self) -> Duration {
+        Duration::ZERO
+    }
+}
@@ -0,0 +1,88 @@
+use std::fmt;
+
+use crate::api::bank::{BankError, TransferReceipt};
+use crate::api::calendar::BusinessCalendar;
+use crate::api::date::Date;
+use crate::api::decimal::Decimal;
+use crate::api::money::Money;
+
+/// Longest interval accepted between transfers, in days (ten years).
+pub const MAX_INTERVAL_DAYS: u32 = 3650;
+
+/// Errors raised when a standing order is set up.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum StandingOrderError {
+    NonPositiveAmount,
+    /// The interval is zero or longer than `MAX_INTERVAL_DAYS`.
+    InvalidInterval(u32),
+}
+
+impl fmt::Display for StandingOrderError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            StandingOrderError::NonPositiveAmount => write!(f, "standing order amount must be greater than zero"),
+            StandingOrderError::InvalidInterval(days) => {
+                write!(f, "invalid standing order interval {} (expected 1-{} days)", days, MAX_INTERVAL_DAYS)
+            }
+        }
+    }
+}
+
+impl std::error::Error for StandingOrderError {}
+
+/// A transfer of `amount` from account `from` to account `to` repeated
+/// every `interval_days` days. `amount` may be in any currency; each run
+/// converts and rounds it like `Bank::transfer`. `next` is the date the
+/// next transfer is scheduled for, before rolling to a business day (see
+/// `due_date`). `id` is assigned by the `Bank`.
+#[derive(Debug, Clone)]
+pub struct StandingOrder {
+    pub id: usize,
+    pub from: String,
+    pub to: String,
+    pub amount: Money,
+    pub interval_days: u32,
+    pub next: Date,
+}
+
+impl StandingOrder {
+    /// Set up an order whose first transfer falls due on `first`. Fails if
+    /// the amount is not positive or the interval is outside
+    /// 1-`MAX_INTERVAL_DAYS`.
+    pub fn new(id: usize, from: &str, to: &str, amount: Money, interval_days: u32, first: Date) -> Result<Self, StandingOrderError> {
+        if amount.amount <= Decimal::ZERO {
+            return Err(StandingOrderError::NonPositiveAmount);
+        }
+        if interval_days == 0 || interval_days > MAX_INTERVAL_DAYS {
+            return Err(StandingOrderError::InvalidInterval(interval_days));
+        }
+        Ok(Self { id, from: from.to_string(), to: to.to_string(), amount, interval_days, next: first })
+    }
+
+    /// The date the next transfer is made: `next` rolled to a business
+    /// day of `calendar`.
+    pub fn due_date(&self, calendar: &BusinessCalendar) -> Date {
+        calendar.roll(self.next)
+    }
+
+    /// Whether a transfer falls due on or before `today`.
+    pub fn is_due(&self, today: Date, calendar: &BusinessCalendar) -> bool {
+        self.due_date(calendar) <= today
+    }
+
+    /// Move `next` on by one interval and return it. The interval counts
+    /// from the scheduled date, not the rolled one.
+    pub fn advance(&mut self) -> Date {
+        self.next = self.next.add_days(i64::from(self.interval_days));
+        self.next
+    }
+}
+
+/// One transfer attempted by `Bank::run_standing_orders`: the order, the
+/// business day it fell due, and the receipt or the reason it was refused.
+#[derive(Debug, Clone)]
+pub struct StandingOrderRun {
+    pub order: usize,
+    pub date: Date,
+    pub result: Result<TransferReceipt, BankError>,
+}
@@ -0,0 +1,348 @@
+use std::io::{self, Write};
+
+use crate::api::account::TransactionType;
+use crate::api::date::{Date, TimeZone, SECS_PER_DAY};
+use crate::api::decimal::Decimal;
+use crate::api::format::{format_amount, Locale};
+use crate::api::search::TransactionQuery;
+
+/// `BANKID` written in OFX statements; the simulated bank has no routing
+/// number, so importers key accounts on this plus the account id.
+pub const OFX_BANK_ID: &str = "RUSTFOREX";
+
+/// The bank's name in the header of a printable `Statement`.
+pub const BANK_NAME: &str = "Rust Forex Bank";
+
+/// Memo of the deposit that credits interest.
+pub const INTEREST_MEMO: &str = "Interest";
+
+/// Memo of the withdrawal that charges interest at a negative rate.
+pub const CARRYING_CHARGE_MEMO: &str = "Carrying charge";
+
+/// Memo of the withdrawal that takes the tax withheld from interest.
+pub const WITHHOLDING_TAX_MEMO: &str = "Withholding tax";
+
+/// Memo of the withdrawal that takes an account's flat monthly service
+/// charge (see `ServiceCharges`).
+pub const SERVICE_CHARGE_MEMO: &str = "Service charge";
+
+/// Memo of the withdrawal that takes the charge for a month below the
+/// maintaining balance (see `ServiceCharges`).
+pub const MAINTAINING_BALANCE_MEMO: &str = "Below maintaining balance";
+
+/// File formats an account statement can be exported in.
+/// - `Csv`: spreadsheets (`Account::export_csv`).
+/// - `Ofx`: OFX 2.1 XML bank statement, read by GnuCash, Quicken, and most
+///   personal finance tools (`Account::export_ofx`).
+/// - `Qif`: Quicken Interchange Format `!Type:Bank` list
+///   (`Account::export_qif`). QIF has no currency field.
+/// - `Html`: a printable `Statement` for people, with totals and an
+///   interest summary (`Statement::write_html`); print it to PDF from a
+///   browser.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StatementFormat {
+    Csv,
+    Ofx,
+    Qif,
+    Html,
+}
+
+impl StatementFormat {
+    /// Parse "csv", "ofx", "qif", or "html" (any case).
+    pub fn parse(s: &str) -> Option<Self> {
+        match s.trim().to_lowercase().as_str() {
+            "csv" => Some(StatementFormat::Csv),
+            "ofx" => Some(StatementFormat::Ofx),
+            "qif" => Some(StatementFormat::Qif),
+            "html" => Some(StatementFormat::Html),
+            _ => None,
+        }
+    }
+
+    /// File extension without the dot, e.g. "ofx".
+    pub fn extension(&self) -> &'static str {
+        match self {
+            StatementFormat::Csv => "csv",
+            StatementFormat::Ofx => "ofx",
+            StatementFormat::Qif => "qif",
+            StatementFormat::Html => "html",
+        }
+    }
+}
+
+/// One transaction on a `Statement`; `amount` is unsigned and `balance` is
+/// the running balance right after it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StatementLine {
+    pub timestamp: i64,
+    pub tx_type: TransactionType,
+    pub memo: String,
+    pub amount: Decimal,
+    pub balance: Decimal,
+}
+
+/// Interest over a `Statement`'s period: the annual rate in effect on its
+/// first and last day, and the interest credited and carrying charges
+/// taken in it (both unsigned).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct InterestSummary {
+    pub opening_rate: Decimal,
+    pub closing_rate: Decimal,
+    pub credited: Decimal,
+    pub charged: Decimal,
+}
+
+/// A printable account statement for `start` through `end` (inclusive,
+/// UTC), built by `Account::statement` or, with the holder and the bank's
+/// locale, time zone, and symbols, `Bank::statement`. Amounts are in
+/// `currency` and are shown with `symbol`, `decimals` places, and `locale`;
+/// the time it was generated is shown in `time_zone`.
+#[derive(Debug, Clone)]
+pub struct Statement {
+    pub bank: String,
+    /// When the statement was made (Unix seconds, UTC).
+    pub generated: i64,
+    pub account_id: usize,
+    pub account: String,
+    /// The owning customer's name, if any.
+    pub holder: Option<String>,
+    /// The owning customer's contact details, if any.
+    pub contact: Option<String>,
+    pub currency: String,
+    pub symbol: String,
+    pub decimals: u32,
+    pub locale: Locale,
+    pub time_zone: TimeZone,
+    pub start: Date,
+    pub end: Date,
+    /// The balance at the end of the day before `start`.
+    pub opening: Decimal,
+    /// The balance at the end of `end`.
+    pub closing: Decimal,
+    pub lines: Vec<StatementLine>,
+    pub interest: InterestSummary,
+}
+
+impl Statement {
+    /// Sum of the deposits in the period, interest included.
+    pub fn total_deposits(&self) -> Decimal {
+        self.total(TransactionType::Deposit)
+    }
+
+    /// Sum of the withdrawals in the period, carrying and service charges
+    /// included.
+    pub fn total_withdrawals(&self) -> Decimal {
+        self.total(TransactionType::Withdraw)
+    }
+
+    /// The service charges taken in the period (see `ServiceCharges`), in
+    /// posting order.
+    pub fn service_charges(&self) -> impl Iterator<Item = &StatementLine> {
+        self.lines
+            .iter()
+            .filter(|l| l.tx_type == TransactionType::Withdraw && matches!(l.memo.as_str(), SERVICE_CHARGE_MEMO | MAINTAINING_BALANCE_MEMO))
+    }
+
+    fn total(&self, tx_type: TransactionType) -> Decimal {
+        self.lines.iter().filter(|l| l.tx_type == tx_type).fold(Decimal::ZERO, |sum, l| sum + l.amount)
+    }
+
+    /// `amount` as the statement shows it, e.g. "₱1,234.50".
+    pub fn format(&self, amount: Decimal) -> String {
+        format_amount(amount, &self.symbol, self.decimals, self.locale)
+    }
+
+    /// Write the statement as a self-contained HTML page laid out for
+    /// printing: the bank header, account details, a summary of balances,
+    /// the transaction table, the service charges, if any, and the interest
+    /// summary.
+    pub fn write_html(&self, writer: &mut impl Write) -> io::Result<()> {
+        let title = format!("Statement for {}, {} to {}", self.account, self.start, self.end);
+        writeln!(writer, "<!DOCTYPE html>")?;
+        writeln!(writer, "<html lang=\"en\">")?;
+        writeln!(writer, "<head>")?;
+        writeln!(writer, "<meta charset=\"utf-8\">")?;
+        writeln!(writer, "<title>{}</title>", html_escape(&title))?;
+        writeln!(writer, "<style>")?;
+        writeln!(writer, "body {{ font-family: sans-serif; font-size: 10pt; margin: 2em; color: #222; }}")?;
+        writeln!(writer, "header {{ border-bottom: 2px solid #222; margin-bottom: 1em; }}")?;
+        writeln!(writer, "h1 {{ font-size: 16pt; margin: 0; }}")?;
+        writeln!(writer, "h2 {{ font-size: 12pt; margin-top: 1.5em; }}")?;
+        writeln!(writer, "table {{ border-collapse: collapse; width: 100%; }}")?;
+        writeln!(writer, "th, td {{ padding: 0.25em 0.5em; border-bottom: 1px solid #ccc; text-align: left; }}")?;
+        writeln!(writer, "td.amount, th.amount {{ text-align: right; white-space: nowrap; }}")?;
+        writeln!(writer, "dl {{ display: grid; grid-template-columns: max-content auto; gap: 0.2em 1em; }}")?;
+        writeln!(writer, "dt {{ font-weight: bold; }} dd {{ margin: 0; }}")?;
+        writeln!(writer, "@media print {{ body {{ margin: 0; }} thead {{ display: table-header-group; }} tr {{ page-break-inside: avoid; }} }}")?;
+        writeln!(writer, "</style>")?;
+        writeln!(writer, "</head>")?;
+        writeln!(writer, "<body>")?;
+        writeln!(writer, "<header>")?;
+        writeln!(writer, "<h1>{}</h1>", html_escape(&self.bank))?;
+        writeln!(writer, "<p>Account statement, {} to {}. Generated {}.</p>", self.start, self.end, self.time_zone.format_timestamp(self.generated))?;
+        writeln!(writer, "</header>")?;
+
+        writeln!(writer, "<section>")?;
+        writeln!(writer, "<h2>Account</h2>")?;
+        writeln!(writer, "<dl>")?;
+        if let Some(holder) = &self.holder {
+            writeln!(writer, "<dt>Holder</dt><dd>{}</dd>", html_escape(holder))?;
+        }
+        writeln!(writer, "<dt>Account</dt><dd>{}</dd>", html_escape(&self.account))?;
+        writeln!(writer, "<dt>Account ID</dt><dd>{}</dd>", self.account_id)?;
+        writeln!(writer, "<dt>Currency</dt><dd>{}</dd>", html_escape(&self.currency))?;
+        writeln!(writer, "</dl>")?;
+        writeln!(writer, "</section>")?;
+
+        writeln!(writer, "<section>")?;
+        writeln!(writer, "<h2>Summary</h2>")?;
+        writeln!(writer, "<table>")?;
+        for (label, amount) in [
+            ("Opening balance", self.opening),
+            ("Deposits", self.total_deposits()),
+            ("Withdrawals", self.total_withdrawals()),
+            ("Closing balance", self.closing),
+        ] {
+            writeln!(writer, "<tr><th>{}</th><td class=\"amount\">{}</td></tr>", label, html_escape(&self.format(amount)))?;
+        }
+        writeln!(writer, "</table>")?;
+        writeln!(writer, "</section>")?;
+
+        writeln!(writer, "<section>")?;
+        writeln!(writer, "<h2>Transactions</h2>")?;
+        if self.lines.is_empty() {
+            writeln!(writer, "<p>No transactions in this period.</p>")?;
+        } else {
+            writeln!(writer, "<table>")?;
+            writeln!(writer, "<thead><tr><th>Date</th><th>Description</th><th class=\"amount\">Deposit</th><th class=\"amount\">Withdrawal</th><th class=\"amount\">Balance</th></tr></thead>")?;
+            writeln!(writer, "<tbody>")?;
+            for line in &self.lines {
+                let amount = html_escape(&self.format(line.amount));
+                let (deposit, withdrawal, default_memo) = match line.tx_type {
+                    TransactionType::Deposit => (amount, String::new(), "Deposit"),
+                    TransactionType::Withdraw => (String::new(), amount, "Withdrawal"),
+                };
+                let memo = if line.memo.is_empty() { default_memo } else { &line.memo };
+                writeln!(
+                    writer,
+                    "<tr><td>{}</td><td>{}</td><td class=\"amount\">{}</td><td class=\"amount\">{}</td><td class=\"amount\">{}</td></tr>",
+                    Date::from_timestamp(line.timestamp),
+                    html_escape(memo),
+                    deposit,
+                    withdrawal,
+                    html_escape(&self.format(line.balance))
+                )?;
+            }
+            writeln!(writer, "</tbody>")?;
+            writeln!(writer, "</table>")?;
+        }
+        writeln!(writer, "</section>")?;
+
+        if self.service_charges().next().is_some() {
+            writeln!(writer, "<section>")?;
+            writeln!(writer, "<h2>Service charges</h2>")?;
+            writeln!(writer, "<table>")?;
+            let mut total = Decimal::ZERO;
+            for line in self.service_charges() {
+                total += line.amount;
+                writeln!(
+                    writer,
+                    "<tr><td>{}</td><td>{}</td><td class=\"amount\">{}</td></tr>",
+                    Date::from_timestamp(line.timestamp),
+                    html_escape(&line.memo),
+                    html_escape(&self.format(line.amount))
+                )?;
+            }
+            writeln!(writer, "<tr><th colspan=\"2\">Total</th><td class=\"amount\">{}</td></tr>", html_escape(&self.format(total)))?;
+            writeln!(writer, "</table>")?;
+            writeln!(writer, "</section>")?;
+        }
+
+        writeln!(writer, "<section>")?;
+        writeln!(writer, "<h2>Interest</h2>")?;
+        writeln!(writer, "<dl>")?;
+        let rate = |r: Decimal| format!("{}%", (r * Decimal::from(100)).round_dp(4));
+        writeln!(writer, "<dt>Annual rate on {}</dt><dd>{}</dd>", self.start, rate(self.interest.opening_rate))?;
+        if self.interest.closing_rate != self.interest.opening_rate {
+            writeln!(writer, "<dt>Annual rate on {}</dt><dd>{}</dd>", self.end, rate(self.interest.closing_rate))?;
+        }
+        writeln!(writer, "<dt>Interest credited</dt><dd>{}</dd>", html_escape(&self.format(self.interest.credited)))?;
+        if !self.interest.charged.is_zero() {
+            writeln!(writer, "<dt>Carrying charges</dt><dd>{}</dd>", html_escape(&self.format(self.interest.charged)))?;
+        }
+        writeln!(writer, "</dl>")?;
+        writeln!(writer, "</section>")?;
+        writeln!(writer, "</body>")?;
+        writeln!(writer, "</html>")
+    }
+}
+
+/// Options for `Account::export_csv`.
+/// - `query`: which transactions to include; the balance column still
+///   counts every transaction, as in `Account::history`.
+/// - `delimiter`: field separator, `,` by default (`;` suits locales that
+///   use a decimal comma).
+/// - `header`: write the column names as the first row.
+/// - `time`: add a `time` column (`HH:MM:SSZ`, UTC) after the date.
+#[derive(Debug, Clone)]
+pub struct CsvOptions {
+    pub query: TransactionQuery,
+    pub delimiter: char,
+    pub header: bool,
+    pub time: bool,
+}
+
+impl Default for CsvOptions {
+    fn default() -> Self {
+        Self {
+            query: TransactionQuery::default(),
+            delimiter: ',',
+            header: true,
+            time: false,
+        }
+    }
+}
+
+/// Quote a CSV field when it contains the delimiter, a quote, or a line
+/// break, doubling inner quotes (RFC 4180).
+pub(crate) fn csv_field(cell: &str, delimiter: char) -> String {
+    if cell.contains([delimiter, '"', '\n', '\r']) {
+        format!("\"{}\"", cell.replace('"', "\"\""))
+    } else {
+        cell.to_string()
+    }
+}
+
+/// File-system-safe stem for an account's export file: `{id}-{name}` with
+/// anything other than letters, digits, `-`, and `_` replaced by `_`. The
+/// id keeps names that sanitize alike apart.
+pub(crate) fn file_stem(id: usize, name: &str) -> String {
+    let name: String = name
+        .chars()
+        .map(|c| if c.is_alphanumeric() || c == '-' || c == '_' { c } else { '_' })
+        .collect();
+    format!("{}-{}", id, name)
+}
+
+/// OFX date-time (`YYYYMMDDHHMMSS[0:GMT]`, UTC with the zone spelled out)
+/// for Unix seconds.
+pub(crate) fn ofx_datetime(secs: i64) -> String {
+    let date = Date::from_timestamp(secs);
+    let tod = secs.rem_euclid(SECS_PER_DAY);
+    format!("{:04}{:02}{:02}{:02}{:02}{:02}[0:GMT]", date.year, date.month, date.day, tod / 3600, tod % 3600 / 60, tod % 60)
+}
+
+/// Escape text for HTML element content.
+pub(crate) fn html_escape(text: &str) -> String {
+    xml_escape(text).replace('"', "&quot;")
+}
+
+/// Escape text for an OFX (XML) element.
+pub(crate) fn xml_escape(text: &str) -> String {
+    text.replace('&', "&amp;").replace('<', "&lt;").replace('>', "&gt;")
+}
+
+/// QIF date in the US `MM/DD/YYYY` form most importers default to.
+pub(crate) fn qif_date(date: Date) -> String {
+    format!("{:02}/{:02}/{:04}", date.month, date.day, date.year)
+}
@@ -0,0 +1,75 @@
+use std::io::{self, Write};
+
+use crate::api::date::Month;
+use crate::api::decimal::Decimal;
+use crate::api::money::Money;
+use crate::api::statement::csv_field;
+
+/// One account's month in a `MonthlySummary`, in the account's currency:
+/// its balance at the start and end of the month, what was deposited and
+/// withdrawn, the interest credited (part of `inflow`), and the fees
+/// charged: conversion fees on the conversions it made, plus carrying
+/// charges for a negative rate and service charges (the latter part of
+/// `outflow`).
+/// `decimals` is the currency's number of decimal places.
+#[derive(Debug, Clone, PartialEq)]
+pub struct AccountSummary {
+    pub account: String,
+    pub decimals: u32,
+    pub opening: Money,
+    pub inflow: Money,
+    pub outflow: Money,
+    pub interest: Money,
+    pub fees: Money,
+    pub closing: Money,
+}
+
+impl AccountSummary {
+    /// The balance's change over the month: inflow less outflow.
+    pub fn net_change(&self) -> Money {
+        Money::new(self.closing.amount - self.opening.amount, &self.closing.currency)
+    }
+
+    /// An account's month before any postings are counted: nothing in or
+    /// out, opening and closing at `opening`.
+    pub(crate) fn new(account: &str, decimals: u32, opening: Money) -> Self {
+        let zero = Money::new(Decimal::ZERO, &opening.currency);
+        Self {
+            account: account.to_string(),
+            decimals,
+            inflow: zero.clone(),
+            outflow: zero.clone(),
+            interest: zero.clone(),
+            fees: zero,
+            closing: opening.clone(),
+            opening,
+        }
+    }
+}
+
+/// Every active account's month, from `Bank::monthly_summary`, in account
+/// order. Dates go by UTC, like statements and budgets.
+#[derive(Debug, Clone, PartialEq)]
+pub struct MonthlySummary {
+    pub month: Month,
+    pub accounts: Vec<AccountSummary>,
+}
+
+impl MonthlySummary {
+    /// Write the summary as CSV, one row per account: `month`, `account`,
+    /// `currency`, `opening`, `inflow`, `outflow`, `interest`, `fees`,
+    /// `net_change`, and `closing`. Amounts are plain numbers with the
+    /// currency's decimal places.
+    pub fn write_csv(&self, writer: &mut impl Write) -> io::Result<()> {
+        writeln!(writer, "month,account,currency,opening,inflow,outflow,interest,fees,net_change,closing")?;
+        for a in &self.accounts {
+            let dp = a.decimals as usize;
+            let amounts: Vec<String> = [&a.opening, &a.inflow, &a.outflow, &a.interest, &a.fees, &a.net_change(), &a.closing]
+                .iter()
+                .map(|m| format!("{:.*}", dp, m.amount))
+                .collect();
+            writeln!(writer, "{},{},{},{}", self.month, csv_field(&a.account, ','), a.opening.currency, amounts.join(","))?;
+        }
+        Ok(())
+    }
+}
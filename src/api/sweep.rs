@@ -0,0 +1,60 @@
+use std::fmt;
+
+use crate::api::bank::{BankError, TransferReceipt};
+use crate::api::decimal::Decimal;
+use crate::api::money::Money;
+
+/// Errors raised when a sweep rule is set up.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SweepError {
+    /// The threshold is below zero, which no balance can be held at.
+    NegativeThreshold(Decimal),
+}
+
+impl fmt::Display for SweepError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SweepError::NegativeThreshold(threshold) => write!(f, "sweep threshold {} must not be below zero", threshold),
+        }
+    }
+}
+
+impl std::error::Error for SweepError {}
+
+/// A standing instruction to move whatever account `from` holds above
+/// `threshold` (in its own currency) to account `to` at the end of each
+/// day. The excess is converted and rounded like `Bank::transfer` when
+/// the two accounts hold different currencies. `id` is assigned by the
+/// `Bank`.
+#[derive(Debug, Clone)]
+pub struct SweepRule {
+    pub id: usize,
+    pub from: String,
+    pub to: String,
+    pub threshold: Money,
+}
+
+impl SweepRule {
+    /// Set up a rule keeping `threshold` in `from`. Fails if the threshold
+    /// is below zero; at zero the whole balance is swept.
+    pub fn new(id: usize, from: &str, to: &str, threshold: Money) -> Result<Self, SweepError> {
+        if threshold.amount < Decimal::ZERO {
+            return Err(SweepError::NegativeThreshold(threshold.amount));
+        }
+        Ok(Self { id, from: from.to_string(), to: to.to_string(), threshold })
+    }
+
+    /// What a balance of `balance` holds above the threshold, if anything.
+    pub fn excess(&self, balance: &Money) -> Option<Money> {
+        let excess = balance.amount - self.threshold.amount;
+        (excess > Decimal::ZERO).then(|| Money::new(excess, &balance.currency))
+    }
+}
+
+/// One sweep made by `Bank::run_sweeps`: the rule, and the receipt of the
+/// transfer or the reason it was refused.
+#[derive(Debug, Clone)]
+pub struct SweepRun {
+    pub rule: usize,
+    pub result: Result<TransferReceipt, BankError>,
+}
@@ -0,0 +1,67 @@
+use std::fmt;
+
+use crate::api::decimal::Decimal;
+use crate::api::money::Money;
+
+/// Errors raised when a transaction is tagged.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TagError {
+    /// A tag is empty or only spaces.
+    Empty,
+    /// A tag (given) contains a comma, which separates tags in lists.
+    Comma(String),
+}
+
+impl fmt::Display for TagError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TagError::Empty => write!(f, "a tag cannot be empty"),
+            TagError::Comma(tag) => write!(f, "tag {} cannot contain a comma", tag),
+        }
+    }
+}
+
+impl std::error::Error for TagError {}
+
+/// `tags` as they are stored: trimmed, lowercased, and with repeats
+/// dropped, in the order given. Fails on an empty tag or one containing a
+/// comma.
+pub fn normalize(tags: &[&str]) -> Result<Vec<String>, TagError> {
+    let mut normalized: Vec<String> = Vec::with_capacity(tags.len());
+    for tag in tags {
+        let tag = tag.trim().to_lowercase();
+        if tag.is_empty() {
+            return Err(TagError::Empty);
+        }
+        if tag.contains(',') {
+            return Err(TagError::Comma(tag));
+        }
+        if !normalized.contains(&tag) {
+            normalized.push(tag);
+        }
+    }
+    Ok(normalized)
+}
+
+/// The transactions carrying one tag over a period, from
+/// `Account::report_by_tag`: how many there were, and what they paid in
+/// and took out, in the account's currency.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TagTotal {
+    pub tag: String,
+    pub count: usize,
+    pub inflow: Money,
+    pub outflow: Money,
+}
+
+impl TagTotal {
+    /// Inflow less outflow.
+    pub fn net(&self) -> Money {
+        Money::new(self.inflow.amount - self.outflow.amount, &self.inflow.currency)
+    }
+
+    /// A total of nothing yet for `tag`.
+    pub(crate) fn empty(tag: &str, currency: &str) -> Self {
+        Self { tag: tag.to_string(), count: 0, inflow: Money::new(Decimal::ZERO, currency), outflow: Money::new(Decimal::ZERO, currency) }
+    }
+}
@@ -0,0 +1,187 @@
+use std::io::{self, Write};
+
+use crate::api::date::{Month, TimeZone};
+use crate::api::decimal::Decimal;
+use crate::api::format::{format_amount, Locale};
+use crate::api::statement::{csv_field, html_escape};
+
+/// One month on a `TaxCertificate`: the interest credited in it and the
+/// tax withheld from that interest, both unsigned.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TaxCertificateLine {
+    pub month: Month,
+    pub gross: Decimal,
+    pub withheld: Decimal,
+}
+
+impl TaxCertificateLine {
+    /// Interest less the tax withheld.
+    pub fn net(&self) -> Decimal {
+        self.gross - self.withheld
+    }
+}
+
+/// A year-end certificate of the interest an account earned and the tax
+/// withheld from it (in the manner of BIR Forms 2306 and 2307), built by
+/// `Account::tax_certificate` or, with the holder and the bank's locale,
+/// time zone, and symbols, `Bank::tax_certificate`. Interest counts the
+/// "Interest" deposits, including interest paid in from another account,
+/// and the tax the "Withholding tax" withdrawals, by month (UTC). Amounts
+/// are in `currency` and are shown like a `Statement`'s.
+#[derive(Debug, Clone)]
+pub struct TaxCertificate {
+    pub bank: String,
+    /// When the certificate was made (Unix seconds, UTC).
+    pub generated: i64,
+    pub year: i32,
+    pub account_id: usize,
+    pub account: String,
+    /// The owning customer's name, if any.
+    pub holder: Option<String>,
+    /// The owning customer's address and identity document, if on file.
+    pub address: Option<String>,
+    pub identification: Option<String>,
+    pub currency: String,
+    pub symbol: String,
+    pub decimals: u32,
+    pub locale: Locale,
+    pub time_zone: TimeZone,
+    /// January through December.
+    pub months: Vec<TaxCertificateLine>,
+}
+
+impl TaxCertificate {
+    /// Interest credited over the year.
+    pub fn gross(&self) -> Decimal {
+        self.months.iter().fold(Decimal::ZERO, |sum, m| sum + m.gross)
+    }
+
+    /// Tax withheld over the year.
+    pub fn withheld(&self) -> Decimal {
+        self.months.iter().fold(Decimal::ZERO, |sum, m| sum + m.withheld)
+    }
+
+    /// Interest less the tax withheld, over the year.
+    pub fn net(&self) -> Decimal {
+        self.gross() - self.withheld()
+    }
+
+    /// `amount` as the certificate shows it, e.g. "₱1,234.50".
+    pub fn format(&self, amount: Decimal) -> String {
+        format_amount(amount, &self.symbol, self.decimals, self.locale)
+    }
+
+    /// Write the certificate as CSV: `year`, `account`, `currency`,
+    /// `month`, `gross_interest`, `tax_withheld`, and `net_interest`, one
+    /// row per month and a last row with `total` as its month. Amounts are
+    /// plain numbers with the currency's decimal places.
+    pub fn write_csv(&self, writer: &mut impl Write) -> io::Result<()> {
+        writeln!(writer, "year,account,currency,month,gross_interest,tax_withheld,net_interest")?;
+        let dp = self.decimals as usize;
+        let rows = self
+            .months
+            .iter()
+            .map(|m| (m.month.to_string(), m.gross, m.withheld, m.net()))
+            .chain([(String::from("total"), self.gross(), self.withheld(), self.net())]);
+        for (month, gross, withheld, net) in rows {
+            writeln!(
+                writer,
+                "{},{},{},{},{:.*},{:.*},{:.*}",
+                self.year,
+                csv_field(&self.account, ','),
+                self.currency,
+                month,
+                dp,
+                gross,
+                dp,
+                withheld,
+                dp,
+                net
+            )?;
+        }
+        Ok(())
+    }
+
+    /// Write the certificate as a self-contained HTML page laid out for
+    /// printing, like `Statement::write_html`: the bank header, the payee,
+    /// the monthly table, and the year's totals. Print it to PDF from a
+    /// browser.
+    pub fn write_html(&self, writer: &mut impl Write) -> io::Result<()> {
+        let title = format!("Certificate of interest and tax withheld for {}, {}", self.account, self.year);
+        writeln!(writer, "<!DOCTYPE html>")?;
+        writeln!(writer, "<html lang=\"en\">")?;
+        writeln!(writer, "<head>")?;
+        writeln!(writer, "<meta charset=\"utf-8\">")?;
+        writeln!(writer, "<title>{}</title>", html_escape(&title))?;
+        writeln!(writer, "<style>")?;
+        writeln!(writer, "body {{ font-family: sans-serif; font-size: 10pt; margin: 2em; color: #222; }}")?;
+        writeln!(writer, "header {{ border-bottom: 2px solid #222; margin-bottom: 1em; }}")?;
+        writeln!(writer, "h1 {{ font-size: 16pt; margin: 0; }}")?;
+        writeln!(writer, "h2 {{ font-size: 12pt; margin-top: 1.5em; }}")?;
+        writeln!(writer, "table {{ border-collapse: collapse; width: 100%; }}")?;
+        writeln!(writer, "th, td {{ padding: 0.25em 0.5em; border-bottom: 1px solid #ccc; text-align: left; }}")?;
+        writeln!(writer, "td.amount, th.amount {{ text-align: right; white-space: nowrap; }}")?;
+        writeln!(writer, "tfoot td, tfoot th {{ font-weight: bold; border-top: 2px solid #222; }}")?;
+        writeln!(writer, "dl {{ display: grid; grid-template-columns: max-content auto; gap: 0.2em 1em; }}")?;
+        writeln!(writer, "dt {{ font-weight: bold; }} dd {{ margin: 0; }}")?;
+        writeln!(writer, "@media print {{ body {{ margin: 0; }} }}")?;
+        writeln!(writer, "</style>")?;
+        writeln!(writer, "</head>")?;
+        writeln!(writer, "<body>")?;
+        writeln!(writer, "<header>")?;
+        writeln!(writer, "<h1>{}</h1>", html_escape(&self.bank))?;
+        writeln!(
+            writer,
+            "<p>Certificate of interest income and final tax withheld, January to December {}. Generated {}.</p>",
+            self.year,
+            self.time_zone.format_timestamp(self.generated)
+        )?;
+        writeln!(writer, "</header>")?;
+
+        writeln!(writer, "<section>")?;
+        writeln!(writer, "<h2>Payee</h2>")?;
+        writeln!(writer, "<dl>")?;
+        if let Some(holder) = &self.holder {
+            writeln!(writer, "<dt>Holder</dt><dd>{}</dd>", html_escape(holder))?;
+        }
+        if let Some(address) = &self.address {
+            writeln!(writer, "<dt>Address</dt><dd>{}</dd>", html_escape(address))?;
+        }
+        if let Some(identification) = &self.identification {
+            writeln!(writer, "<dt>Identification</dt><dd>{}</dd>", html_escape(identification))?;
+        }
+        writeln!(writer, "<dt>Account</dt><dd>{}</dd>", html_escape(&self.account))?;
+        writeln!(writer, "<dt>Account ID</dt><dd>{}</dd>", self.account_id)?;
+        writeln!(writer, "<dt>Currency</dt><dd>{}</dd>", html_escape(&self.currency))?;
+        writeln!(writer, "</dl>")?;
+        writeln!(writer, "</section>")?;
+
+        writeln!(writer, "<section>")?;
+        writeln!(writer, "<h2>Interest and tax withheld</h2>")?;
+        writeln!(writer, "<table>")?;
+        writeln!(writer, "<thead><tr><th>Month</th><th class=\"amount\">Gross interest</th><th class=\"amount\">Tax withheld</th><th class=\"amount\">Net interest</th></tr></thead>")?;
+        writeln!(writer, "<tbody>")?;
+        for m in &self.months {
+            writeln!(
+                writer,
+                "<tr><td>{}</td><td class=\"amount\">{}</td><td class=\"amount\">{}</td><td class=\"amount\">{}</td></tr>",
+                m.month,
+                html_escape(&self.format(m.gross)),
+                html_escape(&self.format(m.withheld)),
+                html_escape(&self.format(m.net()))
+            )?;
+        }
+        writeln!(writer, "</tbody>")?;
+        writeln!(
+            writer,
+            "<tfoot><tr><th>Total</th><td class=\"amount\">{}</td><td class=\"amount\">{}</td><td class=\"amount\">{}</td></tr></tfoot>",
+            html_escape(&self.format(self.gross())),
+            html_escape(&self.format(self.withheld())),
+            html_escape(&self.format(self.net()))
+        )?;
+        writeln!(writer, "</table>")?;
+        writeln!(writer, "</section>")?;
+        writeln!(writer, "</body>")?;
+        writeln!(writer, "</html>")
+    }
+}
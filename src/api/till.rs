@@ -0,0 +1,208 @@
+use std::collections::BTreeMap;
+use std::fmt;
+
+use crate::api::decimal::{Decimal, RoundingStrategy};
+use crate::api::denomination::{CashBreakdown, DenominationCount};
+use crate::api::money::Money;
+
+/// Errors raised when cash cannot move through the till.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TillError {
+    NonPositiveAmount,
+    /// This much of the amount is smaller than the currency's smallest
+    /// denomination, or the currency has none, so it cannot be paid in cash.
+    NotCash(Money),
+    /// The drawer holds too few bills and coins to pay out this amount.
+    InsufficientCash(Money),
+}
+
+impl fmt::Display for TillError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TillError::NonPositiveAmount => write!(f, "cash amount must be greater than zero"),
+            TillError::NotCash(rest) => write!(f, "{} cannot be paid in bills and coins", rest),
+            TillError::InsufficientCash(amount) => write!(f, "the till cannot make up {} from the cash in the drawer", amount),
+        }
+    }
+}
+
+impl std::error::Error for TillError {}
+
+/// A teller's cash drawer: how many bills and coins of each denomination it
+/// holds, by currency. The counts are what the drawer should hold given
+/// the cash that went in and out through the `Bank`'s cash operations;
+/// `balance` compares them with a physical count.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Till {
+    cash: BTreeMap<String, BTreeMap<Decimal, u64>>,
+}
+
+impl Till {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Put `count` bills or coins of `value` in `currency` into the drawer,
+    /// e.g. the opening float.
+    pub fn load(&mut self, currency: &str, value: Decimal, count: u64) {
+        if count > 0 {
+            *self.cash.entry(currency.to_string()).or_default().entry(value).or_default() += count;
+        }
+    }
+
+    /// How many bills or coins of `value` in `currency` the drawer holds.
+    pub fn count(&self, currency: &str, value: Decimal) -> u64 {
+        self.cash.get(currency).and_then(|c| c.get(&value)).copied().unwrap_or(0)
+    }
+
+    /// The drawer's contents in `currency`, largest denomination first.
+    pub fn contents(&self, currency: &str) -> Vec<DenominationCount> {
+        self.cash
+            .get(currency)
+            .into_iter()
+            .flat_map(|c| c.iter().rev())
+            .filter(|(_, count)| **count > 0)
+            .map(|(value, count)| DenominationCount { value: *value, count: *count })
+            .collect()
+    }
+
+    /// The currencies the drawer holds or has held, in code order.
+    pub fn currencies(&self) -> Vec<&str> {
+        self.cash.keys().map(String::as_str).collect()
+    }
+
+    /// Total cash held in `currency`.
+    pub fn total(&self, currency: &str) -> Money {
+        let total = self.contents(currency).iter().map(|p| p.value * Decimal::from(p.count)).sum();
+        Money::new(total, currency)
+    }
+
+    /// The bills and coins `amount` is taken in as, counted out greedily
+    /// over `denominations` (see `CashBreakdown::new`). Fails if any of it
+    /// is not payable in cash.
+    pub fn plan_cash_in(amount: &Money, denominations: &[Decimal]) -> Result<CashBreakdown, TillError> {
+        if amount.amount <= Decimal::ZERO {
+            return Err(TillError::NonPositiveAmount);
+        }
+        let breakdown = CashBreakdown::new(amount, denominations);
+        if !breakdown.remainder.amount.is_zero() {
+            return Err(TillError::NotCash(breakdown.remainder));
+        }
+        Ok(breakdown)
+    }
+
+    /// The bills and coins the drawer would pay `amount` out in: as many of
+    /// the largest denomination as it holds and fit, then the next. Greedy
+    /// counting can refuse an amount another mix would make up (60 from
+    /// one 50 and three 20s). Fails with `InsufficientCash` if the drawer
+    /// cannot make the amount exactly.
+    pub fn plan_payout(&self, amount: &Money) -> Result<CashBreakdown, TillError> {
+        if amount.amount <= Decimal::ZERO {
+            return Err(TillError::NonPositiveAmount);
+        }
+        let mut left = amount.amount;
+        let mut pieces = Vec::new();
+        for held in self.contents(&amount.currency) {
+            let fit = (left / held.value).round_dp_with_strategy(0, RoundingStrategy::ToZero);
+            let count = fit.to_minor_units(0).and_then(|n| u64::try_from(n).ok()).unwrap_or(0).min(held.count);
+            if count > 0 {
+                left -= Decimal::from(count) * held.value;
+                pieces.push(DenominationCount { value: held.value, count });
+            }
+        }
+        if !left.is_zero() {
+            return Err(TillError::InsufficientCash(amount.clone()));
+        }
+        Ok(CashBreakdown { amount: amount.clone(), pieces, remainder: Money::new(Decimal::ZERO, &amount.currency) })
+    }
+
+    /// Add the pieces of `breakdown` to the drawer.
+    pub fn put(&mut self, breakdown: &CashBreakdown) {
+        for p in &breakdown.pieces {
+            self.load(&breakdown.amount.currency, p.value, p.count);
+        }
+    }
+
+    /// Remove the pieces of `breakdown`, planned by `plan_payout`, from the
+    /// drawer. Counts never go below zero.
+    pub fn take(&mut self, breakdown: &CashBreakdown) {
+        if let Some(cash) = self.cash.get_mut(&breakdown.amount.currency) {
+            for p in &breakdown.pieces {
+                if let Some(held) = cash.get_mut(&p.value) {
+                    *held = held.saturating_sub(p.count);
+                }
+            }
+        }
+    }
+
+    /// Compare the drawer with `counted`, a physical count of it, currency
+    /// by currency.
+    pub fn balance(&self, counted: &Till) -> TillReport {
+        let mut codes: Vec<&str> = self.currencies();
+        codes.extend(counted.currencies());
+        codes.sort();
+        codes.dedup();
+        let currencies = codes
+            .into_iter()
+            .map(|code| {
+                let mut values: Vec<Decimal> = self.contents(code).iter().chain(&counted.contents(code)).map(|p| p.value).collect();
+                values.sort_by(|a, b| b.cmp(a));
+                values.dedup();
+                TillBalance {
+                    expected: self.total(code),
+                    counted: counted.total(code),
+                    denominations: values
+                        .into_iter()
+                        .map(|value| DenominationBalance { value, expected: self.count(code, value), counted: counted.count(code, value) })
+                        .collect(),
+                }
+            })
+            .collect();
+        TillReport { currencies }
+    }
+}
+
+/// Expected and counted pieces of one denomination.
+#[derive(Debug, Clone, PartialEq)]
+pub struct DenominationBalance {
+    pub value: Decimal,
+    pub expected: u64,
+    pub counted: u64,
+}
+
+/// Expected and counted cash in one currency, with each denomination
+/// either side holds, largest first.
+#[derive(Debug, Clone)]
+pub struct TillBalance {
+    pub expected: Money,
+    pub counted: Money,
+    pub denominations: Vec<DenominationBalance>,
+}
+
+impl TillBalance {
+    /// Counted less expected: positive when the drawer is over, negative
+    /// when it is short.
+    pub fn difference(&self) -> Money {
+        Money::new(self.counted.amount - self.expected.amount, &self.expected.currency)
+    }
+
+    /// Whether every denomination's count matches. A drawer can total right
+    /// and still be off, e.g. five 20s counted in place of a 100.
+    pub fn is_balanced(&self) -> bool {
+        self.denominations.iter().all(|d| d.expected == d.counted)
+    }
+}
+
+/// An end-of-day till balancing: the drawer as the bank expects it against
+/// a physical count, by currency (see `Till::balance`).
+#[derive(Debug, Clone)]
+pub struct TillReport {
+    pub currencies: Vec<TillBalance>,
+}
+
+impl TillReport {
+    /// Whether every currency balances.
+    pub fn is_balanced(&self) -> bool {
+        self.currencies.iter().all(TillBalance::is_balanced)
+    }
+}
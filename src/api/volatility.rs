@@ -0,0 +1,50 @@
+use crate::api::account::DAY_COUNT_BASIS;
+use crate::api::date::Date;
+use crate::api::decimal::Decimal;
+
+/// Rate observations `Forex::volatility` looks back over unless told
+/// otherwise.
+pub const DEFAULT_WINDOW: usize = 30;
+
+/// How much a currency's rate has moved, from `Forex::volatility`: the
+/// sample standard deviation of its `returns` daily log returns between
+/// `start` and `end` (`daily`), and the same scaled to a year by
+/// √`DAY_COUNT_BASIS` (`annualized`), the units `RateModel::volatility`
+/// is given in. A return is taken between consecutive days with a rate
+/// on record, so days without one stretch the step rather than break it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RateVolatility {
+    pub code: String,
+    pub start: Date,
+    pub end: Date,
+    pub returns: usize,
+    pub daily: Decimal,
+    pub annualized: Decimal,
+}
+
+/// The volatility of `code` over `series`, its closing rates oldest first;
+/// `None` with fewer than two returns or a rate of zero or less.
+pub(crate) fn of_series(code: &str, series: &[(Date, Decimal)]) -> Option<RateVolatility> {
+    let (first, last) = (series.first()?, series.last()?);
+    let returns: Vec<f64> = series
+        .windows(2)
+        .map(|pair| match (pair[0].1.to_f64(), pair[1].1.to_f64()) {
+            (from, to) if from > 0.0 && to > 0.0 => Some((to / from).ln()),
+            _ => None,
+        })
+        .collect::<Option<_>>()?;
+    if returns.len() < 2 {
+        return None;
+    }
+    let mean = returns.iter().sum::<f64>() / returns.len() as f64;
+    let variance = returns.iter().map(|r| (r - mean).powi(2)).sum::<f64>() / (returns.len() - 1) as f64;
+    let daily = variance.sqrt();
+    Some(RateVolatility {
+        code: code.to_string(),
+        start: first.0,
+        end: last.0,
+        returns: returns.len(),
+        daily: Decimal::from_f64(daily)?.round_dp(8),
+        annualized: Decimal::from_f64(daily * f64::from(DAY_COUNT_BASIS).sqrt())?.round_dp(6),
+    })
+}
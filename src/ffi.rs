@@ -0,0 +1,295 @@
+//! C ABI over the engine, declared in `include/rust_forex.h`.
+//!
+//! A bank is an opaque `ForexBank *` created with `forex_bank_new` or
+//! `forex_bank_load` and released with `forex_bank_free`. Amounts cross the
+//! boundary as decimal strings ("1234.56") so they are never rounded through
+//! a float. Functions returning `int` give 0 on success and a negative code
+//! on failure; those returning a string or handle give NULL. Either way
+//! `forex_last_error` describes the most recent failure on the calling
+//! thread. Strings returned by the library are freed with `forex_string_free`.
+use std::cell::RefCell;
+use std::ffi::{c_char, c_int, CStr, CString};
+use std::ptr;
+
+use crate::api::account::TransactionType;
+use crate::api::bank::{Bank, BankError};
+use crate::api::config::Config;
+use crate::api::decimal::Decimal;
+use crate::api::error::Error;
+use crate::api::money::Money;
+use crate::api::persist;
+
+/// An argument was NULL, not UTF-8, or not a valid amount.
+pub const FOREX_INVALID_ARGUMENT: c_int = -1;
+/// The bank refused the operation (unknown account, insufficient funds, ...).
+pub const FOREX_REFUSED: c_int = -2;
+/// Reading or writing a snapshot failed.
+pub const FOREX_IO: c_int = -3;
+
+thread_local! {
+    static LAST_ERROR: RefCell<Option<CString>> = const { RefCell::new(None) };
+}
+
+/// Why a call failed, before it is reported through `LAST_ERROR`.
+enum Failure {
+    Argument(String),
+    Refused(Error),
+}
+
+impl From<BankError> for Failure {
+    fn from(e: BankError) -> Self {
+        Failure::Refused(e.into())
+    }
+}
+
+impl From<Error> for Failure {
+    fn from(e: Error) -> Self {
+        Failure::Refused(e)
+    }
+}
+
+impl Failure {
+    /// Record the message for `forex_last_error` and return the status code.
+    fn report(self) -> c_int {
+        let (code, msg) = match self {
+            Failure::Argument(msg) => (FOREX_INVALID_ARGUMENT, msg),
+            Failure::Refused(Error::Io(e)) => (FOREX_IO, e.to_string()),
+            Failure::Refused(e) => (FOREX_REFUSED, e.to_string()),
+        };
+        set_last_error(&msg);
+        code
+    }
+}
+
+fn set_last_error(msg: &str) {
+    let msg = CString::new(msg.replace('\0', " ")).unwrap_or_default();
+    LAST_ERROR.with(|e| *e.borrow_mut() = Some(msg));
+}
+
+/// Turn a `Result` into a status code.
+fn status(result: Result<(), Failure>) -> c_int {
+    match result {
+        Ok(()) => 0,
+        Err(failure) => failure.report(),
+    }
+}
+
+/// Turn a `Result` into an owned C string, or NULL on failure.
+fn string_result(result: Result<String, Failure>) -> *mut c_char {
+    match result.and_then(|s| CString::new(s).map_err(|_| Failure::Argument(String::from("result contains NUL")))) {
+        Ok(s) => s.into_raw(),
+        Err(failure) => {
+            failure.report();
+            ptr::null_mut()
+        }
+    }
+}
+
+/// Borrow a required C string argument.
+///
+/// # Safety
+/// `ptr` must be NULL or point to a NUL-terminated string that outlives the call.
+unsafe fn text<'a>(ptr: *const c_char, name: &str) -> Result<&'a str, Failure> {
+    if ptr.is_null() {
+        return Err(Failure::Argument(format!("{} is NULL", name)));
+    }
+    // SAFETY: non-null and NUL-terminated per the caller's contract.
+    unsafe { CStr::from_ptr(ptr) }
+        .to_str()
+        .map_err(|_| Failure::Argument(format!("{} is not UTF-8", name)))
+}
+
+/// Borrow an optional C string argument; NULL means absent.
+///
+/// # Safety
+/// As for `text`.
+unsafe fn optional_text<'a>(ptr: *const c_char, name: &str) -> Result<Option<&'a str>, Failure> {
+    if ptr.is_null() { Ok(None) } else { unsafe { text(ptr, name) }.map(Some) }
+}
+
+/// Parse a positive decimal amount argument.
+///
+/// # Safety
+/// As for `text`.
+unsafe fn amount(ptr: *const c_char) -> Result<Decimal, Failure> {
+    let raw = unsafe { text(ptr, "amount") }?;
+    match raw.trim().parse::<Decimal>() {
+        Ok(v) if v > Decimal::ZERO => Ok(v),
+        _ => Err(Failure::Argument(format!("invalid amount {}", raw))),
+    }
+}
+
+/// Borrow the bank behind a handle.
+///
+/// # Safety
+/// `bank` must be NULL or a live handle from `forex_bank_new`/`forex_bank_load`.
+unsafe fn bank_mut<'a>(bank: *mut Bank) -> Result<&'a mut Bank, Failure> {
+    // SAFETY: a live, exclusively used handle per the caller's contract.
+    unsafe { bank.as_mut() }.ok_or_else(|| Failure::Argument(String::from("bank is NULL")))
+}
+
+/// Create a bank with the built-in configuration and no accounts.
+#[unsafe(no_mangle)]
+pub extern "C" fn forex_bank_new() -> *mut Bank {
+    Box::into_raw(Box::new(Config::default().build_bank()))
+}
+
+/// Load a bank from the snapshot file at `path`; NULL on failure.
+///
+/// # Safety
+/// `path` must be NULL or a NUL-terminated string.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn forex_bank_load(path: *const c_char) -> *mut Bank {
+    let loaded = unsafe { text(path, "path") }.and_then(|p| persist::load(p).map_err(|e| Failure::Refused(e.into())));
+    match loaded {
+        Ok(bank) => Box::into_raw(Box::new(bank)),
+        Err(failure) => {
+            failure.report();
+            ptr::null_mut()
+        }
+    }
+}
+
+/// Write the bank to a snapshot file at `path`.
+///
+/// # Safety
+/// `bank` must be a live handle and `path` a NUL-terminated string.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn forex_bank_save(bank: *mut Bank, path: *const c_char) -> c_int {
+    status((|| {
+        let bank = unsafe { bank_mut(bank) }?;
+        let path = unsafe { text(path, "path") }?;
+        persist::save(bank, path).map_err(|e| Failure::Refused(e.into()))
+    })())
+}
+
+/// Release a bank. NULL is ignored.
+///
+/// # Safety
+/// `bank` must be NULL or a live handle, and is invalid afterwards.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn forex_bank_free(bank: *mut Bank) {
+    if !bank.is_null() {
+        // SAFETY: created by `Box::into_raw` in this module and freed once.
+        drop(unsafe { Box::from_raw(bank) });
+    }
+}
+
+/// Open an account named `name` in the base currency, protected by `pin`
+/// unless it is NULL. Refused if `name` is already an account's name or
+/// alias.
+///
+/// # Safety
+/// `bank` must be a live handle; `name` and `pin` NULL or NUL-terminated.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn forex_account_open(bank: *mut Bank, name: *const c_char, pin: *const c_char) -> c_int {
+    status((|| {
+        let bank = unsafe { bank_mut(bank) }?;
+        let name = unsafe { text(name, "name") }?;
+        let pin = unsafe { optional_text(pin, "pin") }?;
+        let acct = bank.create_account(name)?;
+        if let Some(pin) = pin {
+            acct.set_pin(pin);
+        }
+        Ok(())
+    })())
+}
+
+/// Deposit `amount` (in the account's currency) into account `name`.
+///
+/// # Safety
+/// `bank` must be a live handle; the strings NULL or NUL-terminated.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn forex_deposit(bank: *mut Bank, name: *const c_char, amount: *const c_char, pin: *const c_char) -> c_int {
+    unsafe { post(bank, name, amount, pin, TransactionType::Deposit) }
+}
+
+/// Withdraw `amount` (in the account's currency) from account `name`.
+///
+/// # Safety
+/// As for `forex_deposit`.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn forex_withdraw(bank: *mut Bank, name: *const c_char, amount: *const c_char, pin: *const c_char) -> c_int {
+    unsafe { post(bank, name, amount, pin, TransactionType::Withdraw) }
+}
+
+/// # Safety
+/// As for `forex_deposit`.
+unsafe fn post(bank: *mut Bank, name: *const c_char, amount: *const c_char, pin: *const c_char, tx_type: TransactionType) -> c_int {
+    status((|| {
+        let bank = unsafe { bank_mut(bank) }?;
+        let name = unsafe { text(name, "name") }?;
+        let amount = unsafe { self::amount(amount) }?;
+        let pin = unsafe { optional_text(pin, "pin") }?;
+        let currency = bank
+            .accounts
+            .iter()
+            .find(|a| a.name == name)
+            .map(|a| a.currency.clone())
+            .ok_or_else(|| BankError::AccountNotFound(name.to_string()))?;
+        bank.post_transaction(name, tx_type, Money::new(amount, &currency), "", pin)?;
+        Ok(())
+    })())
+}
+
+/// Balance of account `name` as "<amount> <code>", e.g. "1234.56 PHP",
+/// rounded to the currency's minor unit. Free with `forex_string_free`.
+///
+/// # Safety
+/// `bank` must be a live handle and `name` a NUL-terminated string.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn forex_balance(bank: *mut Bank, name: *const c_char) -> *mut c_char {
+    string_result((|| {
+        let bank = unsafe { bank_mut(bank) }?;
+        let name = unsafe { text(name, "name") }?;
+        let acct = bank
+            .accounts
+            .iter()
+            .find(|a| a.name == name)
+            .ok_or_else(|| BankError::AccountNotFound(name.to_string()))?;
+        Ok(money_text(bank, &acct.get_balance()))
+    })())
+}
+
+/// Convert `amount` of currency `from` into `to` at the current rates, as
+/// "<amount> <code>" rounded to the target's minor unit. Free with
+/// `forex_string_free`.
+///
+/// # Safety
+/// `bank` must be a live handle; the strings NUL-terminated.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn forex_convert(bank: *mut Bank, amount: *const c_char, from: *const c_char, to: *const c_char) -> *mut c_char {
+    string_result((|| {
+        let bank = unsafe { bank_mut(bank) }?;
+        let amount = unsafe { self::amount(amount) }?;
+        let from = unsafe { text(from, "from") }?.to_uppercase();
+        let to = unsafe { text(to, "to") }?.to_uppercase();
+        let converted = bank.forex.convert(&Money::new(amount, &from), &to).map_err(Error::from)?;
+        Ok(money_text(bank, &converted))
+    })())
+}
+
+fn money_text(bank: &Bank, m: &Money) -> String {
+    let dp = bank.forex.decimals(&m.currency) as usize;
+    format!("{:.*} {}", dp, m.amount, m.currency)
+}
+
+/// The message for the last failure on this thread, or NULL if none. The
+/// pointer stays valid until the next failing call on the same thread; do
+/// not free it.
+#[unsafe(no_mangle)]
+pub extern "C" fn forex_last_error() -> *const c_char {
+    LAST_ERROR.with(|e| e.borrow().as_ref().map_or(ptr::null(), |s| s.as_ptr()))
+}
+
+/// Free a string returned by this library. NULL is ignored.
+///
+/// # Safety
+/// `s` must be NULL or a string returned by this library, freed once.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn forex_string_free(s: *mut c_char) {
+    if !s.is_null() {
+        // SAFETY: created by `CString::into_raw` in this module.
+        drop(unsafe { CString::from_raw(s) });
+    }
+}
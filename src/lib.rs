@@ -0,0 +1,7 @@
+//! Forex and banking engine: currencies and exchange rates, accounts with
+//! interest, and the `Bank` that ties them together. The console UI in the
+//! `rust_forex` binary is one consumer; other programs can depend on this
+//! library directly.
+pub mod api { pub mod account; pub mod alert; pub mod bank; pub mod budget; pub mod calendar; pub mod cheque; pub mod compaction; pub mod comparison; pub mod compliance; pub mod config; pub mod conversion_log; pub mod credential; pub mod customer; pub mod date; pub mod dca; pub mod decimal; pub mod delivery; pub mod denomination; pub mod error; pub mod event; pub mod fee; pub mod format; pub mod forex; pub mod forward; pub mod goal; pub mod hold; pub mod idempotency; pub mod import; pub mod inbox; pub mod integrity; pub mod ledger; pub mod limit_order; pub mod limit_profile; pub mod loan; pub mod market; pub mod money; pub mod monte_carlo; pub mod moving_average; pub mod notify; pub mod parallel; pub mod paydown; pub mod pending; pub mod persist; pub mod portfolio; pub mod position; pub mod rate_stats; pub mod rates; pub mod receipt; pub mod replay; pub mod role; pub mod rounding; pub mod scenario; pub mod search; pub mod seed; pub mod service_charge; pub mod simulation; pub mod standing_order; pub mod statement; pub mod summary; pub mod sweep; pub mod tag; pub mod tax; pub mod till; pub mod volatility; }
+pub mod ffi;
+pub mod prelude;
@@ -3,29 +3,100 @@ Last names: Robenta*, Lee, Ortega, De Leon
 Language: Rust
 Paradigm(s): Object-oriented with builder pattern, and a procedural flow for the console app 
 ********************/
-mod api { pub mod account; pub mod bank; pub mod forex; }
-mod view { pub mod console; pub mod console_util; }
-use api::forex::Forex;
-use api::bank::Bank;
+use std::path::Path;
+
+use rust_forex::api;
+mod view { pub mod cli; pub mod console; pub mod console_util; pub mod export; #[cfg(feature = "server")] pub mod graphql; pub mod i18n; pub mod journal; pub mod json; pub mod line_editor; #[cfg(feature = "server")] pub mod metrics; pub mod repl; pub mod rpc; #[cfg(feature = "server")] pub mod server; pub mod table; pub mod webhook; #[cfg(feature = "server")] pub mod websocket; }
+use api::config::{Config, CONFIG_FILE};
+use api::persist;
+use api::seed::DemoData;
 use view::console::ConsoleApp;
+use view::i18n::{set_lang, tr, Lang};
+use view::journal::Journal;
 
 fn main() {
-    // Initial exchange rate retrieved from bsp.gov.ph on 10/20/2025
-    let forex = Forex::new()
-        .create_currency("PHP", "Philippine Peso", 1.0)
-        .create_currency("USD", "US Dollar", 58.1130)
-        .create_currency("JPY", "Japanese Yen", 0.3865)
-        .create_currency("GBP", "British Pound", 78.0632)
-        .create_currency("EUR", "Euro", 67.7598)
-        .create_currency("CNY", "Chinese Yuan", 8.1531)
-        .set_base_rate("PHP");
+    let mut args: Vec<String> = std::env::args().skip(1).collect();
+    if let Some(pos) = args.iter().position(|a| a == "--lang") {
+        let tag = args.get(pos + 1).cloned().unwrap_or_default();
+        let Some(lang) = Lang::parse(&tag) else {
+            eprintln!("Unknown language '{}'. Use --lang en or --lang fil.", tag);
+            std::process::exit(2);
+        };
+        set_lang(lang);
+        args.drain(pos..pos + 2);
+    }
+    let config_path = match args.iter().position(|a| a == "--config") {
+        Some(pos) => {
+            let Some(path) = args.get(pos + 1).cloned() else {
+                eprintln!("--config needs a file name.");
+                std::process::exit(2);
+            };
+            args.drain(pos..pos + 2);
+            Some(path)
+        }
+        None => Path::new(CONFIG_FILE).exists().then(|| CONFIG_FILE.to_string()),
+    };
+    let mut config = match config_path.map(|path| (Config::load(&path), path)) {
+        Some((Ok(config), _)) => config,
+        Some((Err(e), path)) => {
+            eprintln!("Cannot load {}: {}", path, e);
+            std::process::exit(2);
+        }
+        None => Config::default(),
+    };
+    if let Err(e) = config.apply_env(|name| std::env::var(name).ok()) {
+        eprintln!("Invalid environment setting: {}", e);
+        std::process::exit(2);
+    }
+    if let Err(e) = view::cli::check_macros(&config.macros) {
+        eprintln!("Invalid macro: {}", e);
+        std::process::exit(2);
+    }
 
-    let bank = Bank::new()
-        .set_forex(forex)
-        .set_annual_interest(0.05)
-        .set_base_currency("PHP")
-        .build();
+    let fresh = args.iter().any(|a| a == "--fresh");
+    let demo = args.iter().any(|a| a == "--demo");
+    let read_only = args.iter().any(|a| a == "--read-only");
+    args.retain(|a| a != "--fresh" && a != "--demo" && a != "--read-only");
 
-    let mut app = ConsoleApp::new(bank);
-    app.run();
-}
\ No newline at end of file
+    if args.is_empty() {
+        let data = Path::new(&config.data_file);
+        let mut bank = if demo {
+            let mut bank = config.build_bank();
+            match DemoData::new(bank.now() as u64).populate(&mut bank) {
+                Ok(report) => println!("{}", tr!("session.demo", report.accounts.len(), report.transactions, report.seed)),
+                Err(e) => {
+                    eprintln!("Cannot generate demo data: {}", e);
+                    std::process::exit(2);
+                }
+            }
+            bank
+        } else if !fresh && data.exists() {
+            match persist::load(data) {
+                Ok(bank) => {
+                    println!("{}", tr!("session.resumed", config.data_file));
+                    bank
+                }
+                Err(e) => {
+                    eprintln!("Cannot load {}: {}. Start with --fresh to ignore it.", config.data_file, e);
+                    std::process::exit(2);
+                }
+            }
+        } else {
+            config.build_bank()
+        };
+        bank.set_read_only(read_only);
+        bank.set_teller(config.teller.as_deref());
+        let mut app = ConsoleApp::new(bank, &config.data_file, view::cli::event_bus(&config));
+        app.journal = config.journal_file.as_deref().map(Journal::new);
+        app.macros = config.macros.clone();
+        app.run();
+    } else if fresh {
+        eprintln!("--fresh only applies to the interactive console; use --data to pick another file.");
+        std::process::exit(2);
+    } else if demo {
+        eprintln!("--demo only applies to the interactive console; use the demo command instead.");
+        std::process::exit(2);
+    } else {
+        std::process::exit(view::cli::run(&args, &config, read_only));
+    }
+}
@@ -0,0 +1,8 @@
+//! One-line import for the common API surface:
+//! `use rust_forex::prelude::*;`
+pub use crate::api::account::{Account, AccountError, TransactionType};
+pub use crate::api::bank::{Bank, BankError};
+pub use crate::api::decimal::Decimal;
+pub use crate::api::error::Error;
+pub use crate::api::forex::{Currency, Forex, ForexError};
+pub use crate::api::money::{CurrencyMismatch, Money};
@@ -0,0 +1,4804 @@
+use std::collections::BTreeMap;
+use std::fmt;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+use crate::api::account::{adjust_for_inflation, convert_forecast, summarize_forecast, Account, AccountError, ForecastStep, InterestForecast, InterestMethod, Promotion, RateChange, Transaction, TransactionType};
+use crate::api::alert::AlertSettings;
+use crate::api::bank::{Bank, BankError, EndOfDay, TransferReceipt, EXCHANGE_RATE_DP};
+use crate::api::budget::{Envelope, EnvelopeStatus};
+use crate::api::cheque::Cheque;
+use crate::api::compaction::{self, CompactionReport};
+use crate::api::comparison::{AccountComparison, ComparedAccount};
+use crate::api::compliance::ConversionLimit;
+use crate::api::config::{Config, MacroConfig};
+use crate::api::conversion_log::{ConversionFilter, ConversionRecord, DailyTurnover};
+use crate::api::date::{Date, Month};
+use crate::api::dca::DcaSimulation;
+use crate::api::decimal::{Decimal, RoundingStrategy};
+use crate::api::error::Error;
+use crate::api::fee::{Conversion, ConversionLeg, FeeBasis, FeeSchedule, FeeTier, PairSpread};
+use crate::api::forex::{BasketQuote, Currency, ForexError, RateSource, RateType, BASKET_RATE_DP, RATE_HISTORY_DAYS};
+use crate::api::forward::{ForwardContract, ForwardSide, ForwardValuation};
+use crate::api::goal::{GoalProgress, SavingsGoal};
+use crate::api::hold::Hold;
+use crate::api::import::{CsvRows, ImportReport};
+use crate::api::integrity::IntegrityReport;
+use crate::api::limit_order::{LimitOrder, LimitOrderFill};
+use crate::api::limit_profile::{LimitKind, LimitProfile, LimitTarget};
+use crate::api::loan::{AmortizationRow, Loan, PaymentFrequency, MAX_TERM};
+use crate::api::market::{MarketSimulator, MarketStep, RateModel};
+use crate::api::money::Money;
+use crate::api::monte_carlo::{DepositBehavior, Distribution, MonteCarloOutcome, DEFAULT_PATHS, MAX_PATHS, PERCENTILES};
+use crate::api::moving_average::{self, AveragedClose, MovingAverage};
+use crate::api::delivery::FileDelivery;
+use crate::api::notify::{ConsoleNotifier, EventBus, FileNotifier};
+use crate::api::paydown::{PaydownChoice, PaydownComparison};
+use crate::api::pending::{HeldTransaction, PendingTransaction, TransactionState};
+use crate::api::persist;
+use crate::api::portfolio::Portfolio;
+use crate::api::position::PositionReport;
+use crate::api::rate_stats::{RateStats, RateTrend, TREND_DAYS};
+use crate::api::rates::YearBasis;
+use crate::api::receipt::ExchangeReceipt;
+use crate::api::replay::{RateHistory, RateReplay};
+use crate::api::scenario::{self, Compounding, Scenario, ScenarioComparison};
+use crate::api::search::TransactionQuery;
+use crate::api::seed::{DemoData, DemoReport, DEFAULT_DEMO_ACCOUNTS, DEFAULT_DEMO_DAYS};
+use crate::api::service_charge::{MaintainingBalance, ServiceCharges};
+use crate::api::standing_order::{StandingOrder, MAX_INTERVAL_DAYS};
+use crate::api::statement::StatementFormat;
+use crate::api::summary::MonthlySummary;
+use crate::api::tax::TaxCertificate;
+use crate::api::sweep::SweepRule;
+use crate::api::tag::{self, TagTotal};
+use crate::api::volatility::{RateVolatility, DEFAULT_WINDOW};
+use crate::view::journal::{script_line, Journal};
+use crate::view::json::Json;
+use crate::view::{repl, rpc};
+#[cfg(feature = "server")]
+use crate::view::server;
+use crate::view::table::{Align, Table};
+use crate::view::webhook::WebhookNotifier;
+
+const USAGE: &str = "\
+Usage: rust_forex [--config FILE] [--data FILE] [--read-only] [--json] <command> [options]
+       rust_forex [--config FILE] [--data FILE] [--read-only] [--json] --script SCRIPT
+       rust_forex [--config FILE] [--data FILE] [--read-only] [--json] MACRO [ARG...]
+       rust_forex [--config FILE] [--data FILE] [--read-only] --serve ADDR
+       rust_forex [--config FILE] [--data FILE] [--read-only] --rpc
+       rust_forex [--config FILE] [--data FILE] [--read-only] --repl
+
+With no command, the interactive menu starts.
+
+Commands:
+  rates [--window DAYS]                          List exchange rates with their annualized volatility
+                                                 over the last DAYS daily moves (30 by default)
+  rate-history --code CODE [--days N] [--sma N] [--ema N]
+                                                 CODE's last N daily closing rates (30 by default)
+                                                 with their simple and exponential moving averages
+                                                 over N days (20 by default)
+  rate-summary [--code CODE] [--start YYYY-MM-DD] [--end YYYY-MM-DD]
+                                                 Open, high, low, average, and close of each
+                                                 currency's daily rates, over every close on
+                                                 record by default
+  rate --code CODE --rate N [--source NAME]      Record an exchange rate published by NAME (e.g.
+                                                 BSP; manual by default) and fill limit orders
+  cash-rate --code CODE [--rate N]               Quote CODE in cash at N, or at its transfer rate
+                                                 without --rate
+  convert --from CODE --to CODE --amount N [--rates transfer|cash]
+                                                 Quote a conversion at transfer (default) or cash
+                                                 rates
+  dca --from CODE --to CODE --amount N --rates R,R,... | --history F [--every DAYS]
+                                                 Simulate buying N of FROM every period vs. a lump sum,
+                                                 at given rates or every DAYS days of a rate file
+  basket --code CODE --name NAME --weights CODE:PCT,...
+                                                 Define a currency basket worth 1 base unit today
+  baskets                                        List baskets and their components' weights
+  fee-schedule --tiers FROM:RATE,... | --tiers none
+                                                 Charge RATE on conversions worth FROM or more in
+                                                 the base currency, or no fees
+  spread --pair CODE/CODE --rate R | --rate none
+                                                 Charge R on exchanges between the pair instead of
+                                                 the fee tiers, or stop
+  fees                                           List the conversion fee tiers and pair spreads
+  conversion-limit --code CODE [--per-transaction N|none] [--per-day N|none]
+                   [--passphrase P]
+                                                 Cap conversions from and into CODE; leaving both
+                                                 out removes the cap. Needs the admin passphrase
+  conversion-limits                              List the conversion limits by currency
+  limit-profile --target type:NAME|kyc:STATUS [--max-deposit N|none] [--max-withdrawal N|none]
+                [--max-daily-conversion N|none] [--passphrase P]
+                                                 Cap deposits, withdrawals, and a day's conversions
+                                                 in the base currency for an account type or KYC
+                                                 status; leaving all out removes the profile
+  limit-profiles [--account NAME]                List the limit profiles, or those an account
+                                                 falls under
+  conversions [--account NAME] [--currency CODE] [--start YYYY-MM-DD] [--end YYYY-MM-DD]
+                                                 List executed conversions, oldest first
+  receipt --number N                             Print the exchange receipt numbered N
+  turnover [--account NAME] [--currency CODE] [--start YYYY-MM-DD] [--end YYYY-MM-DD]
+                                                 Daily FX turnover in the base currency
+  accounts                                       List accounts and balances
+  alias --account NAME --alias ALIAS             Let ALIAS stand for the account wherever an
+                                                 account name is accepted
+  unalias --alias ALIAS                          Remove an alias
+  aliases                                        List aliases and their accounts
+  archive --account NAME                         Archive an emptied account: it keeps its history
+                                                 but leaves listings and reports
+  unarchive --account NAME                       Restore an archived account
+  archived                                       List archived accounts
+  register --account NAME [--currency CODE] [--pin PIN]
+                                                 Open an account, in the base currency by default
+  deposit --account NAME --amount N [--currency CODE] [--memo M] [--pin PIN]
+          [--tags TAG,...] [--idempotency-key KEY]
+  withdraw --account NAME --amount N [--memo M] [--category C] [--pin PIN]
+           [--tags TAG,...] [--idempotency-key KEY]
+                                                 Post N; a repeated KEY returns the first
+                                                 result instead of posting again
+  alert --account NAME [--below N|none] [--withdrawal-above N|none] [--inactive-days N|none]
+                                                 Alert when the balance falls below N, a withdrawal
+                                                 goes above N, or nothing is posted for N days
+  alerts --account NAME                          Show an account's alerts
+  service-charge --account NAME [--monthly N|none] [--maintaining BALANCE:CHARGE|none]
+                                                 Charge N every month, or CHARGE for a month whose
+                                                 average daily balance is below BALANCE
+  service-charges --account NAME                 Show an account's service charges
+  account-type --account NAME --type NAME|none [--passphrase P]
+                                                 Set an account's type, which picks its limit
+                                                 profile. Needs the admin passphrase
+  hold --account NAME --amount N --reason TEXT [--expires YYYY-MM-DD] [--pin PIN]
+                                                 Keep N back from the available balance without
+                                                 posting it, for a week unless given an expiry
+  holds --account NAME                           List an account's active holds
+  release --account NAME --id N [--pin PIN]      End a hold, making its amount available again
+  capture --account NAME --id N [--amount N] [--pin PIN]
+                                                 Withdraw N, or the whole hold, and end the hold
+  deposit-cheque --account NAME --amount N --number NO [--pin PIN]
+                                                 Credit a cheque now; it is available once it
+                                                 clears after the clearing period
+  cheques --account NAME [--status clearing|cleared|returned|all]
+                                                 List an account's cheques (all by default)
+  return-cheque --account NAME --id N --reason TEXT
+                                                 Reverse a cheque that bounced before clearing
+  auto-convert --account NAME --currencies CODE,...|none
+                                                 Convert deposits in these currencies into the
+                                                 account's currency as they are posted
+  transfer --from NAME --to NAME --amount N [--currency CODE] [--pin PIN]
+           [--override-limits PASSPHRASE] [--idempotency-key KEY]
+                                                 Move N between accounts; the admin passphrase
+                                                 lifts the conversion limits
+  exchange --from NAME --to NAME --amount N [--pin PIN] [--override-limits PASSPHRASE]
+                                                 Exchange N of FROM's currency into the holder's
+                                                 account TO in another currency
+  approvals [--status pending|posted|rejected|all] [--account NAME]
+                                                 List withdrawals and transfers held for an admin's
+                                                 approval (pending by default), or those of NAME
+  approve --id N [--passphrase P]                Post a held withdrawal or transfer
+  reject --id N --reason TEXT [--passphrase P]   Refuse a held withdrawal or transfer
+  balance --account NAME                         Show an account balance, and what is available
+                                                 after holds, uncleared cheques, and pending
+                                                 withdrawals and transfers
+  history --account NAME                         List an account's transactions
+  tag --account NAME --seq N --tags TAG,...|none Replace the tags of transaction N (its Seq in
+                                                 history)
+  tags --account NAME [--start YYYY-MM-DD] [--end YYYY-MM-DD]
+                                                 Inflows and outflows per tag, over the whole
+                                                 history by default
+  statement --account NAME [--format F] [--start YYYY-MM-DD] [--end YYYY-MM-DD]
+                                                 Print a csv, ofx, qif, or printable html statement
+  import --account NAME --file F [--delimiter C] [--pin PIN]
+                                                 Load a CSV transaction history, skipping bad rows
+  forecast --account NAME --days N [--rate R] [--inflation R] [--step S] [--currency CODE]
+           [--since YYYY-MM-DD]
+                                                 Day-by-day interest forecast, optionally at rate R
+                                                 and in real terms at inflation R; --step weekly,
+                                                 monthly, or yearly prints one row per period;
+                                                 --currency adds the amounts in CODE at today's rate;
+                                                 --since starts it on a past date, earning on the
+                                                 balances the transactions left each day
+  interest-rate --account NAME --rate R [--date YYYY-MM-DD]
+                                                 Change an account's annual rate from a date
+                                                 (default today)
+  interest-rates --account NAME                  List an account's annual rates by effective date
+  promotion --account NAME --bonus R --days N [--start YYYY-MM-DD]
+                                                 Add R to an account's rate for N days from a date
+                                                 (default today), then revert
+  end-promotion --account NAME                   End an account's promotion now
+  interest-payout --account NAME --to NAME|none  Pay an account's interest into another account,
+                                                 or add it to the account again
+  year-basis --basis 365|actual|360 [--account NAME]
+                                                 Count 365 days a year, 365 or 366, or 360 in daily
+                                                 interest, for an account or the whole bank
+  interest-method --method daily|average|minimum [--account NAME]
+                                                 Accrue interest on each day's balance, or on the
+                                                 month's average daily or lowest balance credited
+                                                 at its end
+  interest-grace --days N [--account NAME]       Let deposits earn interest N days after they are
+                                                 posted (0 from the day itself), for an account or
+                                                 the whole bank
+  scenarios --account NAME --days N --scenarios NAME:RATE[:COMPOUNDING[:AMOUNT:FREQUENCY]],...
+                                                 Compare growth under several rates, compounding
+                                                 modes, and contributions
+  compare --account NAME --with NAME --days N    Forecast two accounts side by side and find the
+                                                 day one overtakes the other
+  monte-carlo --account NAME --days N [--paths N] [--seed S] [--rate-volatility R]
+              [--drift R] [--volatility R] [--deposit N --every DAYS [--chance P]]
+                                                 Spread of where a balance may end up over N
+                                                 randomized paths of its rate, exchange rate
+                                                 (volatility 0.10 by default), and deposits
+  pnl [--account NAME]                           FX profit and loss of foreign-currency accounts
+  portfolio [--account NAME] [--date YYYY-MM-DD] Value holdings in the base currency, of every
+                                                 account when --account is absent
+  interest --days N | --since YYYY-MM-DD         Post N days of interest to every account, or
+                                                 what each balance earned from a date through
+                                                 yesterday, counting each transaction from its day
+  goal --account NAME --name GOAL --target N --date YYYY-MM-DD
+  goals --account NAME [--frequency F]           Show progress on an account's goals
+  envelope --account NAME --category C --limit N Set a monthly budget for a spending category
+  budget --account NAME [--date YYYY-MM-DD]      Show spending against each budget that month
+  summary [--month YYYY-MM] [--format text|csv]  Each account's inflow, outflow, interest, fees,
+                                                 and net change over a month (default this one)
+  tax-certificate --account NAME [--year YYYY] [--format text|csv|html]
+                                                 Interest earned and tax withheld over a year (default
+                                                 last year); html prints to PDF from a browser
+  loan --account NAME --amount N --rate R --term N [--frequency F] [--pin PIN]
+  schedule --loan ID                             Show a loan's amortization schedule
+  repay --loan ID [--pin PIN]                    Pay a loan's next installment
+  paydown --loan ID | --principal N --rate R --term N [--frequency F]
+          --amount N --deposit-rate R --days N   Compare paying N off a loan, or a quote in the base
+                                                 currency, with depositing it, after withholding tax
+  order --from NAME --to NAME --amount N --every DAYS [--currency CODE]
+        [--start YYYY-MM-DD] [--pin PIN]         Set up a standing order
+  orders                                         List standing orders and their next dates
+  skip --order ID                                Skip a standing order's next transfer
+  sweep --from NAME --to NAME --above N [--pin PIN]
+                                                 Move what an account holds above N to another
+                                                 at each end of day
+  sweeps                                         List sweep rules
+  cancel --order ID | --limit ID | --sweep ID    Cancel a standing order, limit order, or sweep
+  forward --account NAME --side buy|sell --amount N --currency CODE --rate R
+          --date YYYY-MM-DD [--pin PIN]          Book an FX forward for a value date
+  forwards                                       Open forwards marked to market at spot
+  limit --account NAME --amount N --from CODE --to CODE --rate R [--expires YYYY-MM-DD]
+        [--average sma:N|ema:N] [--pin PIN]      Convert when the rate reaches R, and is past its
+                                                 moving average if given, until the expiry date
+                                                 or cancelled
+  limits                                         The order book: open limit orders, what is
+                                                 filled, and today's spot rates
+  amend --limit ID [--amount N] [--rate R] [--expires YYYY-MM-DD|none]
+        [--average sma:N|ema:N|none]             Change an open limit order's terms
+  eod [--date YYYY-MM-DD]                        End of day: settle forwards, run standing orders
+  simulate --days N [--seed S] [--drift R] [--volatility R] [--models CODE:DRIFT:VOL,...]
+                                                 Move rates as random walks for N days, running
+                                                 each end of day (volatility 0.10 by default)
+  replay --file F [--delimiter C] [--start YYYY-MM-DD] [--end YYYY-MM-DD]
+                                                 Feed historical rates in day by day, running
+                                                 each end of day
+  compact --before YYYY-MM-DD --archive FILE     Roll transactions posted before the date into one
+                                                 opening balance per account, appending them to
+                                                 the CSV FILE
+  demo [--accounts N] [--days N] [--seed S]      Open N demo accounts (12 by default) with N days
+                                                 of generated history and rates (90 by default)
+  verify                                         Check the ledger's invariants and list violations
+  rounding                                       Rounding residue per currency and its net effect
+                                                 in the base currency
+  help                                           Show this message
+
+A command that changes an account also takes --if-version N: it is refused if
+the account (a transfer's source) is no longer at version N, as reported by
+`--json balance`.
+
+Commands only an admin may run (rate, cash-rate, basket, fee-schedule, spread,
+archive, unarchive, alert, service-charge, auto-convert, interest-rate,
+promotion, end-promotion, interest-payout, year-basis, interest-method,
+interest-grace, interest, eod, simulate, replay, compact, and demo) also take
+--passphrase P, the admin passphrase, and are refused without it.
+
+A script holds one command per line, e.g. `deposit --account Alice --amount 100`;
+blank lines and lines starting with # are skipped. It stops at the first error.
+A [[macro]] in forex.toml runs its commands the same way, with each ARG put in
+for its parameter in turn.
+
+State is loaded from and saved to FILE (default: data_file in forex.toml,
+else bank.snapshot). A fresh bank is set up from --config FILE, else forex.toml
+when it exists, else the built-in defaults.
+--read-only refuses every command that would change the bank and never
+writes FILE, so a shared file can be inspected safely.
+--json prints every result, and errors, as one JSON object per line.
+--serve answers HTTP requests on ADDR (e.g. 127.0.0.1:8080) with the same
+JSON, in a build with the server feature; see the README for the routes.
+--rpc reads JSON-RPC 2.0 requests from stdin, one per line, and answers each
+on stdout; methods are the command names above, params their options.
+--repl prompts for commands typed as above, with the main options given
+bare in order (e.g. `deposit Alice 100`); type help there for the forms.";
+
+/// Command names accepted by `parse`.
+pub const COMMANDS: &[&str] = &[
+    "rates", "rate-history", "rate-summary", "rate", "cash-rate", "convert", "dca", "basket", "baskets", "fee-schedule", "spread", "fees", "conversion-limit", "conversion-limits", "limit-profile", "limit-profiles", "conversions", "receipt", "turnover", "accounts", "alias", "unalias", "aliases", "archive", "unarchive", "archived", "register", "deposit", "withdraw", "alert", "alerts", "service-charge", "service-charges", "account-type", "hold", "holds", "release", "capture", "deposit-cheque", "cheques", "return-cheque", "auto-convert", "transfer", "exchange", "approvals", "approve", "reject", "balance", "history", "tag", "tags", "statement", "import", "forecast", "interest-rate", "interest-rates", "promotion", "end-promotion", "interest-payout", "year-basis", "interest-method", "interest-grace", "scenarios", "compare", "monte-carlo", "pnl", "portfolio", "interest", "goal", "goals", "envelope", "budget", "summary", "tax-certificate", "loan", "schedule",
+    "repay", "paydown", "order", "orders", "skip", "sweep", "sweeps", "cancel", "forward", "forwards", "limit", "limits", "amend", "eod", "simulate", "replay", "compact", "demo", "verify", "rounding", "help",
+];
+
+/// Commands only an admin may run, as the console keeps their menus to the
+/// Admin role: `parse` wraps them in `Command::Admin` with their
+/// `--passphrase`.
+pub const ADMIN_COMMANDS: &[&str] = &[
+    "rate", "cash-rate", "basket", "fee-schedule", "spread", "archive", "unarchive", "alert", "service-charge", "auto-convert", "interest-rate", "promotion", "end-promotion", "interest-payout", "year-basis", "interest-method", "interest-grace", "interest", "eod", "simulate", "replay", "compact", "demo",
+];
+
+/// One non-interactive command, parsed from the command line.
+#[derive(Debug, Clone)]
+pub enum Command {
+    /// Volatility is measured over `window` daily returns.
+    Rates { window: usize },
+    /// The last `days` closes, with `sma`- and `ema`-day averages.
+    RateHistory { code: String, days: usize, sma: usize, ema: usize },
+    /// Every currency without `code`; bounds left out reach each
+    /// currency's first or last close.
+    RateSummary { code: Option<String>, start: Option<Date>, end: Option<Date> },
+    /// `source` is who published the rate; `None` is manual entry.
+    Rate { code: String, rate: Decimal, source: Option<RateSource> },
+    /// `None` quotes the currency in cash at its transfer rate again.
+    CashRate { code: String, rate: Option<Decimal> },
+    Convert { from: String, to: String, amount: Decimal, rate_type: RateType },
+    /// Buys `amount` of `from` worth of `to` once per rate; rates are in
+    /// units of `from` per unit of `to`.
+    Dca { from: String, to: String, amount: Decimal, rates: DcaRates },
+    /// `weights` are fractions of the basket's value, adding up to 1.
+    Basket { code: String, name: String, weights: Vec<(String, Decimal)> },
+    Baskets,
+    FeeSchedule(FeeSchedule),
+    /// `None` removes the pair's spread.
+    Spread { pair: (String, String), rate: Option<Decimal> },
+    Fees,
+    /// `passphrase` is the admin passphrase.
+    ConversionLimit { code: String, limit: ConversionLimit, passphrase: String },
+    ConversionLimits,
+    /// `passphrase` is the admin passphrase.
+    LimitProfile { target: LimitTarget, profile: LimitProfile, passphrase: String },
+    LimitProfiles { account: Option<String> },
+    Conversions(ConversionFilter),
+    Receipt { number: usize },
+    Turnover(ConversionFilter),
+    Accounts,
+    Alias { account: String, alias: String },
+    Unalias { alias: String },
+    Aliases,
+    Archive { account: String },
+    Unarchive { account: String },
+    Archived,
+    /// `currency` defaults to the base currency.
+    Register { account: String, currency: Option<String>, pin: Option<String> },
+    /// `category` files a withdrawal under one of the account's budget
+    /// envelopes; `tags` are normalized by `tag::normalize`;
+    /// `idempotency_key` makes a retry return the first result.
+    /// `currency`, for deposits only, defaults to the account's currency.
+    Post {
+        tx_type: TransactionType,
+        account: String,
+        amount: Decimal,
+        currency: Option<String>,
+        memo: String,
+        category: Option<String>,
+        tags: Vec<String>,
+        pin: Option<String>,
+        idempotency_key: Option<String>,
+    },
+    /// `currencies` replaces the ones converted before; empty stops
+    /// converting.
+    AutoConvert { account: String, currencies: Vec<String> },
+    /// Each alert left out keeps its setting; `Some(None)` turns it off.
+    Alert { account: String, below: Option<Option<Decimal>>, withdrawal_above: Option<Option<Decimal>>, inactive_days: Option<Option<u32>> },
+    Alerts { account: String },
+    /// `None` leaves a charge as it is; `Some(None)` turns it off.
+    ServiceCharge { account: String, monthly: Option<Option<Decimal>>, maintaining: Option<Option<MaintainingBalance>> },
+    ServiceCharges { account: String },
+    /// `None` clears the account's type.
+    AccountType { account: String, kind: Option<String>, passphrase: String },
+    /// `amount` is in the account's currency; `expires` defaults to
+    /// `DEFAULT_HOLD_DAYS` from today.
+    Hold { account: String, amount: Decimal, reason: String, expires: Option<Date>, pin: Option<String> },
+    Holds { account: String },
+    Release { account: String, id: usize, pin: Option<String> },
+    /// `amount` defaults to the whole hold.
+    Capture { account: String, id: usize, amount: Option<Decimal>, pin: Option<String> },
+    /// `amount` is in the account's currency; `number` is the cheque's.
+    DepositCheque { account: String, amount: Decimal, number: String, pin: Option<String> },
+    /// `status` is a `Cheque::status` name, or `None` for every cheque.
+    Cheques { account: String, status: Option<String> },
+    ReturnCheque { account: String, id: usize, reason: String },
+    /// `currency` defaults to the source account's currency;
+    /// `override_limits`, the admin passphrase, lifts the conversion limits.
+    Transfer {
+        from: String,
+        to: String,
+        amount: Decimal,
+        currency: Option<String>,
+        pin: Option<String>,
+        override_limits: Option<String>,
+        idempotency_key: Option<String>,
+    },
+    /// `amount` is in `from`'s currency.
+    Exchange { from: String, to: String, amount: Decimal, pin: Option<String>, override_limits: Option<String> },
+    /// `status` is a `TransactionState` name, or `None` for every request;
+    /// `account` keeps those paying from or into it.
+    Approvals { status: Option<String>, account: Option<String> },
+    /// `passphrase` is the admin passphrase.
+    Approve { id: usize, passphrase: String },
+    Reject { id: usize, passphrase: String, reason: String },
+    Balance { account: String },
+    History { account: String },
+    /// `sequence` is the transaction's bank-wide posting number; empty
+    /// `tags` removes them.
+    Tag { account: String, sequence: u64, tags: Vec<String> },
+    /// The period defaults to the whole history, as for statements.
+    TagReport { account: String, start: Option<Date>, end: Option<Date> },
+    /// `format` defaults to CSV; the period to the whole history.
+    Statement { account: String, format: StatementFormat, start: Option<Date>, end: Option<Date> },
+    /// Loads the CSV history in `file`, split on `delimiter`, into the
+    /// account.
+    Import { account: String, file: PathBuf, delimiter: char, pin: Option<String> },
+    /// `rate` is a what-if annual rate, as a fraction, in place of the
+    /// account's own; `inflation` adds real amounts to each day; `step`
+    /// summarizes the days into one row per week, month, or year; `since`
+    /// starts a value-dated forecast on that date instead of today.
+    Forecast {
+        account: String,
+        days: usize,
+        rate: Option<Decimal>,
+        inflation: Option<Decimal>,
+        step: ForecastStep,
+        currency: Option<String>,
+        since: Option<Date>,
+    },
+    /// Changes the account's annual rate, a fraction, from `date` (today
+    /// when absent).
+    InterestRate { account: String, rate: Decimal, date: Option<Date> },
+    InterestRates { account: String },
+    /// Adds `bonus`, a fraction, to the account's rate for `days` days
+    /// from `start` (today when absent).
+    Promotion { account: String, bonus: Decimal, days: usize, start: Option<Date> },
+    EndPromotion { account: String },
+    /// `None` adds the account's interest to it again.
+    InterestPayout { account: String, to: Option<String> },
+    YearBasis { account: Option<String>, basis: YearBasis },
+    /// For every account, and new ones, when `account` is absent.
+    InterestMethod { account: Option<String>, method: InterestMethod },
+    /// For every account, and new ones, when `account` is absent.
+    InterestGrace { account: Option<String>, days: u32 },
+    /// Grows the account's balance under each scenario for `days` days.
+    Scenarios { account: String, days: usize, scenarios: Vec<Scenario> },
+    /// Forecasts `account` and `with` side by side for `days` days.
+    Compare { account: String, with: String, days: usize },
+    /// Runs `paths` paths of `days` days; `seed` defaults to the current
+    /// time. `fx` moves a foreign account's exchange rate, by default at
+    /// 0.10 volatility; a base-currency account takes none.
+    MonteCarlo { account: String, days: usize, paths: usize, seed: Option<u64>, rate_volatility: Decimal, fx: Option<RateModel>, deposits: Option<DepositBehavior> },
+    /// Every foreign-currency account when `account` is absent.
+    Pnl { account: Option<String> },
+    /// Every account when `account` is absent; `date` defaults to today.
+    Portfolio { account: Option<String>, date: Option<Date> },
+    /// Posts `days` of interest to every account, or what each earned
+    /// value-dated `since` a date; the parser sets exactly one.
+    Interest { days: Option<usize>, since: Option<Date> },
+    Goal { account: String, name: String, target: Decimal, date: Date },
+    /// Contributions are computed per `frequency` period.
+    Goals { account: String, frequency: PaymentFrequency },
+    /// `limit` is per calendar month.
+    Envelope { account: String, category: String, limit: Decimal },
+    /// The month containing `date`, by default today.
+    Budget { account: String, date: Option<Date> },
+    /// `month` defaults to the current one; `csv` prints CSV instead of a
+    /// table.
+    Summary { month: Option<Month>, csv: bool },
+    /// `year` defaults to last year; `format` is CSV or HTML, or a table
+    /// when absent.
+    TaxCertificate { account: String, year: Option<i32>, format: Option<StatementFormat> },
+    /// `rate` is annual, as a fraction; `term` counts payments.
+    Loan { account: String, amount: Decimal, rate: Decimal, term: u32, frequency: PaymentFrequency, pin: Option<String> },
+    Schedule { loan: usize },
+    Repay { loan: usize, pin: Option<String> },
+    /// Pays `amount` off `loan`, in its currency, or deposits it at
+    /// `deposit_rate`.
+    Paydown { loan: PaydownLoan, amount: Decimal, deposit_rate: Decimal, days: usize },
+    /// `currency` defaults to the source account's currency and `start`,
+    /// the first due date, to today.
+    Order { from: String, to: String, amount: Decimal, currency: Option<String>, every: u32, start: Option<Date>, pin: Option<String> },
+    Orders,
+    Skip { order: usize },
+    Cancel { order: usize },
+    /// `above` is in the source account's currency.
+    Sweep { from: String, to: String, above: Decimal, pin: Option<String> },
+    Sweeps,
+    CancelSweep { sweep: usize },
+    /// `rate` is in units of the account's currency per unit of `currency`.
+    Forward { account: String, side: ForwardSide, amount: Decimal, currency: String, rate: Decimal, date: Date, pin: Option<String> },
+    Forwards,
+    /// Converts `amount` of `from` into `to`; `rate` is in units of the
+    /// account's currency per unit of the other one. Without `expires`
+    /// the order is good till cancelled, and without `average` it fills
+    /// on its limit alone.
+    Limit { account: String, amount: Decimal, from: String, to: String, rate: Decimal, expires: Option<Date>, average: Option<MovingAverage>, pin: Option<String> },
+    Limits,
+    /// Terms left out keep their current values; `expires` is
+    /// `Some(None)` for good till cancelled, `average` `Some(None)` to
+    /// fill on the limit alone.
+    AmendLimit { limit: usize, amount: Option<Decimal>, rate: Option<Decimal>, expires: Option<Option<Date>>, average: Option<Option<MovingAverage>> },
+    CancelLimit { limit: usize },
+    /// `date` defaults to today.
+    EndOfDay { date: Option<Date> },
+    /// Moves rates for `days` days by `models`, else by `default`; `seed`
+    /// defaults to the current time.
+    Simulate { days: usize, seed: Option<u64>, default: RateModel, models: Vec<(String, RateModel)> },
+    /// Feeds the rates in `file` between `start` and `end` into the bank.
+    Replay { file: PathBuf, delimiter: char, start: Option<Date>, end: Option<Date> },
+    /// Compacts transactions posted before `before` into `archive`.
+    Compact { before: Date, archive: PathBuf },
+    /// Opens `accounts` demo accounts with `days` of history; `seed`
+    /// defaults to the current time.
+    Demo { accounts: usize, days: usize, seed: Option<u64> },
+    Verify,
+    Rounding,
+    Help,
+    /// `command`, refused unless its account (see `versioned_account`) is
+    /// still at `version`; from `--if-version`.
+    Versioned { version: u64, command: Box<Command> },
+    /// One of `ADMIN_COMMANDS`, run only once `passphrase` unlocks the
+    /// admin role (see `Bank::as_admin`).
+    Admin { passphrase: String, command: Box<Command> },
+}
+
+/// Where `dca` takes its rates from.
+#[derive(Debug, Clone)]
+pub enum DcaRates {
+    Listed(Vec<Decimal>),
+    /// Every `every`-th day of a historical rate file (see
+    /// `RateHistory::read_csv`), from the first quoting both currencies.
+    History { file: PathBuf, every: usize },
+}
+
+/// The loan `paydown` compares against.
+#[derive(Debug, Clone)]
+pub enum PaydownLoan {
+    /// A loan on the books, by ID, as it stands.
+    Booked(usize),
+    /// A loan quote in the base currency, with no payments made.
+    Quote { principal: Decimal, rate: Decimal, term: u32, frequency: PaymentFrequency },
+}
+
+impl Command {
+    /// Commands that change the bank and must be saved afterwards.
+    pub fn mutates(&self) -> bool {
+        matches!(
+            self,
+            Command::Rate { .. }
+                | Command::CashRate { .. }
+                | Command::Basket { .. }
+                | Command::FeeSchedule(_)
+                | Command::Spread { .. }
+                | Command::ConversionLimit { .. }
+                | Command::LimitProfile { .. }
+                | Command::Alias { .. }
+                | Command::Unalias { .. }
+                | Command::Archive { .. }
+                | Command::Unarchive { .. }
+                | Command::Register { .. }
+                | Command::Post { .. }
+                | Command::AutoConvert { .. }
+                | Command::Alert { .. }
+                | Command::ServiceCharge { .. }
+                | Command::AccountType { .. }
+                | Command::Hold { .. }
+                | Command::Release { .. }
+                | Command::Capture { .. }
+                | Command::DepositCheque { .. }
+                | Command::ReturnCheque { .. }
+                | Command::Tag { .. }
+                | Command::Transfer { .. }
+                | Command::Exchange { .. }
+                | Command::Approve { .. }
+                | Command::Reject { .. }
+                | Command::Import { .. }
+                | Command::InterestRate { .. }
+                | Command::Promotion { .. }
+                | Command::EndPromotion { .. }
+                | Command::InterestPayout { .. }
+                | Command::YearBasis { .. }
+                | Command::InterestMethod { .. }
+                | Command::InterestGrace { .. }
+                | Command::Goal { .. }
+                | Command::Envelope { .. }
+                | Command::Interest { .. }
+                | Command::Loan { .. }
+                | Command::Repay { .. }
+                | Command::Order { .. }
+                | Command::Skip { .. }
+                | Command::Cancel { .. }
+                | Command::Sweep { .. }
+                | Command::CancelSweep { .. }
+                | Command::Forward { .. }
+                | Command::Limit { .. }
+                | Command::AmendLimit { .. }
+                | Command::CancelLimit { .. }
+                | Command::EndOfDay { .. }
+                | Command::Simulate { .. }
+                | Command::Replay { .. }
+                | Command::Compact { .. }
+                | Command::Demo { .. }
+        ) || matches!(self, Command::Versioned { command, .. } | Command::Admin { command, .. } if command.mutates())
+    }
+
+    /// The command itself, under any `Versioned` or `Admin` wrapping.
+    #[cfg(feature = "server")]
+    pub fn unwrapped(&self) -> &Command {
+        match self {
+            Command::Versioned { command, .. } | Command::Admin { command, .. } => command.unwrapped(),
+            command => command,
+        }
+    }
+
+    /// The account whose version `--if-version` checks: the one a command
+    /// changes, or a transfer's source. `None` for commands that change no
+    /// single account.
+    pub fn versioned_account(&self) -> Option<&str> {
+        match self {
+            Command::Alias { account, .. }
+            | Command::Archive { account }
+            | Command::Unarchive { account }
+            | Command::Post { account, .. }
+            | Command::AutoConvert { account, .. }
+            | Command::Alert { account, .. }
+            | Command::ServiceCharge { account, .. }
+            | Command::AccountType { account, .. }
+            | Command::Hold { account, .. }
+            | Command::Release { account, .. }
+            | Command::Capture { account, .. }
+            | Command::DepositCheque { account, .. }
+            | Command::ReturnCheque { account, .. }
+            | Command::Tag { account, .. }
+            | Command::Import { account, .. }
+            | Command::InterestRate { account, .. }
+            | Command::Promotion { account, .. }
+            | Command::EndPromotion { account }
+            | Command::InterestPayout { account, .. }
+            | Command::Goal { account, .. }
+            | Command::Envelope { account, .. }
+            | Command::Loan { account, .. }
+            | Command::Forward { account, .. }
+            | Command::Limit { account, .. } => Some(account),
+            Command::Transfer { from, .. } | Command::Exchange { from, .. } | Command::Order { from, .. } | Command::Sweep { from, .. } => Some(from),
+            Command::YearBasis { account, .. } | Command::InterestMethod { account, .. } | Command::InterestGrace { account, .. } => account.as_deref(),
+            Command::Admin { command, .. } => command.versioned_account(),
+            _ => None,
+        }
+    }
+}
+
+/// Why a CLI invocation failed: bad arguments (exit code 2) or a command
+/// the bank refused (exit code 1).
+#[derive(Debug)]
+pub enum CliError {
+    Usage(String),
+    Failed(Error),
+}
+
+impl CliError {
+    /// Process exit code for this failure.
+    fn exit_code(&self) -> i32 {
+        match self {
+            CliError::Usage(_) => 2,
+            CliError::Failed(_) => 1,
+        }
+    }
+}
+
+impl fmt::Display for CliError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CliError::Usage(msg) => write!(f, "{}", msg),
+            CliError::Failed(e) => write!(f, "{}", e),
+        }
+    }
+}
+
+impl From<Error> for CliError {
+    fn from(e: Error) -> Self {
+        CliError::Failed(e)
+    }
+}
+
+impl From<BankError> for CliError {
+    fn from(e: BankError) -> Self {
+        CliError::Failed(e.into())
+    }
+}
+
+impl From<AccountError> for CliError {
+    fn from(e: AccountError) -> Self {
+        CliError::Failed(e.into())
+    }
+}
+
+impl From<ForexError> for CliError {
+    fn from(e: ForexError) -> Self {
+        CliError::Failed(e.into())
+    }
+}
+
+impl From<io::Error> for CliError {
+    fn from(e: io::Error) -> Self {
+        CliError::Failed(e.into())
+    }
+}
+
+/// Run the command in `args` (program name excluded), or every command in the
+/// `--script` file, against the bank stored in the data file (`--data`, else
+/// the configured `data_file`), or against a fresh bank built from `config`
+/// when the file does not exist yet. Events the commands cause go to the
+/// configured notifiers (see `event_bus`). With `read_only`, the bank is
+/// opened read-only (see `Bank::set_read_only`). Prints the results and
+/// returns the process exit code.
+pub fn run(args: &[String], config: &Config, read_only: bool) -> i32 {
+    let json = args.iter().any(|a| a == "--json");
+    let rpc = args.iter().any(|a| a == "--rpc");
+    let repl = args.iter().any(|a| a == "--repl");
+    let args: Vec<String> = args.iter().filter(|a| !matches!(a.as_str(), "--json" | "--rpc" | "--repl")).cloned().collect();
+    let outcome = split_flags(&args).and_then(|(positional, mut flags)| {
+        let data = PathBuf::from(flags.remove("data").unwrap_or_else(|| config.data_file.clone()));
+        if rpc {
+            return match flags.is_empty() && positional.is_empty() {
+                true => Ok((data, Mode::Rpc)),
+                false => Err(CliError::Usage(String::from("--rpc takes no command or other options"))),
+            };
+        }
+        if repl {
+            return match flags.is_empty() && positional.is_empty() {
+                true => Ok((data, Mode::Repl)),
+                false => Err(CliError::Usage(String::from("--repl takes no command or other options"))),
+            };
+        }
+        if let Some(addr) = flags.remove("serve") {
+            return match flags.is_empty() && positional.is_empty() {
+                true => Ok((data, Mode::Serve(addr))),
+                false => Err(CliError::Usage(String::from("--serve takes no command or other options"))),
+            };
+        }
+        match flags.remove("script") {
+            Some(script) if positional.is_empty() && flags.is_empty() => Ok((data, Mode::Script(PathBuf::from(script)))),
+            Some(_) => Err(CliError::Usage(String::from("--script takes no command or other options"))),
+            None => match positional.split_first().and_then(|(name, args)| Some((config.macros.iter().find(|m| m.name == *name)?, args))) {
+                Some((definition, _)) if !flags.is_empty() => Err(CliError::Usage(format!("macro {} takes no options", definition.name))),
+                Some((definition, args)) => {
+                    let args: Vec<String> = args.iter().map(|a| a.to_string()).collect();
+                    Ok((data, Mode::Macro(definition.name.clone(), expand_macro(definition, &args)?)))
+                }
+                None => {
+                    let words = command_words(&positional, &flags);
+                    Ok((data, Mode::Single(parse(&positional, flags)?, words)))
+                }
+            },
+        }
+    });
+    let (data, mode) = match outcome {
+        Ok(parsed) => parsed,
+        Err(e) => return report(&e, json),
+    };
+    let mut bank = if data.exists() {
+        match persist::load(&data) {
+            Ok(bank) => bank,
+            Err(e) => return report(&e.into(), json),
+        }
+    } else {
+        config.build_bank()
+    };
+    bank.set_read_only(read_only);
+    bank.set_teller(config.teller.as_deref());
+    let journal = config.journal_file.as_deref().map(Journal::new);
+    let mut session = Session { bank, data, json, notifiers: event_bus(config), journal };
+    match mode {
+        Mode::Single(command, words) => match session.execute(&command) {
+            Ok(output) if command.mutates() => {
+                let code = session.save();
+                if code == 0 {
+                    session.journal("cli", words, &output);
+                }
+                code
+            }
+            Ok(_) => 0,
+            Err(e) => report(&e, json),
+        },
+        Mode::Script(script) => session.run_script(&script),
+        Mode::Macro(name, commands) => session.run_macro(&name, commands),
+        #[cfg(feature = "server")]
+        Mode::Serve(addr) => match server::serve(&addr, session.bank, session.data, session.notifiers) {
+            Ok(()) => 0,
+            Err(e) => report(&e.into(), json),
+        },
+        #[cfg(not(feature = "server"))]
+        Mode::Serve(addr) => report(&CliError::Usage(format!("--serve {} needs a build with the server feature (cargo build --features server)", addr)), json),
+        Mode::Rpc => rpc::run(session.bank, session.data, session.notifiers),
+        Mode::Repl => repl::run(session.bank, session.data, session.notifiers, session.journal, &config.macros),
+    }
+}
+
+/// What one invocation does: a single command from the arguments (and the
+/// words it was given, for the journal), every line of a script file, the
+/// commands a macro expanded to, an HTTP server on an address, JSON-RPC on
+/// stdio, or commands typed at a prompt.
+enum Mode {
+    Single(Command, Vec<String>),
+    Script(PathBuf),
+    Macro(String, Vec<Vec<String>>),
+    Serve(String),
+    Rpc,
+    Repl,
+}
+
+/// The bank being worked on, where it is saved, how results are printed,
+/// where its events are sent, and the journal its changes are recorded in.
+struct Session {
+    bank: Bank,
+    data: PathBuf,
+    json: bool,
+    notifiers: EventBus,
+    journal: Option<Journal>,
+}
+
+impl Session {
+    /// Execute `command`, print its result, and return it.
+    fn execute(&mut self, command: &Command) -> Result<Output, CliError> {
+        let output = execute(&mut self.bank, command)?;
+        report_notify_failures(self.notifiers.drain(&mut self.bank));
+        if self.json {
+            println!("{}", output.to_json(&self.bank));
+        } else {
+            println!("{}", output.to_text(&self.bank));
+        }
+        Ok(output)
+    }
+
+    /// Record the command `words` from `origin` in the journal, if there is
+    /// one, with its defaults pinned (see `pin_defaults`). A failed write is
+    /// reported; the command itself already succeeded.
+    fn journal(&self, origin: &str, words: Vec<String>, output: &Output) {
+        let Some(journal) = &self.journal else {
+            return;
+        };
+        if let Err(e) = journal.record(origin, &pin_defaults(words, output)) {
+            eprintln!("Journal failed: {}", e);
+        }
+    }
+
+    /// Execute `script` line by line. Blank lines and lines starting with `#`
+    /// are skipped. Stops at the first failing line; commands that already
+    /// ran are kept and saved, just as if they had been run one at a time,
+    /// and the ones that changed the bank are journaled once saved.
+    fn run_script(&mut self, script: &Path) -> i32 {
+        let text = match fs::read_to_string(script) {
+            Ok(text) => text,
+            Err(e) => {
+                let msg = format!("cannot read {}: {}", script.display(), e);
+                return report(&CliError::Failed(Error::Io(io::Error::new(e.kind(), msg))), self.json);
+            }
+        };
+        let lines = text
+            .lines()
+            .enumerate()
+            .map(|(n, line)| (n + 1, line.trim()))
+            .filter(|(_, line)| !line.is_empty() && !line.starts_with('#'))
+            .map(|(n, line)| (n, line.to_string(), tokenize(line)));
+        self.run_lines("script", &script.display().to_string(), lines)
+    }
+
+    /// Execute the commands a macro named `name` expanded to (see
+    /// `expand_macro`), in order and with the same rules as a script.
+    fn run_macro(&mut self, name: &str, commands: Vec<Vec<String>>) -> i32 {
+        let lines = commands.into_iter().enumerate().map(|(i, words)| (i + 1, script_line(&words).unwrap_or_else(|| words.join(" ")), Ok(words)));
+        self.run_lines(&format!("macro {}", name), name, lines)
+    }
+
+    /// Execute `lines`, each its number in `source`, its text, and its
+    /// words, journaling them from `origin`; see `run_script`.
+    fn run_lines(&mut self, origin: &str, source: &str, lines: impl Iterator<Item = (usize, String, Result<Vec<String>, CliError>)>) -> i32 {
+        let mut ran = Vec::new();
+        let mut code = 0;
+        for (n, line, words) in lines {
+            match words.and_then(|words| {
+                let command = parse_words(&words)?;
+                let output = self.execute(&command)?;
+                Ok((command, words, output))
+            }) {
+                Ok((command, words, output)) if command.mutates() => ran.push((words, output)),
+                Ok(_) => {}
+                Err(e) => {
+                    if !self.json {
+                        eprintln!("{}:{}: {}", source, n, line);
+                    }
+                    code = report(&e, self.json);
+                    break;
+                }
+            }
+        }
+        if !ran.is_empty() {
+            let saved = self.save();
+            if saved == 0 {
+                for (words, output) in ran {
+                    self.journal(origin, words, &output);
+                }
+            }
+            if code == 0 {
+                code = saved;
+            }
+        }
+        code
+    }
+
+    /// Save the bank to the data file, returning the exit code.
+    fn save(&self) -> i32 {
+        match persist::save(&self.bank, &self.data) {
+            Ok(()) => 0,
+            Err(e) => report(&e.into(), self.json),
+        }
+    }
+}
+
+/// The notifiers `config` asks for: stderr, an event log file, and each
+/// `[[webhook]]`; and its statement channels: a directory and, with the
+/// `smtp` feature, e-mail.
+pub fn event_bus(config: &Config) -> EventBus {
+    let mut bus = EventBus::new();
+    if config.notify_console {
+        bus.subscribe(Box::new(ConsoleNotifier));
+    }
+    if let Some(path) = &config.notify_file {
+        bus.subscribe(Box::new(FileNotifier::new(path)));
+    }
+    for hook in &config.webhooks {
+        match WebhookNotifier::start(hook.clone()) {
+            Ok(notifier) => bus.subscribe(Box::new(notifier)),
+            Err(e) => eprintln!("{}", e),
+        }
+    }
+    if let Some(dir) = &config.statements_dir {
+        bus.subscribe_statements(Box::new(FileDelivery::new(dir)));
+    }
+    if let Some(server) = &config.smtp_server {
+        #[cfg(feature = "smtp")]
+        bus.subscribe_statements(Box::new(crate::api::delivery::SmtpDelivery::new(server, &config.smtp_from)));
+        #[cfg(not(feature = "smtp"))]
+        eprintln!("smtp_server {} ignored: built without the smtp feature", server);
+    }
+    bus
+}
+
+/// Report notifiers that failed; the operation itself already succeeded.
+pub fn report_notify_failures(failures: Vec<io::Error>) {
+    for e in failures {
+        eprintln!("Notification failed: {}", e);
+    }
+}
+
+/// Print `err` and return its exit code. Text goes to stderr; in JSON mode
+/// the error is an object on stdout so pipelines see it in-band.
+fn report(err: &CliError, json: bool) -> i32 {
+    if json {
+        println!("{}", error_json(err));
+    } else if let CliError::Usage(msg) = err {
+        eprintln!("{}\n\n{}", msg, USAGE);
+    } else {
+        eprintln!("Error: {}", err);
+    }
+    err.exit_code()
+}
+
+/// `{"error": message, "kind": "usage" | "failed"}`, as printed by `--json`.
+/// A limit profile's refusal adds `"limit"`, the cap that was exceeded.
+pub fn error_json(err: &CliError) -> Json {
+    let kind = match err {
+        CliError::Usage(_) => "usage",
+        CliError::Failed(_) => "failed",
+    };
+    let mut fields = vec![("error", Json::str(err)), ("kind", Json::str(kind))];
+    if let CliError::Failed(Error::Bank(BankError::LimitExceeded(breach))) = err {
+        fields.push((
+            "limit",
+            Json::object([
+                ("account", Json::str(&breach.account)),
+                ("target", Json::str(&breach.target)),
+                ("movement", Json::str(breach.kind.name())),
+                ("currency", Json::str(&breach.currency)),
+                ("limit", Json::num(breach.limit)),
+                ("used", match breach.kind {
+                    LimitKind::Conversion => Json::num(breach.used),
+                    _ => Json::Null,
+                }),
+                ("amount", Json::num(breach.amount)),
+            ]),
+        ));
+    }
+    Json::object(fields)
+}
+
+/// `words` with the seed or date the command ran with added when `output`
+/// shows it was left to default (see `Output::pinned`), so a replay of the
+/// journal repeats it rather than rolling again.
+pub fn pin_defaults(mut words: Vec<String>, output: &Output) -> Vec<String> {
+    if let Some((key, value)) = output.pinned()
+        && !words.iter().any(|w| w.strip_prefix("--") == Some(key))
+    {
+        words.extend([format!("--{}", key), value]);
+    }
+    words
+}
+
+/// The commands of `definition` as words, each `{param}` replaced by the
+/// argument given for it. Arguments are put in after the commands are
+/// split into words, so one holding spaces stays one word.
+pub fn expand_macro(definition: &MacroConfig, args: &[String]) -> Result<Vec<Vec<String>>, CliError> {
+    if args.len() != definition.params.len() {
+        return Err(CliError::Usage(format!("macro {} takes {}", definition.name, definition.usage())));
+    }
+    definition
+        .commands
+        .iter()
+        .map(|command| Ok(tokenize(command)?.iter().map(|word| substitute(word, &definition.params, args)).collect()))
+        .collect()
+}
+
+/// `word` with each `{param}` replaced by the matching entry of `args`.
+fn substitute(word: &str, params: &[String], args: &[String]) -> String {
+    let mut out = String::with_capacity(word.len());
+    let mut rest = word;
+    while let Some(open) = rest.find('{') {
+        out.push_str(&rest[..open]);
+        rest = &rest[open..];
+        let arg = rest.find('}').and_then(|close| params.iter().position(|p| *p == rest[1..close]).map(|i| (close, &args[i])));
+        match arg {
+            Some((close, arg)) => {
+                out.push_str(arg);
+                rest = &rest[close + 1..];
+            }
+            None => {
+                out.push('{');
+                rest = &rest[1..];
+            }
+        }
+    }
+    out.push_str(rest);
+    out
+}
+
+/// Check `macros` against the commands: a macro may not take a command's
+/// name, and each of its commands must be one, written as a script line.
+pub fn check_macros(macros: &[MacroConfig]) -> Result<(), String> {
+    for definition in macros {
+        if COMMANDS.contains(&definition.name.as_str()) {
+            return Err(format!("macro {} has the name of a command", definition.name));
+        }
+        for command in &definition.commands {
+            let words = tokenize(command).map_err(|e| format!("macro {}: {}: {}", definition.name, command, e))?;
+            if !words.first().is_some_and(|w| COMMANDS.contains(&w.as_str())) {
+                return Err(format!("macro {}: {} does not start with a command", definition.name, command));
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Parse the words of one script line into a command.
+pub fn parse_words(words: &[String]) -> Result<Command, CliError> {
+    let (positional, flags) = split_flags(words)?;
+    parse(&positional, flags)
+}
+
+/// `positional` and then `flags` as `--key value` pairs: a command in the
+/// words a script line splits into.
+fn command_words(positional: &[&str], flags: &BTreeMap<String, String>) -> Vec<String> {
+    positional
+        .iter()
+        .map(|w| w.to_string())
+        .chain(flags.iter().flat_map(|(key, value)| [format!("--{}", key), value.clone()]))
+        .collect()
+}
+
+/// The lines of the usage text describing `command`: its synopsis and
+/// description, or `None` for an unknown command.
+pub fn usage_of(command: &str) -> Option<String> {
+    let entry = |line: &str| line.starts_with("  ") && !line.starts_with("   ") && line.split_whitespace().next() == Some(command);
+    let mut lines = USAGE.lines().skip_while(|line| !entry(line));
+    let first = lines.next()?;
+    let rest = lines.take_while(|line| line.starts_with("   "));
+    Some(std::iter::once(first).chain(rest).collect::<Vec<_>>().join("\n"))
+}
+
+/// Split a script line into words on whitespace. Double quotes group words,
+/// so `--memo "rent for May"` is one value.
+pub fn tokenize(line: &str) -> Result<Vec<String>, CliError> {
+    let mut words = Vec::new();
+    let mut word = String::new();
+    let mut in_word = false;
+    let mut quoted = false;
+    for ch in line.chars() {
+        match ch {
+            '"' => {
+                quoted = !quoted;
+                in_word = true;
+            }
+            c if c.is_whitespace() && !quoted => {
+                if in_word {
+                    words.push(std::mem::take(&mut word));
+                    in_word = false;
+                }
+            }
+            c => {
+                word.push(c);
+                in_word = true;
+            }
+        }
+    }
+    if quoted {
+        return Err(CliError::Usage(String::from("unterminated quote")));
+    }
+    if in_word {
+        words.push(word);
+    }
+    Ok(words)
+}
+
+/// Separate `args` into positional words and `--key value` options.
+fn split_flags(args: &[String]) -> Result<(Vec<&str>, BTreeMap<String, String>), CliError> {
+    let mut flags = BTreeMap::new();
+    let mut positional = Vec::new();
+    let mut iter = args.iter();
+    while let Some(arg) = iter.next() {
+        if let Some(key) = arg.strip_prefix("--") {
+            let value = iter
+                .next()
+                .ok_or_else(|| CliError::Usage(format!("missing value for --{}", key)))?;
+            flags.insert(key.to_string(), value.clone());
+        } else {
+            positional.push(arg.as_str());
+        }
+    }
+    Ok((positional, flags))
+}
+
+/// Build the command named by `positional` from its options. Every option
+/// must be used by the command.
+pub fn parse(positional: &[&str], mut flags: BTreeMap<String, String>) -> Result<Command, CliError> {
+    let if_version = flags
+        .remove("if-version")
+        .map(|raw| raw.parse::<u64>().map_err(|_| CliError::Usage(format!("invalid --if-version {} (expected a whole number)", raw))))
+        .transpose()?;
+    let passphrase = match positional {
+        [verb] if ADMIN_COMMANDS.contains(verb) => Some(flags.remove("passphrase").unwrap_or_default()),
+        _ => None,
+    };
+    let command = match positional {
+        ["rates"] => Command::Rates {
+            window: match flags.remove("window") {
+                None => DEFAULT_WINDOW,
+                Some(raw) => match raw.parse::<usize>() {
+                    Ok(v) if (2..=RATE_HISTORY_DAYS).contains(&v) => v,
+                    _ => return Err(CliError::Usage(format!("invalid --window {} (expected 2-{} days)", raw, RATE_HISTORY_DAYS))),
+                },
+            },
+        },
+        ["rate-history"] => Command::RateHistory {
+            code: required(&mut flags, "code")?.to_uppercase(),
+            days: history_days(&mut flags, "days", 30)?,
+            sma: history_days(&mut flags, "sma", moving_average::DEFAULT_DAYS)?,
+            ema: history_days(&mut flags, "ema", moving_average::DEFAULT_DAYS)?,
+        },
+        ["rate-summary"] => Command::RateSummary {
+            code: flags.remove("code").map(|code| code.to_uppercase()),
+            start: flags.remove("start").map(|raw| date(&raw, "start")).transpose()?,
+            end: flags.remove("end").map(|raw| date(&raw, "end")).transpose()?,
+        },
+        ["rate"] => Command::Rate {
+            code: required(&mut flags, "code")?.to_uppercase(),
+            rate: positive(&mut flags, "rate")?,
+            source: flags.remove("source").and_then(|s| RateSource::parse(&s)),
+        },
+        ["cash-rate"] => Command::CashRate {
+            code: required(&mut flags, "code")?.to_uppercase(),
+            rate: if flags.contains_key("rate") { Some(positive(&mut flags, "rate")?) } else { None },
+        },
+        ["convert"] => Command::Convert {
+            from: required(&mut flags, "from")?.to_uppercase(),
+            to: required(&mut flags, "to")?.to_uppercase(),
+            amount: positive(&mut flags, "amount")?,
+            rate_type: match flags.remove("rates") {
+                None => RateType::Transfer,
+                Some(raw) => RateType::parse(&raw).ok_or_else(|| CliError::Usage(format!("invalid --rates {} (expected transfer or cash)", raw)))?,
+            },
+        },
+        ["accounts"] => Command::Accounts,
+        ["alias"] => Command::Alias { account: required(&mut flags, "account")?, alias: required(&mut flags, "alias")? },
+        ["unalias"] => Command::Unalias { alias: required(&mut flags, "alias")? },
+        ["aliases"] => Command::Aliases,
+        ["archive"] => Command::Archive { account: required(&mut flags, "account")? },
+        ["unarchive"] => Command::Unarchive { account: required(&mut flags, "account")? },
+        ["archived"] => Command::Archived,
+        ["register"] => Command::Register {
+            account: required(&mut flags, "account")?,
+            currency: flags.remove("currency").map(|c| c.to_uppercase()),
+            pin: flags.remove("pin"),
+        },
+        [verb @ ("deposit" | "withdraw")] => Command::Post {
+            tx_type: if *verb == "deposit" { TransactionType::Deposit } else { TransactionType::Withdraw },
+            account: required(&mut flags, "account")?,
+            amount: positive(&mut flags, "amount")?,
+            currency: match flags.remove("currency") {
+                Some(_) if *verb == "withdraw" => return Err(CliError::Usage(String::from("--currency applies to deposits only"))),
+                currency => currency.map(|c| c.to_uppercase()),
+            },
+            memo: flags.remove("memo").unwrap_or_default(),
+            category: match flags.remove("category") {
+                Some(_) if *verb == "deposit" => return Err(CliError::Usage(String::from("--category applies to withdrawals only"))),
+                category => category,
+            },
+            tags: match flags.remove("tags") {
+                Some(raw) => tags(&raw)?,
+                None => Vec::new(),
+            },
+            pin: flags.remove("pin"),
+            idempotency_key: flags.remove("idempotency-key"),
+        },
+        ["alert"] => Command::Alert {
+            account: required(&mut flags, "account")?,
+            below: if flags.contains_key("below") { Some(limit_cap(&mut flags, "below")?) } else { None },
+            withdrawal_above: if flags.contains_key("withdrawal-above") { Some(limit_cap(&mut flags, "withdrawal-above")?) } else { None },
+            inactive_days: flags
+                .remove("inactive-days")
+                .map(|raw| match raw.trim() {
+                    "none" => Ok(None),
+                    days => days.parse().map(Some).map_err(|_| CliError::Usage(format!("invalid --inactive-days {} (expected a number of days or none)", raw))),
+                })
+                .transpose()?,
+        },
+        ["alerts"] => Command::Alerts { account: required(&mut flags, "account")? },
+        ["service-charge"] => Command::ServiceCharge {
+            account: required(&mut flags, "account")?,
+            monthly: if flags.contains_key("monthly") { Some(limit_cap(&mut flags, "monthly")?) } else { None },
+            maintaining: flags
+                .remove("maintaining")
+                .map(|raw| match raw.trim() {
+                    "none" => Ok(None),
+                    spec => spec
+                        .split_once(':')
+                        .and_then(|(balance, charge)| Some(MaintainingBalance { balance: balance.trim().parse().ok()?, charge: charge.trim().parse().ok()? }))
+                        .map(Some)
+                        .ok_or_else(|| CliError::Usage(format!("invalid --maintaining {} (expected BALANCE:CHARGE or none)", raw))),
+                })
+                .transpose()?,
+        },
+        ["service-charges"] => Command::ServiceCharges { account: required(&mut flags, "account")? },
+        ["account-type"] => Command::AccountType {
+            account: required(&mut flags, "account")?,
+            kind: Some(required(&mut flags, "type")?).filter(|kind| !kind.trim().eq_ignore_ascii_case("none")),
+            passphrase: flags.remove("passphrase").unwrap_or_default(),
+        },
+        ["hold"] => Command::Hold {
+            account: required(&mut flags, "account")?,
+            amount: positive(&mut flags, "amount")?,
+            reason: required(&mut flags, "reason")?,
+            expires: flags.remove("expires").map(|raw| date(&raw, "expires")).transpose()?,
+            pin: flags.remove("pin"),
+        },
+        ["holds"] => Command::Holds { account: required(&mut flags, "account")? },
+        ["release"] => Command::Release { account: required(&mut flags, "account")?, id: id(&mut flags, "id")?, pin: flags.remove("pin") },
+        ["capture"] => Command::Capture {
+            account: required(&mut flags, "account")?,
+            id: id(&mut flags, "id")?,
+            amount: if flags.contains_key("amount") { Some(positive(&mut flags, "amount")?) } else { None },
+            pin: flags.remove("pin"),
+        },
+        ["deposit-cheque"] => Command::DepositCheque {
+            account: required(&mut flags, "account")?,
+            amount: positive(&mut flags, "amount")?,
+            number: required(&mut flags, "number")?,
+            pin: flags.remove("pin"),
+        },
+        ["cheques"] => Command::Cheques {
+            account: required(&mut flags, "account")?,
+            status: match flags.remove("status").map(|s| s.to_lowercase()) {
+                None => None,
+                Some(s) if s == "all" => None,
+                Some(s) if matches!(s.as_str(), "clearing" | "cleared" | "returned") => Some(s),
+                Some(s) => return Err(CliError::Usage(format!("invalid --status {} (expected clearing, cleared, returned, or all)", s))),
+            },
+        },
+        ["return-cheque"] => Command::ReturnCheque { account: required(&mut flags, "account")?, id: id(&mut flags, "id")?, reason: required(&mut flags, "reason")? },
+        ["auto-convert"] => Command::AutoConvert {
+            account: required(&mut flags, "account")?,
+            currencies: match required(&mut flags, "currencies")?.trim() {
+                raw if raw.eq_ignore_ascii_case("none") => Vec::new(),
+                raw => raw.split(',').map(|code| code.trim().to_uppercase()).filter(|code| !code.is_empty()).collect(),
+            },
+        },
+        ["transfer"] => Command::Transfer {
+            from: required(&mut flags, "from")?,
+            to: required(&mut flags, "to")?,
+            amount: positive(&mut flags, "amount")?,
+            currency: flags.remove("currency").map(|c| c.to_uppercase()),
+            pin: flags.remove("pin"),
+            override_limits: flags.remove("override-limits"),
+            idempotency_key: flags.remove("idempotency-key"),
+        },
+        ["exchange"] => Command::Exchange {
+            from: required(&mut flags, "from")?,
+            to: required(&mut flags, "to")?,
+            amount: positive(&mut flags, "amount")?,
+            pin: flags.remove("pin"),
+            override_limits: flags.remove("override-limits"),
+        },
+        ["approvals"] => Command::Approvals {
+            status: match flags.remove("status").map(|s| s.to_lowercase()) {
+                None => Some(String::from("pending")),
+                Some(s) if s == "all" => None,
+                Some(s) if matches!(s.as_str(), "pending" | "posted" | "rejected") => Some(s),
+                Some(s) => return Err(CliError::Usage(format!("invalid --status {} (expected pending, posted, rejected, or all)", s))),
+            },
+            account: flags.remove("account"),
+        },
+        ["approve"] => Command::Approve { id: id(&mut flags, "id")?, passphrase: flags.remove("passphrase").unwrap_or_default() },
+        ["reject"] => Command::Reject {
+            id: id(&mut flags, "id")?,
+            passphrase: flags.remove("passphrase").unwrap_or_default(),
+            reason: required(&mut flags, "reason")?,
+        },
+        ["balance"] => Command::Balance { account: required(&mut flags, "account")? },
+        ["history"] => Command::History { account: required(&mut flags, "account")? },
+        ["tag"] => Command::Tag {
+            account: required(&mut flags, "account")?,
+            sequence: id(&mut flags, "seq")? as u64,
+            tags: match required(&mut flags, "tags")?.trim() {
+                raw if raw.eq_ignore_ascii_case("none") => Vec::new(),
+                raw => tags(raw)?,
+            },
+        },
+        ["tags"] => Command::TagReport {
+            account: required(&mut flags, "account")?,
+            start: flags.remove("start").map(|raw| date(&raw, "start")).transpose()?,
+            end: flags.remove("end").map(|raw| date(&raw, "end")).transpose()?,
+        },
+        ["statement"] => Command::Statement {
+            account: required(&mut flags, "account")?,
+            format: match flags.remove("format") {
+                None => StatementFormat::Csv,
+                Some(raw) => StatementFormat::parse(&raw)
+                    .ok_or_else(|| CliError::Usage(format!("invalid --format {} (expected csv, ofx, qif, or html)", raw)))?,
+            },
+            start: flags.remove("start").map(|raw| date(&raw, "start")).transpose()?,
+            end: flags.remove("end").map(|raw| date(&raw, "end")).transpose()?,
+        },
+        ["import"] => Command::Import {
+            account: required(&mut flags, "account")?,
+            file: PathBuf::from(required(&mut flags, "file")?),
+            delimiter: delimiter(&mut flags)?,
+            pin: flags.remove("pin"),
+        },
+        ["forecast"] => Command::Forecast {
+            account: required(&mut flags, "account")?,
+            since: match (flags.remove("since"), flags.contains_key("rate")) {
+                (Some(_), true) => return Err(CliError::Usage(String::from("--since cannot be combined with --rate"))),
+                (since, _) => since.map(|raw| date(&raw, "since")).transpose()?,
+            },
+            days: days(&mut flags)?,
+            rate: annual_rate(&mut flags, "rate")?,
+            inflation: annual_fraction(&mut flags, "inflation")?,
+            step: match flags.remove("step") {
+                None => ForecastStep::Daily,
+                Some(raw) => ForecastStep::parse(&raw)
+                    .ok_or_else(|| CliError::Usage(format!("invalid --step {} (expected daily, weekly, monthly, or yearly)", raw)))?,
+            },
+            currency: flags.remove("currency").map(|c| c.to_uppercase()),
+        },
+        ["interest-rate"] => Command::InterestRate {
+            account: required(&mut flags, "account")?,
+            rate: annual_rate(&mut flags, "rate")?.ok_or_else(|| CliError::Usage(String::from("missing --rate")))?,
+            date: flags.remove("date").map(|raw| date(&raw, "date")).transpose()?,
+        },
+        ["interest-rates"] => Command::InterestRates { account: required(&mut flags, "account")? },
+        ["promotion"] => Command::Promotion {
+            account: required(&mut flags, "account")?,
+            bonus: annual_fraction(&mut flags, "bonus")?.ok_or_else(|| CliError::Usage(String::from("missing --bonus")))?,
+            days: days(&mut flags)?,
+            start: flags.remove("start").map(|raw| date(&raw, "start")).transpose()?,
+        },
+        ["end-promotion"] => Command::EndPromotion { account: required(&mut flags, "account")? },
+        ["interest-payout"] => Command::InterestPayout {
+            account: required(&mut flags, "account")?,
+            to: Some(required(&mut flags, "to")?).filter(|to| !to.trim().eq_ignore_ascii_case("none")),
+        },
+        ["year-basis"] => Command::YearBasis {
+            account: flags.remove("account"),
+            basis: {
+                let raw = required(&mut flags, "basis")?;
+                YearBasis::parse(&raw).ok_or_else(|| CliError::Usage(format!("invalid --basis {} (expected 365, actual, or 360)", raw)))?
+            },
+        },
+        ["interest-method"] => Command::InterestMethod {
+            account: flags.remove("account"),
+            method: {
+                let raw = required(&mut flags, "method")?;
+                InterestMethod::parse(&raw).ok_or_else(|| CliError::Usage(format!("invalid --method {} (expected daily, average, or minimum)", raw)))?
+            },
+        },
+        ["interest-grace"] => Command::InterestGrace {
+            account: flags.remove("account"),
+            days: {
+                let raw = required(&mut flags, "days")?;
+                raw.parse().map_err(|_| CliError::Usage(format!("invalid --days {} (expected a number of days, 0 for none)", raw)))?
+            },
+        },
+        ["scenarios"] => Command::Scenarios {
+            account: required(&mut flags, "account")?,
+            days: days(&mut flags)?,
+            scenarios: scenarios(&mut flags)?,
+        },
+        ["compare"] => Command::Compare {
+            account: required(&mut flags, "account")?,
+            with: required(&mut flags, "with")?,
+            days: days(&mut flags)?,
+        },
+        ["monte-carlo"] => Command::MonteCarlo {
+            account: required(&mut flags, "account")?,
+            days: days(&mut flags)?,
+            paths: match flags.remove("paths") {
+                None => DEFAULT_PATHS,
+                Some(raw) => match raw.parse::<usize>() {
+                    Ok(v) if (1..=MAX_PATHS).contains(&v) => v,
+                    _ => return Err(CliError::Usage(format!("invalid --paths {} (expected 1-{})", raw, MAX_PATHS))),
+                },
+            },
+            seed: seed(&mut flags)?,
+            rate_volatility: annual_fraction(&mut flags, "rate-volatility")?.unwrap_or(Decimal::ZERO),
+            fx: match (annual_rate(&mut flags, "drift")?, annual_fraction(&mut flags, "volatility")?) {
+                (None, None) => None,
+                (drift, volatility) => Some(RateModel::new(drift.unwrap_or(Decimal::ZERO), volatility.unwrap_or(Decimal::new(10, 2)))),
+            },
+            deposits: match flags.remove("deposit") {
+                None if flags.contains_key("every") || flags.contains_key("chance") => {
+                    return Err(CliError::Usage(String::from("--every and --chance need --deposit")));
+                }
+                None => None,
+                Some(raw) => Some(DepositBehavior {
+                    amount: match raw.parse::<Decimal>() {
+                        Ok(v) if v > Decimal::ZERO => v,
+                        _ => return Err(CliError::Usage(format!("invalid --deposit {}", raw))),
+                    },
+                    every: match required(&mut flags, "every")?.parse::<usize>() {
+                        Ok(v) if (1..=999999).contains(&v) => v,
+                        _ => return Err(CliError::Usage(String::from("invalid --every (expected 1-999999 days)"))),
+                    },
+                    chance: match flags.remove("chance").map(|raw| raw.parse::<f64>()) {
+                        None => 1.0,
+                        Some(Ok(v)) if (0.0..=1.0).contains(&v) => v,
+                        Some(_) => return Err(CliError::Usage(String::from("invalid --chance (expected 0 to 1)"))),
+                    },
+                }),
+            },
+        },
+        ["pnl"] => Command::Pnl { account: flags.remove("account") },
+        ["interest"] => match flags.remove("since") {
+            Some(raw) => Command::Interest { days: None, since: Some(date(&raw, "since")?) },
+            None => Command::Interest { days: Some(days(&mut flags)?), since: None },
+        },
+        ["portfolio"] => Command::Portfolio {
+            account: flags.remove("account"),
+            date: flags.remove("date").map(|raw| date(&raw, "date")).transpose()?,
+        },
+        ["goal"] => Command::Goal {
+            account: required(&mut flags, "account")?,
+            name: required(&mut flags, "name")?,
+            target: positive(&mut flags, "target")?,
+            date: date(&required(&mut flags, "date")?, "date")?,
+        },
+        ["goals"] => Command::Goals { account: required(&mut flags, "account")?, frequency: frequency(&mut flags)? },
+        ["envelope"] => Command::Envelope {
+            account: required(&mut flags, "account")?,
+            category: required(&mut flags, "category")?,
+            limit: positive(&mut flags, "limit")?,
+        },
+        ["budget"] => Command::Budget {
+            account: required(&mut flags, "account")?,
+            date: flags.remove("date").map(|raw| date(&raw, "date")).transpose()?,
+        },
+        ["summary"] => Command::Summary {
+            month: flags
+                .remove("month")
+                .map(|raw| Month::parse(&raw).ok_or_else(|| CliError::Usage(format!("invalid --month {} (expected YYYY-MM)", raw))))
+                .transpose()?,
+            csv: match flags.remove("format").as_deref() {
+                None | Some("text") => false,
+                Some("csv") => true,
+                Some(raw) => return Err(CliError::Usage(format!("invalid --format {} (expected text or csv)", raw))),
+            },
+        },
+        ["tax-certificate"] => Command::TaxCertificate {
+            account: required(&mut flags, "account")?,
+            year: flags
+                .remove("year")
+                .map(|raw| raw.parse().ok().filter(|y| (1..=9999).contains(y)).ok_or_else(|| CliError::Usage(format!("invalid --year {} (expected YYYY)", raw))))
+                .transpose()?,
+            format: match flags.remove("format").as_deref() {
+                None | Some("text") => None,
+                Some("csv") => Some(StatementFormat::Csv),
+                Some("html") => Some(StatementFormat::Html),
+                Some(raw) => return Err(CliError::Usage(format!("invalid --format {} (expected text, csv, or html)", raw))),
+            },
+        },
+        ["loan"] => Command::Loan {
+            account: required(&mut flags, "account")?,
+            amount: positive(&mut flags, "amount")?,
+            rate: match required(&mut flags, "rate")?.parse::<Decimal>() {
+                Ok(v) if v >= Decimal::ZERO => v,
+                _ => return Err(CliError::Usage(String::from("invalid --rate (expected an annual fraction, e.g. 0.06)"))),
+            },
+            term: match required(&mut flags, "term")?.parse::<u32>() {
+                Ok(v) if (1..=MAX_TERM).contains(&v) => v,
+                _ => return Err(CliError::Usage(format!("invalid --term (expected 1-{} payments)", MAX_TERM))),
+            },
+            frequency: frequency(&mut flags)?,
+            pin: flags.remove("pin"),
+        },
+        ["schedule"] => Command::Schedule { loan: id(&mut flags, "loan")? },
+        ["repay"] => Command::Repay { loan: id(&mut flags, "loan")?, pin: flags.remove("pin") },
+        ["paydown"] => Command::Paydown {
+            loan: match flags.contains_key("loan") {
+                true if flags.contains_key("principal") => return Err(CliError::Usage(String::from("give either --loan or --principal, not both"))),
+                true => PaydownLoan::Booked(id(&mut flags, "loan")?),
+                false => PaydownLoan::Quote {
+                    principal: positive(&mut flags, "principal")?,
+                    rate: match required(&mut flags, "rate")?.parse::<Decimal>() {
+                        Ok(v) if v >= Decimal::ZERO => v,
+                        _ => return Err(CliError::Usage(String::from("invalid --rate (expected an annual fraction, e.g. 0.06)"))),
+                    },
+                    term: match required(&mut flags, "term")?.parse::<u32>() {
+                        Ok(v) if (1..=MAX_TERM).contains(&v) => v,
+                        _ => return Err(CliError::Usage(format!("invalid --term (expected 1-{} payments)", MAX_TERM))),
+                    },
+                    frequency: frequency(&mut flags)?,
+                },
+            },
+            amount: positive(&mut flags, "amount")?,
+            deposit_rate: annual_rate(&mut flags, "deposit-rate")?.ok_or_else(|| CliError::Usage(String::from("missing --deposit-rate")))?,
+            days: days(&mut flags)?,
+        },
+        ["order"] => Command::Order {
+            from: required(&mut flags, "from")?,
+            to: required(&mut flags, "to")?,
+            amount: positive(&mut flags, "amount")?,
+            currency: flags.remove("currency").map(|c| c.to_uppercase()),
+            every: match required(&mut flags, "every")?.parse::<u32>() {
+                Ok(v) if (1..=MAX_INTERVAL_DAYS).contains(&v) => v,
+                _ => return Err(CliError::Usage(format!("invalid --every (expected 1-{} days)", MAX_INTERVAL_DAYS))),
+            },
+            start: flags.remove("start").map(|raw| date(&raw, "start")).transpose()?,
+            pin: flags.remove("pin"),
+        },
+        ["orders"] => Command::Orders,
+        ["skip"] => Command::Skip { order: id(&mut flags, "order")? },
+        ["sweep"] => Command::Sweep {
+            from: required(&mut flags, "from")?,
+            to: required(&mut flags, "to")?,
+            above: match required(&mut flags, "above")?.parse::<Decimal>() {
+                Ok(v) if v >= Decimal::ZERO => v,
+                _ => return Err(CliError::Usage(String::from("invalid --above (expected an amount of zero or more)"))),
+            },
+            pin: flags.remove("pin"),
+        },
+        ["sweeps"] => Command::Sweeps,
+        ["cancel"] if flags.contains_key("limit") => Command::CancelLimit { limit: id(&mut flags, "limit")? },
+        ["cancel"] if flags.contains_key("sweep") => Command::CancelSweep { sweep: id(&mut flags, "sweep")? },
+        ["cancel"] => Command::Cancel { order: id(&mut flags, "order")? },
+        ["forward"] => Command::Forward {
+            account: required(&mut flags, "account")?,
+            side: {
+                let raw = required(&mut flags, "side")?;
+                ForwardSide::parse(&raw).ok_or_else(|| CliError::Usage(format!("invalid --side {} (expected buy or sell)", raw)))?
+            },
+            amount: positive(&mut flags, "amount")?,
+            currency: required(&mut flags, "currency")?.to_uppercase(),
+            rate: positive(&mut flags, "rate")?,
+            date: date(&required(&mut flags, "date")?, "date")?,
+            pin: flags.remove("pin"),
+        },
+        ["forwards"] => Command::Forwards,
+        ["limit"] => Command::Limit {
+            account: required(&mut flags, "account")?,
+            amount: positive(&mut flags, "amount")?,
+            from: required(&mut flags, "from")?.to_uppercase(),
+            to: required(&mut flags, "to")?.to_uppercase(),
+            rate: positive(&mut flags, "rate")?,
+            expires: flags.remove("expires").map(|raw| date(&raw, "expires")).transpose()?,
+            average: flags.remove("average").map(|raw| average(&raw)).transpose()?,
+            pin: flags.remove("pin"),
+        },
+        ["limits"] => Command::Limits,
+        ["amend"] => Command::AmendLimit {
+            limit: id(&mut flags, "limit")?,
+            amount: if flags.contains_key("amount") { Some(positive(&mut flags, "amount")?) } else { None },
+            rate: if flags.contains_key("rate") { Some(positive(&mut flags, "rate")?) } else { None },
+            expires: flags
+                .remove("expires")
+                .map(|raw| if raw.trim().eq_ignore_ascii_case("none") { Ok(None) } else { date(&raw, "expires").map(Some) })
+                .transpose()?,
+            average: flags
+                .remove("average")
+                .map(|raw| if raw.trim().eq_ignore_ascii_case("none") { Ok(None) } else { average(&raw).map(Some) })
+                .transpose()?,
+        },
+        ["dca"] => Command::Dca {
+            from: required(&mut flags, "from")?.to_uppercase(),
+            to: required(&mut flags, "to")?.to_uppercase(),
+            amount: positive(&mut flags, "amount")?,
+            rates: match flags.remove("history") {
+                Some(file) if !flags.contains_key("rates") => DcaRates::History {
+                    file: PathBuf::from(file),
+                    every: match flags.remove("every") {
+                        None => 1,
+                        Some(raw) => match raw.parse::<usize>() {
+                            Ok(v) if (1..=MAX_INTERVAL_DAYS as usize).contains(&v) => v,
+                            _ => return Err(CliError::Usage(format!("invalid --every {} (expected 1-{})", raw, MAX_INTERVAL_DAYS))),
+                        },
+                    },
+                },
+                Some(_) => return Err(CliError::Usage(String::from("give either --rates or --history, not both"))),
+                None => {
+                    let raw = required(&mut flags, "rates")?;
+                    DcaRates::Listed(
+                        raw.split(',')
+                            .map(|r| match r.trim().parse::<Decimal>() {
+                                Ok(v) if v > Decimal::ZERO => Ok(v),
+                                _ => Err(CliError::Usage(format!("invalid --rates {} (expected positive rates, e.g. 58,57.5,59)", raw))),
+                            })
+                            .collect::<Result<_, _>>()?,
+                    )
+                }
+            },
+        },
+        ["basket"] => Command::Basket {
+            code: required(&mut flags, "code")?.to_uppercase(),
+            name: required(&mut flags, "name")?,
+            weights: weights(&mut flags)?,
+        },
+        ["baskets"] => Command::Baskets,
+        ["fee-schedule"] => Command::FeeSchedule(fee_tiers(&mut flags)?),
+        ["spread"] => {
+            let raw = required(&mut flags, "pair")?;
+            let pair = raw
+                .split_once('/')
+                .map(|(a, b)| (a.trim().to_uppercase(), b.trim().to_uppercase()))
+                .ok_or_else(|| CliError::Usage(format!("invalid --pair {} (expected CODE/CODE, e.g. USD/JPY)", raw)))?;
+            let rate = required(&mut flags, "rate")?;
+            Command::Spread {
+                pair,
+                rate: if rate.trim().eq_ignore_ascii_case("none") {
+                    None
+                } else {
+                    Some(rate.parse().map_err(|_| CliError::Usage(format!("invalid --rate {} (expected a fraction, e.g. 0.02, or none)", rate)))?)
+                },
+            }
+        }
+        ["fees"] => Command::Fees,
+        ["conversion-limit"] => Command::ConversionLimit {
+            code: required(&mut flags, "code")?.to_uppercase(),
+            limit: ConversionLimit { per_transaction: limit_cap(&mut flags, "per-transaction")?, per_day: limit_cap(&mut flags, "per-day")? },
+            passphrase: flags.remove("passphrase").unwrap_or_default(),
+        },
+        ["conversion-limits"] => Command::ConversionLimits,
+        ["limit-profile"] => Command::LimitProfile {
+            target: {
+                let raw = required(&mut flags, "target")?;
+                LimitTarget::parse(&raw).ok_or_else(|| CliError::Usage(format!("invalid --target {} (expected type:NAME or kyc:STATUS)", raw)))?
+            },
+            profile: LimitProfile {
+                max_deposit: limit_cap(&mut flags, "max-deposit")?,
+                max_withdrawal: limit_cap(&mut flags, "max-withdrawal")?,
+                max_daily_conversion: limit_cap(&mut flags, "max-daily-conversion")?,
+            },
+            passphrase: flags.remove("passphrase").unwrap_or_default(),
+        },
+        ["limit-profiles"] => Command::LimitProfiles { account: flags.remove("account") },
+        ["conversions"] => Command::Conversions(conversion_filter(&mut flags)?),
+        ["receipt"] => Command::Receipt { number: id(&mut flags, "number")? },
+        ["turnover"] => Command::Turnover(conversion_filter(&mut flags)?),
+        ["eod"] => Command::EndOfDay { date: flags.remove("date").map(|raw| date(&raw, "date")).transpose()? },
+        ["simulate"] => Command::Simulate {
+            days: days(&mut flags)?,
+            seed: seed(&mut flags)?,
+            default: RateModel::new(
+                match flags.remove("drift") {
+                    None => Decimal::ZERO,
+                    Some(raw) => raw.parse().map_err(|_| CliError::Usage(format!("invalid --drift {} (expected an annual fraction, e.g. 0.02)", raw)))?,
+                },
+                annual_fraction(&mut flags, "volatility")?.unwrap_or(Decimal::new(10, 2)),
+            ),
+            models: models(&mut flags)?,
+        },
+        ["replay"] => Command::Replay {
+            file: PathBuf::from(required(&mut flags, "file")?),
+            delimiter: delimiter(&mut flags)?,
+            start: flags.remove("start").map(|raw| date(&raw, "start")).transpose()?,
+            end: flags.remove("end").map(|raw| date(&raw, "end")).transpose()?,
+        },
+        ["compact"] => Command::Compact {
+            before: date(&required(&mut flags, "before")?, "before")?,
+            archive: PathBuf::from(required(&mut flags, "archive")?),
+        },
+        ["demo"] => Command::Demo {
+            accounts: match flags.remove("accounts") {
+                None => DEFAULT_DEMO_ACCOUNTS,
+                Some(raw) => raw
+                    .parse()
+                    .ok()
+                    .filter(|n| (1..=10_000).contains(n))
+                    .ok_or_else(|| CliError::Usage(format!("invalid --accounts {} (expected 1-10000)", raw)))?,
+            },
+            days: if flags.contains_key("days") { days(&mut flags)? } else { DEFAULT_DEMO_DAYS },
+            seed: seed(&mut flags)?,
+        },
+        ["verify"] => Command::Verify,
+        ["rounding"] => Command::Rounding,
+        ["help"] => Command::Help,
+        [] => return Err(CliError::Usage(String::from("missing command"))),
+        [other, ..] => return Err(CliError::Usage(format!("unknown command {}", other))),
+    };
+    if let Some(key) = flags.keys().next() {
+        return Err(CliError::Usage(format!("unexpected option --{}", key)));
+    }
+    let command = match passphrase {
+        Some(passphrase) => Command::Admin { passphrase, command: Box::new(command) },
+        None => command,
+    };
+    match if_version {
+        Some(_) if command.versioned_account().is_none() => {
+            Err(CliError::Usage(String::from("--if-version applies only to commands that change an account")))
+        }
+        Some(version) => Ok(Command::Versioned { version, command: Box::new(command) }),
+        None => Ok(command),
+    }
+}
+
+fn required(flags: &mut BTreeMap<String, String>, key: &str) -> Result<String, CliError> {
+    flags
+        .remove(key)
+        .ok_or_else(|| CliError::Usage(format!("missing --{}", key)))
+}
+
+fn positive(flags: &mut BTreeMap<String, String>, key: &str) -> Result<Decimal, CliError> {
+    let raw = required(flags, key)?;
+    match raw.parse::<Decimal>() {
+        Ok(v) if v > Decimal::ZERO => Ok(v),
+        _ => Err(CliError::Usage(format!("invalid --{} {}", key, raw))),
+    }
+}
+
+/// An optional conversion cap: an amount of zero or more, or "none".
+fn limit_cap(flags: &mut BTreeMap<String, String>, key: &str) -> Result<Option<Decimal>, CliError> {
+    let Some(raw) = flags.remove(key) else {
+        return Ok(None);
+    };
+    if raw.trim().eq_ignore_ascii_case("none") {
+        return Ok(None);
+    }
+    match raw.parse::<Decimal>() {
+        Ok(v) if v >= Decimal::ZERO => Ok(Some(v)),
+        _ => Err(CliError::Usage(format!("invalid --{} {} (expected an amount or none)", key, raw))),
+    }
+}
+
+/// An optional `--seed`; commands that take one draw it from the clock
+/// when it is absent.
+/// An optional span of rate history in days, from 1 to
+/// `RATE_HISTORY_DAYS`.
+fn history_days(flags: &mut BTreeMap<String, String>, key: &str, default: usize) -> Result<usize, CliError> {
+    let Some(raw) = flags.remove(key) else {
+        return Ok(default);
+    };
+    match raw.parse::<usize>() {
+        Ok(v) if (1..=RATE_HISTORY_DAYS).contains(&v) => Ok(v),
+        _ => Err(CliError::Usage(format!("invalid --{} {} (expected 1-{} days)", key, raw, RATE_HISTORY_DAYS))),
+    }
+}
+
+/// `--average sma:20`: a moving average a limit order waits for.
+fn average(raw: &str) -> Result<MovingAverage, CliError> {
+    MovingAverage::parse(raw)
+        .filter(|a| a.days <= RATE_HISTORY_DAYS)
+        .ok_or_else(|| CliError::Usage(format!("invalid --average {} (expected sma:DAYS or ema:DAYS)", raw)))
+}
+
+fn seed(flags: &mut BTreeMap<String, String>) -> Result<Option<u64>, CliError> {
+    flags
+        .remove("seed")
+        .map(|raw| raw.parse().map_err(|_| CliError::Usage(format!("invalid --seed {} (expected a whole number)", raw))))
+        .transpose()
+}
+
+fn days(flags: &mut BTreeMap<String, String>) -> Result<usize, CliError> {
+    let raw = required(flags, "days")?;
+    match raw.parse::<usize>() {
+        Ok(v) if (1..=999999).contains(&v) => Ok(v),
+        _ => Err(CliError::Usage(format!("invalid --days {} (expected 1-999999)", raw))),
+    }
+}
+
+/// An optional annual rate given as a fraction, e.g. `--rate 0.06`.
+fn annual_fraction(flags: &mut BTreeMap<String, String>, key: &str) -> Result<Option<Decimal>, CliError> {
+    let Some(raw) = flags.remove(key) else {
+        return Ok(None);
+    };
+    match raw.parse::<Decimal>() {
+        Ok(v) if v >= Decimal::ZERO => Ok(Some(v)),
+        _ => Err(CliError::Usage(format!("invalid --{} {} (expected an annual fraction, e.g. 0.06)", key, raw))),
+    }
+}
+
+/// An optional annual interest rate given as a fraction, which unlike
+/// `annual_fraction` may be negative, e.g. `--rate -0.005`. Whether the bank
+/// accepts it is checked when the command runs.
+fn annual_rate(flags: &mut BTreeMap<String, String>, key: &str) -> Result<Option<Decimal>, CliError> {
+    flags
+        .remove(key)
+        .map(|raw| raw.parse::<Decimal>().map_err(|_| CliError::Usage(format!("invalid --{} {} (expected an annual fraction, e.g. 0.06)", key, raw))))
+        .transpose()
+}
+
+/// `--frequency`, monthly when absent.
+fn frequency(flags: &mut BTreeMap<String, String>) -> Result<PaymentFrequency, CliError> {
+    match flags.remove("frequency") {
+        None => Ok(PaymentFrequency::Monthly),
+        Some(raw) => PaymentFrequency::parse(&raw).ok_or_else(|| {
+            CliError::Usage(format!("invalid --frequency {} (expected weekly, biweekly, monthly, quarterly, or annually)", raw))
+        }),
+    }
+}
+
+fn id(flags: &mut BTreeMap<String, String>, key: &str) -> Result<usize, CliError> {
+    let raw = required(flags, key)?;
+    raw.parse().map_err(|_| CliError::Usage(format!("invalid --{} {}", key, raw)))
+}
+
+/// `--tags travel,Food`: comma-separated tags, normalized by
+/// `tag::normalize`.
+fn tags(raw: &str) -> Result<Vec<String>, CliError> {
+    let parts: Vec<&str> = raw.split(',').collect();
+    tag::normalize(&parts).map_err(|e| CliError::Usage(format!("invalid --tags {}: {}", raw, e)))
+}
+
+/// `--weights USD:50,EUR:30,JPY:20`: percentages of a basket's value,
+/// returned as fractions.
+fn weights(flags: &mut BTreeMap<String, String>) -> Result<Vec<(String, Decimal)>, CliError> {
+    let raw = required(flags, "weights")?;
+    raw.split(',')
+        .map(|part| {
+            let (code, pct) = part.split_once(':').ok_or_else(|| CliError::Usage(format!("invalid --weights {} (expected CODE:PCT,...)", raw)))?;
+            match pct.trim().parse::<Decimal>() {
+                Ok(pct) if pct > Decimal::ZERO => Ok((code.trim().to_uppercase(), pct / Decimal::from(100))),
+                _ => Err(CliError::Usage(format!("invalid --weights {} (expected CODE:PCT,...)", raw))),
+            }
+        })
+        .collect()
+}
+
+/// `--tiers 0:0.01,10000:0.005`: each tier's threshold in the base
+/// currency and fee rate (a fraction), or `none` for no fees.
+fn fee_tiers(flags: &mut BTreeMap<String, String>) -> Result<FeeSchedule, CliError> {
+    let raw = required(flags, "tiers")?;
+    if raw.trim().eq_ignore_ascii_case("none") {
+        return Ok(FeeSchedule::default());
+    }
+    let invalid = || CliError::Usage(format!("invalid --tiers {} (expected FROM:RATE,... or none)", raw));
+    let tiers = raw
+        .split(',')
+        .map(|part| {
+            let (from, rate) = part.split_once(':').ok_or_else(invalid)?;
+            match (from.trim().parse::<Decimal>(), rate.trim().parse::<Decimal>()) {
+                (Ok(from), Ok(rate)) => Ok(FeeTier { from, rate }),
+                _ => Err(invalid()),
+            }
+        })
+        .collect::<Result<_, _>>()?;
+    FeeSchedule::new(tiers).map_err(|e| CliError::Usage(format!("invalid --tiers {}: {}", raw, e)))
+}
+
+/// `--scenarios base:0.05,monthly:0.05:monthly,saver:0.05:daily:100:monthly`:
+/// a name and annual rate (a fraction), then optionally the compounding
+/// (daily when absent) and a contribution per frequency.
+fn scenarios(flags: &mut BTreeMap<String, String>) -> Result<Vec<Scenario>, CliError> {
+    let raw = required(flags, "scenarios")?;
+    let invalid = || CliError::Usage(format!("invalid --scenarios {} (expected NAME:RATE[:COMPOUNDING[:AMOUNT:FREQUENCY]],...)", raw));
+    raw.split(',')
+        .map(|part| {
+            let fields: Vec<&str> = part.split(':').map(str::trim).collect();
+            let (name, rate) = match fields[..] {
+                [name, rate, ..] if !name.is_empty() => (name, rate.parse::<Decimal>().map_err(|_| invalid())?),
+                _ => return Err(invalid()),
+            };
+            let scenario = Scenario::new(name, rate);
+            match fields[2..] {
+                [] => Ok(scenario),
+                [compounding] => Ok(scenario.with_compounding(Compounding::parse(compounding).ok_or_else(invalid)?)),
+                [compounding, amount, frequency] => Ok(scenario
+                    .with_compounding(Compounding::parse(compounding).ok_or_else(invalid)?)
+                    .with_contribution(amount.parse().map_err(|_| invalid())?, PaymentFrequency::parse(frequency).ok_or_else(invalid)?)),
+                _ => Err(invalid()),
+            }
+        })
+        .collect()
+}
+
+/// `--models USD:0.02:0.1,EUR:-0.01:0.08`: a currency code, then its
+/// annual drift and volatility as fractions.
+fn models(flags: &mut BTreeMap<String, String>) -> Result<Vec<(String, RateModel)>, CliError> {
+    let Some(raw) = flags.remove("models") else {
+        return Ok(Vec::new());
+    };
+    let invalid = || CliError::Usage(format!("invalid --models {} (expected CODE:DRIFT:VOLATILITY,...)", raw));
+    raw.split(',')
+        .map(|part| match part.split(':').map(str::trim).collect::<Vec<_>>()[..] {
+            [code, drift, volatility] if !code.is_empty() => {
+                let model = RateModel::new(drift.parse().map_err(|_| invalid())?, volatility.parse().map_err(|_| invalid())?);
+                Ok((code.to_uppercase(), model))
+            }
+            _ => Err(invalid()),
+        })
+        .collect()
+}
+
+/// `--delimiter`, a comma when absent.
+fn delimiter(flags: &mut BTreeMap<String, String>) -> Result<char, CliError> {
+    let Some(raw) = flags.remove("delimiter") else {
+        return Ok(',');
+    };
+    let mut chars = raw.chars();
+    match (chars.next(), chars.next()) {
+        (Some(c), None) if c != '"' => Ok(c),
+        _ => Err(CliError::Usage(format!("invalid --delimiter {} (expected one character)", raw))),
+    }
+}
+
+/// `--account`, `--currency`, `--start`, and `--end`, each optional.
+fn conversion_filter(flags: &mut BTreeMap<String, String>) -> Result<ConversionFilter, CliError> {
+    Ok(ConversionFilter {
+        start: flags.remove("start").map(|raw| date(&raw, "start")).transpose()?,
+        end: flags.remove("end").map(|raw| date(&raw, "end")).transpose()?,
+        account: flags.remove("account"),
+        currency: flags.remove("currency").map(|c| c.to_uppercase()),
+    })
+}
+
+fn date(raw: &str, key: &str) -> Result<Date, CliError> {
+    Date::parse(raw).ok_or_else(|| CliError::Usage(format!("invalid --{} {} (expected YYYY-MM-DD)", key, raw)))
+}
+
+/// Execute `command` against `bank` and return its result. Commands that
+/// change the bank are refused while it is read-only.
+pub fn execute(bank: &mut Bank, command: &Command) -> Result<Output, CliError> {
+    if command.mutates() {
+        bank.ensure_writable()?;
+    }
+    let today = bank.today();
+    match &with_real_names(bank, command) {
+        Command::Rates { window } => {
+            let currencies = bank.forex.currencies_detailed();
+            let volatility = currencies.iter().filter_map(|c| bank.forex.volatility(&c.code, *window).ok()).collect();
+            Ok(Output::Rates { base: bank.forex.get_base_rate().to_string(), currencies, window: *window, volatility })
+        }
+        Command::RateHistory { code, days, sma, ema } => {
+            let mut closes = bank.forex.averaged_history(code, *sma, *ema)?;
+            closes.drain(..closes.len().saturating_sub(*days));
+            Ok(Output::RateHistory { code: code.clone(), sma: *sma, ema: *ema, closes })
+        }
+        Command::RateSummary { code: None, start, end } => Ok(Output::RateSummary(bank.forex.rate_summary(*start, *end))),
+        Command::RateSummary { code: Some(code), start, end } => {
+            let series = bank.forex.rate_history(code);
+            let (first, last) = (series.first().map_or(today, |c| c.0), series.last().map_or(today, |c| c.0));
+            Ok(Output::RateSummary(vec![bank.forex.stats(code, start.unwrap_or(first), end.unwrap_or(last))?]))
+        }
+        Command::Rate { code, rate, source } => {
+            let fills = bank.set_rate_from(code, *rate, source.clone().unwrap_or(RateSource::Manual))?;
+            let rate = bank.forex.get_rate(code).copied().unwrap_or(*rate);
+            Ok(Output::RateRecorded { code: code.clone(), rate, fills })
+        }
+        Command::Dca { from, to, amount, rates } => {
+            for code in [from, to] {
+                bank.forex.currency(code).ok_or_else(|| ForexError::UnknownCurrency(code.clone()))?;
+            }
+            let rates = match rates {
+                DcaRates::Listed(rates) => rates.clone(),
+                DcaRates::History { file, every } => {
+                    let base = bank.forex.base_currency().code.clone();
+                    let rates: Vec<Decimal> = read_history(file, ',')?.cross_rates(from, to, &base).into_iter().step_by(*every).map(|(_, rate)| rate).collect();
+                    if rates.is_empty() {
+                        let reason = format!("{} has no days quoting both {} and {}", file.display(), from, to);
+                        return Err(io::Error::new(io::ErrorKind::InvalidData, reason).into());
+                    }
+                    rates
+                }
+            };
+            Ok(Output::Dca(DcaSimulation::run(&Money::new(*amount, from), to, &rates).map_err(Error::from)?))
+        }
+        Command::Basket { code, name, weights } => {
+            let code = bank.forex.define_basket(code, name, weights)?.code.clone();
+            Ok(Output::BasketDefined(bank.forex.basket_quote(&code).ok_or_else(|| ForexError::UnknownCurrency(code.clone()))?))
+        }
+        Command::Baskets => Ok(Output::Baskets(bank.forex.basket_quotes())),
+        Command::FeeSchedule(fees) => {
+            bank.forex.set_fee_schedule(fees.clone());
+            Ok(Output::Fees { fees: fees.clone(), spreads: bank.forex.pair_spreads() })
+        }
+        Command::Spread { pair: (a, b), rate } => {
+            match rate {
+                Some(rate) => bank.forex.set_pair_spread(a, b, *rate)?,
+                None => {
+                    bank.forex.remove_pair_spread(a, b);
+                }
+            }
+            Ok(Output::Fees { fees: bank.forex.fee_schedule().clone(), spreads: bank.forex.pair_spreads() })
+        }
+        Command::Fees => Ok(Output::Fees { fees: bank.forex.fee_schedule().clone(), spreads: bank.forex.pair_spreads() }),
+        Command::ConversionLimit { code, limit, passphrase } => {
+            bank.set_conversion_limit(code, *limit, passphrase)?;
+            Ok(Output::ConversionLimits(bank.compliance.conversion_limits.clone()))
+        }
+        Command::ConversionLimits => Ok(Output::ConversionLimits(bank.compliance.conversion_limits.clone())),
+        Command::LimitProfile { target, profile, passphrase } => {
+            bank.set_limit_profile(target.clone(), *profile, passphrase)?;
+            Ok(limit_profiles(bank, None))
+        }
+        Command::LimitProfiles { account: None } => Ok(limit_profiles(bank, None)),
+        Command::LimitProfiles { account: Some(account) } => Ok(limit_profiles(bank, Some(find_account(bank, account)?))),
+        Command::Conversions(filter) => Ok(Output::Conversions(bank.forex.conversion_history(filter).into_iter().cloned().collect())),
+        Command::Receipt { number } => Ok(Output::Receipt(bank.exchange_receipt(*number)?)),
+        Command::Turnover(filter) => Ok(Output::Turnover {
+            base: bank.forex.get_base_rate().to_string(),
+            days: bank.forex.daily_turnover(filter),
+        }),
+        Command::CashRate { code, rate } => {
+            bank.set_cash_rate(code, *rate)?;
+            Ok(Output::CashRateRecorded { code: code.clone(), rate: bank.forex.currency(code).and_then(|c| c.cash_rate) })
+        }
+        Command::Convert { from, to, amount, rate_type } => {
+            let conversion = bank.forex.exchange_at(&Money::new(*amount, from), to, *rate_type)?;
+            Ok(Output::Conversion(conversion, bank.forex.trend(from, to)?))
+        }
+        Command::Accounts => Ok(Output::Accounts {
+            accounts: bank.active_accounts().map(|a| (a.name.clone(), a.id, a.get_balance())).collect(),
+        }),
+        Command::Alias { account, alias } => {
+            bank.add_alias(account, alias)?;
+            Ok(Output::Aliases(aliases(bank)))
+        }
+        Command::Unalias { alias } => {
+            bank.remove_alias(alias)?;
+            Ok(Output::Aliases(aliases(bank)))
+        }
+        Command::Aliases => Ok(Output::Aliases(aliases(bank))),
+        Command::Archive { account } => {
+            bank.archive_account(account)?;
+            Ok(Output::Archived(archived(bank)))
+        }
+        Command::Unarchive { account } => {
+            bank.restore_account(account)?;
+            Ok(Output::Archived(archived(bank)))
+        }
+        Command::Archived => Ok(Output::Archived(archived(bank))),
+        Command::Register { account, currency, pin } => {
+            let acct = match currency {
+                Some(code) => bank.create_account_in(account, code)?,
+                None => bank.create_account(account)?,
+            };
+            if let Some(pin) = pin {
+                acct.set_pin(pin);
+            }
+            Ok(Output::Registered { account: acct.name.clone(), id: acct.id, currency: acct.currency.clone(), protected: acct.is_protected() })
+        }
+        Command::Post { tx_type, account, amount, currency, memo, category, tags, pin, idempotency_key } => {
+            let currency = match currency {
+                Some(code) => code.clone(),
+                None => find_account(bank, account)?.currency.clone(),
+            };
+            if !tags.is_empty() && idempotency_key.is_some() {
+                return Err(CliError::Usage(String::from("--idempotency-key cannot be combined with --tags")));
+            }
+            let posted = match (category, idempotency_key) {
+                (Some(_), Some(_)) => return Err(CliError::Usage(String::from("--idempotency-key cannot be combined with --category"))),
+                (Some(category), None) => bank.spend(account, Money::new(*amount, &currency), category, memo, pin.as_deref()).map(|(balance, status)| (balance, Some(status))),
+                (None, Some(key)) => bank.post_transaction_once(key, account, *tx_type, Money::new(*amount, &currency), memo, pin.as_deref()).map(|balance| (balance, None)),
+                (None, None) => bank.post_transaction(account, *tx_type, Money::new(*amount, &currency), memo, pin.as_deref()).map(|balance| (balance, None)),
+            };
+            let (balance, envelope) = match posted {
+                Ok(posted) => posted,
+                Err(e) => return held(bank, e),
+            };
+            if !tags.is_empty() {
+                let last = find_account(bank, account)?.transactions.len() - 1;
+                bank.tag_transaction(account, last, &tags.iter().map(String::as_str).collect::<Vec<_>>())?;
+            }
+            Ok(Output::Posted { account: account.clone(), tx_type: *tx_type, amount: Money::new(*amount, &currency), balance, envelope })
+        }
+        Command::AutoConvert { account, currencies } => {
+            let acct = bank.set_auto_conversion(account, currencies)?;
+            Ok(Output::AutoConversion { account: acct.name.clone(), currency: acct.currency.clone(), currencies: acct.auto_convert.clone() })
+        }
+        Command::Alert { account, below, withdrawal_above, inactive_days } => {
+            let current = find_account(bank, account)?.alerts.clone();
+            let acct = bank.set_alerts(
+                account,
+                below.unwrap_or(current.balance_below),
+                withdrawal_above.unwrap_or(current.withdrawal_above),
+                inactive_days.unwrap_or(current.inactive_days),
+            )?;
+            Ok(Output::Alerts { account: acct.name.clone(), currency: acct.currency.clone(), alerts: acct.alerts.clone() })
+        }
+        Command::ServiceCharge { account, monthly, maintaining } => {
+            let current = find_account(bank, account)?.service_charges;
+            let acct = bank.set_service_charges(account, monthly.unwrap_or(current.monthly), maintaining.unwrap_or(current.maintaining))?;
+            Ok(Output::ServiceCharges { account: acct.name.clone(), currency: acct.currency.clone(), charges: acct.service_charges })
+        }
+        Command::AccountType { account, kind, passphrase } => {
+            bank.set_account_type(account, kind.as_deref(), passphrase)?;
+            Ok(limit_profiles(bank, Some(find_account(bank, account)?)))
+        }
+        Command::ServiceCharges { account } => {
+            let acct = find_account(bank, account)?;
+            Ok(Output::ServiceCharges { account: acct.name.clone(), currency: acct.currency.clone(), charges: acct.service_charges })
+        }
+        Command::Hold { account, amount, reason, expires, pin } => {
+            let amount = Money::new(*amount, &find_account(bank, account)?.currency);
+            let hold = bank.place_hold(account, amount, reason, *expires, pin.as_deref())?;
+            Ok(Output::HoldPlaced { account: account.clone(), hold, available: bank.available_balance(account)? })
+        }
+        Command::Holds { account } => {
+            let acct = find_account(bank, account)?;
+            Ok(Output::Holds {
+                account: acct.name.clone(),
+                holds: acct.active_holds(today).cloned().collect(),
+                balance: acct.get_balance(),
+                available: bank.available_balance(account)?,
+            })
+        }
+        Command::Release { account, id, pin } => {
+            let hold = bank.release_hold(account, *id, pin.as_deref())?;
+            Ok(Output::HoldReleased { account: account.clone(), hold, available: bank.available_balance(account)? })
+        }
+        Command::Capture { account, id, amount, pin } => {
+            let (hold, balance) = bank.capture_hold(account, *id, *amount, pin.as_deref())?;
+            let captured = Money::new(amount.unwrap_or(hold.amount.amount), &hold.amount.currency);
+            Ok(Output::HoldCaptured { account: account.clone(), hold, captured, balance })
+        }
+        Command::DepositCheque { account, amount, number, pin } => {
+            let amount = Money::new(*amount, &find_account(bank, account)?.currency);
+            let cheque = bank.deposit_cheque(account, amount, number, pin.as_deref())?;
+            let balance = find_account(bank, account)?.get_balance();
+            Ok(Output::ChequeDeposited { account: account.clone(), cheque, balance, available: bank.available_balance(account)? })
+        }
+        Command::Cheques { account, status } => {
+            let acct = find_account(bank, account)?;
+            Ok(Output::Cheques {
+                account: acct.name.clone(),
+                cheques: acct.cheques.iter().map(|c| (c.clone(), c.status(today))).filter(|(_, s)| status.as_deref().is_none_or(|status| *s == status)).collect(),
+            })
+        }
+        Command::ReturnCheque { account, id, reason } => {
+            let (cheque, balance) = bank.return_cheque(account, *id, reason)?;
+            Ok(Output::ChequeReturned { account: account.clone(), cheque, balance })
+        }
+        Command::Alerts { account } => {
+            let acct = find_account(bank, account)?;
+            Ok(Output::Alerts { account: acct.name.clone(), currency: acct.currency.clone(), alerts: acct.alerts.clone() })
+        }
+        Command::Transfer { from, to, amount, currency, pin, override_limits, idempotency_key } => {
+            let currency = match currency {
+                Some(code) => code.clone(),
+                None => find_account(bank, from)?.currency.clone(),
+            };
+            let amount = Money::new(*amount, &currency);
+            let transfer = |bank: &mut Bank| match idempotency_key {
+                Some(key) => bank.transfer_once(key, from, to, amount.clone(), pin.as_deref()),
+                None => bank.transfer(from, to, amount.clone(), pin.as_deref()),
+            };
+            let receipt = match override_limits {
+                Some(passphrase) => bank.override_conversion_limits(passphrase, transfer),
+                None => transfer(bank),
+            };
+            match receipt {
+                Ok(receipt) => Ok(Output::Transferred(receipt)),
+                Err(e) => held(bank, e),
+            }
+        }
+        Command::Approvals { status, account } => {
+            let account = account.as_deref().map(|name| find_account(bank, name).map(|a| a.name.clone())).transpose()?;
+            Ok(Output::Approvals(
+                bank.pending
+                    .iter()
+                    .filter(|p| status.as_deref().is_none_or(|s| p.state.name() == s) && account.as_deref().is_none_or(|a| p.held.involves(a)))
+                    .cloned()
+                    .collect(),
+            ))
+        }
+        Command::Approve { id, passphrase } => Ok(Output::Approval(bank.approve(*id, passphrase)?.clone())),
+        Command::Reject { id, passphrase, reason } => Ok(Output::Approval(bank.reject(*id, passphrase, reason)?.clone())),
+        Command::Exchange { from, to, amount, pin, override_limits } => {
+            let receipt = match override_limits {
+                Some(passphrase) => bank.override_conversion_limits(passphrase, |bank| bank.exchange(from, to, *amount, pin.as_deref()))?,
+                None => bank.exchange(from, to, *amount, pin.as_deref())?,
+            };
+            Ok(Output::Exchanged(receipt))
+        }
+        Command::History { account } => {
+            let acct = find_account(bank, account)?;
+            Ok(Output::History {
+                account: account.clone(),
+                entries: acct
+                    .history(&TransactionQuery::default())
+                    .into_iter()
+                    .map(|e| (e.transaction.to_transaction(), e.balance))
+                    .collect(),
+            })
+        }
+        Command::Tag { account, sequence, tags } => {
+            let index = bank.transaction_index(account, *sequence)?;
+            let tags = bank.tag_transaction(account, index, &tags.iter().map(String::as_str).collect::<Vec<_>>())?;
+            Ok(Output::Tagged { account: account.clone(), sequence: *sequence, tags })
+        }
+        Command::TagReport { account, start, end } => {
+            let acct = find_account(bank, account)?;
+            let query = TransactionQuery { from: *start, to: *end, ..TransactionQuery::default() };
+            let (start, end) = acct.statement_period(&query, today);
+            Ok(Output::TagReport { account: acct.name.clone(), start, end, totals: acct.report_by_tag(start, end) })
+        }
+        Command::Statement { account, format, start, end } => {
+            let query = TransactionQuery { from: *start, to: *end, ..TransactionQuery::default() };
+            let acct = find_account(bank, account)?;
+            let mut content = Vec::new();
+            match format {
+                StatementFormat::Html => {
+                    let (start, end) = acct.statement_period(&query, today);
+                    bank.statement(account, start, end)?.write_html(&mut content)?;
+                }
+                _ => acct.export(&mut content, *format, &query, bank.now())?,
+            }
+            Ok(Output::Statement {
+                account: account.clone(),
+                format: *format,
+                content: String::from_utf8_lossy(&content).into_owned(),
+            })
+        }
+        Command::Import { account, file, delimiter, pin } => {
+            let currency = find_account(bank, account)?.currency.clone();
+            let rows = fs::File::open(file)
+                .and_then(|f| CsvRows::new(io::BufReader::new(f), &currency, *delimiter))
+                .map_err(|e| io::Error::new(e.kind(), format!("cannot import {}: {}", file.display(), e)))?;
+            let report = bank.bulk_load(account, rows, pin.as_deref())?;
+            let balance = find_account(bank, account)?.get_balance();
+            Ok(Output::Imported { account: account.clone(), report, balance })
+        }
+        Command::Balance { account } => {
+            let acct = find_account(bank, account)?;
+            Ok(Output::Balance { account: account.clone(), balance: acct.get_balance(), available: bank.available_balance(account)?, version: acct.version })
+        }
+        Command::Forecast { account, days, rate, inflation, step, currency, since } => {
+            let acct = find_account(bank, account)?;
+            let start = since.unwrap_or(today);
+            let annual_interest = rate.unwrap_or_else(|| acct.rate_on(start));
+            let mut forecast = match (rate, since) {
+                (Some(rate), _) => {
+                    bank.check_interest_rate(*rate)?;
+                    acct.forecast_with_rate(*days, *rate, today)?
+                }
+                (None, Some(since)) => acct.value_dated_forecast(*days, *since)?,
+                (None, None) => acct.get_interest_forecast(*days, ForecastStep::Daily, today)?,
+            };
+            if let Some(inflation) = inflation {
+                adjust_for_inflation(&mut forecast, *inflation, acct.year_basis, start)?;
+            }
+            if let Some(currency) = currency {
+                let rate = bank.forex.convert(&Money::new(Decimal::ONE, &acct.currency), currency)?.amount;
+                convert_forecast(&mut forecast, currency, rate)?;
+            }
+            let forecast = summarize_forecast(forecast, start, *step)?;
+            Ok(Output::Forecast {
+                account: account.clone(),
+                annual_interest,
+                inflation: *inflation,
+                step: *step,
+                currency: currency.clone(),
+                days: forecast,
+            })
+        }
+        Command::InterestRate { account, rate, date } => {
+            Ok(interest_rates(bank.change_account_interest(account, *rate, date.unwrap_or(today))?, today))
+        }
+        Command::InterestRates { account } => Ok(interest_rates(find_account(bank, account)?, today)),
+        Command::Promotion { account, bonus, days, start } => {
+            Ok(interest_rates(bank.start_promotion(account, *bonus, start.unwrap_or(today), *days)?, today))
+        }
+        Command::EndPromotion { account } => {
+            bank.end_promotion(account)?;
+            Ok(interest_rates(find_account(bank, account)?, today))
+        }
+        Command::InterestPayout { account, to } => Ok(interest_rates(bank.set_interest_payout(account, to.as_deref())?, today)),
+        Command::YearBasis { account: Some(account), basis } => Ok(interest_rates(bank.change_account_year_basis(account, *basis)?, today)),
+        Command::YearBasis { account: None, basis } => {
+            bank.change_year_basis(*basis)?;
+            Ok(Output::YearBasis(*basis))
+        }
+        Command::InterestMethod { account: Some(account), method } => Ok(interest_rates(bank.change_account_interest_method(account, *method)?, today)),
+        Command::InterestMethod { account: None, method } => {
+            bank.change_interest_method(*method)?;
+            Ok(Output::InterestMethod(*method))
+        }
+        Command::InterestGrace { account: Some(account), days } => Ok(interest_rates(bank.change_account_interest_grace(account, *days)?, today)),
+        Command::InterestGrace { account: None, days } => {
+            bank.change_interest_grace(*days)?;
+            Ok(Output::InterestGrace(*days))
+        }
+        Command::Scenarios { account, days, scenarios } => {
+            let acct = find_account(bank, account)?;
+            let comparison = scenario::compare(&acct.get_balance(), scenarios, *days, today, acct.year_basis).map_err(Error::from)?;
+            Ok(Output::Scenarios { account: account.clone(), comparison })
+        }
+        Command::Compare { account, with, days } => Ok(Output::Comparison(bank.compare_accounts(account, with, *days)?)),
+        Command::MonteCarlo { account, days, paths, seed, rate_volatility, fx, deposits } => {
+            let mut simulation = bank
+                .monte_carlo(account, *days)?
+                .with_seed(seed.unwrap_or_else(|| bank.now() as u64))
+                .with_paths(*paths)
+                .with_rate_volatility(*rate_volatility);
+            simulation = match (&simulation.fx, fx) {
+                (None, Some(_)) => return Err(CliError::Usage(String::from("--drift and --volatility need a foreign-currency account"))),
+                (None, None) => simulation,
+                (Some(_), fx) => simulation.with_fx_model(fx.unwrap_or(RateModel::new(Decimal::ZERO, Decimal::new(10, 2)))),
+            };
+            if let Some(deposits) = deposits {
+                simulation = simulation.with_deposits(deposits.clone());
+            }
+            Ok(Output::MonteCarlo { account: account.clone(), outcome: simulation.run().map_err(Error::from)? })
+        }
+        Command::Pnl { account } => Ok(Output::Pnl(match account {
+            Some(name) => vec![bank.position_report(name)?],
+            None => bank.position_reports()?,
+        })),
+        Command::Portfolio { account: Some(account), date } => Ok(Output::Portfolio(bank.portfolio_value(account, date.unwrap_or(today))?)),
+        Command::Portfolio { account: None, date } => Ok(Output::Portfolios(bank.portfolio_values(date.unwrap_or(today))?)),
+        Command::Interest { days, since } => Ok(Output::InterestPosted(match since {
+            Some(since) => bank.post_interest_all_since(*since)?,
+            None => bank.post_interest_all(days.unwrap_or_default())?,
+        })),
+        Command::Goal { account, name, target, date } => {
+            let acct = bank.find_account_mut(account)?;
+            let goal = SavingsGoal::new(name, Money::new(*target, &acct.currency), *date);
+            acct.add_goal(goal.clone(), today).map_err(Error::from)?;
+            Ok(Output::GoalSet { account: account.clone(), goal })
+        }
+        Command::Goals { account, frequency } => {
+            let acct = find_account(bank, account)?;
+            let goals = acct
+                .goals
+                .iter()
+                .map(|g| Ok((g.clone(), acct.goal_progress(&g.name, *frequency, today)?)))
+                .collect::<Result<_, AccountError>>()
+                .map_err(Error::from)?;
+            Ok(Output::Goals { account: account.clone(), frequency: *frequency, goals })
+        }
+        Command::Envelope { account, category, limit } => {
+            let acct = bank.find_account_mut(account)?;
+            let envelope = Envelope::new(category, Money::new(*limit, &acct.currency));
+            acct.add_envelope(envelope.clone()).map_err(Error::from)?;
+            Ok(Output::EnvelopeSet { account: account.clone(), envelope })
+        }
+        Command::Budget { account, date } => {
+            let date = date.unwrap_or(today);
+            Ok(Output::Budget { account: account.clone(), date, envelopes: find_account(bank, account)?.budget(date) })
+        }
+        Command::Summary { month, csv } => {
+            let summary = bank.monthly_summary(month.unwrap_or(Month::of(today)));
+            let csv = match csv {
+                true => {
+                    let mut content = Vec::new();
+                    summary.write_csv(&mut content)?;
+                    Some(String::from_utf8_lossy(&content).into_owned())
+                }
+                false => None,
+            };
+            Ok(Output::Summary { summary, csv })
+        }
+        Command::TaxCertificate { account, year, format } => {
+            let certificate = bank.tax_certificate(account, year.unwrap_or(today.year - 1))?;
+            let mut content = Vec::new();
+            match format {
+                Some(StatementFormat::Html) => certificate.write_html(&mut content)?,
+                Some(_) => certificate.write_csv(&mut content)?,
+                None => {}
+            }
+            let content = format.map(|_| String::from_utf8_lossy(&content).into_owned());
+            Ok(Output::TaxCertificate { certificate, content })
+        }
+        Command::Loan { account, amount, rate, term, frequency, pin } => {
+            let loan = bank.open_loan(account, *amount, *rate, *term, *frequency, pin.as_deref())?;
+            Ok(Output::LoanOpened(loan.clone()))
+        }
+        Command::Schedule { loan } => {
+            let loan = bank.find_loan(*loan).ok_or(BankError::LoanNotFound(*loan))?;
+            Ok(Output::Schedule { rows: loan.amortization_schedule(), loan: loan.clone() })
+        }
+        Command::Repay { loan, pin } => {
+            let row = bank.pay_loan(*loan, pin.as_deref())?;
+            let loan = bank.find_loan(*loan).ok_or(BankError::LoanNotFound(*loan))?.clone();
+            let balance = find_account(bank, &loan.account)?.get_balance();
+            Ok(Output::LoanPaid { loan, row: Box::new(row), balance })
+        }
+        Command::Paydown { loan, amount, deposit_rate, days } => {
+            let loan = match loan {
+                PaydownLoan::Booked(id) => bank.find_loan(*id).ok_or(BankError::LoanNotFound(*id))?.clone(),
+                PaydownLoan::Quote { principal, rate, term, frequency } => {
+                    let base = &bank.base_currency.code;
+                    let principal = Money::new(*principal, base);
+                    Loan::new(0, "", principal, bank.forex.decimals(base), *rate, *term, *frequency).map_err(BankError::from)?
+                }
+            };
+            let amount = Money::new(*amount, &loan.principal.currency);
+            Ok(Output::Paydown(PaydownComparison::run(&loan, &amount, *deposit_rate, bank.withholding_tax_rate, *days).map_err(Error::from)?))
+        }
+        Command::Order { from, to, amount, currency, every, start, pin } => {
+            let currency = match currency {
+                Some(code) => code.clone(),
+                None => find_account(bank, from)?.currency.clone(),
+            };
+            let first = start.unwrap_or(today);
+            let order = bank.create_standing_order(from, to, Money::new(*amount, &currency), *every, first, pin.as_deref())?;
+            Ok(Output::OrderCreated(order.clone()))
+        }
+        Command::Orders => Ok(Output::Orders(bank.standing_orders.clone())),
+        Command::Skip { order } => {
+            bank.skip_standing_order(*order)?;
+            let order = bank.find_standing_order(*order).ok_or(BankError::StandingOrderNotFound(*order))?;
+            Ok(Output::OrderSkipped(order.clone()))
+        }
+        Command::Cancel { order } => Ok(Output::OrderCancelled(bank.cancel_standing_order(*order)?)),
+        Command::Sweep { from, to, above, pin } => Ok(Output::SweepCreated(bank.create_sweep(from, to, *above, pin.as_deref())?.clone())),
+        Command::Sweeps => Ok(Output::Sweeps(bank.sweeps.clone())),
+        Command::CancelSweep { sweep } => Ok(Output::SweepCancelled(bank.cancel_sweep(*sweep)?)),
+        Command::Forward { account, side, amount, currency, rate, date, pin } => {
+            let contract = bank.book_forward(account, *side, Money::new(*amount, currency), *rate, *date, pin.as_deref())?;
+            Ok(Output::ForwardBooked(contract.clone()))
+        }
+        Command::Forwards => Ok(Output::Forwards(bank.forward_valuations()?)),
+        Command::Limit { account, amount, from, to, rate, expires, average, pin } => {
+            let id = bank.place_limit_order(account, Money::new(*amount, from), to, *rate, *expires, pin.as_deref())?.id;
+            Ok(Output::LimitPlaced(bank.confirm_limit_order(id, *average)?.clone()))
+        }
+        Command::AmendLimit { limit, amount, rate, expires, average } => {
+            let current = bank
+                .limit_orders
+                .iter()
+                .find(|o| o.id == *limit && !o.filled)
+                .ok_or(BankError::LimitOrderNotFound(*limit))?;
+            let (amount, rate, expires) = (amount.unwrap_or(current.amount.amount), rate.unwrap_or(current.limit), expires.unwrap_or(current.expires));
+            let average = average.unwrap_or(current.average);
+            bank.amend_limit_order(*limit, amount, rate, expires)?;
+            Ok(Output::LimitAmended(bank.confirm_limit_order(*limit, average)?.clone()))
+        }
+        Command::Limits => Ok(Output::Limits(bank.open_limit_orders().into_iter().map(|(o, spot)| (o.clone(), spot)).collect())),
+        Command::CancelLimit { limit } => Ok(Output::LimitCancelled(bank.cancel_limit_order(*limit)?)),
+        Command::Simulate { days, seed, default, models } => {
+            let seed = seed.unwrap_or_else(|| bank.now() as u64);
+            let mut simulator = models.iter().fold(MarketSimulator::new(seed).with_default(*default), |sim, (code, model)| sim.with_model(code, *model));
+            let steps = simulator.run(bank, *days).map_err(Error::from)?;
+            Ok(Output::Simulated { seed, steps, positions: bank.position_reports()? })
+        }
+        Command::Replay { file, delimiter, start, end } => {
+            let history = read_history(file, *delimiter)?.between(*start, *end);
+            let codes = history.codes.clone();
+            let steps = RateReplay::new(history).run(bank).map_err(Error::from)?;
+            Ok(Output::Replayed { codes, steps, positions: bank.position_reports()? })
+        }
+        Command::EndOfDay { date } => {
+            let date = date.unwrap_or(today);
+            Ok(Output::EndOfDay(bank.end_of_day(date)))
+        }
+        Command::Demo { accounts, days, seed } => {
+            let seed = seed.unwrap_or_else(|| bank.now() as u64);
+            Ok(Output::Demo(DemoData::new(seed).with_accounts(*accounts).with_days(*days).populate(bank)?))
+        }
+        Command::Compact { before, archive } => Ok(Output::Compacted(compaction::compact(bank, *before, archive)?)),
+        Command::Verify => Ok(Output::Verified(bank.verify())),
+        Command::Rounding => {
+            let (residue, total) = bank.rounding_effect();
+            Ok(Output::Rounding { strategy: bank.rounding.strategy, residue, total })
+        }
+        Command::Help => Ok(Output::Help),
+        Command::Versioned { version, command } => {
+            if let Some(account) = command.versioned_account() {
+                bank.expect_version(account, *version)?;
+            }
+            execute(bank, command)
+        }
+        Command::Admin { passphrase, command } => bank.as_admin(passphrase, |bank| execute(bank, command)),
+    }
+}
+
+/// The result of one command, printable as text or as JSON.
+#[derive(Debug, Clone)]
+pub enum Output {
+    /// `volatility` holds the currencies with enough history over
+    /// `window` daily returns.
+    Rates { base: String, currencies: Vec<Currency>, window: usize, volatility: Vec<RateVolatility> },
+    RateHistory { code: String, sma: usize, ema: usize, closes: Vec<AveragedClose> },
+    RateSummary(Vec<RateStats>),
+    /// The limit orders the new rate reached, filled or refused.
+    RateRecorded { code: String, rate: Decimal, fills: Vec<LimitOrderFill> },
+    CashRateRecorded { code: String, rate: Option<Decimal> },
+    /// A quote, fee included; amounts are exact.
+    Conversion(Conversion, RateTrend),
+    Dca(DcaSimulation),
+    BasketDefined(BasketQuote),
+    Baskets(Vec<BasketQuote>),
+    Fees { fees: FeeSchedule, spreads: Vec<PairSpread> },
+    /// Every capped currency's limit, by code.
+    ConversionLimits(BTreeMap<String, ConversionLimit>),
+    /// Every limit profile, or, with `account`, the account's name and type
+    /// and only the profiles it falls under; the caps are in `base`.
+    LimitProfiles { account: Option<(String, Option<String>)>, base: String, profiles: Vec<(LimitTarget, LimitProfile)> },
+    Conversions(Vec<ConversionRecord>),
+    Receipt(ExchangeReceipt),
+    /// `days` has the volumes in `base`.
+    Turnover { base: String, days: Vec<DailyTurnover> },
+    Accounts { accounts: Vec<(String, usize, Money)> },
+    /// `(alias, account)` pairs.
+    Aliases(Vec<(String, String)>),
+    /// The currencies deposits to `account` are converted from into its
+    /// `currency`.
+    AutoConversion { account: String, currency: String, currencies: Vec<String> },
+    Alerts { account: String, currency: String, alerts: AlertSettings },
+    ServiceCharges { account: String, currency: String, charges: ServiceCharges },
+    /// `available` is the account's available balance after the change.
+    HoldPlaced { account: String, hold: Hold, available: Money },
+    HoldReleased { account: String, hold: Hold, available: Money },
+    /// `captured` is what was withdrawn from `hold`.
+    HoldCaptured { account: String, hold: Hold, captured: Money, balance: Money },
+    Holds { account: String, holds: Vec<Hold>, balance: Money, available: Money },
+    ChequeDeposited { account: String, cheque: Cheque, balance: Money, available: Money },
+    ChequeReturned { account: String, cheque: Cheque, balance: Money },
+    /// Each cheque with its `Cheque::status` today.
+    Cheques { account: String, cheques: Vec<(Cheque, &'static str)> },
+    /// `(account, id, day archived)` of every archived account.
+    Archived(Vec<(String, usize, Date)>),
+    Registered { account: String, id: usize, currency: String, protected: bool },
+    /// `envelope` is the budget a categorized withdrawal went against.
+    Posted { account: String, tx_type: TransactionType, amount: Money, balance: Money, envelope: Option<EnvelopeStatus> },
+    Transferred(TransferReceipt),
+    Exchanged(TransferReceipt),
+    /// A withdrawal or transfer held for approval, or just approved or
+    /// rejected.
+    Approval(PendingTransaction),
+    Approvals(Vec<PendingTransaction>),
+    /// `version` is the account's, for `--if-version`.
+    Balance { account: String, balance: Money, available: Money, version: u64 },
+    /// Each transaction with the running balance after it.
+    History { account: String, entries: Vec<(Transaction, Money)> },
+    Tagged { account: String, sequence: u64, tags: Vec<String> },
+    TagReport { account: String, start: Date, end: Date, totals: Vec<TagTotal> },
+    /// The exported file's text.
+    Statement { account: String, format: StatementFormat, content: String },
+    /// Rows posted and skipped by an import, and the balance after it.
+    Imported { account: String, report: ImportReport, balance: Money },
+    Forecast { account: String, annual_interest: Decimal, inflation: Option<Decimal>, step: ForecastStep, currency: Option<String>, days: Vec<InterestForecast> },
+    /// An account's opening annual rate and its changes since.
+    /// `current` is the rate in force today, counting the promotion.
+    /// `payout` is the account the interest is paid into, if not this one.
+    InterestRates {
+        account: String,
+        opening: Decimal,
+        changes: Vec<RateChange>,
+        promotion: Option<Promotion>,
+        current: Decimal,
+        payout: Option<String>,
+        year_basis: YearBasis,
+        interest_method: InterestMethod,
+        grace_days: u32,
+    },
+    YearBasis(YearBasis),
+    InterestMethod(InterestMethod),
+    InterestGrace(u32),
+    Scenarios { account: String, comparison: ScenarioComparison },
+    Comparison(AccountComparison),
+    MonteCarlo { account: String, outcome: MonteCarloOutcome },
+    Pnl(Vec<PositionReport>),
+    Portfolio(Portfolio),
+    Portfolios(Vec<Portfolio>),
+    /// Each account's name and the interest posted to it.
+    InterestPosted(Vec<(String, Money)>),
+    GoalSet { account: String, goal: SavingsGoal },
+    Goals { account: String, frequency: PaymentFrequency, goals: Vec<(SavingsGoal, GoalProgress)> },
+    EnvelopeSet { account: String, envelope: Envelope },
+    /// Each envelope's status in the month containing `date`.
+    Budget { account: String, date: Date, envelopes: Vec<EnvelopeStatus> },
+    /// `csv` holds the summary written as CSV when that was asked for.
+    Summary { summary: MonthlySummary, csv: Option<String> },
+    /// `content` holds the certificate written as CSV or HTML when that
+    /// was asked for.
+    TaxCertificate { certificate: TaxCertificate, content: Option<String> },
+    LoanOpened(Loan),
+    Schedule { loan: Loan, rows: Vec<AmortizationRow> },
+    /// The loan after the payment, the row paid, and the account balance.
+    LoanPaid { loan: Loan, row: Box<AmortizationRow>, balance: Money },
+    Paydown(PaydownComparison),
+    OrderCreated(StandingOrder),
+    Orders(Vec<StandingOrder>),
+    OrderSkipped(StandingOrder),
+    OrderCancelled(StandingOrder),
+    SweepCreated(SweepRule),
+    Sweeps(Vec<SweepRule>),
+    SweepCancelled(SweepRule),
+    ForwardBooked(ForwardContract),
+    Forwards(Vec<ForwardValuation>),
+    LimitPlaced(LimitOrder),
+    /// Open orders with today's spot rate for each.
+    Limits(Vec<(LimitOrder, Decimal)>),
+    LimitAmended(LimitOrder),
+    LimitCancelled(LimitOrder),
+    EndOfDay(EndOfDay),
+    /// Each simulated day, then the FX profit and loss of the
+    /// foreign-currency accounts at the final rates.
+    Simulated { seed: u64, steps: Vec<MarketStep>, positions: Vec<PositionReport> },
+    /// Each replayed day, with the file's currency `codes`, then the FX
+    /// profit and loss at the last rates.
+    Replayed { codes: Vec<String>, steps: Vec<MarketStep>, positions: Vec<PositionReport> },
+    Compacted(CompactionReport),
+    Demo(DemoReport),
+    Verified(IntegrityReport),
+    /// Each currency's residue with its base-currency value (`None` when
+    /// the currency is no longer quoted), and their total.
+    Rounding { strategy: RoundingStrategy, residue: Vec<(String, Decimal, Option<Money>)>, total: Money },
+    Help,
+}
+
+impl Output {
+    /// The option a command left to default that its result depends on,
+    /// with the value it took: the seed of `simulate` and `demo`, and the
+    /// date of `eod`.
+    pub fn pinned(&self) -> Option<(&'static str, String)> {
+        match self {
+            Output::Simulated { seed, .. } => Some(("seed", seed.to_string())),
+            Output::Demo(report) => Some(("seed", report.seed.to_string())),
+            Output::EndOfDay(eod) => Some(("date", eod.date.to_string())),
+            _ => None,
+        }
+    }
+
+    /// Human-readable rendering, amounts formatted in the bank's locale.
+    pub fn to_text(&self, bank: &Bank) -> String {
+        match self {
+            Output::Rates { base, currencies, window, volatility } => {
+                let mut table = Table::new(&[
+                    ("Code", Align::Left),
+                    ("Rate", Align::Right),
+                    ("Cash", Align::Right),
+                    ("Vol", Align::Right),
+                    ("Name", Align::Left),
+                    ("Source", Align::Left),
+                    ("Updated", Align::Left),
+                ]);
+                for c in currencies {
+                    let (rate, cash) = if c.code == *base {
+                        (c.rate.to_string(), c.rate.to_string())
+                    } else {
+                        (c.format_rate(c.rate), c.format_rate(c.rate_of(RateType::Cash)))
+                    };
+                    let vol = volatility.iter().find(|v| v.code == c.code).map_or_else(|| String::from("-"), |v| percent(v.annualized));
+                    table.row([
+                        c.code.clone(),
+                        rate,
+                        cash,
+                        vol,
+                        c.name.clone(),
+                        c.source.as_ref().map_or_else(|| "unknown".to_string(), RateSource::to_string),
+                        match (c.updated, bank.forex.is_carried(&c.code, bank.today())) {
+                            (Some(t), true) => format!("{} (carried)", bank.format_timestamp(t)),
+                            (Some(t), false) => bank.format_timestamp(t),
+                            (None, _) => "unknown".to_string(),
+                        },
+                    ]);
+                }
+                format!("Rates per 1 unit in {}:\n{}\nVol: annualized volatility of the last {} daily moves on record.", base, table, window)
+            }
+            Output::RateSummary(stats) if stats.is_empty() => String::from("No closing rates on record in the period."),
+            Output::RateSummary(stats) => {
+                let mut table = Table::new(&[
+                    ("Code", Align::Left),
+                    ("From", Align::Left),
+                    ("To", Align::Left),
+                    ("Days", Align::Right),
+                    ("Open", Align::Right),
+                    ("High", Align::Right),
+                    ("Low", Align::Right),
+                    ("Average", Align::Right),
+                    ("Close", Align::Right),
+                    ("Change", Align::Right),
+                ]);
+                for s in stats {
+                    let dp = bank.forex.rate_decimals(&s.code) as usize;
+                    let rate = |r: Decimal| format!("{:.*}", dp, r);
+                    table.row([
+                        s.code.clone(),
+                        s.start.to_string(),
+                        s.end.to_string(),
+                        s.days.to_string(),
+                        rate(s.open),
+                        rate(s.high),
+                        rate(s.low),
+                        rate(s.average),
+                        rate(s.close),
+                        percent(s.change()),
+                    ]);
+                }
+                format!("Daily closing rates per 1 unit in {}:\n{}", bank.forex.get_base_rate(), table)
+            }
+            Output::RateHistory { code, closes, .. } if closes.is_empty() => format!("No closing rates on record for {}.", code),
+            Output::RateHistory { code, sma, ema, closes } => {
+                let dp = bank.forex.rate_decimals(code) as usize;
+                let rate = |r: Option<Decimal>| r.map_or_else(|| String::from("-"), |r| format!("{:.*}", dp, r));
+                let (sma_header, ema_header) = (format!("SMA {}", sma), format!("EMA {}", ema));
+                let mut table = Table::new(&[("Date", Align::Left), ("Rate", Align::Right), (&sma_header, Align::Right), (&ema_header, Align::Right)]);
+                for close in closes {
+                    let date = if close.carried { format!("{} *", close.date) } else { close.date.to_string() };
+                    table.row([date, rate(Some(close.rate)), rate(close.sma), rate(close.ema)]);
+                }
+                format!(
+                    "Closing rates of {} in {}:\n{}\nSMA and EMA: simple and exponential moving averages of the last {} and {} closes.{}",
+                    code,
+                    bank.forex.get_base_rate(),
+                    table,
+                    sma,
+                    ema,
+                    if closes.iter().any(|c| c.carried) { "\n*: no rate published that day; the last business day's close is carried." } else { "" }
+                )
+            }
+            Output::RateRecorded { code, fills, .. } => std::iter::once(format!("Recorded exchange rate for {}.", code))
+                .chain(fills.iter().map(|fill| fill_line(bank, fill)))
+                .collect::<Vec<_>>()
+                .join("\n"),
+            Output::CashRateRecorded { code, rate: Some(_) } => format!("Recorded cash rate for {}.", code),
+            Output::CashRateRecorded { code, rate: None } => format!("{} is quoted in cash at its transfer rate.", code),
+            Output::Conversion(c, trend) if c.fee.amount.is_zero() => {
+                format!("{} = {}{}{}", bank.format_money(&c.source), bank.format_money(&c.gross), leg_lines(bank, &c.legs), trend_line(trend))
+            }
+            Output::Conversion(c, trend) => format!(
+                "{} = {}{}\nFee ({}{}): {}\nYou receive: {}{}",
+                bank.format_money(&c.source),
+                bank.format_money(&c.gross),
+                leg_lines(bank, &c.legs),
+                percent(c.fee_rate),
+                if c.fee_basis == FeeBasis::PairSpread { format!(", {}/{} spread", c.source.currency, c.gross.currency) } else { String::new() },
+                bank.format_money(&c.fee),
+                bank.format_money(&c.net),
+                trend_line(trend)
+            ),
+            Output::Dca(sim) => {
+                let mut table = Table::new(&[("Period", Align::Right), ("Rate", Align::Right), ("Spent", Align::Right), ("Bought", Align::Right)]);
+                for p in &sim.purchases {
+                    table.row([p.period.to_string(), p.rate.to_string(), bank.format_money(&p.spent), bank.format_money(&p.units)]);
+                }
+                let first = sim.purchases.first().map_or(sim.final_rate, |p| p.rate);
+                let advantage = sim.advantage();
+                format!(
+                    "{}\nInvested {} over {} periods.\nPeriodic: {} at an average of {}, worth {} at {}.\nLump sum: {} at {}, worth {}.\nPeriodic purchases {} the lump sum by {}.",
+                    table,
+                    bank.format_money(&sim.invested),
+                    sim.purchases.len(),
+                    bank.format_money(&sim.units),
+                    sim.average_cost.round_dp(6),
+                    bank.format_money(&sim.final_value),
+                    sim.final_rate,
+                    bank.format_money(&sim.lump_sum_units),
+                    first,
+                    bank.format_money(&sim.lump_sum_value),
+                    if advantage.amount < Decimal::ZERO { "trail" } else { "beat" },
+                    bank.format_money(&Money::new(advantage.amount.abs(), &advantage.currency))
+                )
+            }
+            Output::BasketDefined(quote) => {
+                let shares: Vec<String> = quote
+                    .shares
+                    .iter()
+                    .map(|s| format!("{} {} ({})", s.quantity.round_dp(BASKET_RATE_DP), s.code, percent(s.weight)))
+                    .collect();
+                format!(
+                    "Defined basket {} ({}): 1 {} = {} {}, made of {}.",
+                    quote.currency.code,
+                    quote.currency.name,
+                    quote.currency.code,
+                    quote.currency.rate,
+                    bank.forex.get_base_rate(),
+                    shares.join(", ")
+                )
+            }
+            Output::Conversions(records) if records.is_empty() => String::from("No conversions."),
+            Output::Conversions(records) => {
+                let mut table = Table::new(&[
+                    ("Receipt", Align::Right),
+                    ("Time", Align::Left),
+                    ("Account", Align::Left),
+                    ("In", Align::Right),
+                    ("Out", Align::Right),
+                    ("Rate", Align::Right),
+                    ("Fee", Align::Right),
+                ]);
+                for r in records {
+                    table.row([
+                        r.receipt.to_string(),
+                        bank.format_timestamp(r.timestamp),
+                        r.account.clone().unwrap_or_else(|| String::from("-")),
+                        bank.format_money(&r.amount_in),
+                        bank.format_money(&r.amount_out),
+                        r.rate.round_dp(EXCHANGE_RATE_DP).to_string(),
+                        bank.format_money(&r.fee),
+                    ]);
+                }
+                table.to_string()
+            }
+            Output::Receipt(receipt) => receipt.to_string(),
+            Output::Turnover { days, .. } if days.is_empty() => String::from("No conversions."),
+            Output::Turnover { base, days } => {
+                let mut table = Table::new(&[("Date", Align::Left), ("Conversions", Align::Right), ("Volume", Align::Right)]);
+                for d in days {
+                    table.row([d.date.to_string(), d.conversions.to_string(), bank.format_money(&Money::new(d.volume, base))]);
+                }
+                let count: usize = days.iter().map(|d| d.conversions).sum();
+                let volume = days.iter().fold(Decimal::ZERO, |total, d| total + d.volume);
+                format!("{}\nTotal: {} conversion(s), {}", table, count, bank.format_money(&Money::new(volume, base)))
+            }
+            Output::Fees { fees, spreads } if fees.is_empty() && spreads.is_empty() => String::from("No conversion fees."),
+            Output::Fees { fees, spreads } => {
+                let base = bank.forex.get_base_rate();
+                let mut sections = Vec::new();
+                if !fees.is_empty() {
+                    let mut table = Table::new(&[("From", Align::Right), ("Fee", Align::Right)]);
+                    for t in fees.tiers() {
+                        table.row([bank.format_money(&Money::new(t.from, base)), percent(t.rate)]);
+                    }
+                    sections.push(format!("Conversion fees by volume in {}:\n{}", base, table));
+                }
+                if !spreads.is_empty() {
+                    let mut table = Table::new(&[("Pair", Align::Left), ("Fee", Align::Right)]);
+                    for s in spreads {
+                        table.row([s.label(), percent(s.rate)]);
+                    }
+                    sections.push(format!("Pair spreads, in place of the fees by volume:\n{}", table));
+                }
+                sections.join("\n")
+            }
+            Output::LimitProfiles { account, base, profiles } => {
+                let heading = match account {
+                    None if profiles.is_empty() => return String::from("No limit profiles."),
+                    None => format!("Limit profiles, in {}:", base),
+                    Some((name, kind)) => {
+                        let kind = kind.as_ref().map_or_else(|| String::from("no type"), |kind| format!("type {}", kind));
+                        if profiles.is_empty() {
+                            return format!("{} ({}) falls under no limit profile.", name, kind);
+                        }
+                        format!("{} ({}) falls under these limit profiles, in {}:", name, kind, base)
+                    }
+                };
+                let mut table = Table::new(&[("Target", Align::Left), ("Max deposit", Align::Right), ("Max withdrawal", Align::Right), ("Max daily conversion", Align::Right)]);
+                for (target, profile) in profiles {
+                    let cap = |cap: Option<Decimal>| cap.map_or(String::from("-"), |cap| bank.format_money(&Money::new(cap, base)));
+                    table.row([target.to_string(), cap(profile.max_deposit), cap(profile.max_withdrawal), cap(profile.max_daily_conversion)]);
+                }
+                format!("{}\n{}", heading, table)
+            }
+            Output::ConversionLimits(limits) if limits.is_empty() => String::from("No conversion limits."),
+            Output::ConversionLimits(limits) => {
+                let mut table = Table::new(&[("Currency", Align::Left), ("Per transaction", Align::Right), ("Per day", Align::Right)]);
+                for (code, limit) in limits {
+                    let cap = |cap: Option<Decimal>| cap.map_or(String::from("-"), |cap| bank.format_money(&Money::new(cap, code)));
+                    table.row([code.clone(), cap(limit.per_transaction), cap(limit.per_day)]);
+                }
+                format!("Conversion limits:\n{}", table)
+            }
+            Output::Baskets(quotes) => {
+                let mut table = Table::new(&[
+                    ("Basket", Align::Left),
+                    ("Rate", Align::Right),
+                    ("Component", Align::Left),
+                    ("Quantity", Align::Right),
+                    ("Weight", Align::Right),
+                ]);
+                for q in quotes {
+                    for s in &q.shares {
+                        table.row([
+                            q.currency.code.clone(),
+                            q.currency.rate.to_string(),
+                            s.code.clone(),
+                            s.quantity.round_dp(BASKET_RATE_DP).to_string(),
+                            percent(s.weight),
+                        ]);
+                    }
+                }
+                table.to_string()
+            }
+            Output::Accounts { accounts } => {
+                let mut table = Table::new(&[("ID", Align::Right), ("Name", Align::Left), ("Balance", Align::Right)]);
+                for (name, id, balance) in accounts {
+                    table.row([id.to_string(), name.clone(), bank.format_money(balance)]);
+                }
+                table.to_string()
+            }
+            Output::Archived(accounts) if accounts.is_empty() => String::from("No archived accounts."),
+            Output::Archived(accounts) => {
+                let mut table = Table::new(&[("ID", Align::Right), ("Name", Align::Left), ("Archived", Align::Left)]);
+                for (name, id, on) in accounts {
+                    table.row([id.to_string(), name.clone(), on.to_string()]);
+                }
+                table.to_string()
+            }
+            Output::Aliases(aliases) if aliases.is_empty() => String::from("No aliases."),
+            Output::Aliases(aliases) => {
+                let mut table = Table::new(&[("Alias", Align::Left), ("Account", Align::Left)]);
+                for (alias, account) in aliases {
+                    table.row([alias, account]);
+                }
+                table.to_string()
+            }
+            Output::AutoConversion { account, currencies, .. } if currencies.is_empty() => format!("Deposits to {} are not converted.", account),
+            Output::AutoConversion { account, currency, currencies } => {
+                format!("Deposits to {} in {} are converted into {} as they are posted.", account, currencies.join(", "), currency)
+            }
+            Output::Alerts { account, alerts, .. } if alerts.is_empty() => format!("No alerts are set for {}.", account),
+            Output::Alerts { account, currency, alerts } => {
+                let mut lines = vec![format!("Alerts for {}:", account)];
+                if let Some(threshold) = alerts.balance_below {
+                    lines.push(format!("  balance below {}", bank.format_money(&Money::new(threshold, currency))));
+                }
+                if let Some(threshold) = alerts.withdrawal_above {
+                    lines.push(format!("  withdrawal above {}", bank.format_money(&Money::new(threshold, currency))));
+                }
+                if let Some(days) = alerts.inactive_days {
+                    lines.push(format!("  no activity for {} days", days));
+                }
+                lines.join("\n")
+            }
+            Output::ServiceCharges { account, charges, .. } if charges.is_empty() => format!("No service charges are set for {}.", account),
+            Output::ServiceCharges { account, currency, charges } => {
+                let mut lines = vec![format!("Service charges for {}, taken at each month's end:", account)];
+                if let Some(charge) = charges.monthly {
+                    lines.push(format!("  {} every month", bank.format_money(&Money::new(charge, currency))));
+                }
+                if let Some(m) = charges.maintaining {
+                    lines.push(format!(
+                        "  {} for a month whose average daily balance is below {}",
+                        bank.format_money(&Money::new(m.charge, currency)),
+                        bank.format_money(&Money::new(m.balance, currency))
+                    ));
+                }
+                lines.join("\n")
+            }
+            Output::HoldPlaced { account, hold, available } => format!(
+                "Placed hold {} of {} on {} for {}, until {}.\nAvailable: {}",
+                hold.id,
+                bank.format_money(&hold.amount),
+                account,
+                hold.reason,
+                hold.expiry,
+                bank.format_money(available)
+            ),
+            Output::HoldReleased { account, hold, available } => {
+                format!("Released hold {} of {} on {}.\nAvailable: {}", hold.id, bank.format_money(&hold.amount), account, bank.format_money(available))
+            }
+            Output::HoldCaptured { account, hold, captured, balance } => {
+                format!("Captured {} of hold {} on {} for {}.\nUpdated Balance: {}", bank.format_money(captured), hold.id, account, hold.reason, bank.format_money(balance))
+            }
+            Output::Holds { account, holds, .. } if holds.is_empty() => format!("No active holds on {}.", account),
+            Output::Holds { holds, balance, available, .. } => {
+                let mut table = Table::new(&[("ID", Align::Right), ("Placed", Align::Left), ("Expires", Align::Left), ("Amount", Align::Right), ("Reason", Align::Left)]);
+                for h in holds {
+                    table.row([h.id.to_string(), h.placed.to_string(), h.expiry.to_string(), bank.format_money(&h.amount), h.reason.clone()]);
+                }
+                format!("{}\nBalance: {}\nAvailable: {}", table, bank.format_money(balance), bank.format_money(available))
+            }
+            Output::ChequeDeposited { account, cheque, balance, available } => format!(
+                "Deposited cheque {} of {} to {} as cheque {}; it clears on {}.\nUpdated Balance: {}\nAvailable: {}",
+                cheque.number,
+                bank.format_money(&cheque.amount),
+                account,
+                cheque.id,
+                cheque.clears,
+                bank.format_money(balance),
+                bank.format_money(available)
+            ),
+            Output::ChequeReturned { account, cheque, balance } => {
+                let reason = cheque.returned.as_ref().map_or("", |(_, reason)| reason.as_str());
+                format!("Returned cheque {} of {} on {}: {}.\nUpdated Balance: {}", cheque.number, bank.format_money(&cheque.amount), account, reason, bank.format_money(balance))
+            }
+            Output::Cheques { account, cheques } if cheques.is_empty() => format!("No cheques on {}.", account),
+            Output::Cheques { cheques, .. } => {
+                let mut table = Table::new(&[
+                    ("ID", Align::Right),
+                    ("Number", Align::Left),
+                    ("Deposited", Align::Left),
+                    ("Clears", Align::Left),
+                    ("Amount", Align::Right),
+                    ("Status", Align::Left),
+                ]);
+                for (c, status) in cheques {
+                    let status = match &c.returned {
+                        Some((on, reason)) => format!("returned {}: {}", on, reason),
+                        None => status.to_string(),
+                    };
+                    table.row([c.id.to_string(), c.number.clone(), c.deposited.to_string(), c.clears.to_string(), bank.format_money(&c.amount), status]);
+                }
+                table.to_string()
+            }
+            Output::Registered { account, id, currency, .. } if *currency != bank.base_currency.code => {
+                format!("Registered account {} (ID {}) in {}.", account, id, currency)
+            }
+            Output::Registered { account, id, .. } => format!("Registered account {} (ID {}).", account, id),
+            Output::Posted { balance, envelope: None, .. } => format!("Updated Balance: {}", bank.format_money(balance)),
+            Output::Posted { balance, envelope: Some(status), .. } => {
+                let budget = if status.is_overspent() {
+                    format!("Over the {} budget by {} this month.", status.category, bank.format_money(&status.overspent))
+                } else {
+                    format!("{} left in the {} budget this month.", bank.format_money(&status.remaining), status.category)
+                };
+                format!("Updated Balance: {}\n{}", bank.format_money(balance), budget)
+            }
+            Output::Transferred(r) if r.fee.amount.is_zero() => format!(
+                "Transferred {} from {} to {} (credited {}{}).{}",
+                bank.format_money(&r.debited),
+                r.from,
+                r.to,
+                bank.format_money(&r.credited),
+                receipt_note(r),
+                leg_lines(bank, &r.legs)
+            ),
+            Output::Transferred(r) => format!(
+                "Transferred {} from {} to {} (credited {} after a {} conversion fee{}).{}",
+                bank.format_money(&r.debited),
+                r.from,
+                r.to,
+                bank.format_money(&r.credited),
+                bank.format_money(&r.fee),
+                receipt_note(r),
+                leg_lines(bank, &r.legs)
+            ),
+            Output::Approval(a) => match &a.state {
+                TransactionState::Pending => format!("Held the {} for an admin's approval as request {}; it posts once approved.", held_text(bank, &a.held), a.id),
+                TransactionState::Posted => format!("Approved request {}: posted the {}.", a.id, held_text(bank, &a.held)),
+                TransactionState::Rejected(reason) => format!("Rejected request {}, the {}: {}", a.id, held_text(bank, &a.held), reason),
+            },
+            Output::Approvals(requests) if requests.is_empty() => String::from("No approval requests."),
+            Output::Approvals(requests) => {
+                let mut table = Table::new(&[
+                    ("ID", Align::Right),
+                    ("Requested", Align::Left),
+                    ("Type", Align::Left),
+                    ("From", Align::Left),
+                    ("To", Align::Left),
+                    ("Amount", Align::Right),
+                    ("Status", Align::Left),
+                ]);
+                for a in requests {
+                    let to = match &a.held {
+                        HeldTransaction::Transfer { to, .. } => to.clone(),
+                        HeldTransaction::Withdrawal { .. } => String::new(),
+                    };
+                    let status = match &a.state {
+                        TransactionState::Rejected(reason) => format!("rejected: {}", reason),
+                        state => state.name().to_string(),
+                    };
+                    table.row([a.id.to_string(), a.requested.to_string(), a.held.name().to_string(), a.held.account().to_string(), to, bank.format_money(a.held.amount()), status]);
+                }
+                table.to_string()
+            }
+            Output::Exchanged(r) => format!(
+                "Exchanged {} from {} for {} in {} at {} (fee {}{}).{}",
+                bank.format_money(&r.debited),
+                r.from,
+                bank.format_money(&r.credited),
+                r.to,
+                r.rate.round_dp(EXCHANGE_RATE_DP),
+                bank.format_money(&r.fee),
+                receipt_note(r),
+                leg_lines(bank, &r.legs)
+            ),
+            Output::Balance { balance, available, .. } if available == balance => format!("Balance: {}", bank.format_money(balance)),
+            Output::Balance { balance, available, .. } => {
+                format!("Balance: {}\nAvailable: {} (the rest is on hold, clearing, or pending)", bank.format_money(balance), bank.format_money(available))
+            }
+            Output::History { entries, .. } => {
+                let mut table = Table::new(&[
+                    ("Seq", Align::Right),
+                    ("Date", Align::Left),
+                    ("Type", Align::Left),
+                    ("Amount", Align::Right),
+                    ("Balance", Align::Right),
+                    ("Memo", Align::Left),
+                    ("Tags", Align::Left),
+                ]);
+                for (t, balance) in entries {
+                    table.row([
+                        t.sequence.to_string(),
+                        bank.local_date(t.timestamp).to_string(),
+                        format!("{:?}", t.tx_type()),
+                        bank.format_money(&Money::new(t.amount(), &balance.currency)),
+                        bank.format_money(balance),
+                        t.memo.clone(),
+                        t.tags.join(", "),
+                    ]);
+                }
+                table.to_string()
+            }
+            Output::Tagged { account, sequence, tags } if tags.is_empty() => format!("Removed the tags from transaction {} of {}.", sequence, account),
+            Output::Tagged { account, sequence, tags } => format!("Tagged transaction {} of {}: {}.", sequence, account, tags.join(", ")),
+            Output::TagReport { account, start, end, totals } if totals.is_empty() => {
+                format!("No tagged transactions for {} from {} to {}.", account, start, end)
+            }
+            Output::TagReport { account, start, end, totals } => {
+                let mut table = Table::new(&[
+                    ("Tag", Align::Left),
+                    ("Count", Align::Right),
+                    ("Inflow", Align::Right),
+                    ("Outflow", Align::Right),
+                    ("Net", Align::Right),
+                ]);
+                for t in totals {
+                    table.row([
+                        t.tag.clone(),
+                        t.count.to_string(),
+                        bank.format_money(&t.inflow),
+                        bank.format_money(&t.outflow),
+                        bank.format_money(&t.net()),
+                    ]);
+                }
+                format!("Tags for {}, {} to {}:\n{}", account, start, end, table)
+            }
+            Output::Statement { content, .. } => content.trim_end().to_string(),
+            Output::Forecast { inflation, step, currency, days, .. } => {
+                // The rate column only appears when a scheduled change
+                // falls inside the forecast.
+                let repriced = days.windows(2).any(|w| w[0].annual_interest != w[1].annual_interest);
+                let summarized = *step != ForecastStep::Daily;
+                let mut columns = vec![(if summarized { "Days" } else { "Day" }, Align::Right)];
+                if repriced {
+                    columns.push(("Rate", Align::Right));
+                }
+                columns.extend([("Interest", Align::Right), ("Balance", Align::Right)]);
+                if inflation.is_some() {
+                    columns.extend([("Real Interest", Align::Right), ("Real Balance", Align::Right)]);
+                }
+                let converted_columns = currency.as_ref().map(|c| [format!("Interest in {}", c), format!("Balance in {}", c)]);
+                if let Some([interest, balance]) = &converted_columns {
+                    columns.extend([(interest.as_str(), Align::Right), (balance.as_str(), Align::Right)]);
+                }
+                let mut table = Table::new(&columns);
+                let optional = |m: &Option<Money>| m.as_ref().map_or_else(String::new, |m| bank.format_money(m));
+                for f in days {
+                    let mut row = vec![if summarized { format!("{}-{}", f.first_day, f.day) } else { f.day.to_string() }];
+                    if repriced {
+                        row.push(percent(f.annual_interest));
+                    }
+                    row.extend([bank.format_money(&f.interest), bank.format_money(&f.balance)]);
+                    if inflation.is_some() {
+                        row.extend([optional(&f.real_interest), optional(&f.real_balance)]);
+                    }
+                    if currency.is_some() {
+                        row.extend([optional(&f.converted_interest), optional(&f.converted_balance)]);
+                    }
+                    table.row(row);
+                }
+                if days.iter().any(|f| f.annual_interest < Decimal::ZERO) {
+                    format!("{}\nA negative rate charges the balance instead of paying interest, so it declines.", table)
+                } else {
+                    table.to_string()
+                }
+            }
+            Output::InterestRates { account, opening, changes, promotion, current, payout, year_basis, interest_method, grace_days } => {
+                let mut table = Table::new(&[("Effective", Align::Left), ("Rate", Align::Right)]);
+                table.row([String::from("Opened"), percent(*opening)]);
+                for c in changes {
+                    table.row([c.effective.to_string(), percent(c.annual_interest)]);
+                }
+                let mut out = format!("Annual interest of {}:\n{}", account, table);
+                if let Some(p) = promotion {
+                    out.push_str(&format!("\nPromotion: +{} from {} until {}", percent(p.bonus), p.start, p.end.add_days(-1)));
+                }
+                out.push_str(&format!("\nIn force today: {}", percent(*current)));
+                if let Some(payout) = payout {
+                    out.push_str(&format!("\nInterest is paid into {}", payout));
+                }
+                if *year_basis != YearBasis::default() {
+                    out.push_str(&format!("\nYear basis: {}", year_basis.label()));
+                }
+                if *interest_method != InterestMethod::default() {
+                    out.push_str(&format!("\nInterest method: {}", interest_method.label()));
+                }
+                if *grace_days > 0 {
+                    out.push_str(&format!("\nDeposits earn from {} days after they are posted", grace_days));
+                }
+                out
+            }
+            Output::YearBasis(basis) => format!("Interest now accrues on {} for every account, and new accounts open on it", basis.label()),
+            Output::InterestMethod(method) => format!("Every account now accrues interest by: {}, and new accounts open with it", method.label()),
+            Output::InterestGrace(0) => String::from("Deposits to every account now earn interest from the day they are posted"),
+            Output::InterestGrace(days) => format!("Deposits to every account, and new accounts, now earn interest from {} days after they are posted", days),
+            Output::Scenarios { comparison, .. } => {
+                let names: Vec<&str> = comparison.series.iter().map(|s| s.scenario.name.as_str()).collect();
+                let columns: Vec<(&str, Align)> = std::iter::once("Day").chain(names).map(|h| (h, Align::Right)).collect();
+                let mut table = Table::new(&columns);
+                for day in comparison.checkpoints(10) {
+                    let balances = comparison.series.iter().map(|s| bank.format_money(&s.points[day - 1].balance));
+                    table.row(std::iter::once(day.to_string()).chain(balances));
+                }
+                table.row(std::iter::once(String::from("Interest")).chain(comparison.series.iter().map(|s| bank.format_money(&s.last().interest))));
+                table.row(std::iter::once(String::from("Contributed")).chain(comparison.series.iter().map(|s| bank.format_money(&s.last().contributed))));
+                table.to_string()
+            }
+            Output::Comparison(c) => {
+                let [first, second] = &c.accounts;
+                let mut table = Table::new(&[
+                    ("Day", Align::Right),
+                    ("Date", Align::Left),
+                    (first.account.as_str(), Align::Right),
+                    (second.account.as_str(), Align::Right),
+                    ("Gap", Align::Right),
+                ]);
+                for day in c.checkpoints(10) {
+                    let [a, b] = [&first.points[day - 1], &second.points[day - 1]];
+                    table.row([day.to_string(), c.date_of(day).to_string(), bank.format_money(&a.balance), bank.format_money(&b.balance), bank.format_money(&c.gap_on(day))]);
+                }
+                table.row([String::from("Interest"), String::new(), bank.format_money(&first.last().interest), bank.format_money(&second.last().interest), String::new()]);
+                let side = |a: &ComparedAccount| format!("{} ({} at {:.2}%)", a.account, bank.format_money(&a.opening), a.annual_interest * Decimal::from(100));
+                let outcome = match (c.crossover, c.crossover.and_then(|day| c.ahead_on(day))) {
+                    (Some(day), Some(ahead)) => {
+                        format!("{} overtakes {} on day {} ({}).", c.accounts[ahead].account, c.accounts[1 - ahead].account, day, c.date_of(day))
+                    }
+                    (Some(day), None) => format!("{} and {} draw level on day {} ({}).", first.account, second.account, day, c.date_of(day)),
+                    (None, _) => match c.ahead_on(c.horizon) {
+                        Some(ahead) => format!("No crossover within {} days; {} stays ahead.", c.horizon, c.accounts[ahead].account),
+                        None => format!("No crossover within {} days; the two stay level.", c.horizon),
+                    },
+                };
+                format!("Comparing {} and {} over {} days:\n{}\n{}", side(first), side(second), c.horizon, table, outcome)
+            }
+            Output::MonteCarlo { account, outcome } => {
+                let mut columns = vec![("Percentile", Align::Right), ("Balance", Align::Right)];
+                if outcome.value.is_some() {
+                    columns.push(("Value", Align::Right));
+                }
+                let mut table = Table::new(&columns);
+                let row = |label: String, pick: &dyn Fn(&Distribution) -> Money| {
+                    std::iter::once(label)
+                        .chain(std::iter::once(&outcome.balance).chain(&outcome.value).map(|d| bank.format_money(&pick(d))))
+                        .collect::<Vec<_>>()
+                };
+                table.row(row(String::from("lowest"), &|d| d.min()));
+                for p in PERCENTILES {
+                    table.row(row(format!("{}th", p), &|d| d.percentile(p)));
+                }
+                table.row(row(String::from("highest"), &|d| d.max()));
+                table.row(row(String::from("mean"), &|d| d.mean()));
+                let opening = match &outcome.opening_value {
+                    Some(value) => format!("{} ({})", bank.format_money(&outcome.opening), bank.format_money(value)),
+                    None => bank.format_money(&outcome.opening),
+                };
+                let mut lines = vec![
+                    format!("Monte Carlo of {} from {} over {} days, {} paths, seed {}:", account, opening, outcome.days, outcome.paths, outcome.seed),
+                    table.to_string(),
+                    format!("Chance of ending below the opening balance: {:.1}%", outcome.balance.chance_below(outcome.opening.amount) * 100.0),
+                ];
+                if let (Some(value), Some(opening)) = (&outcome.value, &outcome.opening_value) {
+                    lines.push(format!("Chance of ending below its opening value: {:.1}%", value.chance_below(opening.amount) * 100.0));
+                }
+                if !outcome.deposited.amount.is_zero() {
+                    lines.push(format!("Deposited per path on average: {}", bank.format_money(&outcome.deposited)));
+                }
+                lines.join("\n")
+            }
+            Output::Pnl(reports) => pnl_table(bank, reports).to_string(),
+            Output::Portfolio(p) => {
+                let mut table = Table::new(&[("Asset", Align::Left), ("Amount", Align::Right), ("Rate", Align::Right), ("Value", Align::Right)]);
+                for h in &p.holdings {
+                    table.row([h.asset.to_string(), bank.format_money(&h.amount), h.rate.to_string(), bank.format_money(&h.value)]);
+                }
+                table.row([String::from("total"), String::new(), String::new(), bank.format_money(&p.total)]);
+                format!("Portfolio of {} as of {}:\n{}", p.account, p.as_of, table)
+            }
+            Output::Portfolios(portfolios) => {
+                let mut table = Table::new(&[("Account", Align::Left), ("Holdings", Align::Right), ("Value", Align::Right)]);
+                for p in portfolios {
+                    table.row([p.account.clone(), p.holdings.len().to_string(), bank.format_money(&p.total)]);
+                }
+                match portfolios.first() {
+                    Some(p) => format!("Portfolios as of {}:\n{}", p.as_of, table),
+                    None => String::from("No accounts."),
+                }
+            }
+            Output::Imported { account, report, balance } => {
+                let summary = format!("Imported {} transaction(s) to {}. Balance: {}", report.imported, account, bank.format_money(balance));
+                if report.errors.is_empty() {
+                    return summary;
+                }
+                let mut table = Table::new(&[("Row", Align::Right), ("Reason", Align::Left)]);
+                for e in &report.errors {
+                    table.row([e.row.to_string(), e.failure.to_string()]);
+                }
+                format!("{}\nSkipped {} row(s):\n{}", summary, report.errors.len(), table)
+            }
+            Output::InterestPosted(posted) => {
+                let mut table = Table::new(&[("Account", Align::Left), ("Interest", Align::Right)]);
+                for (account, amount) in posted {
+                    table.row([account.clone(), bank.format_money(amount)]);
+                }
+                let mut out = format!("Posted interest to {} account(s):\n{}", posted.len(), table);
+                if posted.iter().any(|(_, amount)| amount.amount < Decimal::ZERO) {
+                    out.push_str("\nNegative amounts are carrying charges taken at a negative rate.");
+                }
+                out
+            }
+            Output::GoalSet { account, goal } => {
+                format!("Set goal {} for {}: {} by {}.", goal.name, account, bank.format_money(&goal.target), goal.target_date)
+            }
+            Output::Goals { frequency, goals, .. } => {
+                let mut table = Table::new(&[
+                    ("Goal", Align::Left),
+                    ("Target", Align::Right),
+                    ("By", Align::Left),
+                    ("Saved", Align::Right),
+                    ("Progress", Align::Right),
+                    ("Needed", Align::Right),
+                ]);
+                for (goal, progress) in goals {
+                    table.row([
+                        goal.name.clone(),
+                        bank.format_money(&goal.target),
+                        goal.target_date.to_string(),
+                        bank.format_money(&progress.saved),
+                        format!("{:.0}%", progress.fraction * Decimal::from(100)),
+                        format!("{} {}", bank.format_money(&progress.contribution), frequency.name()),
+                    ]);
+                }
+                table.to_string()
+            }
+            Output::EnvelopeSet { account, envelope } => {
+                format!("Set a monthly budget of {} for {} on {}.", bank.format_money(&envelope.limit), envelope.category, account)
+            }
+            Output::Budget { account, envelopes, .. } if envelopes.is_empty() => format!("{} has no budgets.", account),
+            Output::Budget { date, envelopes, .. } => {
+                let mut table = Table::new(&[
+                    ("Category", Align::Left),
+                    ("Limit", Align::Right),
+                    ("Spent", Align::Right),
+                    ("Remaining", Align::Right),
+                    ("Status", Align::Left),
+                ]);
+                for status in envelopes {
+                    table.row([
+                        status.category.clone(),
+                        bank.format_money(&status.limit),
+                        bank.format_money(&status.spent),
+                        bank.format_money(&status.remaining),
+                        if status.is_overspent() { format!("over by {}", bank.format_money(&status.overspent)) } else { String::from("ok") },
+                    ]);
+                }
+                format!("Budgets for {}-{:02}:\n{}", date.year, date.month, table)
+            }
+            Output::Summary { csv: Some(content), .. } => content.trim_end().to_string(),
+            Output::Summary { summary, .. } if summary.accounts.is_empty() => format!("No accounts to summarize for {}.", summary.month),
+            Output::Summary { summary, .. } => {
+                let mut table = Table::new(&[
+                    ("Account", Align::Left),
+                    ("Opening", Align::Right),
+                    ("Inflow", Align::Right),
+                    ("Outflow", Align::Right),
+                    ("Interest", Align::Right),
+                    ("Fees", Align::Right),
+                    ("Net Change", Align::Right),
+                    ("Closing", Align::Right),
+                ]);
+                for a in &summary.accounts {
+                    table.row([
+                        a.account.clone(),
+                        bank.format_money(&a.opening),
+                        bank.format_money(&a.inflow),
+                        bank.format_money(&a.outflow),
+                        bank.format_money(&a.interest),
+                        bank.format_money(&a.fees),
+                        bank.format_money(&a.net_change()),
+                        bank.format_money(&a.closing),
+                    ]);
+                }
+                format!("Summary for {}:\n{}", summary.month, table)
+            }
+            Output::TaxCertificate { content: Some(content), .. } => content.trim_end().to_string(),
+            Output::TaxCertificate { certificate: c, .. } => {
+                let mut table = Table::new(&[
+                    ("Month", Align::Left),
+                    ("Gross Interest", Align::Right),
+                    ("Tax Withheld", Align::Right),
+                    ("Net Interest", Align::Right),
+                ]);
+                for m in &c.months {
+                    table.row([m.month.to_string(), c.format(m.gross), c.format(m.withheld), c.format(m.net())]);
+                }
+                table.row([String::from("Total"), c.format(c.gross()), c.format(c.withheld()), c.format(c.net())]);
+                let holder = c.holder.as_ref().map(|h| format!(" (holder {})", h)).unwrap_or_default();
+                format!("Interest and tax withheld for {}{}, {}:\n{}", c.account, holder, c.year, table)
+            }
+            Output::LoanOpened(loan) => format!(
+                "Opened loan {} for {}: {} over {} {} payments of {}.",
+                loan.id,
+                loan.account,
+                bank.format_money(&loan.principal),
+                loan.term,
+                loan.frequency.name(),
+                loan.payment().map_or_else(|e| e.to_string(), |p| bank.format_money(&p))
+            ),
+            Output::Schedule { loan, rows } => {
+                let mut table = Table::new(&[
+                    ("Period", Align::Right),
+                    ("Payment", Align::Right),
+                    ("Principal", Align::Right),
+                    ("Interest", Align::Right),
+                    ("Remaining", Align::Right),
+                    ("Status", Align::Left),
+                ]);
+                for row in rows {
+                    table.row([
+                        row.period.to_string(),
+                        bank.format_money(&row.payment),
+                        bank.format_money(&row.principal),
+                        bank.format_money(&row.interest),
+                        bank.format_money(&row.remaining),
+                        if row.period <= loan.payments_made { String::from("paid") } else { String::new() },
+                    ]);
+                }
+                table.to_string()
+            }
+            Output::LoanPaid { loan, row, balance } => format!(
+                "Paid {} on loan {} ({}/{}); {} outstanding. Updated Balance: {}",
+                bank.format_money(&row.payment),
+                loan.id,
+                row.period,
+                loan.term,
+                bank.format_money(&row.remaining),
+                bank.format_money(balance)
+            ),
+            Output::Paydown(c) => {
+                let apy = |r: Decimal| format!("{:.4}%", r * Decimal::from(100));
+                let mut lines = vec![
+                    format!("Paying {} off the loan or depositing it, over {} days:", bank.format_money(&c.amount), c.days),
+                    format!("Pay down: saves {} at {} ({} APY).", bank.format_money(&c.interest_saved), percent(c.loan_rate), apy(c.loan_apy)),
+                    format!(
+                        "Deposit:  earns {} at {}{} ({} APY).",
+                        bank.format_money(&c.interest_earned),
+                        percent(c.deposit_rate),
+                        if c.tax_rate.is_zero() { String::new() } else { format!(" less {} tax", percent(c.tax_rate)) },
+                        apy(c.deposit_apy)
+                    ),
+                ];
+                let by = bank.format_money(&Money::new(c.advantage().amount.abs(), &c.amount.currency));
+                lines.push(match c.better() {
+                    PaydownChoice::PayDown => format!("Paying down comes out ahead by {}.", by),
+                    PaydownChoice::Deposit => format!("Depositing comes out ahead by {}.", by),
+                    PaydownChoice::Either => String::from("Both come out the same."),
+                });
+                if let Some(rate) = c.break_even_rate {
+                    lines.push(format!("A deposit breaks even at {} a year before tax.", percent(rate)));
+                }
+                lines.join("\n")
+            }
+            Output::OrderCreated(order) => format!(
+                "Standing order {}: {} from {} to {} every {} day(s), first on {}.",
+                order.id,
+                bank.format_money(&order.amount),
+                order.from,
+                order.to,
+                order.interval_days,
+                order.next
+            ),
+            Output::Orders(orders) => {
+                let mut table = Table::new(&[
+                    ("ID", Align::Right),
+                    ("From", Align::Left),
+                    ("To", Align::Left),
+                    ("Amount", Align::Right),
+                    ("Every", Align::Right),
+                    ("Next", Align::Left),
+                ]);
+                for order in orders {
+                    table.row([
+                        order.id.to_string(),
+                        order.from.clone(),
+                        order.to.clone(),
+                        bank.format_money(&order.amount),
+                        format!("{} day(s)", order.interval_days),
+                        order.due_date(&bank.calendar).to_string(),
+                    ]);
+                }
+                table.to_string()
+            }
+            Output::OrderSkipped(order) => format!("Skipped standing order {}; next transfer on {}.", order.id, order.due_date(&bank.calendar)),
+            Output::OrderCancelled(order) => format!("Cancelled standing order {}.", order.id),
+            Output::SweepCreated(sweep) => format!(
+                "Sweep {}: {} keeps {}; the excess moves to {} at each end of day.",
+                sweep.id,
+                sweep.from,
+                bank.format_money(&sweep.threshold),
+                sweep.to
+            ),
+            Output::Sweeps(sweeps) if sweeps.is_empty() => String::from("No sweeps."),
+            Output::Sweeps(sweeps) => {
+                let mut table = Table::new(&[("ID", Align::Right), ("From", Align::Left), ("To", Align::Left), ("Above", Align::Right)]);
+                for sweep in sweeps {
+                    table.row([sweep.id.to_string(), sweep.from.clone(), sweep.to.clone(), bank.format_money(&sweep.threshold)]);
+                }
+                table.to_string()
+            }
+            Output::SweepCancelled(sweep) => format!("Cancelled sweep {}.", sweep.id),
+            Output::ForwardBooked(f) => format!(
+                "Forward {}: {} {} for {} at {} on {}.",
+                f.id,
+                f.side.name(),
+                bank.format_money(&f.amount),
+                f.account,
+                f.rate,
+                f.value_date
+            ),
+            Output::Forwards(valuations) => {
+                let mut table = Table::new(&[
+                    ("ID", Align::Right),
+                    ("Account", Align::Left),
+                    ("Side", Align::Left),
+                    ("Amount", Align::Right),
+                    ("Rate", Align::Right),
+                    ("Value Date", Align::Left),
+                    ("Spot", Align::Right),
+                    ("MTM", Align::Right),
+                ]);
+                for v in valuations {
+                    let f = &v.contract;
+                    table.row([
+                        f.id.to_string(),
+                        f.account.clone(),
+                        f.side.name().to_string(),
+                        bank.format_money(&f.amount),
+                        f.rate.to_string(),
+                        f.value_date.to_string(),
+                        v.spot.to_string(),
+                        bank.format_money(&v.mark_to_market),
+                    ]);
+                }
+                table.to_string()
+            }
+            Output::LimitPlaced(o) | Output::LimitAmended(o) => format!(
+                "Limit order {}: {} to {} for {} when the rate reaches {}{}, {}.{}",
+                o.id,
+                bank.format_money(&o.amount),
+                o.to,
+                o.account,
+                o.limit,
+                o.average.map_or_else(String::new, |a| format!(" and is past its {}", a)),
+                o.expires.map_or_else(|| String::from("good till cancelled"), |d| format!("until {}", d)),
+                if o.filled_amount > Decimal::ZERO { format!(" {} is already filled.", bank.format_money(&Money::new(o.filled_amount, &o.amount.currency))) } else { String::new() }
+            ),
+            Output::Limits(orders) => {
+                let mut table = Table::new(&[
+                    ("ID", Align::Right),
+                    ("Account", Align::Left),
+                    ("Amount", Align::Right),
+                    ("To", Align::Left),
+                    ("Filled", Align::Right),
+                    ("Limit", Align::Right),
+                    ("Average", Align::Left),
+                    ("Spot", Align::Right),
+                    ("Expires", Align::Left),
+                ]);
+                for (o, spot) in orders {
+                    table.row([
+                        o.id.to_string(),
+                        o.account.clone(),
+                        bank.format_money(&o.amount),
+                        o.to.clone(),
+                        bank.format_money(&Money::new(o.filled_amount, &o.amount.currency)),
+                        o.limit.to_string(),
+                        o.average.map_or_else(|| String::from("-"), |a| a.to_string()),
+                        spot.to_string(),
+                        o.expires.map_or_else(|| String::from("GTC"), |d| d.to_string()),
+                    ]);
+                }
+                table.to_string()
+            }
+            Output::LimitCancelled(o) => format!("Cancelled limit order {}.", o.id),
+            Output::EndOfDay(eod) if eod.carried.is_empty() && eod.forwards.is_empty() && eod.standing_orders.is_empty() && eod.sweeps.is_empty() && eod.service_charges.is_empty() && eod.statements == 0 => {
+                format!("No forwards, standing orders, sweeps, or service charges due by {}.", eod.date)
+            }
+            Output::EndOfDay(eod) => end_of_day_lines(bank, eod).join("\n"),
+            Output::Simulated { seed, steps, positions } => {
+                let codes: Vec<String> = steps.first().map_or(Vec::new(), |s| s.moves.iter().map(|m| m.code.clone()).collect());
+                format!("Simulated {} day(s) with seed {}:\n{}", steps.len(), seed, market_text(bank, &codes, steps, positions))
+            }
+            Output::Replayed { codes, steps, positions } => {
+                format!("Replayed {} day(s):\n{}", steps.len(), market_text(bank, codes, steps, positions))
+            }
+            Output::Compacted(report) if report.accounts.is_empty() => {
+                format!("No transactions posted before {} to compact.", report.cutoff)
+            }
+            Output::Compacted(report) => {
+                let mut table = Table::new(&[("Account", Align::Left), ("Compacted", Align::Right), ("Opening balance", Align::Right)]);
+                for c in &report.accounts {
+                    table.row([c.account.clone(), c.compacted.to_string(), bank.format_money(&c.opening)]);
+                }
+                let mut text = format!(
+                    "Compacted {} transaction(s) posted before {} into {}:\n{}",
+                    report.compacted(),
+                    report.cutoff,
+                    report.archive.display(),
+                    table
+                );
+                if report.reviews_dropped > 0 {
+                    text.push_str(&format!("\nDropped {} reviewed flag(s) for compacted postings.", report.reviews_dropped));
+                }
+                text
+            }
+            Output::Demo(report) => {
+                let mut table = Table::new(&[("Account", Align::Left), ("Balance", Align::Right)]);
+                for name in &report.accounts {
+                    if let Some(acct) = bank.find_account(name) {
+                        table.row([name.clone(), bank.format_money(&acct.get_balance())]);
+                    }
+                }
+                format!(
+                    "Opened {} demo account(s) with {} transaction(s) from {} (seed {}):\n{}",
+                    report.accounts.len(),
+                    report.transactions,
+                    report.start,
+                    report.seed,
+                    table
+                )
+            }
+            Output::Verified(report) => {
+                let checked = format!("Checked {} account(s) and {} transaction(s)", report.accounts, report.transactions);
+                if report.is_clean() {
+                    return format!("{}: no violations.", checked);
+                }
+                let lines: Vec<String> = report.violations.iter().map(|v| format!("- {}", v)).collect();
+                format!("{}: {} violation(s):\n{}", checked, report.violations.len(), lines.join("\n"))
+            }
+            Output::Rounding { strategy, residue, .. } if residue.is_empty() => format!("Rounding: {:?}. No residue recorded.", strategy),
+            Output::Rounding { strategy, residue, total } => {
+                let mut table = Table::new(&[("Currency", Align::Left), ("Residue", Align::Right), ("Value", Align::Right)]);
+                for (code, amount, value) in residue {
+                    table.row([code.clone(), amount.to_string(), value.as_ref().map_or("-".to_string(), |v| v.amount.to_string())]);
+                }
+                format!("Rounding: {:?}\n{}\nNet effect: {} (exact minus posted, at transfer rates)", strategy, table, total)
+            }
+            Output::Help => USAGE.to_string(),
+        }
+    }
+
+    /// Machine-readable rendering. Amounts are rounded to their currency's
+    /// minor unit and carry their currency code.
+    pub fn to_json(&self, bank: &Bank) -> Json {
+        let money = |m: &Money| {
+            Json::object([
+                ("amount", Json::num(m.amount.round_dp(bank.forex.decimals(&m.currency)))),
+                ("currency", Json::str(&m.currency)),
+            ])
+        };
+        let legs_json = |legs: &[ConversionLeg]| {
+            Json::Array(legs.iter().map(|l| Json::object([("from", money(&l.from)), ("to", money(&l.to)), ("rate", Json::num(l.rate))])).collect())
+        };
+        let loan_json = |loan: &Loan| {
+            Json::object([
+                ("loan", Json::num(loan.id)),
+                ("account", Json::str(&loan.account)),
+                ("principal", money(&loan.principal)),
+                ("annual_rate", Json::num(loan.annual_rate)),
+                ("term", Json::num(loan.term)),
+                ("frequency", Json::str(loan.frequency.name())),
+                ("payment", loan.payment().map_or(Json::Null, |p| money(&p))),
+                ("payments_made", Json::num(loan.payments_made)),
+                ("outstanding", money(&loan.outstanding())),
+            ])
+        };
+        let row_json = |row: &AmortizationRow, paid: bool| {
+            Json::object([
+                ("period", Json::num(row.period)),
+                ("payment", money(&row.payment)),
+                ("principal", money(&row.principal)),
+                ("interest", money(&row.interest)),
+                ("remaining", money(&row.remaining)),
+                ("paid", Json::Bool(paid)),
+            ])
+        };
+        let order_json = |order: &StandingOrder| {
+            Json::object([
+                ("order", Json::num(order.id)),
+                ("from", Json::str(&order.from)),
+                ("to", Json::str(&order.to)),
+                ("amount", money(&order.amount)),
+                ("interval_days", Json::num(order.interval_days)),
+                ("next", Json::str(order.next)),
+                ("due", Json::str(order.due_date(&bank.calendar))),
+            ])
+        };
+        let hold_json = |h: &Hold| {
+            Json::object([
+                ("id", Json::num(h.id)),
+                ("amount", money(&h.amount)),
+                ("reason", Json::str(&h.reason)),
+                ("placed", Json::str(h.placed)),
+                ("expires", Json::str(h.expiry)),
+            ])
+        };
+        let cheque_json = |c: &Cheque, status: &str| {
+            Json::object([
+                ("id", Json::num(c.id)),
+                ("number", Json::str(&c.number)),
+                ("amount", money(&c.amount)),
+                ("sequence", Json::num(c.sequence)),
+                ("deposited", Json::str(c.deposited)),
+                ("clears", Json::str(c.clears)),
+                ("status", Json::str(status)),
+                ("reason", c.returned.as_ref().map_or(Json::Null, |(_, reason)| Json::str(reason))),
+            ])
+        };
+        let approval_json = |a: &PendingTransaction| {
+            let (from, to, memo, category) = match &a.held {
+                HeldTransaction::Withdrawal { account, memo, category, .. } => (account, Json::Null, Json::str(memo), category.as_ref().map_or(Json::Null, Json::str)),
+                HeldTransaction::Transfer { from, to, .. } => (from, Json::str(to), Json::Null, Json::Null),
+            };
+            Json::object([
+                ("id", Json::num(a.id)),
+                ("requested", Json::str(a.requested)),
+                ("type", Json::str(a.held.name())),
+                ("account", Json::str(from)),
+                ("to", to),
+                ("amount", money(a.held.amount())),
+                ("memo", memo),
+                ("category", category),
+                ("status", Json::str(a.state.name())),
+                ("reason", match &a.state {
+                    TransactionState::Rejected(reason) => Json::str(reason),
+                    _ => Json::Null,
+                }),
+            ])
+        };
+        let sweep_json = |sweep: &SweepRule| {
+            Json::object([
+                ("sweep", Json::num(sweep.id)),
+                ("from", Json::str(&sweep.from)),
+                ("to", Json::str(&sweep.to)),
+                ("above", money(&sweep.threshold)),
+            ])
+        };
+        let forward_fields = |f: &ForwardContract| {
+            vec![
+                ("forward", Json::num(f.id)),
+                ("account", Json::str(&f.account)),
+                ("side", Json::str(f.side.name())),
+                ("amount", money(&f.amount)),
+                ("rate", Json::num(f.rate)),
+                ("value_date", Json::str(f.value_date)),
+                ("settled", Json::Bool(f.settled)),
+            ]
+        };
+        let limit_fields = |o: &LimitOrder| {
+            vec![
+                ("limit_order", Json::num(o.id)),
+                ("account", Json::str(&o.account)),
+                ("amount", money(&o.amount)),
+                ("to", Json::str(&o.to)),
+                ("limit", Json::num(o.limit)),
+                ("filled", Json::Bool(o.filled)),
+                ("filled_amount", money(&Money::new(o.filled_amount, &o.amount.currency))),
+                ("remaining", money(&o.remaining())),
+                ("expires", o.expires.map_or(Json::Null, Json::str)),
+                ("average", o.average.map_or(Json::Null, Json::str)),
+            ]
+        };
+        let envelope_json = |s: &EnvelopeStatus| {
+            Json::object([
+                ("category", Json::str(&s.category)),
+                ("limit", money(&s.limit)),
+                ("spent", money(&s.spent)),
+                ("remaining", money(&s.remaining)),
+                ("overspent", money(&s.overspent)),
+                ("over_budget", Json::Bool(s.is_overspent())),
+            ])
+        };
+        let basket_json = |q: &BasketQuote| {
+            Json::object([
+                ("code", Json::str(&q.currency.code)),
+                ("name", Json::str(&q.currency.name)),
+                ("rate", Json::num(q.currency.rate)),
+                ("components", Json::Array(q.shares.iter().map(|s| Json::object([
+                    ("currency", Json::str(&s.code)),
+                    ("quantity", Json::num(s.quantity)),
+                    ("weight", Json::num(s.weight.round_dp(BASKET_RATE_DP))),
+                ])).collect())),
+            ])
+        };
+        let fill_json = |fill: &LimitOrderFill| {
+            let mut fields = limit_fields(&fill.order);
+            fields.extend([("spot", Json::num(fill.spot)), ("converted", money(&fill.converted))]);
+            match &fill.result {
+                Ok(amount) => fields.extend([("ok", Json::Bool(true)), ("posted", money(amount))]),
+                Err(e) => fields.extend([("ok", Json::Bool(false)), ("error", Json::str(e))]),
+            }
+            Json::object(fields)
+        };
+        let position_json = |r: &PositionReport| {
+            Json::object([
+                ("account", Json::str(&r.account)),
+                ("balance", money(&r.balance)),
+                ("spot", Json::num(r.spot)),
+                ("market_value", money(&r.market_value)),
+                ("cost_basis", money(&r.cost_basis)),
+                ("unrealized", money(&r.unrealized)),
+                ("realized", money(&r.realized)),
+            ])
+        };
+        let end_of_day_json = |eod: &EndOfDay| {
+            Json::object([
+                ("date", Json::str(eod.date)),
+                ("carried", Json::Array(eod.carried.iter().map(|(code, rate)| Json::object([("code", Json::str(code)), ("rate", Json::num(rate))])).collect())),
+                ("forwards", Json::Array(eod.forwards.iter().map(|s| {
+                    let mut fields = vec![("forward", Json::num(s.contract.id)), ("date", Json::str(s.contract.settlement_date(&bank.calendar)))];
+                    match &s.result {
+                        Ok(amount) => fields.extend([("ok", Json::Bool(true)), ("side", Json::str(s.contract.side.name())), ("posted", money(amount))]),
+                        Err(e) => fields.extend([("ok", Json::Bool(false)), ("error", Json::str(e))]),
+                    }
+                    Json::object(fields)
+                }).collect())),
+                ("standing_orders", Json::Array(eod.standing_orders.iter().map(|run| {
+                    let mut fields = vec![("order", Json::num(run.order)), ("date", Json::str(run.date))];
+                    match &run.result {
+                        Ok(r) => fields.extend([
+                            ("ok", Json::Bool(true)),
+                            ("from", Json::str(&r.from)),
+                            ("to", Json::str(&r.to)),
+                            ("debited", money(&r.debited)),
+                            ("credited", money(&r.credited)),
+                        ]),
+                        Err(e) => fields.extend([("ok", Json::Bool(false)), ("error", Json::str(e))]),
+                    }
+                    Json::object(fields)
+                }).collect())),
+                ("sweeps", Json::Array(eod.sweeps.iter().map(|run| {
+                    let mut fields = vec![("sweep", Json::num(run.rule))];
+                    match &run.result {
+                        Ok(r) => fields.extend([
+                            ("ok", Json::Bool(true)),
+                            ("from", Json::str(&r.from)),
+                            ("to", Json::str(&r.to)),
+                            ("debited", money(&r.debited)),
+                            ("credited", money(&r.credited)),
+                        ]),
+                        Err(e) => fields.extend([("ok", Json::Bool(false)), ("error", Json::str(e))]),
+                    }
+                    Json::object(fields)
+                }).collect())),
+                ("service_charges", Json::Array(eod.service_charges.iter().map(|run| {
+                    let mut fields = vec![("account", Json::str(&run.account)), ("memo", Json::str(run.memo)), ("average_balance", money(&run.average))];
+                    match &run.result {
+                        Ok(charged) => fields.extend([("ok", Json::Bool(true)), ("charged", money(charged))]),
+                        Err(e) => fields.extend([("ok", Json::Bool(false)), ("error", Json::str(e))]),
+                    }
+                    Json::object(fields)
+                }).collect())),
+                ("statements", Json::num(eod.statements)),
+            ])
+        };
+        let step_json = |step: &MarketStep| {
+            Json::object([
+                ("date", Json::str(step.date)),
+                ("rates", Json::Array(step.moves.iter().map(|m| Json::object([
+                    ("code", Json::str(&m.code)),
+                    ("old", Json::num(m.old)),
+                    ("new", Json::num(m.new)),
+                ])).collect())),
+                ("fills", Json::Array(step.fills.iter().map(fill_json).collect())),
+                ("end_of_day", end_of_day_json(&step.end_of_day)),
+            ])
+        };
+        let portfolio_json = |p: &Portfolio| {
+            Json::object([
+                ("account", Json::str(&p.account)),
+                ("as_of", Json::str(p.as_of)),
+                ("holdings", Json::Array(p.holdings.iter().map(|h| Json::object([
+                    ("asset", Json::str(h.asset)),
+                    ("amount", money(&h.amount)),
+                    ("rate", Json::num(h.rate)),
+                    ("value", money(&h.value)),
+                ])).collect())),
+                ("total", money(&p.total)),
+            ])
+        };
+        match self {
+            Output::Rates { base, currencies, window, volatility } => Json::object([
+                ("base", Json::str(base)),
+                ("window", Json::num(window)),
+                ("rates", Json::Array(currencies.iter().map(|c| Json::object([
+                    ("code", Json::str(&c.code)),
+                    ("name", Json::str(&c.name)),
+                    ("rate", Json::num(c.rate)),
+                    ("cash_rate", c.cash_rate.map_or(Json::Null, Json::num)),
+                    ("rate_decimals", Json::num(c.rate_decimals)),
+                    ("updated", c.updated.map_or(Json::Null, Json::num)),
+                    ("carried", Json::Bool(bank.forex.is_carried(&c.code, bank.today()))),
+                    ("source", c.source.as_ref().map_or(Json::Null, Json::str)),
+                    ("volatility", volatility.iter().find(|v| v.code == c.code).map_or(Json::Null, volatility_json)),
+                ])).collect())),
+            ]),
+            Output::RateSummary(stats) => Json::object([
+                ("base", Json::str(bank.forex.get_base_rate())),
+                ("stats", Json::Array(stats.iter().map(|s| Json::object([
+                    ("code", Json::str(&s.code)),
+                    ("start", Json::str(s.start)),
+                    ("end", Json::str(s.end)),
+                    ("days", Json::num(s.days)),
+                    ("open", Json::num(s.open)),
+                    ("high", Json::num(s.high)),
+                    ("low", Json::num(s.low)),
+                    ("average", Json::num(s.average)),
+                    ("close", Json::num(s.close)),
+                    ("change", Json::num(s.change().round_dp(6))),
+                ])).collect())),
+            ]),
+            Output::RateHistory { code, sma, ema, closes } => Json::object([
+                ("code", Json::str(code)),
+                ("base", Json::str(bank.forex.get_base_rate())),
+                ("sma_days", Json::num(sma)),
+                ("ema_days", Json::num(ema)),
+                ("closes", Json::Array(closes.iter().map(|c| Json::object([
+                    ("date", Json::str(c.date)),
+                    ("rate", Json::num(c.rate)),
+                    ("carried", Json::Bool(c.carried)),
+                    ("sma", c.sma.map_or(Json::Null, Json::num)),
+                    ("ema", c.ema.map_or(Json::Null, Json::num)),
+                ])).collect())),
+            ]),
+            Output::RateRecorded { code, rate, fills } => Json::object([
+                ("code", Json::str(code)),
+                ("rate", Json::num(rate)),
+                ("fills", Json::Array(fills.iter().map(fill_json).collect())),
+            ]),
+            Output::CashRateRecorded { code, rate } => Json::object([
+                ("code", Json::str(code)),
+                ("cash_rate", rate.map_or(Json::Null, Json::num)),
+            ]),
+            Output::Conversion(c, trend) => Json::object([
+                ("from", money(&c.source)),
+                ("to", money(&c.gross)),
+                ("rates", Json::str(c.rate_type.name())),
+                ("rate", Json::num(c.rate)),
+                ("fee_rate", Json::num(c.fee_rate)),
+                ("fee_basis", Json::str(c.fee_basis.name())),
+                ("fee", money(&c.fee)),
+                ("net", money(&c.net)),
+                ("legs", legs_json(&c.legs)),
+                ("trend", Json::object([
+                    ("rate", Json::num(trend.rate)),
+                    ("previous", trend.previous.map_or(Json::Null, |(date, rate)| Json::object([("date", Json::str(date)), ("rate", Json::num(rate))]))),
+                    ("vs_previous", trend.vs_previous().map_or(Json::Null, |c| Json::num(c.round_dp(6)))),
+                    ("average", trend.average.map_or(Json::Null, Json::num)),
+                    ("vs_average", trend.vs_average().map_or(Json::Null, |c| Json::num(c.round_dp(6)))),
+                ])),
+            ]),
+            Output::Dca(sim) => Json::object([
+                ("purchases", Json::Array(sim.purchases.iter().map(|p| Json::object([
+                    ("period", Json::num(p.period)),
+                    ("rate", Json::num(p.rate)),
+                    ("spent", money(&p.spent)),
+                    ("units", money(&p.units)),
+                ])).collect())),
+                ("invested", money(&sim.invested)),
+                ("units", money(&sim.units)),
+                ("average_cost", Json::num(sim.average_cost.round_dp(6))),
+                ("final_rate", Json::num(sim.final_rate)),
+                ("final_value", money(&sim.final_value)),
+                ("lump_sum_units", money(&sim.lump_sum_units)),
+                ("lump_sum_value", money(&sim.lump_sum_value)),
+                ("advantage", money(&sim.advantage())),
+            ]),
+            Output::BasketDefined(quote) => basket_json(quote),
+            Output::Conversions(records) => Json::object([("conversions", Json::Array(records.iter().map(|r| Json::object([
+                ("receipt", Json::num(r.receipt)),
+                ("timestamp", Json::num(r.timestamp)),
+                ("date", Json::str(r.date())),
+                ("account", r.account.as_ref().map_or(Json::Null, Json::str)),
+                ("in", money(&r.amount_in)),
+                ("out", money(&r.amount_out)),
+                ("rate", Json::num(r.rate)),
+                ("fee", money(&r.fee)),
+                ("volume", Json::num(r.volume)),
+                ("teller", r.teller.as_ref().map_or(Json::Null, Json::str)),
+                ("customer", r.customer.as_ref().map_or(Json::Null, Json::str)),
+            ])).collect()))]),
+            Output::Receipt(r) => Json::object([
+                ("number", Json::num(r.number)),
+                ("bank", Json::str(&r.bank)),
+                ("timestamp", Json::num(r.timestamp)),
+                ("date", Json::str(bank.local_date(r.timestamp))),
+                ("account", r.account.as_ref().map_or(Json::Null, Json::str)),
+                ("pair", Json::str(r.pair())),
+                ("sold", money(&r.sold)),
+                ("bought", money(&r.bought)),
+                ("rate", Json::num(r.rate)),
+                ("fee", money(&r.fee)),
+                ("teller", r.teller.as_ref().map_or(Json::Null, Json::str)),
+                ("customer", r.customer.as_ref().map_or(Json::Null, Json::str)),
+            ]),
+            Output::Turnover { base, days } => Json::object([
+                ("base", Json::str(base)),
+                ("days", Json::Array(days.iter().map(|d| Json::object([
+                    ("date", Json::str(d.date)),
+                    ("conversions", Json::num(d.conversions)),
+                    ("volume", Json::num(d.volume)),
+                ])).collect())),
+            ]),
+            Output::Fees { fees, spreads } => Json::object([
+                ("tiers", Json::Array(fees.tiers().iter().map(|t| Json::object([
+                    ("from", Json::num(t.from)),
+                    ("rate", Json::num(t.rate)),
+                ])).collect())),
+                ("spreads", Json::Array(spreads.iter().map(|s| Json::object([
+                    ("pair", Json::str(s.label())),
+                    ("rate", Json::num(s.rate)),
+                ])).collect())),
+            ]),
+            Output::ConversionLimits(limits) => Json::object([("limits", Json::Array(limits.iter().map(|(code, limit)| Json::object([
+                ("currency", Json::str(code)),
+                ("per_transaction", limit.per_transaction.map_or(Json::Null, Json::num)),
+                ("per_day", limit.per_day.map_or(Json::Null, Json::num)),
+            ])).collect()))]),
+            Output::LimitProfiles { account, base, profiles } => Json::object([
+                ("account", account.as_ref().map_or(Json::Null, |(name, _)| Json::str(name))),
+                ("account_type", account.as_ref().and_then(|(_, kind)| kind.as_ref()).map_or(Json::Null, Json::str)),
+                ("currency", Json::str(base)),
+                ("profiles", Json::Array(profiles.iter().map(|(target, profile)| Json::object([
+                    ("target", Json::str(target)),
+                    ("max_deposit", profile.max_deposit.map_or(Json::Null, Json::num)),
+                    ("max_withdrawal", profile.max_withdrawal.map_or(Json::Null, Json::num)),
+                    ("max_daily_conversion", profile.max_daily_conversion.map_or(Json::Null, Json::num)),
+                ])).collect())),
+            ]),
+            Output::Baskets(quotes) => Json::object([("baskets", Json::Array(quotes.iter().map(basket_json).collect()))]),
+            Output::Accounts { accounts } => Json::Array(accounts.iter().map(|(name, id, balance)| Json::object([
+                ("account", Json::str(name)),
+                ("id", Json::num(id)),
+                ("balance", money(balance)),
+            ])).collect()),
+            Output::Aliases(aliases) => Json::Array(aliases.iter().map(|(alias, account)| Json::object([
+                ("alias", Json::str(alias)),
+                ("account", Json::str(account)),
+            ])).collect()),
+            Output::AutoConversion { account, currency, currencies } => Json::object([
+                ("account", Json::str(account)),
+                ("currency", Json::str(currency)),
+                ("auto_convert", Json::Array(currencies.iter().map(Json::str).collect())),
+            ]),
+            Output::Alerts { account, currency, alerts } => Json::object([
+                ("account", Json::str(account)),
+                ("currency", Json::str(currency)),
+                ("balance_below", alerts.balance_below.map_or(Json::Null, |t| money(&Money::new(t, currency)))),
+                ("withdrawal_above", alerts.withdrawal_above.map_or(Json::Null, |t| money(&Money::new(t, currency)))),
+                ("inactive_days", alerts.inactive_days.map_or(Json::Null, Json::num)),
+            ]),
+            Output::ServiceCharges { account, currency, charges } => Json::object([
+                ("account", Json::str(account)),
+                ("currency", Json::str(currency)),
+                ("monthly", charges.monthly.map_or(Json::Null, |c| money(&Money::new(c, currency)))),
+                ("maintaining_balance", charges.maintaining.map_or(Json::Null, |m| money(&Money::new(m.balance, currency)))),
+                ("maintaining_charge", charges.maintaining.map_or(Json::Null, |m| money(&Money::new(m.charge, currency)))),
+            ]),
+            Output::HoldPlaced { account, hold, available } | Output::HoldReleased { account, hold, available } => {
+                Json::object([("account", Json::str(account)), ("hold", hold_json(hold)), ("available", money(available))])
+            }
+            Output::HoldCaptured { account, hold, captured, balance } => {
+                Json::object([("account", Json::str(account)), ("hold", hold_json(hold)), ("captured", money(captured)), ("balance", money(balance))])
+            }
+            Output::ChequeDeposited { account, cheque, balance, available } => {
+                Json::object([("account", Json::str(account)), ("cheque", cheque_json(cheque, "clearing")), ("balance", money(balance)), ("available", money(available))])
+            }
+            Output::ChequeReturned { account, cheque, balance } => Json::object([("account", Json::str(account)), ("cheque", cheque_json(cheque, "returned")), ("balance", money(balance))]),
+            Output::Cheques { account, cheques } => {
+                Json::object([("account", Json::str(account)), ("cheques", Json::Array(cheques.iter().map(|(c, status)| cheque_json(c, status)).collect()))])
+            }
+            Output::Holds { account, holds, balance, available } => Json::object([
+                ("account", Json::str(account)),
+                ("holds", Json::Array(holds.iter().map(hold_json).collect())),
+                ("balance", money(balance)),
+                ("available", money(available)),
+            ]),
+            Output::Archived(accounts) => Json::Array(accounts.iter().map(|(name, id, on)| Json::object([
+                ("account", Json::str(name)),
+                ("id", Json::num(id)),
+                ("archived", Json::str(on.to_string())),
+            ])).collect()),
+            Output::Registered { account, id, currency, protected } => Json::object([
+                ("account", Json::str(account)),
+                ("id", Json::num(id)),
+                ("currency", Json::str(currency)),
+                ("protected", Json::Bool(*protected)),
+            ]),
+            Output::Posted { account, tx_type, amount, balance, envelope } => {
+                let mut fields = vec![
+                    ("account", Json::str(account)),
+                    ("type", Json::str(format!("{:?}", tx_type).to_lowercase())),
+                    ("amount", money(amount)),
+                    ("balance", money(balance)),
+                ];
+                if let Some(status) = envelope {
+                    fields.push(("budget", envelope_json(status)));
+                }
+                Json::object(fields)
+            }
+            Output::Approval(a) => approval_json(a),
+            Output::Approvals(requests) => Json::object([("approvals", Json::Array(requests.iter().map(approval_json).collect()))]),
+            Output::Transferred(r) | Output::Exchanged(r) => Json::object([
+                ("from", Json::str(&r.from)),
+                ("to", Json::str(&r.to)),
+                ("debited", money(&r.debited)),
+                ("credited", money(&r.credited)),
+                ("rate", Json::num(r.rate)),
+                ("fee", money(&r.fee)),
+                ("legs", legs_json(&r.legs)),
+                ("receipt", r.receipt.map_or(Json::Null, Json::num)),
+            ]),
+            Output::Balance { account, balance, available, version } => {
+                Json::object([("account", Json::str(account)), ("balance", money(balance)), ("available", money(available)), ("version", Json::num(version))])
+            }
+            Output::History { account, entries } => Json::object([
+                ("account", Json::str(account)),
+                ("transactions", Json::Array(entries.iter().map(|(t, balance)| Json::object([
+                    ("sequence", Json::num(t.sequence)),
+                    ("date", Json::str(t.date())),
+                    ("type", Json::str(format!("{:?}", t.tx_type()).to_lowercase())),
+                    ("amount", money(&Money::new(t.amount(), &balance.currency))),
+                    ("balance", money(balance)),
+                    ("memo", Json::str(&t.memo)),
+                    ("tags", Json::Array(t.tags.iter().map(Json::str).collect())),
+                ])).collect())),
+            ]),
+            Output::Tagged { account, sequence, tags } => Json::object([
+                ("account", Json::str(account)),
+                ("sequence", Json::num(sequence)),
+                ("tags", Json::Array(tags.iter().map(Json::str).collect())),
+            ]),
+            Output::TagReport { account, start, end, totals } => Json::object([
+                ("account", Json::str(account)),
+                ("start", Json::str(start)),
+                ("end", Json::str(end)),
+                ("tags", Json::Array(totals.iter().map(|t| Json::object([
+                    ("tag", Json::str(&t.tag)),
+                    ("count", Json::num(t.count)),
+                    ("inflow", money(&t.inflow)),
+                    ("outflow", money(&t.outflow)),
+                    ("net", money(&t.net())),
+                ])).collect())),
+            ]),
+            Output::Statement { account, format, content } => Json::object([
+                ("account", Json::str(account)),
+                ("format", Json::str(format.extension())),
+                ("content", Json::str(content)),
+            ]),
+            Output::Forecast { account, annual_interest, inflation, step, currency, days } => Json::object([
+                ("account", Json::str(account)),
+                ("annual_interest", Json::num(annual_interest)),
+                ("inflation", inflation.map_or(Json::Null, Json::num)),
+                ("step", Json::str(step.name())),
+                ("currency", currency.as_ref().map_or(Json::Null, Json::str)),
+                ("days", Json::Array(days.iter().map(|f| {
+                    let mut fields = vec![
+                        ("first_day", Json::num(f.first_day)),
+                        ("day", Json::num(f.day)),
+                        ("annual_interest", Json::num(f.annual_interest)),
+                        ("interest", money(&f.interest)),
+                        ("balance", money(&f.balance)),
+                    ];
+                    if let (Some(interest), Some(balance)) = (&f.real_interest, &f.real_balance) {
+                        fields.push(("real_interest", money(interest)));
+                        fields.push(("real_balance", money(balance)));
+                    }
+                    if let (Some(interest), Some(balance)) = (&f.converted_interest, &f.converted_balance) {
+                        fields.push(("converted_interest", money(interest)));
+                        fields.push(("converted_balance", money(balance)));
+                    }
+                    Json::object(fields)
+                }).collect())),
+            ]),
+            Output::InterestRates { account, opening, changes, promotion, current, payout, year_basis, interest_method, grace_days } => Json::object([
+                ("account", Json::str(account)),
+                ("opening", Json::num(opening)),
+                ("changes", Json::Array(changes.iter().map(|c| Json::object([
+                    ("effective", Json::str(c.effective)),
+                    ("annual_interest", Json::num(c.annual_interest)),
+                ])).collect())),
+                ("promotion", promotion.map_or(Json::Null, |p| Json::object([
+                    ("bonus", Json::num(p.bonus)),
+                    ("start", Json::str(p.start)),
+                    ("end", Json::str(p.end)),
+                ]))),
+                ("current", Json::num(current)),
+                ("payout", payout.as_ref().map_or(Json::Null, Json::str)),
+                ("year_basis", Json::str(year_basis.name())),
+                ("interest_method", Json::str(interest_method.name())),
+                ("interest_grace_days", Json::num(grace_days)),
+            ]),
+            Output::YearBasis(basis) => Json::object([("year_basis", Json::str(basis.name()))]),
+            Output::InterestMethod(method) => Json::object([("interest_method", Json::str(method.name()))]),
+            Output::InterestGrace(days) => Json::object([("interest_grace_days", Json::num(days))]),
+            Output::Scenarios { account, comparison } => Json::object([
+                ("account", Json::str(account)),
+                ("opening", money(&comparison.opening)),
+                ("days", Json::num(comparison.horizon)),
+                ("scenarios", Json::Array(comparison.series.iter().map(|s| Json::object([
+                    ("name", Json::str(&s.scenario.name)),
+                    ("annual_interest", Json::num(s.scenario.annual_interest)),
+                    ("compounding", Json::str(s.scenario.compounding.name())),
+                    ("contribution", s.scenario.contribution.map_or(Json::Null, |c| Json::object([
+                        ("amount", Json::num(c.amount)),
+                        ("frequency", Json::str(c.frequency.name())),
+                    ]))),
+                    ("points", Json::Array(s.points.iter().map(|p| Json::object([
+                        ("day", Json::num(p.day)),
+                        ("balance", money(&p.balance)),
+                        ("interest", money(&p.interest)),
+                        ("contributed", money(&p.contributed)),
+                    ])).collect())),
+                ])).collect())),
+            ]),
+            Output::Comparison(c) => Json::object([
+                ("start", Json::str(c.start)),
+                ("days", Json::num(c.horizon)),
+                ("crossover", c.crossover.map_or(Json::Null, Json::num)),
+                ("crossover_date", c.crossover.map_or(Json::Null, |day| Json::str(c.date_of(day)))),
+                ("accounts", Json::Array(c.accounts.iter().map(|a| Json::object([
+                    ("account", Json::str(&a.account)),
+                    ("opening", money(&a.opening)),
+                    ("opening_value", money(&a.opening_value)),
+                    ("annual_interest", Json::num(a.annual_interest)),
+                    ("rate", Json::num(a.rate)),
+                    ("points", Json::Array(a.points.iter().map(|p| Json::object([
+                        ("day", Json::num(p.day)),
+                        ("balance", money(&p.balance)),
+                        ("interest", money(&p.interest)),
+                        ("value", money(&p.value)),
+                    ])).collect())),
+                ])).collect())),
+            ]),
+            Output::MonteCarlo { account, outcome } => {
+                let distribution = |d: &Distribution| {
+                    Json::object([
+                        ("min", money(&d.min())),
+                        ("mean", money(&d.mean())),
+                        ("max", money(&d.max())),
+                        ("percentiles", Json::Array(PERCENTILES.iter().map(|p| Json::object([("percentile", Json::num(p)), ("amount", money(&d.percentile(*p)))])).collect())),
+                    ])
+                };
+                Json::object([
+                    ("account", Json::str(account)),
+                    ("seed", Json::num(outcome.seed)),
+                    ("paths", Json::num(outcome.paths)),
+                    ("days", Json::num(outcome.days)),
+                    ("opening", money(&outcome.opening)),
+                    ("opening_value", outcome.opening_value.as_ref().map_or(Json::Null, money)),
+                    ("balance", distribution(&outcome.balance)),
+                    ("value", outcome.value.as_ref().map_or(Json::Null, distribution)),
+                    ("chance_below_opening", Json::num(format!("{:.4}", outcome.balance.chance_below(outcome.opening.amount)))),
+                    ("deposited", money(&outcome.deposited)),
+                ])
+            }
+            Output::Pnl(reports) => Json::object([("positions", Json::Array(reports.iter().map(position_json).collect()))]),
+            Output::Portfolio(p) => portfolio_json(p),
+            Output::Portfolios(portfolios) => Json::object([("portfolios", Json::Array(portfolios.iter().map(portfolio_json).collect()))]),
+            Output::Imported { account, report, balance } => Json::object([
+                ("account", Json::str(account)),
+                ("imported", Json::num(report.imported)),
+                ("balance", money(balance)),
+                ("errors", Json::Array(report.errors.iter().map(|e| Json::object([
+                    ("row", Json::num(e.row)),
+                    ("reason", Json::str(e.failure.to_string())),
+                ])).collect())),
+            ]),
+            Output::InterestPosted(posted) => Json::object([("posted", Json::Array(posted.iter().map(|(account, amount)| Json::object([
+                ("account", Json::str(account)),
+                ("amount", money(amount)),
+            ])).collect()))]),
+            Output::GoalSet { account, goal } => Json::object([
+                ("account", Json::str(account)),
+                ("name", Json::str(&goal.name)),
+                ("target", money(&goal.target)),
+                ("date", Json::str(goal.target_date)),
+            ]),
+            Output::Goals { account, frequency, goals } => Json::object([
+                ("account", Json::str(account)),
+                ("frequency", Json::str(frequency.name())),
+                ("goals", Json::Array(goals.iter().map(|(goal, progress)| Json::object([
+                    ("name", Json::str(&goal.name)),
+                    ("target", money(&goal.target)),
+                    ("date", Json::str(goal.target_date)),
+                    ("saved", money(&progress.saved)),
+                    ("progress", Json::num(progress.fraction.round_dp(4))),
+                    ("periods_left", Json::num(progress.periods_left)),
+                    ("contribution", money(&progress.contribution)),
+                    ("reached", Json::Bool(progress.is_reached())),
+                ])).collect())),
+            ]),
+            Output::EnvelopeSet { account, envelope } => Json::object([
+                ("account", Json::str(account)),
+                ("category", Json::str(&envelope.category)),
+                ("limit", money(&envelope.limit)),
+            ]),
+            Output::Budget { account, date, envelopes } => Json::object([
+                ("account", Json::str(account)),
+                ("month", Json::str(format!("{}-{:02}", date.year, date.month))),
+                ("budgets", Json::Array(envelopes.iter().map(envelope_json).collect())),
+            ]),
+            Output::Summary { summary, .. } => Json::object([
+                ("month", Json::str(summary.month)),
+                ("accounts", Json::Array(summary.accounts.iter().map(|a| Json::object([
+                    ("account", Json::str(&a.account)),
+                    ("opening", money(&a.opening)),
+                    ("inflow", money(&a.inflow)),
+                    ("outflow", money(&a.outflow)),
+                    ("interest", money(&a.interest)),
+                    ("fees", money(&a.fees)),
+                    ("net_change", money(&a.net_change())),
+                    ("closing", money(&a.closing)),
+                ])).collect())),
+            ]),
+            Output::TaxCertificate { certificate: c, content } => {
+                let amount = |amount: Decimal| money(&Money::new(amount, &c.currency));
+                Json::object([
+                    ("account", Json::str(&c.account)),
+                    ("year", Json::num(c.year)),
+                    ("currency", Json::str(&c.currency)),
+                    ("holder", c.holder.as_deref().map_or(Json::Null, Json::str)),
+                    ("gross_interest", amount(c.gross())),
+                    ("tax_withheld", amount(c.withheld())),
+                    ("net_interest", amount(c.net())),
+                    ("months", Json::Array(c.months.iter().map(|m| Json::object([
+                        ("month", Json::str(m.month)),
+                        ("gross_interest", amount(m.gross)),
+                        ("tax_withheld", amount(m.withheld)),
+                        ("net_interest", amount(m.net())),
+                    ])).collect())),
+                    ("content", content.as_deref().map_or(Json::Null, Json::str)),
+                ])
+            }
+            Output::LoanOpened(loan) => loan_json(loan),
+            Output::Schedule { loan, rows } => Json::object([
+                ("loan", Json::num(loan.id)),
+                ("account", Json::str(&loan.account)),
+                ("payments_made", Json::num(loan.payments_made)),
+                ("schedule", Json::Array(rows.iter().map(|row| row_json(row, row.period <= loan.payments_made)).collect())),
+            ]),
+            Output::LoanPaid { loan, row, balance } => Json::object([
+                ("loan", loan_json(loan)),
+                ("paid", row_json(row, true)),
+                ("balance", money(balance)),
+            ]),
+            Output::Paydown(c) => Json::object([
+                ("amount", money(&c.amount)),
+                ("days", Json::num(c.days)),
+                ("loan_rate", Json::num(c.loan_rate)),
+                ("loan_apy", Json::num(c.loan_apy.round_dp(6))),
+                ("deposit_rate", Json::num(c.deposit_rate)),
+                ("tax_rate", Json::num(c.tax_rate)),
+                ("deposit_apy", Json::num(c.deposit_apy.round_dp(6))),
+                ("interest_saved", money(&c.interest_saved)),
+                ("interest_earned", money(&c.interest_earned)),
+                ("advantage", money(&c.advantage())),
+                ("better", Json::str(c.better().name())),
+                ("break_even_rate", c.break_even_rate.map_or(Json::Null, |r| Json::num(r.round_dp(6)))),
+            ]),
+            Output::OrderCreated(order) | Output::OrderSkipped(order) => order_json(order),
+            Output::Orders(orders) => Json::object([("standing_orders", Json::Array(orders.iter().map(order_json).collect()))]),
+            Output::OrderCancelled(order) => Json::object([("cancelled", order_json(order))]),
+            Output::SweepCreated(sweep) => sweep_json(sweep),
+            Output::Sweeps(sweeps) => Json::object([("sweeps", Json::Array(sweeps.iter().map(sweep_json).collect()))]),
+            Output::SweepCancelled(sweep) => Json::object([("cancelled", sweep_json(sweep))]),
+            Output::ForwardBooked(f) => Json::object(forward_fields(f)),
+            Output::Forwards(valuations) => Json::object([("forwards", Json::Array(valuations.iter().map(|v| {
+                let mut fields = forward_fields(&v.contract);
+                fields.extend([("spot", Json::num(v.spot)), ("mark_to_market", money(&v.mark_to_market))]);
+                Json::object(fields)
+            }).collect()))]),
+            Output::LimitPlaced(o) | Output::LimitAmended(o) => Json::object(limit_fields(o)),
+            Output::Limits(orders) => Json::object([("limit_orders", Json::Array(orders.iter().map(|(o, spot)| {
+                let mut fields = limit_fields(o);
+                fields.push(("spot", Json::num(spot)));
+                Json::object(fields)
+            }).collect()))]),
+            Output::LimitCancelled(o) => Json::object([("cancelled", Json::object(limit_fields(o)))]),
+            Output::EndOfDay(eod) => end_of_day_json(eod),
+            Output::Simulated { seed, steps, positions } => Json::object([
+                ("seed", Json::num(seed)),
+                ("days", Json::Array(steps.iter().map(step_json).collect())),
+                ("positions", Json::Array(positions.iter().map(position_json).collect())),
+            ]),
+            Output::Replayed { steps, positions, .. } => Json::object([
+                ("days", Json::Array(steps.iter().map(step_json).collect())),
+                ("positions", Json::Array(positions.iter().map(position_json).collect())),
+            ]),
+            Output::Compacted(report) => Json::object([
+                ("before", Json::str(report.cutoff)),
+                ("archive", Json::str(report.archive.display())),
+                ("accounts", Json::Array(report.accounts.iter().map(|c| Json::object([
+                    ("account", Json::str(&c.account)),
+                    ("compacted", Json::num(c.compacted)),
+                    ("opening", money(&c.opening)),
+                ])).collect())),
+                ("reviews_dropped", Json::num(report.reviews_dropped)),
+            ]),
+            Output::Demo(report) => Json::object([
+                ("seed", Json::num(report.seed)),
+                ("start", Json::str(report.start)),
+                ("days", Json::num(report.steps.len())),
+                ("transactions", Json::num(report.transactions)),
+                ("accounts", Json::Array(report.accounts.iter().filter_map(|name| bank.find_account(name)).map(|acct| Json::object([
+                    ("account", Json::str(&acct.name)),
+                    ("balance", money(&acct.get_balance())),
+                ])).collect())),
+            ]),
+            Output::Rounding { strategy, residue, total } => Json::object([
+                ("strategy", Json::str(format!("{:?}", strategy))),
+                ("residue", Json::Array(residue.iter().map(|(code, amount, value)| Json::object([
+                    ("currency", Json::str(code)),
+                    ("amount", Json::num(amount)),
+                    ("value", value.as_ref().map_or(Json::Null, |v| Json::num(v.amount))),
+                ])).collect())),
+                ("total", Json::object([("amount", Json::num(total.amount)), ("currency", Json::str(&total.currency))])),
+            ]),
+            Output::Verified(report) => Json::object([
+                ("accounts", Json::num(report.accounts)),
+                ("transactions", Json::num(report.transactions)),
+                ("ok", Json::Bool(report.is_clean())),
+                ("violations", Json::Array(report.violations.iter().map(|v| Json::object([
+                    ("kind", Json::str(v.kind())),
+                    ("message", Json::str(v)),
+                ])).collect())),
+            ]),
+            Output::Help => Json::object([("usage", Json::str(USAGE))]),
+        }
+    }
+}
+
+/// A conversion quote's trend line, e.g. "Trend: ▲ 0.52% since
+/// 2026-06-01, ▼ 0.10% vs. the 7-day average"; empty without the history
+/// for either.
+fn trend_line(trend: &RateTrend) -> String {
+    let mut parts = Vec::new();
+    if let (Some(change), Some((date, _))) = (trend.vs_previous(), trend.previous) {
+        parts.push(format!("{} since {}", trend_arrow(change), date));
+    }
+    if let Some(change) = trend.vs_average() {
+        parts.push(format!("{} vs. the {}-day average", trend_arrow(change), TREND_DAYS));
+    }
+    match parts.is_empty() {
+        true => String::new(),
+        false => format!("\nTrend: {}", parts.join(", ")),
+    }
+}
+
+/// `change`, a fraction, as an arrow and a percentage: "▲ 0.52%", "▼ 0.10%",
+/// or "= 0.00%" when it rounds to nothing.
+pub fn trend_arrow(change: Decimal) -> String {
+    let percent = (change * Decimal::from(100)).round_dp(2);
+    let arrow = if percent > Decimal::ZERO { "▲" } else if percent < Decimal::ZERO { "▼" } else { "=" };
+    format!("{} {:.2}%", arrow, percent.abs())
+}
+
+fn volatility_json(v: &RateVolatility) -> Json {
+    Json::object([
+        ("start", Json::str(v.start)),
+        ("end", Json::str(v.end)),
+        ("returns", Json::num(v.returns)),
+        ("daily", Json::num(v.daily)),
+        ("annualized", Json::num(v.annualized)),
+    ])
+}
+
+/// Simulated or replayed days as a table of each day's rates for `codes`
+/// (blank where a rate did not move), the fills and end-of-day runs they
+/// caused, then the FX profit and loss at the final rates.
+fn market_text(bank: &Bank, codes: &[String], steps: &[MarketStep], positions: &[PositionReport]) -> String {
+    let columns: Vec<(&str, Align)> = std::iter::once(("Date", Align::Left)).chain(codes.iter().map(|c| (c.as_str(), Align::Right))).collect();
+    let mut table = Table::new(&columns);
+    let mut events = Vec::new();
+    for step in steps {
+        let rate = |code: &String| step.moves.iter().find(|m| m.code == *code).map_or_else(String::new, |m| m.new.to_string());
+        table.row(std::iter::once(step.date.to_string()).chain(codes.iter().map(rate)));
+        events.extend(step.fills.iter().map(|fill| format!("{} {}", step.date, fill_line(bank, fill))));
+        events.extend(end_of_day_lines(bank, &step.end_of_day));
+    }
+    let mut out = table.to_string();
+    if !events.is_empty() {
+        out.push_str(&format!("\n{}", events.join("\n")));
+    }
+    if !positions.is_empty() {
+        out.push_str(&format!("\nFX profit and loss at the final rates:\n{}", pnl_table(bank, positions)));
+    }
+    out
+}
+
+/// A rate history file, with the file named in any error.
+fn read_history(file: &Path, delimiter: char) -> io::Result<RateHistory> {
+    fs::File::open(file)
+        .and_then(|f| RateHistory::read_csv(io::BufReader::new(f), delimiter))
+        .map_err(|e| io::Error::new(e.kind(), format!("cannot read {}: {}", file.display(), e)))
+}
+
+/// One limit order fill, as text.
+fn fill_line(bank: &Bank, fill: &LimitOrderFill) -> String {
+    match &fill.result {
+        Ok(amount) => format!(
+            "Limit order {} {} at {}: {} to {} for {}, {} {}.{}",
+            fill.order.id,
+            if fill.order.filled { "filled" } else { "partly filled" },
+            fill.spot,
+            bank.format_money(&fill.converted),
+            fill.order.to,
+            fill.order.account,
+            if amount.currency == fill.order.to { "credited" } else { "debited" },
+            bank.format_money(amount),
+            if fill.order.filled { String::new() } else { format!(" {} remains open.", bank.format_money(&fill.order.remaining())) }
+        ),
+        Err(e) => format!("Limit order {} not filled at {}: {}", fill.order.id, fill.spot, e),
+    }
+}
+
+/// "; receipt N" for a transfer that converted, to close its summary's
+/// parenthesis; nothing otherwise.
+fn receipt_note(receipt: &TransferReceipt) -> String {
+    receipt.receipt.map(|n| format!("; receipt {}", n)).unwrap_or_default()
+}
+
+/// One indented line per leg of a conversion routed through another
+/// currency, each starting on a new line; nothing for a single leg, whose
+/// rate is the conversion's own.
+fn leg_lines(bank: &Bank, legs: &[ConversionLeg]) -> String {
+    if legs.len() < 2 {
+        return String::new();
+    }
+    legs.iter()
+        .enumerate()
+        .map(|(i, l)| {
+            format!(
+                "\n  Leg {}: {} -> {} at {} {} per {}",
+                i + 1,
+                bank.format_money(&l.from),
+                bank.format_money(&l.to),
+                l.rate.round_dp(EXCHANGE_RATE_DP),
+                l.to.currency,
+                l.from.currency
+            )
+        })
+        .collect()
+}
+
+/// One line per forward settled and standing order run by an end of day.
+fn end_of_day_lines(bank: &Bank, eod: &EndOfDay) -> Vec<String> {
+    eod.carried
+        .iter()
+        .map(|(code, rate)| format!("{} {}: no rate is published on a non-business day; carried the last close, {}.", eod.date, code, bank.forex.currency(code).map_or_else(|| rate.to_string(), |c| c.format_rate(*rate))))
+        .chain(eod.forwards.iter().map(|s| match &s.result {
+            Ok(amount) => format!(
+                "{} forward {}: {} {} at {}, {} {}.",
+                s.contract.settlement_date(&bank.calendar),
+                s.contract.id,
+                s.contract.side.name(),
+                bank.format_money(&s.contract.amount),
+                s.contract.rate,
+                if s.contract.side == ForwardSide::Sell { "credited" } else { "debited" },
+                bank.format_money(amount)
+            ),
+            Err(e) => format!("{} forward {}: failed: {}", s.contract.settlement_date(&bank.calendar), s.contract.id, e),
+        }))
+        .chain(eod.standing_orders.iter().map(|run| match &run.result {
+            Ok(r) => format!(
+                "{} standing order {}: transferred {} from {} to {} (credited {}).",
+                run.date,
+                run.order,
+                bank.format_money(&r.debited),
+                r.from,
+                r.to,
+                bank.format_money(&r.credited)
+            ),
+            Err(e) => format!("{} standing order {}: failed: {}", run.date, run.order, e),
+        }))
+        .chain(eod.sweeps.iter().map(|run| match &run.result {
+            Ok(r) => format!(
+                "{} sweep {}: moved {} from {} to {} (credited {}).",
+                eod.date,
+                run.rule,
+                bank.format_money(&r.debited),
+                r.from,
+                r.to,
+                bank.format_money(&r.credited)
+            ),
+            Err(e) => format!("{} sweep {}: failed: {}", eod.date, run.rule, e),
+        }))
+        .chain(eod.service_charges.iter().map(|run| match &run.result {
+            Ok(charged) => format!(
+                "{} {} on {}: charged {} (average daily balance {}).",
+                eod.date,
+                run.memo.to_lowercase(),
+                run.account,
+                bank.format_money(charged),
+                bank.format_money(&run.average)
+            ),
+            Err(e) => format!("{} {} on {}: failed: {}", eod.date, run.memo.to_lowercase(), run.account, e),
+        }))
+        .chain((eod.statements > 0).then(|| format!("{} issued {} month-end statement(s).", eod.date, eod.statements)))
+        .collect()
+}
+
+/// FX profit and loss, one row per account.
+fn pnl_table(bank: &Bank, reports: &[PositionReport]) -> Table {
+    let mut table = Table::new(&[
+        ("Account", Align::Left),
+        ("Balance", Align::Right),
+        ("Spot", Align::Right),
+        ("Value", Align::Right),
+        ("Cost", Align::Right),
+        ("Unrealized", Align::Right),
+        ("Realized", Align::Right),
+    ]);
+    for r in reports {
+        table.row([
+            r.account.clone(),
+            bank.format_money(&r.balance),
+            r.spot.to_string(),
+            bank.format_money(&r.market_value),
+            bank.format_money(&r.cost_basis),
+            bank.format_money(&r.unrealized),
+            bank.format_money(&r.realized),
+        ]);
+    }
+    table
+}
+
+/// A fraction as a percentage, e.g. 0.5 → "50%".
+fn percent(fraction: Decimal) -> String {
+    format!("{}%", (fraction * Decimal::from(100)).round_dp(2))
+}
+
+/// `acct`'s rates as of `today`, for `interest-rate`, `interest-rates`,
+/// and promotions.
+fn interest_rates(acct: &Account, today: Date) -> Output {
+    Output::InterestRates {
+        account: acct.name.clone(),
+        opening: acct.annual_interest,
+        changes: acct.rate_changes.clone(),
+        promotion: acct.promotion,
+        current: acct.rate_on(today),
+        payout: acct.interest_payout.clone(),
+        year_basis: acct.year_basis,
+        interest_method: acct.interest_method,
+        grace_days: acct.interest_grace_days,
+    }
+}
+
+/// The request a withdrawal or transfer was held as, when `e` says it
+/// awaits approval; otherwise `e` itself.
+fn held(bank: &Bank, e: BankError) -> Result<Output, CliError> {
+    match e {
+        BankError::AwaitingApproval(id) if let Some(request) = bank.pending.iter().find(|p| p.id == id) => Ok(Output::Approval(request.clone())),
+        e => Err(Error::from(e).into()),
+    }
+}
+
+/// e.g. "withdrawal of ₱150,000.00 from Alice", amounts formatted for the
+/// bank's locale.
+fn held_text(bank: &Bank, held: &HeldTransaction) -> String {
+    match held {
+        HeldTransaction::Withdrawal { account, amount, .. } => format!("withdrawal of {} from {}", bank.format_money(amount), account),
+        HeldTransaction::Transfer { from, to, amount } => format!("transfer of {} from {} to {}", bank.format_money(amount), from, to),
+    }
+}
+
+fn find_account<'a>(bank: &'a Bank, name: &str) -> Result<&'a Account, CliError> {
+    bank.find_account(name).ok_or_else(|| BankError::AccountNotFound(name.to_string()).into())
+}
+
+/// `Output::LimitProfiles` for every profile, or for those `account`
+/// falls under.
+fn limit_profiles(bank: &Bank, account: Option<&Account>) -> Output {
+    let profiles = match account {
+        Some(acct) => bank.limit_profiles_of(acct).into_iter().map(|(t, p)| (t.clone(), *p)).collect(),
+        None => bank.compliance.limit_profiles.iter().map(|(t, p)| (t.clone(), *p)).collect(),
+    };
+    Output::LimitProfiles { account: account.map(|a| (a.name.clone(), a.account_type.clone())), base: bank.base_currency.code.clone(), profiles }
+}
+
+/// Every alias with its account's name, by account.
+fn aliases(bank: &Bank) -> Vec<(String, String)> {
+    bank.accounts.iter().flat_map(|a| a.aliases.iter().map(|alias| (alias.clone(), a.name.clone()))).collect()
+}
+
+/// Every archived account with its ID and the day it was archived.
+fn archived(bank: &Bank) -> Vec<(String, usize, Date)> {
+    bank.archived_accounts().filter_map(|a| Some((a.name.clone(), a.id, a.archived?))).collect()
+}
+
+/// `command` with every account it names, other than a new one, given by
+/// the account's real name, so an alias works wherever a name does.
+fn with_real_names(bank: &Bank, command: &Command) -> Command {
+    let mut command = command.clone();
+    let real = |name: &mut String| {
+        if let Some(real) = bank.resolve_account(name) {
+            *name = real.to_string();
+        }
+    };
+    match &mut command {
+        Command::Alias { account, .. }
+        | Command::Archive { account }
+        | Command::Unarchive { account }
+        | Command::Post { account, .. }
+        | Command::AutoConvert { account, .. }
+        | Command::Alert { account, .. }
+        | Command::Alerts { account }
+        | Command::ServiceCharge { account, .. }
+        | Command::ServiceCharges { account }
+        | Command::AccountType { account, .. }
+        | Command::Hold { account, .. }
+        | Command::Holds { account }
+        | Command::Release { account, .. }
+        | Command::Capture { account, .. }
+        | Command::DepositCheque { account, .. }
+        | Command::Cheques { account, .. }
+        | Command::ReturnCheque { account, .. }
+        | Command::Balance { account }
+        | Command::History { account }
+        | Command::Tag { account, .. }
+        | Command::TagReport { account, .. }
+        | Command::Statement { account, .. }
+        | Command::Import { account, .. }
+        | Command::Forecast { account, .. }
+        | Command::InterestRate { account, .. }
+        | Command::InterestRates { account }
+        | Command::Promotion { account, .. }
+        | Command::EndPromotion { account }
+        | Command::Scenarios { account, .. }
+        | Command::Goal { account, .. }
+        | Command::Goals { account, .. }
+        | Command::Envelope { account, .. }
+        | Command::Budget { account, .. }
+        | Command::TaxCertificate { account, .. }
+        | Command::Loan { account, .. }
+        | Command::Forward { account, .. }
+        | Command::Limit { account, .. } => real(account),
+        Command::Pnl { account } | Command::Portfolio { account, .. } => account.iter_mut().for_each(real),
+        Command::InterestPayout { account, to } => {
+            real(account);
+            to.iter_mut().for_each(real);
+        }
+        Command::YearBasis { account, .. } | Command::InterestMethod { account, .. } | Command::InterestGrace { account, .. } => account.iter_mut().for_each(real),
+        Command::Versioned { command: inner, .. } | Command::Admin { command: inner, .. } => **inner = with_real_names(bank, inner),
+        Command::Conversions(filter) | Command::Turnover(filter) => filter.account.iter_mut().for_each(real),
+        Command::LimitProfiles { account } => account.iter_mut().for_each(real),
+        Command::MonteCarlo { account, .. } => real(account),
+        Command::Compare { account, with, .. } => {
+            real(account);
+            real(with);
+        }
+        Command::Transfer { from, to, .. } | Command::Exchange { from, to, .. } | Command::Order { from, to, .. } | Command::Sweep { from, to, .. } => {
+            real(from);
+            real(to);
+        }
+        _ => {}
+    }
+    command
+}
@@ -1,180 +1,4115 @@
-use crate::api::{account::TransactionType, bank::Bank};
+use std::panic::{self, AssertUnwindSafe};
+
+use crate::api::{
+    account::{adjust_for_inflation, convert_forecast, summarize_forecast, ForecastStep, InterestMethod, TransactionType}, bank::{Bank, BankError, EndOfDay, EXCHANGE_RATE_DP}, budget::Envelope, compaction, customer::{Customer, IdType, Identification, VerificationStatus}, date::{Date, Month}, dca::DcaSimulation, decimal::{Decimal, RoundingStrategy}, denomination::CashBreakdown, fee::{ConversionLeg, ConversionPreview, FeeBasis}, forex::{Currency, RateSource, RateType, BASKET_RATE_DP, DEFAULT_RATE_DP, RATE_HISTORY_DAYS},
+    config::MacroConfig, forward::ForwardSide, goal::SavingsGoal, hold::{Hold, DEFAULT_HOLD_DAYS}, inbox::{Inbox, InboxMessage}, limit_order::LimitOrderFill, limit_profile::{LimitProfile, LimitTarget}, loan::{Loan, PaymentFrequency, MAX_TERM}, market::{MarketSimulator, RateModel}, monte_carlo::{DepositBehavior, Distribution, DEFAULT_PATHS, MAX_PATHS, PERCENTILES}, moving_average::{self, MovingAverage}, rate_stats::TREND_DAYS, rates::YearBasis, paydown::{PaydownChoice, PaydownComparison}, pending::HeldTransaction, portfolio::Asset, scenario::{self, Compounding, Scenario}, standing_order::MAX_INTERVAL_DAYS, money::Money, event::BankEvent, notify::EventBus, persist, role::Role, search::TransactionQuery, service_charge::MaintainingBalance, till::Till, volatility::DEFAULT_WINDOW,
+};
+use crate::view::cli::{self, report_notify_failures, ADMIN_COMMANDS};
 use crate::view::console_util::{
-    ask_yes_no, convert_amount, currency_menu_lists, print_currency_menu, read_f64_prompt,
-    read_string_prompt, read_usize_prompt,
+    EndOfInput, ask_yes_no, confirm_explicit, currency_menu_lists, match_currency, offer_csv_export, page_size, print_currency_menu, print_paged,
+    progress_bar,
+    read_currency_prompt, read_decimal_prompt, read_masked_prompt, read_optional_amount_prompt, read_signed_decimal_prompt, read_string_prompt, read_tx_type_filter, read_usize_prompt,
+    set_amount_locale,
 };
+use crate::view::export::Csv;
+use crate::view::i18n::tr;
+use crate::view::journal::{script_line, Journal};
+use crate::view::line_editor;
+use crate::view::table::{Align, Table};
 
 pub struct ConsoleApp {
     pub bank: Bank,
+    pub role: Role,
+    /// The passphrase that unlocked `Role::Admin`, journaled with the
+    /// admin commands so the journal replays; `None` for a teller.
+    admin_passphrase: Option<String>,
+    /// Customer logged in to this session, if any. Menus then default to and
+    /// show only that customer's accounts; `None` is a staff session that
+    /// sees every account.
+    pub customer: Option<usize>,
+    /// Session file: saved after every operation that changes the bank, and
+    /// the default for Save/Load Snapshot (the configured `data_file`).
+    pub data_file: String,
+    /// Where operations that change the bank are recorded as commands, if
+    /// anywhere (the configured `journal_file`).
+    pub journal: Option<Journal>,
+    /// Command sequences Run Macro offers (the configured `[[macro]]`s).
+    pub macros: Vec<MacroConfig>,
+    /// Whether the operation running has recorded itself in `journal`.
+    journaled: bool,
+    undo: Vec<UndoEntry>,
+    /// Encoded bank as last written to `data_file`, to skip unchanged saves.
+    saved: String,
+    /// Where the bank's events are sent after each operation.
+    notifiers: EventBus,
+    /// Alerts and flags kept from `notifiers` until they are dismissed.
+    inbox: Inbox,
+}
+
+/// How many operations "Undo Last Operation" can step back through.
+const UNDO_LIMIT: usize = 10;
+
+/// How many of an account's latest transactions Transaction Tags offers
+/// to tag.
+const TAG_RECENT_TRANSACTIONS: usize = 10;
+
+/// How many of a currency's latest closing rates Rate History shows.
+const RATE_HISTORY_SHOWN: usize = 30;
+
+/// The compounding modes the APY and interest calculators offer, most
+/// frequent first.
+const COMPOUNDINGS: [Compounding; 7] = [
+    Compounding::Daily,
+    Compounding::Periodic(PaymentFrequency::Weekly),
+    Compounding::Periodic(PaymentFrequency::Biweekly),
+    Compounding::Periodic(PaymentFrequency::Monthly),
+    Compounding::Periodic(PaymentFrequency::Quarterly),
+    Compounding::Periodic(PaymentFrequency::Annually),
+    Compounding::Simple,
+];
+
+/// A reversible console operation, newest last on `ConsoleApp::undo`.
+/// - `Transaction`: a deposit or withdrawal, by account and 0-based index.
+/// - `Rate`: the currency's catalog entry before its rate was overwritten.
+enum UndoEntry {
+    Transaction { account: String, index: usize, tx_type: TransactionType, amount: Money },
+    Rate(Currency),
 }
 
+/// A main-menu entry; `label` and `help` (its Help screen description) are message-catalog keys. Entries are
+/// shown only to roles allowed by `role`; `mutates` entries, which only change the bank, are hidden while it
+/// is read-only; `needs_account` entries are refused until an account exists.
+struct MenuEntry {
+    label: &'static str,
+    help: &'static str,
+    role: Role,
+    mutates: bool,
+    needs_account: bool,
+    handler: fn(&mut ConsoleApp),
+}
+
+const MAIN_MENU: &[MenuEntry] = &[
+    MenuEntry { label: "menu.register", help: "help.register", role: Role::Teller, mutates: true, needs_account: false, handler: ConsoleApp::menu_register_account },
+    MenuEntry { label: "menu.list_accounts", help: "help.list_accounts", role: Role::Teller, mutates: false, needs_account: true, handler: ConsoleApp::menu_list_accounts },
+    MenuEntry { label: "menu.deposit", help: "help.deposit", role: Role::Teller, mutates: true, needs_account: true, handler: ConsoleApp::menu_deposit },
+    MenuEntry { label: "menu.withdraw", help: "help.withdraw", role: Role::Teller, mutates: true, needs_account: true, handler: ConsoleApp::menu_withdraw },
+    MenuEntry { label: "menu.transfer", help: "help.transfer", role: Role::Teller, mutates: true, needs_account: true, handler: ConsoleApp::menu_transfer },
+    MenuEntry { label: "menu.standing_orders", help: "help.standing_orders", role: Role::Teller, mutates: false, needs_account: true, handler: ConsoleApp::menu_standing_orders },
+    MenuEntry { label: "menu.till", help: "help.till", role: Role::Teller, mutates: false, needs_account: false, handler: ConsoleApp::menu_till },
+    MenuEntry { label: "menu.show_rates", help: "help.show_rates", role: Role::Teller, mutates: false, needs_account: false, handler: ConsoleApp::menu_show_rates },
+    MenuEntry { label: "menu.rate_history", help: "help.rate_history", role: Role::Teller, mutates: false, needs_account: false, handler: ConsoleApp::menu_rate_history },
+    MenuEntry { label: "menu.rate_summary", help: "help.rate_summary", role: Role::Teller, mutates: false, needs_account: false, handler: ConsoleApp::menu_rate_summary },
+    MenuEntry { label: "menu.exchange", help: "help.exchange", role: Role::Teller, mutates: true, needs_account: true, handler: ConsoleApp::menu_currency_exchange },
+    MenuEntry { label: "menu.receipts", help: "help.receipts", role: Role::Teller, mutates: false, needs_account: false, handler: ConsoleApp::menu_exchange_receipts },
+    MenuEntry { label: "menu.forwards", help: "help.forwards", role: Role::Teller, mutates: false, needs_account: true, handler: ConsoleApp::menu_forwards },
+    MenuEntry { label: "menu.limit_orders", help: "help.limit_orders", role: Role::Teller, mutates: false, needs_account: true, handler: ConsoleApp::menu_limit_orders },
+    MenuEntry { label: "menu.pnl", help: "help.pnl", role: Role::Teller, mutates: false, needs_account: true, handler: ConsoleApp::menu_fx_pnl },
+    MenuEntry { label: "menu.portfolio", help: "help.portfolio", role: Role::Teller, mutates: false, needs_account: true, handler: ConsoleApp::menu_portfolio },
+    MenuEntry { label: "menu.dca", help: "help.dca", role: Role::Teller, mutates: false, needs_account: false, handler: ConsoleApp::menu_dca },
+    MenuEntry { label: "menu.record_rate", help: "help.record_rate", role: Role::Admin, mutates: true, needs_account: true, handler: ConsoleApp::menu_record_exchange_rate },
+    MenuEntry { label: "menu.currencies", help: "help.currencies", role: Role::Admin, mutates: true, needs_account: false, handler: ConsoleApp::menu_manage_currencies },
+    MenuEntry { label: "menu.simulate", help: "help.simulate", role: Role::Admin, mutates: true, needs_account: false, handler: ConsoleApp::menu_simulate_market },
+    MenuEntry { label: "menu.show_interest", help: "help.show_interest", role: Role::Teller, mutates: false, needs_account: true, handler: ConsoleApp::menu_show_interest },
+    MenuEntry { label: "menu.compare_rates", help: "help.compare_rates", role: Role::Teller, mutates: false, needs_account: true, handler: ConsoleApp::menu_compare_rates },
+    MenuEntry { label: "menu.compare_accounts", help: "help.compare_accounts", role: Role::Teller, mutates: false, needs_account: true, handler: ConsoleApp::menu_compare_accounts },
+    MenuEntry { label: "menu.apy", help: "help.apy", role: Role::Teller, mutates: false, needs_account: false, handler: ConsoleApp::menu_apy_calculator },
+    MenuEntry { label: "menu.calculator", help: "help.calculator", role: Role::Teller, mutates: false, needs_account: false, handler: ConsoleApp::menu_interest_calculator },
+    MenuEntry { label: "menu.monte_carlo", help: "help.monte_carlo", role: Role::Teller, mutates: false, needs_account: true, handler: ConsoleApp::menu_monte_carlo },
+    MenuEntry { label: "menu.paydown", help: "help.paydown", role: Role::Teller, mutates: false, needs_account: false, handler: ConsoleApp::menu_loan_vs_deposit },
+    MenuEntry { label: "menu.goals", help: "help.goals", role: Role::Teller, mutates: false, needs_account: true, handler: ConsoleApp::menu_savings_goals },
+    MenuEntry { label: "menu.budget", help: "help.budget", role: Role::Teller, mutates: false, needs_account: true, handler: ConsoleApp::menu_budget },
+    MenuEntry { label: "menu.tags", help: "help.tags", role: Role::Teller, mutates: false, needs_account: true, handler: ConsoleApp::menu_tags },
+    MenuEntry { label: "menu.summary", help: "help.summary", role: Role::Teller, mutates: false, needs_account: true, handler: ConsoleApp::menu_monthly_summary },
+    MenuEntry { label: "menu.tax", help: "help.tax", role: Role::Teller, mutates: false, needs_account: true, handler: ConsoleApp::menu_tax_certificate },
+    MenuEntry { label: "menu.post_interest", help: "help.post_interest", role: Role::Admin, mutates: true, needs_account: true, handler: ConsoleApp::menu_post_interest },
+    MenuEntry { label: "menu.rounding", help: "help.rounding", role: Role::Admin, mutates: false, needs_account: false, handler: ConsoleApp::menu_rounding },
+    MenuEntry { label: "menu.set_interest", help: "help.set_interest", role: Role::Admin, mutates: true, needs_account: false, handler: ConsoleApp::menu_set_interest_rate },
+    MenuEntry { label: "menu.year_basis", help: "help.year_basis", role: Role::Admin, mutates: true, needs_account: false, handler: ConsoleApp::menu_year_basis },
+    MenuEntry { label: "menu.interest_method", help: "help.interest_method", role: Role::Admin, mutates: true, needs_account: false, handler: ConsoleApp::menu_interest_method },
+    MenuEntry { label: "menu.interest_grace", help: "help.interest_grace", role: Role::Admin, mutates: true, needs_account: false, handler: ConsoleApp::menu_interest_grace },
+    MenuEntry { label: "menu.promotion", help: "help.promotion", role: Role::Admin, mutates: true, needs_account: true, handler: ConsoleApp::menu_promotion },
+    MenuEntry { label: "menu.holds", help: "help.holds", role: Role::Teller, mutates: true, needs_account: true, handler: ConsoleApp::menu_holds },
+    MenuEntry { label: "menu.cheques", help: "help.cheques", role: Role::Teller, mutates: true, needs_account: true, handler: ConsoleApp::menu_cheques },
+    MenuEntry { label: "menu.service_charges", help: "help.service_charges", role: Role::Admin, mutates: true, needs_account: true, handler: ConsoleApp::menu_service_charges },
+    MenuEntry { label: "menu.limit_profiles", help: "help.limit_profiles", role: Role::Admin, mutates: true, needs_account: false, handler: ConsoleApp::menu_limit_profiles },
+    MenuEntry { label: "menu.end_of_day", help: "help.end_of_day", role: Role::Admin, mutates: true, needs_account: true, handler: ConsoleApp::menu_end_of_day },
+    MenuEntry { label: "menu.review_flagged", help: "help.review_flagged", role: Role::Admin, mutates: false, needs_account: true, handler: ConsoleApp::menu_review_flagged },
+    MenuEntry { label: "menu.approvals", help: "help.approvals", role: Role::Admin, mutates: true, needs_account: false, handler: ConsoleApp::menu_approvals },
+    MenuEntry { label: "menu.verify", help: "help.verify", role: Role::Admin, mutates: false, needs_account: false, handler: ConsoleApp::menu_verify_ledger },
+    MenuEntry { label: "menu.history", help: "help.history", role: Role::Teller, mutates: false, needs_account: true, handler: ConsoleApp::menu_transaction_history },
+    MenuEntry { label: "menu.search", help: "help.search", role: Role::Teller, mutates: false, needs_account: true, handler: ConsoleApp::menu_search_transactions },
+    MenuEntry { label: "menu.customers", help: "help.customers", role: Role::Teller, mutates: true, needs_account: false, handler: ConsoleApp::menu_customers },
+    MenuEntry { label: "menu.aliases", help: "help.aliases", role: Role::Teller, mutates: true, needs_account: true, handler: ConsoleApp::menu_aliases },
+    MenuEntry { label: "menu.archive", help: "help.archive", role: Role::Admin, mutates: true, needs_account: true, handler: ConsoleApp::menu_archive },
+    MenuEntry { label: "menu.snapshots", help: "help.snapshots", role: Role::Admin, mutates: true, needs_account: false, handler: ConsoleApp::menu_snapshots },
+    MenuEntry { label: "menu.undo", help: "help.undo", role: Role::Teller, mutates: true, needs_account: false, handler: ConsoleApp::menu_undo },
+    MenuEntry { label: "menu.macros", help: "help.macros", role: Role::Teller, mutates: false, needs_account: false, handler: ConsoleApp::menu_run_macro },
+    MenuEntry { label: "menu.inbox", help: "help.inbox", role: Role::Teller, mutates: false, needs_account: false, handler: ConsoleApp::menu_inbox },
+    MenuEntry { label: "menu.help", help: "help.help", role: Role::Teller, mutates: false, needs_account: false, handler: ConsoleApp::menu_help },
+    MenuEntry { label: "menu.switch_role", help: "help.switch_role", role: Role::Teller, mutates: false, needs_account: false, handler: ConsoleApp::menu_switch_role },
+    MenuEntry { label: "menu.log_in", help: "help.log_in", role: Role::Teller, mutates: false, needs_account: false, handler: ConsoleApp::menu_log_in },
+];
+
 impl ConsoleApp {
-    pub fn new(bank: Bank) -> Self {
-        Self { bank }
+    pub fn new(bank: Bank, data_file: &str, mut notifiers: EventBus) -> Self {
+        let saved = persist::encode(&bank);
+        set_amount_locale(bank.locale);
+        let inbox = Inbox::new();
+        notifiers.subscribe(Box::new(inbox.clone()));
+        Self { bank, role: Role::Teller, admin_passphrase: None, customer: None, data_file: data_file.to_string(), journal: None, macros: Vec::new(), journaled: false, undo: Vec::new(), saved, notifiers, inbox }
     }
 
+    /// Run the menu until the user leaves it or input ends (see
+    /// `EndOfInput`). Either way the session is saved before returning.
     pub fn run(&mut self) {
+        let outcome = panic::catch_unwind(AssertUnwindSafe(|| self.run_menu()));
+        self.log_out();
+        self.autosave();
+        report_notify_failures(self.notifiers.drain(&mut self.bank));
+        match outcome {
+            Ok(()) => {}
+            Err(payload) if payload.is::<EndOfInput>() => println!("\n{}", tr!("session.goodbye")),
+            Err(payload) => panic::resume_unwind(payload),
+        }
+    }
+
+    fn run_menu(&mut self) {
+        self.menu_log_in();
+        self.menu_switch_role();
         loop {
-            println!("\nMain Menu\n");
-            println!("Select Transaction:");
-            println!("[1] Register Account Name");
-            println!("[2] Deposit Amount");
-            println!("[3] Withdraw Amount");
-            println!("[4] Currency Exchange");
-            println!("[5] Record Exchange Rates");
-            println!("[6] Show Interest Computation");
+            self.refresh_completions();
+            let entries = self.menu_entries();
+
+            let role = match self.role {
+                Role::Teller => tr!("role.teller"),
+                Role::Admin => tr!("role.admin"),
+            };
+            println!("\n{}", tr!("main.title", role));
+            if let Some(customer) = self.customer.and_then(|id| self.bank.find_customer(id)) {
+                println!("{}", tr!("session.customer", customer.name, customer.id));
+            }
+            if self.bank.is_read_only() {
+                println!("{}", tr!("session.read_only"));
+            }
+            println!();
+            println!("{}", tr!("main.select"));
+            let unread = self.inbox_messages().iter().filter(|m| !m.read).count();
+            for (i, entry) in entries.iter().enumerate() {
+                if entry.label == "menu.inbox" && unread > 0 {
+                    println!("[{}] {} ({})", i + 1, tr!(entry.label), unread);
+                } else {
+                    println!("[{}] {}", i + 1, tr!(entry.label));
+                }
+            }
 
             let choice = read_usize_prompt("");
 
-            if choice < 1 || choice > 6 {
-                println!("Invalid option. Please select 1-6.");
+            let Some(entry) = entries.get(choice - 1) else {
+                println!("{}", tr!("main.invalid_option", entries.len()));
                 continue;
-            }
+            };
 
-            if choice != 1 && self.bank.accounts.len() < 1 {
-                println!("Please registered an account through [1] before proceeding.");
+            if entry.needs_account && self.bank.accounts.is_empty() {
+                println!("{}", tr!("main.needs_account"));
                 continue;
             }
 
-            match choice {
-                1 => self.menu_register_account(),
-                2 => self.menu_deposit(),
-                3 => self.menu_withdraw(),
-                4 => self.menu_currency_exchange(),
-                5 => self.menu_record_exchange_rate(),
-                6 => self.menu_show_interest(),
-                _ => println!("Invalid option. Please select 1-6."),
+            let before = self.journal.is_some().then(|| persist::encode(&self.bank));
+            self.journaled = false;
+            (entry.handler)(self);
+            if let Some(before) = before
+                && !self.journaled
+                && persist::encode(&self.bank) != before
+            {
+                self.note_unjournaled(entry.label);
             }
+            self.autosave();
+            report_notify_failures(self.notifiers.drain(&mut self.bank));
 
-            if !ask_yes_no("Back to the Main Menu (Y/N): ") {
+            if !ask_yes_no(tr!("main.back")) {
                 break;
             }
         }
     }
 
-    fn menu_register_account(&mut self) {
-        println!("\nRegister Account Name\n");
-        println!("Register Account Name");
-        let name = read_string_prompt("Account Name: ");
-        let _ = self.bank.create_account(&name);
+    /// The main menu entries the current role may use; a read-only bank
+    /// leaves out the ones that change it.
+    fn menu_entries(&self) -> Vec<&'static MenuEntry> {
+        MAIN_MENU.iter().filter(|e| self.role.allows(e.role) && !(e.mutates && self.bank.is_read_only())).collect()
     }
 
-    fn menu_deposit(&mut self) {
-        println!("\nDeposit Amount\n");
-        let name = read_string_prompt("Account Name: ");
-        let currency_code = self.bank.base_currency.code.clone();
-    if let Some(acct) = self.bank.find_account_mut(&name) {
-            println!("Current Balance: {:.2}", acct.get_balance());
-            println!("Currency: {}", currency_code);
-            let amount = read_f64_prompt("Deposit Amount: ");
-            acct.create_transaction(TransactionType::Deposit, amount);
-            println!("Updated Balance: {:.2}", acct.get_balance());
-        } else {
-            println!("Account not found. Please register first.");
+    /// Offer the current account names and aliases and currency codes to
+    /// Tab completion.
+    fn refresh_completions(&self) {
+        let mut words: Vec<String> = self
+            .bank
+            .accounts
+            .iter()
+            .filter(|a| self.visible(a.id))
+            .flat_map(|a| std::iter::once(&a.name).chain(&a.aliases).cloned())
+            .collect();
+        words.extend(self.bank.forex.currencies_detailed().into_iter().map(|c| c.code));
+        words.sort();
+        words.dedup();
+        line_editor::set_completions(words);
+    }
+
+    /// Write the bank to `data_file` if it changed since the last save. A
+    /// failed write is reported and retried after the next operation. A
+    /// read-only bank is never written.
+    fn autosave(&mut self) {
+        if self.bank.is_read_only() {
+            return;
+        }
+        let encoded = persist::encode(&self.bank);
+        if encoded == self.saved {
+            return;
+        }
+        match std::fs::write(&self.data_file, &encoded) {
+            Ok(()) => self.saved = encoded,
+            Err(e) => println!("{}", tr!("session.save_failed", self.data_file, e)),
         }
     }
 
-    fn menu_withdraw(&mut self) {
-        println!("\nWithdraw Amount\n");
-        let name = read_string_prompt("Account Name: ");
-        let currency_code = self.bank.base_currency.code.clone();
-    if let Some(acct) = self.bank.find_account_mut(&name) {
-            println!("Current Balance: {:.2}", acct.get_balance());
-            println!("Currency: {}", currency_code);
-            let amount = read_f64_prompt("Withdraw Amount: ");
+    /// Record the operation just made in the journal as the `command` that
+    /// repeats it, with each option given a value.
+    fn journal(&mut self, command: &str, options: &[(&str, Option<&str>)]) {
+        let words: Vec<String> = std::iter::once(command.to_string())
+            .chain(options.iter().filter_map(|(key, value)| value.map(|v| [format!("--{}", key), v.to_string()])).flatten())
+            .collect();
+        self.journal_words(&words);
+    }
 
-            if amount > acct.get_balance() {
-                println!("Insufficient balance for withdrawal.");
+    /// Record the command `words`, which made the operation just done, in
+    /// the journal (see `with_admin_passphrase`).
+    fn journal_words(&mut self, words: &[String]) {
+        self.journaled = true;
+        let Some(journal) = &self.journal else {
+            return;
+        };
+        if let Err(e) = journal.record("console", &self.with_admin_passphrase(words.to_vec())) {
+            println!("{}", tr!("session.journal_failed", e));
+        }
+    }
+
+    /// `words` with the session's admin passphrase added if they are one of
+    /// `ADMIN_COMMANDS` given without one, so an admin's commands run and
+    /// replay; unchanged in a teller session.
+    fn with_admin_passphrase(&self, mut words: Vec<String>) -> Vec<String> {
+        if let (Some(verb), Some(passphrase)) = (words.first(), &self.admin_passphrase)
+            && ADMIN_COMMANDS.contains(&verb.as_str())
+            && !words.iter().any(|w| w == "--passphrase")
+        {
+            words.extend([String::from("--passphrase"), passphrase.clone()]);
+        }
+        words
+    }
+
+    /// Note in the journal that the menu entry `label` changed the bank
+    /// without recording a command, so replaying the journal will not
+    /// repeat that change.
+    fn note_unjournaled(&self, label: &str) {
+        let Some(journal) = &self.journal else {
+            return;
+        };
+        let entry = label.strip_prefix("menu.").unwrap_or(label);
+        if let Err(e) = journal.note("console", &format!("{} changed the bank with no command form; replay will not repeat it", entry)) {
+            println!("{}", tr!("session.journal_failed", e));
+        }
+    }
+
+    /// Tie the session to a customer, or leave it a staff session (an empty
+    /// answer). Ends any session already open; both ends are recorded as
+    /// bank events, so the event log shows whose session each operation ran
+    /// in.
+    fn menu_log_in(&mut self) {
+        self.log_out();
+        if self.bank.customers.is_empty() {
+            return;
+        }
+        loop {
+            let answer = read_string_prompt(tr!("session.log_in"));
+            if answer.is_empty() {
                 return;
             }
-            acct.create_transaction(TransactionType::Withdraw, amount);
-            println!("Updated Balance: {:.2}", acct.get_balance());
-        } else {
-            println!("Account not found. Please register first.");
+            let Ok(id) = answer.parse::<usize>() else {
+                println!("{}", tr!("customers.not_found"));
+                continue;
+            };
+            let pin = self.bank.is_customer_protected(id).then(|| read_masked_prompt(tr!("session.pin")));
+            match self.bank.log_in(id, pin.as_deref()) {
+                Ok(()) => {
+                    self.customer = Some(id);
+                    if let Some(customer) = self.bank.find_customer(id) {
+                        println!("{}", tr!("session.welcome", customer.name));
+                    }
+                    return;
+                }
+                Err(BankError::Account(_)) => println!("{}", tr!("pin.incorrect")),
+                Err(_) => println!("{}", tr!("customers.not_found")),
+            }
         }
     }
 
-    fn menu_record_exchange_rate(&mut self) {
-        println!("\nRecord Exchange Rate");
-        let (codes, names) = currency_menu_lists(&self.bank);
-        print_currency_menu(&names);
-        let sel = read_usize_prompt("Select Foreign Currency: ");
-        if self.bank.forex.get_base_rate() == codes.get(sel.saturating_sub(1)).cloned().unwrap_or_default()  {
-            println!("Cannot update the base currency exchange rate.");
+    fn log_out(&mut self) {
+        if let Some(id) = self.customer.take() {
+            let _ = self.bank.log_out(id);
+        }
+    }
+
+    /// True if the bank may be changed; otherwise says it is read-only.
+    /// Guards the changes menus make without a fallible bank operation.
+    fn writable(&self) -> bool {
+        if self.bank.is_read_only() {
+            println!("{}", tr!("session.read_only_refused"));
+        }
+        !self.bank.is_read_only()
+    }
+
+    /// Whether the session may see the account with this ID: any account in
+    /// a staff session, only the customer's own once one is logged in.
+    /// Archived accounts are hidden from everyone.
+    fn visible(&self, account_id: usize) -> bool {
+        if self.bank.archived_accounts().any(|a| a.id == account_id) {
+            return false;
+        }
+        match self.customer.and_then(|id| self.bank.find_customer(id)) {
+            Some(customer) => customer.owns(account_id),
+            None => true,
+        }
+    }
+
+    /// Read an account name, or an alias, which reads as the account's
+    /// name. In a customer session an empty answer picks the customer's
+    /// first account, shown in the prompt, and another customer's account
+    /// reads as an empty name, so it is reported as not found like any
+    /// unknown one. A name that finds nothing offers the closest match.
+    fn read_account_name(&self, prompt: &str) -> String {
+        let default = self.customer.and_then(|id| self.bank.customer_accounts(id).first().map(|a| a.name.clone()));
+        let name = match &default {
+            Some(default) => {
+                let label = prompt.trim_end().trim_end_matches(':');
+                let name = read_string_prompt(&format!("{} [{}]: ", label, default));
+                if name.is_empty() { default.clone() } else { name }
+            }
+            None => read_string_prompt(prompt),
+        };
+        match self.bank.find_account(&name) {
+            Some(acct) if !self.visible(acct.id) => String::new(),
+            Some(acct) => acct.name.clone(),
+            None => self.suggest_account(&name, true).unwrap_or(name),
+        }
+    }
+
+    /// Read the name or alias of any active account, e.g. a transfer's
+    /// destination, as the account's name, offering the closest match
+    /// when it finds nothing.
+    fn read_any_account_name(&self, prompt: &str) -> String {
+        let name = read_string_prompt(prompt);
+        match self.bank.find_account(&name) {
+            Some(acct) if acct.is_archived() => String::new(),
+            Some(acct) => acct.name.clone(),
+            None => self.suggest_account(&name, false).unwrap_or(name),
+        }
+    }
+
+    /// Ask "Did you mean 'Alice'?" about the account closest to `name`,
+    /// among those the session can see when `visible_only`. Returns the
+    /// account's name if the answer is yes.
+    fn suggest_account(&self, name: &str, visible_only: bool) -> Option<String> {
+        let (acct, _) = self
+            .bank
+            .search_accounts(name)
+            .into_iter()
+            .find(|(a, _)| !visible_only || self.visible(a.id))?;
+        ask_yes_no(&tr!("accounts.suggest", acct.name)).then(|| acct.name.clone())
+    }
+
+    /// Select the operator role. Entering the admin role requires the bank's
+    /// admin passphrase; a failed attempt falls back to teller.
+    fn menu_switch_role(&mut self) {
+        println!("\n{}", tr!("role.select"));
+        println!("[1] {}", tr!("role.teller"));
+        println!("[2] {}", tr!("role.admin"));
+        self.admin_passphrase = match read_usize_prompt("") {
+            2 => {
+                let passphrase = read_masked_prompt(tr!("role.passphrase"));
+                if self.bank.verify_admin(&passphrase) {
+                    Some(passphrase)
+                } else {
+                    println!("{}", tr!("role.bad_passphrase"));
+                    None
+                }
+            }
+            _ => None,
+        };
+        self.role = if self.admin_passphrase.is_some() { Role::Admin } else { Role::Teller };
+    }
+
+    fn menu_set_interest_rate(&mut self) {
+        println!("\n{}\n", tr!("menu.set_interest"));
+        println!("{}", tr!("interest.current", format!("{:.2}", self.bank.annual_interest * Decimal::from(100))));
+        let percent = read_signed_decimal_prompt(tr!("interest.new"));
+        match self.bank.change_annual_interest(percent / Decimal::from(100)) {
+            Ok(()) => {
+                println!("{}", tr!("interest.set", format!("{:.2}", percent)));
+                if percent < Decimal::ZERO {
+                    println!("{}", tr!("interest.negative", format!("{:.2}", -percent)));
+                }
+            }
+            Err(e) => println!("{}", tr!("interest.failed", e)),
+        }
+    }
+
+    /// Change the days a year counts in daily interest, for one account or,
+    /// with no account given, for the whole bank.
+    fn menu_year_basis(&mut self) {
+        println!("\n{}\n", tr!("menu.year_basis"));
+        println!("{}", tr!("year_basis.current", self.bank.year_basis.label()));
+        let name = read_string_prompt(tr!("year_basis.account"));
+        let name = match name.trim() {
+            "" => None,
+            name => match self.bank.find_account(name) {
+                Some(acct) => {
+                    println!("{}", tr!("year_basis.account_current", acct.name, acct.year_basis.label()));
+                    Some(acct.name.clone())
+                }
+                None => {
+                    println!("{}", tr!("err.account_not_found"));
+                    return;
+                }
+            },
+        };
+        let options = [YearBasis::Fixed365, YearBasis::Actual, YearBasis::Fixed360];
+        println!("[1] {}", tr!("year_basis.fixed365"));
+        println!("[2] {}", tr!("year_basis.actual"));
+        println!("[3] {}", tr!("year_basis.fixed360"));
+        let Some(&basis) = read_usize_prompt("").checked_sub(1).and_then(|i| options.get(i)) else {
+            println!("{}", tr!("err.invalid_option"));
+            return;
+        };
+        let result = match &name {
+            Some(name) => self.bank.change_account_year_basis(name, basis).map(|_| ()),
+            None => self.bank.change_year_basis(basis),
+        };
+        match result {
+            Ok(()) => {
+                self.journal("year-basis", &[("basis", Some(basis.name())), ("account", name.as_deref())]);
+                match &name {
+                    Some(name) => println!("{}", tr!("year_basis.set", name, basis.label())),
+                    None => println!("{}", tr!("year_basis.set_all", basis.label())),
+                }
+            }
+            Err(e) => println!("{}", tr!("year_basis.failed", e)),
+        }
+    }
+
+    /// Change how daily interest is accrued, for one account or, with no
+    /// account given, for the whole bank.
+    fn menu_interest_method(&mut self) {
+        println!("\n{}\n", tr!("menu.interest_method"));
+        println!("{}", tr!("interest_method.current", self.bank.interest_method.label()));
+        let name = read_string_prompt(tr!("year_basis.account"));
+        let name = match name.trim() {
+            "" => None,
+            name => match self.bank.find_account(name) {
+                Some(acct) => {
+                    println!("{}", tr!("interest_method.account_current", acct.name, acct.interest_method.label()));
+                    Some(acct.name.clone())
+                }
+                None => {
+                    println!("{}", tr!("err.account_not_found"));
+                    return;
+                }
+            },
+        };
+        let options = [InterestMethod::DailyBalance, InterestMethod::AverageDailyBalance, InterestMethod::MinimumMonthlyBalance];
+        println!("[1] {}", tr!("interest_method.daily"));
+        println!("[2] {}", tr!("interest_method.average"));
+        println!("[3] {}", tr!("interest_method.minimum"));
+        let Some(&method) = read_usize_prompt("").checked_sub(1).and_then(|i| options.get(i)) else {
+            println!("{}", tr!("err.invalid_option"));
             return;
+        };
+        let result = match &name {
+            Some(name) => self.bank.change_account_interest_method(name, method).map(|_| ()),
+            None => self.bank.change_interest_method(method),
+        };
+        match result {
+            Ok(()) => {
+                self.journal("interest-method", &[("method", Some(method.name())), ("account", name.as_deref())]);
+                match &name {
+                    Some(name) => println!("{}", tr!("interest_method.set", name, method.label())),
+                    None => println!("{}", tr!("interest_method.set_all", method.label())),
+                }
+            }
+            Err(e) => println!("{}", tr!("interest_method.failed", e)),
         }
+    }
 
-    if let Some(code) = codes.get(sel.saturating_sub(1)).cloned() {
-            let new_rate = read_f64_prompt("Exchange Rate: ");
+    /// Change how many days deposits wait before earning interest, for one
+    /// account or, with no account given, for the whole bank.
+    fn menu_interest_grace(&mut self) {
+        println!("\n{}\n", tr!("menu.interest_grace"));
+        println!("{}", tr!("interest_grace.current", self.bank.interest_grace_days));
+        let name = read_string_prompt(tr!("year_basis.account"));
+        let name = match name.trim() {
+            "" => None,
+            name => match self.bank.find_account(name) {
+                Some(acct) => {
+                    println!("{}", tr!("interest_grace.account_current", acct.name, acct.interest_grace_days));
+                    Some(acct.name.clone())
+                }
+                None => {
+                    println!("{}", tr!("err.account_not_found"));
+                    return;
+                }
+            },
+        };
+        let Ok(days) = read_string_prompt(tr!("interest_grace.days")).trim().parse::<u32>() else {
+            println!("{}", tr!("interest_grace.bad_days"));
+            return;
+        };
+        let result = match &name {
+            Some(name) => self.bank.change_account_interest_grace(name, days).map(|_| ()),
+            None => self.bank.change_interest_grace(days),
+        };
+        match result {
+            Ok(()) => {
+                self.journal("interest-grace", &[("days", Some(&days.to_string())), ("account", name.as_deref())]);
+                match &name {
+                    Some(name) => println!("{}", tr!("interest_grace.set", name, days)),
+                    None => println!("{}", tr!("interest_grace.set_all", days)),
+                }
+            }
+            Err(e) => println!("{}", tr!("interest_grace.failed", e)),
+        }
+    }
 
-            let before = self.bank.forex.get_rate(&code).copied();
-            self.bank.forex.set_rate(&code, new_rate);
-            let after = self.bank.forex.get_rate(&code).copied();
-            match (before, after) {
-                (Some(old), Some(curr)) if (old - curr).abs() < f64::EPSILON => {
-                    println!("Note: Exchange rate for {} was not updated by set_rate.", code);
+    /// Start a promotion on one account from today, or end its current one
+    /// when the bonus entered is zero.
+    fn menu_promotion(&mut self) {
+        println!("\n{}\n", tr!("menu.promotion"));
+        let name = self.read_account_name(tr!("prompt.account_name"));
+        let Some(acct) = self.bank.accounts.iter().find(|a| a.name == name) else {
+            println!("{}", tr!("err.account_not_found"));
+            return;
+        };
+        let hundred = Decimal::from(100);
+        let today = self.bank.today();
+        println!("{}", tr!("show_interest.rate", format!("{:.2}", acct.rate_on(today) * hundred)));
+        if let Some(p) = acct.promotion.filter(|p| p.end > today) {
+            println!("{}", tr!("promotion.current", format!("{:.2}", p.bonus * hundred), p.end.add_days(-1)));
+        }
+        let bonus = read_signed_decimal_prompt(tr!("promotion.bonus"));
+        if bonus == Decimal::ZERO {
+            if let Ok(Some(_)) = self.bank.end_promotion(&name) {
+                self.journal("end-promotion", &[("account", Some(&name))]);
+                if let Some(acct) = self.bank.accounts.iter().find(|a| a.name == name) {
+                    println!("{}", tr!("promotion.ended", name, format!("{:.2}", acct.rate_on(today) * hundred)));
                 }
-                _ => println!("Recorded exchange rate for {}.", code),
             }
+            return;
+        }
+        let days = read_usize_prompt(tr!("promotion.days"));
+        match self.bank.start_promotion(&name, bonus / hundred, today, days) {
+            Ok(_) => {
+                self.journal(
+                    "promotion",
+                    &[("account", Some(&name)), ("bonus", Some(&(bonus / hundred).to_string())), ("days", Some(&days.to_string())), ("start", Some(&today.to_string()))],
+                );
+                let Some(acct) = self.bank.accounts.iter().find(|a| a.name == name) else { return };
+                let Some(p) = acct.promotion else { return };
+                let after = acct.standard_rate_on(p.end);
+                println!(
+                    "{}",
+                    tr!("promotion.set", name, format!("{:.2}", acct.rate_on(today) * hundred), p.end.add_days(-1), format!("{:.2}", after * hundred))
+                );
+            }
+            Err(e) => println!("{}", tr!("promotion.failed", e)),
+        }
+    }
+
+    /// Set one account's monthly service charge and maintaining balance;
+    /// zero or a blank answer turns a fee off.
+    fn menu_service_charges(&mut self) {
+        println!("\n{}\n", tr!("menu.service_charges"));
+        let name = self.read_account_name(tr!("prompt.account_name"));
+        let Some(acct) = self.bank.accounts.iter().find(|a| a.name == name) else {
+            println!("{}", tr!("err.account_not_found"));
+            return;
+        };
+        let money = |amount: Decimal| self.bank.format_money(&Money::new(amount, &acct.currency));
+        let current = acct.service_charges;
+        if let Some(charge) = current.monthly {
+            println!("{}", tr!("charges.current_monthly", money(charge)));
+        }
+        if let Some(m) = current.maintaining {
+            println!("{}", tr!("charges.current_maintaining", money(m.charge), money(m.balance)));
+        }
+        let monthly = read_optional_amount_prompt(tr!("charges.monthly")).filter(|c| *c > Decimal::ZERO);
+        let maintaining = read_optional_amount_prompt(tr!("charges.maintaining"))
+            .filter(|b| *b > Decimal::ZERO)
+            .map(|balance| MaintainingBalance { balance, charge: read_decimal_prompt(tr!("charges.maintaining_charge")) });
+        match self.bank.set_service_charges(&name, monthly, maintaining) {
+            Ok(_) => {
+                let monthly_arg = monthly.map_or_else(|| String::from("none"), |c| c.to_string());
+                let maintaining_arg = maintaining.map_or_else(|| String::from("none"), |m| format!("{}:{}", m.balance, m.charge));
+                self.journal("service-charge", &[("account", Some(&name)), ("monthly", Some(&monthly_arg)), ("maintaining", Some(&maintaining_arg))]);
+                println!("{}", tr!("charges.set", name));
+            }
+            Err(e) => println!("{}", tr!("charges.failed", e)),
+        }
+    }
+
+    /// List the limit profiles, then give an account a type or set the
+    /// caps for an account type or KYC status; blank caps remove them.
+    fn menu_limit_profiles(&mut self) {
+        println!("\n{}\n", tr!("menu.limit_profiles"));
+        let base = self.bank.base_currency.code.clone();
+        let cap = |cap: Option<Decimal>| cap.map_or(String::from("-"), |cap| self.bank.format_money(&Money::new(cap, &base)));
+        if self.bank.compliance.limit_profiles.is_empty() {
+            println!("{}", tr!("limits.none"));
         } else {
-            println!("Invalid selection.");
+            let mut table = Table::new(&[
+                (tr!("col.target"), Align::Left),
+                (tr!("col.max_deposit"), Align::Right),
+                (tr!("col.max_withdrawal"), Align::Right),
+                (tr!("col.max_conversion"), Align::Right),
+            ]);
+            for (target, p) in &self.bank.compliance.limit_profiles {
+                table.row([target.to_string(), cap(p.max_deposit), cap(p.max_withdrawal), cap(p.max_daily_conversion)]);
+            }
+            println!("{}", table);
+        }
+        println!("[1] {}", tr!("limits.set_type"));
+        println!("[2] {}", tr!("limits.set_profile"));
+        println!("[3] {}", tr!("holds.back"));
+        match read_usize_prompt("") {
+            1 => {
+                let name = self.read_account_name(tr!("prompt.account_name"));
+                let Some(acct) = self.bank.find_account(&name) else {
+                    println!("{}", tr!("err.account_not_found"));
+                    return;
+                };
+                println!("{}", tr!("limits.current_type", acct.account_type.as_deref().unwrap_or("-")));
+                let kind = read_string_prompt(tr!("limits.type"));
+                let kind = Some(kind.trim()).filter(|k| !k.is_empty());
+                let passphrase = read_masked_prompt(tr!("role.passphrase"));
+                match self.bank.set_account_type(&name, kind, &passphrase) {
+                    Ok(acct) => {
+                        let kind = acct.account_type.clone();
+                        self.journal("account-type", &[("account", Some(&name)), ("type", Some(kind.as_deref().unwrap_or("none"))), ("passphrase", Some(&passphrase))]);
+                        println!("{}", tr!("limits.type_set", name, kind.as_deref().unwrap_or("-")));
+                    }
+                    Err(e) => println!("{}", tr!("limits.failed", e)),
+                }
+            }
+            2 => {
+                let raw = read_string_prompt(tr!("limits.target"));
+                let Some(target) = LimitTarget::parse(&raw) else {
+                    println!("{}", tr!("limits.bad_target", raw));
+                    return;
+                };
+                let profile = LimitProfile {
+                    max_deposit: read_optional_amount_prompt(tr!("limits.max_deposit")),
+                    max_withdrawal: read_optional_amount_prompt(tr!("limits.max_withdrawal")),
+                    max_daily_conversion: read_optional_amount_prompt(tr!("limits.max_conversion")),
+                };
+                let passphrase = read_masked_prompt(tr!("role.passphrase"));
+                match self.bank.set_limit_profile(target.clone(), profile, &passphrase) {
+                    Ok(()) => {
+                        let arg = |cap: Option<Decimal>| cap.map_or_else(|| String::from("none"), |cap| cap.to_string());
+                        self.journal(
+                            "limit-profile",
+                            &[
+                                ("target", Some(&target.to_string())),
+                                ("max-deposit", Some(&arg(profile.max_deposit))),
+                                ("max-withdrawal", Some(&arg(profile.max_withdrawal))),
+                                ("max-daily-conversion", Some(&arg(profile.max_daily_conversion))),
+                                ("passphrase", Some(&passphrase)),
+                            ],
+                        );
+                        println!("{}", tr!("limits.set", target));
+                    }
+                    Err(e) => println!("{}", tr!("limits.failed", e)),
+                }
+            }
+            _ => {}
         }
     }
 
-    fn menu_currency_exchange(&mut self) {
-        loop {
-            println!("\nForeign Currency Exchange");
+    fn menu_register_account(&mut self) {
+        println!("\n{}\n", tr!("menu.register"));
+        println!("{}", tr!("menu.register"));
+        let name = read_string_prompt(tr!("prompt.account_name"));
+        let mut currency = None;
+        let acct = if ask_yes_no(tr!("accounts.foreign")) {
             let (codes, names) = currency_menu_lists(&self.bank);
-            println!("Source Currency Option:");
             print_currency_menu(&names);
-            let src_sel = read_usize_prompt("Source Currency: ");
-            if let Some(src) = codes.get(src_sel.saturating_sub(1)).cloned() {
-                let amount = read_f64_prompt("Source Amount: ");
-                println!("Exchanged Currency Options:");
-                print_currency_menu(&names);
-                let dst_sel = read_usize_prompt("Exchange Currency: ");
-                if let Some(dst) = codes.get(dst_sel.saturating_sub(1)).cloned() {
-                    match convert_amount(&self.bank, &src, &dst, amount) {
-                        Some(out) => println!("Exchange Amount: {:.2}", out),
-                        None => println!("Cannot convert due to missing rates."),
+            let code = read_currency_prompt(tr!("accounts.currency"), &codes, &names);
+            match self.bank.create_account_in(&name, &code) {
+                Ok(acct) => {
+                    currency = Some(code);
+                    acct
+                }
+                Err(e) => {
+                    println!("{}", tr!("accounts.failed", e));
+                    return;
+                }
+            }
+        } else {
+            match self.bank.create_account(&name) {
+                Ok(acct) => acct,
+                Err(e) => {
+                    println!("{}", tr!("accounts.failed", e));
+                    return;
+                }
+            }
+        };
+        let account_id = acct.id;
+        let mut protected = None;
+        if ask_yes_no(tr!("pin.protect")) {
+            loop {
+                let pin = read_masked_prompt(tr!("pin.prompt"));
+                if pin.is_empty() {
+                    println!("{}", tr!("pin.empty"));
+                    continue;
+                }
+                if read_masked_prompt(tr!("pin.confirm")) == pin {
+                    acct.set_pin(&pin);
+                    println!("{}", tr!("pin.set"));
+                    protected = Some(pin);
+                    break;
+                }
+                println!("{}", tr!("pin.mismatch"));
+            }
+        }
+        self.journal("register", &[("account", Some(&name)), ("currency", currency.as_deref()), ("pin", protected.as_deref())]);
+        if let Some(customer) = self.customer.and_then(|id| self.bank.customers.iter_mut().find(|c| c.id == id)) {
+            customer.account_ids.push(account_id);
+        }
+    }
+
+    /// Prompt for the account's PIN when it is protected. Returns `None` when
+    /// verification fails, otherwise the PIN to pass to the bank (if any).
+    fn authorize(&self, name: &str) -> Option<Option<String>> {
+        let protected = self
+            .bank
+            .accounts
+            .iter()
+            .any(|a| a.name == name && a.is_protected());
+        if !protected {
+            return Some(None);
+        }
+        let pin = read_masked_prompt(tr!("pin.prompt"));
+        if self.bank.verify_account(name, Some(&pin)) {
+            Some(Some(pin))
+        } else {
+            println!("{}", tr!("pin.incorrect"));
+            None
+        }
+    }
+
+    fn menu_list_accounts(&mut self) {
+        println!("\n{}\n", tr!("accounts.title"));
+        let mut table = Table::new(&[
+            (tr!("col.id"), Align::Right),
+            (tr!("col.name"), Align::Left),
+            (tr!("col.balance"), Align::Right),
+            (tr!("col.currency"), Align::Left),
+            (tr!("col.status"), Align::Left),
+        ]);
+        let accounts: Vec<_> = self.bank.accounts.iter().filter(|a| self.visible(a.id)).collect();
+        for acct in &accounts {
+            let status = if acct.is_protected() { tr!("status.protected") } else { tr!("status.unprotected") };
+            table.row([
+                acct.id.to_string(),
+                acct.name.clone(),
+                self.bank.format_money(&acct.get_balance()),
+                acct.currency.clone(),
+                status.to_string(),
+            ]);
+        }
+        println!("{}", table);
+        println!("{}", tr!("accounts.count", accounts.len()));
+    }
+
+    fn menu_deposit(&mut self) {
+        println!("\n{}\n", tr!("menu.deposit"));
+        let name = self.read_account_name(tr!("prompt.account_name"));
+        let Some(balance) = self.bank.find_account(&name).map(|acct| acct.get_balance()) else {
+            println!("{}", tr!("err.account_not_found"));
+            return;
+        };
+        println!("{}", tr!("balance.current", self.bank.format_money(&balance)));
+        let currency_code = balance.currency;
+        let Some(pin) = self.authorize(&name) else {
+            return;
+        };
+        let amount = Money::new(read_decimal_prompt(tr!("deposit.prompt")), &currency_code);
+        if !self.confirm_large_transaction(&amount) {
+            println!("{}", tr!("deposit.cancelled"));
+            return;
+        }
+        let memo = read_string_prompt(tr!("prompt.memo"));
+        match self.bank.post_transaction(&name, TransactionType::Deposit, amount.clone(), &memo, pin.as_deref()) {
+            Ok(balance) => {
+                let memo = Some(memo.as_str()).filter(|m| !m.is_empty());
+                self.journal("deposit", &[("account", Some(&name)), ("amount", Some(&amount.amount.to_string())), ("memo", memo), ("pin", pin.as_deref())]);
+                self.record_transaction(&name, TransactionType::Deposit, amount);
+                println!("{}", tr!("balance.updated", self.bank.format_money(&balance)));
+            }
+            Err(e) => println!("{}", tr!("deposit.failed", e)),
+        }
+    }
+
+    fn menu_withdraw(&mut self) {
+        println!("\n{}\n", tr!("menu.withdraw"));
+        let name = self.read_account_name(tr!("prompt.account_name"));
+        let Some(balance) = self.bank.find_account(&name).map(|acct| acct.get_balance()) else {
+            println!("{}", tr!("err.account_not_found"));
+            return;
+        };
+        println!("{}", tr!("balance.current", self.bank.format_money(&balance)));
+        self.print_available(&name);
+        let currency_code = balance.currency.clone();
+        let Some(pin) = self.authorize(&name) else {
+            return;
+        };
+        let amount = Money::new(read_decimal_prompt(tr!("withdraw.prompt")), &currency_code);
+        let mut summary = vec![
+            tr!("summary.account", name),
+            tr!("summary.withdraw", self.bank.format_money(&amount)),
+        ];
+        if let Ok(after) = balance.checked_sub(&amount) {
+            summary.push(tr!("summary.balance_after", self.bank.format_money(&after)));
+        }
+        if !self.confirm_with_summary(&amount, &summary) {
+            println!("{}", tr!("withdraw.cancelled"));
+            return;
+        }
+        let memo = read_string_prompt(tr!("prompt.memo"));
+        let categories: Vec<String> = self
+            .bank
+            .accounts
+            .iter()
+            .find(|a| a.name == name)
+            .map(|a| a.envelopes.iter().map(|e| e.category.clone()).collect())
+            .unwrap_or_default();
+        let category = if categories.is_empty() { String::new() } else { read_string_prompt(&tr!("budget.choose", categories.join(", "))) };
+        let result = if category.is_empty() {
+            self.bank.post_transaction(&name, TransactionType::Withdraw, amount.clone(), &memo, pin.as_deref()).map(|balance| (balance, None))
+        } else {
+            self.bank.spend(&name, amount.clone(), &category, &memo, pin.as_deref()).map(|(balance, status)| (balance, Some(status)))
+        };
+        if matches!(result, Ok(_) | Err(BankError::AwaitingApproval(_))) {
+            let (memo, category) = (Some(memo.as_str()).filter(|m| !m.is_empty()), Some(category.as_str()).filter(|c| !c.is_empty()));
+            self.journal(
+                "withdraw",
+                &[("account", Some(&name)), ("amount", Some(&amount.amount.to_string())), ("memo", memo), ("category", category), ("pin", pin.as_deref())],
+            );
+        }
+        match result {
+            Ok((balance, status)) => {
+                self.record_transaction(&name, TransactionType::Withdraw, amount);
+                println!("{}", tr!("balance.updated", self.bank.format_money(&balance)));
+                match status {
+                    Some(s) if s.is_overspent() => println!("{}", tr!("budget.warning", s.category, self.bank.format_money(&s.overspent))),
+                    Some(s) => println!("{}", tr!("budget.left", self.bank.format_money(&s.remaining), s.category)),
+                    None => {}
+                }
+            }
+            Err(BankError::AwaitingApproval(id)) => println!("{}", tr!("approval.held", id)),
+            Err(e) => println!("{}", tr!("withdraw.failed", e)),
+        }
+    }
+
+    /// List one account's authorization holds, then place a hold, release
+    /// one, or capture one as a withdrawal.
+    fn menu_holds(&mut self) {
+        println!("\n{}\n", tr!("menu.holds"));
+        let name = self.read_account_name(tr!("prompt.account_name"));
+        let today = self.bank.today();
+        let Some(acct) = self.bank.accounts.iter().find(|a| a.name == name) else {
+            println!("{}", tr!("err.account_not_found"));
+            return;
+        };
+        let currency = acct.currency.clone();
+        let holds: Vec<Hold> = acct.active_holds(today).cloned().collect();
+        if holds.is_empty() {
+            println!("{}", tr!("holds.none", name));
+        } else {
+            let mut table = Table::new(&[
+                (tr!("col.id"), Align::Right),
+                (tr!("col.date"), Align::Left),
+                (tr!("col.expires"), Align::Left),
+                (tr!("col.amount"), Align::Right),
+                (tr!("col.memo"), Align::Left),
+            ]);
+            for h in &holds {
+                table.row([h.id.to_string(), h.placed.to_string(), h.expiry.to_string(), self.bank.format_money(&h.amount), h.reason.clone()]);
+            }
+            println!("{}", table);
+        }
+        println!("{}", tr!("balance.current", self.bank.format_money(&acct.get_balance())));
+        self.print_available(&name);
+        println!("[1] {}", tr!("holds.place"));
+        println!("[2] {}", tr!("holds.release"));
+        println!("[3] {}", tr!("holds.capture"));
+        println!("[4] {}", tr!("holds.back"));
+        let choice = read_usize_prompt("");
+        if !(1..=3).contains(&choice) {
+            return;
+        }
+        let Some(pin) = self.authorize(&name) else {
+            return;
+        };
+        match choice {
+            1 => {
+                let amount = read_decimal_prompt(tr!("holds.amount"));
+                let reason = read_string_prompt(tr!("holds.reason"));
+                let raw = read_string_prompt(&tr!("holds.expires", DEFAULT_HOLD_DAYS));
+                let expiry = match raw.is_empty() {
+                    true => None,
+                    false => match Date::parse(&raw) {
+                        Some(date) => Some(date),
+                        None => {
+                            println!("{}", tr!("goal.bad_date"));
+                            return;
+                        }
+                    },
+                };
+                match self.bank.place_hold(&name, Money::new(amount, &currency), &reason, expiry, pin.as_deref()) {
+                    Ok(hold) => {
+                        let expiry = hold.expiry.to_string();
+                        self.journal(
+                            "hold",
+                            &[("account", Some(&name)), ("amount", Some(&amount.to_string())), ("reason", Some(&hold.reason)), ("expires", Some(&expiry)), ("pin", pin.as_deref())],
+                        );
+                        println!("{}", tr!("holds.placed", hold.id, self.bank.format_money(&hold.amount), hold.expiry));
+                        self.print_available(&name);
                     }
-                } else {
-                    println!("Invalid selection.");
+                    Err(e) => println!("{}", tr!("holds.failed", e)),
+                }
+            }
+            2 => {
+                let id = read_usize_prompt(tr!("holds.id"));
+                match self.bank.release_hold(&name, id, pin.as_deref()) {
+                    Ok(hold) => {
+                        self.journal("release", &[("account", Some(&name)), ("id", Some(&id.to_string())), ("pin", pin.as_deref())]);
+                        println!("{}", tr!("holds.released", hold.id, self.bank.format_money(&hold.amount)));
+                    }
+                    Err(e) => println!("{}", tr!("holds.failed", e)),
+                }
+            }
+            _ => {
+                let id = read_usize_prompt(tr!("holds.id"));
+                let amount = read_optional_amount_prompt(tr!("holds.capture_amount")).filter(|a| *a > Decimal::ZERO);
+                match self.bank.capture_hold(&name, id, amount, pin.as_deref()) {
+                    Ok((hold, balance)) => {
+                        let captured = Money::new(amount.unwrap_or(hold.amount.amount), &currency);
+                        self.journal(
+                            "capture",
+                            &[("account", Some(&name)), ("id", Some(&id.to_string())), ("amount", amount.map(|a| a.to_string()).as_deref()), ("pin", pin.as_deref())],
+                        );
+                        self.record_transaction(&name, TransactionType::Withdraw, captured.clone());
+                        println!("{}", tr!("holds.captured", self.bank.format_money(&captured), hold.id));
+                        println!("{}", tr!("balance.updated", self.bank.format_money(&balance)));
+                    }
+                    Err(e) => println!("{}", tr!("holds.failed", e)),
                 }
-            } else {
-                println!("Invalid selection.");
             }
+        }
+    }
 
-            if !ask_yes_no("Convert another currency (Y/N)? ") {
-                break;
+    /// List one account's cheques, then deposit a cheque or return one that
+    /// bounced before clearing.
+    fn menu_cheques(&mut self) {
+        println!("\n{}\n", tr!("menu.cheques"));
+        let name = self.read_account_name(tr!("prompt.account_name"));
+        let today = self.bank.today();
+        let Some(acct) = self.bank.accounts.iter().find(|a| a.name == name) else {
+            println!("{}", tr!("err.account_not_found"));
+            return;
+        };
+        let currency = acct.currency.clone();
+        if acct.cheques.is_empty() {
+            println!("{}", tr!("cheques.none", name));
+        } else {
+            let mut table = Table::new(&[
+                (tr!("col.id"), Align::Right),
+                (tr!("col.number"), Align::Left),
+                (tr!("col.date"), Align::Left),
+                (tr!("col.clears"), Align::Left),
+                (tr!("col.amount"), Align::Right),
+                (tr!("col.status"), Align::Left),
+            ]);
+            for c in &acct.cheques {
+                table.row([c.id.to_string(), c.number.clone(), c.deposited.to_string(), c.clears.to_string(), self.bank.format_money(&c.amount), c.status(today).to_string()]);
+            }
+            println!("{}", table);
+        }
+        println!("{}", tr!("balance.current", self.bank.format_money(&acct.get_balance())));
+        self.print_available(&name);
+        println!("[1] {}", tr!("cheques.deposit"));
+        println!("[2] {}", tr!("cheques.return"));
+        println!("[3] {}", tr!("holds.back"));
+        match read_usize_prompt("") {
+            1 => {
+                let Some(pin) = self.authorize(&name) else {
+                    return;
+                };
+                let number = read_string_prompt(tr!("cheques.number"));
+                let amount = read_decimal_prompt(tr!("cheques.amount"));
+                match self.bank.deposit_cheque(&name, Money::new(amount, &currency), &number, pin.as_deref()) {
+                    Ok(cheque) => {
+                        self.journal(
+                            "deposit-cheque",
+                            &[("account", Some(&name)), ("amount", Some(&amount.to_string())), ("number", Some(&cheque.number)), ("pin", pin.as_deref())],
+                        );
+                        println!("{}", tr!("cheques.deposited", cheque.number, self.bank.format_money(&cheque.amount), cheque.clears));
+                        self.print_available(&name);
+                    }
+                    Err(e) => println!("{}", tr!("cheques.failed", e)),
+                }
+            }
+            2 => {
+                let id = read_usize_prompt(tr!("cheques.id"));
+                let reason = read_string_prompt(tr!("cheques.reason"));
+                match self.bank.return_cheque(&name, id, &reason) {
+                    Ok((cheque, balance)) => {
+                        self.journal("return-cheque", &[("account", Some(&name)), ("id", Some(&id.to_string())), ("reason", Some(reason.trim()))]);
+                        println!("{}", tr!("cheques.returned", cheque.number, self.bank.format_money(&cheque.amount)));
+                        println!("{}", tr!("balance.updated", self.bank.format_money(&balance)));
+                    }
+                    Err(e) => println!("{}", tr!("cheques.failed", e)),
+                }
             }
+            _ => {}
         }
     }
 
-    fn menu_show_interest(&mut self) {
-        println!("\nShow Interest Amount\n");
-        let name = read_string_prompt("Account Name: ");
-        let currency_code = self.bank.base_currency.code.clone();
-        let interest_rate = self.bank.annual_interest;
-    if let Some(acct) = self.bank.find_account_mut(&name) {
-            println!("Current Balance: {:.2}", acct.get_balance());
-            println!("Currency: {}", currency_code);
-            println!("Interest Rate: {:.0}%", interest_rate * 100.0);
-            let days = read_usize_prompt("Total Number of Days: ");
+    /// Print the named account's available balance when holds, uncleared
+    /// cheques, or pending withdrawals or transfers keep part of its
+    /// balance back.
+    fn print_available(&self, name: &str) {
+        let Some(balance) = self.bank.find_account(name).map(|a| a.get_balance()) else {
+            return;
+        };
+        if let Some(available) = self.bank.available_balance(name).ok().filter(|a| *a != balance) {
+            println!("{}", tr!("balance.available", self.bank.format_money(&available)));
+        }
+    }
 
-            if days < 1 || days > 999999 {
-                println!("Please enter a valid number of days between 1 and 999999.");
-                return;
+    fn menu_transfer(&mut self) {
+        println!("\n{}\n", tr!("menu.transfer"));
+        let from = self.read_account_name(tr!("transfer.source"));
+        let Some(src_currency) = self.bank.accounts.iter().find(|a| a.name == from).map(|a| a.currency.clone()) else {
+            println!("{}", tr!("err.account_not_found"));
+            return;
+        };
+        self.print_available(&from);
+        let to = self.read_any_account_name(tr!("transfer.destination"));
+        if !self.bank.accounts.iter().any(|a| a.name == to) {
+            println!("{}", tr!("err.account_not_found"));
+            return;
+        }
+        let Some(pin) = self.authorize(&from) else {
+            return;
+        };
+        let amount = read_decimal_prompt(tr!("prompt.amount"));
+        let code = read_string_prompt(&tr!("transfer.currency", src_currency)).to_uppercase();
+        let code = if code.is_empty() { src_currency } else { code };
+        if self.bank.forex.get_rate(&code).is_none() {
+            println!("{}", tr!("err.unknown_currency", code));
+            return;
+        }
+        let amount = Money::new(amount, &code);
+        let summary = [
+            tr!("summary.from", from),
+            tr!("summary.to", to),
+            tr!("summary.amount", self.bank.format_money(&amount)),
+        ];
+        if !self.confirm_with_summary(&amount, &summary) {
+            println!("{}", tr!("transfer.cancelled"));
+            return;
+        }
+        match self.with_limit_override(|bank| bank.transfer(&from, &to, amount.clone(), pin.as_deref())) {
+            Ok((receipt, passphrase)) => {
+                self.journal(
+                    "transfer",
+                    &[
+                        ("from", Some(&from)),
+                        ("to", Some(&to)),
+                        ("amount", Some(&amount.amount.to_string())),
+                        ("currency", Some(&amount.currency)),
+                        ("pin", pin.as_deref()),
+                        ("override-limits", passphrase.as_deref()),
+                    ],
+                );
+                println!("\n{}", tr!("transfer.receipt"));
+                println!("{}", tr!("transfer.from", receipt.from, self.bank.format_money(&receipt.debited)));
+                println!("{}", tr!("transfer.to", receipt.to, self.bank.format_money(&receipt.credited)));
+                println!("{}", tr!("transfer.rate", receipt.debited.currency, receipt.rate, receipt.credited.currency));
+                self.print_legs(&receipt.legs);
+                if !receipt.fee.amount.is_zero() {
+                    println!("{}", tr!("transfer.fee", self.bank.format_money(&receipt.fee)));
+                }
+                self.offer_receipt(receipt.receipt);
             }
+            Err(BankError::AwaitingApproval(id)) => {
+                self.journal(
+                    "transfer",
+                    &[("from", Some(&from)), ("to", Some(&to)), ("amount", Some(&amount.amount.to_string())), ("currency", Some(&amount.currency)), ("pin", pin.as_deref())],
+                );
+                println!("{}", tr!("approval.held", id));
+            }
+            Err(e) => println!("{}", tr!("transfer.failed", e)),
+        }
+    }
 
-            let forecast = acct.get_interest_forecast(days);
-            println!("Day \t| Interest \t| Balance |");
-            for f in forecast {
-                println!("{} \t| {:.2} \t\t| {:.2} |", f.day, f.interest, f.balance);
+    fn menu_standing_orders(&mut self) {
+        println!("\n{}\n", tr!("menu.standing_orders"));
+        println!("[1] {}", tr!("order.create"));
+        println!("[2] {}", tr!("order.list"));
+        println!("[3] {}", tr!("order.skip"));
+        println!("[4] {}", tr!("order.cancel"));
+        match read_usize_prompt("") {
+            1 => {
+                let from = self.read_account_name(tr!("transfer.source"));
+                let Some(src_currency) = self.bank.accounts.iter().find(|a| a.name == from).map(|a| a.currency.clone()) else {
+                    println!("{}", tr!("err.account_not_found"));
+                    return;
+                };
+                let to = self.read_any_account_name(tr!("transfer.destination"));
+                let Some(pin) = self.authorize(&from) else {
+                    return;
+                };
+                let amount = Money::new(read_decimal_prompt(tr!("prompt.amount")), &src_currency);
+                let every = read_usize_prompt(&tr!("order.every", MAX_INTERVAL_DAYS));
+                let start = read_string_prompt(tr!("order.start"));
+                let first = if start.is_empty() { Some(self.bank.today()) } else { Date::parse(&start) };
+                let Some(first) = first else {
+                    println!("{}", tr!("goal.bad_date"));
+                    return;
+                };
+                let every = u32::try_from(every).unwrap_or(u32::MAX);
+                match self.bank.create_standing_order(&from, &to, amount, every, first, pin.as_deref()) {
+                    Ok(order) => println!("{}", tr!("order.created", order.id, order.next)),
+                    Err(e) => println!("{}", tr!("order.failed", e)),
+                }
             }
-        } else {
-            println!("Account not found. Please register first.");
+            2 => {
+                if self.bank.standing_orders.is_empty() {
+                    println!("{}", tr!("order.none"));
+                    return;
+                }
+                let mut table = Table::new(&[
+                    (tr!("col.id"), Align::Right),
+                    (tr!("col.from"), Align::Left),
+                    (tr!("col.to"), Align::Left),
+                    (tr!("col.amount"), Align::Right),
+                    (tr!("col.every"), Align::Right),
+                    (tr!("col.next"), Align::Left),
+                ]);
+                for order in &self.bank.standing_orders {
+                    table.row([
+                        order.id.to_string(),
+                        order.from.clone(),
+                        order.to.clone(),
+                        self.bank.format_money(&order.amount),
+                        tr!("order.days", order.interval_days),
+                        order.due_date(&self.bank.calendar).to_string(),
+                    ]);
+                }
+                print_paged(&table.to_string(), 2);
+            }
+            3 => {
+                let id = read_usize_prompt(tr!("order.id"));
+                match self.bank.skip_standing_order(id) {
+                    Ok(next) => println!("{}", tr!("order.skipped", id, next)),
+                    Err(e) => println!("{}", tr!("order.failed", e)),
+                }
+            }
+            4 => {
+                let id = read_usize_prompt(tr!("order.id"));
+                match self.bank.cancel_standing_order(id) {
+                    Ok(_) => println!("{}", tr!("order.cancelled", id)),
+                    Err(e) => println!("{}", tr!("order.failed", e)),
+                }
+            }
+            _ => println!("{}", tr!("err.invalid_option")),
         }
     }
-}
 
+    fn menu_forwards(&mut self) {
+        println!("\n{}\n", tr!("menu.forwards"));
+        println!("[1] {}", tr!("forward.book"));
+        println!("[2] {}", tr!("forward.list"));
+        match read_usize_prompt("") {
+            1 => {
+                let name = self.read_account_name(tr!("prompt.account_name"));
+                let Some(account_currency) = self.bank.accounts.iter().find(|a| a.name == name).map(|a| a.currency.clone()) else {
+                    println!("{}", tr!("err.account_not_found"));
+                    return;
+                };
+                let Some(pin) = self.authorize(&name) else {
+                    return;
+                };
+                let side = loop {
+                    match ForwardSide::parse(&read_string_prompt(tr!("forward.side"))) {
+                        Some(side) => break side,
+                        None => println!("{}", tr!("err.invalid_option")),
+                    }
+                };
+                let (codes, names) = currency_menu_lists(&self.bank);
+                print_currency_menu(&names);
+                let code = read_currency_prompt(tr!("forward.currency"), &codes, &names);
+                let amount = Money::new(read_decimal_prompt(tr!("prompt.amount")), &code);
+                let rate = read_decimal_prompt(&tr!("forward.rate", account_currency, code));
+                let Some(value_date) = Date::parse(&read_string_prompt(tr!("forward.value_date"))) else {
+                    println!("{}", tr!("goal.bad_date"));
+                    return;
+                };
+                match self.bank.book_forward(&name, side, amount, rate, value_date, pin.as_deref()) {
+                    Ok(f) => println!("{}", tr!("forward.booked", f.id, f.value_date)),
+                    Err(e) => println!("{}", tr!("forward.failed", e)),
+                }
+            }
+            2 => {
+                let valuations = match self.bank.forward_valuations() {
+                    Ok(valuations) => valuations,
+                    Err(e) => {
+                        println!("{}", tr!("forward.value_failed", e));
+                        return;
+                    }
+                };
+                if valuations.is_empty() {
+                    println!("{}", tr!("forward.none"));
+                    return;
+                }
+                let mut table = Table::new(&[
+                    (tr!("col.id"), Align::Right),
+                    (tr!("col.account"), Align::Left),
+                    (tr!("col.side"), Align::Left),
+                    (tr!("col.amount"), Align::Right),
+                    (tr!("col.rate"), Align::Right),
+                    (tr!("col.value_date"), Align::Left),
+                    (tr!("col.spot"), Align::Right),
+                    (tr!("col.mtm"), Align::Right),
+                ]);
+                for v in &valuations {
+                    let f = &v.contract;
+                    table.row([
+                        f.id.to_string(),
+                        f.account.clone(),
+                        f.side.name().to_string(),
+                        self.bank.format_money(&f.amount),
+                        f.rate.to_string(),
+                        f.value_date.to_string(),
+                        v.spot.to_string(),
+                        self.bank.format_money(&v.mark_to_market),
+                    ]);
+                }
+                print_paged(&table.to_string(), 2);
+            }
+            _ => println!("{}", tr!("err.invalid_option")),
+        }
+    }
+
+    /// Place, list, amend, or cancel limit orders. Orders are filled, and
+    /// expired ones dropped, when a new rate is recorded.
+    fn menu_limit_orders(&mut self) {
+        println!("\n{}\n", tr!("menu.limit_orders"));
+        println!("[1] {}", tr!("limit.place"));
+        println!("[2] {}", tr!("limit.list"));
+        println!("[3] {}", tr!("limit.cancel"));
+        println!("[4] {}", tr!("limit.amend"));
+        match read_usize_prompt("") {
+            1 => {
+                let name = self.read_account_name(tr!("prompt.account_name"));
+                let Some(account_currency) = self.bank.accounts.iter().find(|a| a.name == name).map(|a| a.currency.clone()) else {
+                    println!("{}", tr!("err.account_not_found"));
+                    return;
+                };
+                let Some(pin) = self.authorize(&name) else {
+                    return;
+                };
+                let (codes, names) = currency_menu_lists(&self.bank);
+                print_currency_menu(&names);
+                let from = read_currency_prompt(tr!("exchange.source"), &codes, &names);
+                let to = read_currency_prompt(tr!("exchange.target"), &codes, &names);
+                let amount = Money::new(read_decimal_prompt(tr!("exchange.amount")), &from);
+                let foreign = if to == account_currency { &from } else { &to };
+                let limit = read_decimal_prompt(&tr!("limit.rate", account_currency, foreign));
+                let Some(expires) = read_expiry_prompt() else {
+                    return;
+                };
+                let Some(average) = read_average_prompt() else {
+                    return;
+                };
+                let placed = self.bank.place_limit_order(&name, amount, &to, limit, expires, pin.as_deref()).map(|o| o.id);
+                match placed.and_then(|id| self.bank.confirm_limit_order(id, average)) {
+                    Ok(o) => println!("{}", tr!("limit.placed", o.id, o.limit)),
+                    Err(e) => println!("{}", tr!("limit.failed", e)),
+                }
+            }
+            2 => {
+                let orders = self.bank.open_limit_orders();
+                if orders.is_empty() {
+                    println!("{}", tr!("limit.none"));
+                    return;
+                }
+                let mut table = Table::new(&[
+                    (tr!("col.id"), Align::Right),
+                    (tr!("col.account"), Align::Left),
+                    (tr!("col.amount"), Align::Right),
+                    (tr!("col.to"), Align::Left),
+                    (tr!("col.filled"), Align::Right),
+                    (tr!("col.limit"), Align::Right),
+                    (tr!("col.average"), Align::Left),
+                    (tr!("col.spot"), Align::Right),
+                    (tr!("col.expires"), Align::Left),
+                ]);
+                for (o, spot) in &orders {
+                    table.row([
+                        o.id.to_string(),
+                        o.account.clone(),
+                        self.bank.format_money(&o.amount),
+                        o.to.clone(),
+                        self.bank.format_money(&Money::new(o.filled_amount, &o.amount.currency)),
+                        o.limit.to_string(),
+                        o.average.map_or_else(|| String::from("-"), |a| a.to_string()),
+                        spot.to_string(),
+                        o.expires.map_or_else(|| tr!("limit.gtc").to_string(), |d| d.to_string()),
+                    ]);
+                }
+                print_paged(&table.to_string(), 2);
+            }
+            3 => {
+                let id = read_usize_prompt(tr!("limit.id"));
+                match self.bank.cancel_limit_order(id) {
+                    Ok(_) => println!("{}", tr!("limit.cancelled", id)),
+                    Err(e) => println!("{}", tr!("limit.failed", e)),
+                }
+            }
+            4 => {
+                let id = read_usize_prompt(tr!("limit.id"));
+                let Some(amount) = self.bank.limit_orders.iter().find(|o| o.id == id && !o.filled).map(|o| o.amount.amount) else {
+                    println!("{}", tr!("limit.failed", BankError::LimitOrderNotFound(id)));
+                    return;
+                };
+                let limit = read_decimal_prompt(tr!("limit.new_rate"));
+                let Some(expires) = read_expiry_prompt() else {
+                    return;
+                };
+                let Some(average) = read_average_prompt() else {
+                    return;
+                };
+                let amended = self.bank.amend_limit_order(id, amount, limit, expires).map(|o| o.id);
+                match amended.and_then(|id| self.bank.confirm_limit_order(id, average)) {
+                    Ok(o) => println!("{}", tr!("limit.amended", o.id, o.limit)),
+                    Err(e) => println!("{}", tr!("limit.failed", e)),
+                }
+            }
+            _ => println!("{}", tr!("err.invalid_option")),
+        }
+    }
+
+    /// Cost basis and realized and unrealized FX gains of every
+    /// foreign-currency account, at today's rates.
+    fn menu_fx_pnl(&mut self) {
+        println!("\n{}\n", tr!("menu.pnl"));
+        self.print_positions();
+    }
+
+    /// The FX profit and loss of every foreign-currency account.
+    fn print_positions(&self) {
+        let reports = match self.bank.position_reports() {
+            Ok(reports) => reports,
+            Err(e) => {
+                println!("{}", tr!("pnl.failed", e));
+                return;
+            }
+        };
+        if reports.is_empty() {
+            println!("{}", tr!("pnl.none"));
+            return;
+        }
+        let mut table = Table::new(&[
+            (tr!("col.account"), Align::Left),
+            (tr!("col.balance"), Align::Right),
+            (tr!("col.spot"), Align::Right),
+            (tr!("col.value"), Align::Right),
+            (tr!("col.cost"), Align::Right),
+            (tr!("col.unrealized"), Align::Right),
+            (tr!("col.realized"), Align::Right),
+        ]);
+        for r in &reports {
+            table.row([
+                r.account.clone(),
+                self.bank.format_money(&r.balance),
+                r.spot.to_string(),
+                self.bank.format_money(&r.market_value),
+                self.bank.format_money(&r.cost_basis),
+                self.bank.format_money(&r.unrealized),
+                self.bank.format_money(&r.realized),
+            ]);
+        }
+        println!("{}", tr!("pnl.base", self.bank.base_currency.code));
+        print_paged(&table.to_string(), 2);
+    }
+
+    /// Value one account's holdings in the base currency on a chosen day.
+    fn menu_portfolio(&mut self) {
+        println!("\n{}\n", tr!("menu.portfolio"));
+        let name = self.read_account_name(tr!("prompt.account_name"));
+        let raw = read_string_prompt(tr!("portfolio.date"));
+        let as_of = if raw.is_empty() {
+            self.bank.today()
+        } else {
+            let Some(date) = Date::parse(&raw) else {
+                println!("{}", tr!("goal.bad_date"));
+                return;
+            };
+            date
+        };
+        let portfolio = match self.bank.portfolio_value(&name, as_of) {
+            Ok(portfolio) => portfolio,
+            Err(e) => {
+                println!("{}", tr!("portfolio.failed", e));
+                return;
+            }
+        };
+        let mut table = Table::new(&[
+            (tr!("col.asset"), Align::Left),
+            (tr!("col.amount"), Align::Right),
+            (tr!("col.rate"), Align::Right),
+            (tr!("col.value"), Align::Right),
+        ]);
+        for h in &portfolio.holdings {
+            let asset = match h.asset {
+                Asset::Cash => tr!("portfolio.cash").to_string(),
+                Asset::Forward(id) => tr!("portfolio.forward", id),
+            };
+            table.row([asset, self.bank.format_money(&h.amount), h.rate.to_string(), self.bank.format_money(&h.value)]);
+        }
+        table.row([tr!("portfolio.total").to_string(), String::new(), String::new(), self.bank.format_money(&portfolio.total)]);
+        println!("{}", tr!("portfolio.title", portfolio.account, portfolio.as_of, self.bank.base_currency.code));
+        print_paged(&table.to_string(), 2);
+    }
+
+    fn menu_dca(&mut self) {
+        println!("\n{}\n", tr!("menu.dca"));
+        println!("{}", tr!("dca.intro"));
+        let (codes, names) = currency_menu_lists(&self.bank);
+        print_currency_menu(&names);
+        let from = read_currency_prompt(tr!("dca.spend"), &codes, &names);
+        let to = read_currency_prompt(tr!("dca.buy"), &codes, &names);
+        let amount = Money::new(read_decimal_prompt(&tr!("dca.amount", from.clone())), &from);
+        let rates: Vec<Decimal> = loop {
+            let raw = read_string_prompt(&tr!("dca.rates", from.clone(), to.clone()));
+            let parsed: Option<Vec<Decimal>> = raw.split(',').map(|r| r.trim().parse().ok().filter(|v| *v > Decimal::ZERO)).collect();
+            match parsed {
+                Some(rates) => break rates,
+                None => println!("{}", tr!("dca.bad_rates")),
+            }
+        };
+        let sim = match DcaSimulation::run(&amount, &to, &rates) {
+            Ok(sim) => sim,
+            Err(e) => {
+                println!("{}", tr!("dca.failed", e));
+                return;
+            }
+        };
+        let mut table = Table::new(&[
+            (tr!("col.period"), Align::Right),
+            (tr!("col.rate"), Align::Right),
+            (tr!("col.spent"), Align::Right),
+            (tr!("col.bought"), Align::Right),
+        ]);
+        for p in &sim.purchases {
+            table.row([p.period.to_string(), p.rate.to_string(), self.bank.format_money(&p.spent), self.bank.format_money(&p.units)]);
+        }
+        print_paged(&table.to_string(), 2);
+        let fmt = |m: &Money| self.bank.format_money(m);
+        println!("{}", tr!("dca.invested", fmt(&sim.invested), sim.purchases.len()));
+        println!("{}", tr!("dca.periodic", fmt(&sim.units), sim.average_cost.round_dp(6), fmt(&sim.final_value), sim.final_rate));
+        println!("{}", tr!("dca.lump_sum", fmt(&sim.lump_sum_units), rates[0], fmt(&sim.lump_sum_value)));
+        let advantage = sim.advantage();
+        let gap = fmt(&Money::new(advantage.amount.abs(), &advantage.currency));
+        if advantage.amount < Decimal::ZERO {
+            println!("{}", tr!("dca.trail", gap));
+        } else {
+            println!("{}", tr!("dca.beat", gap));
+        }
+    }
+
+    /// Report the limit orders a rate change reached.
+    fn print_fills(&self, fills: &[LimitOrderFill]) {
+        for fill in fills {
+            match &fill.result {
+                Ok(posted) if fill.order.filled => println!("{}", tr!("limit.filled", fill.order.id, fill.order.account, fill.spot, self.bank.format_money(posted))),
+                Ok(posted) => println!(
+                    "{}",
+                    tr!("limit.partly_filled", fill.order.id, fill.order.account, fill.spot, self.bank.format_money(posted), self.bank.format_money(&fill.order.remaining()))
+                ),
+                Err(e) => println!("{}", tr!("limit.fill_failed", fill.order.id, e)),
+            }
+        }
+    }
+
+    /// Settle the forwards, make the standing-order transfers due today,
+    /// and run the sweeps.
+    fn menu_end_of_day(&mut self) {
+        println!("\n{}\n", tr!("menu.end_of_day"));
+        let eod = self.bank.end_of_day(self.bank.today());
+        self.journal("eod", &[("date", Some(&eod.date.to_string()))]);
+        if eod.carried.is_empty() && eod.forwards.is_empty() && eod.standing_orders.is_empty() && eod.sweeps.is_empty() && eod.service_charges.is_empty() && eod.statements == 0 {
+            println!("{}", tr!("eod.nothing_due", eod.date));
+        }
+        self.print_end_of_day(eod);
+    }
+
+    /// Report the forwards settled, standing orders run, sweeps made,
+    /// service charges taken, and month-end statements issued by an end of
+    /// day.
+    fn print_end_of_day(&self, eod: EndOfDay) {
+        for (code, rate) in &eod.carried {
+            let rate = self.bank.forex.currency(code).map_or_else(|| rate.to_string(), |c| c.format_rate(*rate));
+            println!("{}", tr!("eod.carried", eod.date, code, rate));
+        }
+        for s in eod.forwards {
+            match s.result {
+                Ok(posted) => println!("{}", tr!("eod.settled", s.contract.id, s.contract.account, s.contract.side.name(), self.bank.format_money(&posted))),
+                Err(e) => println!("{}", tr!("eod.settle_failed", s.contract.id, e)),
+            }
+        }
+        for run in eod.standing_orders {
+            match run.result {
+                Ok(r) => println!("{}", tr!("order.ran", run.date, run.order, self.bank.format_money(&r.debited), r.from, r.to)),
+                Err(e) => println!("{}", tr!("order.run_failed", run.date, run.order, e)),
+            }
+        }
+        for run in eod.sweeps {
+            match run.result {
+                Ok(r) => println!("{}", tr!("eod.swept", eod.date, run.rule, self.bank.format_money(&r.debited), r.from, r.to)),
+                Err(e) => println!("{}", tr!("eod.sweep_failed", eod.date, run.rule, e)),
+            }
+        }
+        for run in eod.service_charges {
+            let what = run.memo.to_lowercase();
+            match run.result {
+                Ok(charged) => println!("{}", tr!("eod.charged", eod.date, what, run.account, self.bank.format_money(&charged), self.bank.format_money(&run.average))),
+                Err(e) => println!("{}", tr!("eod.charge_failed", eod.date, what, run.account, e)),
+            }
+        }
+        if eod.statements > 0 {
+            println!("{}", tr!("eod.statements", eod.date, eod.statements));
+        }
+    }
+
+    /// Move every rate as a random walk for a number of days, then show the
+    /// rates day by day and the FX profit and loss they leave.
+    fn menu_simulate_market(&mut self) {
+        println!("\n{}\n", tr!("menu.simulate"));
+        println!("{}", tr!("sim.intro"));
+        let days = read_usize_prompt(tr!("sim.days"));
+        let drift = read_percent(tr!("sim.drift"), Decimal::ZERO, true);
+        let volatility = read_percent(tr!("sim.volatility"), Decimal::from(10), false);
+        let seed = self.read_seed();
+        let mut simulator = MarketSimulator::new(seed).with_default(RateModel::new(drift, volatility));
+        let steps = match simulator.run(&mut self.bank, days) {
+            Ok(steps) => {
+                self.journal(
+                    "simulate",
+                    &[
+                        ("days", Some(&days.to_string())),
+                        ("seed", Some(&seed.to_string())),
+                        ("drift", Some(&drift.to_string())),
+                        ("volatility", Some(&volatility.to_string())),
+                    ],
+                );
+                steps
+            }
+            Err(e) => {
+                println!("{}", tr!("sim.failed", e));
+                return;
+            }
+        };
+        let codes: Vec<&str> = steps.first().map_or(Vec::new(), |s| s.moves.iter().map(|m| m.code.as_str()).collect());
+        let columns: Vec<(&str, Align)> = std::iter::once((tr!("col.date"), Align::Left)).chain(codes.iter().map(|c| (*c, Align::Right))).collect();
+        let mut table = Table::new(&columns);
+        for step in &steps {
+            table.row(std::iter::once(step.date.to_string()).chain(step.moves.iter().map(|m| m.new.to_string())));
+        }
+        println!("{}", tr!("sim.done", steps.len(), seed));
+        print_paged(&table.to_string(), 2);
+        for step in steps {
+            self.print_fills(&step.fills);
+            self.print_end_of_day(step.end_of_day);
+        }
+        println!();
+        self.print_positions();
+    }
+
+    /// Remember the transaction just posted to `account` so it can be undone.
+    fn record_transaction(&mut self, account: &str, tx_type: TransactionType, amount: Money) {
+        let Some(count) = self.bank.accounts.iter().find(|a| a.name == account).map(|a| a.transactions.len()) else {
+            return;
+        };
+        self.push_undo(UndoEntry::Transaction { account: account.to_string(), index: count - 1, tx_type, amount });
+    }
+
+    /// Push onto the undo stack, dropping the oldest entry past `UNDO_LIMIT`.
+    fn push_undo(&mut self, entry: UndoEntry) {
+        if self.undo.len() == UNDO_LIMIT {
+            self.undo.remove(0);
+        }
+        self.undo.push(entry);
+    }
+
+    /// Reverse the most recent undoable operation: deposits and withdrawals
+    /// get an offsetting entry, rate changes get the previous rate back.
+    /// Reverting a rate requires the admin role, and the admin passphrase
+    /// is asked for again.
+    fn menu_undo(&mut self) {
+        println!("\n{}\n", tr!("menu.undo"));
+        let Some(entry) = self.undo.last() else {
+            println!("{}", tr!("undo.empty"));
+            return;
+        };
+        let description = match entry {
+            UndoEntry::Transaction { account, tx_type, amount, .. } => match tx_type {
+                TransactionType::Deposit => tr!("undo.deposit", self.bank.format_money(amount), account),
+                TransactionType::Withdraw => tr!("undo.withdraw", self.bank.format_money(amount), account),
+            },
+            UndoEntry::Rate(previous) => {
+                if !self.role.allows(Role::Admin) {
+                    println!("{}", tr!("undo.needs_admin"));
+                    return;
+                }
+                tr!("undo.rate", previous.code, previous.rate)
+            }
+        };
+        println!("{}", description);
+        if !ask_yes_no(tr!("undo.ask")) {
+            return;
+        }
+        let Some(entry) = self.undo.pop() else {
+            return;
+        };
+        let result = match &entry {
+            UndoEntry::Transaction { account, index, .. } => self.bank.reverse_transaction(account, *index).map(|balance| {
+                println!("{}", tr!("balance.updated", self.bank.format_money(&balance)));
+            }),
+            UndoEntry::Rate(previous) => {
+                let passphrase = read_masked_prompt(tr!("role.passphrase"));
+                self.bank.as_admin(&passphrase, |bank| bank.forex.revert_rate(previous).map_err(BankError::from))
+            }
+        };
+        match result {
+            Ok(()) => println!("{}", tr!("undo.done")),
+            Err(e) => {
+                println!("{}", tr!("undo.failed", e));
+                self.undo.push(entry);
+            }
+        }
+    }
+
+    /// Pick a configured macro, ask for each of its parameters, and run its
+    /// commands in order as the command line would, printing each result.
+    /// Stops at the first command that fails; the ones before it stand.
+    /// Macros are not offered in a customer session, since their commands
+    /// can name any account.
+    fn menu_run_macro(&mut self) {
+        println!("\n{}\n", tr!("menu.macros"));
+        if self.customer.is_some() {
+            println!("{}", tr!("macro.customer"));
+            return;
+        }
+        if self.macros.is_empty() {
+            println!("{}", tr!("macro.none"));
+            return;
+        }
+        let mut table = Table::new(&[
+            (tr!("col.id"), Align::Right),
+            (tr!("col.name"), Align::Left),
+            (tr!("col.params"), Align::Left),
+            (tr!("col.description"), Align::Left),
+        ]);
+        for (i, m) in self.macros.iter().enumerate() {
+            table.row([(i + 1).to_string(), m.name.clone(), m.params.join(", "), m.description.clone()]);
+        }
+        println!("{}", table);
+        let Some(definition) = self.macros.get(read_usize_prompt(tr!("macro.select")) - 1).cloned() else {
+            println!("{}", tr!("macro.invalid"));
+            return;
+        };
+        let args: Vec<String> = definition.params.iter().map(|param| read_string_prompt(&tr!("macro.param", param))).collect();
+        let commands = match cli::expand_macro(&definition, &args) {
+            Ok(commands) => commands,
+            Err(e) => {
+                println!("{}", tr!("macro.failed", 1, definition.commands.len(), e));
+                return;
+            }
+        };
+        for (i, words) in commands.iter().enumerate() {
+            println!("\n> {}", script_line(words).unwrap_or_else(|| words.join(" ")));
+            match cli::parse_words(&self.with_admin_passphrase(words.clone())).and_then(|command| Ok((cli::execute(&mut self.bank, &command)?, command))) {
+                Ok((output, command)) => {
+                    println!("{}", output.to_text(&self.bank));
+                    if command.mutates() {
+                        self.journal_words(&cli::pin_defaults(words.clone(), &output));
+                    }
+                }
+                Err(e) => {
+                    println!("{}", tr!("macro.failed", i + 1, commands.len(), e));
+                    return;
+                }
+            }
+        }
+        println!("\n{}", tr!("macro.done", commands.len()));
+    }
+
+    /// `amount` in the base currency, for comparing against thresholds.
+    fn base_amount(&self, amount: &Money) -> Decimal {
+        self.bank
+            .forex
+            .convert(amount, &self.bank.base_currency.code)
+            .map_or(amount.amount, |m| m.amount)
+    }
+
+    /// Ask for confirmation when the amount is flagged as large and the
+    /// bank's compliance settings require it. Returns true to proceed.
+    fn confirm_large_transaction(&self, amount: &Money) -> bool {
+        let compliance = &self.bank.compliance;
+        if !compliance.is_large(self.base_amount(amount)) || !compliance.require_confirmation {
+            return true;
+        }
+        println!("{}", tr!("large.warning"));
+        ask_yes_no(tr!("prompt.proceed"))
+    }
+
+    /// For withdrawals and transfers: above the bank's confirmation
+    /// threshold, print `summary` and require an explicit "yes"; otherwise
+    /// fall back to the large-transaction prompt. Returns true to proceed.
+    fn confirm_with_summary(&self, amount: &Money, summary: &[String]) -> bool {
+        let base_amount = self.base_amount(amount);
+        if !self.bank.compliance.needs_confirmation(base_amount) {
+            return self.confirm_large_transaction(amount);
+        }
+        println!("\n{}", tr!("confirm.summary"));
+        for line in summary {
+            println!("  {}", line);
+        }
+        if self.bank.compliance.is_large(base_amount) {
+            println!("{}", tr!("large.warning"));
+        }
+        confirm_explicit(tr!("confirm.prompt"))
+    }
+
+    /// Run `op`; if a conversion limit refuses it in an admin session, offer
+    /// to run it again with the limits overridden, which takes the admin
+    /// passphrase once more. Returns the passphrase with the result when the
+    /// limits were overridden.
+    fn with_limit_override<T>(&mut self, op: impl Fn(&mut Bank) -> Result<T, BankError>) -> Result<(T, Option<String>), BankError> {
+        match op(&mut self.bank) {
+            Err(BankError::ConversionLimit(breach)) if self.role.allows(Role::Admin) => {
+                if !ask_yes_no(&tr!("override.ask", breach)) {
+                    return Err(BankError::ConversionLimit(breach));
+                }
+                let passphrase = read_masked_prompt(tr!("role.passphrase"));
+                self.bank.override_conversion_limits(&passphrase, op).map(|result| (result, Some(passphrase)))
+            }
+            result => result.map(|result| (result, None)),
+        }
+    }
+
+    fn menu_record_exchange_rate(&mut self) {
+        println!("\n{}", tr!("rate.title"));
+        let (codes, names) = currency_menu_lists(&self.bank);
+        print_currency_menu(&names);
+        let code = read_currency_prompt(tr!("rate.select"), &codes, &names);
+        if self.bank.forex.get_base_rate() == code {
+            println!("{}", tr!("rate.base"));
+            return;
+        }
+
+        let new_rate = read_decimal_prompt(tr!("rate.prompt"));
+        let old_rate = self.bank.forex.get_rate(&code).copied().unwrap_or(Decimal::ZERO);
+        if self.bank.compliance.is_large_rate_change(old_rate, new_rate) {
+            println!("{}", tr!("rate.change", code, old_rate, new_rate, percent_change(old_rate, new_rate)));
+            if !confirm_explicit(tr!("confirm.prompt")) {
+                println!("{}", tr!("rate.cancelled"));
+                return;
+            }
+        }
+
+        let previous = self.bank.forex.currency(&code).cloned();
+        match self.bank.set_rate(&code, new_rate) {
+            Ok(fills) => {
+                self.journal("rate", &[("code", Some(&code)), ("rate", Some(&new_rate.to_string()))]);
+                if let Some(previous) = previous {
+                    self.push_undo(UndoEntry::Rate(previous));
+                }
+                println!("{}", tr!("rate.recorded", code));
+                self.print_fills(&fills);
+            }
+            Err(e) => println!("{}", tr!("rate.failed", e)),
+        }
+    }
+
+    /// Add, rename, or retire catalog currencies, define a basket, or quote
+    /// a currency's cash rate, at runtime (Admin).
+    fn menu_manage_currencies(&mut self) {
+        println!("\n{}\n", tr!("menu.currencies"));
+        println!("[1] {}", tr!("currencies.add"));
+        println!("[2] {}", tr!("currencies.rename"));
+        println!("[3] {}", tr!("currencies.retire"));
+        println!("[4] {}", tr!("currencies.basket"));
+        println!("[5] {}", tr!("currencies.cash_rate"));
+        match read_usize_prompt("") {
+            1 => {
+                let code = read_string_prompt(tr!("currencies.code")).to_uppercase();
+                let name = read_string_prompt(tr!("currencies.name"));
+                // A rate too small to keep at DEFAULT_RATE_DP places would
+                // price the currency at zero.
+                let rate = loop {
+                    let rate = read_decimal_prompt(&tr!("currencies.rate", self.bank.forex.get_base_rate()));
+                    if rate.round_dp(DEFAULT_RATE_DP) > Decimal::ZERO {
+                        break rate;
+                    }
+                    println!("{}", tr!("currencies.bad_rate", DEFAULT_RATE_DP));
+                };
+                match self.bank.forex.add_currency(&code, &name, rate) {
+                    Ok(()) => println!("{}", tr!("currencies.added", name, code)),
+                    Err(e) => println!("{}", tr!("currencies.failed", e)),
+                }
+            }
+            2 => {
+                let (codes, names) = currency_menu_lists(&self.bank);
+                print_currency_menu(&names);
+                let code = read_currency_prompt(tr!("rate.select"), &codes, &names);
+                let name = read_string_prompt(tr!("currencies.name"));
+                match self.bank.rename_currency(&code, &name) {
+                    Ok(()) => println!("{}", tr!("currencies.renamed", code, name)),
+                    Err(e) => println!("{}", tr!("currencies.failed", e)),
+                }
+            }
+            3 => {
+                let (codes, names) = currency_menu_lists(&self.bank);
+                print_currency_menu(&names);
+                let code = read_currency_prompt(tr!("rate.select"), &codes, &names);
+                println!("{}", tr!("currencies.retire_warning", code));
+                if !confirm_explicit(tr!("confirm.prompt")) {
+                    println!("{}", tr!("snap.cancelled"));
+                    return;
+                }
+                match self.bank.retire_currency(&code) {
+                    Ok(_) => println!("{}", tr!("currencies.retired", code)),
+                    Err(e) => println!("{}", tr!("currencies.failed", e)),
+                }
+            }
+            4 => self.define_basket(),
+            5 => self.set_cash_rate(),
+            _ => println!("{}", tr!("err.invalid_option")),
+        }
+    }
+
+    /// Ask for a currency and its cash rate, Enter to quote cash at the
+    /// transfer rate again, then record it.
+    fn set_cash_rate(&mut self) {
+        let (codes, names) = currency_menu_lists(&self.bank);
+        print_currency_menu(&names);
+        let code = read_currency_prompt(tr!("rate.select"), &codes, &names);
+        let rate = loop {
+            let raw = read_string_prompt(&tr!("currencies.cash_prompt", self.bank.forex.get_base_rate()));
+            if raw.trim().is_empty() {
+                break None;
+            }
+            match raw.trim().parse::<Decimal>() {
+                Ok(rate) if rate > Decimal::ZERO => break Some(rate),
+                _ => println!("{}", tr!("currencies.bad_cash_rate")),
+            }
+        };
+        match self.bank.set_cash_rate(&code, rate) {
+            Ok(()) => match rate {
+                Some(rate) => println!("{}", tr!("currencies.cash_set", code, rate)),
+                None => println!("{}", tr!("currencies.cash_cleared", code)),
+            },
+            Err(e) => println!("{}", tr!("currencies.failed", e)),
+        }
+    }
+
+    /// Ask for a basket's code, name, and component weights, then define it.
+    fn define_basket(&mut self) {
+        println!("{}", tr!("currencies.basket_intro", self.bank.forex.get_base_rate()));
+        let code = read_string_prompt(tr!("currencies.code")).to_uppercase();
+        let name = read_string_prompt(tr!("currencies.name"));
+        let (codes, names) = currency_menu_lists(&self.bank);
+        let mut weights = Vec::new();
+        loop {
+            print_currency_menu(&names);
+            let component = read_currency_prompt(tr!("currencies.component"), &codes, &names);
+            let weight = read_decimal_prompt(&tr!("currencies.weight", component));
+            weights.push((component, weight / Decimal::from(100)));
+            let total: Decimal = weights.iter().map(|(_, w)| *w * Decimal::from(100)).sum();
+            println!("{}", tr!("currencies.weights_total", total));
+            if !ask_yes_no(tr!("currencies.another")) {
+                break;
+            }
+        }
+        match self.bank.forex.define_basket(&code, &name, &weights) {
+            Ok(_) => {
+                println!("{}", tr!("currencies.basket_defined", code, name));
+                self.print_baskets();
+            }
+            Err(e) => println!("{}", tr!("currencies.failed", e)),
+        }
+    }
+
+    /// Each basket's components with their quantities and today's weights.
+    fn print_baskets(&self) {
+        let quotes = self.bank.forex.basket_quotes();
+        if quotes.is_empty() {
+            return;
+        }
+        println!("\n{}\n", tr!("rates.baskets"));
+        let mut table = Table::new(&[
+            (tr!("col.basket"), Align::Left),
+            (tr!("col.rate"), Align::Right),
+            (tr!("col.component"), Align::Left),
+            (tr!("col.quantity"), Align::Right),
+            (tr!("col.weight"), Align::Right),
+        ]);
+        for q in quotes {
+            for s in &q.shares {
+                table.row([
+                    q.currency.code.clone(),
+                    q.currency.rate.to_string(),
+                    s.code.clone(),
+                    s.quantity.round_dp(BASKET_RATE_DP).to_string(),
+                    format!("{}%", (s.weight * Decimal::from(100)).round_dp(2)),
+                ]);
+            }
+        }
+        println!("{}", table);
+    }
+
+    fn menu_show_rates(&mut self) {
+        let base = self.bank.forex.get_base_rate().to_string();
+        println!("\n{}\n", tr!("rates.title", base));
+        let mut table = Table::new(&[
+            (tr!("col.code"), Align::Left),
+            (tr!("col.name"), Align::Left),
+            (tr!("col.rate"), Align::Right),
+            (tr!("col.cash_rate"), Align::Right),
+            (tr!("col.volatility"), Align::Right),
+            (tr!("col.source"), Align::Left),
+            (tr!("col.updated"), Align::Left),
+        ]);
+        for c in self.bank.forex.currencies_detailed() {
+            let (rate, cash) = if c.code == base {
+                (tr!("rates.base").to_string(), tr!("rates.base").to_string())
+            } else {
+                (c.format_rate(c.rate), c.format_rate(c.rate_of(RateType::Cash)))
+            };
+            let source = c.source.as_ref().map_or_else(|| tr!("rates.unknown").to_string(), RateSource::to_string);
+            let updated = c.updated.map_or_else(|| tr!("rates.unknown").to_string(), |t| self.bank.format_timestamp(t));
+            let volatility = match self.bank.forex.volatility(&c.code, DEFAULT_WINDOW) {
+                Ok(v) => format!("{:.2}%", v.annualized * Decimal::from(100)),
+                Err(_) => String::from("-"),
+            };
+            table.row([c.code, c.name, rate, cash, volatility, source, updated]);
+        }
+        println!("{}", table);
+        println!("{}", tr!("rates.volatility", DEFAULT_WINDOW));
+        self.print_baskets();
+    }
+
+    /// A currency's last `RATE_HISTORY_SHOWN` closing rates with their
+    /// simple and exponential moving averages.
+    fn menu_rate_history(&mut self) {
+        println!("\n{}\n", tr!("menu.rate_history"));
+        let (codes, names) = currency_menu_lists(&self.bank);
+        print_currency_menu(&names);
+        let code = read_currency_prompt(tr!("rate_history.currency"), &codes, &names);
+        let days = moving_average::DEFAULT_DAYS;
+        let mut closes = match self.bank.forex.averaged_history(&code, days, days) {
+            Ok(closes) => closes,
+            Err(e) => {
+                println!("{}", tr!("rate_history.failed", e));
+                return;
+            }
+        };
+        if closes.is_empty() {
+            println!("{}", tr!("rate_history.none", code));
+            return;
+        }
+        closes.drain(..closes.len().saturating_sub(RATE_HISTORY_SHOWN));
+        let dp = self.bank.forex.rate_decimals(&code) as usize;
+        let rate = |r: Option<Decimal>| r.map_or_else(|| String::from("-"), |r| format!("{:.*}", dp, r));
+        let (sma, ema) = (tr!("col.sma", days), tr!("col.ema", days));
+        let mut table = Table::new(&[(tr!("col.date"), Align::Left), (tr!("col.rate"), Align::Right), (&sma, Align::Right), (&ema, Align::Right)]);
+        for close in &closes {
+            let date = if close.carried { format!("{} *", close.date) } else { close.date.to_string() };
+            table.row([date, rate(Some(close.rate)), rate(close.sma), rate(close.ema)]);
+        }
+        println!("{}", tr!("rate_history.title", code, self.bank.forex.get_base_rate()));
+        print_paged(&table.to_string(), 2);
+        println!("{}", tr!("rate_history.averages", days));
+        if closes.iter().any(|c| c.carried) {
+            println!("{}", tr!("rate_history.carried"));
+        }
+    }
+
+    /// Open, high, low, average, and close of every currency's closing
+    /// rates over a period, by default all of each one's history.
+    fn menu_rate_summary(&mut self) {
+        println!("\n{}\n", tr!("menu.rate_summary"));
+        println!("{}", tr!("rate_summary.hint"));
+        let start = Date::parse(&read_string_prompt(tr!("filter.from")));
+        let end = Date::parse(&read_string_prompt(tr!("filter.to")));
+        let stats = self.bank.forex.rate_summary(start, end);
+        if stats.is_empty() {
+            println!("{}", tr!("rate_summary.none"));
+            return;
+        }
+        let mut table = Table::new(&[
+            (tr!("col.code"), Align::Left),
+            (tr!("col.from"), Align::Left),
+            (tr!("col.until"), Align::Left),
+            (tr!("col.days"), Align::Right),
+            (tr!("col.open"), Align::Right),
+            (tr!("col.high"), Align::Right),
+            (tr!("col.low"), Align::Right),
+            (tr!("col.average_rate"), Align::Right),
+            (tr!("col.close"), Align::Right),
+            (tr!("col.change"), Align::Right),
+        ]);
+        for s in stats {
+            let dp = self.bank.forex.rate_decimals(&s.code) as usize;
+            let rate = |r: Decimal| format!("{:.*}", dp, r);
+            let change = format!("{:.2}%", s.change() * Decimal::from(100));
+            table.row([s.code.clone(), s.start.to_string(), s.end.to_string(), s.days.to_string(), rate(s.open), rate(s.high), rate(s.low), rate(s.average), rate(s.close), change]);
+        }
+        println!("{}", tr!("rate_summary.title", self.bank.forex.get_base_rate()));
+        println!("{}", table);
+    }
+
+    /// Exchange between two of a holder's accounts in different
+    /// currencies, breaking down the cost before booking both legs.
+    fn menu_currency_exchange(&mut self) {
+        loop {
+            println!("\n{}", tr!("exchange.title"));
+            let from = self.read_account_name(tr!("exchange.source_account"));
+            let Some(src_currency) = self.bank.accounts.iter().find(|a| a.name == from).map(|a| a.currency.clone()) else {
+                println!("{}", tr!("err.account_not_found"));
+                return;
+            };
+            let to = self.read_account_name(tr!("exchange.target_account"));
+            let Some(dst_currency) = self.bank.accounts.iter().find(|a| a.name == to).map(|a| a.currency.clone()) else {
+                println!("{}", tr!("err.account_not_found"));
+                return;
+            };
+            let Some(pin) = self.authorize(&from) else {
+                return;
+            };
+            let amount = read_decimal_prompt(&tr!("exchange.amount_in", src_currency));
+            let preview = match self.bank.forex.preview(&src_currency, &dst_currency, amount) {
+                Ok(preview) => preview,
+                Err(e) => {
+                    println!("{}", tr!("exchange.failed", e));
+                    return;
+                }
+            };
+            self.print_preview(&preview);
+            let summary = [
+                tr!("summary.from", from),
+                tr!("summary.to", to),
+                tr!("summary.amount", self.bank.format_money(&preview.source)),
+            ];
+            if !self.confirm_with_summary(&preview.source, &summary) {
+                println!("{}", tr!("exchange.cancelled"));
+                return;
+            }
+            match self.with_limit_override(|bank| bank.exchange(&from, &to, amount, pin.as_deref())) {
+                Ok((receipt, passphrase)) => {
+                    self.journal(
+                        "exchange",
+                        &[("from", Some(&from)), ("to", Some(&to)), ("amount", Some(&amount.to_string())), ("pin", pin.as_deref()), ("override-limits", passphrase.as_deref())],
+                    );
+                    println!("\n{}", tr!("exchange.receipt"));
+                    println!("{}", tr!("transfer.from", receipt.from, self.bank.format_money(&receipt.debited)));
+                    println!("{}", tr!("transfer.to", receipt.to, self.bank.format_money(&receipt.credited)));
+                    println!("{}", tr!("transfer.rate", receipt.debited.currency, receipt.rate.round_dp(EXCHANGE_RATE_DP), receipt.credited.currency));
+                    self.print_legs(&receipt.legs);
+                    println!("{}", tr!("transfer.fee", self.bank.format_money(&receipt.fee)));
+                    self.offer_receipt(receipt.receipt);
+                    self.offer_cash_breakdown(&receipt.credited);
+                }
+                Err(e) => println!("{}", tr!("exchange.failed", e)),
+            }
+
+            if !ask_yes_no(tr!("exchange.again")) {
+                break;
+            }
+        }
+    }
+
+    /// The cost of a conversion before it is confirmed: the mid-market
+    /// rate, the applied rate and its margin when they differ, the fee,
+    /// what is received, and the all-in rate when anything was taken.
+    fn print_preview(&self, preview: &ConversionPreview) {
+        let (from, to) = (&preview.source.currency, &preview.net.currency);
+        let rate = |r: Decimal| r.round_dp(EXCHANGE_RATE_DP);
+        println!("{}", tr!("preview.mid_rate", from, rate(preview.mid_rate), to));
+        self.print_trend(from, to);
+        if preview.applied_rate != preview.mid_rate {
+            println!("{}", tr!("preview.applied_rate", from, rate(preview.applied_rate), to));
+            println!("{}", tr!("preview.margin", self.bank.format_money(&preview.margin)));
+        }
+        if !preview.fee.amount.is_zero() {
+            let gross = Money::new(preview.net.amount + preview.fee.amount, to);
+            println!("{}", tr!("exchange.result", self.bank.format_money(&gross)));
+            let fee_percent = format!("{:.2}", preview.fee_rate * Decimal::from(100));
+            if preview.fee_basis == FeeBasis::PairSpread {
+                let pair = format!("{}/{}", from, to);
+                println!("{}", tr!("exchange.fee_spread", fee_percent, pair, self.bank.format_money(&preview.fee)));
+            } else {
+                println!("{}", tr!("exchange.fee", fee_percent, self.bank.format_money(&preview.fee)));
+            }
+        }
+        println!("{}", tr!("exchange.net", self.bank.format_money(&preview.net)));
+        let cost = preview.total_cost();
+        if !cost.amount.is_zero() {
+            println!("{}", tr!("preview.total_cost", self.bank.format_money(&cost)));
+            println!("{}", tr!("preview.effective_rate", from, rate(preview.effective_rate), to));
+        }
+    }
+
+    /// Whether the rate from `from` to `to` is up or down on the pair's
+    /// last close and its `TREND_DAYS`-day average, where there is the
+    /// history to say.
+    fn print_trend(&self, from: &str, to: &str) {
+        let Ok(trend) = self.bank.forex.trend(from, to) else {
+            return;
+        };
+        if let (Some(change), Some((date, _))) = (trend.vs_previous(), trend.previous) {
+            println!("{}", tr!("preview.trend_previous", cli::trend_arrow(change), date));
+        }
+        if let Some(change) = trend.vs_average() {
+            println!("{}", tr!("preview.trend_average", cli::trend_arrow(change), TREND_DAYS));
+        }
+    }
+
+    /// Each leg of a receipt's conversion when it went through another
+    /// currency, with the amounts in and out and the leg's rate.
+    fn print_legs(&self, legs: &[ConversionLeg]) {
+        if legs.len() < 2 {
+            return;
+        }
+        for (i, leg) in legs.iter().enumerate() {
+            let rate = leg.rate.round_dp(EXCHANGE_RATE_DP);
+            let (from, to) = (self.bank.format_money(&leg.from), self.bank.format_money(&leg.to));
+            println!("{}", tr!("receipt.leg", i + 1, from, to, leg.from.currency, rate, leg.to.currency));
+        }
+    }
+
+    /// Give the number of a conversion's exchange receipt, if it has one,
+    /// and offer to print the receipt.
+    fn offer_receipt(&self, number: Option<usize>) {
+        let Some(number) = number else {
+            return;
+        };
+        println!("{}", tr!("receipts.number", number));
+        if ask_yes_no(tr!("receipts.offer")) {
+            self.print_receipt(number);
+        }
+    }
+
+    /// Print exchange receipt `number` as formatted for the counter. In a
+    /// customer session only receipts for the customer's own accounts are
+    /// found.
+    fn print_receipt(&self, number: usize) {
+        let receipt = self.bank.exchange_receipt(number).ok().filter(|r| {
+            self.customer.is_none() || r.account.as_deref().and_then(|name| self.bank.find_account(name)).is_some_and(|a| self.visible(a.id))
+        });
+        match receipt {
+            Some(receipt) => println!("\n{}", receipt),
+            None => println!("{}", tr!("receipts.not_found", number)),
+        }
+    }
+
+    /// Reprint an earlier exchange receipt by its number, the latest one
+    /// when none is given.
+    fn menu_exchange_receipts(&mut self) {
+        println!("\n{}", tr!("menu.receipts"));
+        let latest = self.bank.forex.next_receipt() - 1;
+        if latest == 0 {
+            println!("{}", tr!("receipts.none"));
+            return;
+        }
+        let answer = read_string_prompt(&tr!("receipts.prompt", latest));
+        match answer.parse::<usize>() {
+            _ if answer.is_empty() => self.print_receipt(latest),
+            Ok(number) => self.print_receipt(number),
+            Err(_) => println!("{}", tr!("receipts.invalid")),
+        }
+    }
+
+    /// Offer to count `amount` out in bills and coins, if its currency has
+    /// any.
+    fn offer_cash_breakdown(&self, amount: &Money) {
+        let breakdown = self.bank.forex.cash_breakdown(amount);
+        if breakdown.pieces.is_empty() || !ask_yes_no(&tr!("cash.offer", self.bank.format_money(amount))) {
+            return;
+        }
+        self.print_breakdown(&breakdown);
+    }
+
+    /// Each bill and coin of `breakdown` with its count and subtotal, then
+    /// the number of pieces and anything not payable in cash.
+    fn print_breakdown(&self, breakdown: &CashBreakdown) {
+        let amount = &breakdown.amount;
+        let mut table = Table::new(&[(tr!("col.denomination"), Align::Right), (tr!("col.count"), Align::Right), (tr!("col.subtotal"), Align::Right)]);
+        for piece in &breakdown.pieces {
+            let money = |value: Decimal| self.bank.format_money(&Money::new(value, &amount.currency));
+            table.row([money(piece.value), piece.count.to_string(), money(piece.value * Decimal::from(piece.count))]);
+        }
+        println!("{}", table);
+        println!("{}", tr!("cash.pieces", breakdown.piece_count()));
+        if !breakdown.remainder.amount.is_zero() {
+            println!("{}", tr!("cash.remainder", self.bank.format_money(&breakdown.remainder)));
+        }
+    }
+
+    /// The teller's cash drawer: show it, load a float, move cash in and
+    /// out with deposits, withdrawals, and walk-in exchanges, and balance
+    /// it against a count at the end of the day.
+    fn menu_till(&mut self) {
+        println!("\n{}\n", tr!("menu.till"));
+        println!("[1] {}", tr!("till.show"));
+        println!("[2] {}", tr!("till.load"));
+        println!("[3] {}", tr!("till.deposit"));
+        println!("[4] {}", tr!("till.withdraw"));
+        println!("[5] {}", tr!("till.exchange"));
+        println!("[6] {}", tr!("till.balance"));
+        match read_usize_prompt("") {
+            2..=6 if !self.writable() => {}
+            1 => self.print_till(),
+            2 => {
+                let (codes, names) = currency_menu_lists(&self.bank);
+                print_currency_menu(&names);
+                let code = read_currency_prompt(tr!("till.currency"), &codes, &names);
+                let value = read_decimal_prompt(&tr!("till.value", code.clone()));
+                let count = read_usize_prompt(tr!("till.how_many"));
+                match self.bank.load_till(&code, value, count as u64) {
+                    Ok(()) => println!("{}", tr!("till.loaded", count, self.bank.format_money(&Money::new(value, &code)))),
+                    Err(e) => println!("{}", tr!("till.failed", e)),
+                }
+            }
+            choice @ (3 | 4) => {
+                let name = self.read_account_name(tr!("prompt.account_name"));
+                let Some(currency) = self.bank.accounts.iter().find(|a| a.name == name).map(|a| a.currency.clone()) else {
+                    println!("{}", tr!("err.account_not_found"));
+                    return;
+                };
+                let Some(pin) = self.authorize(&name) else {
+                    return;
+                };
+                let prompt = if choice == 3 { tr!("deposit.prompt") } else { tr!("withdraw.prompt") };
+                let amount = Money::new(read_decimal_prompt(prompt), &currency);
+                if !self.confirm_large_transaction(&amount) {
+                    println!("{}", tr!("till.cancelled"));
+                    return;
+                }
+                let memo = read_string_prompt(tr!("prompt.memo"));
+                let result = match choice {
+                    3 => self.bank.cash_deposit(&name, amount, &memo, pin.as_deref()).map(|r| (r, tr!("till.taken_in"))),
+                    _ => self.bank.cash_withdrawal(&name, amount, &memo, pin.as_deref()).map(|r| (r, tr!("till.paid_out"))),
+                };
+                match result {
+                    Ok(((balance, cash), heading)) => {
+                        println!("{}", tr!("balance.updated", self.bank.format_money(&balance)));
+                        println!("\n{}", heading);
+                        self.print_breakdown(&cash);
+                    }
+                    Err(e) => println!("{}", tr!("till.failed", e)),
+                }
+            }
+            5 => {
+                let (codes, names) = currency_menu_lists(&self.bank);
+                print_currency_menu(&names);
+                let from = read_currency_prompt(tr!("exchange.source"), &codes, &names);
+                let to = read_currency_prompt(tr!("exchange.target"), &codes, &names);
+                let amount = Money::new(read_decimal_prompt(&tr!("exchange.amount_in", from.clone())), &from);
+                match self.bank.forex.preview_at(&from, &to, amount.amount, RateType::Cash) {
+                    Ok(preview) => self.print_preview(&preview),
+                    Err(e) => {
+                        println!("{}", tr!("exchange.failed", e));
+                        return;
+                    }
+                }
+                if !self.confirm_large_transaction(&amount) || !ask_yes_no(tr!("till.confirm")) {
+                    println!("{}", tr!("till.cancelled"));
+                    return;
+                }
+                match self.with_limit_override(|bank| bank.cash_exchange(&amount, &to)) {
+                    Ok(((conversion, cash), _)) => {
+                        println!("{}", tr!("transfer.rate", from, conversion.rate.round_dp(EXCHANGE_RATE_DP), to));
+                        self.print_legs(&conversion.legs);
+                        println!("{}", tr!("transfer.fee", self.bank.format_money(&conversion.fee)));
+                        println!("\n{}", tr!("till.paid_out"));
+                        self.print_breakdown(&cash);
+                    }
+                    Err(e) => println!("{}", tr!("till.failed", e)),
+                }
+            }
+            6 => self.balance_till(),
+            _ => println!("{}", tr!("err.invalid_option")),
+        }
+    }
+
+    /// The drawer's bills and coins, one table per currency.
+    fn print_till(&self) {
+        let codes = self.bank.till.currencies();
+        if codes.iter().all(|code| self.bank.till.contents(code).is_empty()) {
+            println!("{}", tr!("till.empty"));
+            return;
+        }
+        for code in codes {
+            let total = self.bank.till.total(code);
+            if total.amount.is_zero() {
+                continue;
+            }
+            let pieces = self.bank.till.contents(code);
+            self.print_breakdown(&CashBreakdown { amount: total.clone(), pieces, remainder: Money::new(Decimal::ZERO, code) });
+            println!("{}\n", tr!("till.total", code, self.bank.format_money(&total)));
+        }
+    }
+
+    /// Count the drawer denomination by denomination, then report each
+    /// currency against what the till expects, marking every denomination
+    /// that is over or short. The count becomes the till's contents.
+    fn balance_till(&mut self) {
+        let codes: Vec<String> = self.bank.till.currencies().into_iter().map(str::to_string).collect();
+        if codes.is_empty() {
+            println!("{}", tr!("till.empty"));
+            return;
+        }
+        println!("{}", tr!("till.count_intro"));
+        let mut counted = Till::new();
+        for code in &codes {
+            let mut values = self.bank.forex.currency(code).map_or(Vec::new(), |c| c.denominations.clone());
+            values.extend(self.bank.till.contents(code).iter().map(|p| p.value));
+            values.sort_by(|a, b| b.cmp(a));
+            values.dedup();
+            for value in values {
+                let label = self.bank.format_money(&Money::new(value, code));
+                let count = loop {
+                    let line = read_string_prompt(&tr!("till.count", label.clone()));
+                    match line.trim() {
+                        "" => break 0,
+                        n => match n.parse::<u64>() {
+                            Ok(n) => break n,
+                            Err(_) => println!("{}", tr!("till.bad_count")),
+                        },
+                    }
+                };
+                counted.load(code, value, count);
+            }
+        }
+        let report = match self.bank.balance_till(counted) {
+            Ok(report) => report,
+            Err(e) => {
+                println!("{}", tr!("till.failed", e));
+                return;
+            }
+        };
+        for currency in &report.currencies {
+            let code = &currency.expected.currency;
+            let money = |value: Decimal| self.bank.format_money(&Money::new(value, code));
+            let mut table = Table::new(&[
+                (tr!("col.denomination"), Align::Right),
+                (tr!("col.expected"), Align::Right),
+                (tr!("col.counted"), Align::Right),
+                (tr!("col.status"), Align::Left),
+            ]);
+            for d in &currency.denominations {
+                let status = match d.counted.cmp(&d.expected) {
+                    std::cmp::Ordering::Equal => tr!("till.ok"),
+                    std::cmp::Ordering::Greater => tr!("till.over_mark"),
+                    std::cmp::Ordering::Less => tr!("till.short_mark"),
+                };
+                table.row([money(d.value), d.expected.to_string(), d.counted.to_string(), status.to_string()]);
+            }
+            println!("\n{}", table);
+            let (expected, counted) = (self.bank.format_money(&currency.expected), self.bank.format_money(&currency.counted));
+            let difference = currency.difference().amount;
+            let line = if difference > Decimal::ZERO {
+                tr!("till.over", code, expected, counted, money(difference))
+            } else if difference < Decimal::ZERO {
+                tr!("till.short", code, expected, counted, money(-difference))
+            } else if !currency.is_balanced() {
+                tr!("till.mixed", code, expected, counted)
+            } else {
+                tr!("till.balanced", code, expected, counted)
+            };
+            println!("{}", line);
+        }
+    }
+
+    fn menu_show_interest(&mut self) {
+        println!("\n{}\n", tr!("show_interest.title"));
+        let name = self.read_account_name(tr!("prompt.account_name"));
+        let Some(acct) = self.bank.accounts.iter().find(|a| a.name == name) else {
+            println!("{}", tr!("err.account_not_found"));
+            return;
+        };
+        println!("{}", tr!("balance.current", self.bank.format_money(&acct.get_balance())));
+        let rate = acct.rate_on(self.bank.today());
+        println!("{}", tr!("show_interest.rate", format!("{:.2}", rate * Decimal::from(100))));
+        if rate < Decimal::ZERO {
+            println!("{}", tr!("interest.negative", format!("{:.2}", -rate * Decimal::from(100))));
+        }
+        let days = read_usize_prompt(tr!("show_interest.days"));
+
+        if !(1..=999999).contains(&days) {
+            println!("{}", tr!("show_interest.bad_days"));
+            return;
+        }
+
+        let inflation = loop {
+            let line = read_string_prompt(tr!("show_interest.inflation"));
+            if line.is_empty() {
+                break None;
+            }
+            match line.trim_end_matches('%').trim().parse::<Decimal>() {
+                Ok(percent) if percent >= Decimal::ZERO => break Some(percent / Decimal::from(100)),
+                _ => println!("{}", tr!("show_interest.bad_inflation")),
+            }
+        };
+
+        let step = loop {
+            match read_string_prompt(tr!("show_interest.step")).to_lowercase().as_str() {
+                "" | "d" => break ForecastStep::Daily,
+                "w" => break ForecastStep::Weekly,
+                "m" => break ForecastStep::Monthly,
+                "y" => break ForecastStep::Yearly,
+                _ => println!("{}", tr!("err.invalid_option")),
+            }
+        };
+
+        let (codes, names) = currency_menu_lists(&self.bank);
+        let display = loop {
+            let line = read_string_prompt(tr!("show_interest.currency"));
+            if line.is_empty() {
+                break None;
+            }
+            match match_currency(&line, &codes, &names) {
+                Ok(code) if code == acct.currency => break None,
+                Ok(code) => break Some(code),
+                Err(msg) => println!("{}", msg),
+            }
+        };
+
+        let mut forecast = match acct.get_interest_forecast(days, ForecastStep::Daily, self.bank.today()) {
+            Ok(forecast) => forecast,
+            Err(e) => {
+                println!("{}", tr!("show_interest.failed", e));
+                return;
+            }
+        };
+        if let Some(inflation) = inflation
+            && let Err(e) = adjust_for_inflation(&mut forecast, inflation, acct.year_basis, self.bank.today())
+        {
+            println!("{}", tr!("show_interest.failed", e));
+            return;
+        }
+        if let Some(code) = &display {
+            let converted = self
+                .bank
+                .forex
+                .convert(&Money::new(Decimal::ONE, &acct.currency), code)
+                .map_err(|e| e.to_string())
+                .and_then(|rate| convert_forecast(&mut forecast, code, rate.amount).map_err(|e| e.to_string()));
+            if let Err(e) = converted {
+                println!("{}", tr!("show_interest.failed", e));
+                return;
+            }
+        }
+        let forecast = match summarize_forecast(forecast, self.bank.today(), step) {
+            Ok(forecast) => forecast,
+            Err(e) => {
+                println!("{}", tr!("show_interest.failed", e));
+                return;
+            }
+        };
+        let optional = |m: &Option<Money>| m.as_ref().map_or_else(String::new, |m| self.bank.format_money(m));
+        let summary = forecast.len() > page_size() && ask_yes_no(&tr!("show_interest.summary_prompt", forecast.len()));
+        let summarized = step != ForecastStep::Daily;
+        let day_column = if summarized { tr!("col.days") } else { tr!("col.day") };
+        let mut columns = vec![(day_column, Align::Right), (tr!("col.interest"), Align::Right), (tr!("col.balance"), Align::Right)];
+        if inflation.is_some() {
+            columns.extend([(tr!("col.real_interest"), Align::Right), (tr!("col.real_balance"), Align::Right)]);
+        }
+        let converted_columns = display.as_ref().map(|code| [tr!("col.converted_interest", code), tr!("col.converted_balance", code)]);
+        if let Some([interest, balance]) = &converted_columns {
+            columns.extend([(interest.as_str(), Align::Right), (balance.as_str(), Align::Right)]);
+        }
+        let mut table = Table::new(&columns);
+        for (i, f) in forecast.iter().enumerate() {
+            if summary && i > 0 && i + 1 < forecast.len() {
+                if i == 1 {
+                    table.row(["..."]);
+                }
+                continue;
+            }
+            let mut row = vec![
+                if summarized { format!("{}-{}", f.first_day, f.day) } else { f.day.to_string() },
+                self.bank.format_money(&f.interest),
+                self.bank.format_money(&f.balance),
+            ];
+            if inflation.is_some() {
+                row.extend([optional(&f.real_interest), optional(&f.real_balance)]);
+            }
+            if display.is_some() {
+                row.extend([optional(&f.converted_interest), optional(&f.converted_balance)]);
+            }
+            table.row(row);
+        }
+        print_paged(&table.to_string(), 2);
+
+        if let Some(last) = forecast.last() {
+            let total: Decimal = forecast.iter().map(|f| f.interest.amount).sum();
+            println!("\n{}", tr!("show_interest.total", self.bank.format_money(&Money::new(total, &last.interest.currency))));
+            println!("{}", tr!("show_interest.final", self.bank.format_money(&last.balance)));
+            if let Some(real_balance) = &last.real_balance {
+                let real_total: Decimal = forecast.iter().filter_map(|f| f.real_interest.as_ref()).map(|m| m.amount).sum();
+                println!("{}", tr!("show_interest.real_total", self.bank.format_money(&Money::new(real_total, &acct.currency))));
+                println!("{}", tr!("show_interest.real_final", self.bank.format_money(real_balance)));
+            }
+            if let Some(converted_balance) = &last.converted_balance {
+                let converted_total: Decimal = forecast.iter().filter_map(|f| f.converted_interest.as_ref()).map(|m| m.amount).sum();
+                let total = Money::new(converted_total, &converted_balance.currency);
+                println!("{}", tr!("show_interest.converted_total", total.currency.clone(), self.bank.format_money(&total)));
+                println!("{}", tr!("show_interest.converted_final", converted_balance.currency.clone(), self.bank.format_money(converted_balance)));
+            }
+        }
+
+        let dp = self.bank.forex.decimals(&acct.currency);
+        let rounded = |m: &Option<Money>| m.as_ref().map_or_else(String::new, |m| m.amount.round_dp(dp).to_string());
+        let converted_dp = display.as_ref().map_or(dp, |code| self.bank.forex.decimals(code));
+        let converted = |m: &Option<Money>| m.as_ref().map_or_else(String::new, |m| m.amount.round_dp(converted_dp).to_string());
+        let mut csv = Csv::new(&["day", "interest", "balance", "real_interest", "real_balance", "currency", "converted_interest", "converted_balance", "converted_currency"]);
+        for f in &forecast {
+            csv.row([
+                f.day.to_string(),
+                f.interest.amount.round_dp(dp).to_string(),
+                f.balance.amount.round_dp(dp).to_string(),
+                rounded(&f.real_interest),
+                rounded(&f.real_balance),
+                acct.currency.clone(),
+                converted(&f.converted_interest),
+                converted(&f.converted_balance),
+                display.clone().unwrap_or_default(),
+            ]);
+        }
+        offer_csv_export(&csv);
+    }
+
+    /// Forecasts an account at its own rate and at candidate rates, side by
+    /// side at up to ten checkpoint days. The account's rate is not changed.
+    fn menu_compare_rates(&mut self) {
+        println!("\n{}\n", tr!("compare.title"));
+        let name = self.read_account_name(tr!("prompt.account_name"));
+        let Some(acct) = self.bank.accounts.iter().find(|a| a.name == name) else {
+            println!("{}", tr!("err.account_not_found"));
+            return;
+        };
+        let hundred = Decimal::from(100);
+        println!("{}", tr!("balance.current", self.bank.format_money(&acct.get_balance())));
+        println!("{}", tr!("show_interest.rate", format!("{:.2}", acct.rate_on(self.bank.today()) * hundred)));
+        let days = read_usize_prompt(tr!("show_interest.days"));
+        if !(1..=999999).contains(&days) {
+            println!("{}", tr!("show_interest.bad_days"));
+            return;
+        }
+        let candidates: Vec<Decimal> = loop {
+            let line = read_string_prompt(tr!("compare.rates"));
+            let parsed: Option<Vec<Decimal>> = line
+                .split(',')
+                .map(|s| s.trim().trim_end_matches('%').parse::<Decimal>().ok().filter(|v| *v >= Decimal::ZERO))
+                .collect();
+            match parsed {
+                Some(rates) => break rates.into_iter().map(|p| p / hundred).collect(),
+                None => println!("{}", tr!("compare.bad_rates")),
+            }
+        };
+
+        // Scenario names double as column headers, so repeated rates are
+        // shown once.
+        let mut scenarios = vec![Scenario::new(&tr!("compare.current", format!("{:.2}%", acct.rate_on(self.bank.today()) * hundred)), acct.rate_on(self.bank.today()))];
+        for rate in candidates {
+            if scenarios.iter().all(|s| s.annual_interest != rate) {
+                scenarios.push(Scenario::new(&format!("{:.2}%", rate * hundred), rate));
+            }
+        }
+        let comparison = match scenario::compare(&acct.get_balance(), &scenarios, days, self.bank.today(), acct.year_basis) {
+            Ok(comparison) => comparison,
+            Err(e) => {
+                println!("{}", tr!("show_interest.failed", e));
+                return;
+            }
+        };
+
+        let headers = std::iter::once(tr!("col.day")).chain(comparison.series.iter().map(|s| s.scenario.name.as_str()));
+        let columns: Vec<(&str, Align)> = headers.map(|h| (h, Align::Right)).collect();
+        let mut table = Table::new(&columns);
+        for day in comparison.checkpoints(10) {
+            let balances = comparison.series.iter().map(|s| self.bank.format_money(&s.points[day - 1].balance));
+            table.row(std::iter::once(day.to_string()).chain(balances));
+        }
+        let totals = comparison.series.iter().map(|s| self.bank.format_money(&s.last().interest));
+        table.row(std::iter::once(tr!("compare.total").to_string()).chain(totals));
+        print_paged(&table.to_string(), 2);
+    }
+
+    /// Forecasts two accounts side by side at up to ten checkpoint days,
+    /// each under its own rates, and reports the day one overtakes the
+    /// other, if it does within the horizon.
+    fn menu_compare_accounts(&mut self) {
+        println!("\n{}\n", tr!("menu.compare_accounts"));
+        let first = self.read_account_name(tr!("compare_accounts.first"));
+        let second = self.read_account_name(tr!("compare_accounts.second"));
+        let days = read_usize_prompt(tr!("show_interest.days"));
+        if !(1..=999999).contains(&days) {
+            println!("{}", tr!("show_interest.bad_days"));
+            return;
+        }
+        let c = match self.bank.compare_accounts(&first, &second, days) {
+            Ok(comparison) => comparison,
+            Err(BankError::AccountNotFound(_)) => {
+                println!("{}", tr!("err.account_not_found"));
+                return;
+            }
+            Err(e) => {
+                println!("{}", tr!("show_interest.failed", e));
+                return;
+            }
+        };
+        let hundred = Decimal::from(100);
+        for a in &c.accounts {
+            println!("{}", tr!("compare_accounts.side", a.account, self.bank.format_money(&a.opening), format!("{:.2}", a.annual_interest * hundred)));
+        }
+        println!();
+        let [a, b] = &c.accounts;
+        let mut table = Table::new(&[
+            (tr!("col.day"), Align::Right),
+            (tr!("col.date"), Align::Left),
+            (a.account.as_str(), Align::Right),
+            (b.account.as_str(), Align::Right),
+            (tr!("col.gap"), Align::Right),
+        ]);
+        for day in c.checkpoints(10) {
+            table.row([
+                day.to_string(),
+                c.date_of(day).to_string(),
+                self.bank.format_money(&a.points[day - 1].balance),
+                self.bank.format_money(&b.points[day - 1].balance),
+                self.bank.format_money(&c.gap_on(day)),
+            ]);
+        }
+        table.row([
+            tr!("compare.total").to_string(),
+            String::new(),
+            self.bank.format_money(&a.last().interest),
+            self.bank.format_money(&b.last().interest),
+            String::new(),
+        ]);
+        print_paged(&table.to_string(), 2);
+        match (c.crossover, c.crossover.and_then(|day| c.ahead_on(day))) {
+            (Some(day), Some(ahead)) => {
+                println!("{}", tr!("compare_accounts.overtakes", c.accounts[ahead].account, c.accounts[1 - ahead].account, day, c.date_of(day)))
+            }
+            (Some(day), None) => println!("{}", tr!("compare_accounts.level", a.account, b.account, day, c.date_of(day))),
+            (None, _) => match c.ahead_on(c.horizon) {
+                Some(ahead) => println!("{}", tr!("compare_accounts.no_crossover", days, c.accounts[ahead].account)),
+                None => println!("{}", tr!("compare_accounts.stay_level", days)),
+            },
+        }
+    }
+
+    /// Convert a nominal annual rate to its APY, or an APY to its nominal
+    /// rate, under every compounding frequency.
+    fn menu_apy_calculator(&mut self) {
+        println!("\n{}\n", tr!("menu.apy"));
+        println!("[1] {}", tr!("apy.from_nominal"));
+        println!("[2] {}", tr!("apy.from_apy"));
+        let from_nominal = match read_usize_prompt("") {
+            1 => true,
+            2 => false,
+            _ => {
+                println!("{}", tr!("err.invalid_option"));
+                return;
+            }
+        };
+        let hundred = Decimal::from(100);
+        let rate = read_signed_decimal_prompt(tr!("apy.rate")) / hundred;
+        let mut table = Table::new(&[(tr!("col.compounding"), Align::Left), (tr!("col.nominal"), Align::Right), (tr!("col.apy"), Align::Right)]);
+        for compounding in COMPOUNDINGS {
+            let (nominal, apy) = match from_nominal {
+                true => (Some(rate), compounding.effective_annual_yield(rate)),
+                false => (compounding.nominal_rate(rate), Some(rate)),
+            };
+            let percent = |r: Option<Decimal>| r.map_or_else(|| tr!("apy.out_of_range").to_string(), |r| format!("{:.4}%", r * hundred));
+            table.row([compounding.name().to_string(), percent(nominal), percent(apy)]);
+        }
+        print_paged(&table.to_string(), 2);
+    }
+
+    /// Forecast interest on a principal in the base currency at any rate
+    /// and compounding, with no account, at up to ten checkpoint days.
+    fn menu_interest_calculator(&mut self) {
+        println!("\n{}\n", tr!("menu.calculator"));
+        let base = self.bank.base_currency.code.clone();
+        let principal = Money::new(read_decimal_prompt(&tr!("calculator.principal", base)), &base);
+        let hundred = Decimal::from(100);
+        let rate = read_signed_decimal_prompt(tr!("apy.rate")) / hundred;
+        for (i, compounding) in COMPOUNDINGS.iter().enumerate() {
+            println!("[{}] {}", i + 1, compounding.name());
+        }
+        let Some(&compounding) = COMPOUNDINGS.get(read_usize_prompt(tr!("calculator.compounding")) - 1) else {
+            println!("{}", tr!("err.invalid_option"));
+            return;
+        };
+        let days = read_usize_prompt(tr!("show_interest.days"));
+        if !(1..=999999).contains(&days) {
+            println!("{}", tr!("show_interest.bad_days"));
+            return;
+        }
+        let series = match scenario::calculate(&principal, rate, compounding, days) {
+            Ok(series) => series,
+            Err(e) => {
+                println!("{}", tr!("show_interest.failed", e));
+                return;
+            }
+        };
+        let apy = compounding.effective_annual_yield(rate).map_or_else(|| tr!("apy.out_of_range").to_string(), |r| format!("{:.4}%", r * hundred));
+        println!("\n{}\n", tr!("calculator.terms", self.bank.format_money(&principal), format!("{:.2}", rate * hundred), compounding.name(), apy));
+        let mut table = Table::new(&[(tr!("col.day"), Align::Right), (tr!("col.interest"), Align::Right), (tr!("col.balance"), Align::Right)]);
+        for day in series.checkpoints(10) {
+            let point = &series.points[day - 1];
+            table.row([day.to_string(), self.bank.format_money(&point.interest), self.bank.format_money(&point.balance)]);
+        }
+        print_paged(&table.to_string(), 2);
+        println!("{}", tr!("show_interest.total", self.bank.format_money(&series.last().interest)));
+        println!("{}", tr!("show_interest.final", self.bank.format_money(&series.last().balance)));
+    }
+
+    /// Compare paying a sum off a loan quote in the base currency with
+    /// depositing it, after the bank's withholding tax.
+    fn menu_monte_carlo(&mut self) {
+        println!("\n{}\n", tr!("menu.monte_carlo"));
+        println!("{}", tr!("mc.intro"));
+        let name = self.read_account_name(tr!("prompt.account_name"));
+        let days = read_usize_prompt(tr!("show_interest.days"));
+        if !(1..=999999).contains(&days) {
+            println!("{}", tr!("show_interest.bad_days"));
+            return;
+        }
+        let mut simulation = match self.bank.monte_carlo(&name, days) {
+            Ok(simulation) => simulation,
+            Err(BankError::AccountNotFound(_)) => {
+                println!("{}", tr!("err.account_not_found"));
+                return;
+            }
+            Err(e) => {
+                println!("{}", tr!("sim.failed", e));
+                return;
+            }
+        };
+        simulation = simulation.with_rate_volatility(read_percent(tr!("mc.rate_volatility"), Decimal::ZERO, false));
+        if simulation.fx.is_some() {
+            let drift = read_percent(tr!("sim.drift"), Decimal::ZERO, true);
+            let volatility = read_percent(tr!("sim.volatility"), Decimal::from(10), false);
+            simulation = simulation.with_fx_model(RateModel::new(drift, volatility));
+        }
+        if let Some(amount) = read_optional_amount_prompt(tr!("mc.deposit")).filter(|a| *a > Decimal::ZERO) {
+            let every = read_usize_prompt(tr!("mc.every"));
+            let chance = read_percent(tr!("mc.chance"), Decimal::from(100), false).min(Decimal::ONE);
+            simulation = simulation.with_deposits(DepositBehavior { every, chance: chance.to_f64(), amount });
+        }
+        let paths = loop {
+            let raw = read_string_prompt(tr!("mc.paths"));
+            if raw.is_empty() {
+                break DEFAULT_PATHS;
+            }
+            match raw.parse::<usize>() {
+                Ok(paths) if (1..=MAX_PATHS).contains(&paths) => break paths,
+                _ => println!("{}", tr!("mc.bad_paths", MAX_PATHS)),
+            }
+        };
+        let seed = self.read_seed();
+        let outcome = match simulation.with_paths(paths).with_seed(seed).run() {
+            Ok(outcome) => outcome,
+            Err(e) => {
+                println!("{}", tr!("sim.failed", e));
+                return;
+            }
+        };
+        let opening = match &outcome.opening_value {
+            Some(value) => format!("{} ({})", self.bank.format_money(&outcome.opening), self.bank.format_money(value)),
+            None => self.bank.format_money(&outcome.opening),
+        };
+        println!("\n{}", tr!("mc.title", name, opening, days, paths, seed));
+        let mut columns = vec![(tr!("col.percentile"), Align::Right), (tr!("col.balance"), Align::Right)];
+        if outcome.value.is_some() {
+            columns.push((tr!("col.value"), Align::Right));
+        }
+        let mut table = Table::new(&columns);
+        let row = |label: String, pick: &dyn Fn(&Distribution) -> Money| {
+            std::iter::once(label)
+                .chain(std::iter::once(&outcome.balance).chain(&outcome.value).map(|d| self.bank.format_money(&pick(d))))
+                .collect::<Vec<_>>()
+        };
+        table.row(row(tr!("mc.lowest").to_string(), &|d| d.min()));
+        for p in PERCENTILES {
+            table.row(row(tr!("mc.percentile", p), &|d| d.percentile(p)));
+        }
+        table.row(row(tr!("mc.highest").to_string(), &|d| d.max()));
+        table.row(row(tr!("mc.mean").to_string(), &|d| d.mean()));
+        println!("{}", table);
+        let share = |chance: f64| format!("{:.1}", chance * 100.0);
+        println!("{}", tr!("mc.below", share(outcome.balance.chance_below(outcome.opening.amount))));
+        if let (Some(value), Some(opening)) = (&outcome.value, &outcome.opening_value) {
+            println!("{}", tr!("mc.below_value", share(value.chance_below(opening.amount))));
+        }
+        if !outcome.deposited.amount.is_zero() {
+            println!("{}", tr!("mc.deposited", self.bank.format_money(&outcome.deposited)));
+        }
+        println!("{}", tr!("mc.seed", seed));
+    }
+
+    /// A seed typed by the user, or the clock's time when left blank.
+    fn read_seed(&self) -> u64 {
+        loop {
+            let raw = read_string_prompt(tr!("sim.seed"));
+            if raw.is_empty() {
+                return self.bank.now() as u64;
+            }
+            match raw.parse() {
+                Ok(seed) => return seed,
+                Err(_) => println!("{}", tr!("sim.bad_number")),
+            }
+        }
+    }
+
+    fn menu_loan_vs_deposit(&mut self) {
+        println!("\n{}\n", tr!("menu.paydown"));
+        let base = self.bank.base_currency.code.clone();
+        let principal = Money::new(read_decimal_prompt(&tr!("paydown.principal", base)), &base);
+        let hundred = Decimal::from(100);
+        let loan_rate = read_signed_decimal_prompt(tr!("paydown.loan_rate")) / hundred;
+        let term = read_usize_prompt(tr!("paydown.term"));
+        if term > MAX_TERM as usize {
+            println!("{}", tr!("paydown.bad_term", MAX_TERM));
+            return;
+        }
+        let frequency = match read_string_prompt(tr!("paydown.frequency")).as_str() {
+            "" => PaymentFrequency::Monthly,
+            raw => match PaymentFrequency::parse(raw) {
+                Some(frequency) => frequency,
+                None => {
+                    println!("{}", tr!("err.invalid_option"));
+                    return;
+                }
+            },
+        };
+        let amount = Money::new(read_decimal_prompt(tr!("paydown.amount")), &base);
+        let deposit_rate = read_signed_decimal_prompt(tr!("paydown.deposit_rate")) / hundred;
+        let days = read_usize_prompt(tr!("show_interest.days"));
+        if !(1..=999999).contains(&days) {
+            println!("{}", tr!("show_interest.bad_days"));
+            return;
+        }
+        let dp = self.bank.forex.decimals(&base);
+        let tax_rate = self.bank.withholding_tax_rate;
+        let comparison = Loan::new(0, "", principal, dp, loan_rate, term as u32, frequency)
+            .map_err(|e| e.to_string())
+            .and_then(|loan| PaydownComparison::run(&loan, &amount, deposit_rate, tax_rate, days).map_err(|e| e.to_string()));
+        let c = match comparison {
+            Ok(c) => c,
+            Err(e) => {
+                println!("{}", tr!("paydown.failed", e));
+                return;
+            }
+        };
+        let percent = |r: Decimal, dp: usize| format!("{:.*}", dp, r * hundred);
+        println!();
+        println!("{}", tr!("paydown.pay_down", self.bank.format_money(&c.interest_saved), percent(c.loan_rate, 2), percent(c.loan_apy, 4)));
+        println!(
+            "{}",
+            tr!("paydown.deposit", self.bank.format_money(&c.interest_earned), percent(c.deposit_rate, 2), percent(c.tax_rate, 2), percent(c.deposit_apy, 4))
+        );
+        let by = self.bank.format_money(&Money::new(c.advantage().amount.abs(), &base));
+        match c.better() {
+            PaydownChoice::PayDown => println!("{}", tr!("paydown.ahead_pay_down", by, c.days)),
+            PaydownChoice::Deposit => println!("{}", tr!("paydown.ahead_deposit", by, c.days)),
+            PaydownChoice::Either => println!("{}", tr!("paydown.either", c.days)),
+        }
+        if let Some(rate) = c.break_even_rate {
+            println!("{}", tr!("paydown.break_even", percent(rate, 2)));
+        }
+    }
+
+    fn menu_review_flagged(&mut self) {
+        println!("\n{}\n", tr!("menu.review_flagged"));
+        let pending: Vec<(usize, String, &str, Money)> = self
+            .bank
+            .pending_reviews()
+            .into_iter()
+            .map(|f| (f.id, f.account.clone(), tx_label(f.tx_type), f.amount.clone()))
+            .collect();
+        if pending.is_empty() {
+            println!("{}", tr!("review.none"));
+            return;
+        }
+        let mut table = Table::new(&[
+            (tr!("col.id"), Align::Right),
+            (tr!("col.account"), Align::Left),
+            (tr!("col.type"), Align::Left),
+            (tr!("col.amount"), Align::Right),
+        ]);
+        for (id, account, tx_type, amount) in &pending {
+            table.row([id.to_string(), account.clone(), tx_type.to_string(), self.bank.format_money(amount)]);
+        }
+        println!("{}", table);
+        if !ask_yes_no(tr!("review.ask")) {
+            return;
+        }
+        let id = read_usize_prompt(tr!("review.id"));
+        match self.bank.mark_reviewed(id) {
+            Ok(()) => println!("{}", tr!("review.done", id)),
+            Err(_) => println!("{}", tr!("review.missing", id)),
+        }
+    }
+
+    /// List the withdrawals and transfers held for approval, then approve
+    /// or reject one, which takes the admin passphrase once more.
+    fn menu_approvals(&mut self) {
+        println!("\n{}\n", tr!("menu.approvals"));
+        let pending: Vec<(usize, String, &str, String, String)> = self
+            .bank
+            .pending_transactions(None)
+            .into_iter()
+            .map(|a| {
+                let account = match &a.held {
+                    HeldTransaction::Transfer { from, to, .. } => format!("{} -> {}", from, to),
+                    held => held.account().to_string(),
+                };
+                (a.id, a.requested.to_string(), a.held.name(), account, self.bank.format_money(a.held.amount()))
+            })
+            .collect();
+        if pending.is_empty() {
+            println!("{}", tr!("approval.none"));
+            return;
+        }
+        let mut table = Table::new(&[
+            (tr!("col.id"), Align::Right),
+            (tr!("col.date"), Align::Left),
+            (tr!("col.type"), Align::Left),
+            (tr!("col.account"), Align::Left),
+            (tr!("col.amount"), Align::Right),
+        ]);
+        for (id, requested, kind, account, amount) in &pending {
+            table.row([id.to_string(), requested.clone(), kind.to_string(), account.clone(), amount.clone()]);
+        }
+        println!("{}", table);
+        let id = read_usize_prompt(tr!("approval.id"));
+        if !pending.iter().any(|(p, ..)| *p == id) {
+            println!("{}", tr!("approval.missing", id));
+            return;
+        }
+        println!("[1] {}", tr!("approval.approve"));
+        println!("[2] {}", tr!("approval.reject"));
+        println!("[3] {}", tr!("approval.keep"));
+        let id_text = id.to_string();
+        match read_usize_prompt("") {
+            1 => {
+                let passphrase = read_masked_prompt(tr!("role.passphrase"));
+                match self.bank.approve(id, &passphrase) {
+                    Ok(_) => {
+                        self.journal("approve", &[("id", Some(&id_text)), ("passphrase", Some(&passphrase))]);
+                        println!("{}", tr!("approval.approved", id));
+                    }
+                    Err(e) => println!("{}", tr!("approval.failed", e)),
+                }
+            }
+            2 => {
+                let reason = read_string_prompt(tr!("approval.reason"));
+                let passphrase = read_masked_prompt(tr!("role.passphrase"));
+                match self.bank.reject(id, &passphrase, &reason) {
+                    Ok(_) => {
+                        self.journal("reject", &[("id", Some(&id_text)), ("reason", Some(reason.trim())), ("passphrase", Some(&passphrase))]);
+                        println!("{}", tr!("approval.rejected", id));
+                    }
+                    Err(e) => println!("{}", tr!("approval.failed", e)),
+                }
+            }
+            _ => {}
+        }
+    }
+
+    /// The inbox messages addressed to this session, oldest first: alerts
+    /// on the accounts it can see, and, in a staff session with the Admin
+    /// role, large transactions flagged for review and withdrawals and
+    /// transfers held for approval.
+    fn inbox_messages(&self) -> Vec<InboxMessage> {
+        self.inbox
+            .messages()
+            .into_iter()
+            .filter(|m| match &m.event {
+                BankEvent::AlertRaised { account, .. } => self.bank.find_account(account).is_some_and(|a| self.visible(a.id)),
+                BankEvent::LargeTransactionFlagged { .. } | BankEvent::ApprovalRequested { .. } => self.customer.is_none() && self.role.allows(Role::Admin),
+                _ => false,
+            })
+            .collect()
+    }
+
+    /// Show the session's inbox, newest first, marking it read, then offer
+    /// to dismiss one message or all of them.
+    fn menu_inbox(&mut self) {
+        println!("\n{}\n", tr!("menu.inbox"));
+        let messages = self.inbox_messages();
+        if messages.is_empty() {
+            println!("{}", tr!("inbox.empty"));
+            return;
+        }
+        let mut table = Table::new(&[
+            (tr!("col.id"), Align::Right),
+            (tr!("col.received"), Align::Left),
+            (tr!("col.message"), Align::Left),
+        ]);
+        for m in messages.iter().rev() {
+            let marker = if m.read { "" } else { "* " };
+            table.row([m.id.to_string(), self.bank.format_timestamp(m.received), format!("{}{}", marker, m.event)]);
+        }
+        print_paged(&table.to_string(), 2);
+        let ids: Vec<usize> = messages.iter().map(|m| m.id).collect();
+        self.inbox.mark_read(&ids);
+        println!("\n[1] {}", tr!("inbox.dismiss"));
+        println!("[2] {}", tr!("inbox.dismiss_all"));
+        println!("[3] {}", tr!("inbox.keep"));
+        match read_usize_prompt("") {
+            1 => {
+                let id = read_usize_prompt(tr!("inbox.id"));
+                match ids.contains(&id) && self.inbox.dismiss(&[id]) == 1 {
+                    true => println!("{}", tr!("inbox.dismissed", id)),
+                    false => println!("{}", tr!("inbox.missing", id)),
+                }
+            }
+            2 => println!("{}", tr!("inbox.cleared", self.inbox.dismiss(&ids))),
+            _ => {}
+        }
+    }
+
+    /// Give accounts alternate names that every account prompt accepts.
+    fn menu_aliases(&mut self) {
+        println!("\n{}\n", tr!("menu.aliases"));
+        println!("[1] {}", tr!("alias.add"));
+        println!("[2] {}", tr!("alias.remove"));
+        println!("[3] {}", tr!("alias.list"));
+        match read_usize_prompt("") {
+            1 | 2 if !self.writable() => {}
+            1 => {
+                let name = self.read_account_name(tr!("prompt.account_name"));
+                if self.bank.find_account(&name).is_none() {
+                    println!("{}", tr!("err.account_not_found"));
+                    return;
+                }
+                let alias = read_string_prompt(tr!("alias.prompt"));
+                match self.bank.add_alias(&name, &alias) {
+                    Ok(acct) => println!("{}", tr!("alias.added", alias.trim(), acct.name)),
+                    Err(e) => println!("{}", tr!("alias.failed", e)),
+                }
+            }
+            2 => {
+                let alias = read_string_prompt(tr!("alias.prompt"));
+                if self.bank.find_account(&alias).is_none_or(|a| !self.visible(a.id)) {
+                    println!("{}", tr!("alias.failed", BankError::AliasNotFound(alias)));
+                    return;
+                }
+                match self.bank.remove_alias(&alias) {
+                    Ok(name) => println!("{}", tr!("alias.removed", alias, name)),
+                    Err(e) => println!("{}", tr!("alias.failed", e)),
+                }
+            }
+            3 => {
+                let aliases: Vec<_> = self
+                    .bank
+                    .accounts
+                    .iter()
+                    .filter(|a| self.visible(a.id))
+                    .flat_map(|a| a.aliases.iter().map(move |alias| (alias, &a.name)))
+                    .collect();
+                if aliases.is_empty() {
+                    println!("{}", tr!("alias.none"));
+                    return;
+                }
+                let mut table = Table::new(&[(tr!("col.alias"), Align::Left), (tr!("col.account"), Align::Left)]);
+                for (alias, name) in aliases {
+                    table.row([alias, name]);
+                }
+                println!("{}", table);
+            }
+            _ => println!("{}", tr!("err.invalid_option")),
+        }
+    }
+
+    /// Archive emptied accounts, restore them, or list them. Archived
+    /// accounts are hidden from every other menu, so their names are read
+    /// here without suggestions.
+    fn menu_archive(&mut self) {
+        println!("\n{}\n", tr!("menu.archive"));
+        println!("[1] {}", tr!("archive.archive"));
+        println!("[2] {}", tr!("archive.restore"));
+        println!("[3] {}", tr!("archive.list"));
+        match read_usize_prompt("") {
+            1 | 2 if !self.writable() => {}
+            1 => {
+                let name = self.read_account_name(tr!("prompt.account_name"));
+                match self.bank.archive_account(&name) {
+                    Ok(acct) => println!("{}", tr!("archive.archived", acct.name)),
+                    Err(e) => println!("{}", tr!("archive.failed", e)),
+                }
+            }
+            2 => {
+                let name = read_string_prompt(tr!("prompt.account_name"));
+                match self.bank.restore_account(&name) {
+                    Ok(acct) => println!("{}", tr!("archive.restored", acct.name)),
+                    Err(e) => println!("{}", tr!("archive.failed", e)),
+                }
+            }
+            3 => {
+                let archived: Vec<_> = self.bank.archived_accounts().collect();
+                if archived.is_empty() {
+                    println!("{}", tr!("archive.none"));
+                    return;
+                }
+                let mut table = Table::new(&[(tr!("col.id"), Align::Right), (tr!("col.account"), Align::Left), (tr!("col.archived"), Align::Left)]);
+                for acct in archived {
+                    table.row([acct.id.to_string(), acct.name.clone(), acct.archived.map(|d| d.to_string()).unwrap_or_default()]);
+                }
+                println!("{}", table);
+            }
+            _ => println!("{}", tr!("err.invalid_option")),
+        }
+    }
+
+    fn menu_customers(&mut self) {
+        println!("\n{}\n", tr!("menu.customers"));
+        println!("[1] {}", tr!("customers.register"));
+        println!("[2] {}", tr!("customers.open"));
+        println!("[3] {}", tr!("customers.summary"));
+        println!("[4] {}", tr!("customers.kyc"));
+        println!("[5] {}", tr!("customers.review"));
+        match read_usize_prompt("") {
+            1 | 2 | 4 | 5 if !self.writable() => {}
+            1 => {
+                let name = read_string_prompt(tr!("customers.name"));
+                let contact = read_string_prompt(tr!("customers.contact"));
+                let id = self.bank.create_customer(&name, &contact).id;
+                println!("{}", tr!("customers.registered", name, id));
+            }
+            2 => {
+                let customer_id = read_usize_prompt(tr!("customers.id"));
+                let account_name = read_string_prompt(tr!("prompt.account_name"));
+                match self.bank.open_account_for(customer_id, &account_name) {
+                    Ok(acct) => println!("{}", tr!("customers.opened", acct.name, acct.id)),
+                    Err(_) => println!("{}", tr!("customers.not_found")),
+                }
+            }
+            3 => {
+                let customer_id = read_usize_prompt(tr!("customers.id"));
+                let Some(customer) = self.bank.find_customer(customer_id) else {
+                    println!("{}", tr!("customers.not_found"));
+                    return;
+                };
+                println!("{}", tr!("customers.header", customer.name, customer.contact));
+                println!("{}", kyc_summary(customer));
+                let mut table = Table::new(&[(tr!("col.id"), Align::Right), (tr!("col.account"), Align::Left), (tr!("col.balance"), Align::Right)]);
+                for acct in self.bank.customer_accounts(customer_id) {
+                    table.row([acct.id.to_string(), acct.name.clone(), self.bank.format_money(&acct.get_balance())]);
+                }
+                println!("{}", table);
+                match self.bank.customer_balance(customer_id) {
+                    Ok(total) => println!("{}", tr!("customers.total", self.bank.format_money(&total))),
+                    Err(e) => println!("{}", tr!("customers.total_failed", e)),
+                }
+            }
+            4 => {
+                let customer_id = read_usize_prompt(tr!("customers.id"));
+                if self.bank.find_customer(customer_id).is_none() {
+                    println!("{}", tr!("customers.not_found"));
+                    return;
+                }
+                println!("{}", tr!("kyc.id_type"));
+                for (i, id_type) in IdType::ALL.iter().enumerate() {
+                    println!("[{}] {}", i + 1, id_type_label(*id_type));
+                }
+                let Some(id_type) = IdType::ALL.get(read_usize_prompt("") - 1).copied() else {
+                    println!("{}", tr!("err.invalid_option"));
+                    return;
+                };
+                let id_number = read_string_prompt(tr!("kyc.id_number"));
+                let address = read_string_prompt(tr!("kyc.address"));
+                match self.bank.record_identification(customer_id, Identification { id_type, id_number }, &address) {
+                    Ok(customer) => println!("{}", tr!("kyc.recorded", customer.name)),
+                    Err(e) => println!("{}", tr!("kyc.failed", e)),
+                }
+            }
+            5 => {
+                if !self.role.allows(Role::Admin) {
+                    println!("{}", tr!("kyc.needs_admin"));
+                    return;
+                }
+                let customer_id = read_usize_prompt(tr!("customers.id"));
+                let Some(customer) = self.bank.find_customer(customer_id) else {
+                    println!("{}", tr!("customers.not_found"));
+                    return;
+                };
+                println!("{}", kyc_summary(customer));
+                let name = customer.name.clone();
+                let choices = [VerificationStatus::Verified, VerificationStatus::Rejected, VerificationStatus::Pending, VerificationStatus::Unverified];
+                println!("{}", tr!("kyc.decision"));
+                for (i, status) in choices.iter().enumerate() {
+                    println!("[{}] {}", i + 1, status_label(*status));
+                }
+                let Some(status) = choices.get(read_usize_prompt("") - 1).copied() else {
+                    println!("{}", tr!("err.invalid_option"));
+                    return;
+                };
+                match self.bank.set_verification(customer_id, status) {
+                    Ok(()) => println!("{}", tr!("kyc.updated", name, status_label(status))),
+                    Err(e) => println!("{}", tr!("kyc.failed", e)),
+                }
+            }
+            _ => println!("{}", tr!("err.invalid_option")),
+        }
+    }
+
+    fn menu_savings_goals(&mut self) {
+        println!("\n{}\n", tr!("menu.goals"));
+        let name = self.read_account_name(tr!("prompt.account_name"));
+        let Some(index) = self.bank.accounts.iter().position(|a| a.name == name) else {
+            println!("{}", tr!("err.account_not_found"));
+            return;
+        };
+        println!("[1] {}", tr!("goal.set"));
+        println!("[2] {}", tr!("goal.show"));
+        println!("[3] {}", tr!("goal.remove"));
+        match read_usize_prompt("") {
+            1 | 3 if !self.writable() => {}
+            1 => {
+                let today = self.bank.today();
+                let acct = &mut self.bank.accounts[index];
+                let goal_name = read_string_prompt(tr!("goal.name"));
+                let target = read_decimal_prompt(&tr!("goal.target", acct.currency.clone()));
+                let Some(date) = Date::parse(&read_string_prompt(tr!("goal.date"))) else {
+                    println!("{}", tr!("goal.bad_date"));
+                    return;
+                };
+                let currency = acct.currency.clone();
+                match acct.add_goal(SavingsGoal::new(&goal_name, Money::new(target, &currency), date), today) {
+                    Ok(()) => println!("{}", tr!("goal.added", goal_name)),
+                    Err(e) => println!("{}", tr!("goal.failed", e)),
+                }
+            }
+            2 => {
+                let acct = &self.bank.accounts[index];
+                if acct.goals.is_empty() {
+                    println!("{}", tr!("goal.none"));
+                    return;
+                }
+                for goal in &acct.goals {
+                    match acct.goal_progress(&goal.name, PaymentFrequency::Monthly, self.bank.today()) {
+                        Ok(progress) => {
+                            println!("\n{}", tr!("goal.heading", goal.name.clone(), goal.target_date));
+                            println!("{}", progress_bar(progress.fraction, 20));
+                            println!("{}", tr!("goal.saved", self.bank.format_money(&progress.saved), self.bank.format_money(&goal.target)));
+                            if progress.is_reached() {
+                                println!("{}", tr!("goal.reached"));
+                            } else if progress.periods_left == 0 {
+                                println!("{}", tr!("goal.overdue", self.bank.format_money(&progress.contribution)));
+                            } else {
+                                println!("{}", tr!("goal.monthly", self.bank.format_money(&progress.contribution), progress.periods_left));
+                            }
+                        }
+                        Err(e) => println!("{}", tr!("goal.failed", e)),
+                    }
+                }
+            }
+            3 => {
+                let goal_name = read_string_prompt(tr!("goal.name"));
+                match self.bank.accounts[index].remove_goal(&goal_name) {
+                    Ok(_) => println!("{}", tr!("goal.removed", goal_name)),
+                    Err(e) => println!("{}", tr!("goal.failed", e)),
+                }
+            }
+            _ => println!("{}", tr!("err.invalid_option")),
+        }
+    }
+
+    fn menu_budget(&mut self) {
+        println!("\n{}\n", tr!("menu.budget"));
+        let name = self.read_account_name(tr!("prompt.account_name"));
+        let Some(index) = self.bank.accounts.iter().position(|a| a.name == name) else {
+            println!("{}", tr!("err.account_not_found"));
+            return;
+        };
+        println!("[1] {}", tr!("budget.set"));
+        println!("[2] {}", tr!("budget.show"));
+        println!("[3] {}", tr!("budget.remove"));
+        match read_usize_prompt("") {
+            1 | 3 if !self.writable() => {}
+            1 => {
+                let acct = &mut self.bank.accounts[index];
+                let category = read_string_prompt(tr!("budget.category"));
+                let limit = read_decimal_prompt(&tr!("budget.limit", acct.currency.clone()));
+                let currency = acct.currency.clone();
+                match acct.add_envelope(Envelope::new(&category, Money::new(limit, &currency))) {
+                    Ok(()) => println!("{}", tr!("budget.added", category)),
+                    Err(e) => println!("{}", tr!("budget.failed", e)),
+                }
+            }
+            2 => {
+                let today = self.bank.today();
+                let statuses = self.bank.accounts[index].budget(today);
+                if statuses.is_empty() {
+                    println!("{}", tr!("budget.none"));
+                    return;
+                }
+                println!("\n{}\n", tr!("budget.title", format!("{}-{:02}", today.year, today.month)));
+                let mut table = Table::new(&[
+                    (tr!("col.category"), Align::Left),
+                    (tr!("col.limit"), Align::Right),
+                    (tr!("col.spent"), Align::Right),
+                    (tr!("col.remaining"), Align::Right),
+                    (tr!("col.status"), Align::Left),
+                ]);
+                for s in &statuses {
+                    let status = if s.is_overspent() { tr!("budget.over", self.bank.format_money(&s.overspent)) } else { tr!("budget.ok").to_string() };
+                    table.row([
+                        s.category.clone(),
+                        self.bank.format_money(&s.limit),
+                        self.bank.format_money(&s.spent),
+                        self.bank.format_money(&s.remaining),
+                        status,
+                    ]);
+                }
+                println!("{}", table);
+            }
+            3 => {
+                let category = read_string_prompt(tr!("budget.category"));
+                match self.bank.accounts[index].remove_envelope(&category) {
+                    Ok(_) => println!("{}", tr!("budget.removed", category)),
+                    Err(e) => println!("{}", tr!("budget.failed", e)),
+                }
+            }
+            _ => println!("{}", tr!("err.invalid_option")),
+        }
+    }
+
+    /// Tag one of an account's last transactions, or report what its
+    /// transactions paid in and took out under each tag over a period.
+    fn menu_tags(&mut self) {
+        println!("\n{}\n", tr!("menu.tags"));
+        let name = self.read_account_name(tr!("prompt.account_name"));
+        let Some(acct) = self.bank.find_account(&name) else {
+            println!("{}", tr!("err.account_not_found"));
+            return;
+        };
+        println!("[1] {}", tr!("tags.tag"));
+        println!("[2] {}", tr!("tags.report"));
+        match read_usize_prompt("") {
+            1 if !self.writable() => {}
+            1 => {
+                let count = acct.transactions.len();
+                if count == 0 {
+                    println!("{}", tr!("history.none"));
+                    return;
+                }
+                let mut table = Table::new(&[
+                    (tr!("col.number"), Align::Right),
+                    (tr!("col.date"), Align::Left),
+                    (tr!("col.type"), Align::Left),
+                    (tr!("col.amount"), Align::Right),
+                    (tr!("col.memo"), Align::Left),
+                    (tr!("col.tags"), Align::Left),
+                ]);
+                let first = count.saturating_sub(TAG_RECENT_TRANSACTIONS);
+                for (index, tx) in acct.transactions.iter().enumerate().skip(first) {
+                    table.row([
+                        (index + 1).to_string(),
+                        self.bank.local_date(tx.timestamp).to_string(),
+                        tx_label(tx.tx_type()).to_string(),
+                        self.bank.format_money(&Money::new(tx.amount(), &acct.currency)),
+                        tx.memo.to_string(),
+                        tx.tags.join(),
+                    ]);
+                }
+                println!("{}", table);
+                let number = read_usize_prompt(tr!("tags.number"));
+                let raw = read_string_prompt(tr!("tags.enter"));
+                let tags: Vec<&str> = raw.split(',').filter(|t| !t.trim().is_empty()).collect();
+                match self.bank.tag_transaction(&name, number - 1, &tags) {
+                    Ok(tags) if tags.is_empty() => println!("{}", tr!("tags.cleared", number)),
+                    Ok(tags) => println!("{}", tr!("tags.saved", number, tags.join(", "))),
+                    Err(e) => println!("{}", tr!("tags.failed", e)),
+                }
+            }
+            2 => {
+                println!("{}", tr!("filter.hint"));
+                let query = TransactionQuery {
+                    from: Date::parse(&read_string_prompt(tr!("filter.from"))),
+                    to: Date::parse(&read_string_prompt(tr!("filter.to"))),
+                    ..TransactionQuery::default()
+                };
+                let (start, end) = acct.statement_period(&query, self.bank.today());
+                let totals = acct.report_by_tag(start, end);
+                if totals.is_empty() {
+                    println!("{}", tr!("tags.none"));
+                    return;
+                }
+                println!("\n{}\n", tr!("tags.title", acct.name, start, end));
+                let mut table = Table::new(&[
+                    (tr!("col.tag"), Align::Left),
+                    (tr!("col.count"), Align::Right),
+                    (tr!("col.inflow"), Align::Right),
+                    (tr!("col.outflow"), Align::Right),
+                    (tr!("col.net"), Align::Right),
+                ]);
+                for t in &totals {
+                    table.row([
+                        t.tag.clone(),
+                        t.count.to_string(),
+                        self.bank.format_money(&t.inflow),
+                        self.bank.format_money(&t.outflow),
+                        self.bank.format_money(&t.net()),
+                    ]);
+                }
+                println!("{}", table);
+                println!("{}", tr!("tags.overlap"));
+            }
+            _ => println!("{}", tr!("err.invalid_option")),
+        }
+    }
+
+    /// Show each visible account's month: opening and closing balances,
+    /// inflow, outflow, interest, fees, and net change, then offer it as
+    /// CSV.
+    fn menu_monthly_summary(&mut self) {
+        println!("\n{}\n", tr!("menu.summary"));
+        let raw = read_string_prompt(tr!("summary.month"));
+        let month = if raw.is_empty() {
+            Month::of(self.bank.today())
+        } else {
+            let Some(month) = Month::parse(&raw) else {
+                println!("{}", tr!("summary.invalid_month", raw));
+                return;
+            };
+            month
+        };
+        let mut summary = self.bank.monthly_summary(month);
+        summary.accounts.retain(|a| self.bank.find_account(&a.account).is_some_and(|acct| self.visible(acct.id)));
+        println!("{}\n", tr!("summary.title", month));
+        let mut table = Table::new(&[
+            (tr!("col.account"), Align::Left),
+            (tr!("col.opening"), Align::Right),
+            (tr!("col.inflow"), Align::Right),
+            (tr!("col.outflow"), Align::Right),
+            (tr!("col.interest"), Align::Right),
+            (tr!("col.fees"), Align::Right),
+            (tr!("col.net_change"), Align::Right),
+            (tr!("col.closing"), Align::Right),
+        ]);
+        let mut csv = Csv::new(&["month", "account", "currency", "opening", "inflow", "outflow", "interest", "fees", "net_change", "closing"]);
+        for a in &summary.accounts {
+            let amounts = [&a.opening, &a.inflow, &a.outflow, &a.interest, &a.fees, &a.net_change(), &a.closing];
+            table.row(std::iter::once(a.account.clone()).chain(amounts.iter().map(|m| self.bank.format_money(m))));
+            let dp = a.decimals as usize;
+            csv.row(
+                [month.to_string(), a.account.clone(), a.opening.currency.clone()]
+                    .into_iter()
+                    .chain(amounts.iter().map(|m| format!("{:.*}", dp, m.amount))),
+            );
+        }
+        println!("{}", table);
+        offer_csv_export(&csv);
+    }
+
+    /// Show an account's interest and the tax withheld from it over a
+    /// year, month by month, then offer it as CSV and as a printable HTML
+    /// certificate.
+    fn menu_tax_certificate(&mut self) {
+        println!("\n{}\n", tr!("menu.tax"));
+        let name = self.read_account_name(tr!("prompt.account_name"));
+        let raw = read_string_prompt(tr!("tax.year"));
+        let year = if raw.is_empty() {
+            self.bank.today().year - 1
+        } else {
+            match raw.parse::<i32>() {
+                Ok(year) if (1..=9999).contains(&year) => year,
+                _ => {
+                    println!("{}", tr!("tax.invalid_year", raw));
+                    return;
+                }
+            }
+        };
+        let Ok(certificate) = self.bank.tax_certificate(&name, year) else {
+            println!("{}", tr!("err.account_not_found"));
+            return;
+        };
+        println!("\n{}\n", tr!("tax.title", certificate.account, year));
+        let mut table = Table::new(&[
+            (tr!("col.month"), Align::Left),
+            (tr!("col.gross_interest"), Align::Right),
+            (tr!("col.tax_withheld"), Align::Right),
+            (tr!("col.net_interest"), Align::Right),
+        ]);
+        let mut csv = Csv::new(&["year", "account", "currency", "month", "gross_interest", "tax_withheld", "net_interest"]);
+        let dp = certificate.decimals as usize;
+        let rows = certificate
+            .months
+            .iter()
+            .map(|m| (m.month.to_string(), m.gross, m.withheld, m.net()))
+            .chain([(tr!("tax.total").to_string(), certificate.gross(), certificate.withheld(), certificate.net())]);
+        for (month, gross, withheld, net) in rows {
+            table.row([month.clone(), certificate.format(gross), certificate.format(withheld), certificate.format(net)]);
+            csv.row([
+                year.to_string(),
+                certificate.account.clone(),
+                certificate.currency.clone(),
+                month,
+                format!("{:.*}", dp, gross),
+                format!("{:.*}", dp, withheld),
+                format!("{:.*}", dp, net),
+            ]);
+        }
+        println!("{}", table);
+        offer_csv_export(&csv);
+        if !ask_yes_no(tr!("tax.html_prompt")) {
+            return;
+        }
+        let path = read_string_prompt(tr!("export.file"));
+        if path.is_empty() {
+            println!("{}", tr!("export.cancelled"));
+            return;
+        }
+        let written = std::fs::File::create(&path).and_then(|mut file| certificate.write_html(&mut file));
+        match written {
+            Ok(()) => println!("{}", tr!("tax.html_saved", path)),
+            Err(e) => println!("{}", tr!("export.failed", path, e)),
+        }
+    }
+
+    fn menu_snapshots(&mut self) {
+        println!("\n{}\n", tr!("menu.snapshots"));
+        let labels = self.bank.checkpoint_labels();
+        if labels.is_empty() {
+            println!("{}", tr!("snap.none"));
+        } else {
+            println!("{}", tr!("snap.list", labels.join(", ")));
+        }
+        println!("[1] {}", tr!("snap.create"));
+        println!("[2] {}", tr!("snap.restore"));
+        println!("[3] {}", tr!("snap.save"));
+        println!("[4] {}", tr!("snap.load"));
+        println!("[5] {}", tr!("snap.compact"));
+        match read_usize_prompt("") {
+            1 => {
+                let label = read_string_prompt(tr!("snap.label"));
+                self.bank.checkpoint(&label);
+                println!("{}", tr!("snap.created", label));
+            }
+            2 => {
+                let label = read_string_prompt(tr!("snap.label"));
+                println!("{}", tr!("snap.replace_warning"));
+                if !confirm_explicit(tr!("confirm.prompt")) {
+                    println!("{}", tr!("snap.cancelled"));
+                    return;
+                }
+                match self.bank.restore(&label) {
+                    Ok(()) => {
+                        self.undo.clear();
+                        println!("{}", tr!("snap.restored", label));
+                    }
+                    Err(_) => println!("{}", tr!("snap.missing", label)),
+                }
+            }
+            3 => {
+                let path = self.read_snapshot_path();
+                match persist::save(&self.bank, &path) {
+                    Ok(()) => println!("{}", tr!("snap.written", path)),
+                    Err(e) => println!("{}", tr!("snap.write_failed", e)),
+                }
+            }
+            4 => {
+                let path = self.read_snapshot_path();
+                println!("{}", tr!("snap.replace_warning"));
+                if !confirm_explicit(tr!("confirm.prompt")) {
+                    println!("{}", tr!("snap.cancelled"));
+                    return;
+                }
+                match persist::load(&path) {
+                    Ok(mut bank) => {
+                        bank.set_read_only(self.bank.is_read_only());
+                        bank.set_clock(self.bank.clock());
+                        bank.set_teller(self.bank.teller());
+                        self.bank = bank;
+                        self.undo.clear();
+                        println!("{}", tr!("snap.loaded", path));
+                        self.print_verification();
+                    }
+                    Err(e) => println!("{}", tr!("snap.load_failed", e)),
+                }
+            }
+            5 => {
+                let Some(before) = Date::parse(&read_string_prompt(tr!("snap.compact_before"))) else {
+                    println!("{}", tr!("snap.compact_bad_date"));
+                    return;
+                };
+                let path = read_string_prompt(tr!("snap.archive_path"));
+                println!("{}", tr!("snap.compact_warning"));
+                if !confirm_explicit(tr!("confirm.prompt")) {
+                    println!("{}", tr!("snap.cancelled"));
+                    return;
+                }
+                match compaction::compact(&mut self.bank, before, &path) {
+                    Ok(report) if report.accounts.is_empty() => println!("{}", tr!("snap.compact_none")),
+                    Ok(report) => {
+                        // Undo entries point into the ledgers as they were.
+                        self.undo.clear();
+                        println!("{}", tr!("snap.compacted", report.compacted(), report.accounts.len(), path));
+                    }
+                    Err(e) => println!("{}", tr!("snap.compact_failed", e)),
+                }
+            }
+            _ => println!("{}", tr!("err.invalid_option")),
+        }
+    }
+
+    fn menu_verify_ledger(&mut self) {
+        println!("\n{}\n", tr!("menu.verify"));
+        self.print_verification();
+    }
+
+    /// Run `Bank::verify` and list what it found.
+    fn print_verification(&self) {
+        let report = self.bank.verify();
+        if report.is_clean() {
+            println!("{}", tr!("verify.clean", report.accounts, report.transactions));
+            return;
+        }
+        println!("{}", tr!("verify.found", report.accounts, report.transactions, report.violations.len()));
+        for violation in &report.violations {
+            println!("- {}", violation);
+        }
+    }
+
+    /// Snapshot file prompt; a blank answer means the configured data file.
+    fn read_snapshot_path(&self) -> String {
+        let path = read_string_prompt(&tr!("snap.path", self.data_file));
+        if path.is_empty() { self.data_file.clone() } else { path }
+    }
+
+    fn menu_transaction_history(&mut self) {
+        println!("\n{}\n", tr!("menu.history"));
+        let name = self.read_account_name(tr!("prompt.account_name"));
+        let Some(acct) = self.bank.accounts.iter().find(|a| a.name == name) else {
+            println!("{}", tr!("err.account_not_found"));
+            return;
+        };
+        println!("{}", tr!("filter.hint"));
+        let query = TransactionQuery {
+            tx_type: read_tx_type_filter(tr!("filter.type")),
+            from: Date::parse(&read_string_prompt(tr!("filter.from"))),
+            to: Date::parse(&read_string_prompt(tr!("filter.to"))),
+            ..TransactionQuery::default()
+        };
+
+        let history = acct.history(&query);
+        if history.is_empty() {
+            println!("{}", tr!("history.none"));
+            return;
+        }
+        let mut table = Table::new(&[
+            (tr!("col.date"), Align::Left),
+            (tr!("col.type"), Align::Left),
+            (tr!("col.amount"), Align::Right),
+            (tr!("col.balance"), Align::Right),
+            (tr!("col.memo"), Align::Left),
+            (tr!("col.tags"), Align::Left),
+        ]);
+        for entry in &history {
+            let tx = entry.transaction;
+            table.row([
+                self.bank.local_date(tx.timestamp).to_string(),
+                tx_label(tx.tx_type()).to_string(),
+                self.bank.format_money(&Money::new(tx.amount(), &acct.currency)),
+                self.bank.format_money(&entry.balance),
+                tx.memo.to_string(),
+                tx.tags.join(),
+            ]);
+        }
+        println!("{}", table);
+        println!("{}", tr!("history.count", history.len()));
+
+        let dp = self.bank.forex.decimals(&acct.currency);
+        let mut csv = Csv::new(&["date", "type", "amount", "balance", "currency", "memo"]);
+        for entry in &history {
+            let tx = entry.transaction;
+            csv.row([
+                tx.date().to_string(),
+                format!("{:?}", tx.tx_type()).to_lowercase(),
+                tx.amount().round_dp(dp).to_string(),
+                entry.balance.amount.round_dp(dp).to_string(),
+                acct.currency.clone(),
+                tx.memo.to_string(),
+            ]);
+        }
+        offer_csv_export(&csv);
+    }
+
+    fn menu_search_transactions(&mut self) {
+        println!("\n{}\n", tr!("menu.search"));
+        println!("{}", tr!("filter.hint"));
+        let query = TransactionQuery {
+            min_amount: read_optional_amount_prompt(tr!("filter.min")),
+            max_amount: read_optional_amount_prompt(tr!("filter.max")),
+            from: Date::parse(&read_string_prompt(tr!("filter.from"))),
+            to: Date::parse(&read_string_prompt(tr!("filter.to"))),
+            tx_type: read_tx_type_filter(tr!("filter.type")),
+            memo: Some(read_string_prompt(tr!("filter.memo"))).filter(|m| !m.is_empty()),
+        };
+
+        let mut results = self.bank.search_transactions(&query);
+        results.retain(|(acct, _)| self.visible(acct.id));
+        if results.is_empty() {
+            println!("{}", tr!("history.none"));
+            return;
+        }
+        let mut table = Table::new(&[
+            (tr!("col.account"), Align::Left),
+            (tr!("col.date"), Align::Left),
+            (tr!("col.type"), Align::Left),
+            (tr!("col.amount"), Align::Right),
+            (tr!("col.memo"), Align::Left),
+        ]);
+        for (acct, tx) in &results {
+            table.row([
+                acct.name.clone(),
+                self.bank.local_date(tx.timestamp).to_string(),
+                tx_label(tx.tx_type()).to_string(),
+                self.bank.format_money(&Money::new(tx.amount(), &acct.currency)),
+                tx.memo.to_string(),
+            ]);
+        }
+        println!("{}", table);
+        println!("{}", tr!("search.count", results.len()));
+    }
+
+    fn menu_post_interest(&mut self) {
+        println!("\n{}\n", tr!("menu.post_interest"));
+        let name = read_string_prompt(tr!("post.account"));
+        let raw = read_string_prompt(tr!("post.since"));
+        // A start date posts what the balances earned since then,
+        // value-dated; none accrues a number of days ahead.
+        let (days, since) = if raw.is_empty() {
+            (read_usize_prompt(tr!("post.days")), None)
+        } else {
+            let Some(date) = Date::parse(&raw) else {
+                println!("{}", tr!("goal.bad_date"));
+                return;
+            };
+            (0, Some(date))
+        };
+        if name.is_empty() {
+            let result = match since {
+                Some(since) => self.bank.post_interest_all_since(since),
+                None => self.bank.post_interest_all(days),
+            };
+            match result {
+                Ok(posted) => {
+                    match since {
+                        Some(since) => self.journal("interest", &[("since", Some(&since.to_string()))]),
+                        None => self.journal("interest", &[("days", Some(&days.to_string()))]),
+                    }
+                    let mut table = Table::new(&[(tr!("col.account"), Align::Left), (tr!("col.interest"), Align::Right)]);
+                    for (account, amount) in &posted {
+                        table.row([account.clone(), self.bank.format_money(amount)]);
+                    }
+                    println!("{}", tr!("post.posted_all", posted.len()));
+                    print_paged(&table.to_string(), 2);
+                }
+                Err(e) => println!("{}", tr!("post.failed", e)),
+            }
+            return;
+        }
+        let result = match since {
+            Some(since) => self.bank.post_interest_since(&name, since),
+            None => self.bank.post_interest(&name, days),
+        };
+        match result {
+            Ok(posted) => {
+                println!("{}", tr!("post.posted", self.bank.format_money(&posted)));
+                if let Some(balance) = self.bank.find_account(&name).map(|acct| acct.get_balance()) {
+                    println!("{}", tr!("balance.updated", self.bank.format_money(&balance)));
+                }
+            }
+            Err(BankError::AccountNotFound(_)) => println!("{}", tr!("err.account_not_found")),
+            Err(e) => println!("{}", tr!("post.failed", e)),
+        }
+    }
+
+    /// Describe every operation and the rules behind them. Figures come from
+    /// the live bank (base currency, rates, interest, thresholds) so the
+    /// text always matches what the engine does.
+    fn menu_help(&mut self) {
+        println!("\n{}\n", tr!("menu.help"));
+        let mut table = Table::new(&[
+            (tr!("col.operation"), Align::Left),
+            (tr!("col.role"), Align::Left),
+            (tr!("col.description"), Align::Left),
+        ]);
+        for entry in MAIN_MENU {
+            let role = match entry.role {
+                Role::Teller => tr!("role.teller"),
+                Role::Admin => tr!("role.admin"),
+            };
+            table.row([tr!(entry.label), role, tr!(entry.help)]);
+        }
+        println!("{}\n", table);
+
+        let base = &self.bank.base_currency;
+        let percent = |fraction: Decimal| fraction * Decimal::from(100);
+        println!("{}", tr!("help.glossary"));
+        println!("{}", tr!("help.base", base.name, base.code));
+        if let Some(c) = self.bank.forex.currencies_detailed().into_iter().find(|c| c.code != base.code) {
+            println!("{}", tr!("help.quote", c.code, c.format_rate(c.rate), base.code));
+        }
+        println!("{}", tr!("help.cash_rate"));
+        println!("{}", tr!("help.convert", rounding_label(self.bank.rounding.strategy)));
+        let basis = self.bank.year_basis;
+        println!("{}", tr!("help.interest", basis.days(self.bank.today().year), percent(self.bank.annual_interest)));
+        match basis {
+            YearBasis::Fixed365 => println!("{}", tr!("help.day_count_365")),
+            YearBasis::Actual => println!("{}", tr!("help.day_count_actual")),
+            YearBasis::Fixed360 => println!("{}", tr!("help.day_count_360")),
+        }
+        match self.bank.interest_method {
+            InterestMethod::DailyBalance => {}
+            InterestMethod::AverageDailyBalance => println!("{}", tr!("help.average_daily_balance")),
+            InterestMethod::MinimumMonthlyBalance => println!("{}", tr!("help.minimum_monthly_balance")),
+        }
+        if self.bank.interest_grace_days > 0 {
+            println!("{}", tr!("help.grace_days", self.bank.interest_grace_days));
+        }
+        let compliance = &self.bank.compliance;
+        if let Some(limit) = compliance.large_threshold {
+            println!("{}", tr!("help.large", self.bank.format_money(&Money::new(limit, &base.code))));
+        }
+        if let Some(limit) = compliance.confirm_threshold {
+            println!("{}", tr!("help.confirm", self.bank.format_money(&Money::new(limit, &base.code))));
+        }
+        if let Some(limit) = compliance.approval_threshold {
+            println!("{}", tr!("help.approval", self.bank.format_money(&Money::new(limit, &base.code))));
+        }
+        if let Some(limit) = compliance.rate_change_limit {
+            println!("{}", tr!("help.rate_change", percent(limit)));
+        }
+        if compliance.unverified_limit.is_some() || compliance.unverified_daily_limit.is_some() {
+            let cap = |cap: Option<Decimal>| cap.map_or(tr!("help.no_cap").to_string(), |cap| self.bank.format_money(&Money::new(cap, &base.code)));
+            println!("{}", tr!("help.unverified", cap(compliance.unverified_limit), cap(compliance.unverified_daily_limit)));
+        }
+        for (code, limit) in &compliance.conversion_limits {
+            let cap = |cap: Option<Decimal>| cap.map_or(tr!("help.no_cap").to_string(), |cap| self.bank.format_money(&Money::new(cap, code)));
+            println!("{}", tr!("help.conversion_limit", code, cap(limit.per_transaction), cap(limit.per_day)));
+        }
+        for (target, p) in &compliance.limit_profiles {
+            let cap = |cap: Option<Decimal>| cap.map_or(tr!("help.no_cap").to_string(), |cap| self.bank.format_money(&Money::new(cap, &base.code)));
+            println!("{}", tr!("help.limit_profile", target, cap(p.max_deposit), cap(p.max_withdrawal), cap(p.max_daily_conversion)));
+        }
+    }
+
+    fn menu_rounding(&mut self) {
+        println!("\n{}\n", tr!("menu.rounding"));
+        println!("{}", tr!("rounding.current", rounding_label(self.bank.rounding.strategy)));
+        if self.bank.rounding_residue.is_empty() {
+            println!("{}", tr!("rounding.none"));
+        } else {
+            let (rows, total) = self.bank.rounding_effect();
+            let mut table = Table::new(&[
+                (tr!("col.currency"), Align::Left),
+                (tr!("col.residue"), Align::Right),
+                (tr!("col.value"), Align::Right),
+            ]);
+            for (code, residue, value) in &rows {
+                table.row([code.clone(), residue.to_string(), value.as_ref().map_or("-".to_string(), |v| v.to_string())]);
+            }
+            println!("{}", table);
+            println!("{}", tr!("rounding.effect", total));
+        }
+        if self.bank.is_read_only() || !ask_yes_no(tr!("rounding.ask")) {
+            return;
+        }
+        println!("[1] {}", tr!("rounding.even"));
+        println!("[2] {}", tr!("rounding.half_away"));
+        println!("[3] {}", tr!("rounding.to_zero"));
+        println!("[4] {}", tr!("rounding.away"));
+        let strategy = match read_usize_prompt("") {
+            1 => RoundingStrategy::MidpointNearestEven,
+            2 => RoundingStrategy::MidpointAwayFromZero,
+            3 => RoundingStrategy::ToZero,
+            4 => RoundingStrategy::AwayFromZero,
+            _ => {
+                println!("{}", tr!("err.invalid_option"));
+                return;
+            }
+        };
+        self.bank.rounding.strategy = strategy;
+        println!("{}", tr!("rounding.set", rounding_label(strategy)));
+    }
+}
+
+/// Signed percentage change from `old` to `new`, e.g. "+12.50" or "-3.00";
+/// "n/a" when `old` is zero.
+fn percent_change(old: Decimal, new: Decimal) -> String {
+    let change = new
+        .checked_sub(old)
+        .and_then(|delta| delta.checked_div(old))
+        .and_then(|ratio| ratio.checked_mul(Decimal::from(100)));
+    match change {
+        Some(pct) if pct.is_sign_negative() => format!("{:.2}", pct),
+        Some(pct) => format!("+{:.2}", pct),
+        None => String::from("n/a"),
+    }
+}
+
+/// Localized name of a transaction type for tables.
+fn tx_label(tx_type: TransactionType) -> &'static str {
+    match tx_type {
+        TransactionType::Deposit => tr!("tx.deposit"),
+        TransactionType::Withdraw => tr!("tx.withdraw"),
+    }
+}
+
+fn id_type_label(id_type: IdType) -> &'static str {
+    match id_type {
+        IdType::Passport => tr!("kyc.type.passport"),
+        IdType::DriversLicense => tr!("kyc.type.drivers_license"),
+        IdType::NationalId => tr!("kyc.type.national_id"),
+        IdType::Other => tr!("kyc.type.other"),
+    }
+}
+
+fn status_label(status: VerificationStatus) -> &'static str {
+    match status {
+        VerificationStatus::Unverified => tr!("kyc.status.unverified"),
+        VerificationStatus::Pending => tr!("kyc.status.pending"),
+        VerificationStatus::Verified => tr!("kyc.status.verified"),
+        VerificationStatus::Rejected => tr!("kyc.status.rejected"),
+    }
+}
+
+/// "KYC: Verified | ID: Passport P1234567 | Address: ...".
+fn kyc_summary(customer: &Customer) -> String {
+    let id = customer
+        .identification
+        .as_ref()
+        .map_or(tr!("kyc.no_id").to_string(), |id| format!("{} {}", id_type_label(id.id_type), id.id_number));
+    let address = if customer.address.is_empty() { "-" } else { &customer.address };
+    tr!("kyc.summary", status_label(customer.verification), id, address)
+}
+
+fn rounding_label(strategy: RoundingStrategy) -> &'static str {
+    match strategy {
+        RoundingStrategy::MidpointNearestEven => tr!("rounding.even"),
+        RoundingStrategy::MidpointAwayFromZero => tr!("rounding.half_away"),
+        RoundingStrategy::ToZero => tr!("rounding.to_zero"),
+        RoundingStrategy::AwayFromZero => tr!("rounding.away"),
+    }
+}
+
+/// A limit order's expiry date: `Some(None)` for a blank answer (good till
+/// cancelled), `None` after reporting an unreadable date.
+/// A percentage as a fraction, `default` percent when left blank.
+fn read_percent(prompt: &str, default: Decimal, allow_negative: bool) -> Decimal {
+    loop {
+        let raw = read_string_prompt(prompt);
+        if raw.is_empty() {
+            return default / Decimal::from(100);
+        }
+        match raw.parse::<Decimal>() {
+            Ok(v) if allow_negative || v >= Decimal::ZERO => return v / Decimal::from(100),
+            _ => println!("{}", tr!("sim.bad_number")),
+        }
+    }
+}
+
+/// A moving average for a limit order to wait for, e.g. `sma:20`; blank
+/// for none. `None` if what was typed is not one.
+fn read_average_prompt() -> Option<Option<MovingAverage>> {
+    let raw = read_string_prompt(tr!("limit.average"));
+    if raw.is_empty() {
+        return Some(None);
+    }
+    let average = MovingAverage::parse(&raw).filter(|a| a.days <= RATE_HISTORY_DAYS);
+    if average.is_none() {
+        println!("{}", tr!("limit.bad_average"));
+    }
+    average.map(Some)
+}
+
+fn read_expiry_prompt() -> Option<Option<Date>> {
+    let raw = read_string_prompt(tr!("limit.expires"));
+    if raw.is_empty() {
+        return Some(None);
+    }
+    let date = Date::parse(&raw);
+    if date.is_none() {
+        println!("{}", tr!("goal.bad_date"));
+    }
+    date.map(Some)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::api::forex::Forex;
+
+    fn labels(read_only: bool) -> Vec<&'static str> {
+        let forex = Forex::builder().set_base_currency("PHP", "Philippine Peso").build();
+        let mut bank = Bank::builder().set_forex(forex).build();
+        bank.set_read_only(read_only);
+        let mut app = ConsoleApp::new(bank, "unused.json", EventBus::new());
+        app.role = Role::Admin;
+        app.menu_entries().iter().map(|e| e.label).collect()
+    }
+
+    #[test]
+    fn read_only_sessions_leave_out_the_menus_that_change_the_bank() {
+        let changing = ["menu.customers", "menu.aliases", "menu.archive", "menu.snapshots", "menu.undo"];
+        let writable = labels(false);
+        let read_only = labels(true);
+        for label in changing {
+            assert!(writable.contains(&label), "{} missing from a writable session", label);
+            assert!(!read_only.contains(&label), "{} offered under --read-only", label);
+        }
+        assert!(read_only.contains(&"menu.help"));
+    }
+}
@@ -1,12 +1,15 @@
 use std::io::{self, Write};
+use std::process::{Command, Stdio};
+use std::sync::OnceLock;
 
+use crate::api::account::TransactionType;
 use crate::api::bank::Bank;
-
-pub fn convert_amount(bank: &Bank, src_code: &str, dst_code: &str, amount: f64) -> Option<f64> {
-    let src_rate = bank.forex.get_rate(src_code).copied()?;
-    let dst_rate = bank.forex.get_rate(dst_code).copied()?;
-    Some(amount * src_rate / dst_rate)
-}
+use crate::api::decimal::Decimal;
+use crate::api::format::{evaluate_amount, parse_amount, AmountError, Locale};
+use crate::api::search::edit_distance;
+use crate::view::export::Csv;
+use crate::view::i18n::tr;
+use crate::view::line_editor;
 
 pub fn currency_menu_lists(bank: &Bank) -> (Vec<String>, Vec<String>) {
     let mut codes = Vec::new();
@@ -24,48 +27,281 @@ pub fn print_currency_menu(names: &[String]) {
     }
 }
 
+/// Read a currency from the menu printed by `print_currency_menu`, asking
+/// again until `match_currency` resolves the input.
+pub fn read_currency_prompt(prompt: &str, codes: &[String], names: &[String]) -> String {
+    loop {
+        match match_currency(&read_string_prompt(prompt), codes, names) {
+            Ok(code) => return code,
+            Err(msg) => println!("{}", msg),
+        }
+    }
+}
+
+/// Resolve a currency choice: a menu number, a code in any case ("usd"), or
+/// a fragment of exactly one menu name ("yen"). Unknown input names the
+/// closest code when it is a single typo away ("USX" -> USD).
+pub fn match_currency(input: &str, codes: &[String], names: &[String]) -> Result<String, String> {
+    if input.is_empty() {
+        return Err(tr!("err.invalid_selection").to_string());
+    }
+    if let Ok(n) = input.parse::<usize>() {
+        return codes
+            .get(n.wrapping_sub(1))
+            .cloned()
+            .ok_or_else(|| tr!("err.invalid_selection").to_string());
+    }
+
+    let upper = input.to_uppercase();
+    if let Some(code) = codes.iter().find(|c| **c == upper) {
+        return Ok(code.clone());
+    }
+
+    let lower = input.to_lowercase();
+    let hits: Vec<&String> = codes
+        .iter()
+        .zip(names)
+        .filter(|(_, name)| name.to_lowercase().contains(&lower))
+        .map(|(code, _)| code)
+        .collect();
+    match hits.as_slice() {
+        [code] => return Ok((*code).clone()),
+        [] => {}
+        many => {
+            let list: Vec<&str> = many.iter().map(|c| c.as_str()).collect();
+            return Err(tr!("currency.ambiguous", input, list.join(", ")));
+        }
+    }
+
+    match codes.iter().find(|c| edit_distance(c, &upper) == 1) {
+        Some(code) => Err(tr!("currency.suggest", input, code)),
+        None => Err(tr!("currency.unknown", input)),
+    }
+}
+
+/// Raised from any prompt when input ends (stdin closed, Ctrl-D on an empty
+/// line, or Ctrl-C in the line editor). It unwinds with `resume_unwind`, which
+/// skips the panic message, and `ConsoleApp::run` catches it to save the
+/// session and say goodbye.
+pub struct EndOfInput;
+
+fn end_of_input() -> ! {
+    std::panic::resume_unwind(Box::new(EndOfInput))
+}
+
+/// Read one trimmed line. On a terminal this goes through `line_editor`
+/// (history, editing, Tab completion); piped input is read as plain lines.
 pub fn read_string_prompt(prompt: &str) -> String {
+    match line_editor::read_line(prompt, false) {
+        Some(Some(line)) => return line.trim().to_string(),
+        Some(None) => end_of_input(),
+        None => {}
+    }
     print!("{}", prompt);
     let _ = io::stdout().flush();
     let mut s = String::new();
-    io::stdin().read_line(&mut s).ok();
-    s.trim().to_string()
+    match io::stdin().read_line(&mut s) {
+        Ok(0) | Err(_) => end_of_input(),
+        Ok(_) => s.trim().to_string(),
+    }
+}
+
+/// Read a line without echoing it (PINs/passphrases). Echo is toggled via
+/// `stty` on Unix terminals; if that is unavailable the input stays visible.
+pub fn read_masked_prompt(prompt: &str) -> String {
+    match line_editor::read_line(prompt, true) {
+        Some(Some(line)) => return line.trim().to_string(),
+        Some(None) => end_of_input(),
+        None => {}
+    }
+    let hidden = set_echo(false).then_some(EchoOff);
+    let s = read_string_prompt(prompt);
+    drop(hidden);
+    s
+}
+
+/// Turns echo back on when dropped, including when input ends mid-prompt.
+struct EchoOff;
+
+impl Drop for EchoOff {
+    fn drop(&mut self) {
+        set_echo(true);
+        println!();
+    }
+}
+
+fn set_echo(on: bool) -> bool {
+    Command::new("stty")
+        .arg(if on { "echo" } else { "-echo" })
+        .stdin(Stdio::inherit())
+        .stderr(Stdio::null())
+        .status()
+        .is_ok_and(|st| st.success())
 }
 
 pub fn read_usize_prompt(prompt: &str) -> usize {
     loop {
         let s = read_string_prompt(prompt);
-        if let Ok(v) = s.parse::<usize>() {
-            if v > 0 {
-                return v;
-            }
+        if let Ok(v) = s.parse::<usize>()
+            && v > 0
+        {
+            return v;
+        }
+        println!("{}", tr!("input.number"));
+    }
+}
+
+static AMOUNT_LOCALE: OnceLock<Locale> = OnceLock::new();
+
+/// Select the locale whose separators typed amounts use (see
+/// `parse_amount`), normally the bank's. Only the first call has any
+/// effect.
+pub fn set_amount_locale(locale: Locale) {
+    let _ = AMOUNT_LOCALE.set(locale);
+}
+
+/// `input` as an amount or an arithmetic expression over amounts (see
+/// `evaluate_amount`), echoing the result of an expression so it can be
+/// checked before it is used.
+fn typed_amount(input: &str) -> Result<Decimal, AmountError> {
+    let locale = AMOUNT_LOCALE.get().copied().unwrap_or_default();
+    let value = evaluate_amount(input, locale)?;
+    if parse_amount(input, locale).is_err() {
+        println!("{}", tr!("input.evaluated", value));
+    }
+    Ok(value)
+}
+
+/// Read an amount greater than zero, typed as `parse_amount` reads it
+/// ("1,000.50", "₱1000", "1k", "1e3") or as a sum such as "1500+350*2",
+/// saying what is wrong until one is.
+pub fn read_decimal_prompt(prompt: &str) -> Decimal {
+    loop {
+        match typed_amount(&read_string_prompt(prompt)) {
+            Ok(v) if v > Decimal::ZERO => return v,
+            Ok(_) => println!("{}", tr!("input.amount")),
+            Err(e) => println!("{}", tr!("input.bad_amount", e)),
         }
-        println!("Please enter a valid number > 0.");
     }
 }
 
-pub fn read_f64_prompt(prompt: &str) -> f64 {
+/// Like `read_decimal_prompt`, but a blank answer gives `None` and zero is
+/// accepted, for optional filters and limits.
+pub fn read_optional_amount_prompt(prompt: &str) -> Option<Decimal> {
     loop {
         let s = read_string_prompt(prompt);
-        if let Ok(v) = s.parse::<f64>() {
-            if v > 0.0 {
-                return v;
-            }
+        if s.is_empty() {
+            return None;
+        }
+        match typed_amount(&s) {
+            Ok(v) if v >= Decimal::ZERO => return Some(v),
+            Ok(_) => println!("{}", tr!("input.amount")),
+            Err(e) => println!("{}", tr!("input.bad_amount", e)),
         }
-        println!("Please enter a valid amount > 0.");
     }
 }
 
+/// Like `read_decimal_prompt`, but zero and negative numbers are accepted
+/// too, for rates that may be switched off or below zero.
+pub fn read_signed_decimal_prompt(prompt: &str) -> Decimal {
+    loop {
+        match typed_amount(&read_string_prompt(prompt)) {
+            Ok(v) => return v,
+            Err(e) => println!("{}", tr!("input.bad_amount", e)),
+        }
+    }
+}
+
+/// Terminal height from `stty size`, then `$LINES`, then 24.
+fn terminal_rows() -> usize {
+    let stty = Command::new("stty")
+        .arg("size")
+        .stdin(Stdio::inherit())
+        .stderr(Stdio::null())
+        .output()
+        .ok()
+        .and_then(|out| String::from_utf8(out.stdout).ok())
+        .and_then(|s| s.split_whitespace().next().and_then(|r| r.parse().ok()));
+    stty.or_else(|| std::env::var("LINES").ok().and_then(|l| l.parse().ok()))
+        .filter(|&rows| rows > 0)
+        .unwrap_or(24)
+}
+
+/// `[#####.....]  50%`-style bar for a fraction from 0 to 1, `width`
+/// cells wide.
+pub fn progress_bar(fraction: Decimal, width: usize) -> String {
+    let percent = (fraction.max(Decimal::ZERO).min(Decimal::ONE) * Decimal::from(100)).round_dp(0);
+    let filled = format!("{}", percent).parse::<usize>().unwrap_or(0) * width / 100;
+    format!("[{}{}] {:>3}%", "█".repeat(filled), "░".repeat(width - filled), percent)
+}
+
+/// Body rows that fit on one page below a table heading and the pager prompt.
+pub fn page_size() -> usize {
+    terminal_rows().saturating_sub(4).max(5)
+}
+
+/// Print `text` a page at a time when it is taller than the terminal. The
+/// first `header` lines (a table's heading and rule) repeat on every page;
+/// Enter shows the next page and `q` stops.
+pub fn print_paged(text: &str, header: usize) {
+    let lines: Vec<&str> = text.lines().collect();
+    let (head, body) = lines.split_at(header.min(lines.len()));
+    let page = page_size();
+    for (i, chunk) in body.chunks(page).enumerate() {
+        if i > 0 && read_string_prompt(tr!("page.more")).eq_ignore_ascii_case("q") {
+            return;
+        }
+        for line in head.iter().chain(chunk) {
+            println!("{}", line);
+        }
+    }
+}
+
+/// Offer "Export to CSV (Y/N)?" after a report and write `csv` to a file
+/// the user names; a blank name cancels.
+pub fn offer_csv_export(csv: &Csv) {
+    if !ask_yes_no(tr!("export.prompt")) {
+        return;
+    }
+    let path = read_string_prompt(tr!("export.file"));
+    if path.is_empty() {
+        println!("{}", tr!("export.cancelled"));
+        return;
+    }
+    match csv.save(&path) {
+        Ok(()) => println!("{}", tr!("export.saved", csv.row_count(), path)),
+        Err(e) => println!("{}", tr!("export.failed", path, e)),
+    }
+}
+
+/// Yes/no prompt. Enter means yes; English (y/yes, n/no) and Filipino
+/// (o/oo, h/hindi) answers are accepted in either language.
 pub fn ask_yes_no(prompt: &str) -> bool {
     loop {
         let s = read_string_prompt(prompt);
         let s = s.to_lowercase();
-        if s.is_empty() || s == "y" || s == "yes" {
+        if s.is_empty() || matches!(s.as_str(), "y" | "yes" | "o" | "oo") {
             return true;
-        } else if s == "n" || s == "no" {
+        } else if matches!(s.as_str(), "n" | "no" | "h" | "hindi") {
             return false;
         } else {
-            println!("Please enter Y or N.");
+            println!("{}", tr!("input.yes_no"));
         }
     }
 }
+
+/// Explicit confirmation for large or destructive operations: only a typed
+/// yes (y/yes/o/oo) proceeds; Enter or anything else cancels.
+pub fn confirm_explicit(prompt: &str) -> bool {
+    matches!(read_string_prompt(prompt).to_lowercase().as_str(), "y" | "yes" | "o" | "oo")
+}
+
+/// Optional transaction-type filter: "deposit"/"d" or "withdraw"/"w";
+/// anything else (including blank) means no filter.
+pub fn read_tx_type_filter(prompt: &str) -> Option<TransactionType> {
+    match read_string_prompt(prompt).to_lowercase().as_str() {
+        "deposit" | "d" => Some(TransactionType::Deposit),
+        "withdraw" | "w" => Some(TransactionType::Withdraw),
+        _ => None,
+    }
+}
@@ -0,0 +1,61 @@
+use std::fmt;
+use std::fs;
+use std::io;
+
+/// Spreadsheet-ready CSV built from structured rows, shared by the console
+/// screens that offer "Export to CSV".
+/// - Cells containing commas, quotes, or line breaks are quoted, with inner
+///   quotes doubled (RFC 4180).
+/// - Amounts should be plain numbers with a separate currency column, not
+///   the symbol-formatted text shown on screen.
+///
+/// Build with `Csv::new(&["day", ...])`, add rows with `row`, then `save`.
+#[derive(Debug, Clone)]
+pub struct Csv {
+    headers: Vec<String>,
+    rows: Vec<Vec<String>>,
+}
+
+impl Csv {
+    pub fn new(headers: &[&str]) -> Self {
+        Self {
+            headers: headers.iter().map(|h| h.to_string()).collect(),
+            rows: Vec::new(),
+        }
+    }
+
+    /// Append a row. Missing trailing cells are left blank; extra cells are
+    /// dropped.
+    pub fn row<S: ToString>(&mut self, cells: impl IntoIterator<Item = S>) {
+        let mut cells: Vec<String> = cells.into_iter().map(|c| c.to_string()).collect();
+        cells.resize(self.headers.len(), String::new());
+        self.rows.push(cells);
+    }
+
+    pub fn row_count(&self) -> usize {
+        self.rows.len()
+    }
+
+    /// Write the header and all rows to `path`, replacing any existing file.
+    pub fn save(&self, path: &str) -> io::Result<()> {
+        fs::write(path, self.to_string())
+    }
+}
+
+impl fmt::Display for Csv {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for cells in std::iter::once(&self.headers).chain(&self.rows) {
+            let line: Vec<String> = cells.iter().map(|c| escape(c)).collect();
+            writeln!(f, "{}", line.join(","))?;
+        }
+        Ok(())
+    }
+}
+
+fn escape(cell: &str) -> String {
+    if cell.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", cell.replace('"', "\"\""))
+    } else {
+        cell.to_string()
+    }
+}
@@ -0,0 +1,570 @@
+use std::collections::BTreeMap;
+
+use crate::api::decimal::Decimal;
+use crate::api::event::BankEvent;
+use crate::view::cli::{parse, CliError, Command, COMMANDS};
+use crate::view::json::Json;
+use crate::view::webhook::event_json;
+
+/// Which root type an operation selects from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum OperationKind {
+    Query,
+    Mutation,
+    Subscription,
+}
+
+/// One field of a selection set: `alias: name(args) { selection }`.
+#[derive(Debug, Clone)]
+struct Field {
+    alias: Option<String>,
+    name: String,
+    args: Vec<(String, Value)>,
+    selection: Vec<Field>,
+}
+
+impl Field {
+    /// The key the field's value is answered under.
+    fn key(&self) -> &str {
+        self.alias.as_deref().unwrap_or(&self.name)
+    }
+}
+
+/// An argument value: a `$variable` or a literal.
+#[derive(Debug, Clone)]
+enum Value {
+    Variable(String),
+    Literal(Json),
+}
+
+/// A parsed document's single operation, with its declared variables and
+/// their defaults.
+struct Operation {
+    kind: OperationKind,
+    variables: Vec<(String, Option<Json>)>,
+    selection: Vec<Field>,
+}
+
+/// Answer one GraphQL request over the bank, as `{"data": ..., "errors":
+/// [...]}`. The supported subset: one operation per document (no fragments
+/// or directives), variables, aliases, and nested selections.
+/// - Root fields are the CLI commands and their arguments the command's
+///   options, e.g. `deposit(account: "Alice", amount: "100")`. Queries may
+///   only use commands that leave the bank unchanged; mutations may use any,
+///   and run in order.
+/// - `run` executes a root field's command and returns its `--json` value;
+///   the field's selection picks from that value, with camelCase names
+///   matching snake_case keys (`annualInterest` for `annual_interest`).
+///
+/// A field that fails is answered as null with an entry in `errors`; a
+/// document that cannot be run at all gets only `errors`.
+pub fn execute(document: &str, variables: Option<&Json>, mut run: impl FnMut(&Command) -> Result<Json, CliError>) -> Json {
+    let op = match Parser::new(document).document() {
+        Ok(op) => op,
+        Err(e) => return errors_only(e),
+    };
+    if op.kind == OperationKind::Subscription {
+        return errors_only(String::from("subscriptions are served over a WebSocket on /graphql"));
+    }
+    let vars = match bind(&op, variables) {
+        Ok(vars) => vars,
+        Err(e) => return errors_only(e),
+    };
+    let mut data = Vec::new();
+    let mut errors = Vec::new();
+    for field in &op.selection {
+        let value = resolve(op.kind, field, &vars, &mut run).unwrap_or_else(|msg| {
+            errors.push(Json::object([("message", Json::str(msg)), ("path", Json::Array(vec![Json::str(field.key())]))]));
+            Json::Null
+        });
+        data.push((field.key().to_string(), value));
+    }
+    let mut reply = vec![("data", Json::Object(data))];
+    if !errors.is_empty() {
+        reply.push(("errors", Json::Array(errors)));
+    }
+    Json::object(reply)
+}
+
+/// `{"errors": [{"message": msg}]}`, for a request that never ran.
+pub fn errors_only(msg: String) -> Json {
+    Json::object([("errors", Json::Array(vec![Json::object([("message", Json::str(msg))])]))])
+}
+
+fn resolve(
+    kind: OperationKind,
+    field: &Field,
+    vars: &BTreeMap<String, Json>,
+    run: &mut impl FnMut(&Command) -> Result<Json, CliError>,
+) -> Result<Json, String> {
+    if field.name == "__typename" {
+        return Ok(Json::str(if kind == OperationKind::Mutation { "Mutation" } else { "Query" }));
+    }
+    if field.name == "help" || !COMMANDS.contains(&field.name.as_str()) {
+        return Err(format!("unknown field {}", field.name));
+    }
+    let command = parse(&[field.name.as_str()], flags(&field.args, vars)?).map_err(|e| e.to_string())?;
+    if kind == OperationKind::Query && command.mutates() {
+        return Err(format!("{} changes the bank; send it in a mutation", field.name));
+    }
+    let value = run(&command).map_err(|e| e.to_string())?;
+    select(&value, &field.selection, &field.name)
+}
+
+/// A `subscription { rateChanged(code: "USD") { code old new } }`
+/// operation: each exchange rate change (optionally only for `code`) is
+/// answered with the selected fields of its `/events` message.
+pub struct Subscription {
+    field: Field,
+    code: Option<String>,
+}
+
+impl Subscription {
+    /// Parse and check a subscription document. `rateChanged` is the only
+    /// root field, and it must be the only one.
+    pub fn parse(document: &str, variables: Option<&Json>) -> Result<Self, String> {
+        let op = Parser::new(document).document()?;
+        if op.kind != OperationKind::Subscription {
+            return Err(String::from("expected a subscription; send queries and mutations with POST /graphql"));
+        }
+        let [field] = op.selection.as_slice() else {
+            return Err(String::from("a subscription selects exactly one root field"));
+        };
+        if field.name != "rateChanged" {
+            return Err(format!("unknown subscription field {}; available: rateChanged", field.name));
+        }
+        let mut flags = flags(&field.args, &bind(&op, variables)?)?;
+        let code = flags.remove("code");
+        if let Some(name) = flags.keys().next() {
+            return Err(format!("unknown argument {} on rateChanged", name));
+        }
+        // Check the selection now rather than on the first event.
+        let sample = BankEvent::RateChanged { code: String::new(), old: Decimal::ZERO, new: Decimal::ZERO };
+        select(&event_json(&sample), &field.selection, &field.name)?;
+        Ok(Self { field: field.clone(), code })
+    }
+
+    /// The `data` payload for `event`, or `None` if this subscription does
+    /// not cover it.
+    pub fn data(&self, event: &BankEvent) -> Option<Json> {
+        let BankEvent::RateChanged { code, .. } = event else { return None };
+        if self.code.as_ref().is_some_and(|c| !c.eq_ignore_ascii_case(code)) {
+            return None;
+        }
+        let value = select(&event_json(event), &self.field.selection, &self.field.name).ok()?;
+        Some(Json::Object(vec![(self.field.key().to_string(), value)]))
+    }
+}
+
+/// Pick `selection` out of `value`, field by field; lists are mapped
+/// element-wise. Objects need a selection and scalars must not have one.
+fn select(value: &Json, selection: &[Field], path: &str) -> Result<Json, String> {
+    match value {
+        Json::Array(items) => items.iter().map(|item| select(item, selection, path)).collect::<Result<_, _>>().map(Json::Array),
+        Json::Object(_) if selection.is_empty() => Err(format!("{} is an object; select its fields", path)),
+        Json::Object(_) => selection
+            .iter()
+            .map(|field| {
+                let inner = format!("{}.{}", path, field.name);
+                if !field.args.is_empty() {
+                    return Err(format!("{} takes no arguments", inner));
+                }
+                let found = value.get(&field.name).or_else(|| value.get(&snake_case(&field.name)));
+                let found = found.ok_or_else(|| format!("{} has no field {}", path, field.name))?;
+                Ok((field.key().to_string(), select(found, &field.selection, &inner)?))
+            })
+            .collect::<Result<_, _>>()
+            .map(Json::Object),
+        _ if !selection.is_empty() => Err(format!("{} has no fields to select", path)),
+        scalar => Ok(scalar.clone()),
+    }
+}
+
+/// `annualInterest` -> `annual_interest`.
+fn snake_case(name: &str) -> String {
+    let mut out = String::new();
+    for c in name.chars() {
+        if c.is_ascii_uppercase() {
+            out.push('_');
+            out.push(c.to_ascii_lowercase());
+        } else {
+            out.push(c);
+        }
+    }
+    out
+}
+
+/// The operation's variables: the request's value, else the declared
+/// default, else null.
+fn bind(op: &Operation, given: Option<&Json>) -> Result<BTreeMap<String, Json>, String> {
+    match given {
+        None | Some(Json::Null) | Some(Json::Object(_)) => {}
+        Some(_) => return Err(String::from("variables must be an object")),
+    }
+    Ok(op
+        .variables
+        .iter()
+        .map(|(name, default)| {
+            let value = given.and_then(|g| g.get(name)).or(default.as_ref()).cloned().unwrap_or(Json::Null);
+            (name.clone(), value)
+        })
+        .collect())
+}
+
+/// Turn field arguments into command options, like JSON-RPC params:
+/// strings, numbers, and booleans are taken as their text and null means
+/// absent.
+fn flags(args: &[(String, Value)], vars: &BTreeMap<String, Json>) -> Result<BTreeMap<String, String>, String> {
+    let mut flags = BTreeMap::new();
+    for (name, value) in args {
+        let value = match value {
+            Value::Variable(var) => vars.get(var).ok_or_else(|| format!("variable ${} is not defined", var))?,
+            Value::Literal(json) => json,
+        };
+        match value {
+            Json::Null => {}
+            Json::Str(s) | Json::Num(s) => {
+                flags.insert(name.clone(), s.clone());
+            }
+            Json::Bool(b) => {
+                flags.insert(name.clone(), b.to_string());
+            }
+            _ => return Err(format!("argument {} must be a string, number, or boolean", name)),
+        }
+    }
+    Ok(flags)
+}
+
+/// How deeply selections, list and object values, and list types may nest
+/// in a document; deeper ones are refused before they can exhaust the
+/// stack.
+const MAX_DEPTH: usize = 32;
+
+/// Recursive-descent reader for GraphQL documents; `pos` indexes `chars`
+/// and `depth` counts the nesting levels open (see `MAX_DEPTH`). Commas,
+/// whitespace, and `#` comments are skipped between tokens.
+struct Parser {
+    chars: Vec<char>,
+    pos: usize,
+    depth: usize,
+}
+
+impl Parser {
+    fn new(text: &str) -> Self {
+        Self { chars: text.chars().collect(), pos: 0, depth: 0 }
+    }
+
+    /// Open a nesting level, failing past `MAX_DEPTH`. Each caller closes it
+    /// with `ascend` once the nested part is read.
+    fn descend(&mut self) -> Result<(), String> {
+        self.depth += 1;
+        match self.depth > MAX_DEPTH {
+            true => Err(format!("nested more than {} levels deep at {}", MAX_DEPTH, self.pos)),
+            false => Ok(()),
+        }
+    }
+
+    fn ascend(&mut self) {
+        self.depth -= 1;
+    }
+
+    /// The next character after anything ignorable.
+    fn peek(&mut self) -> Option<char> {
+        while let Some(&c) = self.chars.get(self.pos) {
+            match c {
+                '#' => {
+                    while self.chars.get(self.pos).is_some_and(|&c| c != '\n') {
+                        self.pos += 1;
+                    }
+                }
+                c if c.is_whitespace() || c == ',' || c == '\u{feff}' => self.pos += 1,
+                _ => break,
+            }
+        }
+        self.chars.get(self.pos).copied()
+    }
+
+    fn eat(&mut self, want: char) -> bool {
+        let found = self.peek() == Some(want);
+        if found {
+            self.pos += 1;
+        }
+        found
+    }
+
+    fn expect(&mut self, want: char) -> Result<(), String> {
+        match self.peek() {
+            _ if self.eat(want) => Ok(()),
+            Some(c) => Err(format!("expected {:?} but found {:?} at {}", want, c, self.pos)),
+            None => Err(format!("expected {:?} but the document ended", want)),
+        }
+    }
+
+    fn name(&mut self) -> Result<String, String> {
+        match self.peek() {
+            Some(c) if c == '_' || c.is_ascii_alphabetic() => {}
+            Some(c) => return Err(format!("expected a name but found {:?} at {}", c, self.pos)),
+            None => return Err(String::from("expected a name but the document ended")),
+        }
+        let start = self.pos;
+        while self.chars.get(self.pos).is_some_and(|&c| c == '_' || c.is_ascii_alphanumeric()) {
+            self.pos += 1;
+        }
+        Ok(self.chars[start..self.pos].iter().collect())
+    }
+
+    fn document(&mut self) -> Result<Operation, String> {
+        let mut variables = Vec::new();
+        let kind = if self.peek() == Some('{') {
+            OperationKind::Query
+        } else {
+            let kind = match self.name()?.as_str() {
+                "query" => OperationKind::Query,
+                "mutation" => OperationKind::Mutation,
+                "subscription" => OperationKind::Subscription,
+                "fragment" => return Err(String::from("fragments are not supported")),
+                other => return Err(format!("expected query, mutation, or subscription but found {}", other)),
+            };
+            if self.peek().is_some_and(|c| c == '_' || c.is_ascii_alphabetic()) {
+                self.name()?;
+            }
+            if self.eat('(') {
+                while !self.eat(')') {
+                    self.expect('$')?;
+                    let name = self.name()?;
+                    self.expect(':')?;
+                    self.type_ref()?;
+                    let default = if self.eat('=') { Some(self.literal()?) } else { None };
+                    variables.push((name, default));
+                }
+            }
+            kind
+        };
+        self.no_directives()?;
+        let selection = self.selection_set()?;
+        match self.peek() {
+            None => Ok(Operation { kind, variables, selection }),
+            Some(_) => Err(String::from("only one operation per document is supported")),
+        }
+    }
+
+    /// Skip a variable's type, e.g. `String!` or `[Int]`; the command
+    /// checks the value itself.
+    fn type_ref(&mut self) -> Result<(), String> {
+        if self.eat('[') {
+            self.descend()?;
+            self.type_ref()?;
+            self.expect(']')?;
+            self.ascend();
+        } else {
+            self.name()?;
+        }
+        self.eat('!');
+        Ok(())
+    }
+
+    fn no_directives(&mut self) -> Result<(), String> {
+        match self.peek() {
+            Some('@') => Err(String::from("directives are not supported")),
+            _ => Ok(()),
+        }
+    }
+
+    fn selection_set(&mut self) -> Result<Vec<Field>, String> {
+        self.expect('{')?;
+        self.descend()?;
+        let mut fields = Vec::new();
+        while !self.eat('}') {
+            fields.push(self.field()?);
+        }
+        if fields.is_empty() {
+            return Err(format!("empty selection at {}", self.pos));
+        }
+        self.ascend();
+        Ok(fields)
+    }
+
+    fn field(&mut self) -> Result<Field, String> {
+        if self.peek() == Some('.') {
+            return Err(String::from("fragments are not supported"));
+        }
+        let first = self.name()?;
+        let (alias, name) = if self.eat(':') { (Some(first), self.name()?) } else { (None, first) };
+        let mut args = Vec::new();
+        if self.eat('(') {
+            while !self.eat(')') {
+                let arg = self.name()?;
+                self.expect(':')?;
+                let value = if self.eat('$') { Value::Variable(self.name()?) } else { Value::Literal(self.literal()?) };
+                args.push((arg, value));
+            }
+        }
+        self.no_directives()?;
+        let selection = if self.peek() == Some('{') { self.selection_set()? } else { Vec::new() };
+        Ok(Field { alias, name, args, selection })
+    }
+
+    /// A constant value. Enum values are read as strings; numbers keep
+    /// their source text, as in `Json::parse`.
+    fn literal(&mut self) -> Result<Json, String> {
+        match self.peek() {
+            Some('"') => self.string().map(Json::Str),
+            Some('[') => {
+                self.pos += 1;
+                self.descend()?;
+                let mut items = Vec::new();
+                while !self.eat(']') {
+                    items.push(self.literal()?);
+                }
+                self.ascend();
+                Ok(Json::Array(items))
+            }
+            Some('{') => {
+                self.pos += 1;
+                self.descend()?;
+                let mut fields = Vec::new();
+                while !self.eat('}') {
+                    let key = self.name()?;
+                    self.expect(':')?;
+                    fields.push((key, self.literal()?));
+                }
+                self.ascend();
+                Ok(Json::Object(fields))
+            }
+            Some(c) if c == '-' || c.is_ascii_digit() => {
+                let start = self.pos;
+                while self.chars.get(self.pos).is_some_and(|&c| c.is_ascii_digit() || matches!(c, '-' | '+' | '.' | 'e' | 'E')) {
+                    self.pos += 1;
+                }
+                Ok(Json::Num(self.chars[start..self.pos].iter().collect()))
+            }
+            Some('$') => Err(format!("variables are not allowed here (at {})", self.pos)),
+            _ => Ok(match self.name()?.as_str() {
+                "true" => Json::Bool(true),
+                "false" => Json::Bool(false),
+                "null" => Json::Null,
+                other => Json::str(other),
+            }),
+        }
+    }
+
+    fn string(&mut self) -> Result<String, String> {
+        self.expect('"')?;
+        if self.chars.get(self.pos..self.pos + 2) == Some(&['"', '"']) {
+            return Err(String::from("block strings are not supported"));
+        }
+        let mut out = String::new();
+        loop {
+            let Some(&c) = self.chars.get(self.pos) else {
+                return Err(String::from("unterminated string"));
+            };
+            self.pos += 1;
+            match c {
+                '"' => return Ok(out),
+                '\n' | '\r' => return Err(format!("line break in a string at {}", self.pos - 1)),
+                '\\' => {
+                    let escape = self.chars.get(self.pos).copied();
+                    self.pos += 1;
+                    out.push(match escape {
+                        Some('"') => '"',
+                        Some('\\') => '\\',
+                        Some('/') => '/',
+                        Some('b') => '\u{8}',
+                        Some('f') => '\u{c}',
+                        Some('n') => '\n',
+                        Some('r') => '\r',
+                        Some('t') => '\t',
+                        Some('u') => {
+                            let hex: String = self.chars.get(self.pos..self.pos + 4).unwrap_or_default().iter().collect();
+                            self.pos += 4;
+                            u32::from_str_radix(&hex, 16)
+                                .ok()
+                                .and_then(char::from_u32)
+                                .ok_or_else(|| format!("invalid \\u escape at {}", self.pos - 6))?
+                        }
+                        _ => return Err(format!("invalid escape at {}", self.pos - 2)),
+                    });
+                }
+                c => out.push(c),
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::api::bank::Bank;
+    use crate::api::forex::Forex;
+    use crate::view::cli;
+
+    /// `levels` selections nested in one another: `{a{a{...a}}}`.
+    fn nested(levels: usize) -> String {
+        format!("{}a{}", "{a".repeat(levels - 1) + "{", "}".repeat(levels))
+    }
+
+    #[test]
+    fn selections_up_to_the_depth_limit_parse() {
+        assert!(Parser::new(&nested(MAX_DEPTH)).document().is_ok());
+    }
+
+    #[test]
+    fn deeper_selections_are_refused_without_overflowing_the_stack() {
+        let err = Parser::new(&nested(30_000)).document().err().expect("too deep");
+        assert!(err.contains("nested more than 32 levels"), "{}", err);
+        assert!(Parser::new(&nested(MAX_DEPTH + 1)).document().is_err());
+    }
+
+    #[test]
+    fn deeply_nested_values_and_types_are_refused() {
+        let list = format!("{{deposit(amount: {}1{})}}", "[".repeat(10_000), "]".repeat(10_000));
+        assert!(Parser::new(&list).document().is_err());
+        let object = format!("{{deposit(amount: {}1{})}}", "{a:".repeat(10_000), "}".repeat(10_000));
+        assert!(Parser::new(&object).document().is_err());
+        let types = format!("query($a: {}Int{}) {{rates}}", "[".repeat(10_000), "]".repeat(10_000));
+        assert!(Parser::new(&types).document().is_err());
+    }
+
+    /// Run `document` against `bank` the way `POST /graphql` does.
+    fn run(bank: &mut Bank, document: &str) -> Json {
+        execute(document, None, |command| {
+            let output = cli::execute(bank, command)?;
+            Ok(output.to_json(bank))
+        })
+    }
+
+    #[test]
+    fn admin_mutations_are_refused_without_the_passphrase() {
+        let forex = Forex::builder().set_base_currency("PHP", "Philippine Peso").create_currency("USD", "US Dollar", Decimal::new(56, 0)).build();
+        let mut bank = Bank::builder().set_forex(forex).set_admin_passphrase("secret").build();
+        bank.create_account("Alice").expect("account");
+        let mutations = [
+            r#"rate(code: "USD", rate: "57")"#,
+            r#"spread(pair: "PHP/USD", rate: "0.01")"#,
+            r#"basket(code: "MIX", name: "Mix", weights: "USD:100")"#,
+            r#"archive(account: "Alice")"#,
+            r#"unarchive(account: "Alice")"#,
+            r#"alert(account: "Alice", below: "10")"#,
+            r#"promotion(account: "Alice", bonus: "0.01", days: 30)"#,
+            r#"interest(days: 30)"#,
+            r#"eod"#,
+            r#"simulate(days: 5)"#,
+        ];
+        for mutation in mutations {
+            for passphrase in ["", r#", passphrase: "guess""#] {
+                let field = match mutation.split_once(')') {
+                    Some((args, _)) => format!("{}{})", args, passphrase),
+                    None if passphrase.is_empty() => mutation.to_string(),
+                    None => format!("{}({})", mutation, &passphrase[2..]),
+                };
+                let reply = run(&mut bank, &format!("mutation {{ {} }}", field)).to_string();
+                assert!(reply.contains("incorrect admin passphrase"), "{}: {}", field, reply);
+            }
+        }
+        let rates = |bank: &mut Bank| run(bank, "{ rates { rates { code rate } } }").to_string();
+        assert!(!rates(&mut bank).contains("57"), "{}", rates(&mut bank));
+        let reply = run(&mut bank, r#"mutation { rate(code: "USD", rate: "57", passphrase: "secret") { rate } }"#).to_string();
+        assert!(!reply.contains("errors"), "{}", reply);
+        assert!(rates(&mut bank).contains("57"), "{}", rates(&mut bank));
+    }
+}
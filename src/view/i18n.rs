@@ -0,0 +1,942 @@
+use std::fmt;
+use std::sync::OnceLock;
+
+/// Language of the console text, chosen once at startup with `--lang`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Lang {
+    #[default]
+    En,
+    Fil,
+}
+
+impl Lang {
+    /// Parse a `--lang` value: "en" or "fil" (also "tl").
+    pub fn parse(tag: &str) -> Option<Lang> {
+        match tag.to_lowercase().as_str() {
+            "en" => Some(Lang::En),
+            "fil" | "tl" => Some(Lang::Fil),
+            _ => None,
+        }
+    }
+}
+
+static LANG: OnceLock<Lang> = OnceLock::new();
+
+/// Select the console language. Only the first call has any effect.
+pub fn set_lang(lang: Lang) {
+    let _ = LANG.set(lang);
+}
+
+/// The selected console language (English until `set_lang` is called).
+pub fn lang() -> Lang {
+    LANG.get().copied().unwrap_or_default()
+}
+
+/// Look up `key` in the selected language. Falls back to English, then to
+/// the key itself, so a missing entry is visible but never fatal.
+pub fn t(key: &'static str) -> &'static str {
+    CATALOG
+        .iter()
+        .find(|(k, _, _)| *k == key)
+        .map_or(key, |(_, en, fil)| match lang() {
+            Lang::En => en,
+            Lang::Fil => fil,
+        })
+}
+
+/// `t(key)` with each `{}` replaced by the next argument, in order.
+pub fn tf(key: &'static str, args: &[&dyn fmt::Display]) -> String {
+    let mut out = String::new();
+    let mut args = args.iter();
+    let mut parts = t(key).split("{}").peekable();
+    while let Some(part) = parts.next() {
+        out.push_str(part);
+        if parts.peek().is_some()
+            && let Some(arg) = args.next()
+        {
+            out.push_str(&arg.to_string());
+        }
+    }
+    out
+}
+
+/// Translate a catalog key: `tr!("key")` or `tr!("key", arg, ...)` for
+/// messages with `{}` placeholders.
+macro_rules! tr {
+    ($key:expr) => {
+        $crate::view::i18n::t($key)
+    };
+    ($key:expr, $($arg:expr),+ $(,)?) => {
+        $crate::view::i18n::tf($key, &[$(&$arg),+])
+    };
+}
+pub(crate) use tr;
+
+/// Message catalog: (key, English, Filipino).
+const CATALOG: &[(&str, &str, &str)] = &[
+    // Main menu
+    ("menu.register", "Register Account Name", "Magrehistro ng Pangalan ng Account"),
+    ("menu.list_accounts", "List Accounts", "Listahan ng mga Account"),
+    ("menu.deposit", "Deposit Amount", "Magdeposito"),
+    ("menu.withdraw", "Withdraw Amount", "Mag-withdraw"),
+    ("menu.transfer", "Transfer Funds", "Maglipat ng Pondo"),
+    ("menu.standing_orders", "Standing Orders", "Mga Standing Order"),
+    ("menu.till", "Teller Till", "Kaha ng Teller"),
+    ("menu.show_rates", "Show Exchange Rates", "Ipakita ang mga Palitan"),
+    ("menu.rate_history", "Rate History", "Kasaysayan ng Palitan"),
+    ("menu.rate_summary", "Rate Summary", "Buod ng Palitan"),
+    ("menu.exchange", "Currency Exchange", "Pagpapalit ng Pera"),
+    ("menu.receipts", "Exchange Receipts", "Mga Resibo ng Pagpapalit"),
+    ("menu.forwards", "FX Forwards", "Mga FX Forward"),
+    ("menu.limit_orders", "Limit Orders", "Mga Limit Order"),
+    ("menu.pnl", "FX Profit and Loss", "Kita at Lugi sa FX"),
+    ("menu.portfolio", "Portfolio Value", "Halaga ng Portfolio"),
+    ("menu.dca", "Dollar-Cost Averaging Simulator", "Simulator ng Dollar-Cost Averaging"),
+    ("menu.record_rate", "Record Exchange Rates", "Itala ang mga Palitan"),
+    ("menu.currencies", "Manage Currencies", "Pamahalaan ang mga Pera"),
+    ("menu.simulate", "Simulate Market", "I-simulate ang Merkado"),
+    ("menu.show_interest", "Show Interest Computation", "Ipakita ang Kompyutasyon ng Interes"),
+    ("menu.compare_rates", "Compare Interest Rates", "Paghambingin ang mga Interes"),
+    ("menu.compare_accounts", "Compare Accounts", "Paghambingin ang mga Account"),
+    ("menu.apy", "APY Calculator", "Calculator ng APY"),
+    ("menu.calculator", "Interest Calculator", "Calculator ng Interes"),
+    ("menu.monte_carlo", "Monte Carlo Outlook", "Tanaw na Monte Carlo"),
+    ("menu.paydown", "Loan vs Deposit", "Utang laban sa Deposito"),
+    ("menu.goals", "Savings Goals", "Mga Layunin sa Pag-iipon"),
+    ("menu.budget", "Budget Envelopes", "Mga Sobre ng Badyet"),
+    ("menu.tags", "Transaction Tags", "Mga Tag ng Transaksyon"),
+    ("menu.summary", "Monthly Summary", "Buwanang Buod"),
+    ("menu.tax", "Tax Certificate", "Sertipiko ng Buwis"),
+    ("menu.post_interest", "Post Interest", "Ipasok ang Interes"),
+    ("menu.rounding", "Rounding Settings", "Mga Setting ng Pag-round"),
+    ("menu.set_interest", "Set Annual Interest Rate", "Itakda ang Taunang Interes"),
+    ("menu.year_basis", "Set Year Basis", "Itakda ang Batayan ng Taon"),
+    ("menu.interest_method", "Set Interest Method", "Itakda ang Paraan ng Interes"),
+    ("menu.holds", "Authorization Holds", "Mga Authorization Hold"),
+    ("menu.cheques", "Cheque Deposits", "Mga Deposito ng Tseke"),
+    ("menu.service_charges", "Set Service Charges", "Itakda ang mga Service Charge"),
+    ("menu.limit_profiles", "Limit Profiles", "Mga Profile ng Limitasyon"),
+    ("menu.interest_grace", "Set Interest Grace Period", "Itakda ang Palugit Bago Kumita ng Interes"),
+    ("menu.promotion", "Set Promotional Rate", "Itakda ang Promo na Interes"),
+    ("menu.end_of_day", "Run End of Day", "Patakbuhin ang Katapusan ng Araw"),
+    ("menu.inbox", "Inbox", "Inbox"),
+    ("menu.review_flagged", "Review Flagged Transactions", "Suriin ang mga Na-flag na Transaksyon"),
+    ("menu.approvals", "Approve Withdrawals and Transfers", "Aprubahan ang mga Withdraw at Paglipat"),
+    ("menu.verify", "Verify Ledger", "Suriin ang Ledger"),
+    ("menu.history", "Transaction History", "Kasaysayan ng Transaksyon"),
+    ("menu.search", "Search Transactions", "Maghanap ng Transaksyon"),
+    ("menu.customers", "Customers", "Mga Kustomer"),
+    ("menu.aliases", "Account Aliases", "Mga Alyas ng Account"),
+    ("menu.archive", "Archived Accounts", "Mga Naka-archive na Account"),
+    ("menu.snapshots", "Snapshots", "Mga Snapshot"),
+    ("menu.undo", "Undo Last Operation", "I-undo ang Huling Operasyon"),
+    ("menu.macros", "Run Macro", "Magpatakbo ng Macro"),
+    ("menu.help", "Help and Glossary", "Tulong at Glosaryo"),
+    ("menu.switch_role", "Switch Role", "Palitan ang Tungkulin"),
+    ("menu.log_in", "Change Customer Session", "Palitan ang Session ng Kustomer"),
+    ("main.title", "Main Menu ({})", "Pangunahing Menu ({})"),
+    ("main.select", "Select Transaction:", "Pumili ng Transaksyon:"),
+    ("main.invalid_option", "Invalid option. Please select 1-{}.", "Hindi wastong pagpili. Pumili mula 1-{}."),
+    ("main.needs_account", "Please register an account through [1] before proceeding.", "Magrehistro muna ng account sa [1] bago magpatuloy."),
+    ("main.back", "Back to the Main Menu (Y/N): ", "Bumalik sa Pangunahing Menu (O/H): "),
+    // Roles
+    ("role.select", "Select Role:", "Pumili ng Tungkulin:"),
+    ("role.teller", "Teller", "Teller"),
+    ("role.admin", "Admin", "Admin"),
+    ("role.passphrase", "Admin Passphrase: ", "Passphrase ng Admin: "),
+    ("role.bad_passphrase", "Incorrect passphrase. Continuing as Teller.", "Maling passphrase. Magpapatuloy bilang Teller."),
+    // Shared prompts and errors
+    ("prompt.account_name", "Account Name: ", "Pangalan ng Account: "),
+    ("prompt.memo", "Memo (optional): ", "Memo (opsyonal): "),
+    ("prompt.amount", "Amount: ", "Halaga: "),
+    ("prompt.proceed", "Proceed (Y/N)? ", "Magpatuloy (O/H)? "),
+    ("accounts.suggest", "Did you mean '{}' (Y/N)? ", "'{}' ba ang ibig mong sabihin (O/H)? "),
+    ("err.account_not_found", "Account not found. Please register first.", "Hindi nahanap ang account. Magrehistro muna."),
+    ("err.unknown_currency", "Unknown currency {}.", "Hindi kilalang pera: {}."),
+    ("err.invalid_selection", "Invalid selection.", "Hindi wastong pagpili."),
+    ("currency.unknown", "Unknown currency '{}'. Type a menu number or a code such as USD.", "Hindi kilalang pera '{}'. Mag-type ng numero sa menu o code gaya ng USD."),
+    ("currency.suggest", "Unknown currency '{}'. Did you mean {}?", "Hindi kilalang pera '{}'. {} ba ang ibig mong sabihin?"),
+    ("currency.ambiguous", "'{}' matches more than one currency: {}. Type the code.", "Higit sa isang pera ang tumutugma sa '{}': {}. I-type ang code."),
+    ("err.invalid_option", "Invalid option.", "Hindi wastong pagpili."),
+    ("input.number", "Please enter a valid number > 0.", "Maglagay ng wastong numero na > 0."),
+    ("input.evaluated", "= {}", "= {}"),
+    ("input.bad_amount", "Cannot read that amount: {}. Please try again.", "Hindi mabasa ang halagang iyan: {}. Subukang muli."),
+    ("input.amount", "Please enter a valid amount > 0.", "Maglagay ng wastong halaga na > 0."),
+    ("input.yes_no", "Please enter Y or N.", "Ilagay ang O o H."),
+    // Table columns
+    ("col.id", "ID", "ID"),
+    ("col.name", "Name", "Pangalan"),
+    ("col.balance", "Balance", "Balanse"),
+    ("col.currency", "Currency", "Pera"),
+    ("col.status", "Status", "Katayuan"),
+    ("col.code", "Code", "Kodigo"),
+    ("col.rate", "Rate", "Palitan"),
+    ("col.cash_rate", "Cash Rate", "Palitan sa Cash"),
+    ("col.updated", "Last Updated", "Huling Binago"),
+    ("col.source", "Source", "Pinagmulan"),
+    ("col.day", "Day", "Araw"),
+    ("col.gap", "Gap", "Agwat"),
+    ("col.days", "Days", "Mga Araw"),
+    ("col.interest", "Interest", "Interes"),
+    ("col.real_interest", "Real Interest", "Tunay na Interes"),
+    ("col.real_balance", "Real Balance", "Tunay na Balanse"),
+    ("col.converted_interest", "Interest in {}", "Interes sa {}"),
+    ("col.converted_balance", "Balance in {}", "Balanse sa {}"),
+    ("col.account", "Account", "Account"),
+    ("col.alias", "Alias", "Alyas"),
+    ("col.archived", "Archived", "Na-archive"),
+    ("col.type", "Type", "Uri"),
+    ("col.amount", "Amount", "Halaga"),
+    ("col.date", "Date", "Petsa"),
+    ("col.memo", "Memo", "Memo"),
+    ("col.from", "From", "Mula"),
+    ("col.to", "To", "Papunta"),
+    ("col.every", "Every", "Kada"),
+    ("col.next", "Next", "Susunod"),
+    ("col.side", "Side", "Panig"),
+    ("col.value_date", "Value Date", "Petsa ng Halaga"),
+    ("col.spot", "Spot", "Spot"),
+    ("col.mtm", "Mark-to-Market", "Mark-to-Market"),
+    ("col.limit", "Limit", "Limit"),
+    ("col.filled", "Filled", "Napunan"),
+    ("col.expires", "Expires", "Mag-e-expire"),
+    ("col.value", "Value", "Halaga"),
+    ("col.asset", "Asset", "Ari-arian"),
+    ("col.category", "Category", "Kategorya"),
+    ("col.spent", "Spent", "Nagastos"),
+    ("col.period", "Period", "Panahon"),
+    ("col.bought", "Bought", "Nabili"),
+    ("col.remaining", "Remaining", "Natitira"),
+    ("col.basket", "Basket", "Basket"),
+    ("col.component", "Component", "Bahagi"),
+    ("col.quantity", "Quantity", "Dami"),
+    ("col.weight", "Weight", "Bigat"),
+    ("col.cost", "Cost", "Gastos"),
+    ("col.unrealized", "Unrealized", "Hindi pa Natatanto"),
+    ("col.realized", "Realized", "Natanto"),
+    ("col.operation", "Operation", "Operasyon"),
+    ("col.role", "Role", "Tungkulin"),
+    ("col.received", "Received", "Natanggap"),
+    ("col.message", "Message", "Mensahe"),
+    ("col.description", "What it does", "Ginagawa nito"),
+    ("col.compounding", "Compounding", "Pag-compound"),
+    ("col.params", "Parameters", "Mga Parameter"),
+    ("col.nominal", "Nominal Rate", "Nominal na Interes"),
+    ("col.apy", "APY", "APY"),
+    ("col.denomination", "Denomination", "Denominasyon"),
+    ("col.count", "Count", "Bilang"),
+    ("col.number", "#", "#"),
+    ("col.percentile", "Percentile", "Porsiyentil"),
+    ("col.volatility", "Volatility", "Volatility"),
+    ("col.sma", "SMA {}", "SMA {}"),
+    ("col.ema", "EMA {}", "EMA {}"),
+    ("col.average", "Average", "Average"),
+    ("col.until", "To", "Hanggang"),
+    ("col.open", "Open", "Bukas"),
+    ("col.high", "High", "Pinakamataas"),
+    ("col.low", "Low", "Pinakamababa"),
+    ("col.average_rate", "Average", "Karaniwan"),
+    ("col.close", "Close", "Sara"),
+    ("col.change", "Change", "Pagbabago"),
+    ("col.tag", "Tag", "Tag"),
+    ("col.tags", "Tags", "Mga Tag"),
+    ("col.inflow", "Inflow", "Pumasok"),
+    ("col.outflow", "Outflow", "Lumabas"),
+    ("col.net", "Net", "Neto"),
+    ("col.opening", "Opening", "Simula"),
+    ("col.month", "Month", "Buwan"),
+    ("col.gross_interest", "Gross Interest", "Kabuuang Interes"),
+    ("col.tax_withheld", "Tax Withheld", "Buwis na Kinaltas"),
+    ("col.net_interest", "Net Interest", "Netong Interes"),
+    ("col.closing", "Closing", "Pagsasara"),
+    ("col.fees", "Fees", "Bayarin"),
+    ("col.net_change", "Net Change", "Netong Pagbabago"),
+    ("col.subtotal", "Subtotal", "Subtotal"),
+    ("col.expected", "Expected", "Inaasahan"),
+    ("col.counted", "Counted", "Nabilang"),
+    ("col.residue", "Rounding Residue", "Natirang Pag-round"),
+    ("col.clears", "Clears", "Mako-clear"),
+    ("col.target", "Applies To", "Para Kanino"),
+    ("col.max_deposit", "Max Deposit", "Pinakamalaking Deposito"),
+    ("col.max_withdrawal", "Max Withdrawal", "Pinakamalaking Withdraw"),
+    ("col.max_conversion", "Max Daily Conversion", "Pinakamalaking Palitan Bawat Araw"),
+    ("tx.deposit", "Deposit", "Deposito"),
+    ("tx.withdraw", "Withdraw", "Pag-withdraw"),
+    // Accounts and PINs
+    ("accounts.title", "Registered Accounts", "Mga Rehistradong Account"),
+    ("accounts.count", "{} account(s).", "{} account."),
+    ("status.protected", "PIN-protected", "May PIN"),
+    ("status.unprotected", "Unprotected", "Walang PIN"),
+    ("accounts.foreign", "Hold this account in a foreign currency (Y/N)? ", "Hawakan ang account na ito sa dayuhang pera (O/H)? "),
+    ("accounts.currency", "Account Currency (number or code): ", "Pera ng Account (numero o code): "),
+    ("accounts.failed", "Account not opened: {}.", "Hindi nabuksan ang account: {}."),
+    ("pin.protect", "Protect this account with a PIN (Y/N)? ", "Protektahan ang account na ito ng PIN (O/H)? "),
+    ("pin.prompt", "PIN: ", "PIN: "),
+    ("pin.empty", "PIN cannot be empty.", "Hindi maaaring walang laman ang PIN."),
+    ("pin.confirm", "Confirm PIN: ", "Kumpirmahin ang PIN: "),
+    ("pin.set", "PIN set.", "Naitakda ang PIN."),
+    ("pin.mismatch", "PINs do not match. Try again.", "Hindi magkatugma ang mga PIN. Subukan muli."),
+    ("pin.incorrect", "Incorrect PIN.", "Maling PIN."),
+    // Deposits, withdrawals, transfers
+    ("balance.current", "Current Balance: {}", "Kasalukuyang Balanse: {}"),
+    ("balance.available", "Available Balance: {} (the rest is on hold, clearing, or pending)", "Magagamit na Balanse: {} (naka-hold, nagki-clear, o nakabinbin ang natitira)"),
+    ("balance.updated", "Updated Balance: {}", "Bagong Balanse: {}"),
+    ("deposit.prompt", "Deposit Amount: ", "Halagang Idedeposito: "),
+    ("deposit.cancelled", "Deposit cancelled.", "Kinansela ang deposito."),
+    ("deposit.failed", "Deposit failed: {}.", "Hindi naideposito: {}."),
+    ("withdraw.prompt", "Withdraw Amount: ", "Halagang Iwi-withdraw: "),
+    ("withdraw.cancelled", "Withdrawal cancelled.", "Kinansela ang pag-withdraw."),
+    ("withdraw.failed", "Withdrawal failed: {}.", "Hindi na-withdraw: {}."),
+    ("transfer.source", "Source Account: ", "Account na Pagmumulan: "),
+    ("transfer.destination", "Destination Account: ", "Account na Patutunguhan: "),
+    ("transfer.currency", "Currency of Amount (blank for {}): ", "Pera ng Halaga (blangko para sa {}): "),
+    ("transfer.cancelled", "Transfer cancelled.", "Kinansela ang paglipat."),
+    ("transfer.receipt", "Transfer Receipt", "Resibo ng Paglipat"),
+    ("transfer.from", "From: {} \t| Debited: {}", "Mula: {} \t| Ibinawas: {}"),
+    ("transfer.to", "To: {} \t| Credited: {}", "Para sa: {} \t| Idinagdag: {}"),
+    ("transfer.rate", "Rate Used: 1 {} = {} {}", "Ginamit na Palitan: 1 {} = {} {}"),
+    ("transfer.fee", "Conversion Fee: {}", "Bayad sa Palitan: {}"),
+    ("receipt.leg", "  Leg {}: {} -> {} (1 {} = {} {})", "  Hakbang {}: {} -> {} (1 {} = {} {})"),
+    ("transfer.failed", "Transfer failed: {}.", "Hindi nailipat: {}."),
+    ("confirm.summary", "Please review this transaction:", "Pakisuri ang transaksyong ito:"),
+    ("confirm.prompt", "Type Y to confirm (Enter cancels): ", "I-type ang O para kumpirmahin (Enter para kanselahin): "),
+    ("summary.account", "Account: {}", "Account: {}"),
+    ("summary.withdraw", "Withdraw: {}", "Iwi-withdraw: {}"),
+    ("summary.balance_after", "Balance After: {}", "Balanse Pagkatapos: {}"),
+    ("summary.from", "From: {}", "Mula: {}"),
+    ("summary.to", "To: {}", "Para sa: {}"),
+    ("summary.amount", "Amount: {}", "Halaga: {}"),
+    ("large.warning", "This transaction exceeds the large-transaction threshold and will be flagged for review.", "Lumampas ang transaksyong ito sa limitasyon ng malalaking transaksyon at ifa-flag para suriin."),
+    ("override.ask", "Refused: {}. Override the conversion limit as admin (Y/N)? ", "Tinanggihan: {}. Lampasan ang limitasyon ng palitan bilang admin (O/H)? "),
+    // Rates and conversions
+    ("rate.title", "Record Exchange Rate", "Itala ang Palitan"),
+    ("rate.select", "Select Foreign Currency (number or code): ", "Pumili ng Dayuhang Pera (numero o code): "),
+    ("rate.base", "Cannot update the base currency exchange rate.", "Hindi mababago ang palitan ng batayang pera."),
+    ("rate.prompt", "Exchange Rate: ", "Palitan: "),
+    ("rate.recorded", "Recorded exchange rate for {}.", "Naitala ang palitan para sa {}."),
+    ("rate.change", "The {} rate changes from {} to {} ({}%).", "Magbabago ang palitan ng {} mula {} patungong {} ({}%)."),
+    ("rate.cancelled", "Exchange rate not changed.", "Hindi binago ang palitan."),
+    ("rate.failed", "Exchange rate not recorded: {}.", "Hindi naitala ang palitan: {}."),
+    ("currencies.add", "Add Currency", "Magdagdag ng Pera"),
+    ("currencies.rename", "Rename Currency", "Palitan ang Pangalan ng Pera"),
+    ("currencies.retire", "Retire Currency", "Iretiro ang Pera"),
+    ("currencies.code", "Currency Code (e.g. AUD): ", "Code ng Pera (hal. AUD): "),
+    ("currencies.name", "Currency Name: ", "Pangalan ng Pera: "),
+    ("currencies.rate", "Rate ({} per 1 unit): ", "Palitan ({} bawat 1 yunit): "),
+    ("currencies.bad_rate", "Please enter a rate greater than zero at {} decimal places.", "Maglagay ng palitan na higit sa zero sa {} na decimal place."),
+    ("currencies.added", "Added {} ({}).", "Naidagdag ang {} ({})."),
+    ("currencies.renamed", "{} is now named {}.", "Ang {} ay pinangalanan nang {}."),
+    ("currencies.retire_warning", "{} will be removed from the catalog and can no longer be exchanged.", "Aalisin ang {} sa listahan at hindi na mapapalitan."),
+    ("currencies.retired", "Retired {}.", "Iniretiro ang {}."),
+    ("currencies.failed", "Cannot update currencies: {}.", "Hindi mabago ang mga pera: {}."),
+    ("currencies.basket", "Define Currency Basket", "Gumawa ng Basket ng Pera"),
+    ("currencies.basket_intro", "A basket is worth 1 {} today, split among its components by weight.", "Ang basket ay nagkakahalaga ng 1 {} ngayon, hinati sa mga bahagi ayon sa bigat."),
+    ("currencies.component", "Component currency: ", "Perang bahagi: "),
+    ("currencies.weight", "Weight of {} (% of the basket): ", "Bigat ng {} (% ng basket): "),
+    ("currencies.weights_total", "Weights so far: {}%.", "Kabuuang bigat sa ngayon: {}%."),
+    ("currencies.another", "Add another component (Y/N)? ", "Magdagdag ng isa pang bahagi (O/H)? "),
+    ("currencies.cash_rate", "Set Cash Rate", "Itakda ang Palitan sa Cash"),
+    ("currencies.cash_prompt", "Cash rate ({} per 1 unit, Enter for the transfer rate): ", "Palitan sa cash ({} bawat 1 yunit, Enter para sa palitan ng transfer): "),
+    ("currencies.bad_cash_rate", "Please enter a rate greater than zero, or press Enter.", "Maglagay ng palitan na higit sa zero, o pindutin ang Enter."),
+    ("currencies.cash_set", "{} is now quoted in cash at {}.", "Ang {} ay sinisipi na sa cash sa {}."),
+    ("currencies.cash_cleared", "{} is quoted in cash at its transfer rate again.", "Ang {} ay muling sinisipi sa cash sa palitan ng transfer."),
+    ("currencies.basket_defined", "Defined basket {} ({}).", "Nagawa ang basket na {} ({})."),
+    ("rates.baskets", "Currency Baskets", "Mga Basket ng Pera"),
+    ("rates.title", "Exchange Rates (price of 1 unit in {})", "Mga Palitan (presyo ng 1 yunit sa {})"),
+    ("rates.base", "1 (base)", "1 (batayan)"),
+    ("rates.unknown", "unknown", "hindi alam"),
+    ("rates.volatility", "Volatility: annualized, from the last {} daily moves on record; - until there are enough.", "Volatility: taunan, mula sa huling {} na pang-araw-araw na galaw na nakatala; - hangga't kulang pa."),
+    ("rate_history.currency", "Currency (number or code): ", "Pera (numero o code): "),
+    ("rate_history.title", "Daily closing rates of {} (price of 1 unit in {})", "Pang-araw-araw na pangwakas na palitan ng {} (presyo ng 1 yunit sa {})"),
+    ("rate_history.none", "No closing rates are on record for {} yet.", "Wala pang nakatalang pangwakas na palitan para sa {}."),
+    ("rate_history.failed", "Cannot show the rate history: {}", "Hindi maipakita ang kasaysayan ng palitan: {}"),
+    ("rate_history.averages", "SMA and EMA: simple and exponential moving averages of the last {} closes; - until there are enough.", "SMA at EMA: simple at exponential na moving average ng huling {} na pangwakas na palitan; - hangga't kulang pa."),
+    ("rate_history.carried", "*: no rate published that day; the last business day's close is carried.", "*: walang palitang inilabas nang araw na iyon; dinala ang pangwakas na palitan ng huling araw ng negosyo."),
+    ("rate_summary.hint", "Leave a date blank to start from each currency's first close or end at its last.", "Iwanang blangko ang petsa para magsimula sa unang pangwakas na palitan ng bawat pera o magtapos sa huli."),
+    ("rate_summary.title", "Daily closing rates (price of 1 unit in {})", "Pang-araw-araw na pangwakas na palitan (presyo ng 1 yunit sa {})"),
+    ("rate_summary.none", "No closing rates are on record in that period.", "Walang nakatalang pangwakas na palitan sa panahong iyon."),
+    ("exchange.title", "Foreign Currency Exchange", "Pagpapalit ng Dayuhang Pera"),
+    ("exchange.source", "Source Currency (number or code): ", "Pinagmulang Pera (numero o code): "),
+    ("exchange.amount", "Source Amount: ", "Halagang Papalitan: "),
+    ("exchange.source_account", "Account to Exchange From: ", "Account na Papalitan: "),
+    ("exchange.target_account", "Account to Receive the Exchange: ", "Account na Tatanggap ng Kapalit: "),
+    ("exchange.amount_in", "Amount to Exchange ({}): ", "Halagang Papalitan ({}): "),
+    ("exchange.cancelled", "Exchange cancelled.", "Kinansela ang pagpapalit."),
+    ("exchange.receipt", "Exchange Receipt", "Resibo ng Pagpapalit"),
+    ("receipts.number", "Receipt No.: {}", "Numero ng Resibo: {}"),
+    ("receipts.offer", "Print the receipt (Y/N)? ", "I-print ang resibo (O/H)? "),
+    ("receipts.prompt", "Receipt number (Enter for the latest, {}): ", "Numero ng resibo (Enter para sa pinakahuli, {}): "),
+    ("receipts.none", "No conversions have been made yet.", "Wala pang naisasagawang palitan."),
+    ("receipts.invalid", "Enter a receipt number.", "Maglagay ng numero ng resibo."),
+    ("receipts.not_found", "No exchange receipt numbered {}.", "Walang resibo ng pagpapalit na may numerong {}."),
+    ("exchange.target", "Exchange Currency (number or code): ", "Perang Kapalit (numero o code): "),
+    ("exchange.result", "Exchange Amount: {}", "Halagang Kapalit: {}"),
+    ("exchange.fee", "Fee ({}%): {}", "Bayad ({}%): {}"),
+    ("exchange.fee_spread", "Fee ({}%, {} spread): {}", "Bayad ({}%, spread ng {}): {}"),
+    ("exchange.net", "You Receive: {}", "Matatanggap Mo: {}"),
+    ("exchange.failed", "Cannot exchange: {}.", "Hindi mapalitan: {}."),
+    ("preview.mid_rate", "Mid-Market Rate: 1 {} = {} {}", "Palitan sa Gitna ng Merkado: 1 {} = {} {}"),
+    ("preview.trend_previous", "Trend: {} since the close on {}", "Takbo: {} mula sa pagsasara noong {}"),
+    ("preview.trend_average", "Trend: {} vs. the {}-day average", "Takbo: {} kumpara sa {}-araw na average"),
+    ("preview.applied_rate", "Applied Rate: 1 {} = {} {}", "Inilapat na Palitan: 1 {} = {} {}"),
+    ("preview.margin", "Rate Margin: {}", "Margin sa Palitan: {}"),
+    ("preview.total_cost", "Total Cost: {}", "Kabuuang Gastos: {}"),
+    ("preview.effective_rate", "Effective Rate: 1 {} = {} {}", "Aktuwal na Palitan: 1 {} = {} {}"),
+    ("exchange.again", "Make another exchange (Y/N)? ", "Magpalit pa muli (O/H)? "),
+    ("cash.offer", "Count out {} in cash (Y/N)? ", "Bilangin ang {} sa cash (O/H)? "),
+    ("cash.pieces", "{} bill(s) and coin(s)", "{} perang papel at barya"),
+    ("cash.remainder", "Not payable in cash: {}", "Hindi maibibigay sa cash: {}"),
+    ("till.show", "Show the drawer", "Ipakita ang kaha"),
+    ("till.load", "Load cash (opening float)", "Maglagay ng cash (panimulang pondo)"),
+    ("till.deposit", "Cash deposit", "Deposito ng cash"),
+    ("till.withdraw", "Cash withdrawal", "Pag-withdraw ng cash"),
+    ("till.exchange", "Walk-in exchange (cash for cash)", "Palitan sa counter (cash sa cash)"),
+    ("till.balance", "Balance the till (end of day)", "Balansehin ang kaha (katapusan ng araw)"),
+    ("till.currency", "Currency (number or code): ", "Pera (numero o code): "),
+    ("till.value", "Denomination ({}): ", "Denominasyon ({}): "),
+    ("till.how_many", "How many: ", "Ilan: "),
+    ("till.loaded", "Added {} x {} to the till.", "Naidagdag ang {} x {} sa kaha."),
+    ("till.failed", "Cannot complete: {}.", "Hindi matapos: {}."),
+    ("till.cancelled", "Cancelled.", "Kinansela."),
+    ("till.confirm", "Pay out in cash (Y/N)? ", "Ibigay sa cash (O/H)? "),
+    ("till.taken_in", "Taken in:", "Tinanggap:"),
+    ("till.paid_out", "Paid out:", "Ibinigay:"),
+    ("till.empty", "The till holds no cash.", "Walang cash sa kaha."),
+    ("till.total", "{} in the drawer: {}", "{} sa kaha: {}"),
+    ("till.count_intro", "Count the drawer. Enter how many of each bill and coin you find (Enter for none).", "Bilangin ang kaha. Ilagay kung ilan ang bawat perang papel at barya (Enter kung wala)."),
+    ("till.count", "{}: ", "{}: "),
+    ("till.bad_count", "Please enter a whole number of zero or more.", "Maglagay ng buong bilang na zero pataas."),
+    ("till.ok", "ok", "tama"),
+    ("till.over_mark", "OVER", "SOBRA"),
+    ("till.short_mark", "SHORT", "KULANG"),
+    ("till.balanced", "{}: expected {}, counted {}. Balanced.", "{}: inaasahan {}, nabilang {}. Balanse."),
+    ("till.over", "{}: expected {}, counted {}. OVER by {}.", "{}: inaasahan {}, nabilang {}. SOBRA ng {}."),
+    ("till.short", "{}: expected {}, counted {}. SHORT by {}.", "{}: inaasahan {}, nabilang {}. KULANG ng {}."),
+    ("till.mixed", "{}: expected {}, counted {}. The totals agree but the bills and coins do not.", "{}: inaasahan {}, nabilang {}. Tugma ang kabuuan pero hindi ang mga perang papel at barya."),
+    // Interest
+    ("interest.current", "Current Interest Rate: {}%", "Kasalukuyang Interes: {}%"),
+    ("interest.new", "New Interest Rate (%): ", "Bagong Interes (%): "),
+    ("interest.set", "Annual interest set to {}% for all accounts.", "Itinakda ang taunang interes sa {}% para sa lahat ng account."),
+    ("interest.failed", "Interest rate not changed: {}.", "Hindi napalitan ang interes: {}."),
+    ("year_basis.current", "Bank year basis: {}", "Batayan ng taon ng bangko: {}"),
+    ("year_basis.account", "Account (Enter for every account): ", "Account (Enter para sa lahat ng account): "),
+    ("year_basis.account_current", "{} accrues interest on {}.", "Ang interes ng {} ay batay sa {}."),
+    ("year_basis.fixed365", "Actual/365 Fixed: 365 days, leap years included", "Actual/365 Fixed: 365 araw, kasama ang leap year"),
+    ("year_basis.actual", "Actual/Actual: 366 days in leap years, 365 otherwise", "Actual/Actual: 366 araw sa leap year, 365 sa iba"),
+    ("year_basis.fixed360", "Actual/360: 360 days, as money markets count", "Actual/360: 360 araw, gaya ng money market"),
+    ("year_basis.set", "{} now accrues interest on {}.", "Ang interes ng {} ay batay na sa {}."),
+    ("year_basis.set_all", "Every account now accrues interest on {}, and new accounts open on it.", "Ang interes ng lahat ng account ay batay na sa {}, pati ng mga bagong account."),
+    ("year_basis.failed", "Year basis not changed: {}.", "Hindi napalitan ang batayan ng taon: {}."),
+    ("interest_method.current", "Bank interest method: {}", "Paraan ng interes ng bangko: {}"),
+    ("interest_method.account_current", "{} accrues interest by: {}.", "Paraan ng interes ng {}: {}."),
+    ("interest_method.daily", "Daily balance: each day earns on its closing balance, compounded daily", "Araw-araw na balanse: bawat araw ay kumikita sa balanse sa pagsasara, pinagsasama araw-araw"),
+    ("interest_method.average", "Average daily balance: each month earns on its average balance, credited at month end", "Karaniwang araw-araw na balanse: bawat buwan ay kumikita sa karaniwang balanse, ipinapasok sa katapusan ng buwan"),
+    ("interest_method.minimum", "Minimum monthly balance: each month earns on its lowest balance, credited at month end", "Pinakamababang buwanang balanse: bawat buwan ay kumikita sa pinakamababang balanse, ipinapasok sa katapusan ng buwan"),
+    ("interest_method.set", "{} now accrues interest by: {}.", "Paraan na ng interes ng {}: {}."),
+    ("interest_method.set_all", "Every account now accrues interest by: {}, and new accounts open with it.", "Paraan na ng interes ng lahat ng account: {}, pati ng mga bagong account."),
+    ("interest_method.failed", "Interest method not changed: {}.", "Hindi napalitan ang paraan ng interes: {}."),
+    ("interest_grace.current", "Deposits earn interest from {} days after they are posted.", "Kumikita ng interes ang mga deposito {} araw matapos maipasok."),
+    ("interest_grace.account_current", "{}: deposits earn from {} days after they are posted.", "{}: kumikita ang mga deposito {} araw matapos maipasok."),
+    ("interest_grace.days", "Days before a deposit earns (0 for none): ", "Mga araw bago kumita ang deposito (0 kung wala): "),
+    ("interest_grace.bad_days", "Please enter a whole number of days.", "Maglagay ng buong bilang ng araw."),
+    ("interest_grace.set", "{}: deposits now earn from {} days after they are posted.", "{}: kumikita na ang mga deposito {} araw matapos maipasok."),
+    ("interest_grace.set_all", "Deposits to every account, and new accounts, now earn from {} days after they are posted.", "Kumikita na ang mga deposito sa lahat ng account, pati sa mga bago, {} araw matapos maipasok."),
+    ("interest_grace.failed", "Grace period not changed: {}.", "Hindi napalitan ang palugit: {}."),
+    ("interest.negative", "Negative rate: balances are charged {}% a year instead of earning interest, so they decline.", "Negatibong interes: sinisingil ang balanse ng {}% bawat taon sa halip na kumita, kaya bumababa ito."),
+    ("holds.none", "No active holds on {}.", "Walang aktibong hold sa {}."),
+    ("holds.place", "Place a hold", "Maglagay ng hold"),
+    ("holds.release", "Release a hold", "Alisin ang isang hold"),
+    ("holds.capture", "Capture a hold as a withdrawal", "I-capture ang hold bilang withdraw"),
+    ("holds.back", "Back", "Bumalik"),
+    ("holds.amount", "Amount to Hold: ", "Halagang Iho-hold: "),
+    ("holds.reason", "Reason: ", "Dahilan: "),
+    ("holds.expires", "Expires on (YYYY-MM-DD, blank for {} days): ", "Mag-e-expire sa (YYYY-MM-DD, blangko para sa {} araw): "),
+    ("holds.id", "Hold ID: ", "ID ng Hold: "),
+    ("holds.capture_amount", "Amount to Capture (blank for the whole hold): ", "Halagang Ica-capture (blangko para sa buong hold): "),
+    ("holds.placed", "Hold {} of {} placed until {}.", "Nailagay ang hold {} na {} hanggang {}."),
+    ("holds.released", "Hold {} released; {} is available again.", "Naalis ang hold {}; magagamit muli ang {}."),
+    ("holds.captured", "Withdrew {} from hold {}.", "Na-withdraw ang {} mula sa hold {}."),
+    ("holds.failed", "Hold not changed: {}.", "Hindi nabago ang hold: {}."),
+    ("cheques.none", "No cheques deposited to {}.", "Walang tsekeng idineposito sa {}."),
+    ("cheques.deposit", "Deposit a cheque", "Magdeposito ng tseke"),
+    ("cheques.return", "Return a bounced cheque", "Ibalik ang tumalbog na tseke"),
+    ("cheques.number", "Cheque Number: ", "Numero ng Tseke: "),
+    ("cheques.amount", "Cheque Amount: ", "Halaga ng Tseke: "),
+    ("cheques.id", "Cheque ID: ", "ID ng Tseke: "),
+    ("cheques.reason", "Reason Returned (e.g. insufficient funds): ", "Dahilan ng Pagbalik (hal. kulang ang pondo): "),
+    ("cheques.deposited", "Cheque {} for {} credited; the funds are available once it clears on {}.", "Naipasok ang tsekeng {} na {}; magagamit ang pondo kapag na-clear ito sa {}."),
+    ("cheques.returned", "Cheque {} returned; {} taken back from the balance.", "Naibalik ang tsekeng {}; ibinawas ang {} sa balanse."),
+    ("cheques.failed", "Cheque not changed: {}.", "Hindi nabago ang tseke: {}."),
+    ("charges.current_monthly", "Current service charge: {} every month.", "Kasalukuyang service charge: {} bawat buwan."),
+    ("charges.current_maintaining", "Current maintaining charge: {} when the average daily balance is below {}.", "Kasalukuyang singil sa maintaining: {} kapag mas mababa sa {} ang karaniwang arawang balanse."),
+    ("charges.monthly", "Monthly Service Charge (blank or 0 for none): ", "Buwanang Service Charge (blangko o 0 kung wala): "),
+    ("charges.maintaining", "Maintaining Balance (blank or 0 for none): ", "Maintaining Balance (blangko o 0 kung wala): "),
+    ("charges.maintaining_charge", "Charge Below the Maintaining Balance: ", "Singil Kapag Kulang sa Maintaining Balance: "),
+    ("charges.set", "Service charges for {} updated; they are taken at each month's end.", "Na-update ang mga service charge ng {}; sinisingil ang mga ito sa katapusan ng bawat buwan."),
+    ("charges.failed", "Service charges not changed: {}.", "Hindi napalitan ang mga service charge: {}."),
+    ("limits.none", "No limit profiles.", "Walang profile ng limitasyon."),
+    ("limits.set_type", "Set an account's type", "Itakda ang uri ng account"),
+    ("limits.set_profile", "Set the limits for an account type or KYC status", "Itakda ang mga limitasyon para sa uri ng account o katayuan ng KYC"),
+    ("limits.current_type", "Current account type: {}", "Kasalukuyang uri ng account: {}"),
+    ("limits.type", "Account Type (e.g. savings; blank for none): ", "Uri ng Account (hal. savings; blangko kung wala): "),
+    ("limits.type_set", "{} is now of type {}.", "Ang {} ay nasa uring {} na."),
+    ("limits.target", "Applies To (type:NAME or kyc:STATUS): ", "Para Kanino (type:PANGALAN o kyc:KATAYUAN): "),
+    ("limits.bad_target", "Unknown target {}; enter e.g. type:savings or kyc:unverified.", "Hindi kilalang {}; ilagay hal. type:savings o kyc:unverified."),
+    ("limits.max_deposit", "Max Deposit, in the base currency (blank for no cap): ", "Pinakamalaking Deposito, sa base currency (blangko kung walang hangganan): "),
+    ("limits.max_withdrawal", "Max Withdrawal (blank for no cap): ", "Pinakamalaking Withdraw (blangko kung walang hangganan): "),
+    ("limits.max_conversion", "Max Converted per Day (blank for no cap): ", "Pinakamalaking Mapapalitan Bawat Araw (blangko kung walang hangganan): "),
+    ("limits.set", "Limits for {} updated.", "Na-update ang mga limitasyon para sa {}."),
+    ("limits.failed", "Limits not changed: {}.", "Hindi nabago ang mga limitasyon: {}."),
+    ("promotion.current", "Current promotion: +{}% until {}", "Kasalukuyang promo: +{}% hanggang {}"),
+    ("promotion.bonus", "Bonus Rate (%, 0 to end the promotion): ", "Dagdag na Interes (%, 0 para tapusin ang promo): "),
+    ("promotion.days", "Promotion Length (days): ", "Haba ng Promo (araw): "),
+    ("promotion.set", "{} earns {}% until {}, then reverts to {}%.", "Kikita ang {} ng {}% hanggang {}, saka babalik sa {}%."),
+    ("promotion.ended", "Promotion ended; {} earns {}%.", "Tapos na ang promo; kikita ang {} ng {}%."),
+    ("promotion.failed", "Promotion not set: {}.", "Hindi naitakda ang promo: {}."),
+    ("show_interest.title", "Show Interest Amount", "Ipakita ang Halaga ng Interes"),
+    ("show_interest.rate", "Interest Rate: {}%", "Interes: {}%"),
+    ("show_interest.days", "Total Number of Days: ", "Kabuuang Bilang ng Araw: "),
+    ("show_interest.bad_days", "Please enter a valid number of days between 1 and 999999.", "Maglagay ng wastong bilang ng araw mula 1 hanggang 999999."),
+    ("show_interest.failed", "Cannot compute interest: {}.", "Hindi makompyut ang interes: {}."),
+    ("show_interest.summary_prompt", "The forecast has {} rows. Show summary only (Y/N)? ", "May {} na hilera ang forecast. Buod lamang ang ipakita (O/H)? "),
+    ("show_interest.total", "Total Interest: {}", "Kabuuang Interes: {}"),
+    ("show_interest.final", "Final Balance: {}", "Huling Balanse: {}"),
+    ("show_interest.inflation", "Expected Inflation in % (blank for none): ", "Inaasahang Implasyon sa % (blangko kung wala): "),
+    ("show_interest.step", "One row per Day, Week, Month, or Year (D/W/M/Y, Enter for D): ", "Isang hilera bawat araw, linggo, buwan, o taon (D/W/M/Y, Enter para sa D): "),
+    ("show_interest.bad_inflation", "Please enter an inflation rate of zero or more, or leave it blank.", "Maglagay ng implasyon na zero pataas, o iwanang blangko."),
+    ("show_interest.real_total", "Total Interest in Today's Money: {}", "Kabuuang Interes sa Halaga Ngayon: {}"),
+    ("show_interest.real_final", "Final Balance in Today's Money: {}", "Huling Balanse sa Halaga Ngayon: {}"),
+    ("show_interest.currency", "Also show amounts in currency (code or name, Enter for none): ", "Ipakita rin ang halaga sa pera (code o pangalan, Enter kung wala): "),
+    ("show_interest.converted_total", "Total Interest in {}: {}", "Kabuuang Interes sa {}: {}"),
+    ("show_interest.converted_final", "Final Balance in {}: {}", "Huling Balanse sa {}: {}"),
+    ("compare.title", "Compare Interest Rates", "Paghambingin ang mga Interes"),
+    ("compare.rates", "Candidate Rates in % (comma-separated): ", "Mga Pagpipiliang Interes sa % (hiwalay ng kuwit): "),
+    ("compare.bad_rates", "Please enter one or more rates of zero or more, e.g. 3, 4.5, 6.", "Maglagay ng isa o higit pang interes na zero pataas, hal. 3, 4.5, 6."),
+    ("compare.current", "{} (current)", "{} (kasalukuyan)"),
+    ("compare.total", "Total Interest", "Kabuuang Interes"),
+    ("compare_accounts.first", "First Account Name: ", "Pangalan ng Unang Account: "),
+    ("compare_accounts.second", "Second Account Name: ", "Pangalan ng Ikalawang Account: "),
+    ("compare_accounts.side", "{}: {} at {}% a year", "{}: {} sa {}% bawat taon"),
+    ("compare_accounts.overtakes", "{} overtakes {} on day {} ({}).", "Nalampasan ng {} ang {} sa araw {} ({})."),
+    ("compare_accounts.level", "{} and {} draw level on day {} ({}).", "Nagpantay ang {} at {} sa araw {} ({})."),
+    ("compare_accounts.no_crossover", "No crossover within {} days; {} stays ahead.", "Walang pagtawid sa loob ng {} araw; nananatiling nangunguna ang {}."),
+    ("compare_accounts.stay_level", "No crossover within {} days; the two stay level.", "Walang pagtawid sa loob ng {} araw; nananatiling pantay ang dalawa."),
+    ("apy.from_nominal", "Nominal annual rate to APY", "Nominal na taunang interes patungong APY"),
+    ("apy.from_apy", "APY to nominal annual rate", "APY patungong nominal na taunang interes"),
+    ("apy.rate", "Rate in %: ", "Interes sa %: "),
+    ("apy.out_of_range", "out of range", "labas sa saklaw"),
+    ("calculator.principal", "Principal in {}: ", "Puhunan sa {}: "),
+    ("calculator.compounding", "Compounding: ", "Pag-compound: "),
+    ("mc.intro", "Runs many randomized paths of an account's interest rate, exchange rate, and deposits, and shows where its balance may end up.", "Nagpapatakbo ng maraming random na landas ng interes, palitan, at deposito ng account, at ipinapakita kung saan maaaring mapunta ang balanse nito."),
+    ("mc.rate_volatility", "Interest rate volatility in points a year (Enter for 0): ", "Volatility ng interes sa puntos bawat taon (Enter para sa 0): "),
+    ("mc.deposit", "Typical deposit (Enter for none): ", "Karaniwang deposito (Enter kung wala): "),
+    ("mc.every", "Days between chances to deposit: ", "Mga araw sa pagitan ng pagkakataong magdeposito: "),
+    ("mc.chance", "Chance of each deposit in % (Enter for 100): ", "Tsansa ng bawat deposito sa % (Enter para sa 100): "),
+    ("mc.paths", "Paths (Enter for 1000): ", "Mga landas (Enter para sa 1000): "),
+    ("mc.bad_paths", "Please enter 1 to {} paths.", "Maglagay ng 1 hanggang {} na landas."),
+    ("mc.title", "{} from {} over {} days, {} paths, seed {}:", "{} mula {} sa loob ng {} araw, {} na landas, seed {}:"),
+    ("mc.lowest", "lowest", "pinakamababa"),
+    ("mc.percentile", "{}th", "ika-{}"),
+    ("mc.highest", "highest", "pinakamataas"),
+    ("mc.mean", "mean", "karaniwan"),
+    ("mc.below", "Chance of ending below the opening balance: {}%", "Tsansang magtapos sa ibaba ng panimulang balanse: {}%"),
+    ("mc.below_value", "Chance of ending below its opening value: {}%", "Tsansang magtapos sa ibaba ng panimulang halaga nito: {}%"),
+    ("mc.deposited", "Deposited per path on average: {}", "Karaniwang naideposito bawat landas: {}"),
+    ("mc.seed", "Use seed {} to repeat these paths.", "Gamitin ang seed {} para maulit ang mga landas na ito."),
+    ("paydown.principal", "Loan Principal in {}: ", "Prinsipal ng Utang sa {}: "),
+    ("paydown.loan_rate", "Loan Rate in %: ", "Interes ng Utang sa %: "),
+    ("paydown.term", "Number of Payments: ", "Bilang ng Bayad: "),
+    ("paydown.bad_term", "A loan takes at most {} payments.", "Hanggang {} na bayad lamang ang utang."),
+    ("paydown.frequency", "Payment Frequency (weekly, biweekly, monthly, quarterly, annually; Enter for monthly): ", "Dalas ng Bayad (weekly, biweekly, monthly, quarterly, annually; Enter para sa monthly): "),
+    ("paydown.amount", "Amount to Pay Down or Deposit: ", "Halagang Ibabayad o Idedeposito: "),
+    ("paydown.deposit_rate", "Deposit Rate in %: ", "Interes ng Deposito sa %: "),
+    ("paydown.pay_down", "Pay down: saves {} at {}% ({}% APY).", "Pagbabayad: makakatipid ng {} sa {}% ({}% APY)."),
+    ("paydown.deposit", "Deposit:  earns {} at {}% less {}% tax ({}% APY).", "Deposito: kikita ng {} sa {}% bawas ang {}% buwis ({}% APY)."),
+    ("paydown.ahead_pay_down", "Paying down comes out ahead by {} over {} days.", "Mas sulit ang pagbabayad nang {} sa loob ng {} araw."),
+    ("paydown.ahead_deposit", "Depositing comes out ahead by {} over {} days.", "Mas sulit ang pagdeposito nang {} sa loob ng {} araw."),
+    ("paydown.either", "Both come out the same over {} days.", "Pareho ang kalalabasan ng dalawa sa loob ng {} araw."),
+    ("paydown.break_even", "A deposit breaks even at {}% a year before tax.", "Pantay ang deposito sa {}% bawat taon bago ang buwis."),
+    ("paydown.failed", "Cannot compare: {}.", "Hindi maihambing: {}."),
+    ("calculator.terms", "{} at {}% a year, {} compounding (APY {})", "{} sa {}% bawat taon, {} na pag-compound (APY {})"),
+    ("page.more", "-- Press Enter for more, q to quit -- ", "-- Pindutin ang Enter para sa susunod, q para huminto -- "),
+    ("export.prompt", "Export to CSV (Y/N)? ", "I-export sa CSV (O/H)? "),
+    ("export.file", "File name (blank to cancel): ", "Pangalan ng file (blangko para kanselahin): "),
+    ("export.cancelled", "Export cancelled.", "Kinansela ang pag-export."),
+    ("export.saved", "Exported {} row(s) to {}.", "Na-export ang {} na hilera sa {}."),
+    ("export.failed", "Cannot write {}: {}.", "Hindi maisulat ang {}: {}."),
+    ("post.account", "Account Name (blank for all accounts): ", "Pangalan ng Account (blangko para sa lahat): "),
+    ("post.posted_all", "Posted interest to {} account(s):", "Naipasok ang interes sa {} na account:"),
+    ("post.since", "Value-Date From (YYYY-MM-DD, blank to accrue days ahead): ", "Petsa ng Simula ng Halaga (YYYY-MM-DD, blangko para sa mga araw sa hinaharap): "),
+    ("post.days", "Number of Days to Accrue: ", "Bilang ng Araw ng Interes: "),
+    ("post.posted", "Posted Interest: {}", "Naipasok na Interes: {}"),
+    ("post.failed", "Interest not posted: {}.", "Hindi naipasok ang interes: {}."),
+    // Compliance review
+    ("review.none", "No transactions awaiting review.", "Walang transaksyong naghihintay ng pagsusuri."),
+    ("review.ask", "Mark a transaction as reviewed (Y/N)? ", "Markahan ang isang transaksyon bilang nasuri (O/H)? "),
+    ("review.id", "Flag ID: ", "ID ng Flag: "),
+    ("review.done", "Flag {} marked as reviewed.", "Namarkahang nasuri ang flag {}."),
+    ("review.missing", "No flagged transaction with ID {}.", "Walang na-flag na transaksyon na may ID {}."),
+    ("approval.held", "Held for an admin's approval as request {}; it posts once approved.", "Naka-hold para sa pag-apruba ng admin bilang kahilingan {}; maipo-post ito kapag naaprubahan."),
+    ("approval.none", "No withdrawals or transfers awaiting approval.", "Walang withdraw o paglipat na naghihintay ng pag-apruba."),
+    ("approval.id", "Request ID: ", "ID ng Kahilingan: "),
+    ("approval.missing", "No pending request with ID {}.", "Walang nakabinbing kahilingan na may ID {}."),
+    ("approval.approve", "Approve and post it", "Aprubahan at i-post ito"),
+    ("approval.reject", "Reject it", "Tanggihan ito"),
+    ("approval.keep", "Leave it pending", "Hayaang nakabinbin"),
+    ("approval.reason", "Reason for Rejecting: ", "Dahilan ng Pagtanggi: "),
+    ("approval.approved", "Request {} approved and posted.", "Naaprubahan at nai-post ang kahilingan {}."),
+    ("approval.rejected", "Request {} rejected; nothing was posted.", "Tinanggihan ang kahilingan {}; walang nai-post."),
+    ("approval.failed", "Request not decided: {}.", "Hindi napagpasyahan ang kahilingan: {}."),
+    ("verify.clean", "Checked {} account(s) and {} transaction(s): no violations.", "Nasuri ang {} account at {} transaksyon: walang paglabag."),
+    ("verify.found", "Checked {} account(s) and {} transaction(s): {} violation(s):", "Nasuri ang {} account at {} transaksyon: {} paglabag:"),
+    // Customers
+    ("customers.register", "Register Customer", "Magrehistro ng Kustomer"),
+    ("customers.open", "Open Account for Customer", "Magbukas ng Account para sa Kustomer"),
+    ("customers.summary", "Customer Relationship Summary", "Buod ng Ugnayan sa Kustomer"),
+    ("customers.name", "Customer Name: ", "Pangalan ng Kustomer: "),
+    ("customers.contact", "Contact Info: ", "Impormasyon sa Pakikipag-ugnayan: "),
+    ("customers.registered", "Registered customer {} with ID {}.", "Nairehistro ang kustomer na si {} na may ID {}."),
+    ("customers.id", "Customer ID: ", "ID ng Kustomer: "),
+    ("customers.opened", "Opened account {} (ID {}).", "Nabuksan ang account na {} (ID {})."),
+    ("customers.not_found", "Customer not found.", "Hindi nahanap ang kustomer."),
+    ("customers.header", "Customer: {} ({})", "Kustomer: {} ({})"),
+    ("customers.total", "Total Relationship Balance: {}", "Kabuuang Balanse ng Ugnayan: {}"),
+    ("customers.total_failed", "Total Relationship Balance unavailable: {}.", "Hindi makuha ang Kabuuang Balanse ng Ugnayan: {}."),
+    ("alias.add", "Add Alias", "Magdagdag ng Alyas"),
+    ("alias.remove", "Remove Alias", "Alisin ang Alyas"),
+    ("alias.list", "List Aliases", "Ipakita ang mga Alyas"),
+    ("alias.prompt", "Alias: ", "Alyas: "),
+    ("alias.added", "{} now finds account {}.", "Mahahanap na ng {} ang account na {}."),
+    ("alias.removed", "Removed alias {} from account {}.", "Inalis ang alyas na {} sa account na {}."),
+    ("alias.failed", "Alias not changed: {}.", "Hindi nabago ang alyas: {}."),
+    ("alias.none", "No account has an alias.", "Walang account na may alyas."),
+    ("archive.archive", "Archive Account", "I-archive ang Account"),
+    ("archive.restore", "Restore Account", "Ibalik ang Account"),
+    ("archive.list", "List Archived Accounts", "Ipakita ang mga Naka-archive na Account"),
+    ("archive.archived", "Archived account {}. Its history is kept.", "Na-archive ang account na {}. Nananatili ang kasaysayan nito."),
+    ("archive.restored", "Restored account {}.", "Naibalik ang account na {}."),
+    ("archive.failed", "Account not changed: {}.", "Hindi nabago ang account: {}."),
+    ("archive.none", "No account is archived.", "Walang naka-archive na account."),
+    ("customers.kyc", "Record Identification (KYC)", "Itala ang Pagkakakilanlan (KYC)"),
+    ("customers.review", "Review Verification (Admin)", "Suriin ang Beripikasyon (Admin)"),
+    ("kyc.id_type", "ID Type:", "Uri ng ID:"),
+    ("kyc.type.passport", "Passport", "Pasaporte"),
+    ("kyc.type.drivers_license", "Driver's License", "Lisensya sa Pagmamaneho"),
+    ("kyc.type.national_id", "National ID", "National ID"),
+    ("kyc.type.other", "Other", "Iba pa"),
+    ("kyc.id_number", "ID Number: ", "Numero ng ID: "),
+    ("kyc.address", "Address: ", "Tirahan: "),
+    ("kyc.recorded", "Identification recorded for {}; verification is pending.", "Naitala ang pagkakakilanlan ni {}; nakabinbin ang beripikasyon."),
+    ("kyc.failed", "KYC not updated: {}.", "Hindi nabago ang KYC: {}."),
+    ("kyc.summary", "KYC: {} | ID: {} | Address: {}", "KYC: {} | ID: {} | Tirahan: {}"),
+    ("kyc.no_id", "none on file", "wala pang nakatala"),
+    ("kyc.status.unverified", "Unverified", "Hindi beripikado"),
+    ("kyc.status.pending", "Pending", "Nakabinbin"),
+    ("kyc.status.verified", "Verified", "Beripikado"),
+    ("kyc.status.rejected", "Rejected", "Tinanggihan"),
+    ("kyc.decision", "Decision:", "Pasya:"),
+    ("kyc.updated", "{} is now {}.", "Si {} ay {} na."),
+    ("kyc.needs_admin", "Only an admin can review verification.", "Admin lamang ang maaaring magsuri ng beripikasyon."),
+    // Snapshots
+    ("snap.none", "No checkpoints saved.", "Walang naka-save na checkpoint."),
+    ("snap.list", "Checkpoints: {}", "Mga Checkpoint: {}"),
+    ("snap.create", "Create Checkpoint", "Gumawa ng Checkpoint"),
+    ("snap.restore", "Restore Checkpoint", "Ibalik ang Checkpoint"),
+    ("snap.save", "Save Snapshot to File", "I-save ang Snapshot sa File"),
+    ("snap.load", "Load Snapshot from File", "I-load ang Snapshot mula sa File"),
+    ("snap.label", "Checkpoint Label: ", "Label ng Checkpoint: "),
+    ("snap.created", "Checkpoint '{}' saved.", "Na-save ang checkpoint na '{}'."),
+    ("snap.restored", "Restored checkpoint '{}'.", "Naibalik ang checkpoint na '{}'."),
+    ("snap.missing", "No checkpoint named '{}'.", "Walang checkpoint na '{}'."),
+    ("snap.replace_warning", "This replaces all current accounts, rates, and settings.", "Papalitan nito ang lahat ng kasalukuyang account, palitan, at setting."),
+    ("snap.cancelled", "Cancelled.", "Kinansela."),
+    ("snap.path", "File Path [{}]: ", "Lokasyon ng File [{}]: "),
+    ("snap.written", "Snapshot written to {}.", "Naisulat ang snapshot sa {}."),
+    ("snap.write_failed", "Could not write snapshot: {}", "Hindi maisulat ang snapshot: {}"),
+    ("snap.loaded", "Snapshot loaded from {}.", "Na-load ang snapshot mula sa {}."),
+    ("snap.load_failed", "Could not load snapshot: {}", "Hindi ma-load ang snapshot: {}"),
+    ("snap.compact", "Compact Old Transactions", "Pagsamahin ang Lumang Transaksyon"),
+    ("snap.compact_before", "Compact Transactions Before (YYYY-MM-DD): ", "Pagsamahin ang Transaksyon Bago ang (YYYY-MM-DD): "),
+    ("snap.compact_bad_date", "Invalid date.", "Hindi wastong petsa."),
+    ("snap.archive_path", "Archive File: ", "File ng Archive: "),
+    ("snap.compact_warning", "Older transactions will be replaced by one opening balance per account; the originals go to the archive file only.", "Papalitan ng iisang panimulang balanse bawat account ang mas lumang transaksyon; sa archive file lang mapupunta ang mga orihinal."),
+    ("snap.compacted", "Compacted {} transaction(s) from {} account(s) into {}.", "Napagsama ang {} transaksyon mula sa {} account sa {}."),
+    ("snap.compact_none", "No transactions to compact.", "Walang transaksyong mapagsasama."),
+    ("snap.compact_failed", "Could not compact transactions: {}", "Hindi mapagsama ang transaksyon: {}"),
+    ("session.resumed", "Resumed the saved session from {}.", "Ipinagpatuloy ang naka-save na session mula sa {}."),
+    ("session.demo", "Started a demo bank: {} accounts, {} transactions (seed {}).", "Nagsimula ng demo na bangko: {} account, {} transaksyon (seed {})."),
+    ("session.goodbye", "Input closed. Goodbye!", "Sarado na ang input. Paalam!"),
+    ("session.save_failed", "Could not save the session to {}: {}", "Hindi ma-save ang session sa {}: {}"),
+    ("session.journal_failed", "Could not write the journal: {}", "Hindi maisulat ang journal: {}"),
+    ("session.log_in", "Customer ID to log in as (Enter for a staff session): ", "ID ng Kustomer na magla-log in (Enter para sa session ng kawani): "),
+    ("session.pin", "PIN of one of the customer's accounts: ", "PIN ng isa sa mga account ng kustomer: "),
+    ("session.welcome", "Logged in as {}.", "Naka-log in bilang {}."),
+    ("session.customer", "Customer: {} (ID {})", "Kustomer: {} (ID {})"),
+    ("session.read_only", "Read-only: changes are disabled and nothing is saved.", "Read-only: hindi pinapayagan ang mga pagbabago at walang isi-save."),
+    ("session.read_only_refused", "The bank is open read-only; nothing was changed.", "Read-only ang bangko; walang binago."),
+    // Savings goals
+    ("goal.set", "Set a goal", "Magtakda ng layunin"),
+    ("goal.show", "Show progress", "Ipakita ang pag-usad"),
+    ("goal.remove", "Remove a goal", "Alisin ang layunin"),
+    ("goal.name", "Goal Name: ", "Pangalan ng Layunin: "),
+    ("goal.target", "Target Amount ({}): ", "Halagang Target ({}): "),
+    ("goal.date", "Target Date (YYYY-MM-DD): ", "Petsang Target (YYYY-MM-DD): "),
+    ("goal.bad_date", "Please enter a date as YYYY-MM-DD.", "Maglagay ng petsa bilang YYYY-MM-DD."),
+    ("goal.added", "Goal {} set.", "Naitakda ang layuning {}."),
+    ("goal.removed", "Goal {} removed.", "Naalis ang layuning {}."),
+    ("goal.failed", "Goal not updated: {}", "Hindi nabago ang layunin: {}"),
+    ("goal.none", "This account has no savings goals.", "Walang layunin sa pag-iipon ang account na ito."),
+    ("goal.heading", "{} (by {})", "{} (bago ang {})"),
+    ("goal.saved", "Saved {} of {}", "Naipon ang {} sa {}"),
+    ("goal.reached", "Goal reached.", "Naabot na ang layunin."),
+    ("goal.overdue", "Target date passed; {} short.", "Lumipas na ang petsang target; kulang ng {}."),
+    ("goal.monthly", "Deposit {} a month for {} months to reach it.", "Magdeposito ng {} kada buwan sa loob ng {} buwan para maabot ito."),
+    // Budget envelopes
+    ("summary.month", "Month (YYYY-MM; blank for this month): ", "Buwan (YYYY-MM; blangko para sa buwang ito): "),
+    ("summary.invalid_month", "Invalid month {} (expected YYYY-MM).", "Hindi wastong buwan {} (inaasahan ang YYYY-MM)."),
+    ("summary.title", "Summary for {}", "Buod para sa {}"),
+    ("tax.year", "Year (YYYY; blank for last year): ", "Taon (YYYY; blangko para sa nakaraang taon): "),
+    ("tax.invalid_year", "Invalid year {} (expected YYYY).", "Hindi wastong taon {} (inaasahan ang YYYY)."),
+    ("tax.title", "Interest and tax withheld for {}, {}", "Interes at buwis na kinaltas para sa {}, {}"),
+    ("tax.total", "Total", "Kabuuan"),
+    ("tax.html_prompt", "Save a printable certificate (HTML, print to PDF) (Y/N)? ", "I-save ang sertipikong maipi-print (HTML, i-print sa PDF) (O/H)? "),
+    ("tax.html_saved", "Saved the certificate to {}.", "Na-save ang sertipiko sa {}."),
+    ("tags.tag", "Tag a transaction", "Lagyan ng tag ang isang transaksyon"),
+    ("tags.report", "Report by tag", "Ulat ayon sa tag"),
+    ("tags.number", "Transaction #: ", "Transaksyon #: "),
+    ("tags.enter", "Tags (comma-separated; blank removes them): ", "Mga tag (hiwalay sa kuwit; blangko para alisin): "),
+    ("tags.saved", "Transaction {} tagged: {}.", "Nalagyan ng tag ang transaksyon {}: {}."),
+    ("tags.cleared", "Tags removed from transaction {}.", "Inalis ang mga tag sa transaksyon {}."),
+    ("tags.failed", "Tags not saved: {}", "Hindi nai-save ang mga tag: {}"),
+    ("tags.none", "No tagged transactions in this period.", "Walang transaksyong may tag sa panahong ito."),
+    ("tags.title", "Tags for {}, {} to {}", "Mga Tag para sa {}, {} hanggang {}"),
+    ("tags.overlap", "A transaction with several tags counts under each of them.", "Ang transaksyong may ilang tag ay binibilang sa bawat isa."),
+    ("budget.set", "Set a monthly budget", "Magtakda ng buwanang badyet"),
+    ("budget.show", "Show this month's budgets", "Ipakita ang mga badyet ngayong buwan"),
+    ("budget.remove", "Remove a budget", "Alisin ang badyet"),
+    ("budget.category", "Category: ", "Kategorya: "),
+    ("budget.limit", "Monthly Limit ({}): ", "Buwanang Limitasyon ({}): "),
+    ("budget.added", "Budget for {} set.", "Naitakda ang badyet para sa {}."),
+    ("budget.removed", "Budget for {} removed.", "Naalis ang badyet para sa {}."),
+    ("budget.failed", "Budget not updated: {}", "Hindi nabago ang badyet: {}"),
+    ("budget.none", "This account has no budgets.", "Walang badyet ang account na ito."),
+    ("budget.title", "Budgets for {}", "Mga Badyet para sa {}"),
+    ("budget.ok", "ok", "ayos"),
+    ("budget.over", "over by {}", "lampas ng {}"),
+    ("budget.choose", "Budget category ({}; blank for none): ", "Kategorya ng badyet ({}; iwanang blangko kung wala): "),
+    ("budget.left", "{} left in the {} budget this month.", "May natitirang {} sa badyet na {} ngayong buwan."),
+    ("budget.warning", "Warning: over the {} budget by {} this month.", "Babala: lampas sa badyet na {} ng {} ngayong buwan."),
+    // Standing orders
+    ("order.create", "Set up a standing order", "Mag-set up ng standing order"),
+    ("order.list", "List standing orders", "Ilista ang mga standing order"),
+    ("order.skip", "Skip the next transfer", "Laktawan ang susunod na transfer"),
+    ("order.cancel", "Cancel a standing order", "Kanselahin ang standing order"),
+    ("order.every", "Every how many days (1-{}): ", "Kada ilang araw (1-{}): "),
+    ("order.start", "First Transfer Date (YYYY-MM-DD, blank for today): ", "Petsa ng Unang Transfer (YYYY-MM-DD, blangko para ngayon): "),
+    ("order.id", "Standing Order ID: ", "ID ng Standing Order: "),
+    ("order.days", "{} day(s)", "{} araw"),
+    ("order.created", "Standing order {} set up; first transfer on {}.", "Na-set up ang standing order {}; unang transfer sa {}."),
+    ("order.skipped", "Skipped standing order {}; next transfer on {}.", "Nilaktawan ang standing order {}; susunod na transfer sa {}."),
+    ("order.cancelled", "Standing order {} cancelled.", "Nakansela ang standing order {}."),
+    ("order.failed", "Standing order not updated: {}", "Hindi nabago ang standing order: {}"),
+    ("order.none", "There are no standing orders.", "Walang standing order."),
+    ("order.ran", "{} order {}: transferred {} from {} to {}.", "{} order {}: nailipat ang {} mula {} papunta {}."),
+    ("order.run_failed", "{} order {}: failed: {}", "{} order {}: nabigo: {}"),
+    // FX forwards and end of day
+    ("forward.book", "Book a forward", "Mag-book ng forward"),
+    ("forward.list", "Open forwards (mark-to-market)", "Mga bukas na forward (mark-to-market)"),
+    ("forward.side", "Side (buy/sell): ", "Panig (buy/sell): "),
+    ("forward.currency", "Foreign Currency: ", "Dayuhang Pera: "),
+    ("forward.rate", "Agreed Rate ({} per {}): ", "Napagkasunduang Palitan ({} kada {}): "),
+    ("forward.value_date", "Value Date (YYYY-MM-DD): ", "Petsa ng Halaga (YYYY-MM-DD): "),
+    ("forward.booked", "Forward {} booked; settles on {}.", "Na-book ang forward {}; mase-settle sa {}."),
+    ("forward.failed", "Forward not booked: {}", "Hindi na-book ang forward: {}"),
+    ("forward.none", "There are no open forwards.", "Walang bukas na forward."),
+    ("eod.nothing_due", "No forwards, standing orders, sweeps, or service charges due by {}.", "Walang forward, standing order, sweep, o service charge na dapat gawin hanggang {}."),
+    ("forward.value_failed", "Could not value forwards: {}", "Hindi matasa ang mga forward: {}"),
+    ("eod.carried", "{} {}: no rate is published on a non-business day; carried the last close, {}.", "{} {}: walang palitang inilalabas sa araw na walang negosyo; dinala ang huling pangwakas na palitan, {}."),
+    ("eod.settled", "Forward {} settled for {} ({}): {}.", "Na-settle ang forward {} para kay {} ({}): {}."),
+    ("eod.settle_failed", "Forward {} not settled: {}", "Hindi na-settle ang forward {}: {}"),
+    ("eod.swept", "{} sweep {}: moved {} from {} to {}.", "{} sweep {}: nailipat ang {} mula {} papunta {}."),
+    ("eod.sweep_failed", "{} sweep {}: failed: {}", "{} sweep {}: nabigo: {}"),
+    ("eod.charged", "{} {} on {}: charged {} (average daily balance {}).", "{} {} sa {}: siningil ng {} (karaniwang arawang balanse {})."),
+    ("eod.charge_failed", "{} {} on {}: failed: {}", "{} {} sa {}: nabigo: {}"),
+    ("eod.statements", "{}: issued {} month-end statement(s).", "{}: naglabas ng {} buwanang statement."),
+    // Limit orders
+    ("inbox.empty", "Your inbox is empty.", "Walang laman ang iyong inbox."),
+    ("inbox.dismiss", "Dismiss a message", "Alisin ang isang mensahe"),
+    ("inbox.dismiss_all", "Dismiss all of them", "Alisin ang lahat"),
+    ("inbox.keep", "Keep them", "Panatilihin ang mga ito"),
+    ("inbox.id", "Message ID: ", "ID ng Mensahe: "),
+    ("inbox.dismissed", "Dismissed message {}.", "Inalis ang mensahe {}."),
+    ("inbox.missing", "There is no message {} in your inbox.", "Walang mensahe {} sa iyong inbox."),
+    ("inbox.cleared", "Dismissed {} message(s).", "Inalis ang {} na mensahe."),
+    ("limit.place", "Place a limit order", "Maglagay ng limit order"),
+    ("limit.list", "Open limit orders", "Mga bukas na limit order"),
+    ("limit.cancel", "Cancel a limit order", "Kanselahin ang limit order"),
+    ("limit.amend", "Amend a limit order", "Baguhin ang limit order"),
+    ("limit.new_rate", "New limit rate: ", "Bagong limit na palitan: "),
+    ("limit.expires", "Expires after (YYYY-MM-DD, blank for good till cancelled): ", "Mag-e-expire pagkatapos ng (YYYY-MM-DD, blangko kung hanggang kanselahin): "),
+    ("limit.average", "Also wait for the rate to pass its moving average (e.g. sma:20 or ema:50, blank for none): ", "Hintayin ding lumampas ang palitan sa moving average nito (hal. sma:20 o ema:50, blangko kung wala): "),
+    ("limit.bad_average", "Please enter sma:DAYS or ema:DAYS, e.g. sma:20.", "Maglagay ng sma:DAYS o ema:DAYS, hal. sma:20."),
+    ("limit.gtc", "GTC", "GTC"),
+    ("limit.amended", "Limit order {} amended; it fills when the rate reaches {}.", "Nabago ang limit order {}; mapupunan ito kapag umabot ang palitan sa {}."),
+    ("limit.rate", "Convert when the rate reaches ({} per {}): ", "Ipalit kapag umabot ang palitan sa ({} kada {}): "),
+    ("limit.placed", "Limit order {} placed; it fills when the rate reaches {}.", "Nailagay ang limit order {}; mapupunan ito kapag umabot ang palitan sa {}."),
+    ("limit.failed", "Limit order not changed: {}", "Hindi nabago ang limit order: {}"),
+    ("limit.none", "There are no open limit orders.", "Walang bukas na limit order."),
+    ("limit.id", "Limit Order ID: ", "ID ng Limit Order: "),
+    ("limit.cancelled", "Cancelled limit order {}.", "Kinansela ang limit order {}."),
+    ("limit.filled", "Limit order {} filled for {} at {}: {}.", "Napunan ang limit order {} para kay {} sa {}: {}."),
+    ("limit.partly_filled", "Limit order {} partly filled for {} at {}: {}; {} remains open.", "Bahagyang napunan ang limit order {} para kay {} sa {}: {}; {} pa ang bukas."),
+    ("limit.fill_failed", "Limit order {} not filled: {}", "Hindi napunan ang limit order {}: {}"),
+    // FX profit and loss
+    ("pnl.none", "No accounts are held in a foreign currency.", "Walang account na nasa dayuhang pera."),
+    ("pnl.base", "Values, cost, and gains in {}:", "Mga halaga, gastos, at kita sa {}:"),
+    ("pnl.failed", "Could not value positions: {}", "Hindi matasa ang mga posisyon: {}"),
+    // Market simulation
+    ("sim.intro", "Every rate moves as a random walk, one day at a time; limit orders, forwards, and standing orders run as the days pass.", "Gumagalaw nang random ang bawat palitan, isang araw bawat hakbang; tumatakbo ang mga limit order, forward, at standing order habang lumilipas ang mga araw."),
+    ("sim.days", "Days to simulate: ", "Ilang araw ang i-simulate: "),
+    ("sim.drift", "Annual drift in % (Enter for 0): ", "Taunang drift sa % (Enter para sa 0): "),
+    ("sim.volatility", "Annual volatility in % (Enter for 10): ", "Taunang volatility sa % (Enter para sa 10): "),
+    ("sim.seed", "Seed (Enter for a random one): ", "Seed (Enter para sa random): "),
+    ("sim.bad_number", "Please enter a number, e.g. 2.5.", "Maglagay ng numero, hal. 2.5."),
+    ("sim.failed", "Cannot run the simulation: {}", "Hindi mapatakbo ang simulation: {}"),
+    ("sim.done", "Simulated {} day(s) with seed {}; use the same seed to repeat these rates.", "Na-simulate ang {} araw gamit ang seed {}; gamitin ang parehong seed para maulit ang mga palitang ito."),
+    ("portfolio.date", "As of (YYYY-MM-DD, Enter for today): ", "Hanggang (YYYY-MM-DD, Enter para ngayon): "),
+    ("portfolio.title", "Holdings of {} as of {} (in {}):", "Mga hawak ni {} hanggang {} (sa {}):"),
+    ("portfolio.cash", "Cash", "Cash"),
+    ("portfolio.forward", "Forward {}", "Forward {}"),
+    ("portfolio.total", "Total", "Kabuuan"),
+    ("portfolio.failed", "Could not value the portfolio: {}", "Hindi matasa ang portfolio: {}"),
+    // Dollar-cost averaging
+    ("dca.intro", "Compare buying a fixed amount every period with spending it all at once.", "Ihambing ang pagbili ng parehong halaga bawat panahon sa paggastos nito nang minsanan."),
+    ("dca.spend", "Currency to spend: ", "Perang gagastusin: "),
+    ("dca.buy", "Currency to buy: ", "Perang bibilhin: "),
+    ("dca.amount", "Amount per period ({}): ", "Halaga bawat panahon ({}): "),
+    ("dca.rates", "Rates ({} per 1 {}), separated by commas: ", "Mga palitan ({} bawat 1 {}), hiwalay sa kuwit: "),
+    ("dca.bad_rates", "Please enter positive rates separated by commas, e.g. 58,57.5,59.", "Maglagay ng mga positibong palitan na hiwalay sa kuwit, hal. 58,57.5,59."),
+    ("dca.invested", "Invested {} over {} periods.", "Namuhunan ng {} sa loob ng {} na panahon."),
+    ("dca.periodic", "Periodic: {} at an average of {}, worth {} at {}.", "Pana-panahon: {} sa karaniwang {}, nagkakahalaga ng {} sa {}."),
+    ("dca.lump_sum", "Lump sum: {} at {}, worth {}.", "Minsanan: {} sa {}, nagkakahalaga ng {}."),
+    ("dca.beat", "Periodic purchases beat the lump sum by {}.", "Mas mataas ang pana-panahong pagbili kaysa sa minsanan ng {}."),
+    ("dca.trail", "Periodic purchases trail the lump sum by {}.", "Mas mababa ang pana-panahong pagbili kaysa sa minsanan ng {}."),
+    ("dca.failed", "Cannot run the simulation: {}", "Hindi mapatakbo ang simulation: {}"),
+    // History and search
+    ("filter.hint", "Leave a filter blank to skip it.", "Iwanang blangko ang filter para laktawan ito."),
+    ("filter.type", "Type (deposit/withdraw): ", "Uri (deposit/withdraw): "),
+    ("filter.from", "From Date (YYYY-MM-DD): ", "Mula Petsa (YYYY-MM-DD): "),
+    ("filter.to", "To Date (YYYY-MM-DD): ", "Hanggang Petsa (YYYY-MM-DD): "),
+    ("filter.min", "Minimum Amount: ", "Pinakamababang Halaga: "),
+    ("filter.max", "Maximum Amount: ", "Pinakamataas na Halaga: "),
+    ("filter.memo", "Memo contains: ", "Nasa memo: "),
+    ("history.none", "No matching transactions.", "Walang tugmang transaksyon."),
+    ("history.count", "{} transaction(s).", "{} transaksyon."),
+    ("search.count", "{} matching transaction(s).", "{} tugmang transaksyon."),
+    // Undo
+    ("undo.empty", "Nothing to undo.", "Walang maa-undo."),
+    ("undo.deposit", "Last operation: deposit of {} to {}.", "Huling operasyon: deposito na {} sa {}."),
+    ("undo.withdraw", "Last operation: withdrawal of {} from {}.", "Huling operasyon: pag-withdraw ng {} mula sa {}."),
+    ("undo.rate", "Last operation: {} rate change (previously {}).", "Huling operasyon: pagbago ng palitan ng {} (dati ay {})."),
+    ("undo.needs_admin", "The last operation was a rate change; switch to Admin to undo it.", "Pagbago ng palitan ang huling operasyon; lumipat sa Admin para i-undo ito."),
+    ("undo.ask", "Undo it (Y/N)? ", "I-undo ito (O/H)? "),
+    ("undo.done", "Undone.", "Na-undo na."),
+    ("undo.failed", "Cannot undo: {}.", "Hindi ma-undo: {}."),
+    // Macros
+    ("macro.none", "No macros are defined. Add [[macro]] tables to forex.toml.", "Walang nakatakdang macro. Magdagdag ng [[macro]] sa forex.toml."),
+    ("macro.customer", "Macros run only in staff sessions.", "Sa session ng kawani lamang tumatakbo ang macro."),
+    ("macro.select", "Macro number: ", "Numero ng macro: "),
+    ("macro.invalid", "No macro has that number.", "Walang macro na may ganyang numero."),
+    ("macro.param", "{}: ", "{}: "),
+    ("macro.failed", "Stopped at command {} of {}: {}", "Huminto sa utos {} ng {}: {}"),
+    ("macro.done", "Ran {} command(s).", "Napatakbo ang {} utos."),
+    // Rounding
+    ("rounding.current", "Current Strategy: {} (to each currency's minor unit)", "Kasalukuyang Paraan: {} (sa pinakamaliit na yunit ng bawat pera)"),
+    ("rounding.none", "No rounding residue recorded.", "Walang naitalang natirang pag-round."),
+    ("rounding.effect", "Net Effect: {} (exact minus posted, at transfer rates)", "Kabuuang Epekto: {} (eksakto bawas naitala, sa transfer rate)"),
+    ("rounding.ask", "Change rounding strategy (Y/N)? ", "Palitan ang paraan ng pag-round (O/H)? "),
+    ("rounding.even", "Banker's rounding (half to even)", "Banker's rounding (kalahati papunta sa even)"),
+    ("rounding.half_away", "Half away from zero", "Kalahati palayo sa zero"),
+    ("rounding.to_zero", "Toward zero (truncate)", "Papunta sa zero (putulin)"),
+    ("rounding.away", "Away from zero", "Palayo sa zero"),
+    ("rounding.set", "Rounding strategy set to {}.", "Itinakda ang paraan ng pag-round sa {}."),
+    ("help.register", "Open an account, optionally protected by a PIN", "Magbukas ng account, may PIN kung nais"),
+    ("help.list_accounts", "Show every account with its balance and PIN status", "Ipakita ang bawat account, balanse, at PIN"),
+    ("help.deposit", "Add money to an account", "Magdagdag ng pera sa account"),
+    ("help.withdraw", "Take money out of an account, up to its balance", "Maglabas ng pera mula sa account, hanggang sa balanse"),
+    ("help.transfer", "Move money between accounts, converting if currencies differ", "Maglipat ng pera sa pagitan ng account, ipinapalit kung magkaiba ang pera"),
+    ("help.standing_orders", "Repeat a transfer every few days; skip or cancel it", "Ulitin ang transfer kada ilang araw; laktawan o kanselahin"),
+    ("help.show_rates", "List the currency catalog and when each rate changed", "Ilista ang mga pera at kailan huling binago ang palitan"),
+    ("help.rate_history", "A currency's recent daily closing rates with their moving averages", "Mga kamakailang pangwakas na palitan ng isang pera at ang kanilang moving average"),
+    ("help.rate_summary", "Open, high, low, average, and close of each currency's rates over a period", "Bukas, pinakamataas, pinakamababa, karaniwan, at sara ng palitan ng bawat pera sa isang panahon"),
+    ("help.exchange", "Exchange money between a holder's accounts in two currencies", "Magpalit ng pera sa pagitan ng mga account ng may-ari sa dalawang pera"),
+    ("help.receipts", "Reprint the numbered receipt of an earlier conversion", "Muling i-print ang may numerong resibo ng naunang palitan"),
+    ("help.forwards", "Book a rate for a future date and see open forwards against spot", "Mag-book ng rate para sa petsa sa hinaharap at tingnan ang mga bukas na forward laban sa spot"),
+    ("help.limit_orders", "Convert automatically when a rate reaches a limit", "Awtomatikong magpalit kapag umabot ang palitan sa limit"),
+    ("help.pnl", "Cost basis and realized and unrealized FX gains of foreign-currency accounts", "Gastos at natanto at hindi pa natatantong kita sa FX ng mga account na nasa dayuhang pera"),
+    ("help.portfolio", "Value an account's cash and open forwards in the base currency on a given day", "Tasahin ang cash at bukas na forward ng account sa batayang pera sa isang araw"),
+    ("help.dca", "Compare periodic currency purchases with a lump sum over a rate series", "Ihambing ang pana-panahong pagbili ng pera sa minsanan sa isang serye ng palitan"),
+    ("help.record_rate", "Overwrite a currency's rate against the base currency", "Palitan ang rate ng pera laban sa base na pera"),
+    ("help.currencies", "Add, rename, or retire catalog currencies, define a basket, or set a cash rate", "Magdagdag, magpalit ng pangalan, o magretiro ng pera, gumawa ng basket, o magtakda ng palitan sa cash"),
+    ("help.simulate", "Move every rate as a random walk for some days and watch FX gains and losses", "Igalaw ang bawat palitan nang random sa ilang araw at panoorin ang kita at lugi sa FX"),
+    ("help.show_interest", "Forecast day-by-day compound interest", "Tantiyahin ang interes araw-araw"),
+    ("help.compare_accounts", "Forecast two accounts side by side and find the day one overtakes the other", "Tantiyahin ang dalawang account nang magkatabi at alamin kung kailan malalampasan ng isa ang isa"),
+    ("help.compare_rates", "Compare balance forecasts at several interest rates", "Paghambingin ang tantiya ng balanse sa iba't ibang interes"),
+    ("help.till", "Move cash through the teller's drawer and balance it at the end of the day", "Ilipat ang cash sa kaha ng teller at balansehin ito sa katapusan ng araw"),
+    ("help.apy", "Convert between a nominal annual rate and its effective annual yield", "I-convert ang nominal na taunang interes at ang epektibong taunang kita nito"),
+    ("help.calculator", "Forecast interest on any amount, rate, and compounding without an account", "Tantiyahin ang interes sa anumang halaga, interes, at pag-compound nang walang account"),
+    ("help.monte_carlo", "See the spread of where an account's balance may end up over randomized rate paths", "Tingnan ang saklaw ng maaaring kalabasan ng balanse ng account sa mga random na landas ng interes"),
+    ("help.paydown", "Compare paying a sum off a loan with depositing it", "Paghambingin ang pagbabayad ng halaga sa utang at ang pagdeposito nito"),
+    ("help.goals", "Set savings goals and track progress toward them", "Magtakda ng layunin sa pag-iipon at subaybayan ang pag-usad"),
+    ("help.budget", "Set monthly budgets by category and see what is left", "Magtakda ng buwanang badyet ayon sa kategorya at tingnan ang natitira"),
+    ("help.summary", "Each account's inflow, outflow, interest, fees, and net change over a month", "Pumasok, lumabas, interes, bayarin, at netong pagbabago ng bawat account sa isang buwan"),
+    ("help.tax", "An account's interest and the tax withheld from it over a year, as CSV or a printable certificate", "Interes ng isang account at ang buwis na kinaltas dito sa loob ng isang taon, bilang CSV o sertipikong maipi-print"),
+    ("help.tags", "Tag transactions and total what came in and went out per tag", "Lagyan ng tag ang mga transaksyon at kabuuin ang pumasok at lumabas bawat tag"),
+    ("help.post_interest", "Credit accrued interest to an account", "Ipasok ang naipong interes sa account"),
+    ("help.rounding", "Choose the rounding strategy and view residues", "Pumili ng paraan ng pag-round at tingnan ang natira"),
+    ("help.set_interest", "Change the annual interest rate for all accounts", "Palitan ang taunang interes ng lahat ng account"),
+    ("help.year_basis", "Change how many days a year counts in daily interest, for one account or all", "Palitan kung ilang araw ang isang taon sa araw-araw na interes, para sa isang account o lahat"),
+    ("help.holds", "Keep money back from an account without posting it, then release it or withdraw it", "Magtabi ng pera sa account nang hindi ito pino-post, saka ito alisin o i-withdraw"),
+    ("help.cheques", "Deposit cheques that are credited at once but available only once they clear, and return bounced ones", "Magdeposito ng tsekeng naipapasok agad pero magagamit lang kapag na-clear, at ibalik ang mga tumalbog"),
+    ("help.limit_profiles", "Cap deposits, withdrawals, and daily conversions by account type or KYC status, and set account types", "Limitahan ang deposito, withdraw, at palitan bawat araw ayon sa uri ng account o katayuan ng KYC, at itakda ang uri ng account"),
+    ("help.service_charges", "Set an account's monthly service charge and the fee for falling below its maintaining balance", "Itakda ang buwanang service charge ng account at ang singil kapag kulang sa maintaining balance"),
+    ("help.interest_grace", "Make deposits wait a number of days before they earn interest, for one account or all", "Paghintayin ang mga deposito ng ilang araw bago kumita ng interes, para sa isang account o lahat"),
+    ("help.interest_method", "Accrue interest on daily, average daily, or minimum monthly balances, for one account or all", "Kalkulahin ang interes sa araw-araw, karaniwan, o pinakamababang buwanang balanse, para sa isang account o lahat"),
+    ("help.promotion", "Add a bonus to an account's rate for a number of days, or end it early", "Magdagdag ng bonus sa interes ng account nang ilang araw, o tapusin ito nang maaga"),
+    ("help.end_of_day", "Settle forwards and make standing-order transfers due today", "I-settle ang mga forward at gawin ang mga standing order na dapat ngayon"),
+    ("help.inbox", "Read and dismiss alerts on your accounts and flagged transactions", "Basahin at alisin ang mga alerto sa iyong mga account at mga na-flag na transaksyon"),
+    ("help.review_flagged", "Approve large transactions waiting for review", "Aprubahan ang malalaking transaksyong naghihintay ng pagsusuri"),
+    ("help.approvals", "Approve or reject withdrawals and transfers held above the approval threshold", "Aprubahan o tanggihan ang mga withdraw at paglipat na naka-hold dahil lampas sa threshold ng pag-apruba"),
+    ("help.verify", "Check that balances add up and every transfer has both legs", "Tiyaking tugma ang mga balanse at may dalawang panig ang bawat transfer"),
+    ("help.history", "Statement with running balance and filters", "Pahayag na may tumatakbong balanse at mga filter"),
+    ("help.search", "Find transactions across all accounts", "Maghanap ng transaksyon sa lahat ng account"),
+    ("help.customers", "Manage customers, their KYC details, and the accounts they own", "Pamahalaan ang mga kustomer, kanilang detalye sa KYC, at kanilang mga account"),
+    ("help.aliases", "Give accounts alternate names that account prompts accept", "Bigyan ang mga account ng ibang pangalan na tinatanggap sa mga prompt"),
+    ("help.archive", "Hide emptied accounts from menus and reports, keeping their history, or bring them back", "Itago ang mga naubos na account sa mga menu at ulat nang hindi binubura ang kasaysayan, o ibalik ang mga ito"),
+    ("help.snapshots", "Checkpoint, restore, save, or load the bank state", "Mag-checkpoint, ibalik, i-save, o i-load ang estado ng bangko"),
+    ("help.undo", "Reverse the most recent transaction or rate change", "Baligtarin ang pinakahuling transaksyon o pagbago ng rate"),
+    ("help.macros", "Run a named sequence of commands from forex.toml", "Patakbuhin ang nakapangalang sunud-sunod na utos mula sa forex.toml"),
+    ("help.help", "Show this screen", "Ipakita ang screen na ito"),
+    ("help.switch_role", "Change between Teller and Admin", "Magpalit sa pagitan ng Teller at Admin"),
+    ("help.log_in", "Log a customer in or out; a customer session shows only that customer's accounts", "Mag-log in o mag-log out ng kustomer; ipinapakita lamang ng session ng kustomer ang sarili niyang mga account"),
+    ("help.glossary", "Glossary", "Glosaryo"),
+    ("help.base", "- Base currency: {} ({}). Accounts are opened in it and every rate is quoted against it.", "- Base na pera: {} ({}). Dito binubuksan ang mga account at dito nakabatay ang bawat rate."),
+    ("help.quote", "- Rate: the price of 1 unit of a currency in the base currency, e.g. 1 {} = {} {}.", "- Rate: ang presyo ng 1 yunit ng pera sa base na pera, hal. 1 {} = {} {}."),
+    ("help.cash_rate", "- Cash rate: the rate for bills and coins over the counter, used by the till's walk-in exchanges; a currency without one uses its transfer rate.", "- Palitan sa cash: ang palitan para sa perang papel at barya sa counter, gamit sa walk-in na palitan ng kaha; kung wala, ang palitan ng transfer ang gamit."),
+    ("help.convert", "- Conversion: amount x source rate / target rate, rounded to the target currency's minor unit. Rounding: {}.", "- Pagpapalit: halaga x rate ng pinagmulan / rate ng kapalit, ni-round sa pinakamaliit na yunit ng kapalit. Pag-round: {}."),
+    ("help.interest", "- Interest: compounded daily; daily interest = balance x annual rate / {}. Current annual rate: {}%.", "- Interes: araw-araw na pinagsasama; interes bawat araw = balanse x taunang interes / {}. Kasalukuyang taunang interes: {}%."),
+    ("help.day_count_365", "- Day-count basis: Actual/365 Fixed; every year, leap years included, counts as 365 days.", "- Batayan ng bilang ng araw: Actual/365 Fixed; bawat taon, kasama ang leap year, ay 365 na araw."),
+    ("help.day_count_actual", "- Day-count basis: Actual/Actual; a leap year counts as 366 days, any other as 365.", "- Batayan ng bilang ng araw: Actual/Actual; 366 na araw ang leap year, 365 ang iba."),
+    ("help.average_daily_balance", "- Interest method: average daily balance; a month's interest earns nothing until it is credited at the month's end.", "- Paraan ng interes: karaniwang araw-araw na balanse; walang kinikita ang interes ng buwan hanggang maipasok ito sa katapusan ng buwan."),
+    ("help.minimum_monthly_balance", "- Interest method: minimum monthly balance; a month earns only on the lowest balance held in it, credited at the month's end.", "- Paraan ng interes: pinakamababang buwanang balanse; kumikita lang ang buwan sa pinakamababang balanseng hawak dito, ipinapasok sa katapusan ng buwan."),
+    ("help.grace_days", "- A deposit starts earning interest {} days after it is posted.", "- Nagsisimulang kumita ng interes ang deposito {} araw matapos itong maipasok."),
+    ("help.day_count_360", "- Day-count basis: Actual/360; every year counts as 360 days, so a full year earns a little more than the annual rate.", "- Batayan ng bilang ng araw: Actual/360; 360 na araw ang bawat taon, kaya bahagyang lampas sa taunang interes ang kinikita sa buong taon."),
+    ("help.large", "- Large transactions: amounts above {} are flagged for admin review.", "- Malalaking transaksyon: ang higit sa {} ay ini-flag para suriin ng admin."),
+    ("help.confirm", "- Withdrawals and transfers above {} need an explicit confirmation.", "- Ang withdraw at paglipat na higit sa {} ay kailangan ng tahasang kumpirmasyon."),
+    ("help.approval", "- Withdrawals and transfers above {} wait for an admin's approval before they post.", "- Ang withdraw at paglipat na higit sa {} ay naghihintay ng pag-apruba ng admin bago mai-post."),
+    ("help.rate_change", "- Rate changes of more than {}% need an explicit confirmation.", "- Ang pagbago ng rate na higit sa {}% ay kailangan ng tahasang kumpirmasyon."),
+    ("help.unverified", "- Accounts whose holder has not passed KYC may move at most {} per transaction and {} per day.", "- Ang account na hindi pa beripikado ang may-ari ay makakagalaw ng hanggang {} bawat transaksyon at {} bawat araw."),
+    ("help.conversion_limit", "- Conversion limit on {}: {} per transaction, {} per account per day; an admin can override it.", "- Limitasyon ng palitan sa {}: {} bawat transaksyon, {} bawat account bawat araw; maaari itong lampasan ng admin."),
+    ("help.limit_profile", "- Limits for {}: {} per deposit, {} per withdrawal, {} converted per day.", "- Mga limitasyon para sa {}: {} bawat deposito, {} bawat withdraw, {} napapalitan bawat araw."),
+    ("help.no_cap", "no cap", "walang hangganan"),
+];
@@ -0,0 +1,66 @@
+use std::fs::OpenOptions;
+use std::io::{self, Write};
+use std::path::PathBuf;
+
+use crate::api::date::{format_timestamp, now_timestamp};
+
+/// An append-only record of the commands that changed the bank, each on one
+/// line in the form `--script` reads, so a session can be audited and
+/// replayed onto a fresh data file (`rust_forex --data NEW --script
+/// JOURNAL`). Every command follows a `#` comment, which replay skips,
+/// giving when it ran (UTC) and where it came from: `cli`, `script`,
+/// `macro NAME`, `repl`, or `console`. The file is created if missing,
+/// readable by its owner only on Unix, since it holds the PINs and
+/// passphrases the commands were given.
+#[derive(Debug, Clone)]
+pub struct Journal {
+    path: PathBuf,
+}
+
+impl Journal {
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        Self { path: path.into() }
+    }
+
+    /// Append the command `words` (its name, then its options) run from
+    /// `origin`. A command that cannot be written as a script line is kept
+    /// commented out, so the audit still shows it but replay stops matching.
+    pub fn record(&self, origin: &str, words: &[String]) -> io::Result<()> {
+        match script_line(words) {
+            Some(line) => self.append(&format!("# {} {}\n{}", format_timestamp(now_timestamp()), origin, line)),
+            None => self.note(origin, &format!("not replayable, a value holds a quote or line break: {}", words.join(" "))),
+        }
+    }
+
+    /// Append `note` as a comment from `origin`, for a change that has no
+    /// command form.
+    pub fn note(&self, origin: &str, note: &str) -> io::Result<()> {
+        self.append(&format!("# {} {}: {}", format_timestamp(now_timestamp()), origin, note))
+    }
+
+    fn append(&self, text: &str) -> io::Result<()> {
+        let mut options = OpenOptions::new();
+        options.create(true).append(true);
+        #[cfg(unix)]
+        std::os::unix::fs::OpenOptionsExt::mode(&mut options, 0o600);
+        writeln!(options.open(&self.path)?, "{}", text)
+    }
+}
+
+/// `words` joined into a script line, double-quoting the ones that are
+/// empty or hold whitespace. `None` when a word holds a double quote or a
+/// line break, which script lines cannot carry.
+pub fn script_line(words: &[String]) -> Option<String> {
+    let mut quoted = Vec::with_capacity(words.len());
+    for word in words {
+        if word.contains(['"', '\n', '\r']) {
+            return None;
+        }
+        if word.is_empty() || word.contains(char::is_whitespace) {
+            quoted.push(format!("\"{}\"", word));
+        } else {
+            quoted.push(word.clone());
+        }
+    }
+    Some(quoted.join(" "))
+}
@@ -0,0 +1,299 @@
+use std::fmt;
+
+/// Minimal JSON value for machine-readable output. Numbers are kept as their
+/// decimal text so amounts are never rounded through a float.
+#[derive(Debug, Clone)]
+pub enum Json {
+    Null,
+    Bool(bool),
+    Num(String),
+    Str(String),
+    Array(Vec<Json>),
+    Object(Vec<(String, Json)>),
+}
+
+impl Json {
+    /// A JSON string from anything displayable.
+    pub fn str(value: impl fmt::Display) -> Self {
+        Json::Str(value.to_string())
+    }
+
+    /// A JSON number from anything whose `Display` is a valid number
+    /// (integers, `Decimal`).
+    pub fn num(value: impl fmt::Display) -> Self {
+        Json::Num(value.to_string())
+    }
+
+    /// A JSON object from `(key, value)` pairs, in order.
+    pub fn object(fields: impl IntoIterator<Item = (&'static str, Json)>) -> Self {
+        Json::Object(fields.into_iter().map(|(k, v)| (k.to_string(), v)).collect())
+    }
+
+    /// The value of `key` if this is an object that has it.
+    pub fn get(&self, key: &str) -> Option<&Json> {
+        match self {
+            Json::Object(fields) => fields.iter().find(|(k, _)| k == key).map(|(_, v)| v),
+            _ => None,
+        }
+    }
+
+    /// Parse one JSON document. Numbers keep their source text, like
+    /// `Json::num`, so they can be read back as `Decimal` exactly. Arrays
+    /// and objects may nest at most `MAX_DEPTH` levels.
+    pub fn parse(text: &str) -> Result<Json, String> {
+        let mut parser = Parser { chars: text.chars().collect(), pos: 0, depth: 0 };
+        let value = parser.value()?;
+        parser.skip_ws();
+        match parser.peek() {
+            None => Ok(value),
+            Some(c) => Err(format!("unexpected {:?} after the value at {}", c, parser.pos)),
+        }
+    }
+}
+
+/// How deeply arrays and objects may nest in a parsed document; deeper ones
+/// are refused before they can exhaust the stack.
+const MAX_DEPTH: usize = 32;
+
+/// Recursive-descent reader for `Json::parse`; `pos` indexes `chars` and
+/// `depth` counts the arrays and objects open.
+struct Parser {
+    chars: Vec<char>,
+    pos: usize,
+    depth: usize,
+}
+
+impl Parser {
+    /// Open an array or object, failing past `MAX_DEPTH`.
+    fn descend(&mut self) -> Result<(), String> {
+        self.depth += 1;
+        match self.depth > MAX_DEPTH {
+            true => Err(format!("nested more than {} levels deep at {}", MAX_DEPTH, self.pos)),
+            false => Ok(()),
+        }
+    }
+
+    fn peek(&self) -> Option<char> {
+        self.chars.get(self.pos).copied()
+    }
+
+    fn skip_ws(&mut self) {
+        while self.peek().is_some_and(|c| matches!(c, ' ' | '\t' | '\n' | '\r')) {
+            self.pos += 1;
+        }
+    }
+
+    fn expect(&mut self, want: char) -> Result<(), String> {
+        self.skip_ws();
+        match self.peek() {
+            Some(c) if c == want => {
+                self.pos += 1;
+                Ok(())
+            }
+            Some(c) => Err(format!("expected {:?} but found {:?} at {}", want, c, self.pos)),
+            None => Err(format!("expected {:?} but the input ended", want)),
+        }
+    }
+
+    fn value(&mut self) -> Result<Json, String> {
+        self.skip_ws();
+        match self.peek() {
+            Some(c @ ('{' | '[')) => {
+                self.descend()?;
+                let value = if c == '{' { self.object() } else { self.array() };
+                self.depth -= 1;
+                value
+            }
+            Some('"') => self.string().map(Json::Str),
+            Some('t') => self.literal("true", Json::Bool(true)),
+            Some('f') => self.literal("false", Json::Bool(false)),
+            Some('n') => self.literal("null", Json::Null),
+            Some(c) if c == '-' || c.is_ascii_digit() => self.number(),
+            Some(c) => Err(format!("unexpected {:?} at {}", c, self.pos)),
+            None => Err(String::from("the input ended before a value")),
+        }
+    }
+
+    fn object(&mut self) -> Result<Json, String> {
+        self.expect('{')?;
+        let mut fields = Vec::new();
+        self.skip_ws();
+        if self.peek() == Some('}') {
+            self.pos += 1;
+            return Ok(Json::Object(fields));
+        }
+        loop {
+            self.skip_ws();
+            let key = self.string()?;
+            self.expect(':')?;
+            fields.push((key, self.value()?));
+            self.skip_ws();
+            match self.peek() {
+                Some(',') => self.pos += 1,
+                _ => break,
+            }
+        }
+        self.expect('}')?;
+        Ok(Json::Object(fields))
+    }
+
+    fn array(&mut self) -> Result<Json, String> {
+        self.expect('[')?;
+        let mut items = Vec::new();
+        self.skip_ws();
+        if self.peek() == Some(']') {
+            self.pos += 1;
+            return Ok(Json::Array(items));
+        }
+        loop {
+            items.push(self.value()?);
+            self.skip_ws();
+            match self.peek() {
+                Some(',') => self.pos += 1,
+                _ => break,
+            }
+        }
+        self.expect(']')?;
+        Ok(Json::Array(items))
+    }
+
+    fn string(&mut self) -> Result<String, String> {
+        self.expect('"')?;
+        let mut out = String::new();
+        loop {
+            let c = self.peek().ok_or("unterminated string")?;
+            self.pos += 1;
+            match c {
+                '"' => return Ok(out),
+                '\\' => {
+                    let esc = self.peek().ok_or("unterminated string")?;
+                    self.pos += 1;
+                    out.push(match esc {
+                        '"' => '"',
+                        '\\' => '\\',
+                        '/' => '/',
+                        'b' => '\u{8}',
+                        'f' => '\u{c}',
+                        'n' => '\n',
+                        'r' => '\r',
+                        't' => '\t',
+                        'u' => self.unicode_escape()?,
+                        other => return Err(format!("invalid escape \\{} at {}", other, self.pos)),
+                    });
+                }
+                c if (c as u32) < 0x20 => return Err(format!("control character in string at {}", self.pos)),
+                c => out.push(c),
+            }
+        }
+    }
+
+    /// The four hex digits after `\u`, joining a UTF-16 surrogate pair.
+    fn unicode_escape(&mut self) -> Result<char, String> {
+        let unit = self.hex4()?;
+        let code = if (0xd800..0xdc00).contains(&unit) {
+            if self.chars.get(self.pos..self.pos + 2) != Some(&['\\', 'u']) {
+                return Err(format!("unpaired surrogate at {}", self.pos));
+            }
+            self.pos += 2;
+            let low = self.hex4()?;
+            if !(0xdc00..0xe000).contains(&low) {
+                return Err(format!("unpaired surrogate at {}", self.pos));
+            }
+            0x10000 + ((unit - 0xd800) << 10) + (low - 0xdc00)
+        } else {
+            unit
+        };
+        char::from_u32(code).ok_or_else(|| format!("invalid \\u escape at {}", self.pos))
+    }
+
+    fn hex4(&mut self) -> Result<u32, String> {
+        let digits: String = self.chars.get(self.pos..self.pos + 4).ok_or("truncated \\u escape")?.iter().collect();
+        self.pos += 4;
+        u32::from_str_radix(&digits, 16).map_err(|_| format!("invalid \\u escape {}", digits))
+    }
+
+    fn number(&mut self) -> Result<Json, String> {
+        let start = self.pos;
+        while self.peek().is_some_and(|c| c.is_ascii_digit() || matches!(c, '-' | '+' | '.' | 'e' | 'E')) {
+            self.pos += 1;
+        }
+        let text: String = self.chars[start..self.pos].iter().collect();
+        match text.parse::<f64>() {
+            Ok(_) => Ok(Json::Num(text)),
+            Err(_) => Err(format!("invalid number {} at {}", text, start)),
+        }
+    }
+
+    fn literal(&mut self, word: &str, value: Json) -> Result<Json, String> {
+        let end = self.pos + word.len();
+        if self.chars.get(self.pos..end).is_some_and(|s| s.iter().copied().eq(word.chars())) {
+            self.pos = end;
+            Ok(value)
+        } else {
+            Err(format!("unexpected token at {}", self.pos))
+        }
+    }
+}
+
+impl fmt::Display for Json {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Json::Null => write!(f, "null"),
+            Json::Bool(b) => write!(f, "{}", b),
+            Json::Num(n) => write!(f, "{}", n),
+            Json::Str(s) => write_escaped(f, s),
+            Json::Array(items) => {
+                write!(f, "[")?;
+                for (i, item) in items.iter().enumerate() {
+                    if i > 0 {
+                        write!(f, ",")?;
+                    }
+                    write!(f, "{}", item)?;
+                }
+                write!(f, "]")
+            }
+            Json::Object(fields) => {
+                write!(f, "{{")?;
+                for (i, (key, value)) in fields.iter().enumerate() {
+                    if i > 0 {
+                        write!(f, ",")?;
+                    }
+                    write_escaped(f, key)?;
+                    write!(f, ":{}", value)?;
+                }
+                write!(f, "}}")
+            }
+        }
+    }
+}
+
+fn write_escaped(f: &mut fmt::Formatter<'_>, s: &str) -> fmt::Result {
+    write!(f, "\"")?;
+    for ch in s.chars() {
+        match ch {
+            '"' => write!(f, "\\\"")?,
+            '\\' => write!(f, "\\\\")?,
+            '\n' => write!(f, "\\n")?,
+            '\r' => write!(f, "\\r")?,
+            '\t' => write!(f, "\\t")?,
+            c if (c as u32) < 0x20 => write!(f, "\\u{:04x}", c as u32)?,
+            c => write!(f, "{}", c)?,
+        }
+    }
+    write!(f, "\"")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn nesting_is_capped_without_overflowing_the_stack() {
+        let within = format!("{}1{}", "[".repeat(MAX_DEPTH), "]".repeat(MAX_DEPTH));
+        assert!(Json::parse(&within).is_ok());
+        let arrays = "[".repeat(100_000);
+        assert!(Json::parse(&arrays).unwrap_err().contains("nested more than 32 levels"));
+        let objects = "{\"a\":".repeat(100_000);
+        assert!(Json::parse(&objects).is_err());
+    }
+}
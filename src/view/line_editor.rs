@@ -0,0 +1,315 @@
+use std::io::{self, IsTerminal, Read, Write};
+use std::process::{Command, Stdio};
+use std::sync::{Mutex, MutexGuard};
+
+/// How many entered lines Up/Down can recall.
+const HISTORY_LIMIT: usize = 100;
+
+/// Lines entered so far and the words Tab can complete, shared by every
+/// prompt; `by_word` completes the word at the end of the line rather than
+/// the whole line.
+struct State {
+    history: Vec<String>,
+    completions: Vec<String>,
+    by_word: bool,
+}
+
+static STATE: Mutex<State> = Mutex::new(State { history: Vec::new(), completions: Vec::new(), by_word: false });
+
+fn state() -> MutexGuard<'static, State> {
+    STATE.lock().unwrap_or_else(|e| e.into_inner())
+}
+
+/// Replace the words offered by Tab (account names, currency codes).
+pub fn set_completions(words: Vec<String>) {
+    state().completions = words;
+}
+
+/// Have Tab complete the last word of the line, for typed commands, rather
+/// than the whole line, for prompts that take one answer.
+pub fn set_word_completion(by_word: bool) {
+    state().by_word = by_word;
+}
+
+/// Read one line from the terminal with editing:
+/// - Left/Right, Home/End (or Ctrl-A/Ctrl-E), Backspace/Delete, Ctrl-U to clear.
+/// - Up/Down step through earlier lines.
+/// - Tab completes the line, or its last word (`set_word_completion`), from
+///   `set_completions`; with several matches it fills in their common
+///   prefix, then lists them.
+///
+/// `masked` input (PINs, passphrases) is neither echoed, completed, nor kept
+/// in history. Returns `None` when stdin is not a terminal or it cannot be
+/// switched to raw mode with `stty`; callers then read a plain line instead.
+/// `Some(None)` means the user ended input: Ctrl-D on an empty line, or
+/// Ctrl-C (raw mode keeps the terminal from raising SIGINT).
+pub fn read_line(prompt: &str, masked: bool) -> Option<Option<String>> {
+    if !io::stdin().is_terminal() {
+        return None;
+    }
+    let saved = stty_output("-g")?;
+    if !stty(&["-icanon", "-echo", "-isig", "min", "1"]) {
+        return None;
+    }
+    let line = edit(prompt, masked);
+    stty(&[saved.trim()]);
+    Some(line)
+}
+
+enum Key {
+    Char(char),
+    Enter,
+    Backspace,
+    Delete,
+    Left,
+    Right,
+    Home,
+    End,
+    Up,
+    Down,
+    Tab,
+    ClearLine,
+    EndOfInput,
+    Interrupt,
+    Ignored,
+}
+
+fn edit(prompt: &str, masked: bool) -> Option<String> {
+    let mut buf: Vec<char> = Vec::new();
+    let mut pos = 0;
+    let mut recall = state().history.len();
+    let mut draft: Vec<char> = Vec::new();
+    let mut stdin = io::stdin().lock();
+
+    redraw(prompt, &buf, pos, masked);
+    loop {
+        let key = read_key(&mut stdin);
+        match key {
+            Key::Enter => break,
+            Key::Char(c) => {
+                buf.insert(pos, c);
+                pos += 1;
+            }
+            Key::Backspace if pos > 0 => {
+                pos -= 1;
+                buf.remove(pos);
+            }
+            Key::Delete if pos < buf.len() => {
+                buf.remove(pos);
+            }
+            Key::Left => pos = pos.saturating_sub(1),
+            Key::Right => pos = (pos + 1).min(buf.len()),
+            Key::Home => pos = 0,
+            Key::End => pos = buf.len(),
+            Key::ClearLine => {
+                buf.clear();
+                pos = 0;
+            }
+            Key::Up | Key::Down if !masked => {
+                let state = state();
+                let len = state.history.len();
+                if recall == len {
+                    draft = buf.clone();
+                }
+                recall = match key {
+                    Key::Up => recall.saturating_sub(1),
+                    _ => (recall + 1).min(len),
+                };
+                buf = state.history.get(recall).map_or_else(|| draft.clone(), |h| h.chars().collect());
+                pos = buf.len();
+            }
+            Key::Tab if !masked => {
+                let state = state();
+                if state.by_word {
+                    let start = last_word_start(&buf);
+                    let mut word = buf.split_off(start);
+                    complete(&mut word, &state.completions);
+                    buf.extend(word);
+                } else {
+                    complete(&mut buf, &state.completions);
+                }
+                pos = buf.len();
+            }
+            Key::EndOfInput if buf.is_empty() => return None,
+            Key::Interrupt => return None,
+            _ => {}
+        }
+        redraw(prompt, &buf, pos, masked);
+    }
+    println!();
+
+    let line: String = buf.iter().collect();
+    let mut state = state();
+    if !masked && !line.trim().is_empty() && state.history.last() != Some(&line) {
+        state.history.push(line.clone());
+        if state.history.len() > HISTORY_LIMIT {
+            state.history.remove(0);
+        }
+    }
+    Some(line)
+}
+
+fn read_key(stdin: &mut impl Read) -> Key {
+    let Some(b) = read_byte(stdin) else {
+        return Key::EndOfInput;
+    };
+    match b {
+        b'\r' | b'\n' => Key::Enter,
+        0x7f | 0x08 => Key::Backspace,
+        b'\t' => Key::Tab,
+        0x01 => Key::Home,
+        0x05 => Key::End,
+        0x15 => Key::ClearLine,
+        0x04 => Key::EndOfInput,
+        0x03 => Key::Interrupt,
+        0x1b => read_escape(stdin),
+        b if b >= 0x20 => read_utf8(stdin, b).map_or(Key::Ignored, Key::Char),
+        _ => Key::Ignored,
+    }
+}
+
+/// Decode `ESC [ X`, `ESC O X`, and `ESC [ n ~` sequences.
+fn read_escape(stdin: &mut impl Read) -> Key {
+    let Some(b'[' | b'O') = read_byte(stdin) else {
+        return Key::Ignored;
+    };
+    let mut param = 0u32;
+    loop {
+        let Some(b) = read_byte(stdin) else {
+            return Key::Ignored;
+        };
+        return match b {
+            b'0'..=b'9' => {
+                param = param * 10 + u32::from(b - b'0');
+                continue;
+            }
+            b'A' => Key::Up,
+            b'B' => Key::Down,
+            b'C' => Key::Right,
+            b'D' => Key::Left,
+            b'H' => Key::Home,
+            b'F' => Key::End,
+            b'~' => match param {
+                1 | 7 => Key::Home,
+                3 => Key::Delete,
+                4 | 8 => Key::End,
+                _ => Key::Ignored,
+            },
+            _ => Key::Ignored,
+        };
+    }
+}
+
+fn read_utf8(stdin: &mut impl Read, lead: u8) -> Option<char> {
+    let len = match lead {
+        0x00..=0x7f => 1,
+        0xc0..=0xdf => 2,
+        0xe0..=0xef => 3,
+        0xf0..=0xf7 => 4,
+        _ => return None,
+    };
+    let mut bytes = vec![lead];
+    for _ in 1..len {
+        bytes.push(read_byte(stdin)?);
+    }
+    std::str::from_utf8(&bytes).ok()?.chars().next()
+}
+
+fn read_byte(stdin: &mut impl Read) -> Option<u8> {
+    let mut byte = [0u8; 1];
+    match stdin.read(&mut byte) {
+        Ok(1) => Some(byte[0]),
+        _ => None,
+    }
+}
+
+/// Where the last word of `buf` starts: after the last whitespace outside
+/// double quotes.
+fn last_word_start(buf: &[char]) -> usize {
+    let mut start = 0;
+    let mut quoted = false;
+    for (i, c) in buf.iter().enumerate() {
+        match c {
+            '"' => quoted = !quoted,
+            c if c.is_whitespace() && !quoted => start = i + 1,
+            _ => {}
+        }
+    }
+    start
+}
+
+/// Complete `buf` against `words`, ignoring case. A word in double quotes
+/// (an account name with spaces) also matches without its opening quote.
+fn complete(buf: &mut Vec<char>, words: &[String]) {
+    let typed: String = buf.iter().collect::<String>().to_lowercase();
+    let matches: Vec<&String> = words
+        .iter()
+        .filter(|w| {
+            let w = w.to_lowercase();
+            w.starts_with(&typed) || w.strip_prefix('"').is_some_and(|w| w.starts_with(&typed))
+        })
+        .collect();
+    match matches.as_slice() {
+        [] => {}
+        [word] => *buf = word.chars().collect(),
+        many => {
+            let common = common_prefix(many);
+            if common.len() > buf.len() {
+                *buf = common;
+            } else {
+                let list: Vec<&str> = many.iter().map(|w| w.as_str()).collect();
+                println!("\n{}", list.join("  "));
+            }
+        }
+    }
+}
+
+/// Longest prefix shared by every word, ignoring case; the first word's
+/// spelling is kept.
+fn common_prefix(words: &[&String]) -> Vec<char> {
+    let first: Vec<char> = words[0].chars().collect();
+    let mut len = first.len();
+    for word in &words[1..] {
+        len = first
+            .iter()
+            .zip(word.chars())
+            .take(len)
+            .take_while(|(a, b)| a.to_lowercase().eq(b.to_lowercase()))
+            .count();
+    }
+    first[..len].to_vec()
+}
+
+/// Reprint the prompt and line, then put the cursor back at `pos`.
+fn redraw(prompt: &str, buf: &[char], pos: usize, masked: bool) {
+    let mut out = io::stdout().lock();
+    if masked {
+        let _ = write!(out, "\r{}", prompt);
+    } else {
+        let line: String = buf.iter().collect();
+        let _ = write!(out, "\r{}{}\x1b[K", prompt, line);
+        if pos < buf.len() {
+            let _ = write!(out, "\x1b[{}D", buf.len() - pos);
+        }
+    }
+    let _ = out.flush();
+}
+
+fn stty(args: &[&str]) -> bool {
+    Command::new("stty")
+        .args(args)
+        .stdin(Stdio::inherit())
+        .stderr(Stdio::null())
+        .status()
+        .is_ok_and(|st| st.success())
+}
+
+fn stty_output(arg: &str) -> Option<String> {
+    let out = Command::new("stty")
+        .arg(arg)
+        .stdin(Stdio::inherit())
+        .stderr(Stdio::null())
+        .output()
+        .ok()?;
+    out.status.success().then(|| String::from_utf8_lossy(&out.stdout).into_owned())
+}
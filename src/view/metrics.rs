@@ -0,0 +1,148 @@
+use std::collections::BTreeMap;
+use std::fmt::Write;
+
+use crate::api::bank::Bank;
+use crate::api::decimal::Decimal;
+use crate::api::event::BankEvent;
+
+/// Counters kept by the HTTP server since it started, rendered with gauges
+/// read from the bank for `GET /metrics` (Prometheus text format 0.0.4).
+/// - `requests`: answered requests by status code.
+/// - `transactions`: deposits and withdrawals posted, by type.
+/// - `conversions`: cross-currency transfers by (from, to): how many, and
+///   the amount debited in the source currency.
+/// - `quotes`: `/convert` quotes by (from, to).
+/// - `rate_update_failures`: rate updates the bank refused (unknown
+///   currency, or a move past the rate-change limit).
+#[derive(Debug, Default)]
+pub struct Metrics {
+    requests: BTreeMap<u16, u64>,
+    transactions: BTreeMap<String, u64>,
+    conversions: BTreeMap<(String, String), (u64, Decimal)>,
+    quotes: BTreeMap<(String, String), u64>,
+    rate_update_failures: u64,
+}
+
+impl Metrics {
+    pub fn record_request(&mut self, status: u16) {
+        *self.requests.entry(status).or_default() += 1;
+    }
+
+    pub fn record_quote(&mut self, from: &str, to: &str) {
+        *self.quotes.entry((from.to_string(), to.to_string())).or_default() += 1;
+    }
+
+    pub fn record_rate_failure(&mut self) {
+        self.rate_update_failures += 1;
+    }
+
+    /// Count the postings and conversions among `events`.
+    pub fn record_events(&mut self, events: &[BankEvent]) {
+        for event in events {
+            match event {
+                BankEvent::TransactionPosted { tx_type, .. } => {
+                    *self.transactions.entry(format!("{:?}", tx_type).to_lowercase()).or_default() += 1;
+                }
+                BankEvent::TransferCompleted { debited, credited, .. } if debited.currency != credited.currency => {
+                    let entry = self.conversions.entry((debited.currency.clone(), credited.currency.clone())).or_default();
+                    entry.0 += 1;
+                    entry.1 = entry.1.checked_add(debited.amount).unwrap_or(entry.1);
+                }
+                _ => {}
+            }
+        }
+    }
+
+    /// The exposition text: these counters, then gauges for the bank's
+    /// accounts, balances per currency, rates, and review queue.
+    pub fn render(&self, bank: &Bank, subscribers: usize) -> String {
+        let mut out = String::new();
+        let mut family = |name: &str, kind: &str, help: &str, samples: Vec<(String, String)>| {
+            let _ = writeln!(out, "# HELP {} {}", name, help);
+            let _ = writeln!(out, "# TYPE {} {}", name, kind);
+            for (labels, value) in samples {
+                let _ = writeln!(out, "{}{} {}", name, labels, value);
+            }
+        };
+
+        family(
+            "forex_http_requests_total",
+            "counter",
+            "HTTP requests answered, by status code.",
+            self.requests.iter().map(|(status, n)| (labels(&[("status", &status.to_string())]), n.to_string())).collect(),
+        );
+        family(
+            "forex_transactions_posted_total",
+            "counter",
+            "Deposits and withdrawals posted, by type.",
+            ["deposit", "withdraw"]
+                .iter()
+                .map(|t| (labels(&[("type", t)]), self.transactions.get(*t).copied().unwrap_or(0).to_string()))
+                .collect(),
+        );
+        family(
+            "forex_conversions_total",
+            "counter",
+            "Cross-currency transfers settled.",
+            self.conversions.iter().map(|((from, to), (n, _))| (labels(&[("from", from), ("to", to)]), n.to_string())).collect(),
+        );
+        family(
+            "forex_conversion_volume_total",
+            "counter",
+            "Amount debited by cross-currency transfers, in the source currency.",
+            self.conversions.iter().map(|((from, to), (_, volume))| (labels(&[("from", from), ("to", to)]), volume.to_string())).collect(),
+        );
+        family(
+            "forex_conversion_quotes_total",
+            "counter",
+            "Conversion quotes answered.",
+            self.quotes.iter().map(|((from, to), n)| (labels(&[("from", from), ("to", to)]), n.to_string())).collect(),
+        );
+        family(
+            "forex_rate_update_failures_total",
+            "counter",
+            "Exchange rate updates the bank refused.",
+            vec![(String::new(), self.rate_update_failures.to_string())],
+        );
+
+        let mut balances: BTreeMap<&str, Decimal> = BTreeMap::new();
+        for acct in &bank.accounts {
+            let total = balances.entry(acct.currency.as_str()).or_insert(Decimal::ZERO);
+            *total = total.checked_add(acct.get_balance().amount).unwrap_or(*total);
+        }
+        family("forex_accounts", "gauge", "Open accounts.", vec![(String::new(), bank.active_accounts().count().to_string())]);
+        family(
+            "forex_balance_total",
+            "gauge",
+            "Sum of account balances, by account currency.",
+            balances.iter().map(|(code, total)| (labels(&[("currency", code)]), total.to_string())).collect(),
+        );
+        family(
+            "forex_rate",
+            "gauge",
+            "Exchange rate: price of 1 unit in the base currency.",
+            bank.forex
+                .currencies_detailed()
+                .iter()
+                .map(|c| (labels(&[("code", &c.code)]), c.rate.to_string()))
+                .collect(),
+        );
+        family(
+            "forex_pending_reviews",
+            "gauge",
+            "Flagged transactions awaiting review.",
+            vec![(String::new(), bank.pending_reviews().len().to_string())],
+        );
+        family("forex_event_subscribers", "gauge", "Open event subscriptions: /events clients and GraphQL subscriptions.", vec![(String::new(), subscribers.to_string())]);
+        out
+    }
+}
+
+/// `{name="value",...}` with `\`, `"`, and newlines escaped.
+fn labels(pairs: &[(&str, &str)]) -> String {
+    let body: Vec<String> = pairs
+        .iter()
+        .map(|(name, value)| format!("{}=\"{}\"", name, value.replace('\\', "\\\\").replace('"', "\\\"").replace('\n', "\\n")))
+        .collect();
+    format!("{{{}}}", body.join(","))
+}
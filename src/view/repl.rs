@@ -0,0 +1,268 @@
+use std::io::{self, BufRead, IsTerminal, Write};
+use std::path::PathBuf;
+
+use crate::api::bank::Bank;
+use crate::api::config::MacroConfig;
+use crate::api::notify::EventBus;
+use crate::api::persist;
+use crate::view::cli::{self, report_notify_failures, CliError, Output, COMMANDS};
+use crate::view::journal::{script_line, Journal};
+use crate::view::line_editor;
+
+const PROMPT: &str = "forex> ";
+
+/// The options a command's bare words fill, in order: `deposit Alice 100
+/// rent` is `deposit --account Alice --amount 100 --memo rent`. Options
+/// given as `--key value` are skipped over, so `deposit --amount 100 Alice`
+/// works too.
+const POSITIONAL: &[(&str, &[&str])] = &[
+    ("rate-history", &["code", "days"]),
+    ("rate-summary", &["code", "start", "end"]),
+    ("rate", &["code", "rate"]),
+    ("cash-rate", &["code", "rate"]),
+    ("convert", &["amount", "from", "to"]),
+    ("conversions", &["account"]),
+    ("receipt", &["number"]),
+    ("turnover", &["account"]),
+    ("alias", &["account", "alias"]),
+    ("unalias", &["alias"]),
+    ("archive", &["account"]),
+    ("unarchive", &["account"]),
+    ("register", &["account", "currency"]),
+    ("deposit", &["account", "amount", "memo"]),
+    ("withdraw", &["account", "amount", "memo"]),
+    ("alerts", &["account"]),
+    ("service-charges", &["account"]),
+    ("account-type", &["account", "type"]),
+    ("limit-profiles", &["account"]),
+    ("hold", &["account", "amount", "reason"]),
+    ("holds", &["account"]),
+    ("release", &["account", "id"]),
+    ("capture", &["account", "id", "amount"]),
+    ("deposit-cheque", &["account", "amount", "number"]),
+    ("cheques", &["account"]),
+    ("return-cheque", &["account", "id", "reason"]),
+    ("transfer", &["from", "to", "amount"]),
+    ("exchange", &["from", "to", "amount"]),
+    ("balance", &["account"]),
+    ("history", &["account"]),
+    ("tags", &["account"]),
+    ("statement", &["account", "format"]),
+    ("forecast", &["account", "days"]),
+    ("interest-rate", &["account", "rate"]),
+    ("interest-rates", &["account"]),
+    ("end-promotion", &["account"]),
+    ("compare", &["account", "with", "days"]),
+    ("monte-carlo", &["account", "days"]),
+    ("pnl", &["account"]),
+    ("portfolio", &["account"]),
+    ("interest", &["days"]),
+    ("goals", &["account"]),
+    ("budget", &["account"]),
+    ("summary", &["month"]),
+    ("tax-certificate", &["account", "year"]),
+    ("schedule", &["loan"]),
+    ("repay", &["loan"]),
+    ("skip", &["order"]),
+    ("approve", &["id"]),
+    ("reject", &["id", "reason"]),
+    ("eod", &["date"]),
+    ("simulate", &["days"]),
+];
+
+/// Read commands typed at a `forex> ` prompt until `quit`, `exit`, or the
+/// end of input, for users who would rather type than walk the menus.
+/// Commands are the CLI's, with their main options given bare in order
+/// (see `POSITIONAL`), and macro names run the macro. Tab completes
+/// command, macro, and account names and currency codes. Each command that
+/// changes the bank saves it to `data` and is journaled, from `repl` or
+/// `macro NAME`, in its `--key value` form; the events commands cause go to
+/// `notifiers`. A failed command is reported and the prompt comes back.
+/// Returns the process exit code.
+pub fn run(bank: Bank, data: PathBuf, notifiers: EventBus, journal: Option<Journal>, macros: &[MacroConfig]) -> i32 {
+    let mut session = Repl { bank, data, notifiers, journal, macros };
+    line_editor::set_word_completion(true);
+    println!("Type help for the commands, quit to leave.");
+    loop {
+        session.refresh_completions();
+        let Some(line) = read_line() else { break };
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        if matches!(line, "quit" | "exit") {
+            break;
+        }
+        if let Err(e) = session.handle_line(line) {
+            eprintln!("Error: {}", e);
+        }
+    }
+    0
+}
+
+/// Read one line at the prompt: through `line_editor` on a terminal, else
+/// plainly. `None` at the end of input.
+fn read_line() -> Option<String> {
+    match line_editor::read_line(PROMPT, false) {
+        Some(line) => return line,
+        None if io::stdin().is_terminal() => {
+            print!("{}", PROMPT);
+            let _ = io::stdout().flush();
+        }
+        None => {}
+    }
+    let mut line = String::new();
+    match io::stdin().lock().read_line(&mut line) {
+        Ok(0) | Err(_) => None,
+        Ok(_) => Some(line),
+    }
+}
+
+/// The bank being driven, where it is saved, where its events go, the
+/// journal its changes are recorded in, and the macros it can run.
+struct Repl<'a> {
+    bank: Bank,
+    data: PathBuf,
+    notifiers: EventBus,
+    journal: Option<Journal>,
+    macros: &'a [MacroConfig],
+}
+
+impl Repl<'_> {
+    /// Run one typed line: `help`, a macro, or a command.
+    fn handle_line(&mut self, line: &str) -> Result<(), CliError> {
+        let words = cli::tokenize(line)?;
+        match words.split_first() {
+            Some((name, args)) if name == "help" => self.help(args),
+            Some((name, args)) => match self.macros.iter().find(|m| m.name == *name) {
+                Some(definition) => {
+                    for words in cli::expand_macro(definition, args)? {
+                        println!("> {}", script_line(&words).unwrap_or_else(|| words.join(" ")));
+                        self.run(&format!("macro {}", definition.name), words)?;
+                    }
+                    Ok(())
+                }
+                None => self.run("repl", expand(&words)?),
+            },
+            None => Ok(()),
+        }
+    }
+
+    /// Execute the command `words` and print its result. One that changes
+    /// the bank is saved, then journaled from `origin`.
+    fn run(&mut self, origin: &str, words: Vec<String>) -> Result<(), CliError> {
+        let command = cli::parse_words(&words)?;
+        let output = cli::execute(&mut self.bank, &command)?;
+        report_notify_failures(self.notifiers.drain(&mut self.bank));
+        println!("{}", output.to_text(&self.bank));
+        if command.mutates() {
+            persist::save(&self.bank, &self.data)?;
+            self.journal(origin, words, &output);
+        }
+        Ok(())
+    }
+
+    /// Record the command `words` from `origin` in the journal, if there is
+    /// one; a failed write is reported, as the command already succeeded.
+    fn journal(&self, origin: &str, words: Vec<String>, output: &Output) {
+        let Some(journal) = &self.journal else {
+            return;
+        };
+        if let Err(e) = journal.record(origin, &cli::pin_defaults(words, output)) {
+            eprintln!("Journal failed: {}", e);
+        }
+    }
+
+    /// Print the typed forms of the commands and the macros, or, for `help
+    /// COMMAND`, that command's options.
+    fn help(&self, args: &[String]) -> Result<(), CliError> {
+        if let [name] = args {
+            if let Some(definition) = self.macros.iter().find(|m| m.name == *name) {
+                println!("Macro {}, taking {}: {}", definition.name, definition.usage(), definition.description);
+                return Ok(());
+            }
+            let usage = cli::usage_of(name).ok_or_else(|| CliError::Usage(format!("unknown command {}", name)))?;
+            println!("{}", usage);
+            if let Some(form) = typed_form(name) {
+                println!("\nTyped: {}", form);
+            }
+            return Ok(());
+        }
+        if !args.is_empty() {
+            return Err(CliError::Usage(String::from("help takes at most one command")));
+        }
+        println!("Commands take their options as --key value, as on the command line. These");
+        println!("also take their main options bare, in this order:");
+        for (name, _) in POSITIONAL {
+            println!("  {}", typed_form(name).unwrap_or_default());
+        }
+        let others: Vec<&str> = COMMANDS.iter().copied().filter(|c| *c != "help" && typed_form(c).is_none()).collect();
+        println!("Other commands: {}", others.join(", "));
+        if !self.macros.is_empty() {
+            let names: Vec<&str> = self.macros.iter().map(|m| m.name.as_str()).collect();
+            println!("Macros: {}", names.join(", "));
+        }
+        println!("Type help COMMAND for its options, quit to leave. Tab completes names.");
+        Ok(())
+    }
+
+    /// Offer command, macro, and account names and currency codes to Tab
+    /// completion, quoting names that hold spaces.
+    fn refresh_completions(&self) {
+        let mut words: Vec<String> = COMMANDS.iter().map(|c| c.to_string()).chain(["quit".to_string()]).collect();
+        words.extend(self.macros.iter().map(|m| m.name.clone()));
+        words.extend(
+            self.bank
+                .accounts
+                .iter()
+                .filter(|a| !a.is_archived())
+                .flat_map(|a| std::iter::once(&a.name).chain(&a.aliases))
+                .map(|name| match name.contains(char::is_whitespace) {
+                    true => format!("\"{}\"", name),
+                    false => name.clone(),
+                }),
+        );
+        words.extend(self.bank.forex.currencies_detailed().into_iter().map(|c| c.code));
+        words.sort();
+        words.dedup();
+        line_editor::set_completions(words);
+    }
+}
+
+/// `words` with its bare words after the command turned into the options
+/// they stand for (see `POSITIONAL`), skipping those already given.
+fn expand(words: &[String]) -> Result<Vec<String>, CliError> {
+    let Some((name, rest)) = words.split_first() else {
+        return Ok(Vec::new());
+    };
+    let names = POSITIONAL.iter().find(|(c, _)| c == name).map_or(&[][..], |(_, names)| *names);
+    let mut out = vec![name.clone()];
+    let mut bare = Vec::new();
+    let mut iter = rest.iter();
+    while let Some(word) = iter.next() {
+        if word.starts_with("--") {
+            out.push(word.clone());
+            out.extend(iter.next().cloned());
+        } else {
+            bare.push(word);
+        }
+    }
+    let mut free = names.iter().filter(|n| !out.contains(&format!("--{}", n))).collect::<Vec<_>>().into_iter();
+    for word in bare {
+        let Some(key) = free.next() else {
+            return Err(CliError::Usage(match typed_form(name) {
+                Some(form) => format!("too many arguments; type {}", form),
+                None => format!("{} takes only --key value options", name),
+            }));
+        };
+        out.extend([format!("--{}", key), word.clone()]);
+    }
+    Ok(out)
+}
+
+/// How `command` is typed with its bare words, e.g. `deposit ACCOUNT
+/// AMOUNT MEMO`; `None` when it takes none.
+fn typed_form(command: &str) -> Option<String> {
+    let (name, names) = POSITIONAL.iter().find(|(c, _)| *c == command)?;
+    Some(std::iter::once(name.to_string()).chain(names.iter().map(|n| n.to_uppercase())).collect::<Vec<_>>().join(" "))
+}
@@ -0,0 +1,128 @@
+use std::collections::BTreeMap;
+use std::io::{self, BufRead, Write};
+use std::path::PathBuf;
+
+use crate::api::bank::{Bank, BankError};
+use crate::api::error::Error;
+use crate::api::notify::EventBus;
+use crate::api::persist;
+use crate::view::cli::{execute, parse, report_notify_failures, CliError, COMMANDS};
+use crate::view::json::Json;
+
+/// JSON-RPC 2.0 error codes. -32000 and below are ours: the bank refused
+/// the request, the snapshot could not be saved afterwards, or a limit
+/// profile refused the amount.
+const PARSE_ERROR: i32 = -32700;
+const INVALID_REQUEST: i32 = -32600;
+const METHOD_NOT_FOUND: i32 = -32601;
+const INVALID_PARAMS: i32 = -32602;
+const REFUSED: i32 = -32000;
+const SAVE_FAILED: i32 = -32001;
+const LIMIT_EXCEEDED: i32 = -32002;
+
+/// Answer JSON-RPC 2.0 requests on stdin until it closes, one request (or
+/// batch) per line and one response per line on stdout. Methods are the CLI
+/// command names and `params` is an object of that command's options, e.g.
+/// `{"jsonrpc":"2.0","id":1,"method":"deposit","params":{"account":"Alice","amount":"100"}}`.
+/// Results are the `--json` objects. Requests that change the bank save it
+/// to `data` before they are answered, and the events they cause go to
+/// `notifiers`. Returns the process exit code.
+pub fn run(bank: Bank, data: PathBuf, notifiers: EventBus) -> i32 {
+    let mut session = Rpc { bank, data, notifiers };
+    let mut stdout = io::stdout();
+    for line in io::stdin().lock().lines() {
+        let Ok(line) = line else { break };
+        if line.trim().is_empty() {
+            continue;
+        }
+        if let Some(reply) = session.handle_line(&line) {
+            let _ = writeln!(stdout, "{}", reply);
+            let _ = stdout.flush();
+        }
+    }
+    0
+}
+
+/// The bank being driven, where it is saved, and where its events go.
+struct Rpc {
+    bank: Bank,
+    data: PathBuf,
+    notifiers: EventBus,
+}
+
+impl Rpc {
+    /// Reply to one input line; `None` when it held only notifications.
+    fn handle_line(&mut self, line: &str) -> Option<Json> {
+        match Json::parse(line) {
+            Err(e) => Some(error_reply(Json::Null, PARSE_ERROR, &e)),
+            Ok(Json::Array(batch)) if batch.is_empty() => Some(error_reply(Json::Null, INVALID_REQUEST, "empty batch")),
+            Ok(Json::Array(batch)) => {
+                let replies: Vec<Json> = batch.iter().filter_map(|request| self.handle(request)).collect();
+                (!replies.is_empty()).then_some(Json::Array(replies))
+            }
+            Ok(request) => self.handle(&request),
+        }
+    }
+
+    /// Reply to one request; `None` for a valid notification (no `id`).
+    fn handle(&mut self, request: &Json) -> Option<Json> {
+        let id = request.get("id").cloned();
+        let method = match (request.get("jsonrpc"), request.get("method")) {
+            (Some(Json::Str(version)), Some(Json::Str(method))) if version == "2.0" => method.clone(),
+            _ => {
+                let msg = "expected an object with \"jsonrpc\": \"2.0\" and a \"method\"";
+                return Some(error_reply(id.unwrap_or(Json::Null), INVALID_REQUEST, msg));
+            }
+        };
+        let outcome = self.call(&method, request.get("params"));
+        let id = id?;
+        Some(match outcome {
+            Ok(result) => Json::object([("jsonrpc", Json::str("2.0")), ("id", id), ("result", result)]),
+            Err((code, msg)) => error_reply(id, code, &msg),
+        })
+    }
+
+    fn call(&mut self, method: &str, params: Option<&Json>) -> Result<Json, (i32, String)> {
+        if !COMMANDS.contains(&method) {
+            return Err((METHOD_NOT_FOUND, format!("unknown method {}", method)));
+        }
+        let flags = options(params).map_err(|msg| (INVALID_PARAMS, msg))?;
+        let command = parse(&[method], flags).map_err(|e| (INVALID_PARAMS, e.to_string()))?;
+        let output = execute(&mut self.bank, &command).map_err(|e| match e {
+            CliError::Usage(msg) => (INVALID_PARAMS, msg),
+            CliError::Failed(e @ Error::Bank(BankError::LimitExceeded(_))) => (LIMIT_EXCEEDED, e.to_string()),
+            CliError::Failed(e) => (REFUSED, e.to_string()),
+        })?;
+        report_notify_failures(self.notifiers.drain(&mut self.bank));
+        if command.mutates() {
+            persist::save(&self.bank, &self.data).map_err(|e| (SAVE_FAILED, e.to_string()))?;
+        }
+        Ok(output.to_json(&self.bank))
+    }
+}
+
+/// Turn by-name `params` into command options. Strings, numbers, and
+/// booleans are taken as their text; absent or null params mean none.
+fn options(params: Option<&Json>) -> Result<BTreeMap<String, String>, String> {
+    let fields = match params {
+        None | Some(Json::Null) => return Ok(BTreeMap::new()),
+        Some(Json::Object(fields)) => fields,
+        Some(_) => return Err(String::from("params must be an object")),
+    };
+    fields
+        .iter()
+        .map(|(key, value)| match value {
+            Json::Str(s) | Json::Num(s) => Ok((key.clone(), s.clone())),
+            Json::Bool(b) => Ok((key.clone(), b.to_string())),
+            _ => Err(format!("param {} must be a string, number, or boolean", key)),
+        })
+        .collect()
+}
+
+fn error_reply(id: Json, code: i32, message: &str) -> Json {
+    Json::object([
+        ("jsonrpc", Json::str("2.0")),
+        ("id", id),
+        ("error", Json::object([("code", Json::num(code)), ("message", Json::str(message))])),
+    ])
+}
@@ -0,0 +1,980 @@
+use std::collections::BTreeMap;
+use std::io::{self, BufRead, BufReader, Write};
+use std::net::{TcpListener, TcpStream};
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex, MutexGuard};
+use std::thread;
+use std::time::Duration;
+
+use crate::api::account::AccountError;
+use crate::api::alert;
+use crate::api::bank::{Bank, BankError};
+use crate::api::cheque::ChequeError;
+use crate::api::error::Error;
+use crate::api::event::BankEvent;
+use crate::api::hold::HoldError;
+use crate::api::notify::EventBus;
+use crate::api::pending::{PendingError, TransactionState};
+use crate::api::persist;
+use crate::view::cli::{error_json, execute, parse, report_notify_failures, CliError, Command, Output};
+use crate::view::graphql::{self, Subscription};
+use crate::view::json::Json;
+use crate::view::metrics::Metrics;
+use crate::view::webhook::event_json;
+use crate::view::websocket;
+
+/// Largest request body accepted, in bytes.
+const MAX_BODY: usize = 64 * 1024;
+
+/// How long a connection may sit idle while its request is read.
+const READ_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// How long an event stream client may hold up a send before it is dropped.
+const WRITE_TIMEOUT: Duration = Duration::from_secs(5);
+
+const JSON: &str = "application/json";
+const PROMETHEUS_TEXT: &str = "text/plain; version=0.0.4";
+
+/// WebSocket subprotocol for GraphQL subscriptions.
+const GRAPHQL_WS: &str = "graphql-transport-ws";
+
+/// The bank shared by every connection, the snapshot it is saved to after
+/// each request that changes it, and the WebSocket subscribers and notifiers
+/// that its events are pushed to, plus the counters behind `/metrics`.
+struct Shared {
+    bank: Mutex<Bank>,
+    data: PathBuf,
+    metrics: Mutex<Metrics>,
+    subscribers: Mutex<Vec<Subscriber>>,
+    next_subscriber: AtomicUsize,
+    notifiers: Mutex<EventBus>,
+}
+
+/// One event subscription on a WebSocket: the connection's number, the
+/// GraphQL subscription id (`None` for `/events`), a stream to write to, and
+/// the message each event becomes (`None` to skip it). Every write to a
+/// subscriber's connection happens under the `subscribers` lock, so frames
+/// never interleave.
+struct Subscriber {
+    conn: usize,
+    id: Option<String>,
+    stream: TcpStream,
+    render: Render,
+}
+
+/// Turns an event into a subscriber's message, or `None` to skip it.
+type Render = Box<dyn Fn(&BankEvent) -> Option<String> + Send>;
+
+fn lock<T>(mutex: &Mutex<T>) -> MutexGuard<'_, T> {
+    mutex.lock().unwrap_or_else(|e| e.into_inner())
+}
+
+/// Serve `bank` over HTTP on `addr` (e.g. "127.0.0.1:8080") until the
+/// process is stopped. Each connection gets its own thread; requests take
+/// turns on the bank behind a mutex, so every request sees a consistent
+/// state. Routes map onto the CLI commands (see `route`) and answer with the
+/// same JSON as `--json`. `GET /events` upgrades to a WebSocket that
+/// receives every `BankEvent` as a JSON text message (see `event_json`);
+/// the same events go to `notifiers`. `POST /graphql` answers GraphQL
+/// queries and mutations, and `GET /graphql` upgrades to a WebSocket for
+/// GraphQL subscriptions (see `graphql`). `GET /metrics` reports counters
+/// and gauges in the Prometheus text format.
+pub fn serve(addr: &str, bank: Bank, data: PathBuf, notifiers: EventBus) -> io::Result<()> {
+    let listener = TcpListener::bind(addr)?;
+    eprintln!("Listening on http://{}", listener.local_addr()?);
+    let shared = Arc::new(Shared {
+        bank: Mutex::new(bank),
+        data,
+        metrics: Mutex::new(Metrics::default()),
+        subscribers: Mutex::new(Vec::new()),
+        next_subscriber: AtomicUsize::new(1),
+        notifiers: Mutex::new(notifiers),
+    });
+    for stream in listener.incoming() {
+        let Ok(stream) = stream else { continue };
+        let shared = Arc::clone(&shared);
+        thread::spawn(move || {
+            if let Err(e) = handle(stream, &shared) {
+                eprintln!("connection error: {}", e);
+            }
+        });
+    }
+    Ok(())
+}
+
+/// One parsed HTTP request: method, path, headers (names lowercased), the
+/// query-string and form-body parameters merged into one map, and the raw
+/// body. A JSON body is not read as a form.
+struct Request {
+    method: String,
+    path: String,
+    headers: Vec<(String, String)>,
+    params: BTreeMap<String, String>,
+    body: String,
+}
+
+impl Request {
+    fn header(&self, name: &str) -> Option<&str> {
+        self.headers.iter().find(|(n, _)| n == name).map(|(_, v)| v.as_str())
+    }
+}
+
+fn handle(stream: TcpStream, shared: &Shared) -> io::Result<()> {
+    stream.set_read_timeout(Some(READ_TIMEOUT))?;
+    let mut reader = BufReader::new(stream.try_clone()?);
+    let (status, body) = match read_request(&mut reader) {
+        Ok(req) if req.method == "GET" && (req.path == "/events" || req.path == "/graphql") => {
+            return match req.header("sec-websocket-key") {
+                Some(key) if req.header("upgrade").is_some_and(|u| u.eq_ignore_ascii_case("websocket")) => {
+                    if req.path == "/events" {
+                        stream_events(stream, reader, key, shared)
+                    } else {
+                        graphql_subscriptions(stream, reader, key, shared)
+                    }
+                }
+                _ => {
+                    lock(&shared.metrics).record_request(400);
+                    let msg = format!("GET {} needs a WebSocket upgrade", req.path);
+                    write_response(stream, 400, JSON, &Json::object([("error", Json::str(msg)), ("kind", Json::str("usage"))]).to_string())
+                }
+            };
+        }
+        Ok(req) if req.method == "GET" && req.path == "/metrics" => {
+            // Bank before metrics, the order `respond` takes them in.
+            let subscribers = lock(&shared.subscribers).len();
+            let bank = lock(&shared.bank);
+            let mut metrics = lock(&shared.metrics);
+            metrics.record_request(200);
+            let text = metrics.render(&bank, subscribers);
+            drop((metrics, bank));
+            return write_response(stream, 200, PROMETHEUS_TEXT, &text);
+        }
+        Ok(req) => {
+            let (status, body) = respond(&req, shared);
+            eprintln!("{} {} -> {}", req.method, req.path, status);
+            (status, body)
+        }
+        Err(e) => (400, Json::object([("error", Json::str(e)), ("kind", Json::str("usage"))])),
+    };
+    lock(&shared.metrics).record_request(status);
+    write_response(stream, status, JSON, &body.to_string())
+}
+
+fn read_request(reader: &mut impl BufRead) -> io::Result<Request> {
+    let mut line = String::new();
+    reader.read_line(&mut line)?;
+    let mut parts = line.split_whitespace();
+    let (Some(method), Some(target)) = (parts.next(), parts.next()) else {
+        return Err(bad_request("malformed request line"));
+    };
+    let method = method.to_string();
+    let (path, query) = target.split_once('?').unwrap_or((target, ""));
+    let path = path.to_string();
+    let mut params = parse_form(query)?;
+
+    let mut headers = Vec::new();
+    loop {
+        line.clear();
+        if reader.read_line(&mut line)? == 0 || line.trim_end().is_empty() {
+            break;
+        }
+        if let Some((name, value)) = line.split_once(':') {
+            headers.push((name.trim().to_ascii_lowercase(), value.trim().to_string()));
+        }
+    }
+    let length = match headers.iter().find(|(n, _)| n == "content-length") {
+        Some((_, value)) => value.parse().map_err(|_| bad_request("invalid Content-Length"))?,
+        None => 0,
+    };
+    if length > MAX_BODY {
+        return Err(bad_request("request body too large"));
+    }
+    let mut body = vec![0; length];
+    reader.read_exact(&mut body)?;
+    let body = String::from_utf8(body).map_err(|_| bad_request("request body is not UTF-8"))?;
+    let json = headers.iter().any(|(n, v)| n == "content-type" && v.starts_with(JSON));
+    if !json {
+        params.extend(parse_form(&body)?);
+    }
+    Ok(Request { method, path, headers, params, body })
+}
+
+/// Run the request against the bank and build the status and JSON reply:
+/// 202 for a withdrawal or transfer held for an admin's approval.
+fn respond(req: &Request, shared: &Shared) -> (u16, Json) {
+    if req.method == "POST" && req.path == "/graphql" {
+        return graphql_request(req, shared);
+    }
+    // A segment with a malformed escape matches no route.
+    let segments: Vec<String> = req
+        .path
+        .split('/')
+        .filter(|s| !s.is_empty())
+        .map(percent_decode)
+        .collect::<Option<_>>()
+        .unwrap_or_default();
+    let segments: Vec<&str> = segments.iter().map(String::as_str).collect();
+    let mut params = req.params.clone();
+    if let Some(key) = req.header("idempotency-key") {
+        params.entry(String::from("idempotency-key")).or_insert_with(|| key.to_string());
+    }
+    if let Some(version) = req.header("if-match") {
+        params.entry(String::from("if-version")).or_insert_with(|| version.trim().trim_matches('"').to_string());
+    }
+    let command = match route(&req.method, &segments, params) {
+        Some(Ok(command)) => command,
+        Some(Err(e)) => return (400, error_json(&e)),
+        None => {
+            let msg = format!("no route for {} {}", req.method, req.path);
+            return (404, Json::object([("error", Json::str(msg)), ("kind", Json::str("usage"))]));
+        }
+    };
+
+    let mut bank = lock(&shared.bank);
+    let output = match run(&mut bank, shared, &command) {
+        Ok(output) => output,
+        Err(e) => return (status_of(&e), error_json(&e)),
+    };
+    if command.mutates()
+        && let Err(e) = persist::save(&bank, &shared.data)
+    {
+        return (500, error_json(&e.into()));
+    }
+    let status = match &output {
+        Output::Approval(request) if request.state == TransactionState::Pending => 202,
+        _ if req.method == "POST" => 201,
+        _ => 200,
+    };
+    let reply = output.to_json(&bank);
+    publish(bank, shared);
+    (status, reply)
+}
+
+/// `POST /graphql` with a JSON body `{"query": ..., "variables": {...}}`.
+/// Answered 200 with `data` and any field `errors`, or 400 when the request
+/// could not be run at all.
+fn graphql_request(req: &Request, shared: &Shared) -> (u16, Json) {
+    let request = match Json::parse(&req.body) {
+        Ok(request) => request,
+        Err(e) => return (400, graphql::errors_only(format!("invalid JSON body: {}", e))),
+    };
+    let Some(Json::Str(query)) = request.get("query") else {
+        return (400, graphql::errors_only(String::from("the body needs a \"query\" string")));
+    };
+    let mut bank = lock(&shared.bank);
+    let mut mutated = false;
+    let reply = graphql::execute(query, request.get("variables"), |command| {
+        let output = run(&mut bank, shared, command)?;
+        mutated |= command.mutates();
+        Ok(output.to_json(&bank))
+    });
+    if mutated
+        && let Err(e) = persist::save(&bank, &shared.data)
+    {
+        return (500, graphql::errors_only(e.to_string()));
+    }
+    let status = if reply.get("data").is_some() { 200 } else { 400 };
+    publish(bank, shared);
+    (status, reply)
+}
+
+/// Execute `command`, counting quotes and refused rate updates.
+fn run(bank: &mut Bank, shared: &Shared, command: &Command) -> Result<Output, CliError> {
+    let output = execute(bank, command);
+    match command.unwrapped() {
+        Command::Rate { .. } if output.is_err() => lock(&shared.metrics).record_rate_failure(),
+        Command::Convert { from, to, .. } if output.is_ok() => lock(&shared.metrics).record_quote(from, to),
+        _ => {}
+    }
+    output
+}
+
+/// Hand the bank's pending events to the metrics, notifiers, and WebSocket
+/// subscribers, and its month-end statements to the statement channels,
+/// then let go of the bank. Subscribers that cannot keep up are dropped.
+fn publish(mut bank: MutexGuard<'_, Bank>, shared: &Shared) {
+    // Take the subscriber list before letting go of the bank so events
+    // reach clients in the order they happened.
+    let mut events = bank.take_events();
+    events.extend(alert::evaluate(&mut bank, &events));
+    let statements = bank.take_statements();
+    lock(&shared.metrics).record_events(&events);
+    let mut notifiers = lock(&shared.notifiers);
+    report_notify_failures(notifiers.publish(&events));
+    report_notify_failures(notifiers.deliver(&statements));
+    drop(notifiers);
+    let mut subscribers = lock(&shared.subscribers);
+    drop(bank);
+    subscribers.retain_mut(|s| {
+        events
+            .iter()
+            .filter_map(|e| (s.render)(e))
+            .all(|text| websocket::write_frame(&mut s.stream, websocket::TEXT, text.as_bytes()).is_ok())
+    });
+}
+
+/// Complete a WebSocket handshake, accepting `protocol` if one is given,
+/// and return the connection's number.
+fn upgrade(stream: &mut TcpStream, reader: &BufReader<TcpStream>, key: &str, protocol: Option<&str>, shared: &Shared) -> io::Result<usize> {
+    let protocol = protocol.map(|p| format!("Sec-WebSocket-Protocol: {}\r\n", p)).unwrap_or_default();
+    write!(
+        stream,
+        "HTTP/1.1 101 Switching Protocols\r\nUpgrade: websocket\r\nConnection: Upgrade\r\nSec-WebSocket-Accept: {}\r\n{}\r\n",
+        websocket::accept_key(key),
+        protocol
+    )?;
+    stream.set_write_timeout(Some(WRITE_TIMEOUT))?;
+    reader.get_ref().set_read_timeout(None)?;
+    Ok(shared.next_subscriber.fetch_add(1, Ordering::Relaxed))
+}
+
+/// Send one frame on a subscriber's connection from its own thread.
+fn send(shared: &Shared, stream: &mut TcpStream, opcode: u8, payload: &[u8]) {
+    let _subscribers = lock(&shared.subscribers);
+    let _ = websocket::write_frame(stream, opcode, payload);
+}
+
+/// Drop every subscription on connection `conn` and close it.
+fn hang_up(shared: &Shared, stream: &mut TcpStream, conn: usize) {
+    let mut subscribers = lock(&shared.subscribers);
+    subscribers.retain(|s| s.conn != conn);
+    let _ = websocket::write_frame(stream, websocket::CLOSE, &[]);
+}
+
+/// Complete the WebSocket handshake for `GET /events`, then keep the client
+/// subscribed until it closes the connection. Pings are answered; any other
+/// message from the client is ignored.
+fn stream_events(mut stream: TcpStream, mut reader: BufReader<TcpStream>, key: &str, shared: &Shared) -> io::Result<()> {
+    let conn = upgrade(&mut stream, &reader, key, None, shared)?;
+    eprintln!("GET /events -> 101");
+    lock(&shared.subscribers).push(Subscriber {
+        conn,
+        id: None,
+        stream: stream.try_clone()?,
+        render: Box::new(|e| Some(event_json(e).to_string())),
+    });
+
+    loop {
+        match websocket::read_frame(&mut reader) {
+            Ok((websocket::CLOSE, _)) | Err(_) => break,
+            Ok((websocket::PING, payload)) => send(shared, &mut stream, websocket::PONG, &payload),
+            Ok(_) => {}
+        }
+    }
+    hang_up(shared, &mut stream, conn);
+    Ok(())
+}
+
+/// Complete the WebSocket handshake for `GET /graphql` and speak the
+/// `graphql-transport-ws` protocol: `connection_init` is acknowledged,
+/// `subscribe` starts a `Subscription` whose events arrive as `next`
+/// messages (or answers `error`), `complete` stops one, and `ping` gets a
+/// `pong`. Anything else closes the connection.
+fn graphql_subscriptions(mut stream: TcpStream, mut reader: BufReader<TcpStream>, key: &str, shared: &Shared) -> io::Result<()> {
+    let conn = upgrade(&mut stream, &reader, key, Some(GRAPHQL_WS), shared)?;
+    eprintln!("GET /graphql -> 101");
+    let reply = |stream: &mut TcpStream, message: Json| send(shared, stream, websocket::TEXT, message.to_string().as_bytes());
+
+    loop {
+        let message = match websocket::read_frame(&mut reader) {
+            Ok((websocket::TEXT, payload)) => String::from_utf8(payload).ok().and_then(|text| Json::parse(&text).ok()),
+            Ok((websocket::PING, payload)) => {
+                send(shared, &mut stream, websocket::PONG, &payload);
+                continue;
+            }
+            Ok((websocket::CLOSE, _)) | Err(_) => break,
+            Ok(_) => continue,
+        };
+        let message = message.unwrap_or(Json::Null);
+        let id = match message.get("id") {
+            Some(Json::Str(id)) => Some(id.clone()),
+            _ => None,
+        };
+        match (message.get("type"), id) {
+            (Some(Json::Str(t)), _) if t == "connection_init" => reply(&mut stream, Json::object([("type", Json::str("connection_ack"))])),
+            (Some(Json::Str(t)), _) if t == "ping" => reply(&mut stream, Json::object([("type", Json::str("pong"))])),
+            (Some(Json::Str(t)), _) if t == "pong" => {}
+            (Some(Json::Str(t)), Some(id)) if t == "subscribe" => {
+                let payload = message.get("payload");
+                let query = match payload.and_then(|p| p.get("query")) {
+                    Some(Json::Str(query)) => query.as_str(),
+                    _ => "",
+                };
+                match Subscription::parse(query, payload.and_then(|p| p.get("variables"))) {
+                    Ok(subscription) => {
+                        let tag = id.clone();
+                        lock(&shared.subscribers).push(Subscriber {
+                            conn,
+                            id: Some(id),
+                            stream: stream.try_clone()?,
+                            render: Box::new(move |e| {
+                                let data = subscription.data(e)?;
+                                let payload = Json::object([("data", data)]);
+                                Some(Json::object([("id", Json::str(&tag)), ("type", Json::str("next")), ("payload", payload)]).to_string())
+                            }),
+                        });
+                    }
+                    Err(msg) => {
+                        let errors = Json::Array(vec![Json::object([("message", Json::str(msg))])]);
+                        reply(&mut stream, Json::object([("id", Json::str(id)), ("type", Json::str("error")), ("payload", errors)]));
+                    }
+                }
+            }
+            (Some(Json::Str(t)), Some(id)) if t == "complete" => {
+                lock(&shared.subscribers).retain(|s| s.conn != conn || s.id.as_deref() != Some(id.as_str()));
+            }
+            _ => break,
+        }
+    }
+    hang_up(shared, &mut stream, conn);
+    Ok(())
+}
+
+/// Map a method and path onto a CLI command; `None` if nothing matches. An
+/// `Idempotency-Key` header arrives here as the idempotency-key param, which
+/// postings and transfers accept, and `If-Match` as if-version, which any
+/// route changing an account accepts. A route for one of `ADMIN_COMMANDS`
+/// also takes passphrase, the admin passphrase.
+/// - `GET /accounts`, `POST /accounts` (account, currency, pin)
+/// - `GET /accounts/{name}`: balance; `{name}` may be an alias here and
+///   below
+/// - `GET /aliases`, `POST /accounts/{name}/aliases` (alias),
+///   `DELETE /aliases/{alias}`
+/// - `PUT /accounts/{name}/auto-convert` (currencies)
+/// - `GET /accounts/{name}/alerts`, `PUT /accounts/{name}/alerts` (below,
+///   withdrawal-above, inactive-days)
+/// - `GET /accounts/{name}/service-charges`, `PUT` the same path (monthly,
+///   maintaining)
+/// - `PUT /accounts/{name}/type` (type, passphrase): the account type, which
+///   picks its limit profile
+/// - `GET /accounts/{name}/holds`, `POST` the same path (amount, reason,
+///   expires, pin): authorization holds; `DELETE
+///   /accounts/{name}/holds/{id}` (pin) releases one, `POST
+///   /accounts/{name}/holds/{id}/capture` (amount, pin) withdraws it
+/// - `GET /accounts/{name}/cheques` (status), `POST` the same path (amount,
+///   number, pin): cheque deposits; `POST
+///   /accounts/{name}/cheques/{id}/return` (reason) reverses one that
+///   bounced
+/// - `GET /archived`, `POST /accounts/{name}/archive`, `DELETE` the same
+///   path to restore
+/// - `GET /accounts/{name}/transactions`, `POST` the same path with
+///   type=deposit|withdraw, amount, memo, category (withdrawals), tags, pin
+/// - `PUT /accounts/{name}/transactions/{seq}/tags` (tags), `GET
+///   /accounts/{name}/tags?start=&end=`: totals per tag
+/// - `GET /accounts/{name}/forecast?days=N&rate=R&inflation=R&step=S&currency=C&since=D`
+/// - `GET /accounts/{name}/interest-rates`
+/// - `POST /accounts/{name}/interest-rates` (params: rate, date)
+/// - `POST /accounts/{name}/promotion` (params: bonus, days, start)
+/// - `DELETE /accounts/{name}/promotion`
+/// - `PUT /accounts/{name}/interest-payout` (params: to)
+/// - `PUT /accounts/{name}/year-basis`, `PUT /year-basis` (params: basis)
+/// - `PUT /accounts/{name}/interest-method`, `PUT /interest-method` (params: method)
+/// - `PUT /accounts/{name}/interest-grace`, `PUT /interest-grace` (params: days)
+/// - `GET /accounts/{name}/scenarios?days=N&scenarios=NAME:RATE,...`
+/// - `GET /accounts/{name}/compare?with=NAME&days=N`: two accounts' forecasts
+///   side by side, with their crossover day
+/// - `GET /accounts/{name}/monte-carlo?days=N` (paths, seed,
+///   rate-volatility, drift, volatility, deposit, every, chance): the
+///   spread of ending balances over randomized paths
+/// - `GET /accounts/{name}/statement?format=csv|ofx|qif|html&start=&end=`
+/// - `GET /accounts/{name}/pnl`, `GET /pnl`: FX profit and loss
+/// - `GET /accounts/{name}/portfolio?date=YYYY-MM-DD`, `GET /portfolio`
+///   for every account
+/// - `POST /interest` (days or since): post interest to every account
+/// - `POST /accounts/{name}/envelopes` (category, limit)
+/// - `GET /accounts/{name}/budget?date=YYYY-MM-DD`
+/// - `GET /summary?month=YYYY-MM&format=text|csv`: every account's month
+/// - `GET /accounts/{name}/tax-certificate?year=YYYY&format=text|csv|html`:
+///   interest earned and tax withheld over a year
+/// - `POST /transfers` (from, to, amount, currency, pin, override-limits)
+/// - `POST /exchanges` (from, to, amount, pin, override-limits)
+/// - `GET /approvals` (status): withdrawals and transfers held for approval;
+///   `POST /approvals/{id}/approve` (passphrase), `POST
+///   /approvals/{id}/reject` (reason, passphrase)
+/// - `GET /rates` (window), `PUT /rates/{code}` (rate), `PUT /rates/{code}/cash`
+///   (rate; absent quotes cash at the transfer rate)
+/// - `GET /rates/{code}/history` (days, sma, ema): closing rates with their
+///   moving averages
+/// - `GET /rates/summary` (code, start, end): open, high, low, average, and
+///   close per currency
+/// - `GET /baskets`, `POST /baskets` (code, name, weights)
+/// - `GET /fees`, `POST /fees` (tiers), `POST /fees/spreads` (pair, rate)
+/// - `GET /conversion-limits`, `PUT /conversion-limits/{code}`
+///   (per-transaction, per-day, passphrase; both limits absent removes the
+///   cap)
+/// - `GET /limit-profiles` (account), `PUT /limit-profiles/{target}`
+///   (max-deposit, max-withdrawal, max-daily-conversion, passphrase; all
+///   caps absent removes the profile)
+/// - `GET /conversions?account=&currency=&start=&end=`, `GET /turnover` (same)
+/// - `GET /receipts/{number}`: an exchange receipt
+/// - `GET /convert?from=&to=&amount=&rates=`
+/// - `GET /dca?from=&to=&amount=&rates=`: dollar-cost-averaging simulation
+///   (not `history`, which would read a file on the server)
+/// - `POST /loans` (account, amount, rate, term, frequency, pin)
+/// - `GET /loans/{id}`: amortization schedule
+/// - `POST /loans/{id}/payments` (pin)
+/// - `GET /loans/{id}/paydown` (amount, deposit-rate, days): paying down
+///   vs depositing; `GET /paydown` (principal, rate, term, frequency,
+///   amount, deposit-rate, days) compares a quote
+/// - `GET /orders`, `POST /orders` (from, to, amount, every, currency,
+///   start, pin)
+/// - `POST /orders/{id}/skip`, `DELETE /orders/{id}`
+/// - `GET /sweeps`, `POST /sweeps` (from, to, above, pin),
+///   `DELETE /sweeps/{id}`
+/// - `GET /forwards`, `POST /forwards` (account, side, amount, currency,
+///   rate, date, pin)
+/// - `GET /limits`, `POST /limits` (account, amount, from, to, rate,
+///   expires, pin)
+/// - `PUT /limits/{id}` (amount, rate, expires), `DELETE /limits/{id}`
+/// - `POST /eod` (date)
+/// - `GET /verify`: ledger invariant check
+/// - `GET /rounding`: rounding residue and its base-currency effect
+fn route(method: &str, segments: &[&str], mut params: BTreeMap<String, String>) -> Option<Result<Command, CliError>> {
+    let mut with = |key: &str, value: &str| {
+        params.insert(key.to_string(), value.to_string());
+    };
+    let verb = match (method, segments) {
+        ("GET", ["accounts"]) => "accounts",
+        ("POST", ["accounts"]) => "register",
+        ("GET", ["aliases"]) => "aliases",
+        ("POST", ["accounts", name, "aliases"]) => {
+            with("account", name);
+            "alias"
+        }
+        ("GET", ["accounts", name, "alerts"]) => {
+            with("account", name);
+            "alerts"
+        }
+        ("PUT", ["accounts", name, "alerts"]) => {
+            with("account", name);
+            "alert"
+        }
+        ("GET", ["accounts", name, "service-charges"]) => {
+            with("account", name);
+            "service-charges"
+        }
+        ("PUT", ["accounts", name, "service-charges"]) => {
+            with("account", name);
+            "service-charge"
+        }
+        ("PUT", ["accounts", name, "type"]) => {
+            with("account", name);
+            "account-type"
+        }
+        ("GET", ["accounts", name, "holds"]) => {
+            with("account", name);
+            "holds"
+        }
+        ("POST", ["accounts", name, "holds"]) => {
+            with("account", name);
+            "hold"
+        }
+        ("DELETE", ["accounts", name, "holds", id]) => {
+            with("account", name);
+            with("id", id);
+            "release"
+        }
+        ("POST", ["accounts", name, "holds", id, "capture"]) => {
+            with("account", name);
+            with("id", id);
+            "capture"
+        }
+        ("GET", ["accounts", name, "cheques"]) => {
+            with("account", name);
+            "cheques"
+        }
+        ("POST", ["accounts", name, "cheques"]) => {
+            with("account", name);
+            "deposit-cheque"
+        }
+        ("POST", ["accounts", name, "cheques", id, "return"]) => {
+            with("account", name);
+            with("id", id);
+            "return-cheque"
+        }
+        ("PUT", ["accounts", name, "auto-convert"]) => {
+            with("account", name);
+            "auto-convert"
+        }
+        ("DELETE", ["aliases", alias]) => {
+            with("alias", alias);
+            "unalias"
+        }
+        ("GET", ["archived"]) => "archived",
+        ("POST", ["accounts", name, "archive"]) => {
+            with("account", name);
+            "archive"
+        }
+        ("DELETE", ["accounts", name, "archive"]) => {
+            with("account", name);
+            "unarchive"
+        }
+        ("GET", ["accounts", name]) => {
+            with("account", name);
+            "balance"
+        }
+        ("GET", ["accounts", name, "transactions"]) => {
+            with("account", name);
+            "history"
+        }
+        ("POST", ["accounts", name, "transactions"]) => {
+            with("account", name);
+            match params.remove("type").as_deref() {
+                Some("deposit") => "deposit",
+                Some("withdraw") => "withdraw",
+                _ => return Some(Err(CliError::Usage(String::from("type must be deposit or withdraw")))),
+            }
+        }
+        ("PUT", ["accounts", name, "transactions", seq, "tags"]) => {
+            with("account", name);
+            with("seq", seq);
+            "tag"
+        }
+        ("GET", ["accounts", name, "tags"]) => {
+            with("account", name);
+            "tags"
+        }
+        ("GET", ["accounts", name, "forecast"]) => {
+            with("account", name);
+            "forecast"
+        }
+        ("GET", ["accounts", name, "interest-rates"]) => {
+            with("account", name);
+            "interest-rates"
+        }
+        ("POST", ["accounts", name, "interest-rates"]) => {
+            with("account", name);
+            "interest-rate"
+        }
+        ("POST", ["accounts", name, "promotion"]) => {
+            with("account", name);
+            "promotion"
+        }
+        ("DELETE", ["accounts", name, "promotion"]) => {
+            with("account", name);
+            "end-promotion"
+        }
+        ("PUT", ["accounts", name, "interest-payout"]) => {
+            with("account", name);
+            "interest-payout"
+        }
+        ("PUT", ["accounts", name, "year-basis"]) => {
+            with("account", name);
+            "year-basis"
+        }
+        ("PUT", ["year-basis"]) => "year-basis",
+        ("PUT", ["accounts", name, "interest-method"]) => {
+            with("account", name);
+            "interest-method"
+        }
+        ("PUT", ["interest-method"]) => "interest-method",
+        ("PUT", ["accounts", name, "interest-grace"]) => {
+            with("account", name);
+            "interest-grace"
+        }
+        ("PUT", ["interest-grace"]) => "interest-grace",
+        ("GET", ["accounts", name, "scenarios"]) => {
+            with("account", name);
+            "scenarios"
+        }
+        ("GET", ["accounts", name, "compare"]) => {
+            with("account", name);
+            "compare"
+        }
+        ("GET", ["accounts", name, "monte-carlo"]) => {
+            with("account", name);
+            "monte-carlo"
+        }
+        ("GET", ["accounts", name, "statement"]) => {
+            with("account", name);
+            "statement"
+        }
+        ("GET", ["accounts", name, "pnl"]) => {
+            with("account", name);
+            "pnl"
+        }
+        ("GET", ["pnl"]) => "pnl",
+        ("GET", ["portfolio"]) => "portfolio",
+        ("POST", ["interest"]) => "interest",
+        ("GET", ["accounts", name, "portfolio"]) => {
+            with("account", name);
+            "portfolio"
+        }
+        ("POST", ["accounts", name, "envelopes"]) => {
+            with("account", name);
+            "envelope"
+        }
+        ("GET", ["accounts", name, "budget"]) => {
+            with("account", name);
+            "budget"
+        }
+        ("GET", ["summary"]) => "summary",
+        ("GET", ["accounts", name, "tax-certificate"]) => {
+            with("account", name);
+            "tax-certificate"
+        }
+        ("POST", ["transfers"]) => "transfer",
+        ("POST", ["exchanges"]) => "exchange",
+        ("GET", ["rates"]) => "rates",
+        ("GET", ["rates", "summary"]) => "rate-summary",
+        ("GET", ["dca"]) => {
+            if params.contains_key("history") {
+                return Some(Err(CliError::Usage(String::from("history files cannot be read over HTTP; pass rates"))));
+            }
+            "dca"
+        }
+        ("GET", ["baskets"]) => "baskets",
+        ("POST", ["baskets"]) => "basket",
+        ("GET", ["fees"]) => "fees",
+        ("POST", ["fees"]) => "fee-schedule",
+        ("POST", ["fees", "spreads"]) => "spread",
+        ("GET", ["conversion-limits"]) => "conversion-limits",
+        ("PUT", ["conversion-limits", code]) => {
+            with("code", code);
+            "conversion-limit"
+        }
+        ("GET", ["limit-profiles"]) => "limit-profiles",
+        ("PUT", ["limit-profiles", target]) => {
+            with("target", target);
+            "limit-profile"
+        }
+        ("GET", ["conversions"]) => "conversions",
+        ("GET", ["turnover"]) => "turnover",
+        ("GET", ["receipts", number]) => {
+            with("number", number);
+            "receipt"
+        }
+        ("PUT", ["rates", code]) => {
+            with("code", code);
+            "rate"
+        }
+        ("PUT", ["rates", code, "cash"]) => {
+            with("code", code);
+            "cash-rate"
+        }
+        ("GET", ["rates", code, "history"]) => {
+            with("code", code);
+            "rate-history"
+        }
+        ("GET", ["convert"]) => "convert",
+        ("POST", ["loans"]) => "loan",
+        ("GET", ["loans", id]) => {
+            with("loan", id);
+            "schedule"
+        }
+        ("POST", ["loans", id, "payments"]) => {
+            with("loan", id);
+            "repay"
+        }
+        ("GET", ["loans", id, "paydown"]) => {
+            with("loan", id);
+            "paydown"
+        }
+        ("GET", ["paydown"]) => "paydown",
+        ("GET", ["orders"]) => "orders",
+        ("POST", ["orders"]) => "order",
+        ("POST", ["orders", id, "skip"]) => {
+            with("order", id);
+            "skip"
+        }
+        ("DELETE", ["orders", id]) => {
+            with("order", id);
+            "cancel"
+        }
+        ("GET", ["sweeps"]) => "sweeps",
+        ("POST", ["sweeps"]) => "sweep",
+        ("DELETE", ["sweeps", id]) => {
+            with("sweep", id);
+            "cancel"
+        }
+        ("GET", ["forwards"]) => "forwards",
+        ("POST", ["forwards"]) => "forward",
+        ("GET", ["limits"]) => "limits",
+        ("POST", ["limits"]) => "limit",
+        ("PUT", ["limits", id]) => {
+            with("limit", id);
+            "amend"
+        }
+        ("DELETE", ["limits", id]) => {
+            with("limit", id);
+            "cancel"
+        }
+        ("GET", ["approvals"]) => "approvals",
+        ("POST", ["approvals", id, "approve"]) => {
+            with("id", id);
+            "approve"
+        }
+        ("POST", ["approvals", id, "reject"]) => {
+            with("id", id);
+            "reject"
+        }
+        ("POST", ["eod"]) => "eod",
+        ("GET", ["verify"]) => "verify",
+        ("GET", ["rounding"]) => "rounding",
+        _ => return None,
+    };
+    Some(parse(&[verb], params))
+}
+
+/// 404 for a missing account, alias, loan, standing order, sweep, limit order, hold, cheque, approval request, or exchange receipt, 403 for a read-only bank or a rejected admin passphrase, 409 for an idempotency key reused on a different request, an account that changed since the client's `If-Match` version, an approval request already decided, or a cheque already cleared or returned, 422 for anything else the bank refused.
+fn status_of(err: &CliError) -> u16 {
+    match err {
+        CliError::Usage(_) => 400,
+        CliError::Failed(Error::Bank(BankError::AccountNotFound(_) | BankError::AliasNotFound(_) | BankError::LoanNotFound(_) | BankError::StandingOrderNotFound(_) | BankError::SweepNotFound(_) | BankError::LimitOrderNotFound(_) | BankError::SequenceNotFound(..) | BankError::PendingNotFound(_) | BankError::ReceiptNotFound(_) | BankError::Account(AccountError::Hold(HoldError::NotFound(_)) | AccountError::Cheque(ChequeError::NotFound(_))))) => 404,
+        CliError::Failed(Error::Bank(BankError::ReadOnly | BankError::InvalidPassphrase)) => 403,
+        CliError::Failed(Error::Bank(BankError::AccountExists(_) | BankError::IdempotencyKeyReused(_) | BankError::VersionConflict { .. } | BankError::Pending(PendingError::Decided(_)) | BankError::Account(AccountError::Cheque(ChequeError::Cleared(_) | ChequeError::Returned(_))))) => 409,
+        CliError::Failed(_) => 422,
+    }
+}
+
+fn write_response(mut stream: TcpStream, status: u16, content_type: &str, body: &str) -> io::Result<()> {
+    let reason = match status {
+        200 => "OK",
+        201 => "Created",
+        202 => "Accepted",
+        400 => "Bad Request",
+        403 => "Forbidden",
+        404 => "Not Found",
+        409 => "Conflict",
+        422 => "Unprocessable Entity",
+        _ => "Internal Server Error",
+    };
+    write!(
+        stream,
+        "HTTP/1.1 {} {}\r\nContent-Type: {}\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+        status,
+        reason,
+        content_type,
+        body.len(),
+        body
+    )?;
+    stream.flush()
+}
+
+/// Parse `key=value&key=value` (a query string or form body).
+fn parse_form(text: &str) -> io::Result<BTreeMap<String, String>> {
+    let mut params = BTreeMap::new();
+    for pair in text.split('&').filter(|p| !p.is_empty()) {
+        let (key, value) = pair.split_once('=').unwrap_or((pair, ""));
+        let decode = |s: &str| percent_decode(&s.replace('+', " ")).ok_or_else(|| bad_request("invalid percent-encoding"));
+        params.insert(decode(key)?, decode(value)?);
+    }
+    Ok(params)
+}
+
+/// Decode `%XX` escapes; `None` if an escape is malformed or the result is
+/// not UTF-8.
+fn percent_decode(s: &str) -> Option<String> {
+    let bytes = s.as_bytes();
+    let mut out = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        if bytes[i] == b'%' {
+            let hex = std::str::from_utf8(bytes.get(i + 1..i + 3)?).ok()?;
+            out.push(u8::from_str_radix(hex, 16).ok()?);
+            i += 3;
+        } else {
+            out.push(bytes[i]);
+            i += 1;
+        }
+    }
+    String::from_utf8(out).ok()
+}
+
+fn bad_request(msg: &str) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, msg.to_string())
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::api::decimal::Decimal;
+    use crate::api::forex::Forex;
+
+    /// A bank with one PHP account whose admin passphrase is "secret", saved
+    /// to a fresh file under the temp directory.
+    fn shared(name: &str) -> Shared {
+        let forex = Forex::builder().set_base_currency("PHP", "Philippine Peso").create_currency("USD", "US Dollar", Decimal::new(56, 0)).build();
+        let mut bank = Bank::builder().set_forex(forex).set_admin_passphrase("secret").build();
+        bank.create_account("Alice").expect("account");
+        Shared {
+            bank: Mutex::new(bank),
+            data: std::env::temp_dir().join(format!("rust_forex-server-{}-{}.json", name, std::process::id())),
+            metrics: Mutex::new(Metrics::default()),
+            subscribers: Mutex::new(Vec::new()),
+            next_subscriber: AtomicUsize::new(1),
+            notifiers: Mutex::new(EventBus::new()),
+        }
+    }
+
+    fn request(method: &str, path: &str, params: &[(&str, &str)]) -> Request {
+        Request {
+            method: method.to_string(),
+            path: path.to_string(),
+            headers: Vec::new(),
+            params: params.iter().map(|(k, v)| (k.to_string(), v.to_string())).collect(),
+            body: String::new(),
+        }
+    }
+
+    fn read(raw: impl AsRef<[u8]>) -> io::Result<Request> {
+        read_request(&mut BufReader::new(raw.as_ref()))
+    }
+
+    #[test]
+    fn admin_routes_are_refused_without_the_passphrase() {
+        let shared = shared("admin");
+        let routes = [
+            ("PUT", "/rates/USD", vec![("rate", "57")]),
+            ("POST", "/fees", vec![("tiers", "0:0.01")]),
+            ("POST", "/fees/spreads", vec![("pair", "PHP/USD"), ("rate", "0.01")]),
+            ("POST", "/accounts/Alice/archive", vec![]),
+            ("POST", "/interest", vec![("days", "30")]),
+            ("POST", "/eod", vec![]),
+        ];
+        for (method, path, params) in routes {
+            let (status, reply) = respond(&request(method, path, &params), &shared);
+            assert_eq!(status, 403, "{} {}: {}", method, path, reply);
+            let mut wrong = params;
+            wrong.push(("passphrase", "guess"));
+            let (status, _) = respond(&request(method, path, &wrong), &shared);
+            assert_eq!(status, 403, "{} {} with the wrong passphrase", method, path);
+        }
+        let (status, reply) = respond(&request("PUT", "/rates/USD", &[("rate", "57"), ("passphrase", "secret")]), &shared);
+        assert_eq!(status, 200, "{}", reply);
+        let _ = std::fs::remove_file(&shared.data);
+    }
+
+    #[test]
+    fn setting_an_account_type_needs_the_passphrase() {
+        let shared = shared("account-type");
+        let (status, reply) = respond(&request("PUT", "/accounts/Alice/type", &[("type", "business")]), &shared);
+        assert_eq!(status, 403, "{}", reply);
+        assert_eq!(lock(&shared.bank).find_account("Alice").expect("account").account_type, None);
+        let (status, reply) = respond(&request("PUT", "/accounts/Alice/type", &[("type", "business"), ("passphrase", "secret")]), &shared);
+        assert_eq!(status, 200, "{}", reply);
+        assert_eq!(lock(&shared.bank).find_account("Alice").expect("account").account_type.as_deref(), Some("business"));
+        let _ = std::fs::remove_file(&shared.data);
+    }
+
+    #[test]
+    fn malformed_requests_are_refused() {
+        assert!(read("\r\n\r\n").is_err());
+        assert!(read("GET\r\n\r\n").is_err());
+        assert!(read("POST /deposit HTTP/1.1\r\nContent-Length: ten\r\n\r\n").is_err());
+        assert!(read("POST /deposit HTTP/1.1\r\nContent-Length: 10\r\n\r\nshort").is_err());
+        assert!(read(b"POST /deposit HTTP/1.1\r\nContent-Length: 2\r\n\r\n\xff\xfe").is_err());
+        assert!(read("GET /balance?account=%zz HTTP/1.1\r\n\r\n").is_err());
+    }
+
+    #[test]
+    fn oversized_bodies_are_refused_before_they_are_read() {
+        let raw = format!("POST /deposit HTTP/1.1\r\nContent-Length: {}\r\n\r\n", MAX_BODY + 1);
+        let err = read(&raw).err().expect("too large");
+        assert_eq!(err.to_string(), "request body too large");
+        let raw = format!("POST /deposit HTTP/1.1\r\nContent-Length: {}\r\n\r\n{}", MAX_BODY, "a".repeat(MAX_BODY));
+        assert_eq!(read(&raw).expect("at the limit").body.len(), MAX_BODY);
+    }
+}
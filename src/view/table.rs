@@ -0,0 +1,75 @@
+use std::fmt;
+
+/// Horizontal alignment of a table column.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Align {
+    Left,
+    Right,
+}
+
+/// Fixed-width text table used by the console screens and CLI output.
+/// - Each column is as wide as its widest cell or header.
+/// - `Align::Right` columns line up amounts on their last digit.
+/// - A `-+-` separator line sits under the header.
+///
+/// Build with `Table::new(&[("Day", Align::Right), ...])`, add rows with
+/// `row`, then print it with `{}`.
+#[derive(Debug, Clone)]
+pub struct Table {
+    headers: Vec<(String, Align)>,
+    rows: Vec<Vec<String>>,
+}
+
+impl Table {
+    pub fn new(columns: &[(&str, Align)]) -> Self {
+        Self {
+            headers: columns.iter().map(|(h, a)| (h.to_string(), *a)).collect(),
+            rows: Vec::new(),
+        }
+    }
+
+    /// Append a row. Missing trailing cells are left blank; extra cells are
+    /// dropped.
+    pub fn row<S: ToString>(&mut self, cells: impl IntoIterator<Item = S>) {
+        let mut cells: Vec<String> = cells.into_iter().map(|c| c.to_string()).collect();
+        cells.resize(self.headers.len(), String::new());
+        self.rows.push(cells);
+    }
+
+    fn widths(&self) -> Vec<usize> {
+        self.headers
+            .iter()
+            .enumerate()
+            .map(|(i, (h, _))| {
+                self.rows
+                    .iter()
+                    .map(|r| r[i].chars().count())
+                    .chain(std::iter::once(h.chars().count()))
+                    .max()
+                    .unwrap_or(0)
+            })
+            .collect()
+    }
+}
+
+impl fmt::Display for Table {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let widths = self.widths();
+        let line = |cells: Vec<&str>| {
+            let padded: Vec<String> = cells
+                .iter()
+                .zip(&self.headers)
+                .zip(&widths)
+                .map(|((cell, (_, align)), &width)| match align {
+                    Align::Left => format!("{:<width$}", cell),
+                    Align::Right => format!("{:>width$}", cell),
+                })
+                .collect();
+            padded.join(" | ").trim_end().to_string()
+        };
+        let rule: Vec<String> = widths.iter().map(|&w| "-".repeat(w)).collect();
+        let mut lines = vec![line(self.headers.iter().map(|(h, _)| h.as_str()).collect()), rule.join("-+-")];
+        lines.extend(self.rows.iter().map(|r| line(r.iter().map(String::as_str).collect())));
+        write!(f, "{}", lines.join("\n"))
+    }
+}
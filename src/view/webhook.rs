@@ -0,0 +1,207 @@
+use std::io::{self, BufRead, BufReader, Write};
+use std::net::{TcpStream, ToSocketAddrs};
+use std::sync::mpsc::{self, Sender};
+use std::thread::{self, JoinHandle};
+use std::time::Duration;
+
+use crate::api::alert::Alert;
+use crate::api::config::WebhookConfig;
+use crate::api::event::BankEvent;
+use crate::api::money::Money;
+use crate::api::notify::Notifier;
+use crate::view::json::Json;
+
+/// Tries per delivery before it is given up on.
+const MAX_ATTEMPTS: u32 = 4;
+
+/// Wait before the first retry; doubled before each later one.
+const RETRY_DELAY: Duration = Duration::from_millis(500);
+
+/// Connect, write, and read timeout for one attempt.
+const TIMEOUT: Duration = Duration::from_secs(5);
+
+/// One JSON payload bound for the webhook.
+struct Delivery {
+    kind: String,
+    body: String,
+}
+
+/// `Notifier` that POSTs bank events to one configured webhook. Payloads
+/// are built when events arrive and sent by a background thread in order,
+/// retrying failed deliveries with exponential backoff, so the bank never
+/// waits on the network. Dropping the notifier waits for its queue to
+/// drain, so a one-shot command still delivers what it caused.
+pub struct WebhookNotifier {
+    hook: WebhookConfig,
+    queue: Option<Sender<Delivery>>,
+    worker: Option<JoinHandle<()>>,
+}
+
+impl WebhookNotifier {
+    /// Start the delivery thread; fails if the hook's URL is not usable.
+    pub fn start(hook: WebhookConfig) -> Result<Self, String> {
+        let endpoint = hook.endpoint()?;
+        let url = hook.url.clone();
+        let (queue, deliveries) = mpsc::channel::<Delivery>();
+        let worker = thread::spawn(move || {
+            for delivery in deliveries {
+                deliver(&url, &endpoint, &delivery);
+            }
+        });
+        Ok(Self { hook, queue: Some(queue), worker: Some(worker) })
+    }
+}
+
+impl Notifier for WebhookNotifier {
+    /// Queue the event as the JSON of `event_json` if the webhook wants its
+    /// kind. A rate change that crosses one of the webhook's
+    /// `rate_thresholds` also queues a `rate_threshold_crossed` payload.
+    fn notify(&mut self, event: &BankEvent) -> io::Result<()> {
+        let hook = &self.hook;
+        let mut payloads = Vec::new();
+        if hook.wants(event.kind()) {
+            payloads.push((event.kind(), event_json(event)));
+        }
+        if let BankEvent::RateChanged { code, old, new } = event
+            && hook.wants("rate_threshold_crossed")
+        {
+            for (_, level) in hook.rate_thresholds.iter().filter(|(c, _)| c == code) {
+                if (*old < *level) != (*new < *level) {
+                    payloads.push((
+                        "rate_threshold_crossed",
+                        Json::object([
+                            ("event", Json::str("rate_threshold_crossed")),
+                            ("code", Json::str(code)),
+                            ("threshold", Json::num(level)),
+                            ("direction", Json::str(if new > old { "up" } else { "down" })),
+                            ("old", Json::num(old)),
+                            ("new", Json::num(new)),
+                        ]),
+                    ));
+                }
+            }
+        }
+        let queue = self.queue.as_ref().expect("queue is open until drop");
+        for (kind, body) in payloads {
+            queue
+                .send(Delivery { kind: kind.to_string(), body: body.to_string() })
+                .map_err(|_| io::Error::new(io::ErrorKind::BrokenPipe, format!("webhook {}: delivery thread stopped", hook.url)))?;
+        }
+        Ok(())
+    }
+}
+
+impl Drop for WebhookNotifier {
+    fn drop(&mut self) {
+        drop(self.queue.take());
+        if let Some(worker) = self.worker.take() {
+            let _ = worker.join();
+        }
+    }
+}
+
+/// POST one payload, retrying connection errors and 429/5xx answers up to
+/// `MAX_ATTEMPTS` times. Failures are reported on stderr.
+fn deliver(url: &str, endpoint: &(String, u16, String), delivery: &Delivery) {
+    let mut delay = RETRY_DELAY;
+    let mut last_error = String::new();
+    for attempt in 1..=MAX_ATTEMPTS {
+        match post(endpoint, &delivery.body) {
+            Ok(status) if (200..300).contains(&status) => return,
+            Ok(status) if status != 429 && status < 500 => {
+                eprintln!("webhook {}: {} rejected with HTTP {}", url, delivery.kind, status);
+                return;
+            }
+            Ok(status) => last_error = format!("HTTP {}", status),
+            Err(e) => last_error = e.to_string(),
+        }
+        if attempt < MAX_ATTEMPTS {
+            thread::sleep(delay);
+            delay *= 2;
+        }
+    }
+    eprintln!("webhook {}: gave up on {} after {} attempts: {}", url, delivery.kind, MAX_ATTEMPTS, last_error);
+}
+
+/// Send `body` as `application/json` and return the response status.
+fn post((host, port, path): &(String, u16, String), body: &str) -> io::Result<u16> {
+    let addr = (host.as_str(), *port)
+        .to_socket_addrs()?
+        .next()
+        .ok_or_else(|| io::Error::new(io::ErrorKind::NotFound, format!("cannot resolve {}", host)))?;
+    let mut stream = TcpStream::connect_timeout(&addr, TIMEOUT)?;
+    stream.set_read_timeout(Some(TIMEOUT))?;
+    stream.set_write_timeout(Some(TIMEOUT))?;
+    write!(
+        stream,
+        "POST {} HTTP/1.1\r\nHost: {}:{}\r\nUser-Agent: rust_forex\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+        path,
+        host,
+        port,
+        body.len(),
+        body
+    )?;
+    stream.flush()?;
+    let mut status_line = String::new();
+    BufReader::new(stream).read_line(&mut status_line)?;
+    status_line
+        .split_whitespace()
+        .nth(1)
+        .and_then(|code| code.parse().ok())
+        .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidData, "malformed HTTP response"))
+}
+
+/// `{"event": kind, ...fields}` for one event, amounts shaped as in
+/// `--json` (the bank records them already rounded).
+pub fn event_json(event: &BankEvent) -> Json {
+    let money = |m: &Money| Json::object([("amount", Json::num(m.amount)), ("currency", Json::str(&m.currency))]);
+    let mut fields = vec![("event", Json::str(event.kind()))];
+    match event {
+        BankEvent::AccountOpened { account, currency } => {
+            fields.extend([("account", Json::str(account)), ("currency", Json::str(currency))]);
+        }
+        BankEvent::TransactionPosted { account, tx_type, amount, balance, memo } => fields.extend([
+            ("account", Json::str(account)),
+            ("type", Json::str(format!("{:?}", tx_type).to_lowercase())),
+            ("amount", money(amount)),
+            ("balance", money(balance)),
+            ("memo", Json::str(memo)),
+        ]),
+        BankEvent::TransferCompleted { from, to, debited, credited } => fields.extend([
+            ("from", Json::str(from)),
+            ("to", Json::str(to)),
+            ("debited", money(debited)),
+            ("credited", money(credited)),
+        ]),
+        BankEvent::InterestPosted { account, amount, balance } => {
+            fields.extend([("account", Json::str(account)), ("amount", money(amount)), ("balance", money(balance))]);
+        }
+        BankEvent::RateChanged { code, old, new } => {
+            fields.extend([("code", Json::str(code)), ("old", Json::num(old)), ("new", Json::num(new))]);
+        }
+        BankEvent::LargeTransactionFlagged { id, account, amount } | BankEvent::ApprovalRequested { id, account, amount } => {
+            fields.extend([("id", Json::num(id)), ("account", Json::str(account)), ("amount", money(amount))]);
+        }
+        BankEvent::ApprovalRejected { id, account, reason } => {
+            fields.extend([("id", Json::num(id)), ("account", Json::str(account)), ("reason", Json::str(reason))]);
+        }
+        BankEvent::TransactionsImported { account, imported, rejected, balance } => fields.extend([
+            ("account", Json::str(account)),
+            ("imported", Json::num(imported)),
+            ("rejected", Json::num(rejected)),
+            ("balance", money(balance)),
+        ]),
+        BankEvent::SessionStarted { customer_id, customer } | BankEvent::SessionEnded { customer_id, customer } => {
+            fields.extend([("customer_id", Json::num(customer_id)), ("customer", Json::str(customer))]);
+        }
+        BankEvent::AlertRaised { account, alert } => {
+            fields.extend([("account", Json::str(account)), ("alert", Json::str(alert.kind())), ("message", Json::str(alert))]);
+            match alert {
+                Alert::LowBalance { balance, threshold } => fields.extend([("balance", money(balance)), ("threshold", Json::num(threshold))]),
+                Alert::LargeWithdrawal { amount, threshold } => fields.extend([("amount", money(amount)), ("threshold", Json::num(threshold))]),
+                Alert::Inactive { days, last } => fields.extend([("days", Json::num(days)), ("last", Json::str(last))]),
+            }
+        }
+    }
+    Json::object(fields)
+}